@@ -58,4 +58,37 @@ pub enum GovernanceError {
     
     #[msg("Invalid execution instructions")]
     InvalidExecutionInstructions,
+    
+    #[msg("Proposal execution instructions too large")]
+    ExecutionInstructionsTooLarge,
+    
+    #[msg("Invalid proposal length limits")]
+    InvalidProposalLimits,
+    
+    #[msg("New vote type matches the existing vote")]
+    VoteUnchanged,
+    
+    #[msg("Proposer must wait for the proposal cooldown to elapse")]
+    ProposalCooldownActive,
+    
+    #[msg("Multiple-choice proposals need between 2 and 5 options with valid labels")]
+    InvalidProposalOptions,
+    
+    #[msg("Vote does not match a valid option of this proposal")]
+    InvalidVoteOption,
+    
+    #[msg("Invalid participation tiers")]
+    InvalidParticipationTiers,
+    
+    #[msg("Proposal did not reach quorum or execute, so votes on it do not count")]
+    ParticipationNotEligible,
+    
+    #[msg("Participation for this vote has already been recorded")]
+    ParticipationAlreadyRecorded,
+    
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    
+    #[msg("Execution instructions do not match the proposal")]
+    ExecutionInstructionsMismatch,
 }
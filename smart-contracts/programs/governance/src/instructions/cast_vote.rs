@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{TokenAccount, Mint};
 
-use crate::state::{Governance, Proposal, ProposalState, VoteType, Vote, VoterWeight};
+use crate::state::{Governance, ParticipationTier, Proposal, ProposalState, VoteType, Vote, VoterWeight};
 use crate::errors::GovernanceError;
 
 #[derive(Accounts)]
@@ -93,6 +93,7 @@ pub fn handler(
         voter_weight_account.delegate = None;
         voter_weight_account.delegated_weight = 0;
         voter_weight_account.last_proposal_time = 0;
+        voter_weight_account.participation_count = 0;
         voter_weight_account.bump = *ctx.bumps.get("voter_weight").unwrap();
     }
     
@@ -106,7 +107,15 @@ pub fn handler(
     };
     
     // Apply proposal-specific weight multipliers
-    let final_vote_weight = proposal.calculate_voting_power_for_type(effective_weight);
+    let type_weight = proposal.calculate_voting_power_for_type(effective_weight);
+    
+    // Boost long-term participants according to the configured tiers
+    let voting_power_multiplier = governance.voting_power_multiplier(voter_weight_account.participation_count);
+    let final_vote_weight = (type_weight as u128)
+        .checked_mul(voting_power_multiplier as u128)
+        .and_then(|weight| weight.checked_div(ParticipationTier::BASE_MULTIPLIER_BPS as u128))
+        .and_then(|weight| u64::try_from(weight).ok())
+        .ok_or(GovernanceError::MathOverflow)?;
     
     // Validate vote weight if specified
     if let Some(specified_weight) = vote_weight {
@@ -125,13 +134,14 @@ pub fn handler(
     vote.weight = actual_vote_weight;
     vote.voted_at = current_time;
     vote.bump = *ctx.bumps.get("vote").unwrap();
+    vote.voting_power_multiplier_bps = voting_power_multiplier;
+    vote.participation_recorded = false;
     
     // Update proposal vote counts
-    match vote_type {
-        VoteType::Yes => proposal.yes_votes += actual_vote_weight,
-        VoteType::No => proposal.no_votes += actual_vote_weight,
-        VoteType::Abstain => proposal.abstain_votes += actual_vote_weight,
-    }
+    let tally = proposal.tally_mut(vote_type)?;
+    *tally = tally
+        .checked_add(actual_vote_weight)
+        .ok_or(GovernanceError::MathOverflow)?;
     
     proposal.total_votes += actual_vote_weight;
     proposal.voter_count += 1;
@@ -141,15 +151,12 @@ pub fn handler(
     proposal.update_state(governance, current_time, total_supply);
     
     msg!(
-        "Vote cast: {} voted {} on proposal {} with weight {}",
+        "Vote cast: {} voted {} on proposal {} with weight {} ({} bps participation multiplier)",
         ctx.accounts.voter.key(),
-        match vote_type {
-            VoteType::Yes => "YES",
-            VoteType::No => "NO",
-            VoteType::Abstain => "ABSTAIN",
-        },
+        vote_type.label(),
         proposal.id,
-        actual_vote_weight
+        actual_vote_weight,
+        voting_power_multiplier
     );
     
     Ok(())
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Governance, Proposal, VoteType, Vote};
+use crate::errors::GovernanceError;
+
+#[derive(Accounts)]
+pub struct ChangeVote<'info> {
+    pub voter: Signer<'info>,
+    
+    /// The governance account
+    #[account(
+        seeds = [b"governance", governance.governance_token_mint.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    /// The proposal being voted on
+    #[account(
+        mut,
+        seeds = [b"proposal", governance.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// The existing vote record
+    #[account(
+        mut,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote.bump,
+        constraint = vote.voter == voter.key(),
+        constraint = vote.proposal == proposal.key()
+    )]
+    pub vote: Account<'info, Vote>,
+}
+
+pub fn handler(
+    ctx: Context<ChangeVote>,
+    new_vote_type: VoteType,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+    
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+    
+    // Votes can only be changed while voting is open
+    require!(
+        proposal.is_active(current_time),
+        GovernanceError::VotingEnded
+    );
+    
+    require!(
+        vote.vote_type != new_vote_type,
+        GovernanceError::VoteUnchanged
+    );
+    
+    let weight = vote.weight;
+    
+    // Move the recorded weight from the old tally to the new one
+    let old_tally = proposal.tally_mut(vote.vote_type)?;
+    *old_tally = old_tally
+        .checked_sub(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    let new_tally = proposal.tally_mut(new_vote_type)?;
+    *new_tally = new_tally
+        .checked_add(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    vote.vote_type = new_vote_type;
+    vote.voted_at = current_time;
+    
+    msg!(
+        "Vote changed: {} now votes {} on proposal {} with weight {}",
+        ctx.accounts.voter.key(),
+        new_vote_type.label(),
+        proposal.id,
+        weight
+    );
+    
+    Ok(())
+}
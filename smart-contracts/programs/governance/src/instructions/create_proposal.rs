@@ -1,11 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{TokenAccount, Mint};
 
-use crate::state::{Governance, Proposal, ProposalType, ProposalState, VoterWeight};
+use crate::state::{Governance, Proposal, ProposalOption, ProposalType, ProposalState, VoterWeight};
 use crate::errors::GovernanceError;
 
 #[derive(Accounts)]
-#[instruction(proposal_type: ProposalType, title: String, description: String)]
+#[instruction(proposal_type: ProposalType, title: String, description: String, execution_instructions: Vec<u8>, options: Vec<ProposalOption>)]
 pub struct CreateProposal<'info> {
     #[account(mut)]
     pub proposer: Signer<'info>,
@@ -42,7 +42,7 @@ pub struct CreateProposal<'info> {
     #[account(
         init,
         payer = proposer,
-        space = Proposal::LEN,
+        space = Proposal::space(title.len(), description.len(), execution_instructions.len(), &options),
         seeds = [b"proposal", governance.key().as_ref(), &governance.proposal_count.to_le_bytes()],
         bump
     )]
@@ -60,25 +60,48 @@ pub fn handler(
     title: String,
     description: String,
     execution_instructions: Vec<u8>,
+    options: Vec<ProposalOption>,
 ) -> Result<()> {
     let governance = &mut ctx.accounts.governance;
     let proposal = &mut ctx.accounts.proposal;
     let proposer_voter_weight = &mut ctx.accounts.proposer_voter_weight;
     
-    // Validate input lengths
+    // Validate input lengths against the configured limits
     require!(
-        title.len() <= Proposal::MAX_TITLE_LEN,
+        title.len() <= governance.config.max_title_len as usize,
         GovernanceError::TitleTooLong
     );
     require!(
-        description.len() <= Proposal::MAX_DESCRIPTION_LEN,
+        description.len() <= governance.config.max_description_len as usize,
         GovernanceError::DescriptionTooLong
     );
     require!(
-        execution_instructions.len() <= Proposal::MAX_EXECUTION_INSTRUCTIONS_LEN,
-        GovernanceError::InvalidExecutionInstructions
+        execution_instructions.len() <= governance.config.max_execution_instructions_len as usize,
+        GovernanceError::ExecutionInstructionsTooLarge
     );
     
+    // Multiple-choice proposals carry their own per-option instructions
+    if !options.is_empty() {
+        require!(
+            options.len() >= Proposal::MIN_OPTIONS && options.len() <= Proposal::MAX_OPTIONS,
+            GovernanceError::InvalidProposalOptions
+        );
+        require!(
+            execution_instructions.is_empty(),
+            GovernanceError::InvalidExecutionInstructions
+        );
+        for option in options.iter() {
+            require!(
+                !option.label.is_empty() && option.label.len() <= Proposal::MAX_OPTION_LABEL_LEN,
+                GovernanceError::InvalidProposalOptions
+            );
+            require!(
+                option.execution_instructions.len() <= governance.config.max_execution_instructions_len as usize,
+                GovernanceError::ExecutionInstructionsTooLarge
+            );
+        }
+    }
+    
     // Check proposer has enough tokens to create proposal
     let proposer_token_balance = ctx.accounts.proposer_token_account.amount;
     require!(
@@ -94,7 +117,7 @@ pub fn handler(
         let time_since_last_proposal = current_time - proposer_voter_weight.last_proposal_time;
         require!(
             time_since_last_proposal >= governance.config.proposal_cooldown,
-            GovernanceError::InvalidProposalDuration
+            GovernanceError::ProposalCooldownActive
         );
     } else {
         // Initialize voter weight if this is first time
@@ -103,6 +126,7 @@ pub fn handler(
         proposer_voter_weight.weight = proposer_token_balance;
         proposer_voter_weight.delegate = None;
         proposer_voter_weight.delegated_weight = 0;
+        proposer_voter_weight.participation_count = 0;
         proposer_voter_weight.bump = *ctx.bumps.get("proposer_voter_weight").unwrap();
     }
     
@@ -123,6 +147,10 @@ pub fn handler(
     proposal.state = ProposalState::Active;
     proposal.title = title;
     proposal.description = description;
+    proposal.execution_hash = Proposal::hash_execution_payload(
+        &execution_instructions,
+        options.iter().map(|option| option.execution_instructions.as_slice()),
+    );
     proposal.execution_instructions = execution_instructions;
     proposal.created_at = current_time;
     proposal.voting_start_time = current_time;
@@ -136,6 +164,10 @@ pub fn handler(
     proposal.voter_count = 0;
     proposal.related_event = ctx.accounts.related_event.map(|e| e.key());
     proposal.bump = *ctx.bumps.get("proposal").unwrap();
+    proposal.option_votes = vec![0; options.len()];
+    proposal.options = options;
+    proposal.winning_option = None;
+    proposal.executed = false;
     
     // Increment proposal count
     governance.proposal_count += 1;
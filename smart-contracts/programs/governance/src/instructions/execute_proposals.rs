@@ -29,10 +29,13 @@ pub struct ExecuteProposal<'info> {
     pub proposal: Account<'info, Proposal>,
 }
 
-pub fn handler(ctx: Context<ExecuteProposal>) -> Result<()> {
+pub fn handler(ctx: Context<ExecuteProposal>, execution_instructions: Vec<u8>) -> Result<()> {
     let governance = &ctx.accounts.governance;
     let proposal = &mut ctx.accounts.proposal;
     
+    // A proposal runs at most once
+    require!(!proposal.executed, GovernanceError::ProposalAlreadyExecuted);
+    
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
     
@@ -58,8 +61,28 @@ pub fn handler(ctx: Context<ExecuteProposal>) -> Result<()> {
         GovernanceError::ExecutionPeriodExpired
     );
     
-    // Execute the proposal instructions
-    if !proposal.execution_instructions.is_empty() {
+    // The supplied instructions must be the ones the proposal was created with
+    require!(
+        proposal.matches_execution_hash(&execution_instructions),
+        GovernanceError::ExecutionInstructionsMismatch
+    );
+    
+    // Mark proposal as executed before running anything, so a re-entrant or
+    // repeated call is rejected
+    proposal.executed = true;
+    proposal.state = ProposalState::Executed;
+    
+    if let Some(winning_option) = proposal.winning_option {
+        msg!(
+            "Option {} ({}) won proposal {}",
+            winning_option,
+            proposal.options[winning_option as usize].label,
+            proposal.id
+        );
+    }
+    
+    // Execute the proposal instructions (the winning option's, for multiple-choice proposals)
+    if !execution_instructions.is_empty() {
         // Parse and execute the stored instructions
         // Note: This is a simplified version. In a production system,
         // you'd have a more sophisticated instruction parsing and execution system
@@ -92,9 +115,6 @@ pub fn handler(ctx: Context<ExecuteProposal>) -> Result<()> {
         }
     }
     
-    // Mark proposal as executed
-    proposal.state = ProposalState::Executed;
-    
     msg!(
         "Proposal {} executed successfully by {}",
         proposal.id,
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
 
-use crate::state::{Governance, GovernanceConfig};
+use crate::state::{Governance, GovernanceConfig, ParticipationTier, Proposal};
+use crate::errors::GovernanceError;
 
 #[derive(Accounts)]
 pub struct InitializeGovernance<'info> {
@@ -30,6 +31,38 @@ pub fn handler(
 ) -> Result<()> {
     let governance = &mut ctx.accounts.governance;
     
+    // Proposal length limits must fit within the proposal account layout
+    require!(
+        config.max_title_len > 0 && config.max_title_len as usize <= Proposal::MAX_TITLE_LEN,
+        GovernanceError::InvalidProposalLimits
+    );
+    require!(
+        config.max_description_len > 0 && config.max_description_len as usize <= Proposal::MAX_DESCRIPTION_LEN,
+        GovernanceError::InvalidProposalLimits
+    );
+    require!(
+        config.max_execution_instructions_len as usize <= Proposal::MAX_EXECUTION_INSTRUCTIONS_LEN,
+        GovernanceError::InvalidProposalLimits
+    );
+    
+    // Enabled participation tiers must boost voting power and be listed in ascending order
+    let mut previous_min_votes: Option<u32> = None;
+    for tier in config.participation_tiers.iter().filter(|tier| tier.is_enabled()) {
+        require!(
+            tier.multiplier_bps >= ParticipationTier::BASE_MULTIPLIER_BPS
+                && tier.multiplier_bps <= ParticipationTier::MAX_MULTIPLIER_BPS,
+            GovernanceError::InvalidParticipationTiers
+        );
+        require!(
+            previous_min_votes.is_none_or(|min_votes| tier.min_votes > min_votes),
+            GovernanceError::InvalidParticipationTiers
+        );
+        previous_min_votes = Some(tier.min_votes);
+    }
+    
+    // Cooldown between proposals from the same proposer cannot be negative
+    require!(config.proposal_cooldown >= 0, GovernanceError::InvalidProposalDuration);
+    
     governance.authority = ctx.accounts.authority.key();
     governance.governance_token_mint = ctx.accounts.governance_token_mint.key();
     governance.config = config;
@@ -1,15 +1,19 @@
 pub mod initialize_governance;
 pub mod create_proposal;
 pub mod cast_vote;
+pub mod change_vote;
 pub mod execute_proposal;
 pub mod delegate_votes;
 pub mod revoke_delegation;
 pub mod cancel_proposal;
+pub mod record_participation;
 
 pub use initialize_governance::*;
 pub use create_proposal::*;
 pub use cast_vote::*;
+pub use change_vote::*;
 pub use execute_proposal::*;
 pub use delegate_votes::*;
 pub use revoke_delegation::*;
 pub use cancel_proposal::*;
+pub use record_participation::*;
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::state::{Governance, Proposal, Vote, VoterWeight};
+use crate::errors::GovernanceError;
+
+#[derive(Accounts)]
+pub struct RecordParticipation<'info> {
+    /// The governance account
+    #[account(
+        seeds = [b"governance", governance.governance_token_mint.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    /// The governance token mint
+    #[account(
+        constraint = governance_token_mint.key() == governance.governance_token_mint
+    )]
+    pub governance_token_mint: Account<'info, Mint>,
+    
+    /// The proposal the vote was cast on
+    #[account(
+        mut,
+        seeds = [b"proposal", governance.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// The vote being counted
+    #[account(
+        mut,
+        seeds = [b"vote", proposal.key().as_ref(), vote.voter.as_ref()],
+        bump = vote.bump,
+        constraint = vote.proposal == proposal.key()
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    /// The voter's weight account, which tracks participation
+    #[account(
+        mut,
+        seeds = [b"voter_weight", governance.key().as_ref(), vote.voter.as_ref()],
+        bump = voter_weight.bump
+    )]
+    pub voter_weight: Account<'info, VoterWeight>,
+}
+
+pub fn handler(ctx: Context<RecordParticipation>) -> Result<()> {
+    let governance = &ctx.accounts.governance;
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+    let voter_weight = &mut ctx.accounts.voter_weight;
+    
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+    
+    require!(
+        !vote.participation_recorded,
+        GovernanceError::ParticipationAlreadyRecorded
+    );
+    
+    // Settle the outcome if voting has ended but nobody has touched the proposal since
+    let total_supply = ctx.accounts.governance_token_mint.supply;
+    proposal.update_state(governance, current_time, total_supply);
+    
+    // Only votes on proposals that reached quorum or executed count, so that
+    // spamming low-turnout proposals cannot farm a participation boost
+    require!(
+        proposal.counts_for_participation(),
+        GovernanceError::ParticipationNotEligible
+    );
+    
+    vote.participation_recorded = true;
+    voter_weight.participation_count = voter_weight
+        .participation_count
+        .checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    msg!(
+        "Participation recorded for {} on proposal {} (total: {})",
+        vote.voter,
+        proposal.id,
+        voter_weight.participation_count
+    );
+    
+    Ok(())
+}
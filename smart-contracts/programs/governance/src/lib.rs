@@ -23,15 +23,16 @@ pub mod ticket_governance {
         instructions::initialize_governance::handler(ctx, config)
     }
 
-    /// Create a new proposal
+    /// Create a new proposal, optionally with multiple-choice options
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
         proposal_type: ProposalType,
         title: String,
         description: String,
         execution_instructions: Vec<u8>,
+        options: Vec<ProposalOption>,
     ) -> Result<()> {
-        instructions::create_proposal::handler(ctx, proposal_type, title, description, execution_instructions)
+        instructions::create_proposal::handler(ctx, proposal_type, title, description, execution_instructions, options)
     }
 
     /// Cast a vote on a proposal
@@ -43,9 +44,20 @@ pub mod ticket_governance {
         instructions::cast_vote::handler(ctx, vote_type, vote_weight)
     }
 
-    /// Execute a proposal that has passed
-    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
-        instructions::execute_proposal::handler(ctx)
+    /// Change an existing vote while the voting period is open
+    pub fn change_vote(
+        ctx: Context<ChangeVote>,
+        new_vote_type: VoteType,
+    ) -> Result<()> {
+        instructions::change_vote::handler(ctx, new_vote_type)
+    }
+
+    /// Execute a proposal that has passed, supplying the instructions it committed to
+    pub fn execute_proposal(
+        ctx: Context<ExecuteProposal>,
+        execution_instructions: Vec<u8>,
+    ) -> Result<()> {
+        instructions::execute_proposal::handler(ctx, execution_instructions)
     }
 
     /// Delegate voting power to another account
@@ -65,4 +77,9 @@ pub mod ticket_governance {
     pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
         instructions::cancel_proposal::handler(ctx)
     }
+
+    /// Count a vote towards the voter's participation once the proposal has concluded
+    pub fn record_participation(ctx: Context<RecordParticipation>) -> Result<()> {
+        instructions::record_participation::handler(ctx)
+    }
 }
@@ -1,5 +1,26 @@
 use anchor_lang::prelude::*;
 
+/// Voting power boost granted once a voter has participated in enough proposals
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ParticipationTier {
+    /// Minimum number of counted proposals the voter must have voted on
+    pub min_votes: u32,
+    
+    /// Voting power multiplier in basis points (10000 = 1x, 0 = tier disabled)
+    pub multiplier_bps: u16,
+}
+
+impl ParticipationTier {
+    pub const BASE_MULTIPLIER_BPS: u16 = 10000;
+    pub const MAX_MULTIPLIER_BPS: u16 = 30000;
+    pub const MAX_TIERS: usize = 3;
+    pub const LEN: usize = 4 + 2;
+    
+    pub fn is_enabled(&self) -> bool {
+        self.multiplier_bps > 0
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
 pub struct GovernanceConfig {
     /// Minimum number of tokens required to create a proposal
@@ -19,6 +40,18 @@ pub struct GovernanceConfig {
     
     /// Cool down period between proposals from the same user
     pub proposal_cooldown: i64,
+    
+    /// Maximum proposal title length in bytes (capped by Proposal::MAX_TITLE_LEN)
+    pub max_title_len: u16,
+    
+    /// Maximum proposal description length in bytes (capped by Proposal::MAX_DESCRIPTION_LEN)
+    pub max_description_len: u16,
+    
+    /// Maximum execution instructions size in bytes (capped by Proposal::MAX_EXECUTION_INSTRUCTIONS_LEN)
+    pub max_execution_instructions_len: u16,
+    
+    /// Voting power boosts for long-term participants, in ascending order of min_votes
+    pub participation_tiers: [ParticipationTier; ParticipationTier::MAX_TIERS],
 }
 
 impl Default for GovernanceConfig {
@@ -30,6 +63,14 @@ impl Default for GovernanceConfig {
             voting_duration: 7 * 24 * 60 * 60, // 7 days
             execution_window: 3 * 24 * 60 * 60, // 3 days to execute
             proposal_cooldown: 24 * 60 * 60, // 1 day cooldown
+            max_title_len: 100,
+            max_description_len: 1000,
+            max_execution_instructions_len: 1024,
+            participation_tiers: [
+                ParticipationTier { min_votes: 5, multiplier_bps: 11000 }, // 1.1x after 5 votes
+                ParticipationTier { min_votes: 20, multiplier_bps: 12500 }, // 1.25x after 20 votes
+                ParticipationTier { min_votes: 50, multiplier_bps: 15000 }, // 1.5x after 50 votes
+            ],
         }
     }
 }
@@ -56,7 +97,7 @@ impl Governance {
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
         32 + // governance_token_mint
-        (8 + 2 + 2 + 8 + 8 + 8) + // config
+        (8 + 2 + 2 + 8 + 8 + 8 + 2 + 2 + 2 + ParticipationTier::LEN * ParticipationTier::MAX_TIERS) + // config
         8 + // proposal_count
         1; // bump
         
@@ -70,6 +111,17 @@ impl Governance {
         total_votes >= required_quorum
     }
     
+    /// Voting power multiplier (in basis points) for a voter with the given participation history
+    pub fn voting_power_multiplier(&self, participation_count: u32) -> u16 {
+        self.config
+            .participation_tiers
+            .iter()
+            .filter(|tier| tier.is_enabled() && participation_count >= tier.min_votes)
+            .map(|tier| tier.multiplier_bps)
+            .max()
+            .unwrap_or(ParticipationTier::BASE_MULTIPLIER_BPS)
+    }
+    
     pub fn is_proposal_approved(&self, yes_votes: u64, total_votes: u64) -> bool {
         if total_votes == 0 {
             return false;
@@ -107,6 +159,9 @@ pub struct VoterWeight {
     
     /// Bump seed for PDA
     pub bump: u8,
+    
+    /// Number of quorum-reaching or executed proposals this voter has voted on
+    pub participation_count: u32,
 }
 
 impl VoterWeight {
@@ -117,7 +172,8 @@ impl VoterWeight {
         (1 + 32) + // delegate (Option<Pubkey>)
         8 + // delegated_weight
         8 + // last_proposal_time
-        1; // bump
+        1 + // bump
+        4; // participation_count
         
     pub fn effective_weight(&self) -> u64 {
         self.weight + self.delegated_weight
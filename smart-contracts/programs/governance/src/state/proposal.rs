@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::errors::GovernanceError;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum ProposalType {
@@ -35,6 +38,30 @@ pub enum VoteType {
     Yes,
     No,
     Abstain,
+    /// Vote for an option of a multiple-choice proposal, by index
+    Choice(u8),
+}
+
+impl VoteType {
+    /// Human-readable label for logs
+    pub fn label(&self) -> String {
+        match self {
+            VoteType::Yes => "YES".to_string(),
+            VoteType::No => "NO".to_string(),
+            VoteType::Abstain => "ABSTAIN".to_string(),
+            VoteType::Choice(index) => format!("OPTION {}", index),
+        }
+    }
+}
+
+/// A selectable outcome of a multiple-choice proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct ProposalOption {
+    /// Short label shown to voters
+    pub label: String,
+    
+    /// Instructions to execute if this option wins
+    pub execution_instructions: Vec<u8>,
 }
 
 #[account]
@@ -54,10 +81,10 @@ pub struct Proposal {
     /// Current state of the proposal
     pub state: ProposalState,
     
-    /// Proposal title (max 64 characters)
+    /// Proposal title (max 100 bytes)
     pub title: String,
     
-    /// Proposal description (max 500 characters)
+    /// Proposal description (max 1000 bytes)
     pub description: String,
     
     /// Instructions to execute if proposal passes
@@ -98,12 +125,33 @@ pub struct Proposal {
     
     /// Bump seed for PDA
     pub bump: u8,
+    
+    /// Options of a multiple-choice proposal (empty for yes/no proposals)
+    pub options: Vec<ProposalOption>,
+    
+    /// Total voting weight cast for each option, by index
+    pub option_votes: Vec<u64>,
+    
+    /// Index of the option that won, once voting has ended
+    pub winning_option: Option<u8>,
+    
+    /// Whether the proposal met quorum when voting ended
+    pub quorum_reached: bool,
+    
+    /// Set once the proposal has been executed; it can never run again
+    pub executed: bool,
+    
+    /// Hash of the proposal's and every option's instructions, fixed at creation
+    pub execution_hash: [u8; 32],
 }
 
 impl Proposal {
-    pub const MAX_TITLE_LEN: usize = 64;
-    pub const MAX_DESCRIPTION_LEN: usize = 500;
-    pub const MAX_EXECUTION_INSTRUCTIONS_LEN: usize = 1000;
+    pub const MAX_TITLE_LEN: usize = 100;
+    pub const MAX_DESCRIPTION_LEN: usize = 1000;
+    pub const MAX_EXECUTION_INSTRUCTIONS_LEN: usize = 1024;
+    pub const MIN_OPTIONS: usize = 2;
+    pub const MAX_OPTIONS: usize = 5;
+    pub const MAX_OPTION_LABEL_LEN: usize = 32;
     
     pub const LEN: usize = 8 + // discriminator
         32 + // governance
@@ -125,7 +173,118 @@ impl Proposal {
         8 + // total_votes
         4 + // voter_count
         (1 + 32) + // related_event (Option<Pubkey>)
-        1; // bump
+        1 + // bump
+        4 + // options (empty Vec)
+        4 + // option_votes (empty Vec)
+        (1 + 1) + // winning_option (Option<u8>)
+        1 + // quorum_reached
+        1 + // executed
+        32; // execution_hash
+    
+    /// Account space for a proposal holding the given variable-length fields
+    pub fn space(
+        title_len: usize,
+        description_len: usize,
+        execution_instructions_len: usize,
+        options: &[ProposalOption],
+    ) -> usize {
+        let options_len: usize = options
+            .iter()
+            .take(Self::MAX_OPTIONS)
+            .map(|option| {
+                (4 + option.label.len().min(Self::MAX_OPTION_LABEL_LEN))
+                    + (4 + option.execution_instructions.len().min(Self::MAX_EXECUTION_INSTRUCTIONS_LEN))
+                    + 8 // option_votes entry
+            })
+            .sum();
+        
+        Self::LEN
+            - Self::MAX_TITLE_LEN
+            - Self::MAX_DESCRIPTION_LEN
+            - Self::MAX_EXECUTION_INSTRUCTIONS_LEN
+            + title_len.min(Self::MAX_TITLE_LEN)
+            + description_len.min(Self::MAX_DESCRIPTION_LEN)
+            + execution_instructions_len.min(Self::MAX_EXECUTION_INSTRUCTIONS_LEN)
+            + options_len
+    }
+    
+    pub fn is_multiple_choice(&self) -> bool {
+        !self.options.is_empty()
+    }
+    
+    /// Tally that a vote of the given type counts towards
+    pub fn tally_mut(&mut self, vote_type: VoteType) -> Result<&mut u64> {
+        match (vote_type, self.is_multiple_choice()) {
+            (VoteType::Choice(index), true) => self
+                .option_votes
+                .get_mut(index as usize)
+                .ok_or_else(|| error!(GovernanceError::InvalidVoteOption)),
+            (VoteType::Yes, false) => Ok(&mut self.yes_votes),
+            (VoteType::No, false) => Ok(&mut self.no_votes),
+            (VoteType::Abstain, false) => Ok(&mut self.abstain_votes),
+            _ => err!(GovernanceError::InvalidVoteOption),
+        }
+    }
+    
+    /// Option with strictly the most voting weight, if any
+    pub fn leading_option(&self) -> Option<u8> {
+        let (index, &top) = self
+            .option_votes
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, &votes)| votes)?;
+        let tied = self.option_votes.iter().filter(|&&votes| votes == top).count() > 1;
+        
+        if top == 0 || tied {
+            None
+        } else {
+            Some(index as u8)
+        }
+    }
+    
+    /// Instructions to run on execution: the winning option's for
+    /// multiple-choice proposals, otherwise the proposal's own
+    pub fn instructions_to_execute(&self) -> &[u8] {
+        match self.winning_option {
+            Some(index) => &self.options[index as usize].execution_instructions,
+            None => &self.execution_instructions,
+        }
+    }
+    
+    /// Hash committing to the proposal's own instructions and each option's, in order
+    pub fn hash_execution_payload<'a>(
+        execution_instructions: &'a [u8],
+        option_instructions: impl Iterator<Item = &'a [u8]>,
+    ) -> [u8; 32] {
+        let mut payload = Vec::new();
+        for instructions in std::iter::once(execution_instructions).chain(option_instructions) {
+            payload.extend_from_slice(&(instructions.len() as u32).to_le_bytes());
+            payload.extend_from_slice(instructions);
+        }
+        hash(&payload).to_bytes()
+    }
+    
+    /// Whether `instructions`, in place of the ones due to run, still match the
+    /// hash taken at creation
+    pub fn matches_execution_hash(&self, instructions: &[u8]) -> bool {
+        let payload_hash = match self.winning_option {
+            Some(winner) => Self::hash_execution_payload(
+                &self.execution_instructions,
+                self.options.iter().enumerate().map(|(index, option)| {
+                    if index == winner as usize {
+                        instructions
+                    } else {
+                        option.execution_instructions.as_slice()
+                    }
+                }),
+            ),
+            None => Self::hash_execution_payload(
+                instructions,
+                self.options.iter().map(|option| option.execution_instructions.as_slice()),
+            ),
+        };
+        payload_hash == self.execution_hash
+    }
     
     pub fn is_active(&self, current_time: i64) -> bool {
         self.state == ProposalState::Active &&
@@ -145,7 +304,14 @@ impl Proposal {
                 if current_time > self.voting_end_time {
                     // Voting has ended, determine outcome
                     let quorum_met = governance.is_valid_quorum(self.total_votes, total_supply);
-                    let approved = governance.is_proposal_approved(self.yes_votes, self.total_votes);
+                    self.quorum_reached = quorum_met;
+                    let approved = if self.is_multiple_choice() {
+                        // Plurality: the single highest-weighted option wins
+                        self.winning_option = self.leading_option();
+                        self.winning_option.is_some()
+                    } else {
+                        governance.is_proposal_approved(self.yes_votes, self.total_votes)
+                    };
                     
                     if quorum_met && approved {
                         self.state = ProposalState::Succeeded;
@@ -163,6 +329,12 @@ impl Proposal {
         }
     }
     
+    /// Whether votes on this proposal count towards voter participation
+    pub fn counts_for_participation(&self) -> bool {
+        self.state == ProposalState::Executed
+            || (self.state != ProposalState::Active && self.quorum_reached)
+    }
+    
     pub fn calculate_voting_power_for_type(&self, base_weight: u64) -> u64 {
         match self.proposal_type {
             ProposalType::Emergency => base_weight * 2, // Emergency proposals have double weight
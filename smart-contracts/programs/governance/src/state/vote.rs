@@ -20,16 +20,24 @@ pub struct Vote {
     
     /// Bump seed for PDA
     pub bump: u8,
+    
+    /// Participation multiplier applied to this vote, in basis points
+    pub voting_power_multiplier_bps: u16,
+    
+    /// Whether this vote has been counted towards the voter's participation
+    pub participation_recorded: bool,
 }
 
 impl Vote {
     pub const LEN: usize = 8 + // discriminator
         32 + // proposal
         32 + // voter
-        1 + // vote_type
+        (1 + 1) + // vote_type (VoteType::Choice carries an option index)
         8 + // weight
         8 + // voted_at
-        1; // bump
+        1 + // bump
+        2 + // voting_power_multiplier_bps
+        1; // participation_recorded
 }
 
 #[account]
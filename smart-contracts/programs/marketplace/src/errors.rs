@@ -11,7 +11,7 @@ pub enum MarketplaceError {
     #[msg("The listing has already been fulfilled or canceled")]
     ListingNoLongerActive,
     
-    #[msg("Insufficient funds to purchase ticket")]
+    #[msg("Insufficient funds to cover the payment")]
     InsufficientFunds,
     
     #[msg("Not enough SOL to create a listing")]
@@ -52,4 +52,211 @@ pub enum MarketplaceError {
     
     #[msg("The event has already started")]
     EventAlreadyStarted,
+    
+    #[msg("Dispute deadline has not passed yet")]
+    DisputeDeadlineNotReached,
+    
+    #[msg("No automatic unpause is scheduled")]
+    AutoUnpauseNotScheduled,
+    
+    #[msg("Scheduled unpause time has not been reached")]
+    AutoUnpauseNotReady,
+    
+    #[msg("Invalid health check thresholds")]
+    InvalidHealthThresholds,
+    
+    #[msg("This listing does not accept offers")]
+    OffersNotAllowed,
+    
+    #[msg("Offers are only supported on fixed-price listings")]
+    OffersRequireFixedPrice,
+    
+    #[msg("Offer amount must be greater than zero")]
+    InvalidOfferAmount,
+    
+    #[msg("Sellers cannot make offers on their own listings")]
+    CannotOfferOnOwnListing,
+    
+    #[msg("Settlement grace period must not be negative")]
+    InvalidGracePeriod,
+    
+    #[msg("Auction settlement grace period has not elapsed")]
+    SettlementGracePeriodActive,
+    
+    #[msg("Winner token account is not the winner's associated token account")]
+    InvalidWinnerTokenAccount,
+    
+    #[msg("NFT metadata account is missing or invalid")]
+    InvalidNftMetadata,
+    
+    #[msg("Royalty creator is not a verified creator of the NFT")]
+    UnverifiedRoyaltyCreator,
+    
+    #[msg("Bid is no longer active")]
+    BidNotActive,
+    
+    #[msg("Batch accounts must be listing, mint, escrow and seller token account groups")]
+    InvalidBatchAccounts,
+    
+    #[msg("Too many listings in one batch")]
+    BatchTooLarge,
+    
+    #[msg("Seller has reached the maximum number of active auctions")]
+    TooManyActiveAuctions,
+    
+    #[msg("Resolution is not allowed for this dispute category")]
+    ResolutionNotAllowedForCategory,
+    
+    #[msg("The leading bidder's bid account is required to resolve proxy bids")]
+    MissingLeadingBid,
+    
+    #[msg("Listing has no relist configuration")]
+    RelistNotConfigured,
+    
+    #[msg("Auction has reached its relist limit")]
+    RelistLimitReached,
+    
+    #[msg("Auction ended with a winning bid and cannot be relisted")]
+    AuctionAlreadySold,
+    
+    #[msg("Fee token is not the burnable project token")]
+    FeeTokenNotBurnable,
+    
+    #[msg("Invalid accounts for a token royalty payout")]
+    InvalidRoyaltyPayoutAccounts,
+    
+    #[msg("Royalty price feed is stale")]
+    StaleRoyaltyPrice,
+    
+    #[msg("Insufficient liquidity to pay the royalty in the payout token")]
+    InsufficientRoyaltyLiquidity,
+    
+    #[msg("Royalties can only be paid in a token for auction listings")]
+    TokenRoyaltyRequiresAuction,
+    
+    #[msg("Invalid escrow release condition")]
+    InvalidReleaseCondition,
+    
+    #[msg("Signer is not a confirmer for this escrow")]
+    UnauthorizedConfirmer,
+    
+    #[msg("Confirmer has already confirmed this escrow")]
+    EscrowAlreadyConfirmed,
+    
+    #[msg("Escrow has not reached its confirmation threshold")]
+    InsufficientConfirmations,
+    
+    #[msg("Bidder is not on the auction's whitelist")]
+    BidderNotWhitelisted,
+    
+    #[msg("Fee tiers must have ascending volumes and decreasing fees")]
+    InvalidFeeTiers,
+    
+    #[msg("Inspection period must not be negative")]
+    InvalidInspectionPeriod,
+    
+    #[msg("Escrow is still within its inspection period")]
+    InspectionPeriodActive,
+    
+    #[msg("Escrow delivery has already been recorded")]
+    AlreadyDelivered,
+    
+    #[msg("The leading bid cannot be converted to store credit")]
+    LeadingBidNotConvertible,
+    
+    #[msg("Invalid store credit account")]
+    InvalidStoreCredit,
+    
+    #[msg("Listing has not been live for the minimum listing duration")]
+    ListingTooNew,
+    
+    #[msg("Minimum listing duration cannot be negative")]
+    InvalidMinListingDuration,
+    
+    #[msg("A cancellation reason of at most 200 characters is required")]
+    InvalidCancellationReason,
+    
+    #[msg("Receipt segment capacity must be between 1 and 64")]
+    InvalidReceiptSegmentCapacity,
+    
+    #[msg("Royalty receipt accounts do not match the creator's current segment")]
+    InvalidRoyaltyReceiptAccounts,
+    
+    #[msg("Buyer token account is not the buyer's usable associated token account")]
+    InvalidBuyerTokenAccount,
+    
+    #[msg("Winner claim window cannot be negative")]
+    InvalidClaimWindow,
+    
+    #[msg("This auction has no NFT awaiting the winner's claim")]
+    NoPendingClaim,
+    
+    #[msg("The winner's claim window has closed")]
+    ClaimWindowExpired,
+    
+    #[msg("The winner can still claim the NFT")]
+    ClaimWindowActive,
+    
+    #[msg("Royalty accounts do not match the listing's royalty path")]
+    RoyaltyPathMismatch,
+    
+    #[msg("This NFT has reached the maximum number of auctions")]
+    MintAuctionLimitReached,
+    
+    #[msg("Seller must be verified to list at this price")]
+    SellerVerificationRequired,
+    
+    #[msg("Seller token account is not the seller's associated token account")]
+    InvalidSellerTokenAccount,
+    
+    #[msg("Arbitrator token account is not owned by the arbitrator")]
+    InvalidArbitratorTokenAccount,
+    
+    #[msg("Dispute delay must not be negative and must end before the escrow expires")]
+    InvalidDisputeDelay,
+    
+    #[msg("Escrow is too new to be disputed")]
+    DisputeTooEarly,
+    
+    #[msg("Buy quantity must be at least one")]
+    InvalidBuyQuantity,
+    
+    #[msg("Buy quantity exceeds the units left on the listing")]
+    InsufficientListingQuantity,
+    
+    #[msg("This listing must be bought in full")]
+    PartialFillNotAllowed,
+    
+    #[msg("Listing unit accounts are missing or do not match the listing")]
+    InvalidListingUnitAccounts,
+    
+    #[msg("Only fixed-price listings can hold multiple units")]
+    MultiUnitRequiresFixedPrice,
+    
+    #[msg("Increment tiers must have ascending prices and increments")]
+    InvalidIncrementTiers,
+    
+    #[msg("Royalty config names more recipients than allowed")]
+    TooManyRoyaltyRecipients,
+    
+    #[msg("Cannot buy your own listing")]
+    CannotBuyOwnListing,
+    
+    #[msg("Winner is not the auction's highest bidder")]
+    NotHighestBidder,
+    
+    #[msg("Resolution amounts do not match the decision")]
+    ResolutionAmountsMismatchDecision,
+    
+    #[msg("Listing unit is not the same ticket as the listed one")]
+    ListingUnitMismatch,
+    
+    #[msg("Withdrawal amount must be greater than zero")]
+    InvalidWithdrawalAmount,
+    
+    #[msg("Marketplace does not match the one the dispute was opened on")]
+    InvalidMarketplace,
+    
+    #[msg("Auction has already started")]
+    AuctionAlreadyStarted,
 }
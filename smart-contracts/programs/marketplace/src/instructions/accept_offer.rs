@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::{state::*, errors::*, OfferAccepted, RoyaltyPaid, SellerFeeTierChanged};
+use super::buy_ticket::royalty_payee;
+use super::enable_royalty_receipts::record_royalty_receipt;
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// The marketplace configuration
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        constraint = !marketplace_config.is_paused @ MarketplaceError::MarketplacePaused
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    /// The listing being sold
+    #[account(
+        mut,
+        seeds = [b"listing", mint.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.is_active @ MarketplaceError::ListingNotActive,
+        constraint = listing.allow_offers @ MarketplaceError::OffersNotAllowed
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// Seller's activity counters; the sale counts toward their fee tier
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+
+    /// The accepted offer; closing it returns its rent to the buyer
+    #[account(
+        mut,
+        seeds = [b"offer", listing.key().as_ref(), buyer.key().as_ref()],
+        bump = offer.bump,
+        has_one = listing,
+        has_one = buyer,
+        close = buyer
+    )]
+    pub offer: Account<'info, MarketplaceOffer>,
+
+    /// The buyer who made the offer
+    #[account(mut)]
+    /// CHECK: Validated against the offer
+    pub buyer: UncheckedAccount<'info>,
+
+    /// Escrow token account holding the NFT
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = listing,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    /// Buyer's token account to receive the NFT
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    /// The NFT mint
+    pub mint: Account<'info, Mint>,
+
+    /// Platform fee recipient
+    #[account(
+        mut,
+        constraint = fee_recipient.key() == marketplace_config.admin @ MarketplaceError::InvalidMarketplaceAuthority
+    )]
+    /// CHECK: Validated against the marketplace admin
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    /// Royalty recipient
+    #[account(
+        mut,
+        constraint = listing.royalty_config.as_ref().is_none_or(|royalty_config| royalty_config.creator == royalty_recipient.key()) @ MarketplaceError::InvalidRoyaltyRecipient
+    )]
+    /// CHECK: Validated against the listing's royalty config
+    pub royalty_recipient: UncheckedAccount<'info>,
+
+    /// Receives the royalty instead if the royalty recipient account is closed
+    #[account(
+        mut,
+        constraint = fallback_royalty_recipient.key() == marketplace_config.fallback_royalty_recipient @ MarketplaceError::InvalidRoyaltyRecipient
+    )]
+    /// CHECK: Fallback royalty recipient
+    pub fallback_royalty_recipient: UncheckedAccount<'info>,
+
+    /// The royalty recipient's receipt index; receipts are only kept once it exists
+    #[account(
+        mut,
+        seeds = [b"royalty_receipt_index", royalty_recipient.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA derived from the royalty recipient, may be uninitialized
+    pub royalty_receipt_index: UncheckedAccount<'info>,
+
+    /// The royalty recipient's current receipt segment
+    #[account(mut)]
+    /// CHECK: Validated against the receipt index in the handler
+    pub royalty_receipt_segment: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Move lamports out of the program-owned offer account
+fn pay_from_offer(offer: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    **offer.try_borrow_mut_lamports()? = offer
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(MarketplaceError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(MarketplaceError::MathOverflow)?;
+    Ok(())
+}
+
+pub fn handler(ctx: Context<AcceptOffer>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let price = ctx.accounts.offer.amount;
+
+    let (royalty_bps, min_royalty) = listing
+        .royalty_config
+        .as_ref()
+        .map(|royalty_config| (royalty_config.percentage_bps, royalty_config.min_royalty))
+        .unwrap_or((0, 0));
+
+    let tier_fee_bps = ctx
+        .accounts
+        .marketplace_config
+        .fee_bps_for_volume(ctx.accounts.seller_stats.total_volume);
+
+    let (platform_fee, royalty_fee, seller_proceeds) = split_sale_price(
+        price,
+        tier_fee_bps,
+        royalty_bps,
+        min_royalty,
+        ctx.accounts.marketplace_config.rounding_mode,
+    )
+    .ok_or(MarketplaceError::MathOverflow)?;
+
+    // Transfer NFT to the buyer
+    let mint_key = ctx.accounts.mint.key();
+    let listing_seeds = &[
+        b"listing",
+        mint_key.as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    // Pay out the escrowed offer to the seller, platform, and royalty recipient
+    let offer_info = ctx.accounts.offer.to_account_info();
+    pay_from_offer(&offer_info, &ctx.accounts.seller.to_account_info(), seller_proceeds)?;
+    pay_from_offer(&offer_info, ctx.accounts.fee_recipient.as_ref(), platform_fee)?;
+
+    if royalty_fee > 0 {
+        let (payee, used_fallback) = royalty_payee(
+            ctx.accounts.royalty_recipient.as_ref(),
+            ctx.accounts.fallback_royalty_recipient.as_ref(),
+        );
+        pay_from_offer(&offer_info, payee, royalty_fee)?;
+        if !used_fallback {
+            record_royalty_receipt(
+                ctx.program_id,
+                ctx.accounts.royalty_receipt_index.as_ref(),
+                ctx.accounts.royalty_receipt_segment.as_ref(),
+                ctx.accounts.seller.as_ref(),
+                ctx.accounts.system_program.as_ref(),
+                payee.key(),
+                RoyaltyReceipt {
+                    sale: listing.key(),
+                    amount: royalty_fee,
+                    timestamp: Clock::get()?.unix_timestamp,
+                    payer: ctx.accounts.buyer.key(),
+                },
+            )?;
+        }
+
+        emit!(RoyaltyPaid {
+            listing: listing.key(),
+            recipient: payee.key(),
+            amount: royalty_fee,
+            used_fallback,
+        });
+    }
+
+    // Update marketplace stats
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.total_volume = marketplace_config.total_volume
+        .checked_add(price)
+        .ok_or(MarketplaceError::MathOverflow)?;
+    marketplace_config.total_fees_collected = marketplace_config.total_fees_collected
+        .checked_add(platform_fee)
+        .ok_or(MarketplaceError::MathOverflow)?;
+
+    // Count the sale toward the seller's fee tier
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    seller_stats.record_sale(price)?;
+    let new_tier_fee_bps = marketplace_config.fee_bps_for_volume(seller_stats.total_volume);
+    if new_tier_fee_bps != tier_fee_bps {
+        emit!(SellerFeeTierChanged {
+            seller: ctx.accounts.seller.key(),
+            old_fee_bps: tier_fee_bps,
+            new_fee_bps: new_tier_fee_bps,
+            total_volume: seller_stats.total_volume,
+        });
+    }
+
+    let listing = &mut ctx.accounts.listing;
+    listing.is_active = false;
+
+    emit!(OfferAccepted {
+        offer: ctx.accounts.offer.key(),
+        listing: listing.key(),
+        buyer: ctx.accounts.buyer.key(),
+        seller: ctx.accounts.seller.key(),
+        amount: price,
+        platform_fee,
+        royalty_fee,
+    });
+
+    Ok(())
+}
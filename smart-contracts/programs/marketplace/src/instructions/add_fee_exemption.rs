@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::state::FeeExemption;
+use crate::{MarketplaceConfig, FeeExemptionAdded};
+
+#[derive(Accounts)]
+#[instruction(seller: Pubkey)]
+pub struct AddFeeExemption<'info> {
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FeeExemption::INIT_SPACE,
+        seeds = [b"fee_exemption", seller.as_ref()],
+        bump
+    )]
+    pub fee_exemption: Account<'info, FeeExemption>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddFeeExemption>, seller: Pubkey) -> Result<()> {
+    let fee_exemption = &mut ctx.accounts.fee_exemption;
+    fee_exemption.seller = seller;
+    fee_exemption.added_by = ctx.accounts.admin.key();
+    fee_exemption.added_at = Clock::get()?.unix_timestamp;
+    fee_exemption.bump = *ctx.bumps.get("fee_exemption").unwrap();
+
+    emit!(FeeExemptionAdded {
+        seller,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
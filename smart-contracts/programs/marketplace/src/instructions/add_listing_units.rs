@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, Transfer};
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use crate::state::{Listing, ListingState, ListingType};
+use crate::errors::MarketplaceError;
+use crate::ListingUnitsAdded;
+
+/// Accounts passed per added unit: mint, metadata, seller token account, escrow
+const ACCOUNTS_PER_UNIT: usize = 4;
+
+#[derive(Accounts)]
+pub struct AddListingUnits<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// The fixed-price listing gaining units
+    #[account(
+        mut,
+        seeds = [b"listing", listing.ticket_mint.as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.state == ListingState::Active @ MarketplaceError::ListingNoLongerActive,
+        constraint = listing.listing_type == ListingType::FixedPrice @ MarketplaceError::MultiUnitRequiresFixedPrice
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// Metaplex metadata of the listed ticket, which every added unit must match
+    /// CHECK: Owner and mint are validated in the handler
+    pub ticket_metadata: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves more identical tickets into the listing's escrow, each passed in the
+/// remaining accounts as a `(mint, metadata, seller token account, escrow token
+/// account)` quadruple, where the escrow is the listing's associated token
+/// account. A unit is identical when it sits in the listed ticket's verified
+/// collection or, for a ticket outside any collection, shares its update
+/// authority, symbol and URI.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, AddListingUnits<'info>>,
+    allow_partial_fill: bool,
+) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    require!(
+        !remaining.is_empty() && remaining.len() % ACCOUNTS_PER_UNIT == 0,
+        MarketplaceError::InvalidListingUnitAccounts
+    );
+
+    let listed_metadata = ticket_metadata(&ctx.accounts.ticket_metadata, &ctx.accounts.listing.ticket_mint)?;
+
+    let listing_key = ctx.accounts.listing.key();
+    for unit in remaining.chunks(ACCOUNTS_PER_UNIT) {
+        let (mint, metadata, seller_token_account, escrow) = (&unit[0], &unit[1], &unit[2], &unit[3]);
+        let unit_metadata = ticket_metadata(metadata, &mint.key())?;
+        require!(
+            is_same_ticket(&listed_metadata, &unit_metadata),
+            MarketplaceError::ListingUnitMismatch
+        );
+        require_keys_eq!(
+            escrow.key(),
+            get_associated_token_address(&listing_key, &mint.key()),
+            MarketplaceError::InvalidListingUnitAccounts
+        );
+
+        if escrow.data_is_empty() {
+            associated_token::create(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: ctx.accounts.seller.to_account_info(),
+                    associated_token: escrow.clone(),
+                    authority: ctx.accounts.listing.to_account_info(),
+                    mint: mint.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            ))?;
+        }
+
+        // The token program rejects a seller account that doesn't hold the unit
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: seller_token_account.clone(),
+                    to: escrow.clone(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+    }
+
+    let units_added = (remaining.len() / ACCOUNTS_PER_UNIT) as u32;
+    let listing = &mut ctx.accounts.listing;
+    listing.quantity = listing
+        .units_available()
+        .checked_add(units_added)
+        .ok_or(MarketplaceError::MathOverflow)?;
+    listing.allow_partial_fill = allow_partial_fill;
+
+    emit!(ListingUnitsAdded {
+        listing: listing_key,
+        seller: ctx.accounts.seller.key(),
+        units_added,
+        quantity: listing.quantity,
+        allow_partial_fill,
+    });
+
+    Ok(())
+}
+
+/// The Metaplex metadata in `metadata_info`, checked to belong to `mint`
+fn ticket_metadata(metadata_info: &AccountInfo, mint: &Pubkey) -> Result<Metadata> {
+    require!(
+        metadata_info.owner == &mpl_token_metadata::ID && !metadata_info.data_is_empty(),
+        MarketplaceError::InvalidNftMetadata
+    );
+
+    let metadata = Metadata::from_account_info(metadata_info)
+        .map_err(|_| error!(MarketplaceError::InvalidNftMetadata))?;
+    require_keys_eq!(metadata.mint, *mint, MarketplaceError::InvalidNftMetadata);
+
+    Ok(metadata)
+}
+
+/// Whether `unit` is the same ticket as the `listed` one: in its verified
+/// collection if it has one, otherwise issued by the same update authority
+/// with the same symbol and URI
+fn is_same_ticket(listed: &Metadata, unit: &Metadata) -> bool {
+    let verified_collection = |metadata: &Metadata| {
+        metadata
+            .collection
+            .as_ref()
+            .filter(|collection| collection.verified)
+            .map(|collection| collection.key)
+    };
+
+    match verified_collection(listed) {
+        Some(collection) => verified_collection(unit) == Some(collection),
+        None => {
+            unit.update_authority == listed.update_authority
+                && unit.data.symbol == listed.data.symbol
+                && unit.data.uri == listed.data.uri
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::state::SellerVerification;
+use crate::{MarketplaceConfig, SellerVerificationAdded};
+
+#[derive(Accounts)]
+#[instruction(seller: Pubkey)]
+pub struct AddSellerVerification<'info> {
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + SellerVerification::INIT_SPACE,
+        seeds = [b"seller_verification", seller.as_ref()],
+        bump
+    )]
+    pub seller_verification: Account<'info, SellerVerification>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddSellerVerification>, seller: Pubkey) -> Result<()> {
+    let seller_verification = &mut ctx.accounts.seller_verification;
+    seller_verification.seller = seller;
+    seller_verification.verified_by = ctx.accounts.admin.key();
+    seller_verification.verified_at = Clock::get()?.unix_timestamp;
+    seller_verification.bump = *ctx.bumps.get("seller_verification").unwrap();
+
+    emit!(SellerVerificationAdded {
+        seller,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
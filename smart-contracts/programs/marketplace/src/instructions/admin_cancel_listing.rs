@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::{state::*, errors::*};
+use crate::ListingCancelled;
+
+#[derive(Accounts)]
+pub struct AdminCancelListing<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// The marketplace configuration
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    /// The listing to cancel
+    #[account(
+        mut,
+        seeds = [b"listing", mint.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.is_active @ MarketplaceError::ListingNotActive
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    /// Escrow token account holding the NFT
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = listing,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// Seller's token account receiving the NFT back
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    /// Seller of the listing
+    /// CHECK: Checked against the listing
+    pub seller: UncheckedAccount<'info>,
+    
+    /// Seller's activity counters; an auction leaving the book frees a slot
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+    
+    /// The NFT mint
+    pub mint: Account<'info, Mint>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AdminCancelListing>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    
+    // The override skips the minimum listing duration, not the auction rules
+    if listing.listing_type == ListingType::Auction {
+        let clock = Clock::get()?;
+        if let Some(ref auction_config) = listing.auction_config {
+            require!(
+                clock.unix_timestamp < auction_config.start_time,
+                MarketplaceError::AuctionAlreadyStarted
+            );
+        }
+    }
+
+    // Return NFT to seller
+    let listing_seeds = &[
+        b"listing",
+        ctx.accounts.mint.key().as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.is_active = false;
+    if listing.listing_type == ListingType::Auction {
+        ctx.accounts.seller_stats.close_auction();
+    }
+
+    emit!(ListingCancelled {
+        listing: listing.key(),
+        seller: listing.seller,
+        mint: ctx.accounts.mint.key(),
+    });
+
+    Ok(())
+}
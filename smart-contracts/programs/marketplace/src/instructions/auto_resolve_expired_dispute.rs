@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::MarketplaceError;
+
+#[derive(Accounts)]
+pub struct AutoResolveExpiredDispute<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = dispute.state == DisputeState::Open @ MarketplaceError::DisputeNotOpen,
+        constraint = dispute.escrow == escrow.key() @ MarketplaceError::InvalidEscrow
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(
+        mut,
+        constraint = escrow.state == EscrowState::Disputed @ MarketplaceError::EscrowNotDisputed,
+        constraint = caller.key() == escrow.buyer || caller.key() == escrow.seller @ MarketplaceError::UnauthorizedEscrowParticipant
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == escrow.key() @ MarketplaceError::InvalidEscrowTokenAccount
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.owner == escrow.buyer @ MarketplaceError::InvalidTokenAccountOwner
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = seller_token_account.owner == escrow.seller @ MarketplaceError::InvalidSellerTokenAccount
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = dispute.marketplace @ MarketplaceError::InvalidMarketplace
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", marketplace.key().as_ref()],
+        bump = fee_vault.bump
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == fee_vault.key() @ MarketplaceError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<AutoResolveExpiredDispute>) -> Result<()> {
+    let dispute = &mut ctx.accounts.dispute;
+    let escrow = &mut ctx.accounts.escrow;
+    let clock = Clock::get()?;
+
+    // The arbitrator keeps exclusive control until the deadline passes
+    require!(
+        dispute.is_past_deadline(clock.unix_timestamp),
+        MarketplaceError::DisputeDeadlineNotReached
+    );
+
+    // The escrow's default only applies if the dispute category permits it
+    let decision = dispute.category.fallback_resolution(escrow.default_resolution);
+
+    // Split the escrowed amount according to the default outcome
+    let (buyer_amount, seller_gross) = match decision {
+        ResolutionDecision::FavorBuyer => (escrow.amount, 0),
+        ResolutionDecision::FavorSeller => (0, escrow.amount),
+        ResolutionDecision::Split => {
+            let buyer_half = escrow.amount / 2;
+            (buyer_half, escrow.amount - buyer_half)
+        }
+    };
+
+    // Platform fee only applies to the portion released to the seller
+    let platform_fee = (seller_gross as u128)
+        .checked_mul(escrow.platform_fee_rate as u128)
+        .ok_or(MarketplaceError::ArithmeticOverflow)?
+        .checked_div(10000)
+        .ok_or(MarketplaceError::DivisionByZero)? as u64;
+    let seller_amount = seller_gross
+        .checked_sub(platform_fee)
+        .ok_or(MarketplaceError::ArithmeticUnderflow)?;
+
+    // Create signer seeds
+    let escrow_key = escrow.key();
+    let seeds = &[b"escrow", escrow_key.as_ref(), &[escrow.bump]];
+    let signer = &[&seeds[..]];
+
+    if buyer_amount > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, buyer_amount)?;
+    }
+
+    if seller_amount > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, seller_amount)?;
+    }
+
+    // The platform fee goes into the fee vault, where withdraw_fees can reach it
+    if platform_fee > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
+            authority: escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, platform_fee)?;
+
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.accumulated_fees = fee_vault
+            .accumulated_fees
+            .checked_add(platform_fee)
+            .ok_or(MarketplaceError::Overflow)?;
+    }
+
+    // Update dispute state
+    dispute.state = DisputeState::Resolved;
+    dispute.decision = Some(decision);
+    dispute.reasoning = Some("Arbitrator deadline elapsed; default resolution applied".to_string());
+    dispute.resolved_at = Some(clock.unix_timestamp);
+
+    // A resolved critical dispute no longer blocks unpausing
+    if dispute.category.is_critical() {
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.open_critical_disputes = marketplace.open_critical_disputes.saturating_sub(1);
+    }
+
+    // Update escrow state
+    escrow.amount = 0;
+    escrow.state = EscrowState::Completed;
+    escrow.completed_at = Some(clock.unix_timestamp);
+
+    emit!(DisputeAutoResolved {
+        dispute: dispute.key(),
+        escrow: escrow.key(),
+        decision,
+        buyer_amount,
+        seller_amount,
+        platform_fee,
+        triggered_by: ctx.accounts.caller.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
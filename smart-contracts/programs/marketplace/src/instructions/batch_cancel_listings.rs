@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use anchor_spl::associated_token::get_associated_token_address;
+use crate::{state::*, errors::*};
+use crate::{ListingCancelled, ListingsBatchCancelled};
+
+/// Accounts supplied per listing in `remaining_accounts`:
+/// listing, mint, escrow token account, seller token account
+pub const ACCOUNTS_PER_LISTING: usize = 4;
+
+/// Maximum number of listings cancelled in one transaction
+pub const MAX_BATCH_CANCEL: usize = 8;
+
+#[derive(Accounts)]
+pub struct BatchCancelListings<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// The marketplace configuration
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    /// Seller's activity counters; canceled auctions free a slot
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, BatchCancelListings<'info>>) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    require!(
+        !remaining.is_empty() && remaining.len() % ACCOUNTS_PER_LISTING == 0,
+        MarketplaceError::InvalidBatchAccounts
+    );
+    require!(
+        remaining.len() / ACCOUNTS_PER_LISTING <= MAX_BATCH_CANCEL,
+        MarketplaceError::BatchTooLarge
+    );
+
+    let seller = ctx.accounts.seller.key();
+    let clock = Clock::get()?;
+    let mut cancelled: u8 = 0;
+    let mut skipped: u8 = 0;
+
+    for accounts in remaining.chunks(ACCOUNTS_PER_LISTING) {
+        let listing_info = &accounts[0];
+        let mint_info = &accounts[1];
+        let escrow_info = &accounts[2];
+        let seller_token_info = &accounts[3];
+
+        // Listings owned by someone else, or already closed out, are skipped
+        let mut listing = match Account::<Listing>::try_from(listing_info) {
+            Ok(listing) => listing,
+            Err(_) => {
+                skipped += 1;
+                continue;
+            }
+        };
+        if listing.seller != seller || !listing.is_active {
+            skipped += 1;
+            continue;
+        }
+
+        // Same rules as a single cancel: fixed-price listings stay up for the
+        // minimum duration, and auctions can't be pulled once they start
+        if listing.listing_type == ListingType::FixedPrice
+            && !ctx
+                .accounts
+                .marketplace_config
+                .min_listing_duration_elapsed(listing.created_at, clock.unix_timestamp)
+        {
+            skipped += 1;
+            continue;
+        }
+        if listing.listing_type == ListingType::Auction {
+            if let Some(ref auction_config) = listing.auction_config {
+                if clock.unix_timestamp >= auction_config.start_time {
+                    skipped += 1;
+                    continue;
+                }
+            }
+        }
+
+        let (expected_listing, bump) = Pubkey::find_program_address(
+            &[b"listing", mint_info.key.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(expected_listing, listing_info.key(), MarketplaceError::InvalidBatchAccounts);
+        require_keys_eq!(
+            get_associated_token_address(&listing_info.key(), mint_info.key),
+            escrow_info.key(),
+            MarketplaceError::InvalidBatchAccounts
+        );
+        require_keys_eq!(
+            get_associated_token_address(&seller, mint_info.key),
+            seller_token_info.key(),
+            MarketplaceError::InvalidBatchAccounts
+        );
+        let escrow = Account::<TokenAccount>::try_from(escrow_info)?;
+
+        let mint_key = mint_info.key();
+        let listing_seeds = &[b"listing", mint_key.as_ref(), &[bump]];
+        let signer_seeds = &[&listing_seeds[..]];
+
+        // Return the NFT to the seller
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: escrow_info.clone(),
+                    to: seller_token_info.clone(),
+                    authority: listing_info.clone(),
+                },
+                signer_seeds,
+            ),
+            escrow.amount,
+        )?;
+
+        // Close the emptied escrow so its rent goes back to the seller
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_info.clone(),
+                destination: ctx.accounts.seller.to_account_info(),
+                authority: listing_info.clone(),
+            },
+            signer_seeds,
+        ))?;
+
+        listing.is_active = false;
+        listing.exit(ctx.program_id)?;
+        if listing.listing_type == ListingType::Auction {
+            ctx.accounts.seller_stats.close_auction();
+        }
+        cancelled += 1;
+
+        emit!(ListingCancelled {
+            listing: listing_info.key(),
+            seller,
+            mint: mint_key,
+        });
+    }
+
+    emit!(ListingsBatchCancelled {
+        seller,
+        cancelled,
+        skipped,
+    });
+
+    Ok(())
+}
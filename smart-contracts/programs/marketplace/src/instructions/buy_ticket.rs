@@ -1,20 +1,30 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, TokenAccount, Token, Mint};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
 
-use crate::state::{Listing, ListingState, RoyaltyRecipient};
+use crate::state::{FeeExemption, Listing, ListingState, RoyaltyPath, RoyaltyReceipt, RoyaltyRecipient, SellerStats, StoreCredit};
+use crate::{MarketplaceConfig, RoyaltyPaid, SellerFeeTierChanged, StoreCreditSpent};
 use crate::errors::MarketplaceError;
+use super::enable_royalty_receipts::record_royalty_receipt;
 
 #[derive(Accounts)]
 pub struct BuyTicket<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
     
+    /// The marketplace configuration (for the rounding mode and fallback royalty recipient)
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
     /// The listing being purchased
     #[account(
         mut,
         constraint = listing.state == ListingState::Active @ MarketplaceError::ListingNoLongerActive,
         constraint = listing.listing_type == crate::state::ListingType::FixedPrice,
+        constraint = listing.seller != buyer.key() @ MarketplaceError::CannotBuyOwnListing,
         seeds = [b"listing", listing.ticket_mint.as_ref()],
         bump = listing.bump
     )]
@@ -27,6 +37,22 @@ pub struct BuyTicket<'info> {
     )]
     pub seller: AccountInfo<'info>,
     
+    /// The seller's fee exemption record; the platform fee is waived if it exists
+    #[account(
+        seeds = [b"fee_exemption", seller.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA derived from the seller, may be uninitialized
+    pub fee_exemption: UncheckedAccount<'info>,
+    
+    /// Seller's activity counters; the sale counts toward their fee tier
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+    
     /// The marketplace authority who receives the fee
     #[account(
         mut,
@@ -34,21 +60,36 @@ pub struct BuyTicket<'info> {
     )]
     pub marketplace_authority: AccountInfo<'info>,
     
-    /// The royalty recipient (for basic royalty distribution)
+    /// The royalty recipient, only paid on the legacy royalty path
     #[account(
         mut,
         constraint = royalty_recipient.key() == listing.royalty_recipient @ MarketplaceError::InvalidRoyaltyRecipient
     )]
     pub royalty_recipient: AccountInfo<'info>,
     
-    /// Multiple royalty recipients (only needed for enhanced royalty)
-    /// If not using enhanced royalties, this can be empty
-    pub royalty_recipients: Option<Vec<AccountInfo<'info>>>,
+    /// Receives the royalty instead if the royalty recipient account is closed
+    #[account(
+        mut,
+        constraint = fallback_royalty_recipient.key() == marketplace_config.fallback_royalty_recipient @ MarketplaceError::InvalidRoyaltyRecipient
+    )]
+    pub fallback_royalty_recipient: AccountInfo<'info>,
     
-    /// The ticket mint
+    /// The royalty recipient's receipt index; receipts are only kept once it exists
     #[account(
-        constraint = ticket_mint.key() == listing.ticket_mint
+        mut,
+        seeds = [b"royalty_receipt_index", royalty_recipient.key().as_ref()],
+        bump
     )]
+    /// CHECK: PDA derived from the royalty recipient, may be uninitialized
+    pub royalty_receipt_index: UncheckedAccount<'info>,
+    
+    /// The royalty recipient's current receipt segment
+    #[account(mut)]
+    /// CHECK: Validated against the receipt index in the handler
+    pub royalty_receipt_segment: UncheckedAccount<'info>,
+    
+    /// The mint of a unit still held by the listing; for a single-ticket
+    /// listing this is the listed ticket
     pub ticket_mint: Account<'info, Mint>,
     
     /// The escrow account holding the ticket NFT
@@ -61,12 +102,11 @@ pub struct BuyTicket<'info> {
     
     /// The buyer's token account to receive the NFT
     #[account(
-        init_if_needed,
-        payer = buyer,
-        associated_token::mint = ticket_mint,
-        associated_token::authority = buyer
+        mut,
+        address = get_associated_token_address(&buyer.key(), &ticket_mint.key()) @ MarketplaceError::InvalidBuyerTokenAccount
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Buyer's associated token account, created in the handler before any payment
+    pub buyer_token_account: UncheckedAccount<'info>,
     
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
@@ -74,7 +114,13 @@ pub struct BuyTicket<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handler(ctx: Context<BuyTicket>) -> Result<()> {
+/// Buy `buy_quantity` units of a fixed-price listing. The buyer's
+/// `StoreCredit` account may be passed as the first remaining account to put
+/// their credit toward the price. On the enhanced royalty path the royalty
+/// recipients follow it, in the order of the listing's `royalty_config`.
+/// Every unit past the first is passed last, as a `(mint, escrow token
+/// account, buyer token account)` triple.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, BuyTicket<'info>>, buy_quantity: u32) -> Result<()> {
     let listing = &mut ctx.accounts.listing;
     
     // Validate the ticket can be transferred
@@ -85,11 +131,116 @@ pub fn handler(ctx: Context<BuyTicket>) -> Result<()> {
     let now = clock.unix_timestamp;
     require!(now < listing.event_start_time, MarketplaceError::EventAlreadyStarted);
     
-    // Get the sale price
-    let price = listing.price;
+    // Buyers take every unit unless the seller allows partial fills
+    let units_available = listing.units_available();
+    require!(buy_quantity > 0, MarketplaceError::InvalidBuyQuantity);
+    require!(buy_quantity <= units_available, MarketplaceError::InsufficientListingQuantity);
+    require!(
+        buy_quantity == units_available || listing.allow_partial_fill,
+        MarketplaceError::PartialFillNotAllowed
+    );
+    
+    // The listing price is per unit
+    let price = listing
+        .price
+        .checked_mul(buy_quantity as u64)
+        .ok_or(MarketplaceError::MathOverflow)?;
+    
+    // Split the extra units off the end of the remaining accounts
+    let unit_account_count = (buy_quantity as usize - 1) * 3;
+    require!(
+        ctx.remaining_accounts.len() >= unit_account_count,
+        MarketplaceError::InvalidListingUnitAccounts
+    );
+    let (remaining, unit_accounts) = ctx
+        .remaining_accounts
+        .split_at(ctx.remaining_accounts.len() - unit_account_count);
+    
+    // Exactly one royalty path pays, so the remaining accounts must hold the
+    // enhanced recipients only when the listing uses them. The recipients come
+    // first, then each recipient's receipt index and current receipt segment.
+    let recipient_count = listing.royalty_recipient_count()?;
+    let royalty_account_count = recipient_count * 3;
+    let (credit_account, royalty_accounts) = if remaining.len() == royalty_account_count {
+        (None, remaining)
+    } else if remaining.len() == royalty_account_count + 1 {
+        (Some(&remaining[0]), &remaining[1..])
+    } else {
+        return err!(MarketplaceError::RoyaltyPathMismatch);
+    };
+    let (recipient_accounts, receipt_accounts) = royalty_accounts.split_at(recipient_count);
+    
+    // Make sure the NFT can be delivered before any lamports move
+    if ctx.accounts.buyer_token_account.data_is_empty() {
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.buyer.to_account_info(),
+                associated_token: ctx.accounts.buyer_token_account.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+                mint: ctx.accounts.ticket_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+    } else {
+        let buyer_token_account = Account::<TokenAccount>::try_from(ctx.accounts.buyer_token_account.as_ref())?;
+        require!(!buyer_token_account.is_frozen(), MarketplaceError::InvalidBuyerTokenAccount);
+    }
+    for unit in unit_accounts.chunks(3) {
+        prepare_extra_unit(
+            unit,
+            listing.key(),
+            &ctx.accounts.buyer,
+            &ctx.accounts.associated_token_program,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+        )?;
+    }
+    
+    // Release store credit to the buyer first, so it pays toward the sale below
+    if let Some(credit_info) = credit_account {
+        let mut store_credit = Account::<StoreCredit>::try_from(credit_info)?;
+        require_keys_eq!(
+            store_credit.owner,
+            ctx.accounts.buyer.key(),
+            MarketplaceError::InvalidStoreCredit
+        );
+        let spent = store_credit.spend(price);
+        if spent > 0 {
+            let buyer_info = ctx.accounts.buyer.to_account_info();
+            **credit_info.try_borrow_mut_lamports()? = credit_info
+                .lamports()
+                .checked_sub(spent)
+                .ok_or(MarketplaceError::InsufficientFunds)?;
+            **buyer_info.try_borrow_mut_lamports()? = buyer_info
+                .lamports()
+                .checked_add(spent)
+                .ok_or(MarketplaceError::MathOverflow)?;
+            store_credit.exit(ctx.program_id)?;
+            
+            emit!(StoreCreditSpent {
+                owner: store_credit.owner,
+                amount: spent,
+                remaining: store_credit.balance,
+            });
+        }
+    }
     
-    // Calculate marketplace fee
-    let marketplace_fee = listing.calculate_marketplace_fee(price);
+    // Calculate marketplace fee at the seller's volume tier, waived for exempt sellers
+    let rounding_mode = ctx.accounts.marketplace_config.rounding_mode;
+    let tier_fee_bps = ctx
+        .accounts
+        .marketplace_config
+        .fee_bps_for_volume(ctx.accounts.seller_stats.total_volume);
+    let marketplace_fee = if FeeExemption::is_active(&ctx.accounts.fee_exemption) {
+        0
+    } else {
+        rounding_mode
+            .apply_bps(price, tier_fee_bps)
+            .ok_or(MarketplaceError::MathOverflow)?
+            .min(price)
+    };
     
     // 1. Transfer marketplace fee
     if marketplace_fee > 0 {
@@ -107,40 +258,89 @@ pub fn handler(ctx: Context<BuyTicket>) -> Result<()> {
     // 2. Handle royalty distribution
     let mut royalty_fee = 0;
     
-    // Check if we have enhanced royalty config
-    if let Some(royalty_config) = &listing.royalty_config {
-        // Validate we have the required recipient accounts
-        if let Some(recipient_accounts) = &ctx.accounts.royalty_recipients {
-            require!(
-                recipient_accounts.len() == royalty_config.recipients.len(),
-                MarketplaceError::InvalidRoyaltyRecipients
-            );
-            
-            // Distribute royalties to all recipients
-            royalty_fee = royalty_config.distribute_royalties(
-                &ctx.accounts.buyer.to_account_info(),
-                recipient_accounts,
-                &ctx.accounts.system_program,
-                price,
-                &[]
+    if let (RoyaltyPath::Enhanced, Some(royalty_config)) = (listing.royalty_path, &listing.royalty_config) {
+        // Distribute royalties to all recipients
+        let payouts = royalty_config.distribute_royalties(
+            &ctx.accounts.buyer.to_account_info(),
+            recipient_accounts,
+            &ctx.accounts.system_program,
+            price,
+            price - marketplace_fee,
+            rounding_mode,
+            &[]
+        )?;
+        royalty_fee = payouts.iter().sum();
+        
+        // Keep a receipt for every recipient that was paid
+        for ((recipient_info, amount), receipt) in royalty_config
+            .recipients
+            .iter()
+            .zip(payouts)
+            .zip(receipt_accounts.chunks(2))
+        {
+            if amount == 0 {
+                continue;
+            }
+            record_royalty_receipt(
+                ctx.program_id,
+                &receipt[0],
+                &receipt[1],
+                ctx.accounts.buyer.as_ref(),
+                ctx.accounts.system_program.as_ref(),
+                recipient_info.recipient,
+                RoyaltyReceipt {
+                    sale: listing.key(),
+                    amount,
+                    timestamp: now,
+                    payer: ctx.accounts.buyer.key(),
+                },
             )?;
-        } else {
-            return Err(MarketplaceError::InvalidRoyaltyRecipients.into());
         }
     } else {
         // Use legacy royalty distribution to a single recipient
-        royalty_fee = listing.calculate_royalty_fee(price)?;
+        // Never distribute more than the price, even when rounding up
+        royalty_fee = listing
+            .calculate_royalty_fee(price, rounding_mode)?
+            .min(price - marketplace_fee);
         
         if royalty_fee > 0 {
+            let (payee, used_fallback) = royalty_payee(
+                &ctx.accounts.royalty_recipient,
+                &ctx.accounts.fallback_royalty_recipient,
+            );
             let royalty_accounts = anchor_lang::system_program::Transfer {
                 from: ctx.accounts.buyer.to_account_info(),
-                to: ctx.accounts.royalty_recipient.to_account_info(),
+                to: payee.clone(),
             };
             let royalty_ctx = CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
                 royalty_accounts,
             );
             anchor_lang::system_program::transfer(royalty_ctx, royalty_fee)?;
+            
+            emit!(RoyaltyPaid {
+                listing: listing.key(),
+                recipient: payee.key(),
+                amount: royalty_fee,
+                used_fallback,
+            });
+            
+            if !used_fallback {
+                record_royalty_receipt(
+                    ctx.program_id,
+                    ctx.accounts.royalty_receipt_index.as_ref(),
+                    ctx.accounts.royalty_receipt_segment.as_ref(),
+                    ctx.accounts.buyer.as_ref(),
+                    ctx.accounts.system_program.as_ref(),
+                    payee.key(),
+                    RoyaltyReceipt {
+                        sale: listing.key(),
+                        amount: royalty_fee,
+                        timestamp: now,
+                        payer: ctx.accounts.buyer.key(),
+                    },
+                )?;
+            }
         }
     }
     
@@ -180,8 +380,80 @@ pub fn handler(ctx: Context<BuyTicket>) -> Result<()> {
     
     token::transfer(cpi_ctx, 1)?;
     
-    // Update the listing state
-    listing.state = ListingState::Sold;
+    for unit in unit_accounts.chunks(3) {
+        let unit_transfer = token::Transfer {
+            from: unit[1].clone(),
+            to: unit[2].clone(),
+            authority: ctx.accounts.listing.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), unit_transfer, signer),
+            1,
+        )?;
+    }
+    
+    // Update the listing state, closing it once every unit has sold
+    listing.quantity = units_available - buy_quantity;
+    if listing.quantity == 0 {
+        listing.state = ListingState::Sold;
+    }
+    
+    // Count the sale toward the seller's fee tier
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    seller_stats.record_sale(price)?;
+    let new_tier_fee_bps = ctx.accounts.marketplace_config.fee_bps_for_volume(seller_stats.total_volume);
+    if new_tier_fee_bps != tier_fee_bps {
+        emit!(SellerFeeTierChanged {
+            seller: ctx.accounts.seller.key(),
+            old_fee_bps: tier_fee_bps,
+            new_fee_bps: new_tier_fee_bps,
+            total_volume: seller_stats.total_volume,
+        });
+    }
+    
+    Ok(())
+}
+
+/// Checks an extra unit's `(mint, escrow token account, buyer token account)`
+/// triple and creates the buyer's token account for it if needed, so every
+/// NFT can be delivered before any lamports move
+fn prepare_extra_unit<'info>(
+    unit: &[AccountInfo<'info>],
+    listing: Pubkey,
+    buyer: &Signer<'info>,
+    associated_token_program: &Program<'info, AssociatedToken>,
+    system_program: &Program<'info, System>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let (mint, escrow, buyer_token_account) = (&unit[0], &unit[1], &unit[2]);
+    
+    let escrow_account = Account::<TokenAccount>::try_from(escrow)?;
+    require!(
+        escrow_account.owner == listing && escrow_account.mint == mint.key(),
+        MarketplaceError::InvalidListingUnitAccounts
+    );
+    require_keys_eq!(
+        buyer_token_account.key(),
+        get_associated_token_address(&buyer.key(), &mint.key()),
+        MarketplaceError::InvalidBuyerTokenAccount
+    );
+    
+    if buyer_token_account.data_is_empty() {
+        associated_token::create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: buyer.to_account_info(),
+                associated_token: buyer_token_account.clone(),
+                authority: buyer.to_account_info(),
+                mint: mint.clone(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+    } else {
+        let buyer_token_account = Account::<TokenAccount>::try_from(buyer_token_account)?;
+        require!(!buyer_token_account.is_frozen(), MarketplaceError::InvalidBuyerTokenAccount);
+    }
     
     Ok(())
 }_fee)
@@ -251,3 +523,17 @@ pub fn handler(ctx: Context<BuyTicket>) -> Result<()> {
     
     Ok(())
 }
+
+/// Account that should receive a single-recipient royalty. A recipient with
+/// no lamports has been closed (or never existed), and paying it could strand
+/// the royalty or fail the sale on rent, so the fallback is paid instead.
+pub(crate) fn royalty_payee<'a, 'info>(
+    royalty_recipient: &'a AccountInfo<'info>,
+    fallback_royalty_recipient: &'a AccountInfo<'info>,
+) -> (&'a AccountInfo<'info>, bool) {
+    if royalty_recipient.lamports() == 0 {
+        (fallback_royalty_recipient, true)
+    } else {
+        (royalty_recipient, false)
+    }
+}
@@ -8,6 +8,13 @@ pub struct CancelListing<'info> {
     #[account(mut)]
     pub seller: Signer<'info>,
     
+    /// The marketplace configuration
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
     /// The listing to cancel
     #[account(
         mut,
@@ -35,6 +42,14 @@ pub struct CancelListing<'info> {
     )]
     pub seller_token_account: Account<'info, TokenAccount>,
     
+    /// Seller's activity counters; an auction leaving the book frees a slot
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+    
     /// The NFT mint
     pub mint: Account<'info, Mint>,
     
@@ -45,10 +60,20 @@ pub struct CancelListing<'info> {
 
 pub fn handler(ctx: Context<CancelListing>) -> Result<()> {
     let listing = &ctx.accounts.listing;
+    let clock = Clock::get()?;
+    
+    // Fixed-price listings must stay up for the minimum listing duration
+    if listing.listing_type == ListingType::FixedPrice {
+        require!(
+            ctx.accounts
+                .marketplace_config
+                .min_listing_duration_elapsed(listing.created_at, clock.unix_timestamp),
+            MarketplaceError::ListingTooNew
+        );
+    }
     
     // For auctions, check if there are any bids
     if listing.listing_type == ListingType::Auction {
-        let clock = Clock::get()?;
         if let Some(ref auction_config) = listing.auction_config {
             require!(
                 clock.unix_timestamp < auction_config.start_time,
@@ -79,6 +104,9 @@ pub fn handler(ctx: Context<CancelListing>) -> Result<()> {
     // Mark listing as inactive
     let listing = &mut ctx.accounts.listing;
     listing.is_active = false;
+    if listing.listing_type == ListingType::Auction {
+        ctx.accounts.seller_stats.close_auction();
+    }
 
     Ok(())
 }
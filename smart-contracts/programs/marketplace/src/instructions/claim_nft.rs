@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use solana_program::{system_instruction, program::invoke_signed};
+use crate::{state::*, errors::*};
+use crate::AuctionNftClaimed;
+use super::end_auction::{pay_escrowed_royalty, record_auction_sale, EscrowedRoyaltyAccounts};
+
+#[derive(Accounts)]
+pub struct ClaimNft<'info> {
+    /// Auction winner, paying for their own token account
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    
+    /// The marketplace configuration
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    /// The settled auction listing
+    #[account(
+        mut,
+        seeds = [b"listing", mint.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.claim_deadline.is_some() @ MarketplaceError::NoPendingClaim,
+        constraint = listing.highest_bidder == winner.key() @ MarketplaceError::InvalidOwner
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    /// Escrow token account holding NFT
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = listing,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// Winner's token account receiving the NFT
+    #[account(
+        init_if_needed,
+        payer = winner,
+        associated_token::mint = mint,
+        associated_token::authority = winner,
+    )]
+    pub winner_token_account: Account<'info, TokenAccount>,
+    
+    /// Winning bid account
+    #[account(
+        seeds = [b"bid", listing.key().as_ref(), winner.key().as_ref()],
+        bump = winning_bid.bump
+    )]
+    pub winning_bid: Account<'info, Bid>,
+    
+    /// Bid escrow holding the locked sale price
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", winning_bid.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA holding the locked sale price
+    pub bid_escrow: UncheckedAccount<'info>,
+    
+    /// The NFT mint
+    pub mint: Account<'info, Mint>,
+    
+    /// Seller receiving the locked proceeds
+    #[account(mut)]
+    /// CHECK: Checked against the listing
+    pub seller: UncheckedAccount<'info>,
+    
+    /// Seller's activity counters; the sale counts toward their volume once claimed
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+    
+    /// Platform fee recipient
+    #[account(
+        mut,
+        constraint = fee_recipient.key() == marketplace_config.admin @ MarketplaceError::InvalidMarketplaceAuthority
+    )]
+    /// CHECK: Validated against the marketplace admin
+    pub fee_recipient: UncheckedAccount<'info>,
+    
+    /// Royalty recipient
+    #[account(
+        mut,
+        constraint = listing.royalty_config.as_ref().is_none_or(|royalty_config| royalty_config.creator == royalty_recipient.key()) @ MarketplaceError::InvalidRoyaltyRecipient
+    )]
+    /// CHECK: Validated against the listing's royalty config
+    pub royalty_recipient: UncheckedAccount<'info>,
+    
+    /// Receives the royalty instead if the royalty recipient account is closed
+    #[account(
+        mut,
+        constraint = fallback_royalty_recipient.key() == marketplace_config.fallback_royalty_recipient @ MarketplaceError::InvalidRoyaltyRecipient
+    )]
+    /// CHECK: Fallback royalty recipient
+    pub fallback_royalty_recipient: UncheckedAccount<'info>,
+    
+    /// The royalty recipient's receipt index; receipts are only kept once it exists
+    #[account(
+        mut,
+        seeds = [b"royalty_receipt_index", royalty_recipient.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA derived from the royalty recipient, may be uninitialized
+    pub royalty_receipt_index: UncheckedAccount<'info>,
+    
+    /// The royalty recipient's current receipt segment
+    #[account(mut)]
+    /// CHECK: Validated against the receipt index in the handler
+    pub royalty_receipt_segment: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// A royalty paid in a token takes the same `remaining_accounts` as `end_auction`
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ClaimNft<'info>>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let clock = Clock::get()?;
+    
+    let claim_deadline = listing.claim_deadline.ok_or(MarketplaceError::NoPendingClaim)?;
+    require!(
+        clock.unix_timestamp <= claim_deadline,
+        MarketplaceError::ClaimWindowExpired
+    );
+
+    // Deliver the NFT to the winner
+    let listing_seeds = &[
+        b"listing",
+        ctx.accounts.mint.key().as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.winner_token_account.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    // Release the sale payments held since settlement
+    let proceeds = listing.locked_proceeds;
+    let platform_fee = listing.locked_platform_fee;
+    let royalty_fee = listing.locked_royalty;
+    let winning_bid_key = ctx.accounts.winning_bid.key();
+    let bid_escrow_seeds = &[
+        b"bid_escrow",
+        winning_bid_key.as_ref(),
+        &[*ctx.bumps.get("bid_escrow").unwrap()],
+    ];
+    let bid_signer_seeds = &[&bid_escrow_seeds[..]];
+
+    if proceeds > 0 {
+        invoke_signed(
+            &system_instruction::transfer(
+                &ctx.accounts.bid_escrow.key(),
+                &ctx.accounts.seller.key(),
+                proceeds,
+            ),
+            &[
+                ctx.accounts.bid_escrow.to_account_info(),
+                ctx.accounts.seller.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            bid_signer_seeds,
+        )?;
+    }
+
+    if platform_fee > 0 {
+        invoke_signed(
+            &system_instruction::transfer(
+                &ctx.accounts.bid_escrow.key(),
+                &ctx.accounts.fee_recipient.key(),
+                platform_fee,
+            ),
+            &[
+                ctx.accounts.bid_escrow.to_account_info(),
+                ctx.accounts.fee_recipient.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            bid_signer_seeds,
+        )?;
+    }
+
+    if royalty_fee > 0 {
+        pay_escrowed_royalty(
+            ctx.program_id,
+            listing,
+            royalty_fee,
+            ctx.accounts.winner.key(),
+            &EscrowedRoyaltyAccounts {
+                bid_escrow: ctx.accounts.bid_escrow.as_ref(),
+                royalty_recipient: ctx.accounts.royalty_recipient.as_ref(),
+                fallback_royalty_recipient: ctx.accounts.fallback_royalty_recipient.as_ref(),
+                royalty_receipt_index: ctx.accounts.royalty_receipt_index.as_ref(),
+                royalty_receipt_segment: ctx.accounts.royalty_receipt_segment.as_ref(),
+                rent_payer: ctx.accounts.winner.as_ref(),
+                system_program: ctx.accounts.system_program.as_ref(),
+                token_program: ctx.accounts.token_program.as_ref(),
+                remaining_accounts: ctx.remaining_accounts,
+            },
+            bid_signer_seeds,
+        )?;
+    }
+
+    // The sale only counts once it completes
+    record_auction_sale(
+        &mut ctx.accounts.marketplace_config,
+        &mut ctx.accounts.seller_stats,
+        ctx.accounts.seller.key(),
+        listing.highest_bid,
+        platform_fee,
+    )?;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.claim_deadline = None;
+    listing.locked_proceeds = 0;
+    listing.locked_platform_fee = 0;
+    listing.locked_royalty = 0;
+
+    emit!(AuctionNftClaimed {
+        listing: listing.key(),
+        winner: ctx.accounts.winner.key(),
+        seller: ctx.accounts.seller.key(),
+        proceeds,
+        platform_fee,
+        royalty_fee,
+    });
+
+    Ok(())
+}
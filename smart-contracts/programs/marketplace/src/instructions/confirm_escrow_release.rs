@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::MarketplaceError;
+use crate::EscrowReleaseConfirmed;
+
+#[derive(Accounts)]
+pub struct ConfirmEscrowRelease<'info> {
+    pub confirmer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = escrow.state == EscrowState::Active @ MarketplaceError::EscrowNotActive,
+        constraint = escrow.confirmers.contains(&confirmer.key()) @ MarketplaceError::UnauthorizedConfirmer
+    )]
+    pub escrow: Account<'info, Escrow>,
+}
+
+pub fn handler(ctx: Context<ConfirmEscrowRelease>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    let confirmer = ctx.accounts.confirmer.key();
+
+    // Each confirmer counts once towards the threshold
+    require!(!escrow.has_confirmed(&confirmer), MarketplaceError::EscrowAlreadyConfirmed);
+    escrow.confirmations.push(confirmer);
+
+    emit!(EscrowReleaseConfirmed {
+        escrow: escrow.key(),
+        confirmer,
+        confirmations: escrow.confirmations.len() as u8,
+        required: escrow.required_confirmations(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
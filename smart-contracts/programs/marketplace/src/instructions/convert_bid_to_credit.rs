@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use solana_program::{system_instruction, program::invoke_signed};
+use crate::{state::*, errors::*};
+use crate::BidConvertedToCredit;
+
+#[derive(Accounts)]
+pub struct ConvertBidToCredit<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// The auction the bid was placed on
+    #[account(
+        constraint = listing.listing_type == ListingType::Auction @ MarketplaceError::NotAuctionListing
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// The outbid bid being converted
+    #[account(
+        mut,
+        seeds = [b"bid", listing.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        constraint = bid.is_active @ MarketplaceError::BidNotActive
+    )]
+    pub bid: Account<'info, Bid>,
+
+    /// Bid escrow holding the refundable funds
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", bid.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA holding bid funds
+    pub bid_escrow: UncheckedAccount<'info>,
+
+    /// The bidder's store credit, created on first conversion
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = 8 + StoreCredit::INIT_SPACE,
+        seeds = [b"store_credit", bidder.key().as_ref()],
+        bump
+    )]
+    pub store_credit: Account<'info, StoreCredit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Move a losing bid's escrowed funds into the bidder's store credit instead
+/// of refunding them. Refunds stay the default; this is opt-in per bid.
+pub fn handler(ctx: Context<ConvertBidToCredit>) -> Result<()> {
+    let bidder = ctx.accounts.bidder.key();
+    require!(
+        ctx.accounts.listing.highest_bidder != bidder,
+        MarketplaceError::LeadingBidNotConvertible
+    );
+
+    // Proxy bids escrow their full maximum, all of which is refundable
+    let bid = &ctx.accounts.bid;
+    let credit_amount = bid.amount.max(bid.max_bid);
+
+    let bid_key = bid.key();
+    let bid_escrow_seeds = &[
+        b"bid_escrow",
+        bid_key.as_ref(),
+        &[*ctx.bumps.get("bid_escrow").unwrap()],
+    ];
+    invoke_signed(
+        &system_instruction::transfer(
+            &ctx.accounts.bid_escrow.key(),
+            &ctx.accounts.store_credit.key(),
+            credit_amount,
+        ),
+        &[
+            ctx.accounts.bid_escrow.to_account_info(),
+            ctx.accounts.store_credit.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        &[&bid_escrow_seeds[..]],
+    )?;
+
+    let store_credit = &mut ctx.accounts.store_credit;
+    if store_credit.owner == Pubkey::default() {
+        store_credit.owner = bidder;
+        store_credit.bump = *ctx.bumps.get("store_credit").unwrap();
+    }
+    store_credit.credit(credit_amount)?;
+
+    ctx.accounts.bid.is_active = false;
+
+    emit!(BidConvertedToCredit {
+        listing: ctx.accounts.listing.key(),
+        bidder,
+        amount: credit_amount,
+        balance: store_credit.balance,
+    });
+
+    Ok(())
+}
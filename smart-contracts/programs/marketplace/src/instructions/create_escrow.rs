@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::associated_token::get_associated_token_address;
 use crate::state::*;
 use crate::errors::MarketplaceError;
+use crate::ReleaseCondition;
 
 #[derive(Accounts)]
 pub struct CreateEscrow<'info> {
@@ -31,7 +33,8 @@ pub struct CreateEscrow<'info> {
 
     #[account(
         mut,
-        constraint = escrow_token_account.owner == escrow.key() @ MarketplaceError::InvalidEscrowTokenAccount
+        constraint = escrow_token_account.owner == escrow.key() @ MarketplaceError::InvalidEscrowTokenAccount,
+        constraint = escrow_token_account.key() == get_associated_token_address(&escrow.key(), &escrow_token_account.mint) @ MarketplaceError::InvalidEscrowTokenAccount
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
 
@@ -48,6 +51,12 @@ pub struct CreateEscrowParams {
     pub terms: String,
     pub dispute_period: i64,
     pub platform_fee_rate: u16,
+    pub arbitration_window: Option<i64>,
+    pub inspection_period: i64,
+    pub min_time_before_dispute: i64,
+    pub default_resolution: ResolutionDecision,
+    pub release_condition: Option<ReleaseCondition>,
+    pub confirmers: Vec<Pubkey>,
 }
 
 pub fn create_escrow(ctx: Context<CreateEscrow>, params: CreateEscrowParams) -> Result<()> {
@@ -58,6 +67,16 @@ pub fn create_escrow(ctx: Context<CreateEscrow>, params: CreateEscrowParams) ->
     require!(params.amount > 0, MarketplaceError::InvalidAmount);
     require!(params.expiry_time > clock.unix_timestamp, MarketplaceError::InvalidExpiryTime);
     require!(params.platform_fee_rate <= 1000, MarketplaceError::InvalidFeeRate); // Max 10%
+    let arbitration_window = params.arbitration_window.unwrap_or(Escrow::DEFAULT_ARBITRATION_WINDOW);
+    require!(arbitration_window > 0, MarketplaceError::InvalidExpiryTime);
+    require!(params.inspection_period >= 0, MarketplaceError::InvalidInspectionPeriod);
+    // Disputes must become possible before the escrow expires
+    require!(
+        params.min_time_before_dispute >= 0
+            && clock.unix_timestamp.saturating_add(params.min_time_before_dispute) < params.expiry_time,
+        MarketplaceError::InvalidDisputeDelay
+    );
+    validate_release_condition(&params.release_condition, &params.confirmers)?;
 
     // Initialize escrow
     escrow.buyer = ctx.accounts.creator.key();
@@ -69,6 +88,14 @@ pub fn create_escrow(ctx: Context<CreateEscrow>, params: CreateEscrowParams) ->
     escrow.terms = params.terms;
     escrow.dispute_period = params.dispute_period;
     escrow.platform_fee_rate = params.platform_fee_rate;
+    escrow.arbitration_window = arbitration_window;
+    escrow.inspection_period = params.inspection_period;
+    escrow.delivered_at = None;
+    escrow.min_time_before_dispute = params.min_time_before_dispute;
+    escrow.default_resolution = params.default_resolution;
+    escrow.release_condition = params.release_condition;
+    escrow.confirmers = params.confirmers;
+    escrow.confirmations = Vec::new();
     escrow.state = EscrowState::Active;
     escrow.bump = ctx.bumps.escrow;
 
@@ -95,3 +122,32 @@ pub fn create_escrow(ctx: Context<CreateEscrow>, params: CreateEscrowParams) ->
 
     Ok(())
 }
+
+/// Only multi-confirmation conditions are enforced on token escrows; the
+/// threshold must be reachable by a distinct, bounded set of confirmers
+fn validate_release_condition(
+    release_condition: &Option<ReleaseCondition>,
+    confirmers: &[Pubkey],
+) -> Result<()> {
+    match release_condition {
+        None => require!(confirmers.is_empty(), MarketplaceError::InvalidReleaseCondition),
+        Some(ReleaseCondition::MultiConfirmation { required }) => {
+            require!(
+                confirmers.len() <= Escrow::MAX_CONFIRMERS,
+                MarketplaceError::InvalidReleaseCondition
+            );
+            require!(
+                *required > 0 && *required as usize <= confirmers.len(),
+                MarketplaceError::InvalidReleaseCondition
+            );
+            for (i, confirmer) in confirmers.iter().enumerate() {
+                require!(
+                    !confirmers[..i].contains(confirmer),
+                    MarketplaceError::InvalidReleaseCondition
+                );
+            }
+        }
+        Some(_) => return err!(MarketplaceError::InvalidReleaseCondition),
+    }
+    Ok(())
+}
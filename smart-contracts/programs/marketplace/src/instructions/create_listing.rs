@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
 use anchor_spl::associated_token::AssociatedToken;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
 use crate::{state::*, errors::*};
 
 #[derive(Accounts)]
@@ -44,9 +45,46 @@ pub struct CreateListing<'info> {
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     
+    /// Seller's activity counters, used to cap concurrent auctions
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = 8 + SellerStats::INIT_SPACE,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+    
+    /// The NFT's auction counters, used to cap how often it is auctioned
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = 8 + MintStats::INIT_SPACE,
+        seeds = [b"mint_stats", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+    
+    /// Seller's identity verification, required at or above the verified seller threshold
+    #[account(
+        seeds = [b"seller_verification", seller.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA derived from the seller, may be uninitialized
+    pub seller_verification: UncheckedAccount<'info>,
+    
     /// The NFT mint
     pub mint: Account<'info, Mint>,
     
+    /// Metaplex metadata for the NFT, used to verify any declared royalty creator
+    #[account(
+        seeds = [b"metadata", mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    /// CHECK: Metaplex metadata PDA, deserialized in the handler when needed
+    pub nft_metadata: UncheckedAccount<'info>,
+    
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -61,6 +99,19 @@ pub fn handler(
 ) -> Result<()> {
     require!(price > 0, MarketplaceError::InsufficientFunds);
 
+    // High-value listings are limited to identity-verified sellers. The price
+    // is only where an auction starts, so it is valued at its opening bid or
+    // reserve when either is higher
+    let listing_value = match (&listing_type, &auction_config) {
+        (ListingType::Auction, Some(config)) => config.listing_value(price),
+        _ => price,
+    };
+    SellerVerification::require_for_value(
+        &ctx.accounts.seller_verification,
+        ctx.accounts.marketplace_config.verified_seller_threshold,
+        listing_value,
+    )?;
+
     // Validate auction config if auction listing
     if listing_type == ListingType::Auction {
         require!(auction_config.is_some(), MarketplaceError::InvalidFeePercentage);
@@ -68,6 +119,50 @@ pub fn handler(
         let clock = Clock::get()?;
         require!(config.start_time >= clock.unix_timestamp, MarketplaceError::AuctionNotStarted);
         require!(config.end_time > config.start_time, MarketplaceError::InvalidTimelockDuration);
+        if let Some(opening_bid) = config.opening_bid {
+            require!(opening_bid >= price, MarketplaceError::InvalidPrice);
+        }
+        require!(
+            config.extension_window >= 0 && config.extension_period >= 0,
+            MarketplaceError::InvalidTimelockDuration
+        );
+
+        // Each increment tier must start at a higher price and demand a larger
+        // increment than the one below it, beginning above the flat increment
+        require!(
+            config.increment_tiers.len() <= AuctionConfig::MAX_INCREMENT_TIERS,
+            MarketplaceError::InvalidIncrementTiers
+        );
+        let mut previous_price = 0;
+        let mut previous_increment = config.min_bid_increment;
+        for tier in &config.increment_tiers {
+            require!(
+                tier.min_price > previous_price && tier.min_increment > previous_increment,
+                MarketplaceError::InvalidIncrementTiers
+            );
+            previous_price = tier.min_price;
+            previous_increment = tier.min_increment;
+        }
+    }
+
+    // Count auctions against the seller's concurrent auction cap
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    if seller_stats.seller == Pubkey::default() {
+        seller_stats.seller = ctx.accounts.seller.key();
+        seller_stats.bump = *ctx.bumps.get("seller_stats").unwrap();
+    }
+    if listing_type == ListingType::Auction {
+        seller_stats.open_auction(ctx.accounts.marketplace_config.max_active_auctions_per_seller)?;
+    }
+
+    // Count auctions against the NFT's lifetime auction cap
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    if mint_stats.mint == Pubkey::default() {
+        mint_stats.mint = ctx.accounts.mint.key();
+        mint_stats.bump = *ctx.bumps.get("mint_stats").unwrap();
+    }
+    if listing_type == ListingType::Auction {
+        mint_stats.record_auction(ctx.accounts.marketplace_config.max_auctions_per_mint)?;
     }
 
     // Validate royalty config
@@ -76,6 +171,12 @@ pub fn handler(
             royalty.percentage_bps <= ctx.accounts.marketplace_config.max_royalty_bps,
             MarketplaceError::InvalidRoyaltyPercentage
         );
+        verify_royalty_creator(&ctx.accounts.nft_metadata, &ctx.accounts.mint.key(), &royalty.creator)?;
+        // Token royalty payouts are only converted at auction settlement
+        require!(
+            royalty.royalty_payout_mint.is_none() || listing_type == ListingType::Auction,
+            MarketplaceError::TokenRoyaltyRequiresAuction
+        );
     }
 
     // Transfer NFT to escrow
@@ -99,15 +200,59 @@ pub fn handler(
     listing.auction_config = auction_config;
     listing.royalty_config = royalty_config;
     listing.is_active = true;
+    listing.allow_offers = false;
+    listing.unique_bidders = 0;
+    listing.extensions_applied = 0;
+    listing.highest_bid = 0;
+    listing.highest_bidder = Pubkey::default();
+    listing.highest_max_bid = 0;
+    listing.relist_config = None;
+    listing.relists_applied = 0;
+    listing.claim_deadline = None;
+    listing.locked_proceeds = 0;
+    listing.locked_platform_fee = 0;
+    listing.locked_royalty = 0;
     listing.bump = *ctx.bumps.get("listing").unwrap();
 
+    // A hidden reserve is still stored and enforced, just not announced
+    let public_reserve = listing
+        .auction_config
+        .as_ref()
+        .filter(|config| !config.reserve_hidden)
+        .and_then(|config| config.reserve_price);
+
     emit!(ListingCreated {
         listing: listing.key(),
         seller: ctx.accounts.seller.key(),
         mint: ctx.accounts.mint.key(),
         price,
         listing_type,
+        reserve_price: public_reserve,
     });
 
     Ok(())
 }
+
+/// Rejects a royalty config whose creator is not a verified creator in the
+/// NFT's Metaplex metadata, so sellers cannot route royalties to themselves.
+fn verify_royalty_creator(nft_metadata: &AccountInfo, mint: &Pubkey, creator: &Pubkey) -> Result<()> {
+    require!(
+        nft_metadata.owner == &mpl_token_metadata::ID && !nft_metadata.data_is_empty(),
+        MarketplaceError::InvalidNftMetadata
+    );
+    
+    let metadata = Metadata::from_account_info(nft_metadata)
+        .map_err(|_| error!(MarketplaceError::InvalidNftMetadata))?;
+    require_keys_eq!(metadata.mint, *mint, MarketplaceError::InvalidNftMetadata);
+    
+    let is_verified_creator = metadata
+        .data
+        .creators
+        .as_ref()
+        .is_some_and(|creators| {
+            creators.iter().any(|c| c.verified && c.address == *creator)
+        });
+    require!(is_verified_creator, MarketplaceError::UnverifiedRoyaltyCreator);
+    
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use solana_program::{system_instruction, program::invoke_signed};
+use crate::state::{RoyaltyReceipt, RoyaltyReceiptIndex, RoyaltyReceiptSegment, MAX_RECEIPTS_PER_SEGMENT};
+use crate::{errors::MarketplaceError, RoyaltyReceiptRecorded, RoyaltyReceiptsEnabled};
+
+#[derive(Accounts)]
+#[instruction(segment_capacity: u16)]
+pub struct EnableRoyaltyReceipts<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + RoyaltyReceiptIndex::INIT_SPACE,
+        seeds = [b"royalty_receipt_index", creator.key().as_ref()],
+        bump
+    )]
+    pub royalty_receipt_index: Account<'info, RoyaltyReceiptIndex>,
+
+    /// The first receipt segment
+    #[account(
+        init,
+        payer = creator,
+        space = RoyaltyReceiptSegment::space(segment_capacity),
+        seeds = [RoyaltyReceiptSegment::SEED, creator.key().as_ref(), &0u32.to_le_bytes()],
+        bump
+    )]
+    pub royalty_receipt_segment: Account<'info, RoyaltyReceiptSegment>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<EnableRoyaltyReceipts>, segment_capacity: u16) -> Result<()> {
+    require!(
+        segment_capacity > 0 && segment_capacity <= MAX_RECEIPTS_PER_SEGMENT,
+        MarketplaceError::InvalidReceiptSegmentCapacity
+    );
+
+    let creator = ctx.accounts.creator.key();
+    let royalty_receipt_index = &mut ctx.accounts.royalty_receipt_index;
+    royalty_receipt_index.creator = creator;
+    royalty_receipt_index.segment_capacity = segment_capacity;
+    royalty_receipt_index.current_segment = 0;
+    royalty_receipt_index.total_receipts = 0;
+    royalty_receipt_index.bump = *ctx.bumps.get("royalty_receipt_index").unwrap();
+
+    let royalty_receipt_segment = &mut ctx.accounts.royalty_receipt_segment;
+    royalty_receipt_segment.creator = creator;
+    royalty_receipt_segment.segment = 0;
+    royalty_receipt_segment.receipts = Vec::new();
+    royalty_receipt_segment.bump = *ctx.bumps.get("royalty_receipt_segment").unwrap();
+
+    emit!(RoyaltyReceiptsEnabled {
+        creator,
+        segment_capacity,
+    });
+
+    Ok(())
+}
+
+/// Append a receipt for a royalty paid to `recipient`, if they have enabled
+/// receipts. `receipt_segment` must be the index's current segment; once the
+/// previous segment filled, it is created here at `rent_payer`'s expense.
+pub(crate) fn record_royalty_receipt<'info>(
+    program_id: &Pubkey,
+    receipt_index: &AccountInfo<'info>,
+    receipt_segment: &AccountInfo<'info>,
+    rent_payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    recipient: Pubkey,
+    receipt: RoyaltyReceipt,
+) -> Result<()> {
+    let (expected_index, _) = Pubkey::find_program_address(
+        &[b"royalty_receipt_index", recipient.as_ref()],
+        program_id,
+    );
+    require_keys_eq!(receipt_index.key(), expected_index, MarketplaceError::InvalidRoyaltyReceiptAccounts);
+    if receipt_index.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*receipt_index.owner, *program_id, MarketplaceError::InvalidRoyaltyReceiptAccounts);
+    let mut index = RoyaltyReceiptIndex::try_deserialize(&mut &receipt_index.try_borrow_data()?[..])?;
+    if index.creator != recipient {
+        return Ok(());
+    }
+
+    let segment_number = index.current_segment.to_le_bytes();
+    let (expected_segment, segment_bump) = Pubkey::find_program_address(
+        &[RoyaltyReceiptSegment::SEED, recipient.as_ref(), &segment_number],
+        program_id,
+    );
+    require_keys_eq!(receipt_segment.key(), expected_segment, MarketplaceError::InvalidRoyaltyReceiptAccounts);
+
+    let mut segment = if receipt_segment.data_is_empty() {
+        // The previous segment filled up; open the next one
+        let space = RoyaltyReceiptSegment::space(index.segment_capacity);
+        invoke_signed(
+            &system_instruction::create_account(
+                rent_payer.key,
+                receipt_segment.key,
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                program_id,
+            ),
+            &[rent_payer.clone(), receipt_segment.clone(), system_program.clone()],
+            &[&[RoyaltyReceiptSegment::SEED, recipient.as_ref(), &segment_number, &[segment_bump]]],
+        )?;
+        RoyaltyReceiptSegment {
+            creator: recipient,
+            segment: index.current_segment,
+            receipts: Vec::new(),
+            bump: segment_bump,
+        }
+    } else {
+        RoyaltyReceiptSegment::try_deserialize(&mut &receipt_segment.try_borrow_data()?[..])?
+    };
+
+    let position = segment.receipts.len() as u16;
+    let sale = receipt.sale;
+    let amount = receipt.amount;
+    let payer = receipt.payer;
+    segment.receipts.push(receipt);
+    let receipt_number = index
+        .record(segment.receipts.len())
+        .ok_or(MarketplaceError::MathOverflow)?;
+
+    segment.try_serialize(&mut &mut receipt_segment.try_borrow_mut_data()?[..])?;
+    index.try_serialize(&mut &mut receipt_index.try_borrow_mut_data()?[..])?;
+
+    emit!(RoyaltyReceiptRecorded {
+        creator: recipient,
+        sale,
+        payer,
+        amount,
+        segment: segment.segment,
+        position,
+        receipt_number,
+    });
+
+    Ok(())
+}
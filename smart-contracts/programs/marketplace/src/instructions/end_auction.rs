@@ -1,8 +1,19 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, CloseAccount};
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
 use solana_program::{system_instruction, program::invoke_signed};
 use crate::{state::*, errors::*};
+use crate::{AuctionExpired, AuctionNoSale, RoyaltyPaid, RoyaltyPaidInToken, SellerFeeTierChanged, SettlementRentReimbursed};
+use super::buy_ticket::royalty_payee;
+use super::enable_royalty_receipts::record_royalty_receipt;
+
+/// Accounts supplied in `remaining_accounts` when the royalty is paid in a token:
+/// 0. `[]` the payout mint's `RoyaltyPriceFeed` PDA
+/// 1. `[writable]` the royalty liquidity authority PDA, which takes the SOL royalty
+///    until the admin withdraws it
+/// 2. `[writable]` the liquidity authority's token account for the payout mint
+/// 3. `[writable]` the royalty creator's token account for the payout mint
+const TOKEN_ROYALTY_ACCOUNTS: usize = 4;
 
 #[derive(Accounts)]
 pub struct EndAuction<'info> {
@@ -35,60 +46,116 @@ pub struct EndAuction<'info> {
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     
-    /// Winner's token account
+    /// Winner's token account, created by the caller if the winner has none yet
     #[account(
-        init_if_needed,
-        payer = caller,
-        associated_token::mint = mint,
-        associated_token::authority = winner,
+        mut,
+        address = get_associated_token_address(&winner.key(), &mint.key()) @ MarketplaceError::InvalidWinnerTokenAccount
+    )]
+    /// CHECK: Winner's associated token account, created in the handler if missing
+    pub winner_token_account: UncheckedAccount<'info>,
+    
+    /// Seller's token account, receiving the NFT back if the auction is a no-sale
+    #[account(
+        mut,
+        address = get_associated_token_address(&seller.key(), &mint.key()) @ MarketplaceError::InvalidSellerTokenAccount
     )]
-    pub winner_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Seller's associated token account, created in the handler if missing
+    pub seller_token_account: UncheckedAccount<'info>,
     
-    /// Winning bid account
+    /// Winning bid account (omitted when nobody bid)
     #[account(
         mut,
         seeds = [b"bid", listing.key().as_ref(), winner.key().as_ref()],
-        bump = winning_bid.bump
+        bump = winning_bid.bump,
+        constraint = winning_bid.amount == listing.highest_bid @ MarketplaceError::NotHighestBidder
     )]
-    pub winning_bid: Account<'info, Bid>,
+    pub winning_bid: Option<Account<'info, Bid>>,
     
-    /// Bid escrow holding winning bid funds
+    /// Bid escrow holding winning bid funds (omitted when nobody bid)
     #[account(
         mut,
         seeds = [b"bid_escrow", winning_bid.key().as_ref()],
         bump
     )]
     /// CHECK: PDA holding winning bid funds
-    pub bid_escrow: UncheckedAccount<'info>,
+    pub bid_escrow: Option<UncheckedAccount<'info>>,
     
     /// The NFT mint
     pub mint: Account<'info, Mint>,
     
-    /// Winner of auction
+    /// Winner of auction, refunded any unused proxy headroom
+    #[account(
+        mut,
+        constraint = listing.highest_bid == 0 || winner.key() == listing.highest_bidder @ MarketplaceError::NotHighestBidder
+    )]
     /// CHECK: Winner account
     pub winner: UncheckedAccount<'info>,
     
     /// Seller receiving payment
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = seller.key() == listing.seller @ MarketplaceError::InvalidOwner
+    )]
     /// CHECK: Seller account
     pub seller: UncheckedAccount<'info>,
     
+    /// The seller's fee exemption record; the platform fee is waived if it exists
+    #[account(
+        seeds = [b"fee_exemption", seller.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA derived from the seller, may be uninitialized
+    pub fee_exemption: UncheckedAccount<'info>,
+    
+    /// Seller's activity counters; an auction leaving the book frees a slot
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+    
     /// Platform fee recipient
     #[account(mut)]
     /// CHECK: Fee recipient
     pub fee_recipient: UncheckedAccount<'info>,
     
     /// Royalty recipient
-    #[account(mut)]
-    /// CHECK: Royalty recipient
+    #[account(
+        mut,
+        constraint = listing.royalty_config.as_ref().is_none_or(|royalty_config| royalty_config.creator == royalty_recipient.key()) @ MarketplaceError::InvalidRoyaltyRecipient
+    )]
+    /// CHECK: Validated against the listing's royalty config
     pub royalty_recipient: UncheckedAccount<'info>,
     
+    /// Receives the royalty instead if the royalty recipient account is closed
+    #[account(
+        mut,
+        constraint = fallback_royalty_recipient.key() == marketplace_config.fallback_royalty_recipient @ MarketplaceError::InvalidRoyaltyRecipient
+    )]
+    /// CHECK: Fallback royalty recipient
+    pub fallback_royalty_recipient: UncheckedAccount<'info>,
+    
+    /// The royalty recipient's receipt index; receipts are only kept once it exists
+    #[account(
+        mut,
+        seeds = [b"royalty_receipt_index", royalty_recipient.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA derived from the royalty recipient, may be uninitialized
+    pub royalty_receipt_index: UncheckedAccount<'info>,
+    
+    /// The royalty recipient's current receipt segment
+    #[account(mut)]
+    /// CHECK: Validated against the receipt index in the handler
+    pub royalty_receipt_segment: UncheckedAccount<'info>,
+    
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<EndAuction>) -> Result<()> {
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, EndAuction<'info>>) -> Result<()> {
     let listing = &ctx.accounts.listing;
     let clock = Clock::get()?;
     
@@ -100,33 +167,93 @@ pub fn handler(ctx: Context<EndAuction>) -> Result<()> {
         );
     }
 
-    let winning_bid = &ctx.accounts.winning_bid;
+    // Nobody bid: the NFT simply goes back to the seller
+    if listing.highest_bid == 0 {
+        return settle_no_bids(ctx);
+    }
+
+    let (winning_bid, bid_escrow) = match (&ctx.accounts.winning_bid, &ctx.accounts.bid_escrow) {
+        (Some(winning_bid), Some(bid_escrow)) => (winning_bid, bid_escrow),
+        _ => return err!(MarketplaceError::NoBidsPlaced),
+    };
     require!(winning_bid.is_active, MarketplaceError::NoBidsPlaced);
 
+    // Too few distinct bidders is treated as a no-sale, whatever the price
+    let min_distinct_bidders = listing
+        .auction_config
+        .as_ref()
+        .map_or(0, |config| config.min_distinct_bidders);
+    if listing.unique_bidders < min_distinct_bidders {
+        return settle_no_sale(ctx, min_distinct_bidders);
+    }
+    
+    // A sale below the reserve never settles; the bid can be voided after the grace period
+    if let Some(reserve_price) = listing.auction_config.as_ref().and_then(|config| config.reserve_price) {
+        require!(winning_bid.amount >= reserve_price, MarketplaceError::ReservePriceNotMet);
+    }
+
     let price = winning_bid.amount;
-    let platform_fee_bps = ctx.accounts.marketplace_config.platform_fee_bps;
+    let tier_fee_bps = ctx
+        .accounts
+        .marketplace_config
+        .fee_bps_for_volume(ctx.accounts.seller_stats.total_volume);
+    let platform_fee_bps = if FeeExemption::is_active(&ctx.accounts.fee_exemption) {
+        0
+    } else {
+        tier_fee_bps
+    };
     
     // Calculate fees (same calculation logic as buy_ticket)
-    let platform_fee = (price as u128)
-        .checked_mul(platform_fee_bps as u128)
-        .ok_or(MarketplaceError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(MarketplaceError::MathOverflow)? as u64;
-
-    let mut royalty_fee = 0u64;
-    if let Some(ref royalty_config) = listing.royalty_config {
-        royalty_fee = (price as u128)
-            .checked_mul(royalty_config.percentage_bps as u128)
-            .ok_or(MarketplaceError::MathOverflow)?
-            .checked_div(10000)
-            .ok_or(MarketplaceError::MathOverflow)? as u64;
-    }
-
-    let seller_proceeds = price
-        .checked_sub(platform_fee)
-        .ok_or(MarketplaceError::MathOverflow)?
-        .checked_sub(royalty_fee)
-        .ok_or(MarketplaceError::MathOverflow)?;
+    let (royalty_bps, min_royalty) = listing
+        .royalty_config
+        .as_ref()
+        .map(|royalty_config| (royalty_config.percentage_bps, royalty_config.min_royalty))
+        .unwrap_or((0, 0));
+
+    let (platform_fee, royalty_fee, seller_proceeds) = split_sale_price(
+        price,
+        platform_fee_bps,
+        royalty_bps,
+        min_royalty,
+        ctx.accounts.marketplace_config.rounding_mode,
+    )
+    .ok_or(MarketplaceError::MathOverflow)?;
+
+    // With a claim window the winner collects the NFT themselves, so nothing
+    // is delivered or paid out here and the whole price stays in the bid escrow
+    let claim_window = ctx.accounts.marketplace_config.winner_claim_window;
+    let claim_deadline = if claim_window > 0 {
+        Some(
+            clock
+                .unix_timestamp
+                .checked_add(claim_window)
+                .ok_or(MarketplaceError::MathOverflow)?,
+        )
+    } else {
+        None
+    };
+
+    // Create the winner's token account if needed, reimbursing the caller's
+    // rent out of the seller's proceeds so third-party settlers break even
+    let mut rent_reimbursement = 0;
+    if claim_deadline.is_none() && ctx.accounts.winner_token_account.data_is_empty() {
+        let caller_lamports_before = ctx.accounts.caller.lamports();
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.caller.to_account_info(),
+                associated_token: ctx.accounts.winner_token_account.to_account_info(),
+                authority: ctx.accounts.winner.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+        rent_reimbursement = caller_lamports_before
+            .saturating_sub(ctx.accounts.caller.lamports())
+            .min(seller_proceeds);
+    }
+    let seller_proceeds = seller_proceeds - rent_reimbursement;
 
     // Transfer NFT to winner
     let listing_seeds = &[
@@ -136,16 +263,18 @@ pub fn handler(ctx: Context<EndAuction>) -> Result<()> {
     ];
     let signer_seeds = &[&listing_seeds[..]];
 
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.winner_token_account.to_account_info(),
-            authority: listing.to_account_info(),
-        },
-        signer_seeds,
-    );
-    token::transfer(transfer_ctx, 1)?;
+    if claim_deadline.is_none() {
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.winner_token_account.to_account_info(),
+                authority: listing.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(transfer_ctx, 1)?;
+    }
 
     // Transfer funds from bid escrow
     let bid_escrow_seeds = &[
@@ -155,16 +284,39 @@ pub fn handler(ctx: Context<EndAuction>) -> Result<()> {
     ];
     let bid_signer_seeds = &[&bid_escrow_seeds[..]];
 
-    // Payments to seller, platform, and royalty recipient
-    if seller_proceeds > 0 {
+    if rent_reimbursement > 0 {
         invoke_signed(
             &system_instruction::transfer(
-                &ctx.accounts.bid_escrow.key(),
+                &bid_escrow.key(),
+                &ctx.accounts.caller.key(),
+                rent_reimbursement,
+            ),
+            &[
+                bid_escrow.to_account_info(),
+                ctx.accounts.caller.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            bid_signer_seeds,
+        )?;
+
+        emit!(SettlementRentReimbursed {
+            listing: listing.key(),
+            caller: ctx.accounts.caller.key(),
+            amount: rent_reimbursement,
+        });
+    }
+
+    // Payments to seller, platform, and royalty recipient; a pending claim
+    // holds all of them back until the winner claims
+    if claim_deadline.is_none() && seller_proceeds > 0 {
+        invoke_signed(
+            &system_instruction::transfer(
+                &bid_escrow.key(),
                 &ctx.accounts.seller.key(),
                 seller_proceeds,
             ),
             &[
-                ctx.accounts.bid_escrow.to_account_info(),
+                bid_escrow.to_account_info(),
                 ctx.accounts.seller.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
@@ -172,15 +324,15 @@ pub fn handler(ctx: Context<EndAuction>) -> Result<()> {
         )?;
     }
 
-    if platform_fee > 0 {
+    if claim_deadline.is_none() && platform_fee > 0 {
         invoke_signed(
             &system_instruction::transfer(
-                &ctx.accounts.bid_escrow.key(),
+                &bid_escrow.key(),
                 &ctx.accounts.fee_recipient.key(),
                 platform_fee,
             ),
             &[
-                ctx.accounts.bid_escrow.to_account_info(),
+                bid_escrow.to_account_info(),
                 ctx.accounts.fee_recipient.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
@@ -188,42 +340,79 @@ pub fn handler(ctx: Context<EndAuction>) -> Result<()> {
         )?;
     }
 
-    if royalty_fee > 0 {
+    if claim_deadline.is_none() && royalty_fee > 0 {
+        pay_escrowed_royalty(
+            ctx.program_id,
+            listing,
+            royalty_fee,
+            ctx.accounts.winner.key(),
+            &EscrowedRoyaltyAccounts {
+                bid_escrow: bid_escrow.as_ref(),
+                royalty_recipient: ctx.accounts.royalty_recipient.as_ref(),
+                fallback_royalty_recipient: ctx.accounts.fallback_royalty_recipient.as_ref(),
+                royalty_receipt_index: ctx.accounts.royalty_receipt_index.as_ref(),
+                royalty_receipt_segment: ctx.accounts.royalty_receipt_segment.as_ref(),
+                rent_payer: ctx.accounts.caller.as_ref(),
+                system_program: ctx.accounts.system_program.as_ref(),
+                token_program: ctx.accounts.token_program.as_ref(),
+                remaining_accounts: ctx.remaining_accounts,
+            },
+            bid_signer_seeds,
+        )?;
+    }
+
+    // A proxy winner only pays their effective bid; return the unused headroom
+    let proxy_headroom = winning_bid.max_bid.saturating_sub(price);
+    if proxy_headroom > 0 {
         invoke_signed(
             &system_instruction::transfer(
-                &ctx.accounts.bid_escrow.key(),
-                &ctx.accounts.royalty_recipient.key(),
-                royalty_fee,
+                &bid_escrow.key(),
+                &ctx.accounts.winner.key(),
+                proxy_headroom,
             ),
             &[
-                ctx.accounts.bid_escrow.to_account_info(),
-                ctx.accounts.royalty_recipient.to_account_info(),
+                bid_escrow.to_account_info(),
+                ctx.accounts.winner.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
             bid_signer_seeds,
         )?;
     }
 
-    // Update marketplace stats
-    let marketplace_config = &mut ctx.accounts.marketplace_config;
-    marketplace_config.total_volume = marketplace_config.total_volume
-        .checked_add(price)
-        .ok_or(MarketplaceError::MathOverflow)?;
-    marketplace_config.total_fees_collected = marketplace_config.total_fees_collected
-        .checked_add(platform_fee)
-        .ok_or(MarketplaceError::MathOverflow)?;
+    // A sale awaiting its claim is only counted once the winner claims
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    seller_stats.close_auction();
+    if claim_deadline.is_none() {
+        record_auction_sale(
+            &mut ctx.accounts.marketplace_config,
+            seller_stats,
+            ctx.accounts.seller.key(),
+            price,
+            platform_fee,
+        )?;
+    }
 
     // Mark listing and bid as inactive
     let listing = &mut ctx.accounts.listing;
     listing.is_active = false;
+    if claim_deadline.is_some() {
+        listing.claim_deadline = claim_deadline;
+        listing.locked_proceeds = seller_proceeds;
+        listing.locked_platform_fee = platform_fee;
+        listing.locked_royalty = royalty_fee;
+    }
     
-    let winning_bid = &mut ctx.accounts.winning_bid;
-    winning_bid.is_active = false;
+    if let Some(winning_bid) = ctx.accounts.winning_bid.as_mut() {
+        winning_bid.is_active = false;
+    }
 
     emit!(AuctionEnded {
         listing: listing.key(),
         winner: ctx.accounts.winner.key(),
         winning_bid: price,
+        unique_bidders: listing.unique_bidders,
+        reserve_price: listing.auction_config.as_ref().and_then(|config| config.reserve_price),
+        claim_deadline,
     });
 
     emit!(ItemSold {
@@ -238,3 +427,351 @@ pub fn handler(ctx: Context<EndAuction>) -> Result<()> {
 
     Ok(())
 }
+
+/// Close an auction without a sale: the NFT goes back to the seller and the
+/// winner's full escrow, including any proxy headroom, is refunded
+fn settle_no_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, EndAuction<'info>>,
+    min_distinct_bidders: u32,
+) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let (winning_bid, bid_escrow) = match (&ctx.accounts.winning_bid, &ctx.accounts.bid_escrow) {
+        (Some(winning_bid), Some(bid_escrow)) => (winning_bid, bid_escrow),
+        _ => return err!(MarketplaceError::NoBidsPlaced),
+    };
+    let refund_amount = winning_bid.amount.max(winning_bid.max_bid);
+
+    if ctx.accounts.seller_token_account.data_is_empty() {
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.caller.to_account_info(),
+                associated_token: ctx.accounts.seller_token_account.to_account_info(),
+                authority: ctx.accounts.seller.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+    }
+
+    // Return NFT to seller
+    let listing_seeds = &[
+        b"listing",
+        ctx.accounts.mint.key().as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    // Refund the winning bid from its escrow
+    let bid_escrow_seeds = &[
+        b"bid_escrow",
+        winning_bid.key().as_ref(),
+        &[*ctx.bumps.get("bid_escrow").unwrap()],
+    ];
+    let bid_signer_seeds = &[&bid_escrow_seeds[..]];
+
+    invoke_signed(
+        &system_instruction::transfer(
+            &bid_escrow.key(),
+            &ctx.accounts.winner.key(),
+            refund_amount,
+        ),
+        &[
+            bid_escrow.to_account_info(),
+            ctx.accounts.winner.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        bid_signer_seeds,
+    )?;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.is_active = false;
+    ctx.accounts.seller_stats.close_auction();
+
+    if let Some(winning_bid) = ctx.accounts.winning_bid.as_mut() {
+        winning_bid.is_active = false;
+    }
+
+    emit!(AuctionNoSale {
+        listing: listing.key(),
+        winner: ctx.accounts.winner.key(),
+        seller: ctx.accounts.seller.key(),
+        unique_bidders: listing.unique_bidders,
+        min_distinct_bidders,
+        refunded_amount: refund_amount,
+    });
+
+    Ok(())
+}
+
+/// Close an auction nobody bid on: the NFT goes back to the seller and the
+/// emptied escrow token account is closed, returning its rent to the seller
+fn settle_no_bids<'info>(ctx: Context<'_, '_, '_, 'info, EndAuction<'info>>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+
+    if ctx.accounts.seller_token_account.data_is_empty() {
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.caller.to_account_info(),
+                associated_token: ctx.accounts.seller_token_account.to_account_info(),
+                authority: ctx.accounts.seller.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+    }
+
+    let listing_seeds = &[
+        b"listing",
+        ctx.accounts.mint.key().as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+
+    // Return NFT to seller
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.seller_token_account.to_account_info(),
+                authority: listing.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.escrow_token_account.amount,
+    )?;
+
+    // Close the emptied escrow so its rent goes back to the seller
+    let returned_rent = ctx.accounts.escrow_token_account.to_account_info().lamports();
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.is_active = false;
+    ctx.accounts.seller_stats.close_auction();
+
+    emit!(AuctionExpired {
+        listing: listing.key(),
+        seller: ctx.accounts.seller.key(),
+        mint: ctx.accounts.mint.key(),
+        returned_rent,
+    });
+
+    Ok(())
+}
+
+/// Count a completed auction sale in the marketplace totals and toward the
+/// seller's volume, announcing a move into a new fee tier
+pub(crate) fn record_auction_sale(
+    marketplace_config: &mut MarketplaceConfig,
+    seller_stats: &mut SellerStats,
+    seller: Pubkey,
+    price: u64,
+    platform_fee: u64,
+) -> Result<()> {
+    marketplace_config.total_volume = marketplace_config.total_volume
+        .checked_add(price)
+        .ok_or(MarketplaceError::MathOverflow)?;
+    marketplace_config.total_fees_collected = marketplace_config.total_fees_collected
+        .checked_add(platform_fee)
+        .ok_or(MarketplaceError::MathOverflow)?;
+
+    let old_fee_bps = marketplace_config.fee_bps_for_volume(seller_stats.total_volume);
+    seller_stats.record_sale(price)?;
+    let new_fee_bps = marketplace_config.fee_bps_for_volume(seller_stats.total_volume);
+    if new_fee_bps != old_fee_bps {
+        emit!(SellerFeeTierChanged {
+            seller,
+            old_fee_bps,
+            new_fee_bps,
+            total_volume: seller_stats.total_volume,
+        });
+    }
+    Ok(())
+}
+
+/// Accounts that pay an auction's royalty out of the winning bid's escrow
+pub(crate) struct EscrowedRoyaltyAccounts<'a, 'info> {
+    pub bid_escrow: &'a AccountInfo<'info>,
+    pub royalty_recipient: &'a AccountInfo<'info>,
+    pub fallback_royalty_recipient: &'a AccountInfo<'info>,
+    pub royalty_receipt_index: &'a AccountInfo<'info>,
+    pub royalty_receipt_segment: &'a AccountInfo<'info>,
+    pub rent_payer: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub remaining_accounts: &'a [AccountInfo<'info>],
+}
+
+/// Pay `royalty_fee` out of the bid escrow: in the listing's payout token
+/// through the royalty liquidity pool if it has one, otherwise in SOL to the
+/// royalty recipient (or the fallback recipient if that account is closed)
+pub(crate) fn pay_escrowed_royalty<'info>(
+    program_id: &Pubkey,
+    listing: &Account<'info, Listing>,
+    royalty_fee: u64,
+    winner: Pubkey,
+    accounts: &EscrowedRoyaltyAccounts<'_, 'info>,
+    bid_signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let token_royalty = listing
+        .royalty_config
+        .as_ref()
+        .and_then(|royalty_config| royalty_config.royalty_payout_mint.map(|mint| (royalty_config.creator, mint)));
+
+    if let Some((creator, payout_mint)) = token_royalty {
+        let remaining = accounts.remaining_accounts;
+        require!(
+            remaining.len() >= TOKEN_ROYALTY_ACCOUNTS,
+            MarketplaceError::InvalidRoyaltyPayoutAccounts
+        );
+
+        // Convert the SOL royalty at the admin-posted rate, refusing a stale one
+        let price_feed = Account::<RoyaltyPriceFeed>::try_from(&remaining[0])?;
+        let (expected_price_feed, _) = Pubkey::find_program_address(
+            &[b"royalty_price_feed", payout_mint.as_ref()],
+            program_id,
+        );
+        require_keys_eq!(price_feed.key(), expected_price_feed, MarketplaceError::InvalidRoyaltyPayoutAccounts);
+        require!(price_feed.is_fresh(now), MarketplaceError::StaleRoyaltyPrice);
+        let payout_amount = price_feed
+            .convert_lamports(royalty_fee)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        let (liquidity_authority, liquidity_authority_bump) = Pubkey::find_program_address(
+            &[RoyaltyPriceFeed::LIQUIDITY_AUTHORITY_SEED],
+            program_id,
+        );
+        require_keys_eq!(remaining[1].key(), liquidity_authority, MarketplaceError::InvalidRoyaltyPayoutAccounts);
+        let liquidity = Account::<TokenAccount>::try_from(&remaining[2])?;
+        require!(
+            liquidity.owner == liquidity_authority && liquidity.mint == payout_mint,
+            MarketplaceError::InvalidRoyaltyPayoutAccounts
+        );
+        require!(liquidity.amount >= payout_amount, MarketplaceError::InsufficientRoyaltyLiquidity);
+        let creator_token_account = Account::<TokenAccount>::try_from(&remaining[3])?;
+        require!(
+            creator_token_account.owner == creator && creator_token_account.mint == payout_mint,
+            MarketplaceError::InvalidRoyaltyPayoutAccounts
+        );
+
+        // The SOL royalty goes to the liquidity pool, which pays the creator in the payout token
+        invoke_signed(
+            &system_instruction::transfer(
+                accounts.bid_escrow.key,
+                &liquidity_authority,
+                royalty_fee,
+            ),
+            &[
+                accounts.bid_escrow.clone(),
+                remaining[1].clone(),
+                accounts.system_program.clone(),
+            ],
+            bid_signer_seeds,
+        )?;
+
+        let liquidity_seeds = &[RoyaltyPriceFeed::LIQUIDITY_AUTHORITY_SEED, &[liquidity_authority_bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                accounts.token_program.clone(),
+                Transfer {
+                    from: remaining[2].clone(),
+                    to: remaining[3].clone(),
+                    authority: remaining[1].clone(),
+                },
+                &[&liquidity_seeds[..]],
+            ),
+            payout_amount,
+        )?;
+
+        emit!(RoyaltyPaidInToken {
+            listing: listing.key(),
+            recipient: creator,
+            payout_mint,
+            royalty_lamports: royalty_fee,
+            payout_amount,
+        });
+
+        record_royalty_receipt(
+            program_id,
+            accounts.royalty_receipt_index,
+            accounts.royalty_receipt_segment,
+            accounts.rent_payer,
+            accounts.system_program,
+            creator,
+            RoyaltyReceipt {
+                sale: listing.key(),
+                amount: royalty_fee,
+                timestamp: now,
+                payer: winner,
+            },
+        )?;
+    } else {
+        let (payee, used_fallback) = royalty_payee(
+            accounts.royalty_recipient,
+            accounts.fallback_royalty_recipient,
+        );
+        invoke_signed(
+            &system_instruction::transfer(
+                accounts.bid_escrow.key,
+                payee.key,
+                royalty_fee,
+            ),
+            &[
+                accounts.bid_escrow.clone(),
+                payee.clone(),
+                accounts.system_program.clone(),
+            ],
+            bid_signer_seeds,
+        )?;
+
+        emit!(RoyaltyPaid {
+            listing: listing.key(),
+            recipient: payee.key(),
+            amount: royalty_fee,
+            used_fallback,
+        });
+
+        if !used_fallback {
+            record_royalty_receipt(
+                program_id,
+                accounts.royalty_receipt_index,
+                accounts.royalty_receipt_segment,
+                accounts.rent_payer,
+                accounts.system_program,
+                payee.key(),
+                RoyaltyReceipt {
+                    sale: listing.key(),
+                    amount: royalty_fee,
+                    timestamp: now,
+                    payer: winner,
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use solana_program::{system_instruction, program::invoke_signed};
+use crate::{state::*, errors::*};
+use crate::AuctionForceCancelled;
+
+/// Accounts supplied per bidder in `remaining_accounts`:
+/// bid, bid escrow, bidder wallet
+pub const ACCOUNTS_PER_BIDDER: usize = 3;
+
+/// Longest cancellation reason accepted
+pub const MAX_REASON_LEN: usize = 200;
+
+#[derive(Accounts)]
+pub struct ForceCancelAuction<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// The marketplace configuration
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    /// The auction listing being cancelled
+    #[account(
+        mut,
+        seeds = [b"listing", mint.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.is_active @ MarketplaceError::ListingNotActive,
+        constraint = listing.listing_type == ListingType::Auction @ MarketplaceError::NotAuctionListing
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    /// Escrow token account holding the NFT
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = listing,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// Seller's token account receiving the NFT back
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    /// Seller of the listing
+    /// CHECK: Checked against the listing
+    pub seller: UncheckedAccount<'info>,
+    
+    /// Seller's activity counters; an auction leaving the book frees a slot
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+    
+    /// The NFT mint
+    pub mint: Account<'info, Mint>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel an auction in an emergency regardless of its bids. Every bidder's
+/// accounts must be passed in `remaining_accounts` so all of them are refunded;
+/// bids already converted to store credit are skipped.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, ForceCancelAuction<'info>>,
+    reason: String,
+) -> Result<()> {
+    require!(
+        !reason.trim().is_empty() && reason.len() <= MAX_REASON_LEN,
+        MarketplaceError::InvalidCancellationReason
+    );
+    
+    let remaining = ctx.remaining_accounts;
+    let listing_key = ctx.accounts.listing.key();
+    require!(
+        remaining.len() % ACCOUNTS_PER_BIDDER == 0
+            && remaining.len() / ACCOUNTS_PER_BIDDER == ctx.accounts.listing.unique_bidders as usize,
+        MarketplaceError::InvalidBatchAccounts
+    );
+    
+    let mut seen_bidders: Vec<Pubkey> = Vec::with_capacity(remaining.len() / ACCOUNTS_PER_BIDDER);
+    let mut bidders_refunded: u32 = 0;
+    let mut refunded_amount: u64 = 0;
+    for accounts in remaining.chunks(ACCOUNTS_PER_BIDDER) {
+        let bid_info = &accounts[0];
+        let bid_escrow_info = &accounts[1];
+        let bidder_info = &accounts[2];
+        
+        let mut bid = Account::<Bid>::try_from(bid_info)?;
+        let (expected_bid, _) = Pubkey::find_program_address(
+            &[b"bid", listing_key.as_ref(), bid.bidder.as_ref()],
+            ctx.program_id,
+        );
+        let (expected_escrow, escrow_bump) = Pubkey::find_program_address(
+            &[b"bid_escrow", bid_info.key.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(bid_info.key(), expected_bid, MarketplaceError::InvalidBatchAccounts);
+        require_keys_eq!(bid_escrow_info.key(), expected_escrow, MarketplaceError::InvalidBatchAccounts);
+        require_keys_eq!(bidder_info.key(), bid.bidder, MarketplaceError::InvalidBatchAccounts);
+        require!(
+            !seen_bidders.contains(&bid.bidder),
+            MarketplaceError::InvalidBatchAccounts
+        );
+        seen_bidders.push(bid.bidder);
+        
+        if !bid.is_active {
+            continue;
+        }
+        
+        // Proxy bids escrow their full maximum, all of which goes back
+        let refund = bid.amount.max(bid.max_bid);
+        let bid_key = bid_info.key();
+        let bid_escrow_seeds = &[b"bid_escrow", bid_key.as_ref(), &[escrow_bump]];
+        invoke_signed(
+            &system_instruction::transfer(bid_escrow_info.key, bidder_info.key, refund),
+            &[
+                bid_escrow_info.clone(),
+                bidder_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            &[&bid_escrow_seeds[..]],
+        )?;
+        
+        bid.is_active = false;
+        bid.exit(ctx.program_id)?;
+        bidders_refunded += 1;
+        refunded_amount = refunded_amount
+            .checked_add(refund)
+            .ok_or(MarketplaceError::MathOverflow)?;
+    }
+    
+    // Return NFT to seller
+    let listing = &ctx.accounts.listing;
+    let listing_seeds = &[
+        b"listing",
+        ctx.accounts.mint.key().as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+    
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, 1)?;
+    
+    let listing = &mut ctx.accounts.listing;
+    listing.is_active = false;
+    ctx.accounts.seller_stats.close_auction();
+    
+    msg!("Auction {} force-cancelled: {}", listing_key, reason);
+    
+    emit!(AuctionForceCancelled {
+        listing: listing_key,
+        seller: ctx.accounts.seller.key(),
+        admin: ctx.accounts.admin.key(),
+        bidders_refunded,
+        refunded_amount,
+        reason,
+    });
+    
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use solana_program::{system_instruction, program::invoke_signed};
+use crate::{state::*, errors::*};
+use crate::AuctionVoided;
+
+#[derive(Accounts)]
+pub struct ForceRefundWinner<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    /// The marketplace configuration
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    /// The unsettled auction listing
+    #[account(
+        mut,
+        seeds = [b"listing", mint.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.is_active @ MarketplaceError::ListingNotActive,
+        constraint = listing.listing_type == ListingType::Auction @ MarketplaceError::NotAuctionListing
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    /// Escrow token account holding NFT
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = listing,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// Seller's token account receiving the NFT back
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    /// Winning bid account
+    #[account(
+        mut,
+        seeds = [b"bid", listing.key().as_ref(), winner.key().as_ref()],
+        bump = winning_bid.bump
+    )]
+    pub winning_bid: Account<'info, Bid>,
+    
+    /// Bid escrow holding winning bid funds
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", winning_bid.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA holding winning bid funds
+    pub bid_escrow: UncheckedAccount<'info>,
+    
+    /// The NFT mint
+    pub mint: Account<'info, Mint>,
+    
+    /// Winning bidder receiving the refund
+    #[account(
+        mut,
+        constraint = winner.key() == listing.highest_bidder @ MarketplaceError::NotHighestBidder
+    )]
+    /// CHECK: Checked against the listing's highest bidder
+    pub winner: UncheckedAccount<'info>,
+    
+    /// Seller of the listing
+    /// CHECK: Checked against the listing
+    pub seller: UncheckedAccount<'info>,
+    
+    /// Seller's activity counters; an auction leaving the book frees a slot
+    #[account(
+        mut,
+        seeds = [b"seller_stats", seller.key().as_ref()],
+        bump = seller_stats.bump
+    )]
+    pub seller_stats: Account<'info, SellerStats>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ForceRefundWinner>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let clock = Clock::get()?;
+    
+    // Only void once the settlement grace window after the auction end has passed
+    let auction_config = listing
+        .auction_config
+        .as_ref()
+        .ok_or(MarketplaceError::NotAuctionListing)?;
+    let void_after = auction_config
+        .end_time
+        .checked_add(ctx.accounts.marketplace_config.settlement_grace_period)
+        .ok_or(MarketplaceError::MathOverflow)?;
+    require!(
+        clock.unix_timestamp >= void_after,
+        MarketplaceError::SettlementGracePeriodActive
+    );
+
+    let winning_bid = &ctx.accounts.winning_bid;
+    require!(winning_bid.is_active, MarketplaceError::NoBidsPlaced);
+    // Proxy bids escrow their full maximum, all of which goes back
+    let refund_amount = winning_bid.amount.max(winning_bid.max_bid);
+
+    // Return NFT to seller
+    let listing_seeds = &[
+        b"listing",
+        ctx.accounts.mint.key().as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    // Refund the winning bid from its escrow
+    let bid_escrow_seeds = &[
+        b"bid_escrow",
+        winning_bid.key().as_ref(),
+        &[*ctx.bumps.get("bid_escrow").unwrap()],
+    ];
+    let bid_signer_seeds = &[&bid_escrow_seeds[..]];
+
+    invoke_signed(
+        &system_instruction::transfer(
+            &ctx.accounts.bid_escrow.key(),
+            &ctx.accounts.winner.key(),
+            refund_amount,
+        ),
+        &[
+            ctx.accounts.bid_escrow.to_account_info(),
+            ctx.accounts.winner.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        bid_signer_seeds,
+    )?;
+
+    // Void the sale
+    let listing = &mut ctx.accounts.listing;
+    listing.is_active = false;
+    ctx.accounts.seller_stats.close_auction();
+    
+    let winning_bid = &mut ctx.accounts.winning_bid;
+    winning_bid.is_active = false;
+
+    emit!(AuctionVoided {
+        listing: listing.key(),
+        winner: ctx.accounts.winner.key(),
+        seller: ctx.accounts.seller.key(),
+        refunded_amount: refund_amount,
+        voided_by: ctx.accounts.caller.key(),
+    });
+
+    Ok(())
+}
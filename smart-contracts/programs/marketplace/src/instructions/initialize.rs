@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::{state::*, errors::*, Initialize};
+use crate::{state::*, errors::*, Initialize, MarketplaceConfig};
 
 pub fn handler(
     ctx: Context<Initialize>,
@@ -16,6 +16,18 @@ pub fn handler(
     marketplace_config.total_volume = 0;
     marketplace_config.total_fees_collected = 0;
     marketplace_config.is_paused = false;
+    marketplace_config.rounding_mode = RoundingMode::Floor;
+    marketplace_config.fallback_royalty_recipient = ctx.accounts.admin.key();
+    marketplace_config.settlement_grace_period = MarketplaceConfig::DEFAULT_SETTLEMENT_GRACE_PERIOD;
+    marketplace_config.max_active_auctions_per_seller = 0;
+    marketplace_config.burn_share_bps = 0;
+    marketplace_config.project_token_mint = Pubkey::default();
+    marketplace_config.fee_tiers = Vec::new();
+    marketplace_config.min_listing_duration = 0;
+    marketplace_config.winner_claim_window = 0;
+    marketplace_config.max_auctions_per_mint = 0;
+    marketplace_config.verified_seller_threshold = 0;
+    marketplace_config.arbitrator_fee_bps = 0;
     marketplace_config.bump = *ctx.bumps.get("marketplace_config").unwrap();
 
     Ok(())
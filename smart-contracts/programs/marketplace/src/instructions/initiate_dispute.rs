@@ -23,6 +23,10 @@ pub struct InitiateDispute<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
 
+    /// Counts the dispute if its category is critical
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -44,6 +48,10 @@ pub fn initiate_dispute(ctx: Context<InitiateDispute>, params: DisputeParams) ->
         clock.unix_timestamp < escrow.expiry_time,
         MarketplaceError::EscrowExpired
     );
+    require!(
+        clock.unix_timestamp >= escrow.dispute_eligible_at(),
+        MarketplaceError::DisputeTooEarly
+    );
 
     require!(
         params.description.len() <= 1000,
@@ -52,6 +60,7 @@ pub fn initiate_dispute(ctx: Context<InitiateDispute>, params: DisputeParams) ->
 
     // Initialize dispute
     dispute.escrow = escrow.key();
+    dispute.marketplace = ctx.accounts.marketplace.key();
     dispute.disputer = ctx.accounts.disputer.key();
     dispute.category = params.category;
     dispute.description = params.description;
@@ -59,10 +68,18 @@ pub fn initiate_dispute(ctx: Context<InitiateDispute>, params: DisputeParams) ->
     dispute.requested_resolution = params.requested_resolution;
     dispute.arbitrator = escrow.arbitrator;
     dispute.created_at = clock.unix_timestamp;
-    dispute.deadline = clock.unix_timestamp + 7 * 24 * 60 * 60; // 7 days
+    dispute.deadline = clock.unix_timestamp + escrow.arbitration_window;
     dispute.state = DisputeState::Open;
     dispute.bump = ctx.bumps.dispute;
 
+    if params.category.is_critical() {
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.open_critical_disputes = marketplace
+            .open_critical_disputes
+            .checked_add(1)
+            .ok_or(MarketplaceError::Overflow)?;
+    }
+
     // Freeze escrow
     escrow.state = EscrowState::Disputed;
     escrow.disputed_at = Some(clock.unix_timestamp);
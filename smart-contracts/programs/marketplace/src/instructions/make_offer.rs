@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::{state::*, errors::*, OfferMade};
+
+#[derive(Accounts)]
+pub struct MakeOffer<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// The marketplace configuration
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        constraint = !marketplace_config.is_paused @ MarketplaceError::MarketplacePaused
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    /// The listing the offer is made on
+    #[account(
+        seeds = [b"listing", listing.mint.as_ref()],
+        bump = listing.bump,
+        constraint = listing.is_active @ MarketplaceError::ListingNotActive,
+        constraint = listing.allow_offers @ MarketplaceError::OffersNotAllowed,
+        constraint = listing.seller != buyer.key() @ MarketplaceError::CannotOfferOnOwnListing
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// The offer, which also escrows the offered lamports
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + MarketplaceOffer::INIT_SPACE,
+        seeds = [b"offer", listing.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, MarketplaceOffer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MakeOffer>, amount: u64) -> Result<()> {
+    require!(amount > 0, MarketplaceError::InvalidOfferAmount);
+
+    // Escrow the offered lamports in the offer account
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.offer.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let offer = &mut ctx.accounts.offer;
+    offer.listing = ctx.accounts.listing.key();
+    offer.buyer = ctx.accounts.buyer.key();
+    offer.amount = amount;
+    offer.created_at = Clock::get()?.unix_timestamp;
+    offer.bump = *ctx.bumps.get("offer").unwrap();
+
+    emit!(OfferMade {
+        offer: offer.key(),
+        listing: offer.listing,
+        buyer: offer.buyer,
+        amount,
+    });
+
+    Ok(())
+}
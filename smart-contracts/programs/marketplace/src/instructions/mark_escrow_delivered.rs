@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::MarketplaceError;
+use crate::EscrowDelivered;
+
+#[derive(Accounts)]
+pub struct MarkEscrowDelivered<'info> {
+    pub seller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = escrow.state == EscrowState::Active @ MarketplaceError::EscrowNotActive,
+        constraint = escrow.seller == seller.key() @ MarketplaceError::UnauthorizedRelease
+    )]
+    pub escrow: Account<'info, Escrow>,
+}
+
+pub fn handler(ctx: Context<MarkEscrowDelivered>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    let now = Clock::get()?.unix_timestamp;
+
+    // The inspection window runs from the first delivery; it can't be restarted
+    require!(escrow.delivered_at.is_none(), MarketplaceError::AlreadyDelivered);
+    escrow.delivered_at = Some(now);
+
+    emit!(EscrowDelivered {
+        escrow: escrow.key(),
+        seller: escrow.seller,
+        delivered_at: now,
+        releasable_at: now.saturating_add(escrow.inspection_period),
+    });
+
+    Ok(())
+}
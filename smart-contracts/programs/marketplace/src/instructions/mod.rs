@@ -9,10 +9,49 @@ pub mod create_escrow;
 pub mod release_escrow;
 pub mod initiate_dispute;
 pub mod resolve_dispute;
+pub mod auto_resolve_expired_dispute;
 pub mod update_marketplace_fee;
+pub mod update_rounding_mode;
+pub mod update_fallback_royalty_recipient;
 pub mod pause_marketplace;
 pub mod unpause_marketplace;
+pub mod try_auto_unpause;
+pub mod update_health_thresholds;
 pub mod withdraw_fees;
+pub mod set_allow_offers;
+pub mod make_offer;
+pub mod accept_offer;
+pub mod reject_offer;
+pub mod update_settlement_grace_period;
+pub mod force_refund_winner;
+pub mod add_fee_exemption;
+pub mod remove_fee_exemption;
+pub mod batch_cancel_listings;
+pub mod update_max_active_auctions;
+pub mod set_relist_config;
+pub mod relist_auction;
+pub mod update_fee_burn;
+pub mod update_royalty_price_feed;
+pub mod withdraw_royalty_liquidity;
+pub mod confirm_escrow_release;
+pub mod update_fee_tiers;
+pub mod mark_escrow_delivered;
+pub mod convert_bid_to_credit;
+pub mod update_min_listing_duration;
+pub mod admin_cancel_listing;
+pub mod force_cancel_auction;
+pub mod enable_royalty_receipts;
+pub mod update_winner_claim_window;
+pub mod claim_nft;
+pub mod revert_unclaimed_auction;
+pub mod update_max_auctions_per_mint;
+pub mod reset_mint_auction_count;
+pub mod add_seller_verification;
+pub mod remove_seller_verification;
+pub mod update_verified_seller_threshold;
+pub mod update_arbitrator_fee;
+pub mod add_listing_units;
+pub mod withdraw_listing_units;
 
 // Re-export all handlers
 pub use initialize::*;
@@ -26,7 +65,46 @@ pub use create_escrow::*;
 pub use release_escrow::*;
 pub use initiate_dispute::*;
 pub use resolve_dispute::*;
+pub use auto_resolve_expired_dispute::*;
 pub use update_marketplace_fee::*;
+pub use update_rounding_mode::*;
+pub use update_fallback_royalty_recipient::*;
 pub use pause_marketplace::*;
 pub use unpause_marketplace::*;
+pub use try_auto_unpause::*;
+pub use update_health_thresholds::*;
 pub use withdraw_fees::*;
+pub use set_allow_offers::*;
+pub use make_offer::*;
+pub use accept_offer::*;
+pub use reject_offer::*;
+pub use update_settlement_grace_period::*;
+pub use force_refund_winner::*;
+pub use add_fee_exemption::*;
+pub use remove_fee_exemption::*;
+pub use batch_cancel_listings::*;
+pub use update_max_active_auctions::*;
+pub use set_relist_config::*;
+pub use relist_auction::*;
+pub use update_fee_burn::*;
+pub use update_royalty_price_feed::*;
+pub use withdraw_royalty_liquidity::*;
+pub use confirm_escrow_release::*;
+pub use update_fee_tiers::*;
+pub use mark_escrow_delivered::*;
+pub use convert_bid_to_credit::*;
+pub use update_min_listing_duration::*;
+pub use admin_cancel_listing::*;
+pub use force_cancel_auction::*;
+pub use enable_royalty_receipts::*;
+pub use update_winner_claim_window::*;
+pub use claim_nft::*;
+pub use revert_unclaimed_auction::*;
+pub use update_max_auctions_per_mint::*;
+pub use reset_mint_auction_count::*;
+pub use add_seller_verification::*;
+pub use remove_seller_verification::*;
+pub use update_verified_seller_threshold::*;
+pub use update_arbitrator_fee::*;
+pub use add_listing_units::*;
+pub use withdraw_listing_units::*;
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use solana_program::{system_instruction, program::invoke};
 use crate::{state::*, errors::*};
+use crate::{AuctionExtended, ProxyBidIncreased};
 
 #[derive(Accounts)]
 pub struct PlaceBid<'info> {
@@ -17,6 +18,7 @@ pub struct PlaceBid<'info> {
     
     /// The listing for auction
     #[account(
+        mut,
         seeds = [b"listing", mint.key().as_ref()],
         bump = listing.bump,
         constraint = listing.is_active @ MarketplaceError::ListingNotActive,
@@ -25,9 +27,9 @@ pub struct PlaceBid<'info> {
     )]
     pub listing: Account<'info, Listing>,
     
-    /// The bid account
+    /// The bid account, reused when a bidder raises their bid
     #[account(
-        init,
+        init_if_needed,
         payer = bidder,
         space = 8 + Bid::INIT_SPACE,
         seeds = [b"bid", listing.key().as_ref(), bidder.key().as_ref()],
@@ -37,7 +39,7 @@ pub struct PlaceBid<'info> {
     
     /// Bid escrow PDA to hold funds
     #[account(
-        init,
+        init_if_needed,
         payer = bidder,
         seeds = [b"bid_escrow", bid.key().as_ref()],
         bump,
@@ -52,11 +54,25 @@ pub struct PlaceBid<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
-    let listing = &ctx.accounts.listing;
+/// Place a bid. `max_bid` makes it a proxy bid: the full maximum is escrowed
+/// and the effective bid is raised automatically, only as far as needed, when
+/// a competing bid arrives. If the current leader holds proxy headroom, their
+/// bid account must be passed as the first remaining account. Private
+/// auctions also need a merkle `proof` that the bidder is whitelisted.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
+    amount: u64,
+    max_bid: Option<u64>,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
     let clock = Clock::get()?;
+    let max_bid = max_bid.unwrap_or(amount);
+    require!(max_bid >= amount, MarketplaceError::InvalidPrice);
     
-    // Check auction timing and bid requirements
+    // Check auction timing and bid requirements. Bids under the reserve are
+    // accepted, but the auction can't settle until the reserve is met
+    let mut extended_end_time = None;
     if let Some(ref auction_config) = listing.auction_config {
         require!(
             clock.unix_timestamp >= auction_config.start_time,
@@ -66,42 +82,181 @@ pub fn handler(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
             clock.unix_timestamp < auction_config.end_time,
             MarketplaceError::AuctionEnded
         );
+        require!(
+            auction_config.admits_bidder(&ctx.accounts.bidder.key(), &proof),
+            MarketplaceError::BidderNotWhitelisted
+        );
 
-        // Check minimum bid requirements
-        require!(amount >= listing.price, MarketplaceError::BidTooLow);
+        // The first bid must reach the opening bid (the listing price by default);
+        // later bids must beat the highest bid by the increment for its price band
+        let minimum_bid = if listing.highest_bid == 0 {
+            auction_config.opening_bid.unwrap_or(listing.price)
+        } else {
+            listing.highest_bid
+                .checked_add(auction_config.min_increment_at(listing.highest_bid))
+                .ok_or(MarketplaceError::MathOverflow)?
+        };
+        require!(amount >= minimum_bid, MarketplaceError::BidTooLow);
+        
+        // Anti-sniping: a late bid extends the auction, but only if it meets the
+        // reserve, so sub-reserve bids can't keep an auction alive indefinitely
+        let meets_reserve = auction_config.reserve_price.is_none_or(|reserve| amount >= reserve);
+        let in_extension_window = auction_config.extension_window > 0
+            && clock.unix_timestamp >= auction_config.end_time.saturating_sub(auction_config.extension_window);
+        if meets_reserve && in_extension_window && listing.extensions_applied < auction_config.max_extensions {
+            extended_end_time = Some(
+                auction_config.end_time
+                    .checked_add(auction_config.extension_period)
+                    .ok_or(MarketplaceError::MathOverflow)?,
+            );
+        }
+    }
+    if let Some(new_end_time) = extended_end_time {
+        if let Some(ref mut auction_config) = listing.auction_config {
+            auction_config.end_time = new_end_time;
+        }
+        listing.extensions_applied += 1;
         
-        if let Some(reserve_price) = auction_config.reserve_price {
-            require!(amount >= reserve_price, MarketplaceError::ReservePriceNotMet);
+        emit!(AuctionExtended {
+            listing: listing.key(),
+            bidder: ctx.accounts.bidder.key(),
+            new_end_time,
+            extensions_applied: listing.extensions_applied,
+        });
+    }
+
+    // A fresh bid account means this is the bidder's first bid on the auction;
+    // otherwise they are raising and only the difference over the funds already
+    // escrowed (the previous maximum) is charged
+    let bid = &mut ctx.accounts.bid;
+    let is_first_bid = bid.bidder == Pubkey::default();
+    let escrow_amount = if is_first_bid {
+        max_bid
+    } else {
+        require!(bid.is_active, MarketplaceError::BidNotActive);
+        require!(amount > bid.amount, MarketplaceError::BidTooLow);
+        max_bid.saturating_sub(bid.max_bid.max(bid.amount))
+    };
+
+    // Make sure the bidder can fund the escrow and stay rent-exempt before
+    // moving anything, so a short balance fails with a clear error
+    if escrow_amount > 0 {
+        let rent_reserve = Rent::get()?.minimum_balance(0);
+        let required = escrow_amount
+            .checked_add(rent_reserve)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        let available = ctx.accounts.bidder.lamports();
+        if available < required {
+            msg!(
+                "Bid needs {} lamports ({} escrow + {} rent reserve), bidder has {}",
+                required,
+                escrow_amount,
+                rent_reserve,
+                available
+            );
+            return err!(MarketplaceError::InsufficientFunds);
         }
     }
 
     // Transfer bid amount to escrow
-    invoke(
-        &system_instruction::transfer(
-            &ctx.accounts.bidder.key(),
-            &ctx.accounts.bid_escrow.key(),
-            amount,
-        ),
-        &[
-            ctx.accounts.bidder.to_account_info(),
-            ctx.accounts.bid_escrow.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
-    )?;
+    if escrow_amount > 0 {
+        invoke(
+            &system_instruction::transfer(
+                &ctx.accounts.bidder.key(),
+                &ctx.accounts.bid_escrow.key(),
+                escrow_amount,
+            ),
+            &[
+                ctx.accounts.bidder.to_account_info(),
+                ctx.accounts.bid_escrow.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
 
-    // Initialize bid
-    let bid = &mut ctx.accounts.bid;
-    bid.bidder = ctx.accounts.bidder.key();
-    bid.listing = listing.key();
+    // Initialize or raise the bid
+    if is_first_bid {
+        listing.unique_bidders = listing.unique_bidders
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.listing = listing.key();
+        bid.created_at = clock.unix_timestamp;
+        bid.is_active = true;
+        bid.bump = *ctx.bumps.get("bid").unwrap();
+    }
     bid.amount = amount;
-    bid.created_at = clock.unix_timestamp;
-    bid.is_active = true;
-    bid.bump = *ctx.bumps.get("bid").unwrap();
+    bid.max_bid = bid.max_bid.max(max_bid);
+
+    // Resolve against the current leader's proxy. The leader keeps the lead on
+    // a tie, and each side's effective bid only rises as far as it must
+    let bidder_key = ctx.accounts.bidder.key();
+    let leader_key = listing.highest_bidder;
+    let leader_max = listing.highest_max_bid;
+    let mut new_highest = amount;
+    let mut new_leader = bidder_key;
+    let mut new_leader_max = bid.max_bid;
+    if leader_key != Pubkey::default() && leader_key != bidder_key && leader_max >= amount {
+        let leader_bid_info = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(MarketplaceError::MissingLeadingBid)?;
+        let (expected_leader_bid, _) = Pubkey::find_program_address(
+            &[b"bid", listing.key().as_ref(), leader_key.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            leader_bid_info.key(),
+            expected_leader_bid,
+            MarketplaceError::MissingLeadingBid
+        );
+        let mut leader_bid = Account::<Bid>::try_from(leader_bid_info)?;
+        let min_increment_at = |price: u64| {
+            listing.auction_config
+                .as_ref()
+                .map_or(0, |auction_config| auction_config.min_increment_at(price))
+        };
+
+        if bid.max_bid > leader_max {
+            // The challenger's proxy outlasts the leader's: the leader is pushed
+            // to their maximum and the challenger sits one increment above it
+            leader_bid.amount = leader_max;
+            bid.amount = amount.max(
+                leader_max
+                    .saturating_add(min_increment_at(leader_max))
+                    .min(bid.max_bid),
+            );
+            new_highest = bid.amount;
+        } else {
+            // The leader's proxy covers the challenge: the challenger is pushed
+            // to their maximum and the leader answers one increment above it
+            bid.amount = bid.max_bid;
+            leader_bid.amount = leader_bid.amount.max(
+                bid.max_bid
+                    .saturating_add(min_increment_at(bid.max_bid))
+                    .min(leader_max),
+            );
+            new_highest = leader_bid.amount;
+            new_leader = leader_key;
+            new_leader_max = leader_max;
+        }
+        leader_bid.exit(ctx.program_id)?;
+
+        emit!(ProxyBidIncreased {
+            listing: listing.key(),
+            bidder: new_leader,
+            amount: new_highest,
+            max_bid: new_leader_max,
+        });
+    }
+    listing.highest_bid = new_highest;
+    listing.highest_bidder = new_leader;
+    listing.highest_max_bid = new_leader_max;
 
     emit!(BidPlaced {
         listing: listing.key(),
-        bidder: ctx.accounts.bidder.key(),
-        amount,
+        bidder: bidder_key,
+        amount: bid.amount,
     });
 
     Ok(())
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, OfferRejected};
+
+#[derive(Accounts)]
+pub struct RejectOffer<'info> {
+    /// The listing's seller declining the offer, or the buyer withdrawing it
+    #[account(
+        constraint = authority.key() == listing.seller || authority.key() == offer.buyer @ MarketplaceError::InvalidOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// The listing the offer was made on
+    #[account(
+        seeds = [b"listing", listing.mint.as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// The offer to reject; closing it refunds the escrowed lamports and rent
+    #[account(
+        mut,
+        seeds = [b"offer", listing.key().as_ref(), buyer.key().as_ref()],
+        bump = offer.bump,
+        has_one = listing,
+        has_one = buyer,
+        close = buyer
+    )]
+    pub offer: Account<'info, MarketplaceOffer>,
+
+    /// The buyer who made the offer
+    #[account(mut)]
+    /// CHECK: Refund destination, validated against the offer
+    pub buyer: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<RejectOffer>) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+
+    emit!(OfferRejected {
+        offer: offer.key(),
+        listing: offer.listing,
+        buyer: offer.buyer,
+        amount: offer.amount,
+        rejected_by: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
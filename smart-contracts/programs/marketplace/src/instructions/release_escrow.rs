@@ -1,6 +1,7 @@
 // File: contracts/programs/marketplace/src/instructions/release_escrow.rs
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::associated_token::get_associated_token_address;
 use crate::state::*;
 use crate::errors::MarketplaceError;
 
@@ -18,7 +19,8 @@ pub struct ReleaseEscrow<'info> {
 
     #[account(
         mut,
-        constraint = escrow_token_account.owner == escrow.key() @ MarketplaceError::InvalidEscrowTokenAccount
+        constraint = escrow_token_account.owner == escrow.key() @ MarketplaceError::InvalidEscrowTokenAccount,
+        constraint = escrow_token_account.key() == get_associated_token_address(&escrow.key(), &escrow_token_account.mint) @ MarketplaceError::InvalidEscrowTokenAccount
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
 
@@ -28,14 +30,20 @@ pub struct ReleaseEscrow<'info> {
     )]
     pub seller_token_account: Account<'info, TokenAccount>,
 
+    pub marketplace: Account<'info, Marketplace>,
+
     #[account(
         mut,
-        constraint = platform_token_account.owner == platform_treasury.key() @ MarketplaceError::InvalidPlatformTokenAccount
+        seeds = [b"fee_vault", marketplace.key().as_ref()],
+        bump = fee_vault.bump
     )]
-    pub platform_token_account: Account<'info, TokenAccount>,
+    pub fee_vault: Account<'info, FeeVault>,
 
-    /// CHECK: Platform treasury account
-    pub platform_treasury: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == fee_vault.key() @ MarketplaceError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
 }
@@ -62,6 +70,14 @@ pub fn release_escrow(ctx: Context<ReleaseEscrow>, params: ReleaseParams) -> Res
         clock.unix_timestamp <= escrow.expiry_time || authority.key() == escrow.arbitrator,
         MarketplaceError::EscrowExpired
     );
+    require!(
+        escrow.inspection_complete(clock.unix_timestamp),
+        MarketplaceError::InspectionPeriodActive
+    );
+    require!(
+        escrow.confirmation_threshold_met(),
+        MarketplaceError::InsufficientConfirmations
+    );
 
     // Calculate amounts
     let release_amount = match params.release_type {
@@ -94,17 +110,23 @@ pub fn release_escrow(ctx: Context<ReleaseEscrow>, params: ReleaseParams) -> Res
         token::transfer(cpi_ctx, seller_amount)?;
     }
 
-    // Transfer platform fee
+    // Transfer platform fee into the fee vault so it is withdrawable alongside sale fees
     if platform_fee > 0 {
         let cpi_accounts = Transfer {
             from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.platform_token_account.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
             authority: escrow.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
         
         token::transfer(cpi_ctx, platform_fee)?;
+
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.accumulated_fees = fee_vault
+            .accumulated_fees
+            .checked_add(platform_fee)
+            .ok_or(MarketplaceError::Overflow)?;
     }
 
     // Update escrow state
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use solana_program::{system_instruction, program::invoke_signed};
+use crate::{state::*, errors::*, AuctionRelisted};
+use super::force_cancel_auction::ACCOUNTS_PER_BIDDER;
+
+#[derive(Accounts)]
+pub struct RelistAuction<'info> {
+    /// Anyone may trigger a configured relist
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// The unsold auction listing
+    #[account(
+        mut,
+        seeds = [b"listing", listing.mint.as_ref()],
+        bump = listing.bump,
+        constraint = listing.is_active @ MarketplaceError::ListingNotActive,
+        constraint = listing.listing_type == ListingType::Auction @ MarketplaceError::NotAuctionListing
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// The marketplace configuration
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    /// The NFT's auction counters; each relist counts as another auction
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + MintStats::INIT_SPACE,
+        seeds = [b"mint_stats", listing.mint.as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    /// The seller's identity verification, required if the relisted auction
+    /// is at or above the verified seller threshold
+    #[account(
+        seeds = [b"seller_verification", listing.seller.as_ref()],
+        bump
+    )]
+    /// CHECK: PDA derived from the seller, may be uninitialized
+    pub seller_verification: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Relist an unsold auction. The previous round's bids don't carry over: every
+/// bidder's accounts must be passed in `remaining_accounts` (bid, bid escrow,
+/// bidder wallet) so active bids are refunded and all bid accounts are closed,
+/// leaving bidders free to bid again at the new price.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RelistAuction<'info>>) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+    let clock = Clock::get()?;
+
+    let relist_config = listing.relist_config.ok_or(MarketplaceError::RelistNotConfigured)?;
+    require!(
+        listing.relists_applied < relist_config.max_relists,
+        MarketplaceError::RelistLimitReached
+    );
+
+    let (end_time, reserve_price) = match listing.auction_config {
+        Some(ref auction_config) => (auction_config.end_time, auction_config.reserve_price),
+        None => return err!(MarketplaceError::NotAuctionListing),
+    };
+    require!(clock.unix_timestamp >= end_time, MarketplaceError::AuctionStillActive);
+
+    // Only an auction that ended with no bids, or none meeting the reserve, is unsold
+    let sold = listing.highest_bid > 0
+        && reserve_price.is_none_or(|reserve| listing.highest_bid >= reserve);
+    require!(!sold, MarketplaceError::AuctionAlreadySold);
+
+    // The relisted auction starts at the relist price with any reserve capped
+    // to it, and must clear the verification threshold in force now
+    SellerVerification::require_for_value(
+        &ctx.accounts.seller_verification,
+        ctx.accounts.marketplace_config.verified_seller_threshold,
+        relist_config.price,
+    )?;
+
+    let remaining = ctx.remaining_accounts;
+    let listing_key = listing.key();
+    require!(
+        remaining.len() % ACCOUNTS_PER_BIDDER == 0
+            && remaining.len() / ACCOUNTS_PER_BIDDER == listing.unique_bidders as usize,
+        MarketplaceError::InvalidBatchAccounts
+    );
+
+    let mut seen_bidders: Vec<Pubkey> = Vec::with_capacity(remaining.len() / ACCOUNTS_PER_BIDDER);
+    for accounts in remaining.chunks(ACCOUNTS_PER_BIDDER) {
+        let bid_info = &accounts[0];
+        let bid_escrow_info = &accounts[1];
+        let bidder_info = &accounts[2];
+
+        let bid = Account::<Bid>::try_from(bid_info)?;
+        let (expected_bid, _) = Pubkey::find_program_address(
+            &[b"bid", listing_key.as_ref(), bid.bidder.as_ref()],
+            ctx.program_id,
+        );
+        let (expected_escrow, escrow_bump) = Pubkey::find_program_address(
+            &[b"bid_escrow", bid_info.key.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(bid_info.key(), expected_bid, MarketplaceError::InvalidBatchAccounts);
+        require_keys_eq!(bid_escrow_info.key(), expected_escrow, MarketplaceError::InvalidBatchAccounts);
+        require_keys_eq!(bidder_info.key(), bid.bidder, MarketplaceError::InvalidBatchAccounts);
+        require!(
+            !seen_bidders.contains(&bid.bidder),
+            MarketplaceError::InvalidBatchAccounts
+        );
+        seen_bidders.push(bid.bidder);
+
+        // Bids converted to store credit have nothing left in escrow
+        if bid.is_active {
+            let refund = bid.amount.max(bid.max_bid);
+            let bid_key = bid_info.key();
+            let bid_escrow_seeds = &[b"bid_escrow", bid_key.as_ref(), &[escrow_bump]];
+            invoke_signed(
+                &system_instruction::transfer(bid_escrow_info.key, bidder_info.key, refund),
+                &[
+                    bid_escrow_info.clone(),
+                    bidder_info.clone(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+                &[&bid_escrow_seeds[..]],
+            )?;
+        }
+
+        // Closing the bid lets the bidder start over in the relisted auction
+        bid.close(bidder_info.clone())?;
+    }
+
+    // Listings created before auction counters existed have none yet
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    if mint_stats.mint == Pubkey::default() {
+        mint_stats.mint = listing.mint;
+        mint_stats.bump = *ctx.bumps.get("mint_stats").unwrap();
+    }
+    mint_stats.record_auction(ctx.accounts.marketplace_config.max_auctions_per_mint)?;
+
+    // Restart the auction at the lower price. Any reserve is capped at the new
+    // price so the relisted auction can actually settle
+    let start_time = clock.unix_timestamp;
+    let end_time = start_time
+        .checked_add(relist_config.duration)
+        .ok_or(MarketplaceError::MathOverflow)?;
+    if let Some(ref mut auction_config) = listing.auction_config {
+        auction_config.start_time = start_time;
+        auction_config.end_time = end_time;
+        auction_config.opening_bid = None;
+        auction_config.reserve_price = reserve_price.map(|reserve| reserve.min(relist_config.price));
+    }
+
+    listing.price = relist_config.price;
+    listing.extensions_applied = 0;
+    listing.highest_bid = 0;
+    listing.highest_bidder = Pubkey::default();
+    listing.highest_max_bid = 0;
+    listing.unique_bidders = 0;
+    listing.relists_applied += 1;
+
+    emit!(AuctionRelisted {
+        listing: listing.key(),
+        caller: ctx.accounts.caller.key(),
+        price: relist_config.price,
+        start_time,
+        end_time,
+        relists_applied: listing.relists_applied,
+    });
+
+    Ok(())
+}
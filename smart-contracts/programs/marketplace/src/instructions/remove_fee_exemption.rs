@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::FeeExemption;
+use crate::{MarketplaceConfig, FeeExemptionRemoved};
+
+#[derive(Accounts)]
+pub struct RemoveFeeExemption<'info> {
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    /// Closing the record revokes the exemption
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"fee_exemption", fee_exemption.seller.as_ref()],
+        bump = fee_exemption.bump
+    )]
+    pub fee_exemption: Account<'info, FeeExemption>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<RemoveFeeExemption>) -> Result<()> {
+    emit!(FeeExemptionRemoved {
+        seller: ctx.accounts.fee_exemption.seller,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
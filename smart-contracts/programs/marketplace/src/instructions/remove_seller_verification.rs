@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::SellerVerification;
+use crate::{MarketplaceConfig, SellerVerificationRemoved};
+
+#[derive(Accounts)]
+pub struct RemoveSellerVerification<'info> {
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    /// Closing the record revokes the verification
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"seller_verification", seller_verification.seller.as_ref()],
+        bump = seller_verification.bump
+    )]
+    pub seller_verification: Account<'info, SellerVerification>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<RemoveSellerVerification>) -> Result<()> {
+    emit!(SellerVerificationRemoved {
+        seller: ctx.accounts.seller_verification.seller,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
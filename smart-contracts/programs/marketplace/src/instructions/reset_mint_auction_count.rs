@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, MintAuctionCountReset};
+
+#[derive(Accounts)]
+pub struct ResetMintAuctionCount<'info> {
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    /// The mint's auction counters
+    #[account(
+        mut,
+        seeds = [b"mint_stats", mint_stats.mint.as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ResetMintAuctionCount>) -> Result<()> {
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    let previous_count = mint_stats.auction_count;
+    mint_stats.auction_count = 0;
+
+    emit!(MintAuctionCountReset {
+        admin: ctx.accounts.admin.key(),
+        mint: mint_stats.mint,
+        previous_count,
+    });
+
+    Ok(())
+}
@@ -1,13 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::associated_token::get_associated_token_address;
 use crate::state::*;
 use crate::errors::MarketplaceError;
+use crate::MarketplaceConfig;
 
 #[derive(Accounts)]
 pub struct ResolveDispute<'info> {
     #[account(mut)]
     pub arbitrator: Signer<'info>,
 
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
     #[account(
         mut,
         constraint = dispute.state == DisputeState::Open @ MarketplaceError::DisputeNotOpen,
@@ -23,7 +31,8 @@ pub struct ResolveDispute<'info> {
 
     #[account(
         mut,
-        constraint = escrow_token_account.owner == escrow.key() @ MarketplaceError::InvalidEscrowTokenAccount
+        constraint = escrow_token_account.owner == escrow.key() @ MarketplaceError::InvalidEscrowTokenAccount,
+        constraint = escrow_token_account.key() == get_associated_token_address(&escrow.key(), &escrow_token_account.mint) @ MarketplaceError::InvalidEscrowTokenAccount
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
 
@@ -33,8 +42,31 @@ pub struct ResolveDispute<'info> {
     #[account(mut)]
     pub seller_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut)]
-    pub platform_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = dispute.marketplace @ MarketplaceError::InvalidMarketplace
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", marketplace.key().as_ref()],
+        bump = fee_vault.bump
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == fee_vault.key() @ MarketplaceError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Receives the arbitrator fee
+    #[account(
+        mut,
+        constraint = arbitrator_token_account.owner == arbitrator.key() @ MarketplaceError::InvalidArbitratorTokenAccount
+    )]
+    pub arbitrator_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
 }
@@ -45,6 +77,7 @@ pub struct ResolutionParams {
     pub reasoning: String,
     pub buyer_amount: u64,
     pub seller_amount: u64,
+    pub platform_fee: u64,
 }
 
 pub fn resolve_dispute(ctx: Context<ResolveDispute>, params: ResolutionParams) -> Result<()> {
@@ -54,13 +87,32 @@ pub fn resolve_dispute(ctx: Context<ResolveDispute>, params: ResolutionParams) -
 
     // Validate resolution
     require!(
-        params.buyer_amount + params.seller_amount <= escrow.amount,
-        MarketplaceError::InvalidResolutionAmounts
+        dispute.category.allows(params.decision),
+        MarketplaceError::ResolutionNotAllowedForCategory
+    );
+    require!(
+        params.decision.matches_split(params.buyer_amount, params.seller_amount),
+        MarketplaceError::ResolutionAmountsMismatchDecision
     );
 
-    // Calculate platform fee
-    let total_distributed = params.buyer_amount + params.seller_amount;
-    let platform_fee = escrow.amount - total_distributed;
+    // The arbitrator's cut comes off the top of the escrow
+    let marketplace_config = &ctx.accounts.marketplace_config;
+    let arbitrator_fee = marketplace_config
+        .rounding_mode
+        .apply_bps(escrow.amount, marketplace_config.arbitrator_fee_bps)
+        .ok_or(MarketplaceError::MathOverflow)?;
+
+    // The split must account for the whole escrow, fees included
+    let total_distributed = params.buyer_amount
+        .checked_add(params.seller_amount)
+        .and_then(|sum| sum.checked_add(params.platform_fee))
+        .and_then(|sum| sum.checked_add(arbitrator_fee))
+        .ok_or(MarketplaceError::InvalidResolutionAmounts)?;
+    require!(
+        total_distributed == escrow.amount,
+        MarketplaceError::InvalidResolutionAmounts
+    );
+    let platform_fee = params.platform_fee;
 
     // Create signer seeds
     let escrow_key = escrow.key();
@@ -92,16 +144,35 @@ pub fn resolve_dispute(ctx: Context<ResolveDispute>, params: ResolutionParams) -
         token::transfer(cpi_ctx, params.seller_amount)?;
     }
 
+    // The platform fee goes into the fee vault, where withdraw_fees can reach it
     if platform_fee > 0 {
         let cpi_accounts = Transfer {
             from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.platform_token_account.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
             authority: escrow.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
         
         token::transfer(cpi_ctx, platform_fee)?;
+
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.accumulated_fees = fee_vault
+            .accumulated_fees
+            .checked_add(platform_fee)
+            .ok_or(MarketplaceError::Overflow)?;
+    }
+
+    if arbitrator_fee > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.arbitrator_token_account.to_account_info(),
+            authority: escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        
+        token::transfer(cpi_ctx, arbitrator_fee)?;
     }
 
     // Update dispute state
@@ -110,6 +181,12 @@ pub fn resolve_dispute(ctx: Context<ResolveDispute>, params: ResolutionParams) -
     dispute.reasoning = Some(params.reasoning);
     dispute.resolved_at = Some(clock.unix_timestamp);
 
+    // A resolved critical dispute no longer blocks unpausing
+    if dispute.category.is_critical() {
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.open_critical_disputes = marketplace.open_critical_disputes.saturating_sub(1);
+    }
+
     // Update escrow state
     escrow.state = EscrowState::Completed;
     escrow.completed_at = Some(clock.unix_timestamp);
@@ -121,6 +198,8 @@ pub fn resolve_dispute(ctx: Context<ResolveDispute>, params: ResolutionParams) -
         decision: params.decision,
         buyer_amount: params.buyer_amount,
         seller_amount: params.seller_amount,
+        platform_fee,
+        arbitrator_fee,
         timestamp: clock.unix_timestamp,
     });
 
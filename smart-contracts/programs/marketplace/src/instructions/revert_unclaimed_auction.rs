@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use solana_program::{system_instruction, program::invoke_signed};
+use crate::{state::*, errors::*};
+use crate::UnclaimedAuctionReverted;
+
+#[derive(Accounts)]
+pub struct RevertUnclaimedAuction<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    /// The settled auction listing
+    #[account(
+        mut,
+        seeds = [b"listing", mint.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.claim_deadline.is_some() @ MarketplaceError::NoPendingClaim,
+        constraint = listing.highest_bidder == winner.key() @ MarketplaceError::InvalidOwner
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    /// Escrow token account holding NFT
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = listing,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// Seller's token account receiving the NFT back
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    /// Winning bid account
+    #[account(
+        seeds = [b"bid", listing.key().as_ref(), winner.key().as_ref()],
+        bump = winning_bid.bump
+    )]
+    pub winning_bid: Account<'info, Bid>,
+    
+    /// Bid escrow holding the locked sale price
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", winning_bid.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA holding the locked sale price
+    pub bid_escrow: UncheckedAccount<'info>,
+    
+    /// The NFT mint
+    pub mint: Account<'info, Mint>,
+    
+    /// Winner who never claimed, refunded the locked sale price
+    #[account(mut)]
+    /// CHECK: Checked against the listing
+    pub winner: UncheckedAccount<'info>,
+    
+    /// Seller of the listing
+    /// CHECK: Checked against the listing
+    pub seller: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RevertUnclaimedAuction>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let clock = Clock::get()?;
+    
+    let claim_deadline = listing.claim_deadline.ok_or(MarketplaceError::NoPendingClaim)?;
+    require!(
+        clock.unix_timestamp > claim_deadline,
+        MarketplaceError::ClaimWindowActive
+    );
+
+    // Return the NFT to the seller
+    let listing_seeds = &[
+        b"listing",
+        ctx.accounts.mint.key().as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    // Refund the whole locked price; nothing was paid out at settlement
+    let refunded_amount = listing
+        .locked_proceeds
+        .checked_add(listing.locked_platform_fee)
+        .and_then(|amount| amount.checked_add(listing.locked_royalty))
+        .ok_or(MarketplaceError::MathOverflow)?;
+    if refunded_amount > 0 {
+        let winning_bid_key = ctx.accounts.winning_bid.key();
+        let bid_escrow_seeds = &[
+            b"bid_escrow",
+            winning_bid_key.as_ref(),
+            &[*ctx.bumps.get("bid_escrow").unwrap()],
+        ];
+        invoke_signed(
+            &system_instruction::transfer(
+                &ctx.accounts.bid_escrow.key(),
+                &ctx.accounts.winner.key(),
+                refunded_amount,
+            ),
+            &[
+                ctx.accounts.bid_escrow.to_account_info(),
+                ctx.accounts.winner.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            &[&bid_escrow_seeds[..]],
+        )?;
+    }
+
+    let listing = &mut ctx.accounts.listing;
+    listing.claim_deadline = None;
+    listing.locked_proceeds = 0;
+    listing.locked_platform_fee = 0;
+    listing.locked_royalty = 0;
+
+    emit!(UnclaimedAuctionReverted {
+        listing: listing.key(),
+        winner: ctx.accounts.winner.key(),
+        seller: ctx.accounts.seller.key(),
+        refunded_amount,
+        reverted_by: ctx.accounts.caller.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, ListingOffersToggled};
+
+#[derive(Accounts)]
+pub struct SetAllowOffers<'info> {
+    pub seller: Signer<'info>,
+
+    /// The listing to update
+    #[account(
+        mut,
+        seeds = [b"listing", listing.mint.as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.is_active @ MarketplaceError::ListingNotActive,
+        constraint = listing.listing_type == ListingType::FixedPrice @ MarketplaceError::OffersRequireFixedPrice
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+pub fn handler(ctx: Context<SetAllowOffers>, allow_offers: bool) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+    listing.allow_offers = allow_offers;
+
+    emit!(ListingOffersToggled {
+        listing: listing.key(),
+        allow_offers,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, RelistConfigUpdated};
+
+#[derive(Accounts)]
+pub struct SetRelistConfig<'info> {
+    pub seller: Signer<'info>,
+
+    /// The auction listing to update
+    #[account(
+        mut,
+        seeds = [b"listing", listing.mint.as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.is_active @ MarketplaceError::ListingNotActive,
+        constraint = listing.listing_type == ListingType::Auction @ MarketplaceError::NotAuctionListing
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+pub fn handler(ctx: Context<SetRelistConfig>, relist_config: Option<RelistConfig>) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+
+    // A relist must drop the price, and the number of relists is bounded
+    if let Some(ref config) = relist_config {
+        require!(
+            config.price > 0 && config.price < listing.price,
+            MarketplaceError::InvalidPrice
+        );
+        require!(config.duration > 0, MarketplaceError::InvalidTimelockDuration);
+        require!(
+            config.max_relists > 0 && config.max_relists <= RelistConfig::MAX_RELISTS,
+            MarketplaceError::RelistLimitReached
+        );
+    }
+    listing.relist_config = relist_config;
+
+    emit!(RelistConfigUpdated {
+        listing: listing.key(),
+        relist_config,
+    });
+
+    Ok(())
+}
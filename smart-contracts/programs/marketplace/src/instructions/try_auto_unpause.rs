@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use anchor_spl::associated_token::get_associated_token_address;
+use crate::state::*;
+use crate::errors::MarketplaceError;
+use super::unpause_marketplace::{clear_pause_state, enforce_health_check};
+
+#[derive(Accounts)]
+pub struct TryAutoUnpause<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = marketplace.is_paused @ MarketplaceError::NotPaused
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [b"fee_vault", marketplace.key().as_ref()],
+        bump = fee_vault.bump
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// The fee vault's account for the configured fee mint
+    #[account(
+        constraint = vault_token_account.mint == marketplace.health_thresholds.fee_mint @ MarketplaceError::InvalidVaultTokenAccount,
+        constraint = vault_token_account.key() == get_associated_token_address(&fee_vault.key(), &vault_token_account.mint) @ MarketplaceError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+}
+
+pub fn handler(ctx: Context<TryAutoUnpause>) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+    let clock = Clock::get()?;
+
+    // Only pauses scheduled with a duration can be lifted by anyone
+    let auto_unpause_at = marketplace
+        .auto_unpause_at
+        .ok_or(MarketplaceError::AutoUnpauseNotScheduled)?;
+    require!(
+        clock.unix_timestamp >= auto_unpause_at,
+        MarketplaceError::AutoUnpauseNotReady
+    );
+
+    // The same health checks as a manual unpause still apply
+    enforce_health_check(
+        marketplace,
+        &ctx.accounts.fee_vault,
+        ctx.accounts.vault_token_account.amount,
+        clock.unix_timestamp,
+    )?;
+
+    marketplace.is_paused = false;
+    marketplace.pause_level = PauseLevel::None;
+    marketplace.affected_features.clear();
+    clear_pause_state(marketplace, clock.unix_timestamp);
+
+    emit!(MarketplaceAutoUnpaused {
+        marketplace: marketplace.key(),
+        triggered_by: ctx.accounts.caller.key(),
+        scheduled_at: auto_unpause_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
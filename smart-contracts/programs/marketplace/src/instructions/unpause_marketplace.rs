@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use anchor_spl::associated_token::get_associated_token_address;
 use crate::state::*;
 use crate::errors::MarketplaceError;
 
@@ -13,6 +15,19 @@ pub struct UnpauseMarketplace<'info> {
         constraint = marketplace.is_paused @ MarketplaceError::NotPaused
     )]
     pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [b"fee_vault", marketplace.key().as_ref()],
+        bump = fee_vault.bump
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// The fee vault's account for the configured fee mint
+    #[account(
+        constraint = vault_token_account.mint == marketplace.health_thresholds.fee_mint @ MarketplaceError::InvalidVaultTokenAccount,
+        constraint = vault_token_account.key() == get_associated_token_address(&fee_vault.key(), &vault_token_account.mint) @ MarketplaceError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -26,10 +41,12 @@ pub fn unpause_marketplace(ctx: Context<UnpauseMarketplace>, params: UnpausePara
     let clock = Clock::get()?;
 
     // Perform safety checks before unpause
-    require!(
-        marketplace.system_health_check(),
-        MarketplaceError::SystemHealthCheckFailed
-    );
+    enforce_health_check(
+        marketplace,
+        &ctx.accounts.fee_vault,
+        ctx.accounts.vault_token_account.amount,
+        clock.unix_timestamp,
+    )?;
 
     if params.gradual_rollout {
         // Gradual unpause - enable specific features
@@ -53,11 +70,7 @@ pub fn unpause_marketplace(ctx: Context<UnpauseMarketplace>, params: UnpausePara
 
     // Clear pause-related fields if fully unpaused
     if !marketplace.is_paused {
-        marketplace.pause_reason = None;
-        marketplace.paused_at = None;
-        marketplace.pause_duration = None;
-        marketplace.auto_unpause_at = None;
-        marketplace.unpaused_at = Some(clock.unix_timestamp);
+        clear_pause_state(marketplace, clock.unix_timestamp);
     }
 
     // Emit unpause event
@@ -71,3 +84,26 @@ pub fn unpause_marketplace(ctx: Context<UnpauseMarketplace>, params: UnpausePara
 
     Ok(())
 }
+
+/// Runs the configured health checks, logging the reason code of the first failure
+pub(crate) fn enforce_health_check(
+    marketplace: &Marketplace,
+    fee_vault: &FeeVault,
+    vault_balance: u64,
+    now: i64,
+) -> Result<()> {
+    if let Err(failure) = marketplace.system_health_check(fee_vault.accumulated_fees, vault_balance, now) {
+        msg!("Health check failed: reason code {} ({:?})", failure as u8, failure);
+        return err!(MarketplaceError::SystemHealthCheckFailed);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn clear_pause_state(marketplace: &mut Marketplace, now: i64) {
+    marketplace.pause_reason = None;
+    marketplace.paused_at = None;
+    marketplace.pause_duration = None;
+    marketplace.auto_unpause_at = None;
+    marketplace.unpaused_at = Some(now);
+}
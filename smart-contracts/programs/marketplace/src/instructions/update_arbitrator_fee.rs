@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, ArbitratorFeeUpdated};
+use crate::errors::MarketplaceError;
+
+#[derive(Accounts)]
+pub struct UpdateArbitratorFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateArbitratorFee>, arbitrator_fee_bps: u16) -> Result<()> {
+    require!(arbitrator_fee_bps <= 1000, MarketplaceError::InvalidFeePercentage); // Max 10%
+
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.arbitrator_fee_bps = arbitrator_fee_bps;
+
+    emit!(ArbitratorFeeUpdated {
+        admin: ctx.accounts.admin.key(),
+        arbitrator_fee_bps,
+    });
+
+    Ok(())
+}
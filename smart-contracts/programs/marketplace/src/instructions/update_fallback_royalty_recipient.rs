@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, FallbackRoyaltyRecipientUpdated};
+
+#[derive(Accounts)]
+pub struct UpdateFallbackRoyaltyRecipient<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateFallbackRoyaltyRecipient>,
+    fallback_royalty_recipient: Pubkey,
+) -> Result<()> {
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.fallback_royalty_recipient = fallback_royalty_recipient;
+
+    emit!(FallbackRoyaltyRecipientUpdated {
+        admin: ctx.accounts.admin.key(),
+        fallback_royalty_recipient,
+    });
+
+    Ok(())
+}
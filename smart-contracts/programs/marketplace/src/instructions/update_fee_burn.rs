@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, FeeBurnUpdated};
+use crate::errors::MarketplaceError;
+
+#[derive(Accounts)]
+pub struct UpdateFeeBurn<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateFeeBurn>,
+    burn_share_bps: u16,
+    project_token_mint: Pubkey,
+) -> Result<()> {
+    require!(burn_share_bps <= 10000, MarketplaceError::InvalidFeePercentage);
+
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.burn_share_bps = burn_share_bps;
+    marketplace_config.project_token_mint = project_token_mint;
+
+    emit!(FeeBurnUpdated {
+        admin: ctx.accounts.admin.key(),
+        burn_share_bps,
+        project_token_mint,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::{FeeTier, FeeTiersUpdated, MarketplaceConfig};
+use crate::errors::MarketplaceError;
+
+#[derive(Accounts)]
+pub struct UpdateFeeTiers<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Each tier must start at a higher volume than the last and charge less,
+/// starting below the base platform fee
+pub fn handler(ctx: Context<UpdateFeeTiers>, fee_tiers: Vec<FeeTier>) -> Result<()> {
+    require!(
+        fee_tiers.len() <= MarketplaceConfig::MAX_FEE_TIERS,
+        MarketplaceError::InvalidFeeTiers
+    );
+
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    let mut previous_volume = 0;
+    let mut previous_fee_bps = marketplace_config.platform_fee_bps;
+    for tier in &fee_tiers {
+        require!(
+            tier.min_volume > previous_volume && tier.fee_bps < previous_fee_bps,
+            MarketplaceError::InvalidFeeTiers
+        );
+        previous_volume = tier.min_volume;
+        previous_fee_bps = tier.fee_bps;
+    }
+
+    marketplace_config.fee_tiers = fee_tiers.clone();
+
+    emit!(FeeTiersUpdated {
+        admin: ctx.accounts.admin.key(),
+        fee_tiers,
+    });
+
+    Ok(())
+}
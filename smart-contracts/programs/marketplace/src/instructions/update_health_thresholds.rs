@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::MarketplaceError;
+
+#[derive(Accounts)]
+pub struct UpdateHealthThresholds<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = marketplace.admin == admin.key() @ MarketplaceError::UnauthorizedAdmin
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+pub fn handler(ctx: Context<UpdateHealthThresholds>, thresholds: HealthThresholds) -> Result<()> {
+    require!(thresholds.min_pause_duration >= 0, MarketplaceError::InvalidHealthThresholds);
+
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.health_thresholds = thresholds;
+
+    emit!(HealthThresholdsUpdated {
+        marketplace: marketplace.key(),
+        admin: ctx.accounts.admin.key(),
+        require_fee_vault_solvency: thresholds.require_fee_vault_solvency,
+        fee_mint: thresholds.fee_mint,
+        min_pause_duration: thresholds.min_pause_duration,
+        block_on_critical_disputes: thresholds.block_on_critical_disputes,
+    });
+
+    Ok(())
+}
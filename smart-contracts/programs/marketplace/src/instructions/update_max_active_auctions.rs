@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, MaxActiveAuctionsUpdated};
+
+#[derive(Accounts)]
+pub struct UpdateMaxActiveAuctions<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateMaxActiveAuctions>,
+    max_active_auctions_per_seller: u16,
+) -> Result<()> {
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.max_active_auctions_per_seller = max_active_auctions_per_seller;
+
+    emit!(MaxActiveAuctionsUpdated {
+        admin: ctx.accounts.admin.key(),
+        max_active_auctions_per_seller,
+    });
+
+    Ok(())
+}
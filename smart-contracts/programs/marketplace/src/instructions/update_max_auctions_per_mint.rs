@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, MaxAuctionsPerMintUpdated};
+
+#[derive(Accounts)]
+pub struct UpdateMaxAuctionsPerMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateMaxAuctionsPerMint>,
+    max_auctions_per_mint: u16,
+) -> Result<()> {
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.max_auctions_per_mint = max_auctions_per_mint;
+
+    emit!(MaxAuctionsPerMintUpdated {
+        admin: ctx.accounts.admin.key(),
+        max_auctions_per_mint,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, MinListingDurationUpdated};
+use crate::errors::MarketplaceError;
+
+#[derive(Accounts)]
+pub struct UpdateMinListingDuration<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateMinListingDuration>,
+    min_listing_duration: i64,
+) -> Result<()> {
+    require!(min_listing_duration >= 0, MarketplaceError::InvalidMinListingDuration);
+
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.min_listing_duration = min_listing_duration;
+
+    emit!(MinListingDurationUpdated {
+        admin: ctx.accounts.admin.key(),
+        min_listing_duration,
+    });
+
+    Ok(())
+}
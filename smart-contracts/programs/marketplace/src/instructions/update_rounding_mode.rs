@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, MarketplaceConfig};
+
+#[derive(Accounts)]
+pub struct UpdateRoundingMode<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateRoundingMode>, rounding_mode: RoundingMode) -> Result<()> {
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.rounding_mode = rounding_mode;
+
+    emit!(RoundingModeUpdated {
+        admin: ctx.accounts.admin.key(),
+        rounding_mode,
+    });
+
+    Ok(())
+}
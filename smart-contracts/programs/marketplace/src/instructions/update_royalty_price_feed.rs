@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::RoyaltyPriceFeed;
+use crate::errors::MarketplaceError;
+use crate::{MarketplaceConfig, RoyaltyPriceFeedUpdated};
+
+#[derive(Accounts)]
+pub struct UpdateRoyaltyPriceFeed<'info> {
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + RoyaltyPriceFeed::INIT_SPACE,
+        seeds = [b"royalty_price_feed", payout_mint.key().as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, RoyaltyPriceFeed>,
+
+    /// Token royalties are converted into
+    pub payout_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UpdateRoyaltyPriceFeed>, price_per_sol: u64) -> Result<()> {
+    require!(price_per_sol > 0, MarketplaceError::InvalidPrice);
+
+    let price_feed = &mut ctx.accounts.price_feed;
+    price_feed.payout_mint = ctx.accounts.payout_mint.key();
+    price_feed.price_per_sol = price_per_sol;
+    price_feed.updated_at = Clock::get()?.unix_timestamp;
+    price_feed.bump = *ctx.bumps.get("price_feed").unwrap();
+
+    emit!(RoyaltyPriceFeedUpdated {
+        payout_mint: price_feed.payout_mint,
+        price_per_sol,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
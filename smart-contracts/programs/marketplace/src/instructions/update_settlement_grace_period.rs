@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, SettlementGracePeriodUpdated};
+use crate::errors::MarketplaceError;
+
+#[derive(Accounts)]
+pub struct UpdateSettlementGracePeriod<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateSettlementGracePeriod>,
+    settlement_grace_period: i64,
+) -> Result<()> {
+    require!(settlement_grace_period >= 0, MarketplaceError::InvalidGracePeriod);
+
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.settlement_grace_period = settlement_grace_period;
+
+    emit!(SettlementGracePeriodUpdated {
+        admin: ctx.accounts.admin.key(),
+        settlement_grace_period,
+    });
+
+    Ok(())
+}
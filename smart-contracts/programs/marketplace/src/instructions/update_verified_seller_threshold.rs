@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, VerifiedSellerThresholdUpdated};
+
+#[derive(Accounts)]
+pub struct UpdateVerifiedSellerThreshold<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateVerifiedSellerThreshold>,
+    verified_seller_threshold: u64,
+) -> Result<()> {
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.verified_seller_threshold = verified_seller_threshold;
+
+    emit!(VerifiedSellerThresholdUpdated {
+        admin: ctx.accounts.admin.key(),
+        verified_seller_threshold,
+    });
+
+    Ok(())
+}
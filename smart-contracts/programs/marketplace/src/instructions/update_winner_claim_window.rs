@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::{MarketplaceConfig, WinnerClaimWindowUpdated};
+use crate::errors::MarketplaceError;
+
+#[derive(Accounts)]
+pub struct UpdateWinnerClaimWindow<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateWinnerClaimWindow>,
+    winner_claim_window: i64,
+) -> Result<()> {
+    require!(winner_claim_window >= 0, MarketplaceError::InvalidClaimWindow);
+
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    marketplace_config.winner_claim_window = winner_claim_window;
+
+    emit!(WinnerClaimWindowUpdated {
+        admin: ctx.accounts.admin.key(),
+        winner_claim_window,
+    });
+
+    Ok(())
+}
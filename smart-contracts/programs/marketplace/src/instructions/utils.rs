@@ -232,14 +232,20 @@ pub struct ReleaseEscrow<'info> {
     )]
     pub seller_token_account: Account<'info, TokenAccount>,
 
+    pub marketplace: Account<'info, Marketplace>,
+
     #[account(
         mut,
-        constraint = platform_token_account.owner == platform_treasury.key() @ MarketplaceError::InvalidPlatformTokenAccount
+        seeds = [b"fee_vault", marketplace.key().as_ref()],
+        bump = fee_vault.bump
     )]
-    pub platform_token_account: Account<'info, TokenAccount>,
+    pub fee_vault: Account<'info, FeeVault>,
 
-    /// CHECK: Platform treasury account
-    pub platform_treasury: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == fee_vault.key() @ MarketplaceError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
 }
@@ -298,17 +304,23 @@ pub fn release_escrow(ctx: Context<ReleaseEscrow>, params: ReleaseParams) -> Res
         token::transfer(cpi_ctx, seller_amount)?;
     }
 
-    // Transfer platform fee
+    // Transfer platform fee into the fee vault so it is withdrawable alongside sale fees
     if platform_fee > 0 {
         let cpi_accounts = Transfer {
             from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.platform_token_account.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
             authority: escrow.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
         
         token::transfer(cpi_ctx, platform_fee)?;
+
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.accumulated_fees = fee_vault
+            .accumulated_fees
+            .checked_add(platform_fee)
+            .ok_or(MarketplaceError::Overflow)?;
     }
 
     // Update escrow state
@@ -461,6 +473,7 @@ pub struct ResolutionParams {
     pub reasoning: String,
     pub buyer_amount: u64,
     pub seller_amount: u64,
+    pub platform_fee: u64,
 }
 
 pub fn resolve_dispute(ctx: Context<ResolveDispute>, params: ResolutionParams) -> Result<()> {
@@ -468,15 +481,16 @@ pub fn resolve_dispute(ctx: Context<ResolveDispute>, params: ResolutionParams) -
     let escrow = &mut ctx.accounts.escrow;
     let clock = Clock::get()?;
 
-    // Validate resolution
+    // Validate resolution: the split must account for the whole escrow, fee included
+    let total_distributed = params.buyer_amount
+        .checked_add(params.seller_amount)
+        .and_then(|sum| sum.checked_add(params.platform_fee))
+        .ok_or(MarketplaceError::InvalidResolutionAmounts)?;
     require!(
-        params.buyer_amount + params.seller_amount <= escrow.amount,
+        total_distributed == escrow.amount,
         MarketplaceError::InvalidResolutionAmounts
     );
-
-    // Calculate platform fee
-    let total_distributed = params.buyer_amount + params.seller_amount;
-    let platform_fee = escrow.amount - total_distributed;
+    let platform_fee = params.platform_fee;
 
     // Create signer seeds
     let escrow_key = escrow.key();
@@ -537,6 +551,7 @@ pub fn resolve_dispute(ctx: Context<ResolveDispute>, params: ResolutionParams) -
         decision: params.decision,
         buyer_amount: params.buyer_amount,
         seller_amount: params.seller_amount,
+        platform_fee,
         timestamp: clock.unix_timestamp,
     });
 
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
 use crate::state::*;
 use crate::errors::MarketplaceError;
+use crate::{FeesBurned, MarketplaceConfig};
 
 #[derive(Accounts)]
 pub struct WithdrawFees<'info> {
@@ -13,6 +14,13 @@ pub struct WithdrawFees<'info> {
     )]
     pub marketplace: Account<'info, Marketplace>,
 
+    /// Holds the share of withdrawn fees to burn
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
     #[account(
         mut,
         seeds = [b"fee_vault", marketplace.key().as_ref()],
@@ -27,6 +35,13 @@ pub struct WithdrawFees<'info> {
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
+    /// Mint of the fee token, whose supply shrinks when fees are burned
+    #[account(
+        mut,
+        address = vault_token_account.mint @ MarketplaceError::InvalidVaultTokenAccount
+    )]
+    pub fee_mint: Account<'info, Mint>,
+
     #[account(
         mut,
         constraint = treasury_token_account.owner == marketplace.treasury @ MarketplaceError::InvalidTreasuryTokenAccount
@@ -60,21 +75,55 @@ pub fn withdraw_fees(ctx: Context<WithdrawFees>, params: WithdrawParams) -> Resu
         MarketplaceError::InsufficientVaultBalance
     );
 
+    // Split off the configured share to burn; only the project token can be burned
+    let burn_share_bps = ctx.accounts.marketplace_config.burn_share_bps;
+    let burn_amount = ((withdrawal_amount as u128 * burn_share_bps as u128) / 10000) as u64;
+    let treasury_amount = withdrawal_amount - burn_amount;
+    if burn_share_bps > 0 {
+        require_keys_eq!(
+            ctx.accounts.fee_mint.key(),
+            ctx.accounts.marketplace_config.project_token_mint,
+            MarketplaceError::FeeTokenNotBurnable
+        );
+    }
+
     // Create signer seeds
     let marketplace_key = marketplace.key();
     let seeds = &[b"fee_vault", marketplace_key.as_ref(), &[fee_vault.bump]];
     let signer = &[&seeds[..]];
 
-    // Transfer fees to treasury
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.vault_token_account.to_account_info(),
-        to: ctx.accounts.treasury_token_account.to_account_info(),
-        authority: fee_vault.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-    
-    token::transfer(cpi_ctx, withdrawal_amount)?;
+    if burn_amount > 0 {
+        let burn_accounts = Burn {
+            mint: ctx.accounts.fee_mint.to_account_info(),
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            authority: fee_vault.to_account_info(),
+        };
+        let burn_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            burn_accounts,
+            signer,
+        );
+        token::burn(burn_ctx, burn_amount)?;
+
+        emit!(FeesBurned {
+            mint: ctx.accounts.fee_mint.key(),
+            burned_amount: burn_amount,
+            treasury_amount,
+        });
+    }
+
+    // Transfer the remaining fees to treasury
+    if treasury_amount > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.treasury_token_account.to_account_info(),
+            authority: fee_vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        
+        token::transfer(cpi_ctx, treasury_amount)?;
+    }
 
     // Update fee vault state
     fee_vault.accumulated_fees -= withdrawal_amount;
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
+use crate::state::{Listing, ListingState, ListingType};
+use crate::errors::MarketplaceError;
+use crate::ListingUnitsWithdrawn;
+
+#[derive(Accounts)]
+pub struct WithdrawListingUnits<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// The fixed-price listing giving units back
+    #[account(
+        mut,
+        seeds = [b"listing", listing.ticket_mint.as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        constraint = listing.state == ListingState::Active @ MarketplaceError::ListingNoLongerActive,
+        constraint = listing.listing_type == ListingType::FixedPrice @ MarketplaceError::MultiUnitRequiresFixedPrice
+    )]
+    pub listing: Account<'info, Listing>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Returns unsold units from the listing's escrow to the seller, each passed
+/// in the remaining accounts as a `(mint, escrow token account, seller token
+/// account)` triple, where the seller token account is their associated token
+/// account. Withdrawing every unit left cancels the listing.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawListingUnits<'info>>) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    require!(
+        !remaining.is_empty() && remaining.len() % 3 == 0,
+        MarketplaceError::InvalidListingUnitAccounts
+    );
+
+    let units_withdrawn = (remaining.len() / 3) as u32;
+    let units_available = ctx.accounts.listing.units_available();
+    require!(
+        units_withdrawn <= units_available,
+        MarketplaceError::InsufficientListingQuantity
+    );
+
+    let listing_key = ctx.accounts.listing.key();
+    let pda_seeds = &[
+        b"listing",
+        ctx.accounts.listing.ticket_mint.as_ref(),
+        &[ctx.accounts.listing.bump],
+    ];
+    let signer = &[&pda_seeds[..]];
+
+    for unit in remaining.chunks(3) {
+        let (mint, escrow, seller_token_account) = (&unit[0], &unit[1], &unit[2]);
+
+        // Re-read on every unit so a repeated escrow is caught once emptied
+        let escrow_account = Account::<TokenAccount>::try_from(escrow)?;
+        require!(
+            escrow_account.owner == listing_key
+                && escrow_account.mint == mint.key()
+                && escrow_account.amount == 1,
+            MarketplaceError::InvalidListingUnitAccounts
+        );
+        require_keys_eq!(
+            seller_token_account.key(),
+            get_associated_token_address(&ctx.accounts.seller.key(), &mint.key()),
+            MarketplaceError::InvalidListingUnitAccounts
+        );
+
+        if seller_token_account.data_is_empty() {
+            associated_token::create(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: ctx.accounts.seller.to_account_info(),
+                    associated_token: seller_token_account.clone(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                    mint: mint.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            ))?;
+        }
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: escrow.clone(),
+                    to: seller_token_account.clone(),
+                    authority: ctx.accounts.listing.to_account_info(),
+                },
+                signer,
+            ),
+            1,
+        )?;
+    }
+
+    let listing = &mut ctx.accounts.listing;
+    listing.quantity = units_available - units_withdrawn;
+    if listing.quantity == 0 {
+        listing.state = ListingState::Canceled;
+    }
+
+    emit!(ListingUnitsWithdrawn {
+        listing: listing_key,
+        seller: ctx.accounts.seller.key(),
+        units_withdrawn,
+        quantity: listing.quantity,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use crate::state::RoyaltyPriceFeed;
+use crate::errors::MarketplaceError;
+use crate::{MarketplaceConfig, RoyaltyLiquidityWithdrawn};
+
+#[derive(Accounts)]
+pub struct WithdrawRoyaltyLiquidity<'info> {
+    #[account(
+        seeds = [b"marketplace_config"],
+        bump = marketplace_config.bump,
+        has_one = admin
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+
+    /// Holds the SOL royalties taken in exchange for token payouts
+    #[account(
+        mut,
+        seeds = [RoyaltyPriceFeed::LIQUIDITY_AUTHORITY_SEED],
+        bump
+    )]
+    pub liquidity_authority: SystemAccount<'info>,
+
+    /// Receives the withdrawn SOL
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Move SOL the royalty liquidity pool collected from token-paid royalties
+/// out to `recipient`, so it can fund more payout tokens
+pub fn handler(ctx: Context<WithdrawRoyaltyLiquidity>, amount: u64) -> Result<()> {
+    require!(amount > 0, MarketplaceError::InvalidWithdrawalAmount);
+    require!(
+        amount <= ctx.accounts.liquidity_authority.lamports(),
+        MarketplaceError::InsufficientRoyaltyLiquidity
+    );
+
+    let bump = *ctx.bumps.get("liquidity_authority").unwrap();
+    let liquidity_seeds = &[RoyaltyPriceFeed::LIQUIDITY_AUTHORITY_SEED, &[bump]];
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.liquidity_authority.to_account_info(),
+                to: ctx.accounts.recipient.to_account_info(),
+            },
+            &[&liquidity_seeds[..]],
+        ),
+        amount,
+    )?;
+
+    emit!(RoyaltyLiquidityWithdrawn {
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 // lib.rs
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, CloseAccount};
 use anchor_spl::associated_token::AssociatedToken;
 
@@ -37,26 +38,95 @@ pub mod ticket_marketplace {
         instructions::create_listing::handler(ctx, price, listing_type, auction_config, royalty_config)
     }
 
-    /// Buy a ticket that has been listed on the marketplace at fixed price
-    pub fn buy_ticket(ctx: Context<BuyTicket>) -> Result<()> {
-        instructions::buy_ticket::handler(ctx)
+    /// Buy one or more units of a fixed-price listing
+    pub fn buy_ticket<'info>(ctx: Context<'_, '_, '_, 'info, BuyTicket<'info>>, buy_quantity: u32) -> Result<()> {
+        instructions::buy_ticket::handler(ctx, buy_quantity)
+    }
+
+    /// Add identical tickets to a fixed-price listing, making it multi-unit (seller only)
+    pub fn add_listing_units<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddListingUnits<'info>>,
+        allow_partial_fill: bool,
+    ) -> Result<()> {
+        instructions::add_listing_units::handler(ctx, allow_partial_fill)
+    }
+
+    /// Take unsold units back out of a fixed-price listing (seller only)
+    pub fn withdraw_listing_units<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawListingUnits<'info>>,
+    ) -> Result<()> {
+        instructions::withdraw_listing_units::handler(ctx)
     }
 
     /// Cancel an existing listing
     pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
         instructions::cancel_listing::handler(ctx)
     }
+
+    /// Cancel a listing on the seller's behalf, ignoring the minimum listing duration (admin only)
+    pub fn admin_cancel_listing(ctx: Context<AdminCancelListing>) -> Result<()> {
+        instructions::admin_cancel_listing::handler(ctx)
+    }
+
+    /// Cancel several of the seller's listings at once, skipping any they don't own
+    pub fn batch_cancel_listings<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchCancelListings<'info>>,
+    ) -> Result<()> {
+        instructions::batch_cancel_listings::handler(ctx)
+    }
     
     /// Create a bid on an auction listing
-    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
-        instructions::place_bid::handler(ctx, amount)
+    pub fn place_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::place_bid::handler(ctx, amount, None, Vec::new())
     }
     
-    /// End an auction and distribute proceeds
-    pub fn end_auction(ctx: Context<EndAuction>) -> Result<()> {
+    /// Create a proxy bid that is raised automatically up to `max_bid` when outbid
+    pub fn place_proxy_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
+        amount: u64,
+        max_bid: u64,
+    ) -> Result<()> {
+        instructions::place_bid::handler(ctx, amount, Some(max_bid), Vec::new())
+    }
+    
+    /// Bid on a private auction, proving the bidder is on its whitelist
+    pub fn place_whitelisted_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
+        amount: u64,
+        max_bid: Option<u64>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::place_bid::handler(ctx, amount, max_bid, proof)
+    }
+    
+    /// End an auction and distribute proceeds, or refund the winner if too
+    /// few distinct wallets bid. An auction with no bids returns the NFT
+    /// and the escrow's rent to the seller
+    pub fn end_auction<'info>(ctx: Context<'_, '_, '_, 'info, EndAuction<'info>>) -> Result<()> {
         instructions::end_auction::handler(ctx)
     }
 
+    /// Void an auction left unsettled past the grace window, refunding the winner
+    pub fn force_refund_winner(ctx: Context<ForceRefundWinner>) -> Result<()> {
+        instructions::force_refund_winner::handler(ctx)
+    }
+
+    /// Cancel an auction in an emergency, refunding every bidder (admin only)
+    pub fn force_cancel_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, ForceCancelAuction<'info>>,
+        reason: String,
+    ) -> Result<()> {
+        instructions::force_cancel_auction::handler(ctx, reason)
+    }
+
+    /// Convert an outbid bid into store credit instead of taking a refund
+    pub fn convert_bid_to_credit(ctx: Context<ConvertBidToCredit>) -> Result<()> {
+        instructions::convert_bid_to_credit::handler(ctx)
+    }
+
     /// Claim refund for outbid auction participants
     pub fn claim_bid_refund(ctx: Context<ClaimBidRefund>) -> Result<()> {
         instructions::claim_bid_refund::handler(ctx)
@@ -72,6 +142,16 @@ pub mod ticket_marketplace {
         instructions::release_escrow::handler(ctx)
     }
 
+    /// Record a confirmer's sign-off on a multi-confirmation escrow
+    pub fn confirm_escrow_release(ctx: Context<ConfirmEscrowRelease>) -> Result<()> {
+        instructions::confirm_escrow_release::handler(ctx)
+    }
+
+    /// Seller records delivery, starting the buyer's inspection period
+    pub fn mark_escrow_delivered(ctx: Context<MarkEscrowDelivered>) -> Result<()> {
+        instructions::mark_escrow_delivered::handler(ctx)
+    }
+
     /// Initiate dispute for problematic transactions
     pub fn initiate_dispute(ctx: Context<InitiateDispute>, reason: String) -> Result<()> {
         instructions::initiate_dispute::handler(ctx, reason)
@@ -84,12 +164,94 @@ pub mod ticket_marketplace {
     ) -> Result<()> {
         instructions::resolve_dispute::handler(ctx, resolution)
     }
+
+    /// Apply the escrow's default resolution once the arbitrator deadline has passed
+    pub fn auto_resolve_expired_dispute(ctx: Context<AutoResolveExpiredDispute>) -> Result<()> {
+        instructions::auto_resolve_expired_dispute::handler(ctx)
+    }
     
     /// Update marketplace fee rate (admin only)
     pub fn update_marketplace_fee(ctx: Context<UpdateMarketplaceFee>, new_fee_bps: u16) -> Result<()> {
         instructions::update_marketplace_fee::handler(ctx, new_fee_bps)
     }
 
+    /// Update fee and royalty rounding mode (admin only)
+    pub fn update_rounding_mode(ctx: Context<UpdateRoundingMode>, rounding_mode: RoundingMode) -> Result<()> {
+        instructions::update_rounding_mode::handler(ctx, rounding_mode)
+    }
+
+    /// Update the recipient paid when a listing's royalty recipient is closed (admin only)
+    pub fn update_fallback_royalty_recipient(
+        ctx: Context<UpdateFallbackRoyaltyRecipient>,
+        fallback_royalty_recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::update_fallback_royalty_recipient::handler(ctx, fallback_royalty_recipient)
+    }
+
+    /// Update how long an auction may stay unsettled before it can be voided (admin only)
+    pub fn update_settlement_grace_period(
+        ctx: Context<UpdateSettlementGracePeriod>,
+        settlement_grace_period: i64,
+    ) -> Result<()> {
+        instructions::update_settlement_grace_period::handler(ctx, settlement_grace_period)
+    }
+
+    /// Cap how many auctions a seller may run at once, 0 for no cap (admin only)
+    pub fn update_max_active_auctions(
+        ctx: Context<UpdateMaxActiveAuctions>,
+        max_active_auctions_per_seller: u16,
+    ) -> Result<()> {
+        instructions::update_max_active_auctions::handler(ctx, max_active_auctions_per_seller)
+    }
+
+    /// Set the share of withdrawn fees burned and the project token it applies to (admin only)
+    pub fn update_fee_burn(
+        ctx: Context<UpdateFeeBurn>,
+        burn_share_bps: u16,
+        project_token_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::update_fee_burn::handler(ctx, burn_share_bps, project_token_mint)
+    }
+    
+    /// Replace the graduated platform fee tiers (admin only)
+    pub fn update_fee_tiers(ctx: Context<UpdateFeeTiers>, fee_tiers: Vec<FeeTier>) -> Result<()> {
+        instructions::update_fee_tiers::handler(ctx, fee_tiers)
+    }
+
+    /// Set how long a fixed-price listing must stay live before it can be cancelled (admin only)
+    pub fn update_min_listing_duration(
+        ctx: Context<UpdateMinListingDuration>,
+        min_listing_duration: i64,
+    ) -> Result<()> {
+        instructions::update_min_listing_duration::handler(ctx, min_listing_duration)
+    }
+
+    /// Post the rate used to pay royalties in a token instead of SOL (admin only)
+    pub fn update_royalty_price_feed(
+        ctx: Context<UpdateRoyaltyPriceFeed>,
+        price_per_sol: u64,
+    ) -> Result<()> {
+        instructions::update_royalty_price_feed::handler(ctx, price_per_sol)
+    }
+
+    /// Withdraw SOL collected by the royalty liquidity pool (admin only)
+    pub fn withdraw_royalty_liquidity(
+        ctx: Context<WithdrawRoyaltyLiquidity>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_royalty_liquidity::handler(ctx, amount)
+    }
+
+    /// Exempt a seller from the platform fee on their sales (admin only)
+    pub fn add_fee_exemption(ctx: Context<AddFeeExemption>, seller: Pubkey) -> Result<()> {
+        instructions::add_fee_exemption::handler(ctx, seller)
+    }
+
+    /// Revoke a seller's platform fee exemption (admin only)
+    pub fn remove_fee_exemption(ctx: Context<RemoveFeeExemption>) -> Result<()> {
+        instructions::remove_fee_exemption::handler(ctx)
+    }
+
     /// Emergency pause marketplace (admin only)
     pub fn pause_marketplace(ctx: Context<PauseMarketplace>) -> Result<()> {
         instructions::pause_marketplace::handler(ctx)
@@ -100,10 +262,117 @@ pub mod ticket_marketplace {
         instructions::unpause_marketplace::handler(ctx)
     }
 
+    /// Unpause once the scheduled auto-unpause time has passed (permissionless)
+    pub fn try_auto_unpause(ctx: Context<TryAutoUnpause>) -> Result<()> {
+        instructions::try_auto_unpause::handler(ctx)
+    }
+
+    /// Update the health thresholds checked before unpausing (admin only)
+    pub fn update_health_thresholds(
+        ctx: Context<UpdateHealthThresholds>,
+        thresholds: HealthThresholds,
+    ) -> Result<()> {
+        instructions::update_health_thresholds::handler(ctx, thresholds)
+    }
+
     /// Withdraw platform fees (admin only)
     pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
         instructions::withdraw_fees::handler(ctx, amount)
     }
+
+    /// Enable or disable offers on a fixed-price listing (seller only)
+    pub fn set_allow_offers(ctx: Context<SetAllowOffers>, allow_offers: bool) -> Result<()> {
+        instructions::set_allow_offers::handler(ctx, allow_offers)
+    }
+
+    /// Set or clear the terms for relisting an auction that ends unsold (seller only)
+    pub fn set_relist_config(
+        ctx: Context<SetRelistConfig>,
+        relist_config: Option<RelistConfig>,
+    ) -> Result<()> {
+        instructions::set_relist_config::handler(ctx, relist_config)
+    }
+
+    /// Relist an unsold auction at its configured lower price (permissionless)
+    pub fn relist_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, RelistAuction<'info>>,
+    ) -> Result<()> {
+        instructions::relist_auction::handler(ctx)
+    }
+
+    /// Make an offer on a listing, escrowing the offered lamports
+    pub fn make_offer(ctx: Context<MakeOffer>, amount: u64) -> Result<()> {
+        instructions::make_offer::handler(ctx, amount)
+    }
+
+    /// Accept an offer, selling the ticket to the offering buyer (seller only)
+    pub fn accept_offer(ctx: Context<AcceptOffer>) -> Result<()> {
+        instructions::accept_offer::handler(ctx)
+    }
+
+    /// Reject an offer and refund the buyer (seller, or the buyer to withdraw)
+    pub fn reject_offer(ctx: Context<RejectOffer>) -> Result<()> {
+        instructions::reject_offer::handler(ctx)
+    }
+
+    /// Start keeping on-chain receipts of royalties paid to the signing creator
+    pub fn enable_royalty_receipts(ctx: Context<EnableRoyaltyReceipts>, segment_capacity: u16) -> Result<()> {
+        instructions::enable_royalty_receipts::handler(ctx, segment_capacity)
+    }
+
+    /// Set how long auction winners have to claim their NFT before the sale reverts (admin only)
+    pub fn update_winner_claim_window(
+        ctx: Context<UpdateWinnerClaimWindow>,
+        winner_claim_window: i64,
+    ) -> Result<()> {
+        instructions::update_winner_claim_window::handler(ctx, winner_claim_window)
+    }
+
+    /// Collect a settled auction's NFT as the winner, releasing the held sale payments
+    pub fn claim_nft<'info>(ctx: Context<'_, '_, '_, 'info, ClaimNft<'info>>) -> Result<()> {
+        instructions::claim_nft::handler(ctx)
+    }
+
+    /// Return an unclaimed NFT to the seller and the held price to the winner (anyone)
+    pub fn revert_unclaimed_auction(ctx: Context<RevertUnclaimedAuction>) -> Result<()> {
+        instructions::revert_unclaimed_auction::handler(ctx)
+    }
+
+    /// Cap how many auctions a single NFT may go through, 0 for no cap (admin only)
+    pub fn update_max_auctions_per_mint(
+        ctx: Context<UpdateMaxAuctionsPerMint>,
+        max_auctions_per_mint: u16,
+    ) -> Result<()> {
+        instructions::update_max_auctions_per_mint::handler(ctx, max_auctions_per_mint)
+    }
+
+    /// Clear an NFT's auction count so it can be auctioned again (admin only)
+    pub fn reset_mint_auction_count(ctx: Context<ResetMintAuctionCount>) -> Result<()> {
+        instructions::reset_mint_auction_count::handler(ctx)
+    }
+
+    /// Set the listing price from which sellers must be verified, 0 to disable (admin only)
+    pub fn update_verified_seller_threshold(
+        ctx: Context<UpdateVerifiedSellerThreshold>,
+        verified_seller_threshold: u64,
+    ) -> Result<()> {
+        instructions::update_verified_seller_threshold::handler(ctx, verified_seller_threshold)
+    }
+
+    /// Record a seller as identity-verified for high-value listings (admin only)
+    pub fn add_seller_verification(ctx: Context<AddSellerVerification>, seller: Pubkey) -> Result<()> {
+        instructions::add_seller_verification::handler(ctx, seller)
+    }
+
+    /// Revoke a seller's identity verification (admin only)
+    pub fn remove_seller_verification(ctx: Context<RemoveSellerVerification>) -> Result<()> {
+        instructions::remove_seller_verification::handler(ctx)
+    }
+
+    /// Set the share of a disputed escrow paid to the resolving arbitrator (admin only)
+    pub fn update_arbitrator_fee(ctx: Context<UpdateArbitratorFee>, arbitrator_fee_bps: u16) -> Result<()> {
+        instructions::update_arbitrator_fee::handler(ctx, arbitrator_fee_bps)
+    }
 }
 
 // ============================================================================
@@ -121,9 +390,49 @@ pub struct MarketplaceConfig {
     pub total_volume: u64,              // Total trading volume
     pub total_fees_collected: u64,      // Total platform fees collected
     pub is_paused: bool,                // Emergency pause state
+    pub rounding_mode: RoundingMode,    // Rounding applied to fee and royalty math
+    pub fallback_royalty_recipient: Pubkey, // Paid instead of a closed royalty recipient
+    pub settlement_grace_period: i64,   // Seconds after auction end before an unsettled sale can be voided
+    pub max_active_auctions_per_seller: u16, // Concurrent active auctions allowed per seller (0 = unlimited)
+    pub burn_share_bps: u16,            // Share of withdrawn fees burned instead of sent to treasury
+    pub project_token_mint: Pubkey,     // Native project token; the only fee token that can be burned
+    #[max_len(5)]
+    pub fee_tiers: Vec<FeeTier>,        // Graduated fees by seller volume, ascending (empty = flat fee)
+    pub min_listing_duration: i64,      // Seconds a fixed-price listing must stay live before the seller can cancel
+    pub winner_claim_window: i64,       // Seconds an auction winner has to claim the NFT (0 = delivered at settlement)
+    pub max_auctions_per_mint: u16,     // Auctions a single NFT may go through, relists included (0 = unlimited)
+    pub verified_seller_threshold: u64, // Listing price from which the seller must be verified (0 = never required)
+    pub arbitrator_fee_bps: u16,        // Share of a disputed escrow paid to the arbitrator who resolves it
     pub bump: u8,
 }
 
+impl MarketplaceConfig {
+    pub const DEFAULT_SETTLEMENT_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60; // 7 days
+    pub const MAX_FEE_TIERS: usize = 5;
+
+    /// Platform fee for a seller with `volume` in settled sales: the highest
+    /// tier reached, or the base platform fee below the first tier
+    pub fn fee_bps_for_volume(&self, volume: u64) -> u16 {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.min_volume)
+            .map_or(self.platform_fee_bps, |tier| tier.fee_bps)
+    }
+
+    /// Whether a fixed-price listing created at `created_at` has been live
+    /// long enough for its seller to cancel it
+    pub fn min_listing_duration_elapsed(&self, created_at: i64, now: i64) -> bool {
+        now >= created_at.saturating_add(self.min_listing_duration)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct FeeTier {
+    pub min_volume: u64,                // Seller volume (lamports) from which the tier applies
+    pub fee_bps: u16,                   // Platform fee charged within the tier
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Listing {
@@ -135,6 +444,18 @@ pub struct Listing {
     pub auction_config: Option<AuctionConfig>,
     pub royalty_config: Option<RoyaltyConfig>,
     pub is_active: bool,
+    pub allow_offers: bool,
+    pub unique_bidders: u32,            // Distinct wallets that have bid on this auction
+    pub extensions_applied: u8,         // Anti-sniping extensions granted so far
+    pub highest_bid: u64,               // Largest bid placed so far (0 until the first bid)
+    pub highest_bidder: Pubkey,         // Current leader (default until the first bid)
+    pub highest_max_bid: u64,           // Current leader's proxy maximum
+    pub relist_config: Option<RelistConfig>, // Automatic relist terms if the auction ends unsold
+    pub relists_applied: u8,            // Automatic relists performed so far
+    pub claim_deadline: Option<i64>,    // Set while a settled auction awaits the winner's claim
+    pub locked_proceeds: u64,           // Seller proceeds held in the bid escrow until the claim
+    pub locked_platform_fee: u64,       // Platform fee held in the bid escrow until the claim
+    pub locked_royalty: u64,            // Royalty held in the bid escrow until the claim
     pub bump: u8,
 }
 
@@ -144,6 +465,7 @@ pub struct Bid {
     pub bidder: Pubkey,
     pub listing: Pubkey,
     pub amount: u64,
+    pub max_bid: u64,                   // Proxy maximum escrowed for this bid
     pub created_at: i64,
     pub is_active: bool,
     pub bump: u8,
@@ -190,12 +512,82 @@ pub struct AuctionConfig {
     pub end_time: i64,
     pub min_bid_increment: u64,
     pub reserve_price: Option<u64>,
+    pub opening_bid: Option<u64>,       // Minimum first bid, if higher than the listing price
+    pub extension_window: i64,          // Bids this close to the end extend it (0 = no anti-sniping)
+    pub extension_period: i64,          // Seconds each qualifying bid adds to the end time
+    pub max_extensions: u8,             // Cap on extensions over the auction's lifetime
+    pub bidder_whitelist: Option<[u8; 32]>, // Merkle root of invited bidders (None = public auction)
+    pub reserve_hidden: bool,           // Withhold the reserve from ListingCreated; revealed at settlement
+    pub min_distinct_bidders: u32,      // Fewer distinct bidders settles as a no-sale (0 = no minimum)
+    #[max_len(5)]
+    pub increment_tiers: Vec<IncrementTier>, // Larger increments from higher bids, ascending (empty = flat increment)
+}
+
+impl AuctionConfig {
+    pub const MAX_INCREMENT_TIERS: usize = 5;
+
+    /// The highest price the seller set for an auction starting at `price`,
+    /// which is what it is worth to the seller at the least
+    pub fn listing_value(&self, price: u64) -> u64 {
+        price
+            .max(self.opening_bid.unwrap_or(0))
+            .max(self.reserve_price.unwrap_or(0))
+    }
+
+    /// Minimum increment over a standing bid of `price`: the highest tier
+    /// reached, or the flat increment below the first tier
+    pub fn min_increment_at(&self, price: u64) -> u64 {
+        self.increment_tiers
+            .iter()
+            .rev()
+            .find(|tier| price >= tier.min_price)
+            .map_or(self.min_bid_increment, |tier| tier.min_increment)
+    }
+
+    /// Whether `bidder` may bid. Public auctions admit anyone; private ones
+    /// need a proof from sha256(bidder) up to the whitelist root, hashing
+    /// each pair of nodes in sorted order.
+    pub fn admits_bidder(&self, bidder: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        let root = match self.bidder_whitelist {
+            Some(root) => root,
+            None => return true,
+        };
+
+        let mut node = hashv(&[bidder.as_ref()]).to_bytes();
+        for sibling in proof {
+            node = if node <= *sibling {
+                hashv(&[&node, sibling]).to_bytes()
+            } else {
+                hashv(&[sibling, &node]).to_bytes()
+            };
+        }
+        node == root
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct IncrementTier {
+    pub min_price: u64,                 // Standing bid (lamports) from which the tier applies
+    pub min_increment: u64,             // Minimum increment within the tier
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct RelistConfig {
+    pub price: u64,                     // Lower starting price for the relisted auction
+    pub duration: i64,                  // Seconds each relisted auction runs
+    pub max_relists: u8,                // Relists allowed before the listing must be handled manually
+}
+
+impl RelistConfig {
+    pub const MAX_RELISTS: u8 = 5;
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
 pub struct RoyaltyConfig {
     pub creator: Pubkey,
     pub percentage_bps: u16,  // Basis points (100 = 1%)
+    pub royalty_payout_mint: Option<Pubkey>, // Pay the royalty in this token instead of SOL
+    pub min_royalty: u64,     // Lamport floor on the royalty, capped at the sale price
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
@@ -219,6 +611,7 @@ pub enum ReleaseCondition {
     BuyerConfirmation,
     SellerConfirmation,
     BothPartiesConfirmation,
+    MultiConfirmation { required: u8 }, // Release once `required` distinct confirmers have signed off
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
@@ -325,10 +718,49 @@ pub mod create_escrow;
 pub mod release_escrow;
 pub mod initiate_dispute;
 pub mod resolve_dispute;
+pub mod auto_resolve_expired_dispute;
 pub mod update_marketplace_fee;
+pub mod update_rounding_mode;
+pub mod update_fallback_royalty_recipient;
 pub mod pause_marketplace;
 pub mod unpause_marketplace;
+pub mod try_auto_unpause;
+pub mod update_health_thresholds;
 pub mod withdraw_fees;
+pub mod set_allow_offers;
+pub mod make_offer;
+pub mod accept_offer;
+pub mod reject_offer;
+pub mod update_settlement_grace_period;
+pub mod force_refund_winner;
+pub mod add_fee_exemption;
+pub mod remove_fee_exemption;
+pub mod batch_cancel_listings;
+pub mod update_max_active_auctions;
+pub mod set_relist_config;
+pub mod relist_auction;
+pub mod update_fee_burn;
+pub mod update_royalty_price_feed;
+pub mod withdraw_royalty_liquidity;
+pub mod confirm_escrow_release;
+pub mod update_fee_tiers;
+pub mod mark_escrow_delivered;
+pub mod convert_bid_to_credit;
+pub mod update_min_listing_duration;
+pub mod admin_cancel_listing;
+pub mod force_cancel_auction;
+pub mod enable_royalty_receipts;
+pub mod update_winner_claim_window;
+pub mod claim_nft;
+pub mod revert_unclaimed_auction;
+pub mod update_max_auctions_per_mint;
+pub mod reset_mint_auction_count;
+pub mod add_seller_verification;
+pub mod remove_seller_verification;
+pub mod update_verified_seller_threshold;
+pub mod update_arbitrator_fee;
+pub mod add_listing_units;
+pub mod withdraw_listing_units;
 
 // Context structs for all instructions
 use crate::state::*;
@@ -822,6 +1254,7 @@ pub struct ListingCreated {
     pub mint: Pubkey,
     pub price: u64,
     pub listing_type: ListingType,
+    pub reserve_price: Option<u64>,     // None for fixed-price listings and hidden reserves
 }
 
 #[event]
@@ -847,6 +1280,27 @@ pub struct AuctionEnded {
     pub listing: Pubkey,
     pub winner: Pubkey,
     pub winning_bid: u64,
+    pub unique_bidders: u32,
+    pub reserve_price: Option<u64>,     // Disclosed here even when hidden at listing
+    pub claim_deadline: Option<i64>,    // Set when the winner must claim the NFT themselves
+}
+
+#[event]
+pub struct AuctionNoSale {
+    pub listing: Pubkey,
+    pub winner: Pubkey,
+    pub seller: Pubkey,
+    pub unique_bidders: u32,
+    pub min_distinct_bidders: u32,
+    pub refunded_amount: u64,
+}
+
+#[event]
+pub struct AuctionExpired {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub returned_rent: u64,            // Escrow token account rent returned to the seller
 }
 
 #[event]
@@ -863,3 +1317,368 @@ pub struct DisputeResolved {
     pub resolution: DisputeResolution,
     pub resolved_by: Pubkey,
 }
+
+#[event]
+pub struct DisputeAutoResolved {
+    pub dispute: Pubkey,
+    pub escrow: Pubkey,
+    pub decision: ResolutionDecision,
+    pub buyer_amount: u64,
+    pub seller_amount: u64,
+    pub platform_fee: u64,
+    pub triggered_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundingModeUpdated {
+    pub admin: Pubkey,
+    pub rounding_mode: RoundingMode,
+}
+
+#[event]
+pub struct MarketplaceAutoUnpaused {
+    pub marketplace: Pubkey,
+    pub triggered_by: Pubkey,
+    pub scheduled_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HealthThresholdsUpdated {
+    pub marketplace: Pubkey,
+    pub admin: Pubkey,
+    pub require_fee_vault_solvency: bool,
+    pub fee_mint: Pubkey,
+    pub min_pause_duration: i64,
+    pub block_on_critical_disputes: bool,
+}
+
+#[event]
+pub struct FallbackRoyaltyRecipientUpdated {
+    pub admin: Pubkey,
+    pub fallback_royalty_recipient: Pubkey,
+}
+
+#[event]
+pub struct RoyaltyPaid {
+    pub listing: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub used_fallback: bool,
+}
+
+#[event]
+pub struct ListingOffersToggled {
+    pub listing: Pubkey,
+    pub allow_offers: bool,
+}
+
+#[event]
+pub struct OfferMade {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct OfferAccepted {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub platform_fee: u64,
+    pub royalty_fee: u64,
+}
+
+#[event]
+pub struct OfferRejected {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub rejected_by: Pubkey,
+}
+
+#[event]
+pub struct SettlementGracePeriodUpdated {
+    pub admin: Pubkey,
+    pub settlement_grace_period: i64,
+}
+
+#[event]
+pub struct AuctionVoided {
+    pub listing: Pubkey,
+    pub winner: Pubkey,
+    pub seller: Pubkey,
+    pub refunded_amount: u64,
+    pub voided_by: Pubkey,
+}
+
+#[event]
+pub struct FeeExemptionAdded {
+    pub seller: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct FeeExemptionRemoved {
+    pub seller: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct SellerVerificationAdded {
+    pub seller: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct SellerVerificationRemoved {
+    pub seller: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct VerifiedSellerThresholdUpdated {
+    pub admin: Pubkey,
+    pub verified_seller_threshold: u64,
+}
+
+#[event]
+pub struct ArbitratorFeeUpdated {
+    pub admin: Pubkey,
+    pub arbitrator_fee_bps: u16,
+}
+
+#[event]
+pub struct SettlementRentReimbursed {
+    pub listing: Pubkey,
+    pub caller: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ListingCancelled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct ListingsBatchCancelled {
+    pub seller: Pubkey,
+    pub cancelled: u8,
+    pub skipped: u8,
+}
+
+#[event]
+pub struct MaxActiveAuctionsUpdated {
+    pub admin: Pubkey,
+    pub max_active_auctions_per_seller: u16,
+}
+
+#[event]
+pub struct MaxAuctionsPerMintUpdated {
+    pub admin: Pubkey,
+    pub max_auctions_per_mint: u16,
+}
+
+#[event]
+pub struct MintAuctionCountReset {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub previous_count: u16,
+}
+
+#[event]
+pub struct AuctionExtended {
+    pub listing: Pubkey,
+    pub bidder: Pubkey,
+    pub new_end_time: i64,
+    pub extensions_applied: u8,
+}
+
+#[event]
+pub struct ProxyBidIncreased {
+    pub listing: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub max_bid: u64,
+}
+
+#[event]
+pub struct RelistConfigUpdated {
+    pub listing: Pubkey,
+    pub relist_config: Option<RelistConfig>,
+}
+
+#[event]
+pub struct AuctionRelisted {
+    pub listing: Pubkey,
+    pub caller: Pubkey,
+    pub price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub relists_applied: u8,
+}
+
+#[event]
+pub struct FeeBurnUpdated {
+    pub admin: Pubkey,
+    pub burn_share_bps: u16,
+    pub project_token_mint: Pubkey,
+}
+
+#[event]
+pub struct FeesBurned {
+    pub mint: Pubkey,
+    pub burned_amount: u64,
+    pub treasury_amount: u64,
+}
+
+#[event]
+pub struct RoyaltyPriceFeedUpdated {
+    pub payout_mint: Pubkey,
+    pub price_per_sol: u64,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct RoyaltyLiquidityWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct RoyaltyPaidInToken {
+    pub listing: Pubkey,
+    pub recipient: Pubkey,
+    pub payout_mint: Pubkey,
+    pub royalty_lamports: u64,
+    pub payout_amount: u64,
+}
+
+#[event]
+pub struct EscrowReleaseConfirmed {
+    pub escrow: Pubkey,
+    pub confirmer: Pubkey,
+    pub confirmations: u8,
+    pub required: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeTiersUpdated {
+    pub admin: Pubkey,
+    pub fee_tiers: Vec<FeeTier>,
+}
+
+#[event]
+pub struct SellerFeeTierChanged {
+    pub seller: Pubkey,
+    pub old_fee_bps: u16,
+    pub new_fee_bps: u16,
+    pub total_volume: u64,
+}
+
+#[event]
+pub struct EscrowDelivered {
+    pub escrow: Pubkey,
+    pub seller: Pubkey,
+    pub delivered_at: i64,
+    pub releasable_at: i64,
+}
+
+#[event]
+pub struct BidConvertedToCredit {
+    pub listing: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct StoreCreditSpent {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct MinListingDurationUpdated {
+    pub admin: Pubkey,
+    pub min_listing_duration: i64,
+}
+
+#[event]
+pub struct AuctionForceCancelled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub admin: Pubkey,
+    pub bidders_refunded: u32,
+    pub refunded_amount: u64,
+    pub reason: String,
+}
+
+#[event]
+pub struct RoyaltyReceiptsEnabled {
+    pub creator: Pubkey,
+    pub segment_capacity: u16,
+}
+
+#[event]
+pub struct RoyaltyReceiptRecorded {
+    pub creator: Pubkey,
+    pub sale: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub segment: u32,
+    pub position: u16,
+    pub receipt_number: u64,
+}
+
+#[event]
+pub struct WinnerClaimWindowUpdated {
+    pub admin: Pubkey,
+    pub winner_claim_window: i64,
+}
+
+#[event]
+pub struct AuctionNftClaimed {
+    pub listing: Pubkey,
+    pub winner: Pubkey,
+    pub seller: Pubkey,
+    pub proceeds: u64,
+    pub platform_fee: u64,
+    pub royalty_fee: u64,
+}
+
+#[event]
+pub struct UnclaimedAuctionReverted {
+    pub listing: Pubkey,
+    pub winner: Pubkey,
+    pub seller: Pubkey,
+    pub refunded_amount: u64,
+    pub reverted_by: Pubkey,
+}
+
+#[event]
+pub struct ListingUnitsAdded {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub units_added: u32,
+    pub quantity: u32,
+    pub allow_partial_fill: bool,
+}
+
+#[event]
+pub struct ListingUnitsWithdrawn {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub units_withdrawn: u32,
+    pub quantity: u32,
+}
@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+use crate::ReleaseCondition;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowState {
+    Active,
+    Disputed,
+    Completed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeState {
+    Open,
+    Resolved,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeCategory {
+    ItemNotReceived,
+    ItemNotAsDescribed,
+    EventCancelled,
+    Fraud,
+    Other,
+}
+
+impl DisputeCategory {
+    /// Outcome applied to this category when the arbitrator misses the deadline
+    pub fn default_resolution(&self) -> ResolutionDecision {
+        match self {
+            DisputeCategory::ItemNotReceived => ResolutionDecision::FavorBuyer,
+            DisputeCategory::ItemNotAsDescribed => ResolutionDecision::Split,
+            DisputeCategory::EventCancelled => ResolutionDecision::FavorBuyer,
+            DisputeCategory::Fraud => ResolutionDecision::FavorBuyer,
+            DisputeCategory::Other => ResolutionDecision::Split,
+        }
+    }
+
+    /// Whether a dispute of this category may be resolved with `decision`.
+    /// A ticket that never arrived, or an event that never happened, can't
+    /// end with the seller keeping the full payment.
+    pub fn allows(&self, decision: ResolutionDecision) -> bool {
+        match self {
+            DisputeCategory::ItemNotReceived => decision != ResolutionDecision::FavorSeller,
+            DisputeCategory::EventCancelled => decision == ResolutionDecision::FavorBuyer,
+            DisputeCategory::ItemNotAsDescribed
+            | DisputeCategory::Fraud
+            | DisputeCategory::Other => true,
+        }
+    }
+
+    /// Whether an open dispute of this category keeps the marketplace from unpausing
+    pub fn is_critical(&self) -> bool {
+        matches!(self, DisputeCategory::EventCancelled | DisputeCategory::Fraud)
+    }
+
+    /// The escrow's fallback outcome if this category allows it, otherwise the category default
+    pub fn fallback_resolution(&self, escrow_default: ResolutionDecision) -> ResolutionDecision {
+        if self.allows(escrow_default) {
+            escrow_default
+        } else {
+            self.default_resolution()
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionType {
+    RefundBuyer,
+    PaySeller,
+    Split,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionDecision {
+    FavorBuyer,
+    FavorSeller,
+    Split,
+}
+
+impl ResolutionDecision {
+    /// Whether a buyer/seller payout is consistent with this decision: the
+    /// losing side gets nothing, and a split pays both sides
+    pub fn matches_split(&self, buyer_amount: u64, seller_amount: u64) -> bool {
+        match self {
+            ResolutionDecision::FavorBuyer => seller_amount == 0,
+            ResolutionDecision::FavorSeller => buyer_amount == 0,
+            ResolutionDecision::Split => buyer_amount > 0 && seller_amount > 0,
+        }
+    }
+}
+
+#[account]
+pub struct Escrow {
+    // Parties
+    pub buyer: Pubkey,                   // Wallet that funded the escrow
+    pub seller: Pubkey,                  // Wallet receiving funds on release
+    pub arbitrator: Pubkey,              // Wallet allowed to resolve disputes
+
+    // Funds and terms
+    pub amount: u64,                     // Tokens currently held in escrow
+    pub terms: String,                   // Free-form terms agreed by both parties
+    pub platform_fee_rate: u16,          // Platform fee in basis points
+
+    // Timing
+    pub created_at: i64,                 // Unix timestamp of creation
+    pub expiry_time: i64,                // Unix timestamp after which release is blocked
+    pub dispute_period: i64,             // Seconds the parties have to raise a dispute
+    pub disputed_at: Option<i64>,        // When a dispute was raised (if any)
+    pub completed_at: Option<i64>,       // When the escrow was fully settled
+    pub inspection_period: i64,          // Seconds after delivery before funds can be released (0 = none)
+    pub delivered_at: Option<i64>,       // When the seller marked the goods delivered
+    pub min_time_before_dispute: i64,    // Seconds after creation before either party can dispute (0 = immediately)
+
+    // Dispute handling
+    pub arbitration_window: i64,         // Seconds the arbitrator has to resolve a dispute
+    pub default_resolution: ResolutionDecision, // Outcome applied if the arbitrator misses the dispute deadline
+
+    // Release confirmations
+    pub release_condition: Option<ReleaseCondition>, // Extra condition gating release (None = any party may release)
+    pub confirmers: Vec<Pubkey>,         // Wallets allowed to confirm release
+    pub confirmations: Vec<Pubkey>,      // Confirmers that have signed off so far
+
+    // Lifecycle
+    pub state: EscrowState,
+    pub bump: u8,
+}
+
+impl Escrow {
+    pub const MAX_TERMS_LEN: usize = 500;
+    pub const DEFAULT_ARBITRATION_WINDOW: i64 = 7 * 24 * 60 * 60; // 7 days
+    pub const MAX_CONFIRMERS: usize = 5;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // buyer
+        32 + // seller
+        32 + // arbitrator
+        8 + // amount
+        (4 + Self::MAX_TERMS_LEN) + // terms
+        2 + // platform_fee_rate
+        8 + // created_at
+        8 + // expiry_time
+        8 + // dispute_period
+        (1 + 8) + // disputed_at
+        (1 + 8) + // completed_at
+        8 + // inspection_period
+        (1 + 8) + // delivered_at
+        8 + // min_time_before_dispute
+        8 + // arbitration_window
+        1 + // default_resolution
+        (1 + 1 + 1) + // release_condition
+        (4 + Self::MAX_CONFIRMERS * 32) + // confirmers
+        (4 + Self::MAX_CONFIRMERS * 32) + // confirmations
+        1 + // state
+        1; // bump
+
+    /// Distinct confirmations needed before funds can be released (0 if none)
+    pub fn required_confirmations(&self) -> u8 {
+        match self.release_condition {
+            Some(ReleaseCondition::MultiConfirmation { required }) => required,
+            _ => 0,
+        }
+    }
+
+    /// Whether the buyer's inspection window has closed. Escrows with an
+    /// inspection period stay locked until delivery is recorded.
+    pub fn inspection_complete(&self, now: i64) -> bool {
+        if self.inspection_period == 0 {
+            return true;
+        }
+        self.delivered_at
+            .is_some_and(|delivered_at| now >= delivered_at.saturating_add(self.inspection_period))
+    }
+
+    /// Earliest time a dispute can be raised, giving the seller a chance to deliver first
+    pub fn dispute_eligible_at(&self) -> i64 {
+        self.created_at.saturating_add(self.min_time_before_dispute)
+    }
+
+    pub fn has_confirmed(&self, confirmer: &Pubkey) -> bool {
+        self.confirmations.contains(confirmer)
+    }
+
+    pub fn confirmation_threshold_met(&self) -> bool {
+        self.confirmations.len() >= self.required_confirmations() as usize
+    }
+}
+
+#[account]
+pub struct Dispute {
+    // References
+    pub escrow: Pubkey,                  // Escrow under dispute
+    pub marketplace: Pubkey,             // Marketplace counting the dispute while it is open
+    pub disputer: Pubkey,                // Party that raised the dispute
+    pub arbitrator: Pubkey,              // Arbitrator copied from the escrow
+
+    // Claim details
+    pub category: DisputeCategory,
+    pub description: String,
+    pub evidence_links: Vec<String>,
+    pub requested_resolution: ResolutionType,
+
+    // Timing
+    pub created_at: i64,
+    pub deadline: i64,                   // Arbitrator must act before this timestamp
+    pub resolved_at: Option<i64>,
+
+    // Outcome
+    pub state: DisputeState,
+    pub decision: Option<ResolutionDecision>,
+    pub reasoning: Option<String>,
+    pub bump: u8,
+}
+
+impl Dispute {
+    pub const MAX_DESCRIPTION_LEN: usize = 1000;
+    pub const MAX_EVIDENCE_LINKS: usize = 5;
+    pub const MAX_EVIDENCE_LINK_LEN: usize = 200;
+    pub const MAX_REASONING_LEN: usize = 500;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // escrow
+        32 + // marketplace
+        32 + // disputer
+        32 + // arbitrator
+        1 + // category
+        (4 + Self::MAX_DESCRIPTION_LEN) + // description
+        (4 + Self::MAX_EVIDENCE_LINKS * (4 + Self::MAX_EVIDENCE_LINK_LEN)) + // evidence_links
+        1 + // requested_resolution
+        8 + // created_at
+        8 + // deadline
+        (1 + 8) + // resolved_at
+        1 + // state
+        (1 + 1) + // decision
+        (1 + 4 + Self::MAX_REASONING_LEN) + // reasoning
+        1; // bump
+
+    pub fn is_past_deadline(&self, now: i64) -> bool {
+        now > self.deadline
+    }
+}
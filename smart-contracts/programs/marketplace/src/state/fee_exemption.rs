@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+/// Marks a seller as exempt from the platform fee. The record lives at
+/// `[b"fee_exemption", seller]` and the seller is exempt for as long as it exists.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeExemption {
+    pub seller: Pubkey,
+    pub added_by: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+impl FeeExemption {
+    /// Whether the exemption PDA passed to a sale holds a live record. The
+    /// account is derived from the seller, so it only needs to exist and be
+    /// owned by this program; a removed exemption is closed back to the system program.
+    pub fn is_active(account: &AccountInfo) -> bool {
+        account.owner == &crate::ID && !account.data_is_empty()
+    }
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::state::royalty::RoyaltyConfig;
+use crate::state::rounding::RoundingMode;
+use crate::errors::MarketplaceError;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum ListingType {
@@ -7,7 +9,14 @@ pub enum ListingType {
     Auction,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+/// Which royalty distribution pays out on a sale; exactly one ever runs
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RoyaltyPath {
+    Legacy,                              // `royalty_bps` to the single `royalty_recipient`
+    Enhanced,                            // `royalty_config` split across its recipients
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum ListingState {
     Active,
     Sold,
@@ -23,6 +32,10 @@ pub struct Listing {
     pub listing_type: ListingType,       // Fixed price or auction
     pub state: ListingState,             // Current state of the listing
     
+    // Multi-unit listings of identical tickets, each its own NFT in escrow
+    pub quantity: u32,                   // Units left for sale (0 = just the listed ticket)
+    pub allow_partial_fill: bool,        // Whether a buyer may take fewer than all units
+    
     // Marketplace params
     pub marketplace_authority: Pubkey,   // The marketplace authority (for fees)
     pub marketplace_fee_bps: u16,        // Fee in basis points (e.g., 250 = 2.5%)
@@ -31,9 +44,11 @@ pub struct Listing {
     pub event_pubkey: Pubkey,            // Reference to the event
     pub royalty_recipient: Pubkey,       // Legacy: Single wallet to receive royalties
     pub royalty_bps: u16,                // Legacy: Royalty percentage in basis points
+    pub min_royalty: u64,                // Legacy: Lamport floor on the royalty, capped at the sale price
     
     // Enhanced royalty configuration (optional)
     pub royalty_config: Option<RoyaltyConfig>, // Advanced royalty distribution rules
+    pub royalty_path: RoyaltyPath,       // Which of the two royalty configurations pays
     
     // If this is an auction, we'll have an associated auction account
     pub auction_account: Option<Pubkey>, // Only present for auction listings
@@ -47,40 +62,61 @@ pub struct Listing {
 }
 
 impl Listing {
-    // Calculate total royalty fee based on sale price
-    pub fn calculate_royalty_fee(&self, sale_price: u64) -> Result<u64> {
+    /// Units a buyer can still purchase; a listing never given extra units
+    /// holds only its own ticket
+    pub fn units_available(&self) -> u32 {
+        self.quantity.max(1)
+    }
+    
+    /// Royalty recipient accounts a purchase must supply: none on the legacy
+    /// path, one per configured recipient on the enhanced path. Rejects a
+    /// listing whose flag disagrees with its royalty configuration, or whose
+    /// split names more recipients than a purchase can pay.
+    pub fn royalty_recipient_count(&self) -> Result<usize> {
+        match (self.royalty_path, &self.royalty_config) {
+            (RoyaltyPath::Legacy, None) => Ok(0),
+            (RoyaltyPath::Enhanced, Some(config)) => {
+                config.validate_recipients()?;
+                Ok(config.recipients.len())
+            }
+            _ => err!(MarketplaceError::RoyaltyPathMismatch),
+        }
+    }
+    
+    // Calculate total royalty fee based on sale price, raised to the
+    // configured floor; callers cap it at what the sale leaves
+    pub fn calculate_royalty_fee(&self, sale_price: u64, rounding_mode: RoundingMode) -> Result<u64> {
         // If we have enhanced royalty config, use that
         if let Some(config) = &self.royalty_config {
             let effective_bps = config.effective_basis_points(sale_price)?;
             
-            return Ok((sale_price as u128)
-                .checked_mul(effective_bps as u128)
-                .unwrap()
-                .checked_div(10000)
-                .unwrap() as u64);
+            return rounding_mode
+                .apply_bps(sale_price, effective_bps)
+                .map(|royalty_fee| royalty_fee.max(config.min_royalty))
+                .ok_or(ErrorCode::Overflow.into());
         }
         
         // Otherwise, use legacy flat royalty rate
-        Ok((sale_price as u128)
-            .checked_mul(self.royalty_bps as u128)
-            .unwrap()
-            .checked_div(10000)
-            .unwrap() as u64)
+        rounding_mode
+            .apply_bps(sale_price, self.royalty_bps)
+            .map(|royalty_fee| royalty_fee.max(self.min_royalty))
+            .ok_or(ErrorCode::Overflow.into())
     }
     
     // Calculate marketplace fee
-    pub fn calculate_marketplace_fee(&self, sale_price: u64) -> u64 {
-        (sale_price as u128)
-            .checked_mul(self.marketplace_fee_bps as u128)
-            .unwrap()
-            .checked_div(10000)
-            .unwrap() as u64
+    pub fn calculate_marketplace_fee(&self, sale_price: u64, rounding_mode: RoundingMode) -> Result<u64> {
+        rounding_mode
+            .apply_bps(sale_price, self.marketplace_fee_bps)
+            .map(|marketplace_fee| marketplace_fee.min(sale_price))
+            .ok_or(ErrorCode::Overflow.into())
     }
     
     // Calculate seller proceeds after fees
-    pub fn calculate_seller_proceeds(&self, sale_price: u64) -> Result<u64> {
-        let marketplace_fee = self.calculate_marketplace_fee(sale_price);
-        let royalty_fee = self.calculate_royalty_fee(sale_price)?;
+    pub fn calculate_seller_proceeds(&self, sale_price: u64, rounding_mode: RoundingMode) -> Result<u64> {
+        let marketplace_fee = self.calculate_marketplace_fee(sale_price, rounding_mode)?;
+        let royalty_fee = self
+            .calculate_royalty_fee(sale_price, rounding_mode)?
+            .min(sale_price - marketplace_fee);
         
         sale_price
             .checked_sub(marketplace_fee)
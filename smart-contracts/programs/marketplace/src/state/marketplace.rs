@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PauseLevel {
+    None,
+    Partial,
+    Full,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Listings,
+    Purchases,
+    Auctions,
+    Bids,
+    Escrow,
+    Disputes,
+    Withdrawals,
+}
+
+/// Reason codes reported when the pre-unpause health check fails
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HealthCheckFailure {
+    FeeVaultInsolvent = 1,
+    MinPauseDurationNotMet = 2,
+    CriticalDisputesPending = 3,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct HealthThresholds {
+    pub require_fee_vault_solvency: bool, // Vault balance must cover accumulated fees
+    pub fee_mint: Pubkey,                 // Token whose fee vault account the solvency check reads
+    pub min_pause_duration: i64,          // Seconds the marketplace must stay paused
+    pub block_on_critical_disputes: bool, // No critical dispute may be open
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            require_fee_vault_solvency: true,
+            fee_mint: Pubkey::default(),
+            min_pause_duration: 0,
+            block_on_critical_disputes: true,
+        }
+    }
+}
+
+#[account]
+pub struct Marketplace {
+    // Administration
+    pub admin: Pubkey,                   // Wallet allowed to pause, unpause and withdraw
+    pub treasury: Pubkey,                // Wallet receiving withdrawn fees
+
+    // Pause state
+    pub is_paused: bool,
+    pub pause_level: PauseLevel,
+    pub pause_reason: Option<String>,
+    pub paused_at: Option<i64>,
+    pub pause_duration: Option<i64>,
+    pub auto_unpause_at: Option<i64>,    // Anyone may unpause after this timestamp
+    pub unpaused_at: Option<i64>,
+    pub affected_features: Vec<Feature>,
+
+    // Health checks
+    pub health_thresholds: HealthThresholds,
+    pub open_critical_disputes: u32,     // Critical-category disputes awaiting resolution
+
+    pub bump: u8,
+}
+
+impl Marketplace {
+    pub const MAX_PAUSE_REASON_LEN: usize = 500;
+    pub const MAX_AFFECTED_FEATURES: usize = 7;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // admin
+        32 + // treasury
+        1 + // is_paused
+        1 + // pause_level
+        (1 + 4 + Self::MAX_PAUSE_REASON_LEN) + // pause_reason
+        (1 + 8) + // paused_at
+        (1 + 8) + // pause_duration
+        (1 + 8) + // auto_unpause_at
+        (1 + 8) + // unpaused_at
+        (4 + Self::MAX_AFFECTED_FEATURES) + // affected_features
+        (1 + 32 + 8 + 1) + // health_thresholds
+        4 + // open_critical_disputes
+        1; // bump
+
+    /// Checks the configured thresholds, returning the first one that fails
+    pub fn system_health_check(
+        &self,
+        accumulated_fees: u64,
+        vault_balance: u64,
+        now: i64,
+    ) -> std::result::Result<(), HealthCheckFailure> {
+        let thresholds = &self.health_thresholds;
+
+        if thresholds.require_fee_vault_solvency && vault_balance < accumulated_fees {
+            return Err(HealthCheckFailure::FeeVaultInsolvent);
+        }
+
+        if let Some(paused_at) = self.paused_at {
+            if now < paused_at.saturating_add(thresholds.min_pause_duration) {
+                return Err(HealthCheckFailure::MinPauseDurationNotMet);
+            }
+        }
+
+        if thresholds.block_on_critical_disputes && self.open_critical_disputes > 0 {
+            return Err(HealthCheckFailure::CriticalDisputesPending);
+        }
+
+        Ok(())
+    }
+}
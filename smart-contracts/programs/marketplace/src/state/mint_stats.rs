@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use crate::errors::MarketplaceError;
+
+/// Per-mint auction counters, kept at `[b"mint_stats", mint]`.
+#[account]
+#[derive(InitSpace)]
+pub struct MintStats {
+    pub mint: Pubkey,
+    pub auction_count: u16,             // Auctions run for this mint, relists included, since the last reset
+    pub bump: u8,
+}
+
+impl MintStats {
+    /// Count another auction for the mint, enforcing the marketplace cap (0 = unlimited)
+    pub fn record_auction(&mut self, max_auctions: u16) -> Result<()> {
+        require!(
+            max_auctions == 0 || self.auction_count < max_auctions,
+            MarketplaceError::MintAuctionLimitReached
+        );
+        self.auction_count = self
+            .auction_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::Overflow)?;
+        Ok(())
+    }
+}
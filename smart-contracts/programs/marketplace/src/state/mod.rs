@@ -1,7 +1,29 @@
 pub mod listing;
 pub mod auction;
 pub mod royalty;
+pub mod escrow;
+pub mod rounding;
+pub mod marketplace;
+pub mod offer;
+pub mod fee_exemption;
+pub mod seller_stats;
+pub mod royalty_price_feed;
+pub mod store_credit;
+pub mod royalty_receipt;
+pub mod mint_stats;
+pub mod seller_verification;
 
 pub use listing::*;
 pub use auction::*;
 pub use royalty::*;
+pub use escrow::*;
+pub use rounding::*;
+pub use marketplace::*;
+pub use offer::*;
+pub use fee_exemption::*;
+pub use seller_stats::*;
+pub use royalty_price_feed::*;
+pub use store_credit::*;
+pub use royalty_receipt::*;
+pub use mint_stats::*;
+pub use seller_verification::*;
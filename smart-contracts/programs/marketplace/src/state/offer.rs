@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+/// A buyer's standing offer on a fixed-price listing. The offered lamports
+/// are held by this account until the seller accepts or the offer is rejected.
+#[account]
+#[derive(InitSpace)]
+pub struct MarketplaceOffer {
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
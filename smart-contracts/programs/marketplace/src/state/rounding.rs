@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+/// How fractional lamports are handled when applying a basis-point rate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum RoundingMode {
+    #[default]
+    Floor,
+    Ceil,
+    Nearest,
+}
+
+impl RoundingMode {
+    // Apply basis points to an amount, rounding the remainder according to the mode
+    pub fn apply_bps(&self, amount: u64, bps: u16) -> Option<u64> {
+        self.apply_ratio(amount, bps as u64, 10000)
+    }
+
+    // Take `numerator / denominator` of an amount, rounding the remainder according to the mode
+    pub fn apply_ratio(&self, amount: u64, numerator: u64, denominator: u64) -> Option<u64> {
+        if denominator == 0 {
+            return None;
+        }
+        let denominator = denominator as u128;
+        let product = (amount as u128).checked_mul(numerator as u128)?;
+        let rounded = match self {
+            RoundingMode::Floor => product,
+            RoundingMode::Ceil => product.checked_add(denominator - 1)?,
+            RoundingMode::Nearest => product.checked_add(denominator / 2)?,
+        };
+
+        u64::try_from(rounded / denominator).ok()
+    }
+}
+
+// Split a sale price into (platform_fee, royalty_fee, seller_proceeds).
+// The royalty is raised to `min_royalty` if the percentage comes in under it,
+// and trimmed if rounding up or the floor would distribute more than the price.
+pub fn split_sale_price(
+    price: u64,
+    platform_fee_bps: u16,
+    royalty_bps: u16,
+    min_royalty: u64,
+    rounding_mode: RoundingMode,
+) -> Option<(u64, u64, u64)> {
+    let platform_fee = rounding_mode.apply_bps(price, platform_fee_bps)?.min(price);
+    let royalty_fee = rounding_mode
+        .apply_bps(price, royalty_bps)?
+        .max(min_royalty)
+        .min(price - platform_fee);
+    let seller_proceeds = price - platform_fee - royalty_fee;
+
+    Some((platform_fee, royalty_fee, seller_proceeds))
+}
@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::errors::MarketplaceError;
+use crate::state::rounding::RoundingMode;
 
 /// Structure representing a single royalty recipient with their share
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -24,9 +26,26 @@ pub struct RoyaltyConfig {
     /// For example, higher value sales might pay higher royalty percentages
     pub tier_thresholds: Option<Vec<u64>>,
     pub tier_basis_points_adjustments: Option<Vec<i16>>,
+    
+    /// Lamport floor on the total royalty, split across the recipients by
+    /// their basis points when the percentage comes in under it
+    pub min_royalty: u64,
 }
 
 impl RoyaltyConfig {
+    /// Most recipients a split may name. Each one is a transfer and an account
+    /// in the buyer's transaction, so the cap keeps purchases within limits
+    pub const MAX_ROYALTY_RECIPIENTS: usize = 5;
+    
+    /// Rejects a split naming more than `MAX_ROYALTY_RECIPIENTS` recipients
+    pub fn validate_recipients(&self) -> Result<()> {
+        require!(
+            self.recipients.len() <= Self::MAX_ROYALTY_RECIPIENTS,
+            MarketplaceError::TooManyRoyaltyRecipients
+        );
+        Ok(())
+    }
+    
     /// Calculate the total royalty basis points
     pub fn total_basis_points(&self) -> Result<u16> {
         let mut total: u16 = 0;
@@ -71,16 +90,20 @@ impl RoyaltyConfig {
         }
     }
     
-    /// Distribute royalties to all recipients
+    /// Distribute royalties to all recipients, paying out no more than `max_royalty`.
+    /// Returns the amount paid to each recipient, in recipient order.
     pub fn distribute_royalties<'info>(
         &self,
         from: &AccountInfo<'info>,
         recipient_accounts: &[AccountInfo<'info>],
         system_program: &Program<'info, System>,
         sale_price: u64,
+        max_royalty: u64,
+        rounding_mode: RoundingMode,
         signer_seeds: &[&[&[u8]]]
-    ) -> Result<u64> {
+    ) -> Result<Vec<u64>> {
         // Validate inputs
+        self.validate_recipients()?;
         require!(
             recipient_accounts.len() == self.recipients.len(),
             ErrorCode::InvalidRoyaltyRecipients
@@ -90,27 +113,35 @@ impl RoyaltyConfig {
         let effective_bps = self.effective_basis_points(sale_price)?;
         
         // Calculate the total royalty amount
-        let total_royalty = (sale_price as u128)
-            .checked_mul(effective_bps as u128)
-            .unwrap()
-            .checked_div(10000)
-            .unwrap() as u64;
+        let percentage_royalty = rounding_mode
+            .apply_bps(sale_price, effective_bps)
+            .ok_or(ErrorCode::Overflow)?;
+        let total_royalty = percentage_royalty.max(self.min_royalty).min(max_royalty);
             
         // If total is zero, nothing to distribute
+        let mut payouts = vec![0; self.recipients.len()];
         if total_royalty == 0 {
-            return Ok(0);
+            return Ok(payouts);
         }
         
+        // Below the floor, or when the cap trims it, the total is split by
+        // each recipient's share of the basis points instead of the price
+        let total_bps = self.total_basis_points()?;
+        let split_total = total_royalty != percentage_royalty && total_bps > 0;
+        
         // Calculate each recipient's share and distribute
         let mut total_distributed: u64 = 0;
         
         for (i, recipient_info) in self.recipients.iter().enumerate() {
-            // Calculate this recipient's share
-            let recipient_share = (sale_price as u128)
-                .checked_mul(recipient_info.basis_points as u128)
-                .unwrap()
-                .checked_div(10000)
-                .unwrap() as u64;
+            // Calculate this recipient's share; rounding up never pays out
+            // more than what is left of the total
+            let recipient_share = if split_total {
+                rounding_mode.apply_ratio(total_royalty, recipient_info.basis_points as u64, total_bps as u64)
+            } else {
+                rounding_mode.apply_bps(sale_price, recipient_info.basis_points)
+            }
+            .ok_or(ErrorCode::Overflow)?
+            .min(total_royalty - total_distributed);
                 
             // If share is zero, skip this recipient
             if recipient_share == 0 {
@@ -147,8 +178,9 @@ impl RoyaltyConfig {
             
             total_distributed = total_distributed.checked_add(recipient_share)
                 .ok_or(ErrorCode::Overflow)?;
+            payouts[i] = recipient_share;
         }
         
-        Ok(total_distributed)
+        Ok(payouts)
     }
 }
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+
+/// Rate for paying SOL royalties in another token, kept at
+/// `[b"royalty_price_feed", payout_mint]`. This is not an external oracle:
+/// the marketplace admin posts the rate, and settlement refuses one older
+/// than `MAX_PRICE_AGE`.
+#[account]
+#[derive(InitSpace)]
+pub struct RoyaltyPriceFeed {
+    pub payout_mint: Pubkey,
+    pub price_per_sol: u64,             // Payout token base units worth 1 SOL
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl RoyaltyPriceFeed {
+    /// Seconds a posted rate stays usable for settlement
+    pub const MAX_PRICE_AGE: i64 = 10 * 60;
+    /// Seed of the PDA that owns the marketplace's royalty payout liquidity
+    pub const LIQUIDITY_AUTHORITY_SEED: &[u8] = b"royalty_liquidity";
+
+    /// Whether the rate was posted recently enough to settle against at `now`
+    pub fn is_fresh(&self, now: i64) -> bool {
+        now.saturating_sub(self.updated_at) <= Self::MAX_PRICE_AGE
+    }
+
+    /// Convert a lamport amount into payout token base units, rounding down
+    pub fn convert_lamports(&self, lamports: u64) -> Option<u64> {
+        let converted = (lamports as u128)
+            .checked_mul(self.price_per_sol as u128)?
+            / LAMPORTS_PER_SOL as u128;
+        u64::try_from(converted).ok()
+    }
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+/// Upper bound on receipts per segment, keeping segment accounts well under the
+/// 10KB a program can allocate in one instruction
+pub const MAX_RECEIPTS_PER_SEGMENT: u16 = 64;
+
+/// One royalty payment to a creator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct RoyaltyReceipt {
+    pub sale: Pubkey,                   // Listing the royalty was paid on
+    pub amount: u64,                    // Royalty in lamports
+    pub timestamp: i64,
+    pub payer: Pubkey,                  // Buyer whose payment funded the royalty
+}
+
+/// A creator's opt-in to royalty receipts, kept at
+/// `[b"royalty_receipt_index", creator]`. Receipts are appended to the segment
+/// at `[b"royalty_receipts", creator, current_segment]` until it holds
+/// `segment_capacity` receipts, after which the next segment is opened.
+#[account]
+#[derive(InitSpace)]
+pub struct RoyaltyReceiptIndex {
+    pub creator: Pubkey,
+    pub segment_capacity: u16,          // Receipts per segment before rotating
+    pub current_segment: u32,           // Segment the next receipt is appended to
+    pub total_receipts: u64,            // Receipts recorded across all segments
+    pub bump: u8,
+}
+
+/// An append-only page of a creator's royalty receipts
+#[account]
+pub struct RoyaltyReceiptSegment {
+    pub creator: Pubkey,
+    pub segment: u32,
+    pub receipts: Vec<RoyaltyReceipt>,
+    pub bump: u8,
+}
+
+impl RoyaltyReceiptSegment {
+    pub const SEED: &[u8] = b"royalty_receipts";
+
+    /// Account space for a segment holding up to `capacity` receipts
+    pub fn space(capacity: u16) -> usize {
+        8 + 32 + 4 + 4 + capacity as usize * RoyaltyReceipt::INIT_SPACE + 1
+    }
+}
+
+impl RoyaltyReceiptIndex {
+    /// Count a receipt appended to the current segment, rotating to the next
+    /// segment once `receipts_in_segment` fills it. Returns the receipt's
+    /// position across all segments.
+    pub fn record(&mut self, receipts_in_segment: usize) -> Option<u64> {
+        let receipt_number = self.total_receipts;
+        self.total_receipts = self.total_receipts.checked_add(1)?;
+        if receipts_in_segment >= self.segment_capacity as usize {
+            self.current_segment = self.current_segment.checked_add(1)?;
+        }
+        Some(receipt_number)
+    }
+}
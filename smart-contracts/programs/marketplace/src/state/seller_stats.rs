@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::errors::MarketplaceError;
+
+/// Per-seller activity counters, kept at `[b"seller_stats", seller]`.
+#[account]
+#[derive(InitSpace)]
+pub struct SellerStats {
+    pub seller: Pubkey,
+    pub active_auctions: u16,           // Auctions listed and not yet settled, voided or canceled
+    pub total_volume: u64,              // Lifetime settled sale volume, used for fee tiers
+    pub bump: u8,
+}
+
+impl SellerStats {
+    /// Count a new auction against the seller, enforcing the marketplace cap (0 = unlimited)
+    pub fn open_auction(&mut self, max_active_auctions: u16) -> Result<()> {
+        require!(
+            max_active_auctions == 0 || self.active_auctions < max_active_auctions,
+            MarketplaceError::TooManyActiveAuctions
+        );
+        self.active_auctions = self
+            .active_auctions
+            .checked_add(1)
+            .ok_or(MarketplaceError::Overflow)?;
+        Ok(())
+    }
+
+    /// Add a settled sale to the seller's lifetime volume
+    pub fn record_sale(&mut self, price: u64) -> Result<()> {
+        self.total_volume = self
+            .total_volume
+            .checked_add(price)
+            .ok_or(MarketplaceError::Overflow)?;
+        Ok(())
+    }
+
+    /// Free the seller's slot once an auction settles, is voided or is canceled
+    pub fn close_auction(&mut self) {
+        self.active_auctions = self.active_auctions.saturating_sub(1);
+    }
+}
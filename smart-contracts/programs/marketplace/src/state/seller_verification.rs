@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::errors::MarketplaceError;
+
+/// Marks a seller as identity-verified for high-value listings. The record
+/// lives at `[b"seller_verification", seller]` and the seller is verified for
+/// as long as it exists.
+#[account]
+#[derive(InitSpace)]
+pub struct SellerVerification {
+    pub seller: Pubkey,
+    pub verified_by: Pubkey,
+    pub verified_at: i64,
+    pub bump: u8,
+}
+
+impl SellerVerification {
+    /// Whether the verification PDA passed to a listing holds a live record.
+    /// Like a fee exemption, a revoked verification is closed back to the
+    /// system program.
+    pub fn is_active(account: &AccountInfo) -> bool {
+        account.owner == &crate::ID && !account.data_is_empty()
+    }
+
+    /// Require a live verification for a listing worth at least the
+    /// marketplace's verified seller threshold (0 disables the check)
+    pub fn require_for_value(account: &AccountInfo, threshold: u64, value: u64) -> Result<()> {
+        if threshold > 0 && value >= threshold {
+            require!(Self::is_active(account), MarketplaceError::SellerVerificationRequired);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::errors::MarketplaceError;
+
+/// Marketplace credit a user chose to take instead of a cash bid refund,
+/// kept at `[b"store_credit", owner]`. The account holds the credited
+/// lamports, which can only leave it to pay for a purchase.
+#[account]
+#[derive(InitSpace)]
+pub struct StoreCredit {
+    pub owner: Pubkey,
+    pub balance: u64,                   // Lamports of credit available to spend
+    pub bump: u8,
+}
+
+impl StoreCredit {
+    pub fn credit(&mut self, amount: u64) -> Result<()> {
+        self.balance = self
+            .balance
+            .checked_add(amount)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Take up to `amount` of credit, returning how much was taken
+    pub fn spend(&mut self, amount: u64) -> u64 {
+        let spent = self.balance.min(amount);
+        self.balance -= spent;
+        spent
+    }
+}
@@ -0,0 +1,12 @@
+//! Metadata URI rules shared by the ticket programs, included into each
+//! program with `#[path]` so the accepted schemes can't drift apart
+
+/// URI schemes accepted for ticket metadata links
+pub const ALLOWED_METADATA_URI_SCHEMES: [&str; 3] = ["https://", "ipfs://", "ar://"];
+
+/// Whether `uri` starts with one of the accepted schemes
+pub fn has_allowed_scheme(uri: &str) -> bool {
+    ALLOWED_METADATA_URI_SCHEMES
+        .iter()
+        .any(|scheme| uri.starts_with(scheme))
+}
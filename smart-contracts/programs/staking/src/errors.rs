@@ -58,4 +58,31 @@ pub enum StakingError {
     
     #[msg("Invalid calculation parameters")]
     InvalidCalculation,
+    
+    #[msg("Reward mint does not match the pool's configured reward mint")]
+    RewardMintMismatch,
+    
+    #[msg("Reward vault mint does not match the pool's configured reward mint")]
+    RewardVaultMintMismatch,
+    
+    #[msg("Pool has reached its maximum total rewards")]
+    RewardCapReached,
+    
+    #[msg("The stake receipt NFT for this position must be presented")]
+    StakeReceiptRequired,
+    
+    #[msg("Batch accounts do not match the pools being funded")]
+    InvalidBatchAccounts,
+    
+    #[msg("Too many pools in one batch")]
+    BatchTooLarge,
+    
+    #[msg("Rewards can only be compounded when they are paid in the staked token")]
+    CompoundMintMismatch,
+    
+    #[msg("Minimum interval since the last compound has not elapsed")]
+    CompoundTooSoon,
+    
+    #[msg("Ticket metadata could not be read")]
+    InvalidTicketMetadata,
 }
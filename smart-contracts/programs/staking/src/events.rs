@@ -50,6 +50,23 @@ pub struct RewardsClaimed {
     pub tier: StakingTier,
 }
 
+#[event]
+pub struct RewardsCompounded {
+    pub user: Pubkey,
+    pub pool_id: u32,
+    pub amount: u64,
+    pub new_total_staked: u64,
+    pub tier: StakingTier,
+}
+
+#[event]
+pub struct TicketBoostApplied {
+    pub user: Pubkey,
+    pub pool_id: u32,
+    pub ticket_mint: Pubkey,
+    pub bonus: u64,
+}
+
 #[event]
 pub struct RewardsDistributed {
     pub pool_id: u32,
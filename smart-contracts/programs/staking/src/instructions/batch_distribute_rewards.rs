@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount, Token};
+
+use crate::state::{StakingProgram, StakePool};
+use crate::errors::StakingError;
+use crate::events::RewardsDistributed;
+
+/// Accounts supplied per pool in `remaining_accounts`:
+/// stake pool, authority reward account, reward vault
+pub const ACCOUNTS_PER_POOL: usize = 3;
+
+/// Maximum number of pools funded in one transaction
+pub const MAX_BATCH_DISTRIBUTE: usize = 8;
+
+#[derive(Accounts)]
+pub struct BatchDistributeRewards<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// The staking program account
+    #[account(
+        seeds = [b"staking_program"],
+        bump = staking_program.bump,
+        constraint = staking_program.authority == authority.key() @ StakingError::InvalidAuthority
+    )]
+    pub staking_program: Account<'info, StakingProgram>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, BatchDistributeRewards<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    require!(
+        !amounts.is_empty() && remaining.len() == amounts.len() * ACCOUNTS_PER_POOL,
+        StakingError::InvalidBatchAccounts
+    );
+    require!(amounts.len() <= MAX_BATCH_DISTRIBUTE, StakingError::BatchTooLarge);
+    
+    let authority = ctx.accounts.authority.key();
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // Any failing pool aborts the whole transaction, so either every pool is
+    // funded or none are
+    for (accounts, &amount) in remaining.chunks(ACCOUNTS_PER_POOL).zip(amounts.iter()) {
+        let pool_info = &accounts[0];
+        let source_info = &accounts[1];
+        let vault_info = &accounts[2];
+        
+        require!(amount > 0, StakingError::InvalidStakeAmount);
+        
+        let mut stake_pool = Account::<StakePool>::try_from(pool_info)?;
+        require!(stake_pool.active, StakingError::StakePoolNotActive);
+        
+        // Same checks the single-pool distribution applies to its accounts;
+        // the source is reloaded per pool so earlier transfers are accounted for
+        let source = Account::<TokenAccount>::try_from(source_info)?;
+        require!(
+            source.mint == stake_pool.reward_token_mint,
+            StakingError::RewardMintMismatch
+        );
+        require_keys_eq!(source.owner, authority, StakingError::InvalidAuthority);
+        require!(source.amount >= amount, StakingError::InsufficientRewards);
+        
+        let vault = Account::<TokenAccount>::try_from(vault_info)?;
+        require!(
+            vault.mint == stake_pool.reward_token_mint,
+            StakingError::RewardVaultMintMismatch
+        );
+        require_keys_eq!(vault_info.key(), stake_pool.reward_vault, StakingError::InvalidBatchAccounts);
+        
+        // Update pool rewards before adding new rewards
+        stake_pool.update_rewards(current_time)?;
+        
+        // Clamp funding to what the pool's emission cap still allows
+        let remaining_funding = stake_pool.remaining_reward_funding();
+        require!(remaining_funding > 0, StakingError::RewardCapReached);
+        let amount = amount.min(remaining_funding);
+        
+        let cpi_accounts = token::Transfer {
+            from: source_info.clone(),
+            to: vault_info.clone(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        
+        stake_pool.available_rewards = stake_pool.available_rewards
+            .checked_add(amount)
+            .unwrap();
+        stake_pool.exit(ctx.program_id)?;
+        
+        emit!(RewardsDistributed {
+            pool_id: stake_pool.pool_id,
+            amount,
+            distributor: authority,
+        });
+    }
+    
+    msg!("Distributed rewards to {} pools", amounts.len());
+    
+    Ok(())
+}
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, TokenAccount, Token};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
 
-use crate::state::{StakingProgram, StakePool, UserStake};
+use crate::state::{StakingProgram, StakePool, TicketBoost, UserStake};
 use crate::errors::StakingError;
+use crate::events::TicketBoostApplied;
 
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
@@ -35,7 +37,7 @@ pub struct ClaimRewards<'info> {
     /// User's token account (destination for reward tokens)
     #[account(
         mut,
-        constraint = user_reward_account.mint == stake_pool.reward_token_mint,
+        constraint = user_reward_account.mint == stake_pool.reward_token_mint @ StakingError::RewardMintMismatch,
         constraint = user_reward_account.owner == user.key()
     )]
     pub user_reward_account: Account<'info, TokenAccount>,
@@ -43,6 +45,7 @@ pub struct ClaimRewards<'info> {
     /// Vault holding reward tokens
     #[account(
         mut,
+        constraint = reward_vault.mint == stake_pool.reward_token_mint @ StakingError::RewardVaultMintMismatch,
         constraint = reward_vault.key() == stake_pool.reward_vault
     )]
     pub reward_vault: Account<'info, TokenAccount>,
@@ -50,7 +53,11 @@ pub struct ClaimRewards<'info> {
     pub token_program: Program<'info, Token>,
 }
 
-pub fn handler(ctx: Context<ClaimRewards>) -> Result<()> {
+/// If the pool has a ticket boost, the staker's token account for a ticket
+/// in the qualifying collection may be passed as the first remaining account,
+/// followed by that ticket's Metaplex metadata. The boost applies only while
+/// that account still holds the ticket.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ClaimRewards<'info>>) -> Result<()> {
     let staking_program = &ctx.accounts.staking_program;
     let stake_pool = &mut ctx.accounts.stake_pool;
     let user_stake = &mut ctx.accounts.user_stake;
@@ -69,11 +76,35 @@ pub fn handler(ctx: Context<ClaimRewards>) -> Result<()> {
     stake_pool.update_rewards(current_time)?;
     
     // Calculate total rewards
-    let pending_rewards = user_stake.calculate_pending_rewards(stake_pool.accumulated_reward_per_token)?;
+    let mut pending_rewards = user_stake.calculate_pending_rewards(stake_pool.accumulated_reward_per_token)?;
+    
+    // Boost rewards earned since the last claim for holders of a qualifying ticket
+    if let Some(boost) = stake_pool.config.ticket_boost {
+        if let [ticket_info, metadata_info, ..] = ctx.remaining_accounts {
+            if let Some(ticket_mint) = qualifying_ticket_mint(&boost, &ctx.accounts.user.key(), ticket_info, metadata_info)? {
+                let earned_rewards = pending_rewards.saturating_sub(user_stake.pending_rewards);
+                let bonus = boost.bonus(earned_rewards);
+                pending_rewards = pending_rewards
+                    .checked_add(bonus)
+                    .ok_or(StakingError::MathOverflow)?;
+                
+                emit!(TicketBoostApplied {
+                    user: ctx.accounts.user.key(),
+                    pool_id: stake_pool.pool_id,
+                    ticket_mint,
+                    bonus,
+                });
+            }
+        }
+    }
     
     require!(pending_rewards > 0, StakingError::NoRewardsToClaim);
+    
+    // Pay out no more than the pool's emission cap allows; the rest stays pending
+    let claimable_rewards = pending_rewards.min(stake_pool.remaining_reward_payout());
+    require!(claimable_rewards > 0, StakingError::RewardCapReached);
     require!(
-        stake_pool.available_rewards >= pending_rewards,
+        stake_pool.available_rewards >= claimable_rewards,
         StakingError::InsufficientRewards
     );
     
@@ -96,29 +127,67 @@ pub fn handler(ctx: Context<ClaimRewards>) -> Result<()> {
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-    token::transfer(cpi_ctx, pending_rewards)?;
+    token::transfer(cpi_ctx, claimable_rewards)?;
     
     // Update state
-    user_stake.pending_rewards = 0;
+    user_stake.pending_rewards = pending_rewards - claimable_rewards;
     user_stake.total_rewards_claimed = user_stake.total_rewards_claimed
-        .checked_add(pending_rewards)
+        .checked_add(claimable_rewards)
         .unwrap();
     user_stake.last_reward_time = current_time;
     user_stake.reward_per_token_paid = stake_pool.accumulated_reward_per_token;
     
     stake_pool.available_rewards = stake_pool.available_rewards
-        .checked_sub(pending_rewards)
+        .checked_sub(claimable_rewards)
         .unwrap();
     stake_pool.total_rewards_distributed = stake_pool.total_rewards_distributed
-        .checked_add(pending_rewards)
+        .checked_add(claimable_rewards)
         .unwrap();
     
     msg!(
         "User {} claimed {} rewards from pool {}",
         ctx.accounts.user.key(),
-        pending_rewards,
+        claimable_rewards,
         stake_pool.pool_id
     );
     
     Ok(())
 }
+
+/// The mint of the ticket held in `ticket_info` if it belongs to the staker,
+/// still holds the ticket, and `metadata_info` is that ticket's Metaplex
+/// metadata placing it in the boost's verified collection
+fn qualifying_ticket_mint(
+    boost: &TicketBoost,
+    user: &Pubkey,
+    ticket_info: &AccountInfo,
+    metadata_info: &AccountInfo,
+) -> Result<Option<Pubkey>> {
+    let ticket_account = Account::<TokenAccount>::try_from(ticket_info)?;
+    if ticket_account.owner != *user || ticket_account.amount == 0 {
+        return Ok(None);
+    }
+    
+    let (expected_metadata, _) = Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), ticket_account.mint.as_ref()],
+        &mpl_token_metadata::ID,
+    );
+    if metadata_info.key() != expected_metadata
+        || metadata_info.owner != &mpl_token_metadata::ID
+        || metadata_info.data_is_empty()
+    {
+        return Ok(None);
+    }
+    
+    let metadata = Metadata::from_account_info(metadata_info)
+        .map_err(|_| error!(StakingError::InvalidTicketMetadata))?;
+    let in_collection = metadata
+        .collection
+        .is_some_and(|collection| collection.verified && collection.key == boost.qualifying_collection);
+    
+    if in_collection {
+        Ok(Some(ticket_account.mint))
+    } else {
+        Ok(None)
+    }
+}
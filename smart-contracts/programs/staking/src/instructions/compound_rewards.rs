@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount, Token};
+
+use crate::state::{StakingProgram, StakePool, UserStake};
+use crate::errors::StakingError;
+use crate::events::RewardsCompounded;
+
+#[derive(Accounts)]
+pub struct CompoundRewards<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// The staking program account
+    #[account(
+        seeds = [b"staking_program"],
+        bump = staking_program.bump,
+        constraint = !staking_program.config.paused @ StakingError::StakingPaused
+    )]
+    pub staking_program: Account<'info, StakingProgram>,
+    
+    /// The stake pool
+    #[account(
+        mut,
+        constraint = stake_pool.active @ StakingError::StakePoolNotActive,
+        constraint = stake_pool.reward_token_mint == stake_pool.stake_token_mint @ StakingError::CompoundMintMismatch
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// User's stake account
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.user == user.key()
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// Vault holding staked tokens (destination for compounded rewards)
+    #[account(
+        mut,
+        constraint = stake_vault.key() == stake_pool.stake_vault
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    /// Vault holding reward tokens
+    #[account(
+        mut,
+        constraint = reward_vault.mint == stake_pool.reward_token_mint @ StakingError::RewardVaultMintMismatch,
+        constraint = reward_vault.key() == stake_pool.reward_vault
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Moves the user's pending rewards from the reward vault into their stake.
+/// Compounds are spaced by the pool's minimum compound interval so they
+/// can't be spammed. The ticket boost only applies to claims.
+pub fn handler(ctx: Context<CompoundRewards>) -> Result<()> {
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+    
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+    
+    require!(
+        user_stake.can_compound(current_time, stake_pool.config.min_compound_interval),
+        StakingError::CompoundTooSoon
+    );
+    
+    // Settle rewards at the old stake before it grows
+    stake_pool.update_rewards(current_time)?;
+    let pending_rewards = user_stake.calculate_pending_rewards(stake_pool.accumulated_reward_per_token)?;
+    require!(pending_rewards > 0, StakingError::NoRewardsToClaim);
+    
+    // Compound no more than the pool's emission cap allows; the rest stays pending
+    let compound_amount = pending_rewards.min(stake_pool.remaining_reward_payout());
+    require!(compound_amount > 0, StakingError::RewardCapReached);
+    require!(
+        stake_pool.available_rewards >= compound_amount,
+        StakingError::InsufficientRewards
+    );
+    
+    let new_total_staked = user_stake.staked_amount
+        .checked_add(compound_amount)
+        .ok_or(StakingError::MathOverflow)?;
+    require!(
+        new_total_staked <= stake_pool.config.max_stake_amount
+            && stake_pool.has_capacity(compound_amount),
+        StakingError::MaximumStakeExceeded
+    );
+    
+    // Create PDA signer for stake pool authority
+    let staking_program_key = stake_pool.staking_program;
+    let pool_id_bytes = stake_pool.pool_id.to_le_bytes();
+    let seeds = &[
+        b"stake_pool",
+        staking_program_key.as_ref(),
+        &pool_id_bytes,
+        &[stake_pool.bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    // Move the rewards into the stake vault
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: stake_pool.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer(cpi_ctx, compound_amount)?;
+    
+    // Update user stake; compounding doesn't restart the minimum staking period
+    user_stake.staked_amount = new_total_staked;
+    user_stake.pending_rewards = pending_rewards - compound_amount;
+    user_stake.total_rewards_claimed = user_stake.total_rewards_claimed
+        .checked_add(compound_amount)
+        .ok_or(StakingError::MathOverflow)?;
+    user_stake.reward_per_token_paid = stake_pool.accumulated_reward_per_token;
+    user_stake.last_compound_at = current_time;
+    user_stake.update_tier();
+    
+    // Update pool totals
+    stake_pool.total_staked = stake_pool.total_staked
+        .checked_add(compound_amount)
+        .ok_or(StakingError::MathOverflow)?;
+    stake_pool.available_rewards = stake_pool.available_rewards
+        .checked_sub(compound_amount)
+        .ok_or(StakingError::MathOverflow)?;
+    stake_pool.total_rewards_distributed = stake_pool.total_rewards_distributed
+        .checked_add(compound_amount)
+        .ok_or(StakingError::MathOverflow)?;
+    
+    emit!(RewardsCompounded {
+        user: ctx.accounts.user.key(),
+        pool_id: stake_pool.pool_id,
+        amount: compound_amount,
+        new_total_staked,
+        tier: user_stake.staking_tier,
+    });
+    
+    msg!(
+        "User {} compounded {} rewards in pool {}. New balance: {}",
+        ctx.accounts.user.key(),
+        compound_amount,
+        stake_pool.pool_id,
+        new_total_staked
+    );
+    
+    Ok(())
+}
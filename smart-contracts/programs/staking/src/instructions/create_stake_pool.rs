@@ -84,6 +84,14 @@ pub fn handler(
         pool_config.min_stake_amount <= pool_config.max_stake_amount,
         StakingError::InvalidStakePoolConfig
     );
+    require!(
+        pool_config.ticket_boost.is_none_or(|boost| boost.bonus_bps <= 10000), // Max +100%
+        StakingError::InvalidStakePoolConfig
+    );
+    require!(
+        pool_config.min_compound_interval >= 0,
+        StakingError::InvalidStakePoolConfig
+    );
     
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
@@ -28,7 +28,7 @@ pub struct DistributeRewards<'info> {
     /// Authority's token account (source of reward tokens)
     #[account(
         mut,
-        constraint = authority_reward_account.mint == stake_pool.reward_token_mint,
+        constraint = authority_reward_account.mint == stake_pool.reward_token_mint @ StakingError::RewardMintMismatch,
         constraint = authority_reward_account.owner == authority.key(),
         constraint = authority_reward_account.amount >= amount @ StakingError::InsufficientRewards
     )]
@@ -37,6 +37,7 @@ pub struct DistributeRewards<'info> {
     /// Vault to receive reward tokens
     #[account(
         mut,
+        constraint = reward_vault.mint == stake_pool.reward_token_mint @ StakingError::RewardVaultMintMismatch,
         constraint = reward_vault.key() == stake_pool.reward_vault
     )]
     pub reward_vault: Account<'info, TokenAccount>,
@@ -55,6 +56,11 @@ pub fn handler(ctx: Context<DistributeRewards>, amount: u64) -> Result<()> {
     // Update pool rewards before adding new rewards
     stake_pool.update_rewards(current_time)?;
     
+    // Clamp funding to what the pool's emission cap still allows
+    let remaining_funding = stake_pool.remaining_reward_funding();
+    require!(remaining_funding > 0, StakingError::RewardCapReached);
+    let amount = amount.min(remaining_funding);
+    
     // Transfer reward tokens from authority to reward vault
     let cpi_accounts = token::Transfer {
         from: ctx.accounts.authority_reward_account.to_account_info(),
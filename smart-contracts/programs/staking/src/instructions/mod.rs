@@ -7,6 +7,8 @@ pub mod claim_rewards;
 pub mod distribute_rewards;
 pub mod update_stake_pool;
 pub mod emergency_pause;
+pub mod batch_distribute_rewards;
+pub mod compound_rewards;
 
 pub use initialize_staking::*;
 pub use create_stake_pool::*;
@@ -17,3 +19,5 @@ pub use claim_rewards::*;
 pub use distribute_rewards::*;
 pub use update_stake_pool::*;
 pub use emergency_pause::*;
+pub use batch_distribute_rewards::*;
+pub use compound_rewards::*;
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, TokenAccount, Mint, Token};
+use anchor_spl::associated_token::AssociatedToken;
 
 use crate::state::{StakingProgram, StakePool, UserStake, StakingTier};
 use crate::errors::StakingError;
@@ -53,8 +54,31 @@ pub struct StakeTokens<'info> {
     )]
     pub user_stake: Account<'info, UserStake>,
     
+    /// Receipt NFT mint, derived from the user's stake account
+    #[account(
+        init_if_needed,
+        payer = user,
+        mint::decimals = 0,
+        mint::authority = stake_pool,
+        mint::freeze_authority = stake_pool,
+        seeds = [b"stake_receipt", user_stake.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    
+    /// User's token account for the receipt NFT
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+    
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn handler(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
@@ -87,6 +111,8 @@ pub fn handler(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
         user_stake.reward_per_token_paid = stake_pool.accumulated_reward_per_token;
         user_stake.unstake_request = None;
         user_stake.staking_tier = StakingTier::Bronze;
+        user_stake.receipt_mint = None;
+        user_stake.last_compound_at = 0;
         user_stake.bump = *ctx.bumps.get("user_stake").unwrap();
         
         // Increment staker count for new stakers
@@ -115,8 +141,20 @@ pub fn handler(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
     token::transfer(cpi_ctx, amount)?;
     
+    // Opening a position issues its receipt NFT; top-ups keep the existing one
+    if user_stake.receipt_mint.is_none() {
+        mint_stake_receipt(
+            stake_pool,
+            &ctx.accounts.receipt_mint,
+            &ctx.accounts.receipt_token_account,
+            &ctx.accounts.token_program,
+        )?;
+        user_stake.receipt_mint = Some(ctx.accounts.receipt_mint.key());
+    }
+    
     // Update user stake
     user_stake.staked_amount = new_total_staked;
+    // Every stake restarts the minimum staking period
     user_stake.last_stake_time = current_time;
     user_stake.update_tier();
     
@@ -133,3 +171,42 @@ pub fn handler(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
     
     Ok(())
 }
+
+/// Mints the receipt NFT for a new position into the staker's account,
+/// freezing it there when the pool issues soulbound receipts
+fn mint_stake_receipt<'info>(
+    stake_pool: &Account<'info, StakePool>,
+    receipt_mint: &Account<'info, Mint>,
+    receipt_token_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let staking_program_key = stake_pool.staking_program;
+    let pool_id_bytes = stake_pool.pool_id.to_le_bytes();
+    let seeds = &[
+        b"stake_pool",
+        staking_program_key.as_ref(),
+        &pool_id_bytes,
+        &[stake_pool.bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    let cpi_accounts = token::MintTo {
+        mint: receipt_mint.to_account_info(),
+        to: receipt_token_account.to_account_info(),
+        authority: stake_pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::mint_to(cpi_ctx, 1)?;
+    
+    if stake_pool.config.soulbound_receipts {
+        let cpi_accounts = token::FreezeAccount {
+            account: receipt_token_account.to_account_info(),
+            mint: receipt_mint.to_account_info(),
+            authority: stake_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+        token::freeze_account(cpi_ctx)?;
+    }
+    
+    Ok(())
+}
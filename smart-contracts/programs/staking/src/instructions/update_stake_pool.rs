@@ -37,6 +37,14 @@ pub fn handler(ctx: Context<UpdateStakePool>, new_config: StakePoolConfig) -> Re
         new_config.min_stake_amount <= new_config.max_stake_amount,
         StakingError::InvalidStakePoolConfig
     );
+    require!(
+        new_config.ticket_boost.is_none_or(|boost| boost.bonus_bps <= 10000), // Max +100%
+        StakingError::InvalidStakePoolConfig
+    );
+    require!(
+        new_config.min_compound_interval >= 0,
+        StakingError::InvalidStakePoolConfig
+    );
     
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
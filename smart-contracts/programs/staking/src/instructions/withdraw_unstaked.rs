@@ -1,11 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, TokenAccount, Token};
+use anchor_spl::token::{self, TokenAccount, Mint, Token};
 
 use crate::state::{StakePool, UserStake};
 use crate::errors::StakingError;
 
 #[derive(Accounts)]
 pub struct WithdrawUnstaked<'info> {
+    /// The receipt holder, or the staker for positions without a receipt
     #[account(mut)]
     pub user: Signer<'info>,
     
@@ -19,9 +20,8 @@ pub struct WithdrawUnstaked<'info> {
     /// User's stake account
     #[account(
         mut,
-        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
-        bump = user_stake.bump,
-        constraint = user_stake.user == user.key()
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user_stake.user.as_ref()],
+        bump = user_stake.bump
     )]
     pub user_stake: Account<'info, UserStake>,
     
@@ -40,6 +40,18 @@ pub struct WithdrawUnstaked<'info> {
     )]
     pub stake_vault: Account<'info, TokenAccount>,
     
+    /// Receipt NFT mint for the position, required when one was issued
+    #[account(
+        mut,
+        seeds = [b"stake_receipt", user_stake.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Option<Account<'info, Mint>>,
+    
+    /// Withdrawer's token account holding the receipt NFT
+    #[account(mut)]
+    pub receipt_token_account: Option<Account<'info, TokenAccount>>,
+    
     pub token_program: Program<'info, Token>,
 }
 
@@ -75,6 +87,56 @@ pub fn handler(ctx: Context<WithdrawUnstaked>) -> Result<()> {
     ];
     let signer = &[&seeds[..]];
     
+    // Positions that were issued a receipt can only be withdrawn by its holder,
+    // who need not be the original staker, and the receipt is burned once the
+    // position is fully closed
+    if let Some(receipt_mint_key) = user_stake.receipt_mint {
+        let (receipt_mint, receipt_token_account) = match (
+            &ctx.accounts.receipt_mint,
+            &ctx.accounts.receipt_token_account,
+        ) {
+            (Some(receipt_mint), Some(receipt_token_account)) => (receipt_mint, receipt_token_account),
+            _ => return err!(StakingError::StakeReceiptRequired),
+        };
+        require!(
+            receipt_mint.key() == receipt_mint_key
+                && receipt_token_account.mint == receipt_mint_key
+                && receipt_token_account.owner == ctx.accounts.user.key()
+                && receipt_token_account.amount == 1,
+            StakingError::StakeReceiptRequired
+        );
+        
+        if user_stake.staked_amount == 0 {
+            // Soulbound receipts are frozen and must be thawed before burning
+            if receipt_token_account.is_frozen() {
+                let cpi_accounts = token::ThawAccount {
+                    account: receipt_token_account.to_account_info(),
+                    mint: receipt_mint.to_account_info(),
+                    authority: ctx.accounts.stake_pool.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token::thaw_account(cpi_ctx)?;
+            }
+            
+            let cpi_accounts = token::Burn {
+                mint: receipt_mint.to_account_info(),
+                from: receipt_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token::burn(cpi_ctx, 1)?;
+            
+            user_stake.receipt_mint = None;
+        }
+    } else {
+        require!(
+            user_stake.user == ctx.accounts.user.key(),
+            StakingError::InvalidAuthority
+        );
+    }
+    
     // Transfer tokens from stake vault to user
     let cpi_accounts = token::Transfer {
         from: ctx.accounts.stake_vault.to_account_info(),
@@ -4,6 +4,7 @@ use anchor_spl::token::{Token, TokenAccount, Mint};
 pub mod instructions;
 pub mod state;
 pub mod errors;
+pub mod events;
 
 use instructions::*;
 use state::*;
@@ -56,12 +57,19 @@ pub mod ticket_staking {
     }
 
     /// Claim accumulated rewards
-    pub fn claim_rewards(
-        ctx: Context<ClaimRewards>,
+    pub fn claim_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimRewards<'info>>,
     ) -> Result<()> {
         instructions::claim_rewards::handler(ctx)
     }
 
+    /// Restake accumulated rewards into the user's position
+    pub fn compound_rewards(
+        ctx: Context<CompoundRewards>,
+    ) -> Result<()> {
+        instructions::compound_rewards::handler(ctx)
+    }
+
     /// Distribute rewards to a stake pool (admin only)
     pub fn distribute_rewards(
         ctx: Context<DistributeRewards>,
@@ -70,6 +78,14 @@ pub mod ticket_staking {
         instructions::distribute_rewards::handler(ctx, amount)
     }
 
+    /// Distribute rewards to several stake pools at once, all or nothing (admin only)
+    pub fn batch_distribute_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchDistributeRewards<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::batch_distribute_rewards::handler(ctx, amounts)
+    }
+
     /// Update stake pool configuration (admin only)
     pub fn update_stake_pool(
         ctx: Context<UpdateStakePool>,
@@ -25,6 +25,35 @@ pub struct StakePoolConfig {
     
     /// Whether staking tiers provide bonus rewards
     pub tier_bonus_enabled: bool,
+    
+    /// Maximum rewards this pool will ever emit (0 = unlimited)
+    pub max_total_rewards: u64,
+    
+    /// Whether stake receipt NFTs are frozen in the staker's wallet (soulbound)
+    /// rather than freely transferable
+    pub soulbound_receipts: bool,
+    
+    /// Reward bonus for stakers holding a qualifying event ticket (None = no boost)
+    pub ticket_boost: Option<TicketBoost>,
+    
+    /// Minimum seconds between a staker's reward compounds (0 = no limit)
+    pub min_compound_interval: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TicketBoost {
+    /// Verified Metaplex collection the staker's ticket NFT must belong to
+    pub qualifying_collection: Pubkey,
+    
+    /// Bonus on newly earned rewards in basis points (e.g., 2500 = +25%)
+    pub bonus_bps: u16,
+}
+
+impl TicketBoost {
+    /// Extra rewards granted on top of `earned_rewards`
+    pub fn bonus(&self, earned_rewards: u64) -> u64 {
+        ((earned_rewards as u128) * (self.bonus_bps as u128) / 10000) as u64
+    }
 }
 
 impl Default for StakePoolConfig {
@@ -38,6 +67,10 @@ impl Default for StakePoolConfig {
             accepting_stakes: true,
             pool_capacity: 0, // Unlimited
             tier_bonus_enabled: true,
+            max_total_rewards: 0, // Unlimited
+            soulbound_receipts: false, // Receipts can be transferred or used as collateral
+            ticket_boost: None, // No ticket holder bonus
+            min_compound_interval: 60 * 60, // 1 hour
         }
     }
 }
@@ -120,7 +153,7 @@ impl StakePool {
     pub const LEN: usize = 8 + // discriminator
         32 + // staking_program
         4 + // pool_id
-        (2 + 8 + 8 + 9 + 8 + 1 + 8 + 1) + // config
+        (2 + 8 + 8 + 9 + 8 + 1 + 8 + 1 + 8 + 1 + (1 + 32 + 2) + 8) + // config
         1 + // pool_type
         32 + // stake_token_mint
         32 + // reward_token_mint
@@ -177,6 +210,26 @@ impl StakePool {
         Ok(())
     }
     
+    /// Rewards that can still be funded before the emission cap is reached
+    pub fn remaining_reward_funding(&self) -> u64 {
+        if self.config.max_total_rewards == 0 {
+            return u64::MAX; // Unlimited emissions
+        }
+        
+        self.config.max_total_rewards
+            .saturating_sub(self.total_rewards_distributed)
+            .saturating_sub(self.available_rewards)
+    }
+    
+    /// Rewards that can still be paid out before the emission cap is reached
+    pub fn remaining_reward_payout(&self) -> u64 {
+        if self.config.max_total_rewards == 0 {
+            return u64::MAX; // Unlimited emissions
+        }
+        
+        self.config.max_total_rewards.saturating_sub(self.total_rewards_distributed)
+    }
+    
     /// Check if the pool has capacity for additional stakes
     pub fn has_capacity(&self, additional_amount: u64) -> bool {
         if self.config.pool_capacity == 0 {
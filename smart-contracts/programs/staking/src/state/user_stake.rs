@@ -27,7 +27,7 @@ pub struct UserStake {
     /// When the user first staked in this pool
     pub initial_stake_time: i64,
     
-    /// Last time the user staked additional tokens
+    /// Last time the user staked additional tokens; starts the current minimum staking period
     pub last_stake_time: i64,
     
     /// Last time rewards were claimed or calculated
@@ -48,6 +48,12 @@ pub struct UserStake {
     /// Current staking tier based on staked amount
     pub staking_tier: StakingTier,
     
+    /// Mint of the receipt NFT representing this position, while one is outstanding
+    pub receipt_mint: Option<Pubkey>,
+    
+    /// Last time rewards were compounded into the stake (0 = never)
+    pub last_compound_at: i64,
+    
     /// Bump seed for PDA
     pub bump: u8,
 }
@@ -65,6 +71,8 @@ impl UserStake {
         16 + // reward_per_token_paid
         (1 + 8 + 8 + 8) + // unstake_request (Option<UnstakeRequest>)
         1 + // staking_tier
+        (1 + 32) + // receipt_mint
+        8 + // last_compound_at
         1; // bump
     
     /// Calculate pending rewards for this user
@@ -103,7 +111,13 @@ impl UserStake {
     
     /// Check if user can unstake (minimum staking duration met)
     pub fn can_unstake(&self, current_time: i64, min_duration: i64) -> bool {
-        current_time >= self.last_stake_time + min_duration
+        current_time >= self.last_stake_time.saturating_add(min_duration)
+    }
+    
+    /// Check if the pool's minimum interval since the last compound has elapsed
+    pub fn can_compound(&self, current_time: i64, min_interval: i64) -> bool {
+        self.last_compound_at == 0
+            || current_time >= self.last_compound_at.saturating_add(min_interval)
     }
     
     /// Check if unstaked tokens can be withdrawn
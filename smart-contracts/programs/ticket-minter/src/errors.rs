@@ -149,5 +149,133 @@ pub enum TicketError {
     
     /// Listing already exists
     #[msg("A transfer listing already exists for this ticket")]
-    ListingAlreadyExists
+    ListingAlreadyExists,
+    
+    /// Recipient is not in the event's KYC registry
+    #[msg("Recipient wallet is not KYC-approved for this event")]
+    RecipientNotKycApproved,
+    
+    /// Wallet is already in the KYC registry
+    #[msg("Wallet is already KYC-approved")]
+    KycWalletAlreadyApproved,
+    
+    /// Wallet is not in the KYC registry
+    #[msg("Wallet is not in the KYC registry")]
+    KycWalletNotFound,
+    
+    /// KYC registry is full
+    #[msg("KYC registry has reached maximum capacity")]
+    KycRegistryFull,
+    
+    /// Transfers are frozen around the event
+    #[msg("Ticket transfers are frozen around the event time")]
+    TransfersFrozen,
+    
+    /// Batch mint quantity out of range
+    #[msg("Mint quantity must be between 1 and the per-transaction maximum")]
+    InvalidMintQuantity,
+    
+    /// Batch mint accounts don't match the quantity
+    #[msg("Remaining accounts do not match the tickets being minted")]
+    InvalidBatchMintAccounts,
+    
+    /// Wallet would exceed the ticket type's cap
+    #[msg("Wallet has reached the mint limit for this ticket type")]
+    WalletMintLimitExceeded,
+    
+    /// Capped ticket types are only sold through mint_tickets
+    #[msg("Ticket types with a per-wallet cap must be minted with mint_tickets")]
+    WalletCapRequiresBatchMint,
+    
+    /// Supplied hash doesn't match the event's details
+    #[msg("Event details do not match the supplied hash")]
+    EventDetailsMismatch,
+    
+    /// Too many accounts passed to a batch operation
+    #[msg("Batch operation limit exceeded")]
+    BatchOperationLimitExceeded,
+    
+    /// Primary payee shares must add up to 10000 bps
+    #[msg("Invalid primary payee configuration")]
+    InvalidPrimaryPayees,
+    
+    /// Payee accounts don't match the event's primary payees
+    #[msg("Primary payee accounts do not match the event")]
+    PrimaryPayeeMismatch,
+    
+    /// Ticket type is in presale and no code was given
+    #[msg("Presale access required")]
+    PresaleAccessRequired,
+    
+    /// Presale code doesn't match the ticket type's
+    #[msg("Invalid presale code")]
+    InvalidPresaleCode,
+    
+    /// Schema version is outside the versions this program supports
+    #[msg("Invalid ticket metadata schema version")]
+    InvalidSchemaVersion,
+    
+    /// Ticket must be migrated to its event's metadata schema first
+    #[msg("Ticket metadata schema is outdated")]
+    TicketSchemaOutdated,
+    
+    /// Challenge is still within its validity window
+    #[msg("Verification challenge has not expired")]
+    ChallengeNotExpired,
+    
+    /// Ticket has been resold as many times as its event allows
+    #[msg("Ticket resale limit reached")]
+    ResaleLimitReached,
+    
+    /// Organizer buybacks are disabled or outside their window
+    #[msg("Buyback is not open for this event")]
+    BuybackNotOpen,
+    
+    /// Ticket is missing a name, a valid metadata URI or its ticket type
+    #[msg("Ticket metadata is incomplete")]
+    IncompleteMetadata,
+    
+    /// Ticket wasn't distributed by the organizer or the clawback window has passed
+    #[msg("Clawback window has closed")]
+    ClawbackWindowClosed,
+    
+    /// The organizer has not opened refunds for this event
+    #[msg("Refunds are not open for this event")]
+    RefundsNotOpen,
+    
+    /// Check-in count would overflow
+    #[msg("Event check-in count overflow")]
+    CheckInCountOverflow,
+    
+    /// Dutch auction ending price floor is above 100% of the start price
+    #[msg("Minimum ending price cannot exceed the start price")]
+    InvalidMinEndingPrice,
+    
+    /// Transfer freeze offsets must not be negative
+    #[msg("Invalid transfer freeze window")]
+    InvalidTransferFreeze,
+    
+    /// Flip royalty is above 100% or its decay window is negative
+    #[msg("Invalid flip royalty schedule")]
+    InvalidFlipRoyalty,
+    
+    /// Batch size must be at least one
+    #[msg("Invalid max batch size")]
+    InvalidMaxBatchSize,
+    
+    /// A presale code needs a public sale start
+    #[msg("Presale code requires a public sale start time")]
+    InvalidPresaleConfig,
+    
+    /// Challenge lifetime must be positive
+    #[msg("Invalid verification challenge TTL")]
+    InvalidChallengeTtl,
+    
+    /// Listing price cap is below the event's price floor
+    #[msg("Maximum listing price is below the minimum")]
+    InvalidListingPriceCap,
+    
+    /// Clawback window must not be negative
+    #[msg("Invalid distribution clawback window")]
+    InvalidClawbackWindow
 }
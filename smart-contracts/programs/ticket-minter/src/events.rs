@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{TicketStatus, TicketAttribute};
+use crate::state::{TicketStatus, TicketAttribute, EndingPriceFloor, FlipRoyaltySchedule, PrimaryPayee};
 
 /// Event emitted when a new event is created
 #[event]
@@ -21,9 +21,199 @@ pub struct EventUpdated {
     pub event: Pubkey,
     pub organizer: Pubkey,
     pub name: String,
+    pub previous_details_hash: [u8; 32],
+    pub details_hash: [u8; 32],
     pub updated_at: i64,
 }
 
+/// Event emitted when an event is postponed to new dates
+#[event]
+pub struct EventPostponed {
+    #[index]
+    pub event: Pubkey,
+    pub original_start_date: i64,
+    pub original_end_date: i64,
+    pub new_start_date: i64,
+    pub new_end_date: i64,
+    pub refunds_open: bool,
+    pub details_hash: [u8; 32],
+    pub postponed_by: Pubkey,
+    pub postponed_at: i64,
+}
+
+/// Event emitted when an event's relist cooldown is changed
+#[event]
+pub struct RelistCooldownUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub relist_cooldown: i64,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's max batch size is changed
+#[event]
+pub struct MaxBatchSizeUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub max_batch_size: u16,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's ticket metadata schema version is changed
+#[event]
+pub struct TicketSchemaVersionUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub ticket_schema_version: u8,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's verification challenge lifetime is changed
+#[event]
+pub struct ChallengeTtlUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub challenge_ttl: i64,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's per-ticket resale cap is changed
+#[event]
+pub struct MaxResalesUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub max_resales: u16,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's organizer buyback price or window is changed
+#[event]
+pub struct BuybackConfigUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub buyback_price: u64,
+    pub buyback_window: i64,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's distribution clawback window is changed
+#[event]
+pub struct DistributionClawbackWindowUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub distribution_clawback_window: i64,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's primary-sale split is changed
+#[event]
+pub struct PrimaryPayeesUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub primary_payees: Vec<PrimaryPayee>,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's minimum listing price is changed
+#[event]
+pub struct MinListingPriceUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub min_listing_price: u64,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's resale price cap or price-increase rule is changed
+#[event]
+pub struct ListingPricePolicyUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub max_listing_price: u64,
+    pub allow_price_increase: bool,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's complete metadata requirement is changed
+#[event]
+pub struct MetadataRequirementUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub require_complete_metadata: bool,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's minimum Dutch auction ending price is changed
+#[event]
+pub struct MinEndingPriceUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub min_ending_price: EndingPriceFloor,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's flip royalty schedule is changed
+#[event]
+pub struct FlipRoyaltyUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub flip_royalty: FlipRoyaltySchedule,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's transfer freeze window is changed
+#[event]
+pub struct TransferFreezeUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub freeze_before: i64,
+    pub freeze_after: i64,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when an event's KYC requirement is changed
+#[event]
+pub struct KycRequirementUpdated {
+    #[index]
+    pub event: Pubkey,
+    pub kyc_required: bool,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
+
+/// Event emitted when a wallet is added to an event's KYC registry
+#[event]
+pub struct KycWalletAdded {
+    #[index]
+    pub event: Pubkey,
+    pub wallet: Pubkey,
+    pub added_by: Pubkey,
+    pub added_at: i64,
+}
+
+/// Event emitted when a wallet is removed from an event's KYC registry
+#[event]
+pub struct KycWalletRemoved {
+    #[index]
+    pub event: Pubkey,
+    pub wallet: Pubkey,
+    pub removed_by: Pubkey,
+    pub removed_at: i64,
+}
+
 /// Event emitted when a ticket type is created
 #[event]
 pub struct TicketTypeCreated {
@@ -63,6 +253,37 @@ pub struct TicketMinted {
     pub price: u64,
 }
 
+/// Event emitted when several tickets are minted in one purchase
+#[event]
+pub struct TicketsMinted {
+    #[index]
+    pub event: Pubkey,
+    pub ticket_type: Pubkey,
+    pub owner: Pubkey,
+    pub quantity: u8,
+    pub first_serial_number: u32,
+    pub price: u64,
+}
+
+/// Event emitted when a ticket type's per-wallet cap is changed
+#[event]
+pub struct MaxPerWalletUpdated {
+    #[index]
+    pub ticket_type: Pubkey,
+    pub max_per_wallet: u32,
+    pub updated_by: Pubkey,
+}
+
+/// Event emitted when a ticket type's presale is configured
+#[event]
+pub struct PresaleCodeUpdated {
+    #[index]
+    pub ticket_type: Pubkey,
+    pub presale_enabled: bool,
+    pub public_sale_start: i64,
+    pub updated_by: Pubkey,
+}
+
 /// Event emitted when a ticket status is updated
 #[event]
 pub struct TicketStatusUpdated {
@@ -88,6 +309,17 @@ pub struct TicketTransferred {
     pub transferred_at: i64,
 }
 
+/// Event emitted when a ticket's metadata is migrated to a newer schema
+#[event]
+pub struct TicketMetadataMigrated {
+    #[index]
+    pub ticket: Pubkey,
+    pub event: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub migrated_at: i64,
+}
+
 /// Event emitted when a ticket is revoked
 #[event]
 pub struct TicketRevoked {
@@ -100,6 +332,31 @@ pub struct TicketRevoked {
     pub reason: Option<String>,
 }
 
+/// Event emitted when a holder is refunded for a postponed event's ticket
+#[event]
+pub struct TicketRefunded {
+    #[index]
+    pub ticket: Pubkey,
+    pub event: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub refunded_at: i64,
+}
+
+/// Event emitted when the organizer claws back a distributed ticket
+#[event]
+pub struct TicketClawedBack {
+    #[index]
+    pub ticket: Pubkey,
+    pub mint: Pubkey,
+    pub event: Pubkey,
+    pub from: Pubkey,
+    pub organizer: Pubkey,
+    pub replacement_ticket: Pubkey,
+    pub replacement_mint: Pubkey,
+    pub clawed_back_at: i64,
+}
+
 /// Event emitted when a ticket's transferability is changed
 #[event]
 pub struct TicketTransferabilityChanged {
@@ -143,6 +400,18 @@ pub struct TicketVerified {
     pub marked_as_used: bool,
 }
 
+/// Event emitted when a batch of tickets is verified for entry
+#[event]
+pub struct TicketsBatchVerified {
+    #[index]
+    pub event: Pubkey,
+    pub valid: u32,
+    pub used: u32,
+    pub invalid: u32,
+    pub verified_by: Pubkey,
+    pub verified_at: i64,
+}
+
 /// Event emitted when multiple tickets are batch updated
 #[event]
 pub struct TicketsBatchUpdated {
@@ -167,6 +436,19 @@ pub struct VerificationChallengeGenerated {
     pub expires_at: i64,
 }
 
+/// Event emitted when an expired verification challenge is closed
+#[event]
+pub struct VerificationChallengeClosed {
+    #[index]
+    pub ticket: Pubkey,
+    #[index]
+    pub challenge: Pubkey,
+    pub payer: Pubkey,
+    pub rent_refunded: u64,
+    pub expired_at: i64,
+    pub closed_at: i64,
+}
+
 /// Event emitted when an event's capacity is reached
 #[event]
 pub struct EventCapacityReached {
@@ -3,7 +3,7 @@
 //! This module contains handlers for event-related instructions.
 
 use anchor_lang::prelude::*;
-use crate::{Event, TicketError};
+use crate::{EndingPriceFloor, Event, EventStatus, FlipRoyaltySchedule, PrimaryPayee, Ticket, TicketError};
 
 /// Creates a new event
 pub fn create_event(
@@ -41,10 +41,34 @@ pub fn create_event(
     event.organizer = ctx.accounts.organizer.key();
     event.max_tickets = max_tickets;
     event.tickets_issued = 0;
+    event.checked_in_count = 0;
     event.royalty_basis_points = royalty_basis_points;
+    event.relist_cooldown = 0;
+    event.min_listing_price = 0;
+    event.max_listing_price = 0;
+    event.allow_listing_price_increase = false;
+    event.min_ending_price = EndingPriceFloor::Absolute(0);
+    event.kyc_required = false;
+    event.require_complete_metadata = false;
+    event.freeze_before = 0;
+    event.freeze_after = 0;
+    event.flip_royalty = FlipRoyaltySchedule::default();
+    event.status = EventStatus::Scheduled;
+    event.original_start_date = 0;
+    event.original_end_date = 0;
+    event.refunds_open = false;
+    event.max_batch_size = Event::DEFAULT_MAX_BATCH_SIZE;
+    event.ticket_schema_version = Ticket::CURRENT_METADATA_SCHEMA_VERSION;
+    event.challenge_ttl = Event::DEFAULT_CHALLENGE_TTL;
+    event.max_resales = 0;
+    event.buyback_price = 0;
+    event.buyback_window = 0;
+    event.distribution_clawback_window = 0;
+    event.primary_payees = Vec::new();
     event.validators = Vec::new();
     event.active = true;
     event.bump = *ctx.bumps.get("event").unwrap();
+    event.details_hash = event.compute_details_hash();
 
     msg!("Created new event: {}", event.name);
     Ok(())
@@ -85,11 +109,329 @@ pub fn update_event(
 
     event.start_date = new_start;
     event.end_date = new_end;
+    event.details_hash = event.compute_details_hash();
 
     msg!("Updated event: {}", event.name);
     Ok(())
 }
 
+/// Postpones an event to later dates, keeping its tickets valid
+pub fn postpone_event(
+    ctx: Context<crate::UpdateEvent>,
+    new_start_date: i64,
+    new_end_date: i64,
+    open_refunds: bool,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+
+    if !event.active {
+        return err!(TicketError::EventInactive);
+    }
+
+    // A postponement only ever moves the event later
+    if new_start_date >= new_end_date || new_start_date <= event.start_date {
+        return err!(TicketError::InvalidEventDates);
+    }
+
+    // Holders keep a reference to the dates they originally bought for
+    if event.status == EventStatus::Scheduled {
+        event.original_start_date = event.start_date;
+        event.original_end_date = event.end_date;
+        event.status = EventStatus::Postponed;
+    }
+
+    event.start_date = new_start_date;
+    event.end_date = new_end_date;
+    event.details_hash = event.compute_details_hash();
+    // Once opened for a postponement, refunds stay open
+    event.refunds_open = event.refunds_open || open_refunds;
+
+    msg!(
+        "Postponed event {} to {} - {}",
+        event.name,
+        new_start_date,
+        new_end_date
+    );
+    Ok(())
+}
+
+/// Checks a supplied hash against the event's current details and anchored hash
+pub fn verify_event_details(
+    ctx: Context<crate::VerifyEventDetails>,
+    details_hash: [u8; 32],
+) -> Result<()> {
+    let event = &ctx.accounts.event;
+    let computed_hash = event.compute_details_hash();
+
+    if computed_hash != details_hash || event.details_hash != details_hash {
+        return err!(TicketError::EventDetailsMismatch);
+    }
+
+    msg!("Verified details of event: {}", event.name);
+    Ok(())
+}
+
+/// Sets how long a seller must wait before relisting a ticket after canceling its auction
+pub fn set_relist_cooldown(
+    ctx: Context<crate::UpdateEvent>,
+    relist_cooldown: i64,
+) -> Result<()> {
+    if relist_cooldown < 0 {
+        return err!(TicketError::InvalidAttribute);
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.relist_cooldown = relist_cooldown;
+
+    msg!("Set relist cooldown for event {} to {} seconds", event.name, relist_cooldown);
+    Ok(())
+}
+
+/// Sets the minimum marketplace listing price for an event's tickets
+pub fn set_min_listing_price(
+    ctx: Context<crate::UpdateEvent>,
+    min_listing_price: u64,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+    event.min_listing_price = min_listing_price;
+
+    msg!("Set minimum listing price for event {} to {}", event.name, min_listing_price);
+    Ok(())
+}
+
+/// Sets the resale price cap and whether sellers may raise an active listing's price
+pub fn set_listing_price_policy(
+    ctx: Context<crate::UpdateEvent>,
+    max_listing_price: u64,
+    allow_price_increase: bool,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+
+    // A cap below the floor would leave no valid listing price
+    if max_listing_price > 0 && max_listing_price < event.min_listing_price {
+        return err!(TicketError::InvalidListingPriceCap);
+    }
+
+    event.max_listing_price = max_listing_price;
+    event.allow_listing_price_increase = allow_price_increase;
+
+    msg!(
+        "Set listing price policy for event {}: cap {}, increases allowed: {}",
+        event.name,
+        max_listing_price,
+        allow_price_increase
+    );
+    Ok(())
+}
+
+/// Sets whether an event's tickets need complete metadata to be made transferable
+pub fn set_require_complete_metadata(
+    ctx: Context<crate::UpdateEvent>,
+    require_complete_metadata: bool,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+    event.require_complete_metadata = require_complete_metadata;
+
+    msg!(
+        "Set complete metadata requirement for event {} to {}",
+        event.name,
+        require_complete_metadata
+    );
+    Ok(())
+}
+
+/// Sets the lowest ending price Dutch auctions of an event's tickets may use
+pub fn set_min_ending_price(
+    ctx: Context<crate::UpdateEvent>,
+    min_ending_price: EndingPriceFloor,
+) -> Result<()> {
+    if let EndingPriceFloor::StartPriceBps(bps) = min_ending_price {
+        if bps > 10000 {
+            return err!(TicketError::InvalidMinEndingPrice);
+        }
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.min_ending_price = min_ending_price;
+
+    msg!("Updated minimum Dutch auction ending price for event {}", event.name);
+    Ok(())
+}
+
+/// Sets the escalated royalty schedule for quick resales of an event's tickets
+pub fn set_flip_royalty(
+    ctx: Context<crate::UpdateEvent>,
+    flip_royalty: FlipRoyaltySchedule,
+) -> Result<()> {
+    if flip_royalty.initial_basis_points > 10000 || flip_royalty.decay_window < 0 {
+        return err!(TicketError::InvalidFlipRoyalty);
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.flip_royalty = flip_royalty;
+
+    msg!("Updated flip royalty schedule for event {}", event.name);
+    Ok(())
+}
+
+/// Sets the window around an event during which ticket transfers are frozen
+pub fn set_transfer_freeze(
+    ctx: Context<crate::UpdateEvent>,
+    freeze_before: i64,
+    freeze_after: i64,
+) -> Result<()> {
+    if freeze_before < 0 || freeze_after < 0 {
+        return err!(TicketError::InvalidTransferFreeze);
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.freeze_before = freeze_before;
+    event.freeze_after = freeze_after;
+
+    msg!(
+        "Set transfer freeze for event {} to {}s before start and {}s after end",
+        event.name,
+        freeze_before,
+        freeze_after
+    );
+    Ok(())
+}
+
+/// Sets how many ticket accounts a single batch status update may process
+pub fn set_max_batch_size(
+    ctx: Context<crate::UpdateEvent>,
+    max_batch_size: u16,
+) -> Result<()> {
+    if max_batch_size == 0 {
+        return err!(TicketError::InvalidMaxBatchSize);
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.max_batch_size = max_batch_size;
+
+    msg!("Set max batch size for event {} to {}", event.name, max_batch_size);
+    Ok(())
+}
+
+/// Sets the metadata schema new tickets are minted at. Tickets on an older
+/// schema must be migrated before schema-gated operations accept them.
+pub fn set_ticket_schema_version(
+    ctx: Context<crate::UpdateEvent>,
+    ticket_schema_version: u8,
+) -> Result<()> {
+    if ticket_schema_version < Ticket::LEGACY_METADATA_SCHEMA_VERSION
+        || ticket_schema_version > Ticket::CURRENT_METADATA_SCHEMA_VERSION
+    {
+        return err!(TicketError::InvalidSchemaVersion);
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.ticket_schema_version = ticket_schema_version;
+
+    msg!("Set ticket schema version for event {} to {}", event.name, ticket_schema_version);
+    Ok(())
+}
+
+/// Sets how long verification challenges for the event stay valid
+pub fn set_challenge_ttl(
+    ctx: Context<crate::UpdateEvent>,
+    challenge_ttl: i64,
+) -> Result<()> {
+    if challenge_ttl <= 0 {
+        return err!(TicketError::InvalidChallengeTtl);
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.challenge_ttl = challenge_ttl;
+
+    msg!("Set verification challenge TTL for event {} to {}s", event.name, challenge_ttl);
+    Ok(())
+}
+
+/// Caps how many times each of the event's tickets may be resold (0 = unlimited)
+pub fn set_max_resales(
+    ctx: Context<crate::UpdateEvent>,
+    max_resales: u16,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+    event.max_resales = max_resales;
+
+    msg!("Set max resales for event {} to {}", event.name, max_resales);
+    Ok(())
+}
+
+/// Sets the price and window for organizer buybacks of listed tickets
+/// (a price of 0 disables buybacks)
+pub fn set_buyback_config(
+    ctx: Context<crate::UpdateEvent>,
+    buyback_price: u64,
+    buyback_window: i64,
+) -> Result<()> {
+    if buyback_window < 0 {
+        return err!(TicketError::InvalidAttribute);
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.buyback_price = buyback_price;
+    event.buyback_window = buyback_window;
+
+    msg!(
+        "Set buyback for event {} to {} within {}s of the start",
+        event.name,
+        buyback_price,
+        buyback_window
+    );
+    Ok(())
+}
+
+/// Sets how long after distributing a ticket the organizer may claw it back
+/// (0 disables clawbacks)
+pub fn set_distribution_clawback_window(
+    ctx: Context<crate::UpdateEvent>,
+    distribution_clawback_window: i64,
+) -> Result<()> {
+    if distribution_clawback_window < 0 {
+        return err!(TicketError::InvalidClawbackWindow);
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.distribution_clawback_window = distribution_clawback_window;
+
+    msg!(
+        "Set distribution clawback window for event {} to {}s",
+        event.name,
+        distribution_clawback_window
+    );
+    Ok(())
+}
+
+/// Sets how primary-sale revenue is split. Shares must add up to 10000 bps;
+/// an empty list sends everything to the organizer.
+pub fn set_primary_payees(
+    ctx: Context<crate::UpdateEvent>,
+    primary_payees: Vec<PrimaryPayee>,
+) -> Result<()> {
+    if primary_payees.len() > Event::MAX_PRIMARY_PAYEES {
+        return err!(TicketError::InvalidPrimaryPayees);
+    }
+    if !primary_payees.is_empty() {
+        let total_bps: u32 = primary_payees.iter().map(|payee| payee.share_bps as u32).sum();
+        if total_bps != 10000 {
+            return err!(TicketError::InvalidPrimaryPayees);
+        }
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.primary_payees = primary_payees;
+
+    msg!(
+        "Set {} primary payees for event {}",
+        event.primary_payees.len(),
+        event.name
+    );
+    Ok(())
+}
+
 /// Adds a validator to an event
 pub fn add_validator(
     ctx: Context<crate::AddValidator>,
@@ -0,0 +1,68 @@
+//! KYC instruction handlers
+//!
+//! This module contains handlers for managing KYC-restricted transfers.
+
+use anchor_lang::prelude::*;
+use crate::{KycRegistry, TicketError};
+
+/// Enables or disables KYC-restricted transfers for an event
+pub fn set_kyc_required(
+    ctx: Context<crate::UpdateEvent>,
+    kyc_required: bool,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+    event.kyc_required = kyc_required;
+
+    msg!("Set KYC requirement for event {} to {}", event.name, kyc_required);
+    Ok(())
+}
+
+/// Adds a wallet to an event's KYC registry
+pub fn add_kyc_wallet(
+    ctx: Context<crate::AddKycWallet>,
+    wallet: Pubkey,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.kyc_registry;
+
+    // Set up the registry the first time a wallet is added
+    if registry.event == Pubkey::default() {
+        registry.event = ctx.accounts.event.key();
+        registry.approved_wallets = Vec::new();
+        registry.bump = *ctx.bumps.get("kyc_registry").unwrap();
+    }
+
+    if registry.is_approved(&wallet) {
+        return err!(TicketError::KycWalletAlreadyApproved);
+    }
+
+    if registry.approved_wallets.len() >= KycRegistry::MAX_WALLETS {
+        return err!(TicketError::KycRegistryFull);
+    }
+
+    registry.approved_wallets.push(wallet);
+
+    msg!("Added KYC-approved wallet {} to event {}", wallet, ctx.accounts.event.name);
+    Ok(())
+}
+
+/// Removes a wallet from an event's KYC registry
+pub fn remove_kyc_wallet(
+    ctx: Context<crate::RemoveKycWallet>,
+    wallet: Pubkey,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.kyc_registry;
+
+    // Find wallet index
+    let wallet_position = registry.approved_wallets.iter().position(|&w| w == wallet);
+
+    // Check if wallet exists
+    if wallet_position.is_none() {
+        return err!(TicketError::KycWalletNotFound);
+    }
+
+    // Remove wallet
+    registry.approved_wallets.remove(wallet_position.unwrap());
+
+    msg!("Removed KYC-approved wallet {} from event {}", wallet, ctx.accounts.event.name);
+    Ok(())
+}
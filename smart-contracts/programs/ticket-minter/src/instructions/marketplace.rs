@@ -10,11 +10,11 @@
 
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Mint};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::associated_token::{get_associated_token_address, AssociatedToken};
 use solana_program::program::invoke_signed;
 use solana_program::system_instruction;
 
-use crate::{Ticket, TicketStatus, TicketError, Event, TransferRecord, TransferType};
+use crate::{Ticket, TicketStatus, TicketError, TicketType, Event, KycRegistry, TransferRecord, TransferType};
 
 /// Status of a marketplace listing
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -79,6 +79,16 @@ pub struct MarketplaceListing {
     pub allow_offers: bool,
     // Royalty percentage in basis points (0-10000)
     pub royalty_basis_points: u16,
+    // Segment of the event listing index this listing was recorded in
+    pub index_segment: Option<u32>,
+    // For auctions in bond mode, the share of each bid escrowed up front (0 = full bid)
+    pub bond_basis_points: u16,
+    // For auctions in bond mode, seconds after expiry the winner has to pay in full
+    pub payment_window: i64,
+    // Whether the winner of a bond-mode auction has paid the remainder
+    pub payment_finalized: bool,
+    // SPL mint the seller wants to be paid in, if one was requested
+    pub payment_mint: Option<Pubkey>,
     // PDA bump seed
     pub bump: u8,
 }
@@ -141,6 +151,154 @@ pub struct AuctionHistory {
     pub bump: u8,
 }
 
+/// Most recent listing activity for a ticket mint, used to enforce the relist cooldown
+#[account]
+pub struct LastListingAttempt {
+    // The mint of the ticket NFT
+    pub mint: Pubkey,
+    // The seller who last listed or canceled an auction for this mint
+    pub seller: Pubkey,
+    // When the mint was last listed
+    pub last_listed_at: i64,
+    // When the seller last canceled an auction for this mint
+    pub auction_canceled_at: Option<i64>,
+    // PDA bump seed
+    pub bump: u8,
+}
+
+impl LastListingAttempt {
+    // Space needed for the listing attempt account
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // seller
+        8 +  // last_listed_at
+        9 +  // auction_canceled_at (Option<i64>)
+        1 +  // bump
+        50;  // padding
+
+    /// Rejects a relist by the seller who canceled an auction within the cooldown,
+    /// then records the new listing
+    pub fn record_listing(
+        &mut self,
+        mint: Pubkey,
+        seller: Pubkey,
+        relist_cooldown: i64,
+        current_time: i64,
+        bump: u8,
+    ) -> Result<()> {
+        if self.seller == seller {
+            if let Some(canceled_at) = self.auction_canceled_at {
+                if current_time < canceled_at.saturating_add(relist_cooldown) {
+                    return err!(MarketplaceError::RelistCooldownActive);
+                }
+            }
+        }
+
+        self.mint = mint;
+        self.seller = seller;
+        self.last_listed_at = current_time;
+        self.auction_canceled_at = None;
+        self.bump = bump;
+
+        Ok(())
+    }
+}
+
+/// One segment of an event's index of active marketplace listings. Segments
+/// are bounded; once one fills, clients open the next segment number.
+#[account]
+pub struct EventListingIndex {
+    // The event whose listings are indexed
+    pub event: Pubkey,
+    // Segment number within the event's index
+    pub segment: u32,
+    // Active listings recorded in this segment
+    pub listings: Vec<Pubkey>,
+    // PDA bump seed
+    pub bump: u8,
+}
+
+impl EventListingIndex {
+    // Maximum number of listings tracked per segment
+    pub const MAX_LISTINGS: usize = 32;
+
+    // Space needed for an index segment account
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // event
+        4 +  // segment
+        4 + (Self::MAX_LISTINGS * 32) + // listings vec
+        1 +  // bump
+        50;  // padding
+
+    /// Records a newly created listing in this segment
+    pub fn add_listing(&mut self, listing: Pubkey) -> Result<()> {
+        if self.listings.len() >= Self::MAX_LISTINGS {
+            return err!(MarketplaceError::ListingIndexFull);
+        }
+        self.listings.push(listing);
+        Ok(())
+    }
+
+    /// Drops a listing that has sold or been canceled
+    pub fn remove_listing(&mut self, listing: &Pubkey) {
+        if let Some(position) = self.listings.iter().position(|entry| entry == listing) {
+            self.listings.swap_remove(position);
+        }
+    }
+}
+
+/// Admin-managed registry of the SPL mints the marketplace accepts as payment.
+/// Until it is created, listings and purchases may use any mint.
+#[account]
+pub struct AcceptedPaymentMints {
+    // Admin allowed to update the registry
+    pub admin: Pubkey,
+    // Mints accepted for payment
+    pub mints: Vec<Pubkey>,
+    // PDA bump seed
+    pub bump: u8,
+}
+
+impl AcceptedPaymentMints {
+    // Maximum number of accepted mints
+    pub const MAX_MINTS: usize = 16;
+
+    // Space needed for the registry account
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // admin
+        4 + (Self::MAX_MINTS * 32) + // mints vec
+        1 +  // bump
+        50;  // padding
+
+    /// Whether `mint` may be used for payment
+    pub fn is_accepted(&self, mint: &Pubkey) -> bool {
+        self.mints.contains(mint)
+    }
+
+    /// Adds a mint to the registry
+    pub fn add_mint(&mut self, mint: Pubkey) -> Result<()> {
+        if self.is_accepted(&mint) {
+            return err!(MarketplaceError::PaymentMintAlreadyAccepted);
+        }
+        if self.mints.len() >= Self::MAX_MINTS {
+            return err!(MarketplaceError::AcceptedPaymentMintsFull);
+        }
+        self.mints.push(mint);
+        Ok(())
+    }
+
+    /// Removes a mint from the registry
+    pub fn remove_mint(&mut self, mint: &Pubkey) -> Result<()> {
+        match self.mints.iter().position(|entry| entry == mint) {
+            Some(position) => {
+                self.mints.swap_remove(position);
+                Ok(())
+            },
+            None => err!(MarketplaceError::PaymentMintNotAccepted),
+        }
+    }
+}
+
 impl MarketplaceOffer {
     // Space needed for the offer account
     pub const SPACE: usize = 8 + // discriminator
@@ -174,8 +332,69 @@ impl MarketplaceListing {
         33 + // highest_bidder (Option<Pubkey>)
         1 +  // allow_offers
         2 +  // royalty_basis_points
+        5 +  // index_segment (Option<u32>)
+        2 +  // bond_basis_points
+        8 +  // payment_window
+        1 +  // payment_finalized
+        33 + // payment_mint (Option<Pubkey>)
         1 +  // bump
         50;  // padding
+
+    /// Amount a bid escrows: the bond share in bond mode, otherwise the full bid
+    pub fn escrow_amount_for(&self, bid_amount: u64) -> u64 {
+        if self.bond_basis_points == 0 {
+            return bid_amount;
+        }
+        
+        (bid_amount as u128)
+            .checked_mul(self.bond_basis_points as u128)
+            .unwrap_or(0)
+            .checked_div(10000)
+            .unwrap_or(0) as u64
+    }
+
+    /// Whether the listing has sold, been canceled or finished its auction,
+    /// so its account can be closed
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.status,
+            ListingStatus::Sold
+                | ListingStatus::Canceled
+                | ListingStatus::AuctionEnded
+                | ListingStatus::AuctionExpired
+        )
+    }
+
+    /// Last moment the winner of a bond-mode auction may pay the remainder
+    pub fn payment_deadline(&self) -> Option<i64> {
+        self.expiry.map(|expiry| expiry.saturating_add(self.payment_window))
+    }
+
+    /// Price of a Dutch auction at `current_time`, decaying linearly from the
+    /// starting price to the ending price over the auction's duration
+    pub fn dutch_price_at(&self, current_time: i64) -> u64 {
+        let start_time = self.created_at;
+        let end_time = self.expiry.unwrap_or(start_time);
+        let start_price = self.price;
+        let end_price = self.ending_price.unwrap_or(start_price);
+        
+        if current_time >= end_time {
+            return end_price;
+        }
+        
+        // Linear interpolation of price
+        let elapsed = current_time - start_time;
+        let duration = end_time - start_time;
+        let price_diff = start_price.saturating_sub(end_price);
+        
+        let price_reduction = (price_diff as u128)
+            .checked_mul(elapsed as u128)
+            .unwrap_or(0)
+            .checked_div(duration as u128)
+            .unwrap_or(0) as u64;
+        
+        start_price.saturating_sub(price_reduction)
+    }
 }
 
 impl AuctionHistory {
@@ -212,6 +431,16 @@ pub struct ListingCanceledEvent {
     pub owner: Pubkey,
 }
 
+// Event emitted when a seller changes a listing's price
+#[event]
+pub struct ListingPriceUpdated {
+    #[index]
+    pub listing: Pubkey,
+    pub owner: Pubkey,
+    pub old_price: u64,
+    pub new_price: u64,
+}
+
 // Event emitted when a listing is purchased
 #[event]
 pub struct ListingPurchasedEvent {
@@ -224,6 +453,17 @@ pub struct ListingPurchasedEvent {
     pub royalty_amount: u64,
 }
 
+// Event emitted when the organizer buys back a listed ticket
+#[event]
+pub struct TicketBoughtBackEvent {
+    #[index]
+    pub listing: Pubkey,
+    pub ticket: Pubkey,
+    pub organizer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+}
+
 // Event emitted when a bid is placed
 #[event]
 pub struct BidPlacedEvent {
@@ -254,6 +494,34 @@ pub struct AuctionExpiredEvent {
     pub ticket: Pubkey,
 }
 
+// Event emitted when a seller puts an auction in bond mode
+#[event]
+pub struct AuctionBondSetEvent {
+    #[index]
+    pub listing: Pubkey,
+    pub bond_basis_points: u16,
+    pub payment_window: i64,
+}
+
+// Event emitted when a bond-mode winner pays the remainder of their bid
+#[event]
+pub struct WinningPaymentFinalizedEvent {
+    #[index]
+    pub listing: Pubkey,
+    pub winner: Pubkey,
+    pub amount_paid: u64,
+}
+
+// Event emitted when a bond-mode winner defaults and the bond goes to the seller
+#[event]
+pub struct BondForfeitedEvent {
+    #[index]
+    pub listing: Pubkey,
+    pub winner: Pubkey,
+    pub seller: Pubkey,
+    pub bond_amount: u64,
+}
+
 // Event emitted when an offer is made
 #[event]
 pub struct OfferMadeEvent {
@@ -276,6 +544,32 @@ pub struct OfferAcceptedEvent {
     pub royalty_amount: u64,
 }
 
+// Event emitted when a mint is added to the accepted payment mints
+#[event]
+pub struct PaymentMintAcceptedEvent {
+    #[index]
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+}
+
+// Event emitted when a mint is removed from the accepted payment mints
+#[event]
+pub struct PaymentMintRemovedEvent {
+    #[index]
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+}
+
+// Event emitted when a terminal listing is closed and its rent reclaimed
+#[event]
+pub struct ListingClosedEvent {
+    #[index]
+    pub listing: Pubkey,
+    pub owner: Pubkey,
+    pub closed_by: Pubkey,
+    pub rent_reclaimed: u64,
+}
+
 /// Error specific to marketplace operations
 #[error_code]
 pub enum MarketplaceError {
@@ -306,6 +600,98 @@ pub enum MarketplaceError {
     // Offers are not allowed on this listing
     #[msg("Offers are not allowed on this listing")]
     OffersNotAllowed,
+
+    // Seller canceled an auction for this ticket too recently
+    #[msg("Ticket cannot be relisted until the relist cooldown has elapsed")]
+    RelistCooldownActive,
+
+    // Listing price is under the event's floor
+    #[msg("Listing price is below the event's minimum listing price")]
+    PriceBelowMinimum,
+
+    // Listing price is over the event's resale cap
+    #[msg("Listing price is above the event's maximum listing price")]
+    PriceAboveMaximum,
+
+    // Event does not let sellers raise an active listing's price
+    #[msg("Listing price increases are not allowed for this event")]
+    PriceIncreaseNotAllowed,
+
+    // Dutch auction would decay to a free ticket
+    #[msg("Dutch auction ending price must be greater than zero")]
+    ZeroEndingPrice,
+
+    // Dutch auction ending price is under the event's ending floor
+    #[msg("Dutch auction ending price is below the event's minimum ending price")]
+    EndingPriceBelowFloor,
+
+    // Event listing index segment has no room left
+    #[msg("Event listing index segment is full")]
+    ListingIndexFull,
+
+    // Listing was indexed but its index segment was not supplied
+    #[msg("The listing's event listing index segment must be provided")]
+    ListingIndexRequired,
+
+    // Bond percentage or payment window is out of range
+    #[msg("Invalid auction bond configuration")]
+    InvalidBondConfiguration,
+
+    // Bond terms cannot change once bidding has started
+    #[msg("Auction bond cannot be changed after bids have been placed")]
+    BondLockedAfterBids,
+
+    // The winner can still pay the remainder of their bid
+    #[msg("The winner's payment window is still open")]
+    PaymentWindowActive,
+
+    // The winner missed the payment window
+    #[msg("The winner's payment window has closed")]
+    PaymentWindowClosed,
+
+    // Winning payment has already been completed
+    #[msg("Winning payment has already been finalized")]
+    PaymentAlreadyFinalized,
+
+    // Only the highest bidder can pay for a won auction
+    #[msg("Only the auction winner can finalize payment")]
+    NotAuctionWinner,
+
+    // Mint is already in the accepted payment mints
+    #[msg("Payment mint is already accepted")]
+    PaymentMintAlreadyAccepted,
+
+    // Mint is not in the accepted payment mints
+    #[msg("Payment mint is not in the accepted payment mints")]
+    PaymentMintNotAccepted,
+
+    // Accepted payment mint registry has no room left
+    #[msg("Accepted payment mint registry is full")]
+    AcceptedPaymentMintsFull,
+
+    // Bid only matches the current highest bid, which the earlier bidder keeps
+    #[msg("Bid must exceed the current highest bid; ties go to the earlier bid")]
+    BidTiesHighest,
+
+    // Listing is still open for sale or bidding
+    #[msg("Only sold, canceled or finished listings can be closed")]
+    ListingNotTerminal,
+
+    // Listing escrow still holds the NFT or bid funds
+    #[msg("Listing escrow still holds tokens")]
+    ListingEscrowNotEmpty,
+
+    // Listing took bids but its payment escrow was not supplied
+    #[msg("The listing's payment escrow account must be provided")]
+    PaymentEscrowRequired,
+
+    // Price at execution is above the buyer's maximum
+    #[msg("Purchase price exceeds the buyer's maximum price")]
+    SlippageToleranceExceeded,
+
+    // Buyer is the listing's own seller
+    #[msg("Cannot buy your own listing")]
+    CannotBuyOwnListing,
 }
 
 /// Context for creating a marketplace listing
@@ -338,9 +724,40 @@ pub struct CreateListing<'info> {
     )]
     pub auction_history: Option<Account<'info, AuctionHistory>>,
     
+    // Listing activity for the ticket mint
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = LastListingAttempt::SPACE,
+        seeds = [b"last_listing_attempt", ticket.mint.as_ref()],
+        bump
+    )]
+    pub last_listing_attempt: Account<'info, LastListingAttempt>,
+    
     // The event the ticket belongs to
+    #[account(constraint = event.key() == ticket.event @ TicketError::TicketEventMismatch)]
     pub event: Account<'info, Event>,
     
+    // The ticket's type, which names the NFT
+    #[account(constraint = ticket_type.key() == ticket.ticket_type)]
+    pub ticket_type: Account<'info, TicketType>,
+    
+    // Optional event listing index segment to record the listing in
+    #[account(
+        mut,
+        seeds = [b"event_listing_index", event.key().as_ref(), &event_listing_index.segment.to_le_bytes()],
+        bump = event_listing_index.bump
+    )]
+    pub event_listing_index: Option<Account<'info, EventListingIndex>>,
+    
+    // Optional SPL mint the seller wants to be paid in
+    pub payment_mint: Option<Account<'info, Mint>>,
+    
+    // Accepted payment mint registry, enforced once it has been created
+    /// CHECK: PDA verified by seeds, deserialized in the handler when initialized
+    #[account(seeds = [b"accepted_payment_mints"], bump)]
+    pub accepted_payment_mints: UncheckedAccount<'info>,
+    
     // The owner of the ticket and seller
     #[account(mut, constraint = owner.key() == ticket.owner)]
     pub owner: Signer<'info>,
@@ -364,12 +781,53 @@ pub struct CancelListing<'info> {
     )]
     pub listing: Account<'info, MarketplaceListing>,
     
+    // Listing activity for the ticket mint
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = LastListingAttempt::SPACE,
+        seeds = [b"last_listing_attempt", ticket.mint.as_ref()],
+        bump
+    )]
+    pub last_listing_attempt: Account<'info, LastListingAttempt>,
+    
+    // The event listing index segment holding this listing, if it was indexed
+    #[account(
+        mut,
+        seeds = [b"event_listing_index", listing.event.as_ref(), &listing.index_segment.unwrap_or_default().to_le_bytes()],
+        bump = event_listing_index.bump
+    )]
+    pub event_listing_index: Option<Account<'info, EventListingIndex>>,
+    
     // The owner of the ticket and seller
-    #[account(constraint = owner.key() == listing.owner)]
+    #[account(mut, constraint = owner.key() == listing.owner)]
+    pub owner: Signer<'info>,
+    
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for changing the price of a fixed-price listing
+#[derive(Accounts)]
+pub struct UpdateListingPrice<'info> {
+    // The listing being repriced
+    #[account(
+        mut,
+        seeds = [b"marketplace_listing", listing.ticket.as_ref(), listing.listing_id.as_bytes()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, MarketplaceListing>,
+    
+    // The event, for its listing price policy
+    #[account(constraint = event.key() == listing.event)]
+    pub event: Account<'info, Event>,
+    
+    // The seller
+    #[account(constraint = owner.key() == listing.owner @ TicketError::Unauthorized)]
     pub owner: Signer<'info>,
 }
 
-/// Context for purchasing a fixed-price listing
+/// Context for purchasing a fixed-price listing or Dutch auction
 #[derive(Accounts)]
 pub struct PurchaseListing<'info> {
     // The ticket being purchased
@@ -388,6 +846,17 @@ pub struct PurchaseListing<'info> {
     )]
     pub listing: Account<'info, MarketplaceListing>,
     
+    // The event the ticket belongs to
+    #[account(constraint = event.key() == ticket.event @ TicketError::TicketEventMismatch)]
+    pub event: Account<'info, Event>,
+    
+    // The event's KYC registry (required when the event has KYC enabled)
+    #[account(
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump = kyc_registry.bump
+    )]
+    pub kyc_registry: Option<Account<'info, KycRegistry>>,
+    
     // The mint of the ticket NFT
     pub mint: Account<'info, Mint>,
     
@@ -438,6 +907,19 @@ pub struct PurchaseListing<'info> {
     #[account(mut)]
     pub transfer_record: Option<Account<'info, TransferRecord>>,
     
+    // The event listing index segment holding this listing, if it was indexed
+    #[account(
+        mut,
+        seeds = [b"event_listing_index", listing.event.as_ref(), &listing.index_segment.unwrap_or_default().to_le_bytes()],
+        bump = event_listing_index.bump
+    )]
+    pub event_listing_index: Option<Account<'info, EventListingIndex>>,
+    
+    // Accepted payment mint registry, enforced once it has been created
+    /// CHECK: PDA verified by seeds, deserialized in the handler when initialized
+    #[account(seeds = [b"accepted_payment_mints"], bump)]
+    pub accepted_payment_mints: UncheckedAccount<'info>,
+    
     // Token program
     pub token_program: Program<'info, Token>,
     
@@ -445,6 +927,95 @@ pub struct PurchaseListing<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+/// Context for the organizer buying back a listed ticket
+#[derive(Accounts)]
+pub struct BuybackListing<'info> {
+    // The ticket being bought back
+    #[account(
+        mut,
+        constraint = ticket.key() == listing.ticket,
+        constraint = ticket.owner == listing.owner
+    )]
+    pub ticket: Account<'info, Ticket>,
+    
+    // The listing being bought back
+    #[account(
+        mut,
+        seeds = [b"marketplace_listing", ticket.key().as_ref(), listing.listing_id.as_bytes()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, MarketplaceListing>,
+    
+    // The event the ticket belongs to; only its organizer may buy back
+    #[account(
+        constraint = event.key() == ticket.event @ TicketError::TicketEventMismatch,
+        has_one = organizer
+    )]
+    pub event: Account<'info, Event>,
+    
+    // The mint of the ticket NFT
+    pub mint: Account<'info, Mint>,
+    
+    // The seller's token account
+    #[account(
+        mut,
+        constraint = from_token_account.owner == seller.key(),
+        constraint = from_token_account.mint == mint.key(),
+        constraint = from_token_account.amount == 1
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+    
+    // The organizer's token account receiving the ticket
+    #[account(
+        mut,
+        constraint = to_token_account.owner == organizer.key(),
+        constraint = to_token_account.mint == mint.key()
+    )]
+    pub to_token_account: Account<'info, TokenAccount>,
+    
+    // The seller of the ticket
+    #[account(constraint = seller.key() == listing.owner)]
+    pub seller: Signer<'info>,
+    
+    // The event organizer
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    
+    // Organizer treasury paying the buyback price
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == organizer.key()
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    
+    // Payment to account (seller)
+    #[account(
+        mut,
+        constraint = payment_to_account.owner == seller.key()
+    )]
+    pub payment_to_account: Account<'info, TokenAccount>,
+    
+    // Optional transfer record account
+    #[account(mut)]
+    pub transfer_record: Option<Account<'info, TransferRecord>>,
+    
+    // The event listing index segment holding this listing, if it was indexed
+    #[account(
+        mut,
+        seeds = [b"event_listing_index", listing.event.as_ref(), &listing.index_segment.unwrap_or_default().to_le_bytes()],
+        bump = event_listing_index.bump
+    )]
+    pub event_listing_index: Option<Account<'info, EventListingIndex>>,
+    
+    // Accepted payment mint registry, enforced once it has been created
+    /// CHECK: PDA verified by seeds, deserialized in the handler when initialized
+    #[account(seeds = [b"accepted_payment_mints"], bump)]
+    pub accepted_payment_mints: UncheckedAccount<'info>,
+    
+    // Token program
+    pub token_program: Program<'info, Token>,
+}
+
 /// Context for placing a bid on an auction
 #[derive(Accounts)]
 pub struct PlaceBid<'info> {
@@ -499,6 +1070,11 @@ pub struct PlaceBid<'info> {
     #[account(address = System::id())]
     pub escrow_authority_bump: u8,
     
+    // Accepted payment mint registry, enforced once it has been created
+    /// CHECK: PDA verified by seeds, deserialized in the handler when initialized
+    #[account(seeds = [b"accepted_payment_mints"], bump)]
+    pub accepted_payment_mints: UncheckedAccount<'info>,
+    
     // Token program
     pub token_program: Program<'info, Token>,
 }
@@ -522,6 +1098,17 @@ pub struct SettleAuction<'info> {
     )]
     pub listing: Account<'info, MarketplaceListing>,
     
+    // The event the ticket belongs to
+    #[account(constraint = event.key() == ticket.event @ TicketError::TicketEventMismatch)]
+    pub event: Account<'info, Event>,
+    
+    // The event's KYC registry (required when the event has KYC enabled)
+    #[account(
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump = kyc_registry.bump
+    )]
+    pub kyc_registry: Option<Account<'info, KycRegistry>>,
+    
     // The mint of the ticket NFT
     pub mint: Account<'info, Mint>,
     
@@ -572,10 +1159,23 @@ pub struct SettleAuction<'info> {
     #[account(mut)]
     pub transfer_record: Option<Account<'info, TransferRecord>>,
     
+    // The event listing index segment holding this listing, if it was indexed
+    #[account(
+        mut,
+        seeds = [b"event_listing_index", listing.event.as_ref(), &listing.index_segment.unwrap_or_default().to_le_bytes()],
+        bump = event_listing_index.bump
+    )]
+    pub event_listing_index: Option<Account<'info, EventListingIndex>>,
+    
     // The escrow authority bump
     #[account(address = System::id())]
     pub escrow_authority_bump: u8,
     
+    // Accepted payment mint registry, enforced once it has been created
+    /// CHECK: PDA verified by seeds, deserialized in the handler when initialized
+    #[account(seeds = [b"accepted_payment_mints"], bump)]
+    pub accepted_payment_mints: UncheckedAccount<'info>,
+    
     // Token program
     pub token_program: Program<'info, Token>,
     
@@ -612,6 +1212,11 @@ pub struct MakeOffer<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
     
+    // Accepted payment mint registry, enforced once it has been created
+    /// CHECK: PDA verified by seeds, deserialized in the handler when initialized
+    #[account(seeds = [b"accepted_payment_mints"], bump)]
+    pub accepted_payment_mints: UncheckedAccount<'info>,
+    
     // System program
     pub system_program: Program<'info, System>,
 }
@@ -635,6 +1240,17 @@ pub struct AcceptOffer<'info> {
     )]
     pub listing: Account<'info, MarketplaceListing>,
     
+    // The event the ticket belongs to
+    #[account(constraint = event.key() == ticket.event @ TicketError::TicketEventMismatch)]
+    pub event: Account<'info, Event>,
+    
+    // The event's KYC registry (required when the event has KYC enabled)
+    #[account(
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump = kyc_registry.bump
+    )]
+    pub kyc_registry: Option<Account<'info, KycRegistry>>,
+    
     // The offer being accepted
     #[account(
         mut,
@@ -692,6 +1308,19 @@ pub struct AcceptOffer<'info> {
     #[account(mut)]
     pub transfer_record: Option<Account<'info, TransferRecord>>,
     
+    // The event listing index segment holding this listing, if it was indexed
+    #[account(
+        mut,
+        seeds = [b"event_listing_index", listing.event.as_ref(), &listing.index_segment.unwrap_or_default().to_le_bytes()],
+        bump = event_listing_index.bump
+    )]
+    pub event_listing_index: Option<Account<'info, EventListingIndex>>,
+    
+    // Accepted payment mint registry, enforced once it has been created
+    /// CHECK: PDA verified by seeds, deserialized in the handler when initialized
+    #[account(seeds = [b"accepted_payment_mints"], bump)]
+    pub accepted_payment_mints: UncheckedAccount<'info>,
+    
     // Token program
     pub token_program: Program<'info, Token>,
     
@@ -699,6 +1328,188 @@ pub struct AcceptOffer<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+/// Context for configuring an auction's bid bond
+#[derive(Accounts)]
+pub struct SetAuctionBond<'info> {
+    // The auction listing
+    #[account(
+        mut,
+        seeds = [b"marketplace_listing", listing.ticket.as_ref(), listing.listing_id.as_bytes()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, MarketplaceListing>,
+    
+    // The seller
+    #[account(constraint = owner.key() == listing.owner @ TicketError::Unauthorized)]
+    pub owner: Signer<'info>,
+}
+
+/// Context for the winner of a bond-mode auction paying the rest of their bid
+#[derive(Accounts)]
+pub struct FinalizeWinningPayment<'info> {
+    // The auction listing
+    #[account(
+        mut,
+        seeds = [b"marketplace_listing", listing.ticket.as_ref(), listing.listing_id.as_bytes()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, MarketplaceListing>,
+    
+    // The highest bidder
+    #[account(constraint = listing.highest_bidder == Some(winner.key()) @ MarketplaceError::NotAuctionWinner)]
+    pub winner: Signer<'info>,
+    
+    // Payment from account (winner)
+    #[account(
+        mut,
+        constraint = payment_from_account.owner == winner.key()
+    )]
+    pub payment_from_account: Account<'info, TokenAccount>,
+    
+    // Escrow account holding the bond
+    #[account(
+        mut,
+        constraint = escrow_account.owner == escrow_authority.key()
+    )]
+    pub escrow_account: Account<'info, TokenAccount>,
+    
+    // The PDA that acts as the escrow authority
+    /// CHECK: This is a PDA, we verify its derivation
+    #[account(
+        seeds = [b"escrow", listing.key().as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+    
+    // Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for opening a new segment of an event's listing index
+#[derive(Accounts)]
+#[instruction(segment: u32)]
+pub struct CreateEventListingIndex<'info> {
+    // The event to index
+    pub event: Account<'info, Event>,
+    
+    // The index segment to create
+    #[account(
+        init,
+        payer = payer,
+        space = EventListingIndex::SPACE,
+        seeds = [b"event_listing_index", event.key().as_ref(), &segment.to_le_bytes()],
+        bump
+    )]
+    pub event_listing_index: Account<'info, EventListingIndex>,
+    
+    // Pays for the segment account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for logging the active listings in an index segment
+#[derive(Accounts)]
+pub struct ViewEventListings<'info> {
+    // The event being queried
+    pub event: Account<'info, Event>,
+    
+    // The index segment to read
+    #[account(
+        seeds = [b"event_listing_index", event.key().as_ref(), &event_listing_index.segment.to_le_bytes()],
+        bump = event_listing_index.bump
+    )]
+    pub event_listing_index: Account<'info, EventListingIndex>,
+}
+
+/// Context for creating the accepted payment mint registry
+#[derive(Accounts)]
+pub struct CreateAcceptedPaymentMints<'info> {
+    // The registry to create
+    #[account(
+        init,
+        payer = admin,
+        space = AcceptedPaymentMints::SPACE,
+        seeds = [b"accepted_payment_mints"],
+        bump
+    )]
+    pub accepted_payment_mints: Account<'info, AcceptedPaymentMints>,
+    
+    // The program's data account, whose upgrade authority may create the registry
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = anchor_lang::solana_program::bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ TicketError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    // Becomes the registry admin; must be the program's upgrade authority
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for adding or removing an accepted payment mint
+#[derive(Accounts)]
+pub struct UpdateAcceptedPaymentMints<'info> {
+    // The registry to update
+    #[account(
+        mut,
+        has_one = admin @ TicketError::Unauthorized,
+        seeds = [b"accepted_payment_mints"],
+        bump = accepted_payment_mints.bump
+    )]
+    pub accepted_payment_mints: Account<'info, AcceptedPaymentMints>,
+    
+    // The registry admin
+    pub admin: Signer<'info>,
+}
+
+/// Context for closing a listing that has reached a terminal state
+#[derive(Accounts)]
+pub struct CloseTerminalListing<'info> {
+    // The listing to close; its rent goes back to the seller
+    #[account(
+        mut,
+        close = owner,
+        constraint = listing.is_terminal() @ MarketplaceError::ListingNotTerminal,
+        seeds = [b"marketplace_listing", listing.ticket.as_ref(), listing.listing_id.as_bytes()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, MarketplaceListing>,
+    
+    // The seller who paid for the listing
+    /// CHECK: Only receives the reclaimed rent
+    #[account(mut, address = listing.owner @ TicketError::Unauthorized)]
+    pub owner: UncheckedAccount<'info>,
+    
+    // The PDA that acts as the escrow authority
+    /// CHECK: This is a PDA, we verify its derivation
+    #[account(seeds = [b"escrow", listing.key().as_ref()], bump)]
+    pub escrow_authority: UncheckedAccount<'info>,
+    
+    // The escrow authority's token account for the ticket NFT
+    /// CHECK: May be uninitialized, deserialized in the handler when it exists
+    #[account(address = get_associated_token_address(&escrow_authority.key(), &listing.mint))]
+    pub nft_escrow_account: UncheckedAccount<'info>,
+    
+    // The escrow authority's token account for the listing's payment mint,
+    // required once the listing has a payment mint
+    /// CHECK: May be uninitialized, deserialized in the handler when it exists
+    #[account(
+        address = get_associated_token_address(&escrow_authority.key(), &listing.payment_mint.unwrap_or_default())
+    )]
+    pub payment_escrow_account: Option<UncheckedAccount<'info>>,
+    
+    // Anyone may clean up a terminal listing
+    pub closer: Signer<'info>,
+}
+
 // Implement extension method for TransferRecord to add a transfer
 impl TransferRecord {
     pub fn add_transfer(
@@ -727,6 +1538,176 @@ impl TransferRecord {
     }
 }
 
+/// Removes a sold or canceled listing from its event listing index segment
+fn remove_from_event_listing_index(
+    listing: &MarketplaceListing,
+    listing_key: Pubkey,
+    event_listing_index: Option<&mut Account<EventListingIndex>>,
+) -> Result<()> {
+    if listing.index_segment.is_none() {
+        return Ok(());
+    }
+    
+    match event_listing_index {
+        Some(event_listing_index) => {
+            event_listing_index.remove_listing(&listing_key);
+            Ok(())
+        },
+        None => err!(MarketplaceError::ListingIndexRequired),
+    }
+}
+
+/// Rejects a payment mint missing from the accepted payment mint registry.
+/// Any mint is allowed until the registry has been created.
+fn require_accepted_payment_mint(
+    accepted_payment_mints: &AccountInfo,
+    payment_mint: Option<Pubkey>,
+) -> Result<()> {
+    if accepted_payment_mints.data_is_empty() {
+        return Ok(());
+    }
+    
+    let registry = Account::<AcceptedPaymentMints>::try_from(accepted_payment_mints)?;
+    match payment_mint {
+        Some(mint) if registry.is_accepted(&mint) => Ok(()),
+        _ => err!(TicketError::InvalidPaymentToken),
+    }
+}
+
+/// Royalty rate for a sale at `now`, escalated by the event's flip schedule
+/// when the seller acquired the ticket at `acquired_at`
+fn resale_royalty_basis_points(
+    listing: &MarketplaceListing,
+    event: &Event,
+    acquired_at: i64,
+    now: i64,
+) -> u16 {
+    event
+        .flip_royalty
+        .basis_points_for(listing.royalty_basis_points, now.saturating_sub(acquired_at))
+}
+
+/// Creates the accepted payment mint registry with the program's upgrade
+/// authority as admin
+pub fn create_accepted_payment_mints(
+    ctx: Context<CreateAcceptedPaymentMints>,
+) -> Result<()> {
+    let accepted_payment_mints = &mut ctx.accounts.accepted_payment_mints;
+    accepted_payment_mints.admin = ctx.accounts.admin.key();
+    accepted_payment_mints.mints = Vec::new();
+    accepted_payment_mints.bump = *ctx.bumps.get("accepted_payment_mints").unwrap();
+    
+    Ok(())
+}
+
+/// Adds a mint to the accepted payment mints
+pub fn add_accepted_payment_mint(
+    ctx: Context<UpdateAcceptedPaymentMints>,
+    mint: Pubkey,
+) -> Result<()> {
+    ctx.accounts.accepted_payment_mints.add_mint(mint)?;
+    
+    emit!(PaymentMintAcceptedEvent {
+        mint,
+        admin: ctx.accounts.admin.key(),
+    });
+    
+    Ok(())
+}
+
+/// Removes a mint from the accepted payment mints
+pub fn remove_accepted_payment_mint(
+    ctx: Context<UpdateAcceptedPaymentMints>,
+    mint: Pubkey,
+) -> Result<()> {
+    ctx.accounts.accepted_payment_mints.remove_mint(&mint)?;
+    
+    emit!(PaymentMintRemovedEvent {
+        mint,
+        admin: ctx.accounts.admin.key(),
+    });
+    
+    Ok(())
+}
+
+/// Closes a sold, canceled or finished listing once its escrow is empty,
+/// returning the rent to the seller. Callable by anyone.
+pub fn close_terminal_listing(
+    ctx: Context<CloseTerminalListing>,
+) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    
+    // The NFT must not be sitting in escrow
+    let nft_escrow_account = &ctx.accounts.nft_escrow_account;
+    if !nft_escrow_account.data_is_empty() {
+        let nft_escrow = Account::<TokenAccount>::try_from(nft_escrow_account)?;
+        if nft_escrow.amount > 0 {
+            return err!(MarketplaceError::ListingEscrowNotEmpty);
+        }
+    }
+    
+    // Nor any bid funds. Bids are escrowed in the listing's payment mint, so
+    // any listing with one must show its escrow account
+    if let Some(payment_mint) = listing.payment_mint {
+        let payment_escrow_account = ctx.accounts.payment_escrow_account
+            .as_ref()
+            .ok_or(MarketplaceError::PaymentEscrowRequired)?;
+        if !payment_escrow_account.data_is_empty() {
+            let payment_escrow = Account::<TokenAccount>::try_from(payment_escrow_account)?;
+            if payment_escrow.mint != payment_mint {
+                return err!(TicketError::InvalidPaymentToken);
+            }
+            if payment_escrow.amount > 0 {
+                return err!(MarketplaceError::ListingEscrowNotEmpty);
+            }
+        }
+    } else if listing.highest_bid.is_some() {
+        return err!(MarketplaceError::PaymentEscrowRequired);
+    }
+    
+    emit!(ListingClosedEvent {
+        listing: listing.key(),
+        owner: listing.owner,
+        closed_by: ctx.accounts.closer.key(),
+        rent_reclaimed: listing.to_account_info().lamports(),
+    });
+    
+    Ok(())
+}
+
+/// Opens a new segment of an event's listing index
+pub fn create_event_listing_index(
+    ctx: Context<CreateEventListingIndex>,
+    segment: u32,
+) -> Result<()> {
+    let event_listing_index = &mut ctx.accounts.event_listing_index;
+    event_listing_index.event = ctx.accounts.event.key();
+    event_listing_index.segment = segment;
+    event_listing_index.listings = Vec::new();
+    event_listing_index.bump = *ctx.bumps.get("event_listing_index").unwrap();
+    
+    Ok(())
+}
+
+/// Logs the active listings recorded in an event listing index segment
+pub fn view_event_listings(
+    ctx: Context<ViewEventListings>,
+) -> Result<()> {
+    let event_listing_index = &ctx.accounts.event_listing_index;
+    
+    msg!(
+        "Event {} index segment {} has {} active listings",
+        ctx.accounts.event.name,
+        event_listing_index.segment,
+        event_listing_index.listings.len()
+    );
+    for listing in event_listing_index.listings.iter() {
+        msg!("Active listing: {}", listing);
+    }
+    
+    Ok(())
+}
+
 /// Creates a fixed-price marketplace listing
 pub fn create_listing(
     ctx: Context<CreateListing>,
@@ -742,6 +1723,7 @@ pub fn create_listing(
     if !ticket.transferable {
         return err!(TicketError::NotTransferable);
     }
+    ticket.require_complete_metadata(&ctx.accounts.event, &ctx.accounts.ticket_type)?;
     
     // Check that the ticket is valid
     if ticket.status != TicketStatus::Valid {
@@ -756,6 +1738,27 @@ pub fn create_listing(
     // Get the event account to read royalty information
     let event = &ctx.accounts.event;
     
+    // Enforce the event's price floor
+    if price < event.min_listing_price {
+        return err!(MarketplaceError::PriceBelowMinimum);
+    }
+    if event.max_listing_price > 0 && price > event.max_listing_price {
+        return err!(MarketplaceError::PriceAboveMaximum);
+    }
+    
+    // Block relists that fall inside the cooldown after a canceled auction
+    ctx.accounts.last_listing_attempt.record_listing(
+        ticket.mint,
+        ctx.accounts.owner.key(),
+        event.relist_cooldown,
+        current_time,
+        *ctx.bumps.get("last_listing_attempt").unwrap(),
+    )?;
+    
+    // Sellers can only ask for payment in a mint the marketplace accepts
+    let payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, payment_mint)?;
+    
     // Initialize the listing
     let listing = &mut ctx.accounts.listing;
     listing.listing_id = listing_id;
@@ -774,8 +1777,19 @@ pub fn create_listing(
     listing.highest_bidder = None;
     listing.allow_offers = true;
     listing.royalty_basis_points = event.royalty_basis_points;
+    listing.index_segment = None;
+    listing.bond_basis_points = 0;
+    listing.payment_window = 0;
+    listing.payment_finalized = false;
+    listing.payment_mint = payment_mint;
     listing.bump = *ctx.bumps.get("listing").unwrap();
     
+    // Record the listing in the event's index when a segment is supplied
+    if let Some(event_listing_index) = &mut ctx.accounts.event_listing_index {
+        event_listing_index.add_listing(listing.key())?;
+        listing.index_segment = Some(event_listing_index.segment);
+    }
+    
     // Emit event
     emit!(ListingCreatedEvent {
         listing: listing.key(),
@@ -805,6 +1819,7 @@ pub fn create_auction(
     if !ticket.transferable {
         return err!(TicketError::NotTransferable);
     }
+    ticket.require_complete_metadata(&ctx.accounts.event, &ctx.accounts.ticket_type)?;
     
     // Check that the ticket is valid
     if ticket.status != TicketStatus::Valid {
@@ -819,6 +1834,27 @@ pub fn create_auction(
     // Get the event account to read royalty information
     let event = &ctx.accounts.event;
     
+    // Enforce the event's price floor
+    if start_price < event.min_listing_price {
+        return err!(MarketplaceError::PriceBelowMinimum);
+    }
+    if event.max_listing_price > 0 && start_price > event.max_listing_price {
+        return err!(MarketplaceError::PriceAboveMaximum);
+    }
+    
+    // Block relists that fall inside the cooldown after a canceled auction
+    ctx.accounts.last_listing_attempt.record_listing(
+        ticket.mint,
+        ctx.accounts.owner.key(),
+        event.relist_cooldown,
+        current_time,
+        *ctx.bumps.get("last_listing_attempt").unwrap(),
+    )?;
+    
+    // Sellers can only ask for payment in a mint the marketplace accepts
+    let payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, payment_mint)?;
+    
     // Initialize the listing
     let listing = &mut ctx.accounts.listing;
     listing.listing_id = listing_id;
@@ -837,8 +1873,19 @@ pub fn create_auction(
     listing.highest_bidder = None;
     listing.allow_offers = false;
     listing.royalty_basis_points = event.royalty_basis_points;
+    listing.index_segment = None;
+    listing.bond_basis_points = 0;
+    listing.payment_window = 0;
+    listing.payment_finalized = false;
+    listing.payment_mint = payment_mint;
     listing.bump = *ctx.bumps.get("listing").unwrap();
     
+    // Record the listing in the event's index when a segment is supplied
+    if let Some(event_listing_index) = &mut ctx.accounts.event_listing_index {
+        event_listing_index.add_listing(listing.key())?;
+        listing.index_segment = Some(event_listing_index.segment);
+    }
+    
     // Create auction history account if provided
     if let Some(auction_history) = &mut ctx.accounts.auction_history {
         auction_history.listing = listing.key();
@@ -880,6 +1927,7 @@ pub fn create_dutch_auction(
     if !ticket.transferable {
         return err!(TicketError::NotTransferable);
     }
+    ticket.require_complete_metadata(&ctx.accounts.event, &ctx.accounts.ticket_type)?;
     
     // Check that the ticket is valid
     if ticket.status != TicketStatus::Valid {
@@ -894,6 +1942,37 @@ pub fn create_dutch_auction(
     // Get the event account to read royalty information
     let event = &ctx.accounts.event;
     
+    // Enforce the event's price floor on the lowest price the auction can reach
+    if end_price < event.min_listing_price {
+        return err!(MarketplaceError::PriceBelowMinimum);
+    }
+    
+    // ...and the resale cap on the highest
+    if event.max_listing_price > 0 && start_price > event.max_listing_price {
+        return err!(MarketplaceError::PriceAboveMaximum);
+    }
+    
+    // Never let the price decay to nothing, and respect the event's ending floor
+    if end_price == 0 {
+        return err!(MarketplaceError::ZeroEndingPrice);
+    }
+    if end_price < event.min_ending_price.floor_for(start_price) {
+        return err!(MarketplaceError::EndingPriceBelowFloor);
+    }
+    
+    // Block relists that fall inside the cooldown after a canceled auction
+    ctx.accounts.last_listing_attempt.record_listing(
+        ticket.mint,
+        ctx.accounts.owner.key(),
+        event.relist_cooldown,
+        current_time,
+        *ctx.bumps.get("last_listing_attempt").unwrap(),
+    )?;
+    
+    // Sellers can only ask for payment in a mint the marketplace accepts
+    let payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, payment_mint)?;
+    
     // Initialize the listing
     let listing = &mut ctx.accounts.listing;
     listing.listing_id = listing_id;
@@ -912,8 +1991,19 @@ pub fn create_dutch_auction(
     listing.highest_bidder = None;
     listing.allow_offers = false;
     listing.royalty_basis_points = event.royalty_basis_points;
+    listing.index_segment = None;
+    listing.bond_basis_points = 0;
+    listing.payment_window = 0;
+    listing.payment_finalized = false;
+    listing.payment_mint = payment_mint;
     listing.bump = *ctx.bumps.get("listing").unwrap();
     
+    // Record the listing in the event's index when a segment is supplied
+    if let Some(event_listing_index) = &mut ctx.accounts.event_listing_index {
+        event_listing_index.add_listing(listing.key())?;
+        listing.index_segment = Some(event_listing_index.segment);
+    }
+    
 // Create auction history account if provided
     if let Some(auction_history) = &mut ctx.accounts.auction_history {
         auction_history.listing = listing.key();
@@ -959,6 +2049,21 @@ pub fn cancel_listing(
                 ListingStatus::AuctionExpired
             };
             
+            remove_from_event_listing_index(
+                listing,
+                listing.key(),
+                ctx.accounts.event_listing_index.as_mut(),
+            )?;
+            
+            // Start the relist cooldown for the seller of a canceled auction
+            if listing.listing_type != ListingType::FixedPrice {
+                let last_listing_attempt = &mut ctx.accounts.last_listing_attempt;
+                last_listing_attempt.mint = ctx.accounts.ticket.mint;
+                last_listing_attempt.seller = ctx.accounts.owner.key();
+                last_listing_attempt.auction_canceled_at = Some(Clock::get()?.unix_timestamp);
+                last_listing_attempt.bump = *ctx.bumps.get("last_listing_attempt").unwrap();
+            }
+            
             // Emit event
             emit!(ListingCanceledEvent {
                 listing: listing.key(),
@@ -972,15 +2077,60 @@ pub fn cancel_listing(
     }
 }
 
-/// Purchase a fixed-price listing
+/// Change the price of an active fixed-price listing. Sellers can always lower
+/// it; raising it requires the event to allow increases.
+pub fn update_listing_price(
+    ctx: Context<UpdateListingPrice>,
+    new_price: u64,
+) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+    let event = &ctx.accounts.event;
+    
+    if listing.listing_type != ListingType::FixedPrice || listing.status != ListingStatus::Active {
+        return err!(MarketplaceError::InvalidListingStatus);
+    }
+    
+    if new_price > listing.price && !event.allow_listing_price_increase {
+        return err!(MarketplaceError::PriceIncreaseNotAllowed);
+    }
+    
+    // The new price must still fit the event's floor and resale cap
+    if new_price < event.min_listing_price {
+        return err!(MarketplaceError::PriceBelowMinimum);
+    }
+    if event.max_listing_price > 0 && new_price > event.max_listing_price {
+        return err!(MarketplaceError::PriceAboveMaximum);
+    }
+    
+    let old_price = listing.price;
+    listing.price = new_price;
+    
+    emit!(ListingPriceUpdated {
+        listing: listing.key(),
+        owner: ctx.accounts.owner.key(),
+        old_price,
+        new_price,
+    });
+    
+    Ok(())
+}
+
+/// Purchase a fixed-price listing, or a Dutch auction at its current price.
+/// The purchase fails if the price charged would exceed `max_price`.
 pub fn purchase_listing(
     ctx: Context<PurchaseListing>,
+    max_price: Option<u64>,
 ) -> Result<()> {
     let listing = &ctx.accounts.listing;
     let ticket = &mut ctx.accounts.ticket;
+    let is_dutch_auction = listing.listing_type == ListingType::DutchAuction;
     
-    // Check if listing is active
-    if listing.status != ListingStatus::Active {
+    // Check if listing is active; a Dutch auction can be bought outright until a bid lands
+    if is_dutch_auction {
+        if listing.status != ListingStatus::AuctionActive || listing.highest_bid.is_some() {
+            return err!(TicketError::ListingInactive);
+        }
+    } else if listing.status != ListingStatus::Active {
         return err!(TicketError::ListingInactive);
     }
     
@@ -997,11 +2147,36 @@ pub fn purchase_listing(
         return err!(TicketError::TicketOwnerChanged);
     }
     
+    // A seller buying their own listing would only inflate the ticket's resale count
+    if ctx.accounts.buyer.key() == listing.owner {
+        return err!(MarketplaceError::CannotBuyOwnListing);
+    }
+    
+    // Regulated events only sell to KYC-approved wallets
+    ctx.accounts.event.require_kyc_approved(ctx.accounts.kyc_registry.as_deref(), &ctx.accounts.buyer.key())?;
+    
     // Check that the ticket is valid
     if ticket.status != TicketStatus::Valid {
         return err!(TicketError::InvalidTicket);
     }
     
+    // Sales can't hand the ticket over during the event's transfer freeze
+    if ctx.accounts.event.is_transfer_frozen(Clock::get()?.unix_timestamp) {
+        return err!(TicketError::TransfersFrozen);
+    }
+    
+    // Each sale counts toward the event's resale cap; gifts don't
+    ticket.record_resale(&ctx.accounts.event)?;
+    
+    // Payment must be in the listing's mint, and one the marketplace accepts
+    let payment_mint = ctx.accounts.payment_from_account.mint;
+    if ctx.accounts.payment_to_account.mint != payment_mint
+        || listing.payment_mint.is_some_and(|mint| mint != payment_mint)
+    {
+        return err!(TicketError::InvalidPaymentToken);
+    }
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, Some(payment_mint))?;
+    
     // Transfer the NFT token
     let transfer_ix = token::Transfer {
         from: ctx.accounts.from_token_account.to_account_info(),
@@ -1019,15 +2194,32 @@ pub fn purchase_listing(
     
     // Update ticket owner
     let previous_owner = ticket.owner;
+    let acquired_at = ticket.last_transfer_at;
+    let current_time = Clock::get()?.unix_timestamp;
     ticket.owner = ctx.accounts.buyer.key();
+    ticket.last_transfer_at = current_time;
+    ticket.distributed_at = 0;
     
-    // Process payment
-    let payment_amount = listing.price;
+    // Process payment, at the decayed price for a Dutch auction
+    let payment_amount = if is_dutch_auction {
+        listing.dutch_price_at(current_time)
+    } else {
+        listing.price
+    };
+    if max_price.is_some_and(|max_price| payment_amount > max_price) {
+        return err!(MarketplaceError::SlippageToleranceExceeded);
+    }
     
-    // Calculate royalty amount
-    let royalty_amount = if listing.royalty_basis_points > 0 {
+    // Calculate royalty amount, escalated if the seller is flipping the ticket
+    let royalty_basis_points = resale_royalty_basis_points(
+        listing,
+        &ctx.accounts.event,
+        acquired_at,
+        current_time,
+    );
+    let royalty_amount = if royalty_basis_points > 0 {
         (payment_amount as u128)
-            .checked_mul(listing.royalty_basis_points as u128)
+            .checked_mul(royalty_basis_points as u128)
             .unwrap_or(0)
             .checked_div(10000)
             .unwrap_or(0) as u64
@@ -1084,6 +2276,11 @@ pub fn purchase_listing(
     // Update listing status
     let listing_mut = &mut ctx.accounts.listing;
     listing_mut.status = ListingStatus::Sold;
+    remove_from_event_listing_index(
+        listing_mut,
+        listing_mut.key(),
+        ctx.accounts.event_listing_index.as_mut(),
+    )?;
     
     // Emit purchase event
     emit!(ListingPurchasedEvent {
@@ -1098,6 +2295,109 @@ pub fn purchase_listing(
     Ok(())
 }
 
+/// Organizer buys back a fixed-price listing at the event's buyback price,
+/// paid from the organizer's treasury
+pub fn buyback_listing(ctx: Context<BuybackListing>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let ticket = &mut ctx.accounts.ticket;
+    let event = &ctx.accounts.event;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    if listing.listing_type != ListingType::FixedPrice || listing.status != ListingStatus::Active {
+        return err!(TicketError::ListingInactive);
+    }
+    
+    if let Some(expiry) = listing.expiry {
+        if current_time > expiry {
+            return err!(TicketError::ListingExpired);
+        }
+    }
+    
+    if ticket.status != TicketStatus::Valid {
+        return err!(TicketError::InvalidTicket);
+    }
+    
+    if !event.is_buyback_open(current_time) {
+        return err!(TicketError::BuybackNotOpen);
+    }
+    
+    // The ticket goes back to the organizer rather than to a fan, so neither
+    // the transfer freeze nor the resale cap applies
+    
+    let payment_mint = ctx.accounts.treasury_token_account.mint;
+    if ctx.accounts.payment_to_account.mint != payment_mint
+        || listing.payment_mint.is_some_and(|mint| mint != payment_mint)
+    {
+        return err!(TicketError::InvalidPaymentToken);
+    }
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, Some(payment_mint))?;
+    
+    // Transfer the NFT token to the organizer
+    let transfer_ix = token::Transfer {
+        from: ctx.accounts.from_token_account.to_account_info(),
+        to: ctx.accounts.to_token_account.to_account_info(),
+        authority: ctx.accounts.seller.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_ix,
+        ),
+        1, // NFTs have an amount of 1
+    )?;
+    
+    let previous_owner = ticket.owner;
+    ticket.owner = ctx.accounts.organizer.key();
+    ticket.last_transfer_at = current_time;
+    ticket.distributed_at = 0;
+    
+    // The organizer is the royalty recipient, so the seller is paid the full
+    // buyback price with no royalty withheld
+    let payment_amount = event.buyback_price;
+    let payment_ix = token::Transfer {
+        from: ctx.accounts.treasury_token_account.to_account_info(),
+        to: ctx.accounts.payment_to_account.to_account_info(),
+        authority: ctx.accounts.organizer.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            payment_ix,
+        ),
+        payment_amount,
+    )?;
+    
+    if let Some(transfer_record) = &mut ctx.accounts.transfer_record {
+        transfer_record.add_transfer(
+            previous_owner,
+            ctx.accounts.organizer.key(),
+            payment_amount,
+            current_time,
+            TransferType::Sale,
+        )?;
+    }
+    
+    let listing_mut = &mut ctx.accounts.listing;
+    listing_mut.status = ListingStatus::Sold;
+    remove_from_event_listing_index(
+        listing_mut,
+        listing_mut.key(),
+        ctx.accounts.event_listing_index.as_mut(),
+    )?;
+    
+    emit!(TicketBoughtBackEvent {
+        listing: listing.key(),
+        ticket: ticket.key(),
+        organizer: ctx.accounts.organizer.key(),
+        seller: listing.owner,
+        price: payment_amount,
+    });
+    
+    Ok(())
+}
+
 /// Place a bid on an auction
 pub fn place_bid(
     ctx: Context<PlaceBid>,
@@ -1119,29 +2419,20 @@ pub fn place_bid(
         }
     }
     
+    // Every bid is escrowed in the auction's currency; an auction listed
+    // without one is locked to the mint of its first bid
+    let bid_mint = ctx.accounts.payment_from_account.mint;
+    if ctx.accounts.escrow_account.mint != bid_mint
+        || listing.payment_mint.is_some_and(|mint| mint != bid_mint)
+    {
+        return err!(TicketError::InvalidPaymentToken);
+    }
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, Some(bid_mint))?;
+    listing.payment_mint = Some(bid_mint);
+    
     // For Dutch auctions, calculate current price
     let current_price = if listing.listing_type == ListingType::DutchAuction {
-        let start_time = listing.created_at;
-        let end_time = listing.expiry.unwrap();
-        let start_price = listing.price;
-        let end_price = listing.ending_price.unwrap();
-        
-        if current_time >= end_time {
-            end_price
-        } else {
-            // Linear interpolation of price
-            let elapsed = current_time - start_time;
-            let duration = end_time - start_time;
-            let price_diff = start_price.saturating_sub(end_price);
-            
-            let price_reduction = (price_diff as u128)
-                .checked_mul(elapsed as u128)
-                .unwrap_or(0)
-                .checked_div(duration as u128)
-                .unwrap_or(0) as u64;
-            
-            start_price.saturating_sub(price_reduction)
-        }
+        listing.dutch_price_at(current_time)
     } else {
         // For regular auctions, check against highest bid
         match listing.highest_bid {
@@ -1153,6 +2444,12 @@ pub fn place_bid(
         }
     };
     
+    // Equal bids keep the earlier bidder in the lead, so a bid that only ties
+    // the standing highest bid (possible with a zero increment) is rejected
+    if listing.listing_type == ListingType::Auction && listing.highest_bid == Some(bid_amount) {
+        return err!(MarketplaceError::BidTiesHighest);
+    }
+    
     // Check if bid is high enough
     if bid_amount < current_price {
         return err!(MarketplaceError::BidTooLow);
@@ -1169,7 +2466,8 @@ pub fn place_bid(
     }
     
     // Process payment for new bid
-    // This will be held in escrow until auction ends or outbid
+    // This will be held in escrow until auction ends or outbid; in bond mode
+    // only the bond is escrowed now and the winner pays the rest later
     let payment_ix = token::Transfer {
         from: ctx.accounts.payment_from_account.to_account_info(),
         to: ctx.accounts.escrow_account.to_account_info(),
@@ -1181,7 +2479,7 @@ pub fn place_bid(
             ctx.accounts.token_program.to_account_info(),
             payment_ix,
         ),
-        bid_amount,
+        listing.escrow_amount_for(bid_amount),
     )?;
     
     // Refund previous bidder if there was one
@@ -1211,7 +2509,7 @@ pub fn place_bid(
                         refund_ix,
                         signer,
                     ),
-                    listing.highest_bid.unwrap(),
+                    listing.escrow_amount_for(listing.highest_bid.unwrap()),
                 )?;
             }
         }
@@ -1267,6 +2565,11 @@ pub fn settle_auction(
     if listing.highest_bid.is_none() || listing.highest_bidder.is_none() {
         // No bids, update listing status
         listing.status = ListingStatus::AuctionExpired;
+        remove_from_event_listing_index(
+            listing,
+            listing.key(),
+            ctx.accounts.event_listing_index.as_mut(),
+        )?;
         
         // Emit event
         emit!(AuctionExpiredEvent {
@@ -1277,6 +2580,75 @@ pub fn settle_auction(
         return Ok(());
     }
     
+    // In bond mode the winner must have paid in full, or have let the payment
+    // window lapse, in which case the seller keeps the bond and the NFT
+    if listing.bond_basis_points > 0 && !listing.payment_finalized {
+        if current_time <= listing.payment_deadline().unwrap_or(i64::MAX) {
+            return err!(MarketplaceError::PaymentWindowActive);
+        }
+        
+        let bond_amount = listing.escrow_amount_for(listing.highest_bid.unwrap());
+        let forfeit_ix = token::Transfer {
+            from: ctx.accounts.escrow_account.to_account_info(),
+            to: ctx.accounts.payment_to_account.to_account_info(),
+            authority: ctx.accounts.escrow_authority.to_account_info(),
+        };
+        
+        let escrow_bump = ctx.accounts.escrow_authority_bump;
+        let seeds = &[
+            b"escrow",
+            listing.key().as_ref(),
+            &[escrow_bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                forfeit_ix,
+                signer,
+            ),
+            bond_amount,
+        )?;
+        
+        listing.status = ListingStatus::AuctionExpired;
+        remove_from_event_listing_index(
+            listing,
+            listing.key(),
+            ctx.accounts.event_listing_index.as_mut(),
+        )?;
+        
+        emit!(BondForfeitedEvent {
+            listing: listing.key(),
+            winner: listing.highest_bidder.unwrap(),
+            seller: ctx.accounts.seller.key(),
+            bond_amount,
+        });
+        
+        return Ok(());
+    }
+    
+    // The ticket can only go to the standing highest bidder, who holds the
+    // lead from the earliest of any equal bids
+    let winner = listing.highest_bidder.unwrap();
+    if ctx.accounts.to_token_account.owner != winner {
+        return err!(MarketplaceError::NotAuctionWinner);
+    }
+    
+    // Regulated events only hand tickets to KYC-approved wallets
+    ctx.accounts.event.require_kyc_approved(ctx.accounts.kyc_registry.as_deref(), &winner)?;
+    
+    // The escrowed bids are paid out in the auction's currency
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, Some(ctx.accounts.escrow_account.mint))?;
+    
+    // Sales can't hand the ticket over during the event's transfer freeze
+    if ctx.accounts.event.is_transfer_frozen(current_time) {
+        return err!(TicketError::TransfersFrozen);
+    }
+    
+    // Each sale counts toward the event's resale cap; gifts don't
+    ticket.record_resale(&ctx.accounts.event)?;
+    
     // Transfer the NFT token to the highest bidder
     let transfer_ix = token::Transfer {
         from: ctx.accounts.from_token_account.to_account_info(),
@@ -1294,15 +2666,24 @@ pub fn settle_auction(
     
     // Update ticket owner
     let previous_owner = ticket.owner;
+    let acquired_at = ticket.last_transfer_at;
     ticket.owner = listing.highest_bidder.unwrap();
+    ticket.last_transfer_at = current_time;
+    ticket.distributed_at = 0;
     
     // Process payment
     let payment_amount = listing.highest_bid.unwrap();
     
-    // Calculate royalty amount
-    let royalty_amount = if listing.royalty_basis_points > 0 {
+    // Calculate royalty amount, escalated if the seller is flipping the ticket
+    let royalty_basis_points = resale_royalty_basis_points(
+        listing,
+        &ctx.accounts.event,
+        acquired_at,
+        current_time,
+    );
+    let royalty_amount = if royalty_basis_points > 0 {
         (payment_amount as u128)
-            .checked_mul(listing.royalty_basis_points as u128)
+            .checked_mul(royalty_basis_points as u128)
             .unwrap_or(0)
             .checked_div(10000)
             .unwrap_or(0) as u64
@@ -1369,6 +2750,11 @@ pub fn settle_auction(
     
     // Update listing status
     listing.status = ListingStatus::AuctionEnded;
+    remove_from_event_listing_index(
+        listing,
+        listing.key(),
+        ctx.accounts.event_listing_index.as_mut(),
+    )?;
     
     // Emit auction settled event
     emit!(AuctionSettledEvent {
@@ -1383,6 +2769,91 @@ pub fn settle_auction(
     Ok(())
 }
 
+/// Put an auction in bond mode, so bids escrow only a share up front
+pub fn set_auction_bond(
+    ctx: Context<SetAuctionBond>,
+    bond_basis_points: u16,
+    payment_window: i64,
+) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+    
+    if listing.listing_type != ListingType::Auction || listing.status != ListingStatus::AuctionActive {
+        return err!(MarketplaceError::InvalidListingStatus);
+    }
+    
+    // Bidders must not see the terms change under them
+    if listing.highest_bid.is_some() {
+        return err!(MarketplaceError::BondLockedAfterBids);
+    }
+    
+    if bond_basis_points > 10000 || (bond_basis_points > 0 && payment_window <= 0) {
+        return err!(MarketplaceError::InvalidBondConfiguration);
+    }
+    
+    listing.bond_basis_points = bond_basis_points;
+    listing.payment_window = if bond_basis_points > 0 { payment_window } else { 0 };
+    
+    emit!(AuctionBondSetEvent {
+        listing: listing.key(),
+        bond_basis_points,
+        payment_window: listing.payment_window,
+    });
+    
+    Ok(())
+}
+
+/// Pay the remainder of a winning bond-mode bid into escrow
+pub fn finalize_winning_payment(
+    ctx: Context<FinalizeWinningPayment>,
+) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+    
+    if listing.bond_basis_points == 0 || listing.status != ListingStatus::AuctionActive {
+        return err!(MarketplaceError::InvalidListingStatus);
+    }
+    
+    if listing.payment_finalized {
+        return err!(MarketplaceError::PaymentAlreadyFinalized);
+    }
+    
+    // Payment opens once bidding closes and lasts for the payment window
+    let current_time = Clock::get()?.unix_timestamp;
+    match listing.expiry {
+        Some(expiry) if current_time > expiry => {},
+        _ => return err!(MarketplaceError::AuctionNotEnded),
+    }
+    if current_time > listing.payment_deadline().unwrap_or(i64::MAX) {
+        return err!(MarketplaceError::PaymentWindowClosed);
+    }
+    
+    let winning_bid = listing.highest_bid.unwrap();
+    let remaining = winning_bid.saturating_sub(listing.escrow_amount_for(winning_bid));
+    
+    let payment_ix = token::Transfer {
+        from: ctx.accounts.payment_from_account.to_account_info(),
+        to: ctx.accounts.escrow_account.to_account_info(),
+        authority: ctx.accounts.winner.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            payment_ix,
+        ),
+        remaining,
+    )?;
+    
+    listing.payment_finalized = true;
+    
+    emit!(WinningPaymentFinalizedEvent {
+        listing: listing.key(),
+        winner: ctx.accounts.winner.key(),
+        amount_paid: remaining,
+    });
+    
+    Ok(())
+}
+
 /// Make an offer on a listing
 pub fn make_offer(
     ctx: Context<MakeOffer>,
@@ -1406,6 +2877,9 @@ pub fn make_offer(
         return err!(TicketError::InvalidAttribute);
     }
     
+    // Offers are paid in the listing's currency, which must still be accepted
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, listing.payment_mint)?;
+    
     // Calculate expiry
     let current_time = Clock::get()?.unix_timestamp;
     let expiry = expiry_seconds.map(|seconds| current_time + seconds);
@@ -1464,11 +2938,31 @@ pub fn accept_offer(
         return err!(TicketError::TicketOwnerChanged);
     }
     
+    // Regulated events only sell to KYC-approved wallets
+    ctx.accounts.event.require_kyc_approved(ctx.accounts.kyc_registry.as_deref(), &offer.buyer)?;
+    
     // Check that the ticket is valid
     if ticket.status != TicketStatus::Valid {
         return err!(TicketError::InvalidTicket);
     }
     
+    // The offer is paid in the listing's currency, which must be accepted
+    let payment_mint = ctx.accounts.payment_from_account.mint;
+    if ctx.accounts.payment_to_account.mint != payment_mint
+        || listing.payment_mint.is_some_and(|mint| mint != payment_mint)
+    {
+        return err!(TicketError::InvalidPaymentToken);
+    }
+    require_accepted_payment_mint(&ctx.accounts.accepted_payment_mints, Some(payment_mint))?;
+    
+    // Sales can't hand the ticket over during the event's transfer freeze
+    if ctx.accounts.event.is_transfer_frozen(Clock::get()?.unix_timestamp) {
+        return err!(TicketError::TransfersFrozen);
+    }
+    
+    // Each sale counts toward the event's resale cap; gifts don't
+    ticket.record_resale(&ctx.accounts.event)?;
+    
     // Transfer the NFT token
     let transfer_ix = token::Transfer {
         from: ctx.accounts.from_token_account.to_account_info(),
@@ -1486,15 +2980,25 @@ pub fn accept_offer(
     
     // Update ticket owner
     let previous_owner = ticket.owner;
+    let acquired_at = ticket.last_transfer_at;
+    let current_time = Clock::get()?.unix_timestamp;
     ticket.owner = offer.buyer;
+    ticket.last_transfer_at = current_time;
+    ticket.distributed_at = 0;
     
     // Process payment
     let payment_amount = offer.amount;
     
-    // Calculate royalty amount
-    let royalty_amount = if listing.royalty_basis_points > 0 {
+    // Calculate royalty amount, escalated if the seller is flipping the ticket
+    let royalty_basis_points = resale_royalty_basis_points(
+        listing,
+        &ctx.accounts.event,
+        acquired_at,
+        current_time,
+    );
+    let royalty_amount = if royalty_basis_points > 0 {
         (payment_amount as u128)
-            .checked_mul(listing.royalty_basis_points as u128)
+            .checked_mul(royalty_basis_points as u128)
             .unwrap_or(0)
             .checked_div(10000)
             .unwrap_or(0) as u64
@@ -1553,6 +3057,11 @@ pub fn accept_offer(
     
     // Update listing status
     listing.status = ListingStatus::Sold;
+    remove_from_event_listing_index(
+        listing,
+        listing.key(),
+        ctx.accounts.event_listing_index.as_mut(),
+    )?;
     
     // Emit offer accepted event
     emit!(OfferAcceptedEvent {
@@ -1588,8 +3097,8 @@ pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
     instructions::marketplace::cancel_listing(ctx)
 }
 
-pub fn purchase_listing(ctx: Context<PurchaseListing>) -> Result<()> {
-    instructions::marketplace::purchase_listing(ctx)
+pub fn purchase_listing(ctx: Context<PurchaseListing>, max_price: Option<u64>) -> Result<()> {
+    instructions::marketplace::purchase_listing(ctx, max_price)
 }
 
 pub fn place_bid(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
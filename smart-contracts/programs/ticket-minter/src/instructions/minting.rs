@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo, InitializeMint};
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
 use solana_program::program::invoke_signed;
+use solana_program::program_pack::Pack;
 use mpl_token_metadata::{
     instruction::{create_metadata_accounts_v3, create_master_edition_v3},
     state::{DataV2, Creator},
@@ -10,11 +12,20 @@ use mpl_token_metadata::{
 
 use crate::{Event, TicketType, Ticket, TicketStatus, TicketAttribute, TicketError};
 
-/// Mints a new ticket NFT
-pub fn mint_ticket(
-    ctx: Context<crate::MintTicket>,
+/// Accounts supplied per ticket in `remaining_accounts` for `mint_tickets`:
+/// mint PDA, mint authority PDA, buyer token account, metadata, master edition, ticket PDA
+pub const ACCOUNTS_PER_TICKET: usize = 6;
+
+/// Maximum number of tickets minted in one `mint_tickets` call
+pub const MAX_TICKETS_PER_MINT: u8 = 5;
+
+/// Mints a new ticket NFT. If the event splits primary sales, the payee
+/// accounts are passed in `remaining_accounts` in the event's payee order.
+pub fn mint_ticket<'info>(
+    ctx: Context<'_, '_, '_, 'info, crate::MintTicket<'info>>,
     metadata_uri: String,
     custom_attributes: Option<Vec<TicketAttribute>>,
+    presale_code: Option<String>,
 ) -> Result<()> {
     let event = &ctx.accounts.event;
     let ticket_type = &mut ctx.accounts.ticket_type;
@@ -42,22 +53,27 @@ pub fn mint_ticket(
         return err!(TicketError::EventAtCapacity);
     }
     
+    // Regulated events only sell to KYC-approved wallets
+    event.require_kyc_approved(ctx.accounts.kyc_registry.as_deref(), &buyer.key())?;
+    
+    // Before the public sale, presale types need their code
+    ticket_type.check_presale_access(presale_code.as_deref(), Clock::get()?.unix_timestamp)?;
+    
+    // Per-wallet caps are counted by mint_tickets, so capped types must go through it
+    if ticket_type.max_per_wallet > 0 {
+        return err!(TicketError::WalletCapRequiresBatchMint);
+    }
+    
     // Check payment (simplified - you may want to handle different payment tokens)
     if ticket_type.price > 0 {
-        // Transfer payment from buyer to organizer
-        let transfer_ix = solana_program::system_instruction::transfer(
-            &buyer.key(),
-            &ctx.accounts.organizer.key(),
+        // Pay the organizer, or split between the event's primary payees
+        pay_primary_sale(
+            event,
+            &buyer.to_account_info(),
+            &ctx.accounts.organizer.to_account_info(),
+            ctx.remaining_accounts,
+            &ctx.accounts.system_program.to_account_info(),
             ticket_type.price,
-        );
-        
-        solana_program::program::invoke(
-            &transfer_ix,
-            &[
-                buyer.to_account_info(),
-                ctx.accounts.organizer.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
         )?;
     }
     
@@ -79,74 +95,20 @@ pub fn mint_ticket(
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
     token::mint_to(cpi_ctx, 1)?;
     
-    // Create metadata account
-    let ticket_name = format!("{} - {}", event.name, ticket_type.name);
-    let ticket_symbol = event.symbol.clone();
-    
-    // Prepare creators array (event organizer gets royalties)
-    let creators = vec![Creator {
-        address: event.organizer,
-        verified: false,
-        share: 100,
-    }];
-    
-    // Create metadata
-    let metadata_infos = vec![
+    create_ticket_metadata(
         ctx.accounts.metadata_account.to_account_info(),
-        ctx.accounts.mint.to_account_info(),
-        ctx.accounts.ticket_mint_authority.to_account_info(),
-        buyer.to_account_info(),
-        ctx.accounts.token_metadata_program.to_account_info(),
-        ctx.accounts.system_program.to_account_info(),
-        ctx.accounts.rent.to_account_info(),
-    ];
-    
-    let metadata_ix = create_metadata_accounts_v3(
-        TOKEN_METADATA_ID,
-        ctx.accounts.metadata_account.key(),
-        ctx.accounts.mint.key(),
-        ctx.accounts.ticket_mint_authority.key(),
-        buyer.key(),
-        ctx.accounts.ticket_mint_authority.key(),
-        ticket_name.clone(),
-        ticket_symbol,
-        metadata_uri.clone(),
-        Some(creators),
-        event.royalty_basis_points,
-        true, // update_authority_is_signer
-        true, // is_mutable
-        None, // collection
-        None, // uses
-        None, // collection_details
-    );
-    
-    invoke_signed(&metadata_ix, &metadata_infos, signer)?;
-    
-    // Create master edition
-    let master_edition_infos = vec![
         ctx.accounts.master_edition.to_account_info(),
         ctx.accounts.mint.to_account_info(),
         ctx.accounts.ticket_mint_authority.to_account_info(),
-        ctx.accounts.ticket_mint_authority.to_account_info(),
         buyer.to_account_info(),
-        ctx.accounts.metadata_account.to_account_info(),
         ctx.accounts.token_metadata_program.to_account_info(),
         ctx.accounts.system_program.to_account_info(),
         ctx.accounts.rent.to_account_info(),
-    ];
-    
-    let master_edition_ix = create_master_edition_v3(
-        TOKEN_METADATA_ID,
-        ctx.accounts.master_edition.key(),
-        ctx.accounts.mint.key(),
-        ctx.accounts.ticket_mint_authority.key(),
-        ctx.accounts.ticket_mint_authority.key(),
-        ctx.accounts.metadata_account.key(),
-        buyer.key(),
-        Some(0), // max_supply (0 = unlimited)
-    );
-    
-    invoke_signed(&master_edition_ix, &master_edition_infos, signer)?;
+        signer,
+        event,
+        &ticket_type.name,
+        metadata_uri.clone(),
+    )?;
     
     // Initialize ticket account
     let current_time = Clock::get()?.unix_timestamp;
@@ -157,10 +119,16 @@ pub fn mint_ticket(
     ticket.serial_number = ticket_type.sold + 1;
     ticket.metadata_uri = metadata_uri;
     ticket.status = TicketStatus::Valid;
-    ticket.transferable = true; // Can be changed later by organizer
+    // Events requiring complete metadata keep incomplete tickets from trading
+    ticket.transferable = !event.require_complete_metadata || ticket.has_complete_metadata(ticket_type);
     ticket.used_at = None;
+    ticket.last_transfer_at = current_time;
     ticket.custom_attributes = custom_attributes.unwrap_or_default();
     ticket.bump = *ctx.bumps.get("ticket").unwrap();
+    ticket.metadata_schema_version = event.ticket_schema_version;
+    ticket.event_details_hash = Ticket::issued_details_hash(event, event.ticket_schema_version);
+    ticket.resale_count = 0;
+    ticket.distributed_at = 0;
     
     // Update counts
     ticket_type.sold += 1;
@@ -176,3 +144,366 @@ pub fn mint_ticket(
     
     Ok(())
 }
+
+/// Mints `quantity` tickets of one type to the buyer, each its own NFT. The
+/// per-ticket accounts are passed in `remaining_accounts`, `ACCOUNTS_PER_TICKET` each,
+/// followed by the event's primary payee accounts if it splits primary sales.
+/// Mints are PDAs of the ticket type and serial number, so the buyer is the only signer.
+pub fn mint_tickets<'info>(
+    ctx: Context<'_, '_, '_, 'info, crate::MintTickets<'info>>,
+    quantity: u8,
+    metadata_uri: String,
+    presale_code: Option<String>,
+) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    let event = &ctx.accounts.event;
+    let ticket_type = &ctx.accounts.ticket_type;
+    let buyer = &ctx.accounts.buyer;
+    
+    if quantity == 0 || quantity > MAX_TICKETS_PER_MINT {
+        return err!(TicketError::InvalidMintQuantity);
+    }
+    let ticket_account_count = quantity as usize * ACCOUNTS_PER_TICKET;
+    if remaining.len() != ticket_account_count + event.primary_payees.len() {
+        return err!(TicketError::InvalidBatchMintAccounts);
+    }
+    let (ticket_accounts, payee_accounts) = remaining.split_at(ticket_account_count);
+    
+    if !event.active {
+        return err!(TicketError::EventInactive);
+    }
+    if !ticket_type.active {
+        return err!(TicketError::TicketTypeInactive);
+    }
+    let current_time = Clock::get()?.unix_timestamp;
+    ticket_type.check_presale_access(presale_code.as_deref(), current_time)?;
+    
+    // The whole purchase must fit in the remaining supply and capacity
+    let count = quantity as u32;
+    if ticket_type.sold + count > ticket_type.quantity {
+        return err!(TicketError::TicketTypeSoldOut);
+    }
+    if event.tickets_issued + count > event.max_tickets {
+        return err!(TicketError::EventAtCapacity);
+    }
+    event.require_kyc_approved(ctx.accounts.kyc_registry.as_deref(), &buyer.key())?;
+    
+    // Set up the buyer's mint record the first time they buy this type
+    let record = &mut ctx.accounts.wallet_mint_record;
+    if record.wallet == Pubkey::default() {
+        record.ticket_type = ticket_type.key();
+        record.wallet = buyer.key();
+        record.minted = 0;
+        record.bump = *ctx.bumps.get("wallet_mint_record").unwrap();
+    }
+    if ticket_type.max_per_wallet > 0 && record.minted + count > ticket_type.max_per_wallet {
+        return err!(TicketError::WalletMintLimitExceeded);
+    }
+    
+    let total_price = ticket_type.price
+        .checked_mul(quantity as u64)
+        .ok_or(TicketError::IncorrectPaymentAmount)?;
+    if total_price > 0 {
+        pay_primary_sale(
+            event,
+            &buyer.to_account_info(),
+            &ctx.accounts.organizer.to_account_info(),
+            payee_accounts,
+            &ctx.accounts.system_program.to_account_info(),
+            total_price,
+        )?;
+    }
+    
+    let rent = Rent::get()?;
+    for (index, accounts) in ticket_accounts.chunks(ACCOUNTS_PER_TICKET).enumerate() {
+        let serial_number = ticket_type.sold + index as u32 + 1;
+        let mint_info = &accounts[0];
+        let mint_authority_info = &accounts[1];
+        let token_account_info = &accounts[2];
+        let metadata_info = &accounts[3];
+        let master_edition_info = &accounts[4];
+        let ticket_info = &accounts[5];
+        
+        let ticket_type_key = ticket_type.key();
+        let serial_bytes = serial_number.to_le_bytes();
+        let (mint_address, mint_bump) = Pubkey::find_program_address(
+            &[b"ticket_mint", ticket_type_key.as_ref(), &serial_bytes],
+            ctx.program_id,
+        );
+        let (mint_authority, mint_authority_bump) = Pubkey::find_program_address(
+            &[b"ticket_authority", mint_info.key.as_ref()],
+            ctx.program_id,
+        );
+        let (ticket_address, ticket_bump) = Pubkey::find_program_address(
+            &[b"ticket", mint_info.key.as_ref()],
+            ctx.program_id,
+        );
+        if mint_info.key() != mint_address
+            || mint_authority_info.key() != mint_authority
+            || ticket_info.key() != ticket_address
+            || token_account_info.key() != get_associated_token_address(&buyer.key(), mint_info.key)
+        {
+            return err!(TicketError::InvalidBatchMintAccounts);
+        }
+        
+        // Create and initialize the ticket's mint
+        let mint_seeds = &[b"ticket_mint", ticket_type_key.as_ref(), &serial_bytes, &[mint_bump]];
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: buyer.to_account_info(),
+                    to: mint_info.clone(),
+                },
+                &[&mint_seeds[..]],
+            ),
+            rent.minimum_balance(anchor_spl::token::spl_token::state::Mint::LEN),
+            anchor_spl::token::spl_token::state::Mint::LEN as u64,
+            &ctx.accounts.token_program.key(),
+        )?;
+        token::initialize_mint(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeMint {
+                    mint: mint_info.clone(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+            ),
+            0,
+            &mint_authority,
+            Some(&mint_authority),
+        )?;
+        
+        // Mint the NFT to the buyer's associated token account
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: buyer.to_account_info(),
+                associated_token: token_account_info.clone(),
+                authority: buyer.to_account_info(),
+                mint: mint_info.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+        
+        let mint_key = mint_info.key();
+        let mint_authority_seeds = &[
+            b"ticket_authority",
+            mint_key.as_ref(),
+            &[mint_authority_bump],
+        ];
+        let signer = &[&mint_authority_seeds[..]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: mint_info.clone(),
+                    to: token_account_info.clone(),
+                    authority: mint_authority_info.clone(),
+                },
+                signer,
+            ),
+            1,
+        )?;
+        
+        create_ticket_metadata(
+            metadata_info.clone(),
+            master_edition_info.clone(),
+            mint_info.clone(),
+            mint_authority_info.clone(),
+            buyer.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            signer,
+            event,
+            &ticket_type.name,
+            metadata_uri.clone(),
+        )?;
+        
+        // Create the ticket account at its PDA
+        let ticket_seeds = &[b"ticket", mint_key.as_ref(), &[ticket_bump]];
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: buyer.to_account_info(),
+                    to: ticket_info.clone(),
+                },
+                &[&ticket_seeds[..]],
+            ),
+            rent.minimum_balance(Ticket::SPACE),
+            Ticket::SPACE as u64,
+            ctx.program_id,
+        )?;
+        
+        let mut ticket = Ticket {
+            mint: mint_key,
+            event: event.key(),
+            ticket_type: ticket_type.key(),
+            owner: buyer.key(),
+            serial_number,
+            metadata_uri: metadata_uri.clone(),
+            status: TicketStatus::Valid,
+            transferable: true,
+            used_at: None,
+            last_transfer_at: current_time,
+            custom_attributes: Vec::new(),
+            bump: ticket_bump,
+            metadata_schema_version: event.ticket_schema_version,
+            event_details_hash: Ticket::issued_details_hash(event, event.ticket_schema_version),
+            resale_count: 0,
+            distributed_at: 0,
+        };
+        ticket.transferable = !event.require_complete_metadata || ticket.has_complete_metadata(ticket_type);
+        ticket.try_serialize(&mut &mut ticket_info.try_borrow_mut_data()?[..])?;
+    }
+    
+    // Update counts
+    ctx.accounts.wallet_mint_record.minted += count;
+    ctx.accounts.ticket_type.sold += count;
+    ctx.accounts.event.tickets_issued += count;
+    
+    msg!(
+        "Minted {} tickets for event {} to {}",
+        quantity,
+        ctx.accounts.event.name,
+        ctx.accounts.buyer.key()
+    );
+    
+    Ok(())
+}
+
+/// Creates the Metaplex metadata and master edition for a freshly minted ticket
+pub(crate) fn create_ticket_metadata<'info>(
+    metadata_account: AccountInfo<'info>,
+    master_edition: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    mint_authority: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    token_metadata_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    rent: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    event: &Event,
+    ticket_type_name: &str,
+    metadata_uri: String,
+) -> Result<()> {
+    let ticket_name = format!("{} - {}", event.name, ticket_type_name);
+    let ticket_symbol = event.symbol.clone();
+    
+    // Prepare creators array (event organizer gets royalties)
+    let creators = vec![Creator {
+        address: event.organizer,
+        verified: false,
+        share: 100,
+    }];
+    
+    // Create metadata
+    let metadata_ix = create_metadata_accounts_v3(
+        TOKEN_METADATA_ID,
+        metadata_account.key(),
+        mint.key(),
+        mint_authority.key(),
+        payer.key(),
+        mint_authority.key(),
+        ticket_name,
+        ticket_symbol,
+        metadata_uri,
+        Some(creators),
+        event.royalty_basis_points,
+        true, // update_authority_is_signer
+        true, // is_mutable
+        None, // collection
+        None, // uses
+        None, // collection_details
+    );
+    
+    invoke_signed(
+        &metadata_ix,
+        &[
+            metadata_account.clone(),
+            mint.clone(),
+            mint_authority.clone(),
+            payer.clone(),
+            token_metadata_program.clone(),
+            system_program.clone(),
+            rent.clone(),
+        ],
+        signer,
+    )?;
+    
+    // Create master edition
+    let master_edition_ix = create_master_edition_v3(
+        TOKEN_METADATA_ID,
+        master_edition.key(),
+        mint.key(),
+        mint_authority.key(),
+        mint_authority.key(),
+        metadata_account.key(),
+        payer.key(),
+        Some(0), // max_supply (0 = unlimited)
+    );
+    
+    invoke_signed(
+        &master_edition_ix,
+        &[
+            master_edition,
+            mint,
+            mint_authority.clone(),
+            mint_authority,
+            payer,
+            metadata_account,
+            token_metadata_program,
+            system_program,
+            rent,
+        ],
+        signer,
+    )?;
+    
+    Ok(())
+}
+
+/// Pays a primary sale of `amount` from the buyer. Events without a split pay
+/// the organizer; otherwise each payee account, passed in the event's payee
+/// order, receives its share.
+fn pay_primary_sale<'info>(
+    event: &Event,
+    buyer: &AccountInfo<'info>,
+    organizer: &AccountInfo<'info>,
+    payee_accounts: &[AccountInfo<'info>],
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if event.primary_payees.is_empty() {
+        return transfer_lamports(buyer, organizer, system_program, amount);
+    }
+    
+    if payee_accounts.len() != event.primary_payees.len() {
+        return err!(TicketError::PrimaryPayeeMismatch);
+    }
+    for ((recipient, share), payee_account) in event.primary_split(amount).into_iter().zip(payee_accounts) {
+        if payee_account.key() != recipient {
+            return err!(TicketError::PrimaryPayeeMismatch);
+        }
+        if share > 0 {
+            transfer_lamports(buyer, payee_account, system_program, share)?;
+        }
+    }
+    Ok(())
+}
+
+/// Moves lamports from a signer with a system transfer
+fn transfer_lamports<'info>(
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    solana_program::program::invoke(
+        &solana_program::system_instruction::transfer(from.key, to.key, amount),
+        &[from.clone(), to.clone(), system_program.clone()],
+    )?;
+    Ok(())
+}
@@ -7,6 +7,7 @@ pub mod minting;
 pub mod verification;
 pub mod transfers;
 pub mod marketplace;
+pub mod kyc;
 
 pub use events::*;
 pub use ticket_types::*;
@@ -15,3 +16,4 @@ pub use minting::*;
 pub use verification::*;
 pub use transfers::*;
 pub use marketplace::*;
+pub use kyc::*;
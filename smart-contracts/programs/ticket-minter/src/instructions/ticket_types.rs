@@ -40,6 +40,9 @@ pub fn create_ticket_type(
     ticket_type.price = price;
     ticket_type.quantity = quantity;
     ticket_type.sold = 0;
+    ticket_type.max_per_wallet = 0;
+    ticket_type.presale_code_hash = None;
+    ticket_type.public_sale_start = 0;
     ticket_type.attributes = attributes;
     ticket_type.active = true;
     ticket_type.bump = *ctx.bumps.get("ticket_type").unwrap();
@@ -101,6 +104,47 @@ pub fn update_ticket_type(
     Ok(())
 }
 
+/// Caps how many tickets of a type one wallet may mint
+pub fn set_max_per_wallet(
+    ctx: Context<UpdateTicketType>,
+    max_per_wallet: u32,
+) -> Result<()> {
+    let ticket_type = &mut ctx.accounts.ticket_type;
+    ticket_type.max_per_wallet = max_per_wallet;
+    
+    msg!(
+        "Set per-wallet cap for ticket type '{}' to {}",
+        ticket_type.name,
+        max_per_wallet
+    );
+    
+    Ok(())
+}
+
+/// Restricts a ticket type to holders of a presale code until its public
+/// sale starts. Only the code's hash is stored; `None` removes the presale.
+pub fn set_presale_code(
+    ctx: Context<UpdateTicketType>,
+    presale_code_hash: Option<[u8; 32]>,
+    public_sale_start: i64,
+) -> Result<()> {
+    if presale_code_hash.is_some() && public_sale_start <= 0 {
+        return err!(TicketError::InvalidPresaleConfig);
+    }
+    
+    let ticket_type = &mut ctx.accounts.ticket_type;
+    ticket_type.presale_code_hash = presale_code_hash;
+    ticket_type.public_sale_start = public_sale_start;
+    
+    msg!(
+        "Set presale for ticket type '{}' until {}",
+        ticket_type.name,
+        public_sale_start
+    );
+    
+    Ok(())
+}
+
 /// Context for updating a ticket type
 #[derive(Accounts)]
 pub struct UpdateTicketType<'info> {
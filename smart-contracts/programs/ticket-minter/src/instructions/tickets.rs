@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token;
-use crate::{Ticket, TicketStatus, TicketError, Event};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use crate::{Ticket, TicketStatus, TicketError, TicketType, Event};
+use super::minting::create_ticket_metadata;
 
 /// Updates a ticket's status
 pub fn update_ticket_status(
@@ -29,9 +31,13 @@ pub fn update_ticket_status(
     let old_status = ticket.status;
     ticket.status = new_status;
     
-    // If status is now Used, update the used_at timestamp
+    // If status is now Used, update the used_at timestamp, counting the
+    // ticket's first check-in
     if new_status == TicketStatus::Used && ticket.used_at.is_none() {
         ticket.used_at = Some(current_time);
+        if old_status == TicketStatus::Valid {
+            ctx.accounts.event.record_check_ins(1)?;
+        }
     }
     
     msg!(
@@ -44,6 +50,28 @@ pub fn update_ticket_status(
     Ok(())
 }
 
+/// Upgrades a ticket's on-chain fields to its event's metadata schema.
+/// Tickets already on the event's schema are left unchanged.
+pub fn migrate_ticket_metadata(
+    ctx: Context<crate::MigrateTicketMetadata>,
+) -> Result<()> {
+    let ticket = &mut ctx.accounts.ticket;
+    let from_version = ticket.schema_version();
+    
+    if ticket.migrate_metadata(&ctx.accounts.event) {
+        msg!(
+            "Migrated ticket {} metadata from schema v{} to v{}",
+            ticket.serial_number,
+            from_version,
+            ticket.metadata_schema_version
+        );
+    } else {
+        msg!("Ticket {} metadata is already on schema v{}", ticket.serial_number, from_version);
+    }
+    
+    Ok(())
+}
+
 /// Transfers a ticket to a new owner
 pub fn transfer_ticket(
     ctx: Context<crate::TransferTicket>,
@@ -60,6 +88,22 @@ pub fn transfer_ticket(
         return err!(TicketError::InvalidTicket);
     }
     
+    // Tickets on an outdated metadata schema must be migrated first
+    if !ticket.meets_schema(&ctx.accounts.event) {
+        return err!(TicketError::TicketSchemaOutdated);
+    }
+    
+    // The event may require complete metadata, even of tickets minted before it did
+    ticket.require_complete_metadata(&ctx.accounts.event, &ctx.accounts.ticket_type)?;
+    
+    // No transfers in the freeze window around the event
+    if ctx.accounts.event.is_transfer_frozen(Clock::get()?.unix_timestamp) {
+        return err!(TicketError::TransfersFrozen);
+    }
+    
+    // Regulated events only allow transfers to KYC-approved wallets
+    ctx.accounts.event.require_kyc_approved(ctx.accounts.kyc_registry.as_deref(), &ctx.accounts.to.key())?;
+    
     // Transfer the token
     let transfer_ix = token::Transfer {
         from: ctx.accounts.from_token_account.to_account_info(),
@@ -77,7 +121,17 @@ pub fn transfer_ticket(
     
     // Update ticket owner
     let previous_owner = ticket.owner;
+    let current_time = Clock::get()?.unix_timestamp;
     ticket.owner = ctx.accounts.to.key();
+    ticket.last_transfer_at = current_time;
+    
+    // Transfers out of the organizer's wallet are distributions, which the
+    // organizer may claw back for a while; any later transfer ends that
+    ticket.distributed_at = if previous_owner == ctx.accounts.event.organizer {
+        current_time
+    } else {
+        0
+    };
     
     msg!(
         "Transferred ticket #{} from {} to {}",
@@ -109,6 +163,75 @@ pub fn revoke_ticket(
     Ok(())
 }
 
+/// Refunds a ticket's face value to its holder once the organizer has opened
+/// refunds for a postponed event; the ticket no longer admits anyone
+pub fn refund_ticket(
+    ctx: Context<RefundTicket>,
+) -> Result<()> {
+    if !ctx.accounts.event.refunds_open {
+        return err!(TicketError::RefundsNotOpen);
+    }
+    
+    let ticket = &mut ctx.accounts.ticket;
+    if ticket.status != TicketStatus::Valid {
+        return err!(TicketError::InvalidTicket);
+    }
+    
+    let amount = ctx.accounts.ticket_type.price;
+    if amount > 0 {
+        let refund_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.organizer.to_account_info(),
+            to: ctx.accounts.holder.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), refund_ix),
+            amount,
+        )?;
+    }
+    
+    ticket.status = TicketStatus::Revoked;
+    
+    msg!(
+        "Refunded ticket #{} for event '{}' ({} lamports)",
+        ticket.serial_number,
+        ctx.accounts.event.name,
+        amount
+    );
+    
+    Ok(())
+}
+
+/// Context for refunding a ticket
+#[derive(Accounts)]
+pub struct RefundTicket<'info> {
+    /// The event this ticket belongs to
+    #[account(has_one = organizer)]
+    pub event: Account<'info, Event>,
+    
+    /// The ticket to refund
+    #[account(
+        mut,
+        constraint = ticket.event == event.key() @ TicketError::TicketEventMismatch,
+        constraint = ticket.owner == holder.key(),
+    )]
+    pub ticket: Account<'info, Ticket>,
+    
+    /// The ticket's type, whose price is refunded
+    #[account(constraint = ticket_type.key() == ticket.ticket_type)]
+    pub ticket_type: Account<'info, TicketType>,
+    
+    /// The ticket holder receiving the refund
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    /// The event organizer paying the refund
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
 /// Context for revoking a ticket
 #[derive(Accounts)]
 pub struct RevokeTicket<'info> {
@@ -127,6 +250,201 @@ pub struct RevokeTicket<'info> {
     pub organizer: Signer<'info>,
 }
 
+/// Returns a mistakenly distributed ticket to the organizer. Once minted, the
+/// NFT's mint and freeze authorities belong to its master edition, so the
+/// holder's copy can't be frozen or moved by the program. Instead the
+/// distributed ticket is revoked and a replacement NFT with the same serial
+/// number is minted to the organizer.
+pub fn clawback_ticket(
+    ctx: Context<ClawbackTicket>,
+) -> Result<()> {
+    let event = &ctx.accounts.event;
+    let ticket = &mut ctx.accounts.ticket;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // Used tickets have already been redeemed and can't be taken back
+    if ticket.status != TicketStatus::Valid {
+        return err!(TicketError::InvalidTicket);
+    }
+    
+    // Only tickets still held since a distribution, within the event's window
+    if event.distribution_clawback_window == 0
+        || ticket.distributed_at == 0
+        || current_time > ticket.distributed_at.saturating_add(event.distribution_clawback_window)
+    {
+        return err!(TicketError::ClawbackWindowClosed);
+    }
+    
+    // Mint the replacement NFT to the organizer
+    let replacement_mint_key = ctx.accounts.replacement_mint.key();
+    let mint_authority_bump = *ctx.bumps.get("replacement_mint_authority").unwrap();
+    let mint_authority_seeds = &[
+        b"ticket_authority",
+        replacement_mint_key.as_ref(),
+        &[mint_authority_bump],
+    ];
+    let signer = &[&mint_authority_seeds[..]];
+    
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                mint: ctx.accounts.replacement_mint.to_account_info(),
+                to: ctx.accounts.organizer_token_account.to_account_info(),
+                authority: ctx.accounts.replacement_mint_authority.to_account_info(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+    
+    create_ticket_metadata(
+        ctx.accounts.replacement_metadata.to_account_info(),
+        ctx.accounts.replacement_master_edition.to_account_info(),
+        ctx.accounts.replacement_mint.to_account_info(),
+        ctx.accounts.replacement_mint_authority.to_account_info(),
+        ctx.accounts.organizer.to_account_info(),
+        ctx.accounts.token_metadata_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.rent.to_account_info(),
+        signer,
+        event,
+        &ctx.accounts.ticket_type.name,
+        ticket.metadata_uri.clone(),
+    )?;
+    
+    // The replacement carries the distributed ticket's details
+    let replacement = &mut ctx.accounts.replacement_ticket;
+    replacement.mint = replacement_mint_key;
+    replacement.event = ticket.event;
+    replacement.ticket_type = ticket.ticket_type;
+    replacement.owner = event.organizer;
+    replacement.serial_number = ticket.serial_number;
+    replacement.metadata_uri = ticket.metadata_uri.clone();
+    replacement.status = TicketStatus::Valid;
+    replacement.transferable = ticket.transferable;
+    replacement.used_at = None;
+    replacement.last_transfer_at = current_time;
+    replacement.custom_attributes = ticket.custom_attributes.clone();
+    replacement.bump = *ctx.bumps.get("replacement_ticket").unwrap();
+    replacement.metadata_schema_version = ticket.metadata_schema_version;
+    replacement.event_details_hash = ticket.event_details_hash;
+    replacement.resale_count = ticket.resale_count;
+    replacement.distributed_at = 0;
+    
+    // The misdirected NFT stays with the recipient but no longer admits anyone
+    ticket.status = TicketStatus::Revoked;
+    ticket.distributed_at = 0;
+    
+    msg!(
+        "Clawed back ticket #{} from {}; replacement {} minted to organizer {}",
+        ticket.serial_number,
+        ticket.owner,
+        replacement_mint_key,
+        event.organizer
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClawbackTicket<'info> {
+    /// The event this ticket belongs to
+    #[account(has_one = organizer)]
+    pub event: Account<'info, Event>,
+    
+    /// The distributed ticket
+    #[account(
+        mut,
+        constraint = ticket.event == event.key(),
+        constraint = ticket.mint == mint.key(),
+    )]
+    pub ticket: Account<'info, Ticket>,
+    
+    /// The distributed ticket's type, which names the replacement NFT
+    #[account(constraint = ticket_type.key() == ticket.ticket_type)]
+    pub ticket_type: Account<'info, TicketType>,
+    
+    /// The mint of the distributed ticket NFT
+    pub mint: Account<'info, Mint>,
+    
+    /// The recipient's token account, which must still hold the ticket
+    #[account(
+        constraint = holder_token_account.owner == ticket.owner,
+        constraint = holder_token_account.mint == mint.key(),
+        constraint = holder_token_account.amount == 1
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+    
+    /// The mint of the replacement ticket NFT
+    #[account(
+        init,
+        payer = organizer,
+        mint::decimals = 0,
+        mint::authority = replacement_mint_authority,
+        mint::freeze_authority = replacement_mint_authority,
+    )]
+    pub replacement_mint: Account<'info, Mint>,
+    
+    /// The PDA that has authority over the replacement mint
+    #[account(
+        seeds = [b"ticket_authority", replacement_mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA, safe because we control the seeds
+    pub replacement_mint_authority: UncheckedAccount<'info>,
+    
+    /// The organizer's token account to receive the replacement
+    #[account(
+        init_if_needed,
+        payer = organizer,
+        associated_token::mint = replacement_mint,
+        associated_token::authority = organizer,
+    )]
+    pub organizer_token_account: Account<'info, TokenAccount>,
+    
+    /// The replacement's metadata account through Metaplex
+    /// CHECK: Created through CPI to Metaplex
+    #[account(mut)]
+    pub replacement_metadata: UncheckedAccount<'info>,
+    
+    /// The replacement's master edition account through Metaplex
+    /// CHECK: Created through CPI to Metaplex
+    #[account(mut)]
+    pub replacement_master_edition: UncheckedAccount<'info>,
+    
+    /// The replacement ticket account
+    #[account(
+        init,
+        payer = organizer,
+        space = Ticket::SPACE,
+        seeds = [b"ticket", replacement_mint.key().as_ref()],
+        bump
+    )]
+    pub replacement_ticket: Account<'info, Ticket>,
+    
+    /// The event organizer
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    
+    /// Metaplex Token Metadata program
+    /// CHECK: Checked against the Metaplex program id
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Associated Token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
 /// Sets a ticket's transferability
 pub fn set_ticket_transferability(
     ctx: Context<SetTicketTransferability>,
@@ -134,6 +452,11 @@ pub fn set_ticket_transferability(
 ) -> Result<()> {
     let ticket = &mut ctx.accounts.ticket;
     
+    // Events can require complete metadata so broken tickets never trade
+    if transferable && !ticket.transferable {
+        ticket.require_complete_metadata(&ctx.accounts.event, &ctx.accounts.ticket_type)?;
+    }
+    
     // Update transferability
     let old_transferable = ticket.transferable;
     ticket.transferable = transferable;
@@ -163,17 +486,28 @@ pub struct SetTicketTransferability<'info> {
     )]
     pub ticket: Account<'info, Ticket>,
     
+    /// The ticket's type, which names the NFT
+    #[account(constraint = ticket_type.key() == ticket.ticket_type)]
+    pub ticket_type: Account<'info, TicketType>,
+    
     /// The event organizer
     pub organizer: Signer<'info>,
 }
 
-/// Batch update multiple tickets' status
+/// Batch update multiple tickets' status, returning how many were updated.
+/// Batches larger than the event's max batch size are rejected so callers
+/// paginate instead of exhausting the compute budget.
 pub fn batch_update_ticket_status(
     ctx: Context<BatchUpdateTicketStatus>,
     new_status: TicketStatus,
-) -> Result<()> {
+) -> Result<u32> {
+    if ctx.remaining_accounts.len() > ctx.accounts.event.max_batch_size as usize {
+        return err!(TicketError::BatchOperationLimitExceeded);
+    }
+    
     let current_time = Clock::get()?.unix_timestamp;
-    let mut updated_count = 0;
+    let mut updated_count: u32 = 0;
+    let mut checked_in: u32 = 0;
     
     // Update each ticket in remaining accounts
     for account_info in ctx.remaining_accounts.iter() {
@@ -195,11 +529,15 @@ pub fn batch_update_ticket_status(
             }
             
             // Update status
+            let old_status = ticket.status;
             ticket.status = new_status;
             
-            // If marking as used, set timestamp
+            // If marking as used, set timestamp and count the first check-in
             if new_status == TicketStatus::Used && ticket.used_at.is_none() {
                 ticket.used_at = Some(current_time);
+                if old_status == TicketStatus::Valid {
+                    checked_in += 1;
+                }
             }
             
             // Save the ticket account
@@ -208,6 +546,8 @@ pub fn batch_update_ticket_status(
         }
     }
     
+    ctx.accounts.event.record_check_ins(checked_in)?;
+    
     msg!(
         "Batch updated {} tickets to status {:?} for event '{}'",
         updated_count,
@@ -215,14 +555,14 @@ pub fn batch_update_ticket_status(
         ctx.accounts.event.name
     );
     
-    Ok(())
+    Ok(updated_count)
 }
 
 /// Context for batch updating ticket status
 #[derive(Accounts)]
 pub struct BatchUpdateTicketStatus<'info> {
     /// The event these tickets belong to
-    #[account(constraint = event.is_validator(validator.key()))]
+    #[account(mut, constraint = event.is_validator(validator.key()))]
     pub event: Account<'info, Event>,
     
     /// The validator performing the update
@@ -7,7 +7,7 @@ use anchor_spl::token;
 use anchor_spl::associated_token::{self, AssociatedToken};
 use solana_program::program::invoke_signed;
 use solana_program::system_instruction;
-use crate::{Ticket, TicketStatus, TicketError, Event, TransferRecord};
+use crate::{Ticket, TicketStatus, TicketError, TicketType, Event, KycRegistry, TransferRecord};
 
 /// Transfers a ticket to a new owner
 pub fn transfer_ticket(
@@ -46,6 +46,8 @@ pub fn transfer_ticket(
     // Update ticket owner
     let previous_owner = ticket.owner;
     ticket.owner = to;
+    ticket.last_transfer_at = Clock::get()?.unix_timestamp;
+    ticket.distributed_at = 0;
     
     // Record transfer in history if available
     if let Some(transfer_record) = &mut ctx.accounts.transfer_record {
@@ -139,6 +141,7 @@ pub fn create_transfer_listing(
     if !ticket.transferable {
         return err!(TicketError::NotTransferable);
     }
+    ticket.require_complete_metadata(&ctx.accounts.event, &ctx.accounts.ticket_type)?;
     
     // Only valid tickets can be listed
     if ticket.status != TicketStatus::Valid {
@@ -220,6 +223,17 @@ pub fn accept_transfer_listing(
         }
     }
     
+    // Sales can't hand the ticket over during the event's transfer freeze
+    if ctx.accounts.event.is_transfer_frozen(Clock::get()?.unix_timestamp) {
+        return err!(TicketError::TransfersFrozen);
+    }
+    
+    // Regulated events only sell to KYC-approved wallets
+    ctx.accounts.event.require_kyc_approved(ctx.accounts.kyc_registry.as_deref(), &ctx.accounts.buyer.key())?;
+    
+    // Count the sale toward the event's resale cap
+    ticket.record_resale(&ctx.accounts.event)?;
+    
     // Transfer the token
     let transfer_ix = token::Transfer {
         from: ctx.accounts.from_token_account.to_account_info(),
@@ -238,6 +252,8 @@ pub fn accept_transfer_listing(
     // Update ticket owner
     let previous_owner = ticket.owner;
     ticket.owner = ctx.accounts.buyer.key();
+    ticket.last_transfer_at = Clock::get()?.unix_timestamp;
+    ticket.distributed_at = 0;
     
     // Record transfer in history if available
     if let Some(transfer_record) = &mut ctx.accounts.transfer_record {
@@ -267,31 +283,30 @@ pub fn accept_transfer_listing(
         )?;
         
         // If royalties are configured, transfer royalties
-        if let Some(event) = &ctx.accounts.event {
-            if event.royalty_basis_points > 0 && ctx.accounts.royalty_account.is_some() {
-                // Calculate royalty amount
-                let royalty_amount = (listing.price as u128)
-                    .checked_mul(event.royalty_basis_points as u128)
-                    .unwrap_or(0)
-                    .checked_div(10000)
-                    .unwrap_or(0) as u64;
+        let event = &ctx.accounts.event;
+        if event.royalty_basis_points > 0 && ctx.accounts.royalty_account.is_some() {
+            // Calculate royalty amount
+            let royalty_amount = (listing.price as u128)
+                .checked_mul(event.royalty_basis_points as u128)
+                .unwrap_or(0)
+                .checked_div(10000)
+                .unwrap_or(0) as u64;
+            
+            if royalty_amount > 0 {
+                // Transfer royalty
+                let royalty_ix = token::Transfer {
+                    from: ctx.accounts.payment_from_account.to_account_info(),
+                    to: ctx.accounts.royalty_account.as_ref().unwrap().to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
                 
-                if royalty_amount > 0 {
-                    // Transfer royalty
-                    let royalty_ix = token::Transfer {
-                        from: ctx.accounts.payment_from_account.to_account_info(),
-                        to: ctx.accounts.royalty_account.as_ref().unwrap().to_account_info(),
-                        authority: ctx.accounts.buyer.to_account_info(),
-                    };
-                    
-                    token::transfer(
-                        CpiContext::new(
-                            ctx.accounts.token_program.to_account_info(),
-                            royalty_ix,
-                        ),
-                        royalty_amount,
-                    )?;
-                }
+                token::transfer(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        royalty_ix,
+                    ),
+                    royalty_amount,
+                )?;
             }
         }
     }
@@ -340,6 +355,14 @@ pub struct CreateTransferListing<'info> {
     #[account(constraint = ticket.owner == owner.key())]
     pub ticket: Account<'info, Ticket>,
     
+    /// The event the ticket belongs to
+    #[account(constraint = event.key() == ticket.event @ TicketError::TicketEventMismatch)]
+    pub event: Account<'info, Event>,
+    
+    /// The ticket's type, which names the NFT
+    #[account(constraint = ticket_type.key() == ticket.ticket_type)]
+    pub ticket_type: Account<'info, TicketType>,
+    
     /// The listing account
     #[account(
         init,
@@ -444,8 +467,16 @@ pub struct AcceptTransferListing<'info> {
     #[account(mut)]
     pub royalty_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
     
-    /// Optional event account
-    pub event: Option<Account<'info, Event>>,
+    /// The event the ticket belongs to
+    #[account(constraint = event.key() == ticket.event @ TicketError::TicketEventMismatch)]
+    pub event: Account<'info, Event>,
+    
+    /// The event's KYC registry (required when the event has KYC enabled)
+    #[account(
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump = kyc_registry.bump
+    )]
+    pub kyc_registry: Option<Account<'info, KycRegistry>>,
     
     /// Optional transfer record account
     #[account(mut)]
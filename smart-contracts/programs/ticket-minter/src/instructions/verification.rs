@@ -4,7 +4,7 @@
 //! and performing ownership-based validations.
 
 use anchor_lang::prelude::*;
-use crate::{Ticket, TicketStatus, TicketError};
+use crate::{AttendanceStats, Ticket, TicketStatus, TicketError, TicketVerificationResult};
 
 /// Verifies a ticket for entry to an event
 pub fn verify_ticket_for_entry(
@@ -53,10 +53,12 @@ pub fn verify_and_mark_used(
     // First verify the ticket is valid for entry
     verify_ticket_for_entry(ctx.reborrow())?;
     
-    // Then mark it as used
+    // Then mark it as used. Only valid tickets get this far, so each ticket
+    // is checked in once
     let ticket = &mut ctx.accounts.ticket;
     ticket.status = TicketStatus::Used;
     ticket.used_at = Some(Clock::get()?.unix_timestamp);
+    ctx.accounts.event.record_check_ins(1)?;
     
     msg!("Ticket verified and marked as used");
     Ok(())
@@ -86,13 +88,79 @@ pub fn verify_user_has_ticket_for_event(
     Ok(())
 }
 
+/// Verifies the tickets passed as remaining accounts for entry, marking each
+/// valid one used. A ticket that fails doesn't fail the batch; its result is
+/// reported instead, in the order the tickets were passed. A valid ticket
+/// passed read-only can't be marked used and is reported invalid.
+pub fn batch_verify_tickets(
+    ctx: Context<BatchVerifyTickets>,
+) -> Result<Vec<TicketVerificationResult>> {
+    let event = &ctx.accounts.event;
+    if ctx.remaining_accounts.len() > event.max_batch_size as usize {
+        return err!(TicketError::BatchOperationLimitExceeded);
+    }
+    
+    // Entry is only possible while the event is running
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > event.end_date {
+        return err!(TicketError::EventEnded);
+    }
+    if current_time < event.start_date {
+        return err!(TicketError::EventNotStarted);
+    }
+    
+    let mut results = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut checked_in = 0;
+    for account_info in ctx.remaining_accounts.iter() {
+        let result = match Account::<Ticket>::try_from(account_info) {
+            Ok(mut ticket) if ticket.event == event.key() => match ticket.status {
+                TicketStatus::Valid if account_info.is_writable => {
+                    ticket.status = TicketStatus::Used;
+                    ticket.used_at = Some(current_time);
+                    ticket.exit(ctx.program_id)?;
+                    checked_in += 1;
+                    TicketVerificationResult::Valid
+                }
+                TicketStatus::Used => TicketVerificationResult::Used,
+                _ => TicketVerificationResult::Invalid,
+            },
+            _ => TicketVerificationResult::Invalid,
+        };
+        results.push(result);
+    }
+    
+    msg!(
+        "Batch verified {} tickets for event: {}",
+        results.len(),
+        event.name
+    );
+    ctx.accounts.event.record_check_ins(checked_in)?;
+    
+    Ok(results)
+}
+
+/// Context for verifying a batch of tickets for entry
+#[derive(Accounts)]
+pub struct BatchVerifyTickets<'info> {
+    /// The event the tickets are for
+    #[account(mut, constraint = event.is_validator(validator.key()))]
+    pub event: Account<'info, crate::Event>,
+    
+    /// The validator scanning the tickets
+    pub validator: Signer<'info>,
+    
+    // Ticket accounts are passed as remaining_accounts
+}
+
 /// Context for verifying a ticket for entry
 #[derive(Accounts)]
 pub struct VerifyTicketForEntry<'info> {
     /// The event the ticket is for
+    #[account(mut)]
     pub event: Account<'info, crate::Event>,
     
     /// The ticket to verify
+    #[account(mut, constraint = ticket.event == event.key() @ TicketError::TicketEventMismatch)]
     pub ticket: Account<'info, Ticket>,
     
     /// The owner of the ticket
@@ -117,6 +185,25 @@ pub struct VerifyEventAccess<'info> {
     pub user: Signer<'info>,
 }
 
+/// Reports how many of an event's tickets have been issued and checked in
+pub fn get_attendance_stats(
+    ctx: Context<ViewAttendanceStats>,
+) -> Result<AttendanceStats> {
+    let event = &ctx.accounts.event;
+    
+    Ok(AttendanceStats {
+        total_issued: event.tickets_issued,
+        checked_in_count: event.checked_in_count,
+    })
+}
+
+/// Context for reading an event's attendance stats
+#[derive(Accounts)]
+pub struct ViewAttendanceStats<'info> {
+    /// The event to report on
+    pub event: Account<'info, crate::Event>,
+}
+
 /// Verifies the ownership of multiple tickets for the same user
 pub fn verify_multiple_tickets(
     ctx: Context<VerifyMultipleTickets>,
@@ -205,7 +292,9 @@ pub fn generate_verification_challenge(
     verification.ticket = ticket.key();
     verification.event = event.key();
     verification.owner = ticket.owner;
-    verification.expiration = current_time + 300; // 5 minutes expiration
+    verification.payer = ctx.accounts.validator.key();
+    verification.expiration = current_time + event.challenge_ttl;
+    verification.bump = *ctx.bumps.get("verification_account").unwrap();
     
     msg!("Generated verification challenge");
     Ok(())
@@ -224,6 +313,8 @@ pub struct VerificationChallenge {
     pub event: Pubkey,
     /// Owner of the ticket
     pub owner: Pubkey,
+    /// Validator who paid the account's rent
+    pub payer: Pubkey,
     /// Expiration timestamp
     pub expiration: i64,
     /// Random nonce for this challenge
@@ -240,10 +331,11 @@ impl VerificationChallenge {
         32 + // ticket
         32 + // event
         32 + // owner
+        32 + // payer
         8 + // expiration
         8 + // nonce
         1 + // bump
-        50; // padding
+        18; // padding
 }
 
 /// Context for generating a verification challenge
@@ -277,3 +369,31 @@ pub struct GenerateChallenge<'info> {
     /// System program
     pub system_program: Program<'info, System>,
 }
+
+/// Closes a verification challenge once it has expired, returning its rent to
+/// the validator who paid for it. Anyone may call this.
+pub fn close_expired_challenge(
+    ctx: Context<CloseExpiredChallenge>,
+) -> Result<()> {
+    let challenge = &ctx.accounts.verification_account;
+    
+    // Challenges can still be signed until they expire
+    if Clock::get()?.unix_timestamp <= challenge.expiration {
+        return err!(TicketError::ChallengeNotExpired);
+    }
+    
+    msg!("Closed expired verification challenge for ticket {}", challenge.ticket);
+    Ok(())
+}
+
+/// Context for closing an expired verification challenge
+#[derive(Accounts)]
+pub struct CloseExpiredChallenge<'info> {
+    /// The expired challenge, closed back to its payer
+    #[account(mut, has_one = payer, close = payer)]
+    pub verification_account: Account<'info, VerificationChallenge>,
+    
+    /// The validator who paid for the challenge
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+}
@@ -1,11 +1,12 @@
 /// Mints a new ticket NFT
-    pub fn mint_ticket(
-        ctx: Context<MintTicket>,
+    pub fn mint_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintTicket<'info>>,
         metadata_uri: String,
         custom_attributes: Option<Vec<TicketAttribute>>,
+        presale_code: Option<String>,
     ) -> Result<()> {
         let ticket_type = &ctx.accounts.ticket_type;
-        let result = instructions::minting::mint_ticket(ctx, metadata_uri, custom_attributes)?;
+        let result = instructions::minting::mint_ticket(ctx, metadata_uri, custom_attributes, presale_code)?;
         
         emit!(TicketMinted {
             ticket: ctx.accounts.ticket.key(),
@@ -20,6 +21,28 @@
         Ok(result)
     }
 
+    /// Mints several tickets of one type in a single purchase, each its own NFT
+    pub fn mint_tickets<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintTickets<'info>>,
+        quantity: u8,
+        metadata_uri: String,
+        presale_code: Option<String>,
+    ) -> Result<()> {
+        let first_serial_number = ctx.accounts.ticket_type.sold + 1;
+        let result = instructions::minting::mint_tickets(ctx, quantity, metadata_uri, presale_code)?;
+        
+        emit!(TicketsMinted {
+            event: ctx.accounts.event.key(),
+            ticket_type: ctx.accounts.ticket_type.key(),
+            owner: ctx.accounts.buyer.key(),
+            quantity,
+            first_serial_number,
+            price: ctx.accounts.ticket_type.price,
+        });
+        
+        Ok(result)
+    }
+
     /// Updates a ticket's status
     pub fn update_ticket_status(
         ctx: Context<UpdateTicketStatus>,
@@ -61,6 +84,27 @@
         Ok(result)
     }
 
+    /// Upgrades a ticket's on-chain fields to its event's metadata schema
+    pub fn migrate_ticket_metadata(
+        ctx: Context<MigrateTicketMetadata>,
+    ) -> Result<()> {
+        let from_version = ctx.accounts.ticket.schema_version();
+        let result = instructions::tickets::migrate_ticket_metadata(ctx)?;
+        
+        let to_version = ctx.accounts.ticket.schema_version();
+        if to_version != from_version {
+            emit!(TicketMetadataMigrated {
+                ticket: ctx.accounts.ticket.key(),
+                event: ctx.accounts.event.key(),
+                from_version,
+                to_version,
+                migrated_at: Clock::get()?.unix_timestamp,
+            });
+        }
+        
+        Ok(result)
+    }
+
     /// Updates an event's details
     pub fn update_event(
         ctx: Context<UpdateEvent>,
@@ -70,18 +114,54 @@
         start_date: Option<i64>,
         end_date: Option<i64>,
     ) -> Result<()> {
+        let previous_details_hash = ctx.accounts.event.details_hash;
         let result = instructions::events::update_event(ctx, name.clone(), description, venue, start_date, end_date)?;
         
         emit!(EventUpdated {
             event: ctx.accounts.event.key(),
             organizer: ctx.accounts.organizer.key(),
             name: name.unwrap_or_else(|| ctx.accounts.event.name.clone()),
+            previous_details_hash,
+            details_hash: ctx.accounts.event.details_hash,
             updated_at: Clock::get()?.unix_timestamp,
         });
         
         Ok(result)
     }
 
+    /// Postpones an event to new dates without invalidating its tickets
+    pub fn postpone_event(
+        ctx: Context<UpdateEvent>,
+        new_start_date: i64,
+        new_end_date: i64,
+        open_refunds: bool,
+    ) -> Result<()> {
+        let result = instructions::events::postpone_event(ctx, new_start_date, new_end_date, open_refunds)?;
+        
+        let event = &ctx.accounts.event;
+        emit!(EventPostponed {
+            event: event.key(),
+            original_start_date: event.original_start_date,
+            original_end_date: event.original_end_date,
+            new_start_date,
+            new_end_date,
+            refunds_open: event.refunds_open,
+            details_hash: event.details_hash,
+            postponed_by: ctx.accounts.organizer.key(),
+            postponed_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Checks that an event's details still match a supplied hash
+    pub fn verify_event_details(
+        ctx: Context<VerifyEventDetails>,
+        details_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::events::verify_event_details(ctx, details_hash)
+    }
+
     /// Adds an authorized validator for an event
     pub fn add_validator(
         ctx: Context<AddValidator>,
@@ -115,6 +195,301 @@
         
         Ok(result)
     }
+
+    /// Sets the relist cooldown applied after an auction is canceled
+    pub fn set_relist_cooldown(
+        ctx: Context<UpdateEvent>,
+        relist_cooldown: i64,
+    ) -> Result<()> {
+        let result = instructions::events::set_relist_cooldown(ctx, relist_cooldown)?;
+        
+        emit!(RelistCooldownUpdated {
+            event: ctx.accounts.event.key(),
+            relist_cooldown,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Sets the most ticket accounts a batch status update may process
+    pub fn set_max_batch_size(
+        ctx: Context<UpdateEvent>,
+        max_batch_size: u16,
+    ) -> Result<()> {
+        let result = instructions::events::set_max_batch_size(ctx, max_batch_size)?;
+        
+        emit!(MaxBatchSizeUpdated {
+            event: ctx.accounts.event.key(),
+            max_batch_size,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
+    /// Sets the metadata schema version tickets of an event must be on
+    pub fn set_ticket_schema_version(
+        ctx: Context<UpdateEvent>,
+        ticket_schema_version: u8,
+    ) -> Result<()> {
+        let result = instructions::events::set_ticket_schema_version(ctx, ticket_schema_version)?;
+        
+        emit!(TicketSchemaVersionUpdated {
+            event: ctx.accounts.event.key(),
+            ticket_schema_version,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
+    /// Sets how long the event's verification challenges stay valid
+    pub fn set_challenge_ttl(
+        ctx: Context<UpdateEvent>,
+        challenge_ttl: i64,
+    ) -> Result<()> {
+        let result = instructions::events::set_challenge_ttl(ctx, challenge_ttl)?;
+        
+        emit!(ChallengeTtlUpdated {
+            event: ctx.accounts.event.key(),
+            challenge_ttl,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
+    /// Caps how many times each ticket of an event may be resold
+    pub fn set_max_resales(
+        ctx: Context<UpdateEvent>,
+        max_resales: u16,
+    ) -> Result<()> {
+        let result = instructions::events::set_max_resales(ctx, max_resales)?;
+        
+        emit!(MaxResalesUpdated {
+            event: ctx.accounts.event.key(),
+            max_resales,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
+    /// Sets the price and window for organizer buybacks of listed tickets
+    pub fn set_buyback_config(
+        ctx: Context<UpdateEvent>,
+        buyback_price: u64,
+        buyback_window: i64,
+    ) -> Result<()> {
+        let result = instructions::events::set_buyback_config(ctx, buyback_price, buyback_window)?;
+        
+        emit!(BuybackConfigUpdated {
+            event: ctx.accounts.event.key(),
+            buyback_price,
+            buyback_window,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
+    /// Sets how long after a distribution the organizer may claw a ticket back
+    pub fn set_distribution_clawback_window(
+        ctx: Context<UpdateEvent>,
+        distribution_clawback_window: i64,
+    ) -> Result<()> {
+        let result = instructions::events::set_distribution_clawback_window(ctx, distribution_clawback_window)?;
+        
+        emit!(DistributionClawbackWindowUpdated {
+            event: ctx.accounts.event.key(),
+            distribution_clawback_window,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
+    /// Sets how primary-sale revenue is split between payees
+    pub fn set_primary_payees(
+        ctx: Context<UpdateEvent>,
+        primary_payees: Vec<PrimaryPayee>,
+    ) -> Result<()> {
+        let result = instructions::events::set_primary_payees(ctx, primary_payees.clone())?;
+        
+        emit!(PrimaryPayeesUpdated {
+            event: ctx.accounts.event.key(),
+            primary_payees,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
+    /// Sets the minimum price for marketplace listings of an event's tickets
+    pub fn set_min_listing_price(
+        ctx: Context<UpdateEvent>,
+        min_listing_price: u64,
+    ) -> Result<()> {
+        let result = instructions::events::set_min_listing_price(ctx, min_listing_price)?;
+        
+        emit!(MinListingPriceUpdated {
+            event: ctx.accounts.event.key(),
+            min_listing_price,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Sets the resale price cap and whether sellers may raise listed prices
+    pub fn set_listing_price_policy(
+        ctx: Context<UpdateEvent>,
+        max_listing_price: u64,
+        allow_price_increase: bool,
+    ) -> Result<()> {
+        let result = instructions::events::set_listing_price_policy(ctx, max_listing_price, allow_price_increase)?;
+        
+        emit!(ListingPricePolicyUpdated {
+            event: ctx.accounts.event.key(),
+            max_listing_price,
+            allow_price_increase,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Sets whether tickets need complete metadata before they can be made transferable
+    pub fn set_require_complete_metadata(
+        ctx: Context<UpdateEvent>,
+        require_complete_metadata: bool,
+    ) -> Result<()> {
+        let result = instructions::events::set_require_complete_metadata(ctx, require_complete_metadata)?;
+        
+        emit!(MetadataRequirementUpdated {
+            event: ctx.accounts.event.key(),
+            require_complete_metadata,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Sets the lowest ending price allowed for an event's Dutch auctions
+    pub fn set_min_ending_price(
+        ctx: Context<UpdateEvent>,
+        min_ending_price: EndingPriceFloor,
+    ) -> Result<()> {
+        let result = instructions::events::set_min_ending_price(ctx, min_ending_price)?;
+        
+        emit!(MinEndingPriceUpdated {
+            event: ctx.accounts.event.key(),
+            min_ending_price,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Sets the escalated royalty schedule for quick resales of an event's tickets
+    pub fn set_flip_royalty(
+        ctx: Context<UpdateEvent>,
+        flip_royalty: FlipRoyaltySchedule,
+    ) -> Result<()> {
+        let result = instructions::events::set_flip_royalty(ctx, flip_royalty)?;
+        
+        emit!(FlipRoyaltyUpdated {
+            event: ctx.accounts.event.key(),
+            flip_royalty,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Sets the window around an event during which ticket transfers are frozen
+    pub fn set_transfer_freeze(
+        ctx: Context<UpdateEvent>,
+        freeze_before: i64,
+        freeze_after: i64,
+    ) -> Result<()> {
+        let result = instructions::events::set_transfer_freeze(ctx, freeze_before, freeze_after)?;
+        
+        emit!(TransferFreezeUpdated {
+            event: ctx.accounts.event.key(),
+            freeze_before,
+            freeze_after,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Enables or disables KYC-restricted transfers for an event
+    pub fn set_kyc_required(
+        ctx: Context<UpdateEvent>,
+        kyc_required: bool,
+    ) -> Result<()> {
+        let result = instructions::kyc::set_kyc_required(ctx, kyc_required)?;
+        
+        emit!(KycRequirementUpdated {
+            event: ctx.accounts.event.key(),
+            kyc_required,
+            updated_by: ctx.accounts.organizer.key(),
+            updated_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Adds a KYC-approved wallet for an event
+    pub fn add_kyc_wallet(
+        ctx: Context<AddKycWallet>,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        let result = instructions::kyc::add_kyc_wallet(ctx, wallet)?;
+        
+        emit!(KycWalletAdded {
+            event: ctx.accounts.event.key(),
+            wallet,
+            added_by: ctx.accounts.organizer.key(),
+            added_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+
+    /// Removes a KYC-approved wallet for an event
+    pub fn remove_kyc_wallet(
+        ctx: Context<RemoveKycWallet>,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        let result = instructions::kyc::remove_kyc_wallet(ctx, wallet)?;
+        
+        emit!(KycWalletRemoved {
+            event: ctx.accounts.event.key(),
+            wallet,
+            removed_by: ctx.accounts.organizer.key(),
+            removed_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
     
     /// Verifies a ticket for entry to an event
     pub fn verify_ticket_for_entry(
@@ -134,6 +509,30 @@
         Ok(result)
     }
     
+    /// Verifies a batch of tickets for entry, marking valid ones used and
+    /// returning each ticket's result instead of failing the whole batch
+    pub fn batch_verify_tickets(
+        ctx: Context<BatchVerifyTickets>,
+    ) -> Result<Vec<TicketVerificationResult>> {
+        let event = ctx.accounts.event.key();
+        let verified_by = ctx.accounts.validator.key();
+        let results = instructions::verification::batch_verify_tickets(ctx)?;
+        
+        let count = |outcome: TicketVerificationResult| {
+            results.iter().filter(|result| **result == outcome).count() as u32
+        };
+        emit!(TicketsBatchVerified {
+            event,
+            valid: count(TicketVerificationResult::Valid),
+            used: count(TicketVerificationResult::Used),
+            invalid: count(TicketVerificationResult::Invalid),
+            verified_by,
+            verified_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(results)
+    }
+    
     /// Verifies a ticket and marks it as used
     pub fn verify_and_mark_used(
         ctx: Context<VerifyTicketForEntry>,
@@ -152,6 +551,13 @@
         Ok(result)
     }
     
+    /// Reports an event's issued and checked-in ticket counts
+    pub fn get_attendance_stats(
+        ctx: Context<ViewAttendanceStats>,
+    ) -> Result<AttendanceStats> {
+        instructions::verification::get_attendance_stats(ctx)
+    }
+    
     /// Verifies user has ticket for event access
     pub fn verify_user_has_ticket_for_event(
         ctx: Context<VerifyEventAccess>,
@@ -186,6 +592,28 @@
         Ok(result)
     }
     
+    /// Closes an expired verification challenge, refunding its rent to the payer
+    pub fn close_expired_challenge(
+        ctx: Context<CloseExpiredChallenge>,
+    ) -> Result<()> {
+        let challenge = &ctx.accounts.verification_account;
+        let ticket = challenge.ticket;
+        let expired_at = challenge.expiration;
+        let rent_refunded = challenge.to_account_info().lamports();
+        let result = instructions::verification::close_expired_challenge(ctx)?;
+        
+        emit!(VerificationChallengeClosed {
+            ticket,
+            challenge: ctx.accounts.verification_account.key(),
+            payer: ctx.accounts.payer.key(),
+            rent_refunded,
+            expired_at,
+            closed_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
     /// Revokes a ticket
     pub fn revoke_ticket(
         ctx: Context<RevokeTicket>,
@@ -207,6 +635,46 @@
         Ok(result)
     }
     
+    /// Refunds a ticket's holder once the organizer has opened refunds
+    pub fn refund_ticket(
+        ctx: Context<RefundTicket>,
+    ) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        let result = instructions::tickets::refund_ticket(ctx)?;
+        
+        emit!(TicketRefunded {
+            ticket: ticket.key(),
+            event: ctx.accounts.event.key(),
+            holder: ctx.accounts.holder.key(),
+            amount: ctx.accounts.ticket_type.price,
+            refunded_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
+    /// Claws back a ticket the organizer distributed to the wrong wallet
+    pub fn clawback_ticket(
+        ctx: Context<ClawbackTicket>,
+    ) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        let from = ticket.owner;
+        let result = instructions::tickets::clawback_ticket(ctx)?;
+        
+        emit!(TicketClawedBack {
+            ticket: ticket.key(),
+            mint: ctx.accounts.mint.key(),
+            event: ctx.accounts.event.key(),
+            from,
+            organizer: ctx.accounts.organizer.key(),
+            replacement_ticket: ctx.accounts.replacement_ticket.key(),
+            replacement_mint: ctx.accounts.replacement_mint.key(),
+            clawed_back_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(result)
+    }
+    
     /// Sets ticket transferability
     pub fn set_ticket_transferability(
         ctx: Context<SetTicketTransferability>,
@@ -225,22 +693,24 @@
         Ok(result)
     }
     
-    /// Batch updates ticket status
+    /// Batch updates ticket status, returning the number of tickets updated
     pub fn batch_update_ticket_status(
         ctx: Context<BatchUpdateTicketStatus>,
         new_status: TicketStatus,
-    ) -> Result<()> {
-        let result = instructions::tickets::batch_update_ticket_status(ctx, new_status)?;
+    ) -> Result<u32> {
+        let event = ctx.accounts.event.key();
+        let updated_by = ctx.accounts.validator.key();
+        let tickets_updated = instructions::tickets::batch_update_ticket_status(ctx, new_status)?;
         
         emit!(TicketsBatchUpdated {
-            event: ctx.accounts.event.key(),
+            event,
             new_status,
-            tickets_updated: ctx.remaining_accounts.len() as u32,
-            updated_by: ctx.accounts.validator.key(),
+            tickets_updated,
+            updated_by,
             updated_at: Clock::get()?.unix_timestamp,
         });
         
-        Ok(result)
+        Ok(tickets_updated)
     }
     
     /// Updates a ticket type
@@ -267,6 +737,40 @@
         Ok(result)
     }
     
+    /// Caps how many tickets of a type a single wallet may mint
+    pub fn set_max_per_wallet(
+        ctx: Context<UpdateTicketType>,
+        max_per_wallet: u32,
+    ) -> Result<()> {
+        let result = instructions::ticket_types::set_max_per_wallet(ctx, max_per_wallet)?;
+        
+        emit!(MaxPerWalletUpdated {
+            ticket_type: ctx.accounts.ticket_type.key(),
+            max_per_wallet,
+            updated_by: ctx.accounts.organizer.key(),
+        });
+        
+        Ok(result)
+    }
+    
+    /// Requires a presale code to mint a ticket type before its public sale
+    pub fn set_presale_code(
+        ctx: Context<UpdateTicketType>,
+        presale_code_hash: Option<[u8; 32]>,
+        public_sale_start: i64,
+    ) -> Result<()> {
+        let result = instructions::ticket_types::set_presale_code(ctx, presale_code_hash, public_sale_start)?;
+        
+        emit!(PresaleCodeUpdated {
+            ticket_type: ctx.accounts.ticket_type.key(),
+            presale_enabled: presale_code_hash.is_some(),
+            public_sale_start,
+            updated_by: ctx.accounts.organizer.key(),
+        });
+        
+        Ok(result)
+    }
+    
     /// Sets ticket type active status
     pub fn set_ticket_type_active(
         ctx: Context<SetTicketTypeActive>,
@@ -312,8 +816,23 @@
 
     pub fn purchase_listing(
         ctx: Context<PurchaseListing>,
+        max_price: Option<u64>,
     ) -> Result<()> {
-        instructions::marketplace::purchase_listing(ctx)
+        instructions::marketplace::purchase_listing(ctx, max_price)
+    }
+
+    /// Changes the price of an active fixed-price listing (seller only)
+    pub fn update_listing_price(
+        ctx: Context<UpdateListingPrice>,
+        new_price: u64,
+    ) -> Result<()> {
+        instructions::marketplace::update_listing_price(ctx, new_price)
+    }
+
+    pub fn buyback_listing(
+        ctx: Context<BuybackListing>,
+    ) -> Result<()> {
+        instructions::marketplace::buyback_listing(ctx)
     }
 
     pub fn place_bid(
@@ -329,6 +848,22 @@
         instructions::marketplace::settle_auction(ctx)
     }
 
+    /// Switches an auction to bond mode before bidding starts (seller only)
+    pub fn set_auction_bond(
+        ctx: Context<SetAuctionBond>,
+        bond_basis_points: u16,
+        payment_window: i64,
+    ) -> Result<()> {
+        instructions::marketplace::set_auction_bond(ctx, bond_basis_points, payment_window)
+    }
+
+    /// Pays the rest of a winning bond-mode bid within the payment window
+    pub fn finalize_winning_payment(
+        ctx: Context<FinalizeWinningPayment>,
+    ) -> Result<()> {
+        instructions::marketplace::finalize_winning_payment(ctx)
+    }
+
     pub fn make_offer(
         ctx: Context<MakeOffer>,
         offer_amount: u64,
@@ -343,6 +878,51 @@
         instructions::marketplace::accept_offer(ctx)
     }
 
+    /// Closes a sold, canceled or finished listing and returns its rent to the seller
+    pub fn close_terminal_listing(
+        ctx: Context<CloseTerminalListing>,
+    ) -> Result<()> {
+        instructions::marketplace::close_terminal_listing(ctx)
+    }
+
+    /// Creates the registry of SPL mints the marketplace accepts as payment
+    pub fn create_accepted_payment_mints(
+        ctx: Context<CreateAcceptedPaymentMints>,
+    ) -> Result<()> {
+        instructions::marketplace::create_accepted_payment_mints(ctx)
+    }
+
+    /// Adds a mint to the accepted payment mints (registry admin only)
+    pub fn add_accepted_payment_mint(
+        ctx: Context<UpdateAcceptedPaymentMints>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        instructions::marketplace::add_accepted_payment_mint(ctx, mint)
+    }
+
+    /// Removes a mint from the accepted payment mints (registry admin only)
+    pub fn remove_accepted_payment_mint(
+        ctx: Context<UpdateAcceptedPaymentMints>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        instructions::marketplace::remove_accepted_payment_mint(ctx, mint)
+    }
+
+    /// Opens a new segment of an event's marketplace listing index
+    pub fn create_event_listing_index(
+        ctx: Context<CreateEventListingIndex>,
+        segment: u32,
+    ) -> Result<()> {
+        instructions::marketplace::create_event_listing_index(ctx, segment)
+    }
+
+    /// Logs the active listings in an event listing index segment
+    pub fn view_event_listings(
+        ctx: Context<ViewEventListings>,
+    ) -> Result<()> {
+        instructions::marketplace::view_event_listings(ctx)
+    }
+
     // Transfer listing functions
     pub fn create_transfer_listing(
         ctx: Context<CreateTransferListing>,
@@ -509,6 +1089,13 @@ pub struct MintTicket<'info> {
     #[account(mut, constraint = ticket_type.event == event.key())]
     pub ticket_type: Account<'info, TicketType>,
 
+    /// The event's KYC registry (required when the event has KYC enabled)
+    #[account(
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump = kyc_registry.bump
+    )]
+    pub kyc_registry: Option<Account<'info, KycRegistry>>,
+
     /// The mint account for the NFT
     #[account(
         init,
@@ -582,10 +1169,67 @@ pub struct MintTicket<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Context for minting several tickets at once; each ticket's mint PDA, mint
+/// authority, token account, metadata, master edition and ticket accounts
+/// are passed in `remaining_accounts`
+#[derive(Accounts)]
+pub struct MintTickets<'info> {
+    /// The event for these tickets
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    /// The ticket type being minted
+    #[account(mut, constraint = ticket_type.event == event.key())]
+    pub ticket_type: Account<'info, TicketType>,
+
+    /// The event's KYC registry (required when the event has KYC enabled)
+    #[account(
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump = kyc_registry.bump
+    )]
+    pub kyc_registry: Option<Account<'info, KycRegistry>>,
+
+    /// How many tickets of this type the buyer has minted, created on first use
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = WalletMintRecord::SPACE,
+        seeds = [b"wallet_mint_record", ticket_type.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub wallet_mint_record: Account<'info, WalletMintRecord>,
+
+    /// The buyer of the tickets
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// The event organizer receiving payment
+    /// CHECK: Constraint validates this is the event organizer
+    #[account(mut, constraint = organizer.key() == event.organizer)]
+    pub organizer: UncheckedAccount<'info>,
+
+    /// Metaplex Token Metadata program
+    /// CHECK: This is the Metaplex program
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Associated Token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
 /// Context for updating a ticket's status
 #[derive(Accounts)]
 pub struct UpdateTicketStatus<'info> {
     /// The event this ticket belongs to
+    #[account(mut)]
     pub event: Account<'info, Event>,
 
     /// The ticket to update
@@ -597,6 +1241,17 @@ pub struct UpdateTicketStatus<'info> {
     pub validator: Signer<'info>,
 }
 
+/// Context for migrating a ticket's metadata schema
+#[derive(Accounts)]
+pub struct MigrateTicketMetadata<'info> {
+    /// The event whose schema the ticket is migrated to
+    pub event: Account<'info, Event>,
+
+    /// The ticket to migrate
+    #[account(mut, constraint = ticket.event == event.key() @ TicketError::TicketEventMismatch)]
+    pub ticket: Account<'info, Ticket>,
+}
+
 /// Context for transferring a ticket
 #[derive(Accounts)]
 pub struct TransferTicket<'info> {
@@ -623,6 +1278,21 @@ pub struct TransferTicket<'info> {
     )]
     pub to_token_account: Account<'info, TokenAccount>,
 
+    /// The event the ticket belongs to
+    #[account(constraint = event.key() == ticket.event @ TicketError::TicketEventMismatch)]
+    pub event: Account<'info, Event>,
+
+    /// The ticket's type, which names the NFT
+    #[account(constraint = ticket_type.key() == ticket.ticket_type)]
+    pub ticket_type: Account<'info, TicketType>,
+
+    /// The event's KYC registry (required when the event has KYC enabled)
+    #[account(
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump = kyc_registry.bump
+    )]
+    pub kyc_registry: Option<Account<'info, KycRegistry>>,
+
     /// The current owner transferring the ticket
     #[account(constraint = from.key() == ticket.owner)]
     pub from: Signer<'info>,
@@ -646,6 +1316,13 @@ pub struct UpdateEvent<'info> {
     pub organizer: Signer<'info>,
 }
 
+/// Context for verifying an event's details hash
+#[derive(Accounts)]
+pub struct VerifyEventDetails<'info> {
+    /// The event to verify
+    pub event: Account<'info, Event>,
+}
+
 /// Context for adding a validator
 #[derive(Accounts)]
 pub struct AddValidator<'info> {
@@ -667,3 +1344,47 @@ pub struct RemoveValidator<'info> {
     /// The organizer who created the event
     pub organizer: Signer<'info>,
 }
+
+/// Context for adding a wallet to an event's KYC registry
+#[derive(Accounts)]
+pub struct AddKycWallet<'info> {
+    /// The regulated event
+    #[account(has_one = organizer)]
+    pub event: Account<'info, Event>,
+
+    /// The event's KYC registry, created on first use
+    #[account(
+        init_if_needed,
+        payer = organizer,
+        space = KycRegistry::SPACE,
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump
+    )]
+    pub kyc_registry: Account<'info, KycRegistry>,
+
+    /// The organizer who created the event
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for removing a wallet from an event's KYC registry
+#[derive(Accounts)]
+pub struct RemoveKycWallet<'info> {
+    /// The regulated event
+    #[account(has_one = organizer)]
+    pub event: Account<'info, Event>,
+
+    /// The event's KYC registry
+    #[account(
+        mut,
+        seeds = [b"kyc_registry", event.key().as_ref()],
+        bump = kyc_registry.bump
+    )]
+    pub kyc_registry: Account<'info, KycRegistry>,
+
+    /// The organizer who created the event
+    pub organizer: Signer<'info>,
+}
@@ -3,6 +3,10 @@
 //! This module contains the state definitions for the TicketToken program.
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+#[path = "../../shared/metadata_uri.rs"]
+mod metadata_uri;
 
 /// Status of a ticket
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -17,6 +21,26 @@ pub enum TicketStatus {
     Expired,
 }
 
+/// Outcome of verifying one ticket in a batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TicketVerificationResult {
+    /// Ticket was valid and is now marked used
+    Valid,
+    /// Ticket is not a valid ticket for the event
+    Invalid,
+    /// Ticket was already used for entry
+    Used,
+}
+
+/// Attendance figures for an event, returned by `get_attendance_stats`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AttendanceStats {
+    /// Tickets issued for the event
+    pub total_issued: u32,
+    /// Tickets checked in for entry
+    pub checked_in_count: u32,
+}
+
 /// Attribute for a ticket
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub struct TicketAttribute {
@@ -26,6 +50,70 @@ pub struct TicketAttribute {
     pub value: String,
 }
 
+/// Lowest ending price a Dutch auction may decay to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EndingPriceFloor {
+    /// Fixed floor in lamports
+    Absolute(u64),
+    /// Floor as a fraction of the auction's start price, in basis points
+    StartPriceBps(u16),
+}
+
+impl EndingPriceFloor {
+    /// Resolve the floor for an auction starting at `start_price`
+    pub fn floor_for(&self, start_price: u64) -> u64 {
+        match self {
+            EndingPriceFloor::Absolute(amount) => *amount,
+            EndingPriceFloor::StartPriceBps(bps) => {
+                ((start_price as u128 * *bps as u128) / 10000) as u64
+            }
+        }
+    }
+}
+
+/// Extra resale royalty charged on quick flips, decaying linearly to the base rate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FlipRoyaltySchedule {
+    /// Royalty on a resale made immediately after acquisition, in basis points
+    pub initial_basis_points: u16,
+    /// Seconds over which the royalty decays to the base rate (0 = disabled)
+    pub decay_window: i64,
+}
+
+impl FlipRoyaltySchedule {
+    /// Resolve the royalty for a resale made `held_for` seconds after acquisition
+    pub fn basis_points_for(&self, base_basis_points: u16, held_for: i64) -> u16 {
+        if self.decay_window <= 0
+            || self.initial_basis_points <= base_basis_points
+            || held_for >= self.decay_window
+        {
+            return base_basis_points;
+        }
+
+        let spread = (self.initial_basis_points - base_basis_points) as i128;
+        let remaining = (self.decay_window - held_for.max(0)) as i128;
+        base_basis_points + (spread * remaining / self.decay_window as i128) as u16
+    }
+}
+
+/// Share of primary-sale revenue paid to one recipient
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PrimaryPayee {
+    /// Wallet receiving the share
+    pub recipient: Pubkey,
+    /// Share of each primary sale, in basis points
+    pub share_bps: u16,
+}
+
+/// Scheduling status of an event
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EventStatus {
+    /// Event is running on its original dates
+    Scheduled,
+    /// Event has been moved to new dates; tickets stay valid
+    Postponed,
+}
+
 /// Event account - stores information about an event
 #[account]
 pub struct Event {
@@ -49,8 +137,56 @@ pub struct Event {
     pub max_tickets: u32,
     /// Number of tickets currently issued
     pub tickets_issued: u32,
+    /// Tickets checked in at the gate; a ticket only counts on its first entry
+    pub checked_in_count: u32,
     /// Royalty basis points for secondary sales (e.g., 500 = 5%)
     pub royalty_basis_points: u16,
+    /// Seconds a seller must wait before relisting a ticket whose auction they canceled
+    pub relist_cooldown: i64,
+    /// Minimum price for marketplace listings of this event's tickets (0 = no floor)
+    pub min_listing_price: u64,
+    /// Maximum price for marketplace listings of this event's tickets (0 = no cap)
+    pub max_listing_price: u64,
+    /// Whether sellers may raise the price of an active listing
+    pub allow_listing_price_increase: bool,
+    /// Lowest ending price allowed for Dutch auctions of this event's tickets
+    pub min_ending_price: EndingPriceFloor,
+    /// Whether ticket recipients must be present in the event's KYC registry
+    pub kyc_required: bool,
+    /// Whether tickets need complete metadata before they can be made transferable
+    pub require_complete_metadata: bool,
+    /// Seconds before the start date from which ticket transfers are frozen
+    pub freeze_before: i64,
+    /// Seconds after the end date until which ticket transfers stay frozen
+    pub freeze_after: i64,
+    /// Escalated royalty schedule for resales soon after acquisition
+    pub flip_royalty: FlipRoyaltySchedule,
+    /// Whether the event is on its original dates or has been postponed
+    pub status: EventStatus,
+    /// Start date before the first postponement (0 if never postponed)
+    pub original_start_date: i64,
+    /// End date before the first postponement (0 if never postponed)
+    pub original_end_date: i64,
+    /// Whether holders may claim refunds because of a postponement
+    pub refunds_open: bool,
+    /// Hash of the name, venue and dates, anchored to detect tampering
+    pub details_hash: [u8; 32],
+    /// Most ticket accounts a single batch status update may process
+    pub max_batch_size: u16,
+    /// Metadata schema new tickets are minted at; older tickets must migrate to it
+    pub ticket_schema_version: u8,
+    /// Seconds a verification challenge stays valid before it can be closed
+    pub challenge_ttl: i64,
+    /// Times a ticket may be resold; gifts don't count (0 = unlimited)
+    pub max_resales: u16,
+    /// Price the organizer pays to buy back a listed ticket (0 = buybacks disabled)
+    pub buyback_price: u64,
+    /// Seconds before the start date from which the organizer may buy back listed tickets
+    pub buyback_window: i64,
+    /// Seconds after a distribution during which the organizer may claw the ticket back (0 = disabled)
+    pub distribution_clawback_window: i64,
+    /// Recipients splitting primary-sale revenue (empty = all to the organizer)
+    pub primary_payees: Vec<PrimaryPayee>,
     /// List of validators that can verify/update tickets
     pub validators: Vec<Pubkey>,
     /// Is the event active
@@ -60,6 +196,13 @@ pub struct Event {
 }
 
 impl Event {
+    /// Batch size applied until the organizer configures one
+    pub const DEFAULT_MAX_BATCH_SIZE: u16 = 20;
+    /// Verification challenge lifetime applied until the organizer configures one
+    pub const DEFAULT_CHALLENGE_TTL: i64 = 300;
+    /// Most recipients a primary sale can be split between
+    pub const MAX_PRIMARY_PAYEES: usize = 5;
+
     /// Calculate the space needed for the event account
     pub fn space(event_id: &str) -> usize {
         8 + // discriminator
@@ -73,17 +216,140 @@ impl Event {
         32 + // organizer
         4 + // max_tickets
         4 + // tickets_issued
+        4 + // checked_in_count
         2 + // royalty_basis_points
+        8 + // relist_cooldown
+        8 + // min_listing_price
+        8 + // max_listing_price
+        1 + // allow_listing_price_increase
+        1 + 8 + // min_ending_price
+        1 + // kyc_required
+        1 + // require_complete_metadata
+        8 + // freeze_before
+        8 + // freeze_after
+        2 + 8 + // flip_royalty
+        1 + // status
+        8 + // original_start_date
+        8 + // original_end_date
+        1 + // refunds_open
+        32 + // details_hash
+        2 + // max_batch_size
+        1 + // ticket_schema_version
+        8 + // challenge_ttl
+        2 + // max_resales
+        8 + // buyback_price
+        8 + // buyback_window
+        8 + // distribution_clawback_window
+        4 + (Self::MAX_PRIMARY_PAYEES * (32 + 2)) + // primary_payees
         4 + (10 * 32) + // validators (estimated 10 max)
         1 + // active
         1 + // bump
         200 // padding
     }
 
+    /// Hash the event's name, venue and dates. Strings are length-prefixed so
+    /// shifting bytes between fields changes the hash.
+    pub fn compute_details_hash(&self) -> [u8; 32] {
+        hashv(&[
+            &(self.name.len() as u32).to_le_bytes(),
+            self.name.as_bytes(),
+            &(self.venue.len() as u32).to_le_bytes(),
+            self.venue.as_bytes(),
+            &self.start_date.to_le_bytes(),
+            &self.end_date.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    /// Split a primary sale of `amount` among the primary payees. Shares are
+    /// rounded down and the last payee takes the rounding dust, so the parts
+    /// always add up to `amount`.
+    pub fn primary_split(&self, amount: u64) -> Vec<(Pubkey, u64)> {
+        let mut remaining = amount;
+        let last = self.primary_payees.len().saturating_sub(1);
+        self.primary_payees
+            .iter()
+            .enumerate()
+            .map(|(index, payee)| {
+                let share = if index == last {
+                    remaining
+                } else {
+                    (amount as u128 * payee.share_bps as u128 / 10000) as u64
+                };
+                remaining -= share;
+                (payee.recipient, share)
+            })
+            .collect()
+    }
+
     /// Check if a public key is a validator for this event
     pub fn is_validator(&self, key: Pubkey) -> bool {
         self.validators.contains(&key) || key == self.organizer
     }
+
+    /// Whether ticket transfers are frozen at `now`. The freeze covers
+    /// `[start_date - freeze_before, end_date + freeze_after]` and is off
+    /// while both offsets are zero.
+    pub fn is_transfer_frozen(&self, now: i64) -> bool {
+        if self.freeze_before == 0 && self.freeze_after == 0 {
+            return false;
+        }
+
+        now >= self.start_date.saturating_sub(self.freeze_before)
+            && now <= self.end_date.saturating_add(self.freeze_after)
+    }
+
+    /// Whether the organizer may buy back listed tickets at `now`: buybacks
+    /// are configured and the start date is within the buyback window
+    pub fn is_buyback_open(&self, now: i64) -> bool {
+        self.buyback_price > 0
+            && now >= self.start_date.saturating_sub(self.buyback_window)
+            && now < self.start_date
+    }
+
+    /// Count tickets checked in for the first time toward the event's attendance
+    pub fn record_check_ins(&mut self, count: u32) -> Result<()> {
+        self.checked_in_count = self
+            .checked_in_count
+            .checked_add(count)
+            .ok_or(crate::TicketError::CheckInCountOverflow)?;
+        Ok(())
+    }
+
+    /// Regulated events only let KYC-approved wallets receive tickets
+    pub fn require_kyc_approved(&self, kyc_registry: Option<&KycRegistry>, wallet: &Pubkey) -> Result<()> {
+        if self.kyc_required && !kyc_registry.is_some_and(|registry| registry.is_approved(wallet)) {
+            return err!(crate::TicketError::RecipientNotKycApproved);
+        }
+        Ok(())
+    }
+}
+
+/// KYC registry account - wallets approved to hold tickets for a regulated event
+#[account]
+pub struct KycRegistry {
+    /// Event this registry belongs to
+    pub event: Pubkey,
+    /// Wallets that have passed KYC
+    pub approved_wallets: Vec<Pubkey>,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl KycRegistry {
+    /// Maximum number of approved wallets per registry
+    pub const MAX_WALLETS: usize = 100;
+
+    /// Fixed space for a KYC registry account
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // event
+        4 + (Self::MAX_WALLETS * 32) + // approved_wallets
+        1; // bump
+
+    /// Check if a wallet is KYC-approved
+    pub fn is_approved(&self, wallet: &Pubkey) -> bool {
+        self.approved_wallets.contains(wallet)
+    }
 }
 
 /// Ticket type account - defines a type of ticket for an event
@@ -103,6 +369,12 @@ pub struct TicketType {
     pub quantity: u32,
     /// Number of tickets sold for this type
     pub sold: u32,
+    /// Most tickets of this type a single wallet may mint (0 = no cap)
+    pub max_per_wallet: u32,
+    /// Hash of the code required to mint before the public sale (None = no presale)
+    pub presale_code_hash: Option<[u8; 32]>,
+    /// When this type opens to everyone without a presale code (Unix timestamp)
+    pub public_sale_start: i64,
     /// Attributes specific to this ticket type
     pub attributes: Vec<TicketAttribute>,
     /// Is this ticket type active and available for purchase
@@ -122,11 +394,55 @@ impl TicketType {
         8 + // price
         4 + // quantity
         4 + // sold
+        4 + // max_per_wallet
+        1 + 32 + // presale_code_hash
+        8 + // public_sale_start
         4 + (10 * (4 + 50 + 4 + 50)) + // attributes (estimated 10 max)
         1 + // active
         1 + // bump
         200 // padding
     }
+
+    /// Hash a presale code the way it is stored on the ticket type
+    pub fn hash_presale_code(presale_code: &str) -> [u8; 32] {
+        hashv(&[presale_code.as_bytes()]).to_bytes()
+    }
+
+    /// Check that a mint at `now` may go ahead. Until the public sale starts,
+    /// a type with a presale code can only be minted by presenting that code.
+    pub fn check_presale_access(&self, presale_code: Option<&str>, now: i64) -> Result<()> {
+        let code_hash = match self.presale_code_hash {
+            Some(code_hash) if now < self.public_sale_start => code_hash,
+            _ => return Ok(()),
+        };
+        let presale_code = presale_code.ok_or(crate::TicketError::PresaleAccessRequired)?;
+        if Self::hash_presale_code(presale_code) != code_hash {
+            return err!(crate::TicketError::InvalidPresaleCode);
+        }
+        Ok(())
+    }
+}
+
+/// Wallet mint record - how many tickets of a type a wallet has minted
+#[account]
+pub struct WalletMintRecord {
+    /// Ticket type the record counts mints for
+    pub ticket_type: Pubkey,
+    /// Wallet that minted the tickets
+    pub wallet: Pubkey,
+    /// Tickets of this type minted by the wallet
+    pub minted: u32,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl WalletMintRecord {
+    /// Fixed space for a wallet mint record account
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // ticket_type
+        32 + // wallet
+        4 + // minted
+        1; // bump
 }
 
 /// Ticket account - represents an individual NFT ticket
@@ -150,13 +466,31 @@ pub struct Ticket {
     pub transferable: bool,
     /// Date when the ticket was used (if used)
     pub used_at: Option<i64>,
+    /// Date when the current owner acquired the ticket
+    pub last_transfer_at: i64,
     /// Custom attributes for this specific ticket
     pub custom_attributes: Vec<TicketAttribute>,
     /// Bump seed for PDA derivation
     pub bump: u8,
+    /// Metadata schema the ticket's fields follow. Kept after the bump so
+    /// tickets minted before versioning read 0 from their padding.
+    pub metadata_schema_version: u8,
+    /// Event details hash the ticket was issued against (schema v2 and up)
+    pub event_details_hash: [u8; 32],
+    /// Times the ticket has been sold on the secondary market (gifts excluded)
+    pub resale_count: u16,
+    /// When the organizer distributed the ticket to its current owner (0 = not a distribution)
+    pub distributed_at: i64,
 }
 
 impl Ticket {
+    /// Schema of tickets minted before versioning
+    pub const LEGACY_METADATA_SCHEMA_VERSION: u8 = 1;
+    /// Newest schema this program can mint and migrate tickets to
+    pub const CURRENT_METADATA_SCHEMA_VERSION: u8 = 2;
+    /// Longest metadata URI the ticket account has room for
+    pub const MAX_METADATA_URI_LEN: usize = 200;
+
     /// Fixed space for a ticket account
     pub const SPACE: usize = 8 + // discriminator
         32 + // mint
@@ -168,7 +502,79 @@ impl Ticket {
         1 + // status
         1 + // transferable
         9 + // used_at (Option<i64>)
+        8 + // last_transfer_at
         4 + (5 * (4 + 50 + 4 + 50)) + // custom_attributes (estimated 5 max)
         1 + // bump
-        200; // padding
+        1 + // metadata_schema_version
+        32 + // event_details_hash
+        2 + // resale_count
+        8 + // distributed_at
+        157; // padding
+
+    /// Whether the ticket has what a tradeable ticket needs: a ticket type
+    /// with a name (the NFT's name) and a metadata URI with a supported scheme
+    pub fn has_complete_metadata(&self, ticket_type: &TicketType) -> bool {
+        self.ticket_type != Pubkey::default()
+            && !ticket_type.name.trim().is_empty()
+            && !self.metadata_uri.is_empty()
+            && self.metadata_uri.len() <= Self::MAX_METADATA_URI_LEN
+            && metadata_uri::has_allowed_scheme(&self.metadata_uri)
+    }
+
+    /// Fails if the event requires complete metadata and the ticket lacks it,
+    /// so a broken ticket is never transferred or listed for sale
+    pub fn require_complete_metadata(&self, event: &Event, ticket_type: &TicketType) -> Result<()> {
+        if event.require_complete_metadata && !self.has_complete_metadata(ticket_type) {
+            return err!(crate::TicketError::IncompleteMetadata);
+        }
+        Ok(())
+    }
+
+    /// Count a secondary sale of the ticket, failing once the event's resale
+    /// cap has been reached
+    pub fn record_resale(&mut self, event: &Event) -> Result<()> {
+        if event.max_resales > 0 && self.resale_count >= event.max_resales {
+            return err!(crate::TicketError::ResaleLimitReached);
+        }
+        self.resale_count = self.resale_count.saturating_add(1);
+        Ok(())
+    }
+
+    /// The ticket's metadata schema, counting unversioned tickets as the legacy schema
+    pub fn schema_version(&self) -> u8 {
+        self.metadata_schema_version.max(Self::LEGACY_METADATA_SCHEMA_VERSION)
+    }
+
+    /// Whether the ticket meets the schema its event requires
+    pub fn meets_schema(&self, event: &Event) -> bool {
+        self.schema_version() >= event.ticket_schema_version
+    }
+
+    /// Event details hash recorded on tickets issued at `schema_version`
+    pub fn issued_details_hash(event: &Event, schema_version: u8) -> [u8; 32] {
+        if schema_version >= 2 {
+            event.details_hash
+        } else {
+            [0; 32]
+        }
+    }
+
+    /// Upgrade the ticket's fields to the event's schema, filling in what each
+    /// newer version adds. Tickets already at or past it are left as they are.
+    /// Returns whether anything changed.
+    pub fn migrate_metadata(&mut self, event: &Event) -> bool {
+        let from_version = self.schema_version();
+        let to_version = event.ticket_schema_version;
+        if from_version >= to_version {
+            return false;
+        }
+
+        // v2: record the event details the ticket is held against
+        if from_version < 2 {
+            self.event_details_hash = Self::issued_details_hash(event, to_version);
+        }
+
+        self.metadata_schema_version = to_version;
+        true
+    }
 }
@@ -12,6 +12,13 @@ use solana_program::clock::Clock;
 
 declare_id!("TicketNFT1111111111111111111111111111111111111");
 
+/// Maximum metadata URI length, matching the space reserved in `TicketData`
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+#[path = "../../shared/metadata_uri.rs"]
+pub mod metadata_uri;
+pub use metadata_uri::ALLOWED_METADATA_URI_SCHEMES;
+
 /// Main ticket NFT program
 #[program]
 pub mod ticket_nft {
@@ -31,6 +38,7 @@ pub mod ticket_nft {
         program_config.max_supply = max_supply;
         program_config.total_minted = 0;
         program_config.is_paused = false;
+        program_config.tier_access_levels = TierAccessLevels::default();
         program_config.bump = *ctx.bumps.get("program_config").unwrap();
 
         emit!(ProgramInitialized {
@@ -94,21 +102,41 @@ pub mod ticket_nft {
         require!(seat_number.len() <= 20, TicketError::SeatNumberTooLong);
         require!(name.len() <= 32, TicketError::NameTooLong);
         require!(symbol.len() <= 10, TicketError::SymbolTooLong);
+        validate_metadata_uri(&metadata_uri)?;
 
         let clock = Clock::get()?;
         require!(event_timestamp > clock.unix_timestamp, TicketError::EventInPast);
 
+        // Events with their own authority mint under it, so they can be paused
+        // or rotated without touching other events
+        let event_authority = EventAuthority::try_load(&ctx.accounts.event_authority)?;
+        if let Some(ref authority) = event_authority {
+            require!(!authority.is_paused, TicketError::EventMintingPaused);
+        }
+        let generation = event_authority.as_ref().map_or(0, |a| a.generation).to_le_bytes();
+        let authority_bump = [event_authority
+            .as_ref()
+            .map_or(ctx.accounts.program_config.bump, |a| a.authority_bump)];
+        let mint_authority_seeds: Vec<&[u8]> = if event_authority.is_some() {
+            vec![b"event_mint_authority", event_id.as_bytes(), &generation, &authority_bump]
+        } else {
+            vec![b"program_config", &authority_bump]
+        };
+        let expected_authority = Pubkey::create_program_address(&mint_authority_seeds, ctx.program_id)
+            .map_err(|_| error!(TicketError::InvalidMintAuthority))?;
+        require_keys_eq!(
+            ctx.accounts.mint_authority.key(),
+            expected_authority,
+            TicketError::InvalidMintAuthority
+        );
+
         // Mint NFT token
         let cpi_accounts = MintTo {
             mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.token_account.to_account_info(),
-            authority: ctx.accounts.program_config.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
         };
-        let program_config_seeds = &[
-            b"program_config",
-            &[ctx.accounts.program_config.bump],
-        ];
-        let signer = &[&program_config_seeds[..]];
+        let signer = &[&mint_authority_seeds[..]];
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
         mint_to(cpi_ctx, 1)?;
@@ -116,7 +144,7 @@ pub mod ticket_nft {
         // Create metadata
         let creators = vec![
             Creator {
-                address: ctx.accounts.program_config.key(),
+                address: ctx.accounts.mint_authority.key(),
                 verified: true,
                 share: 100,
             },
@@ -135,8 +163,8 @@ pub mod ticket_nft {
         let metadata_accounts = CreateMetadataAccountsV3 {
             metadata: ctx.accounts.metadata.to_account_info(),
             mint: ctx.accounts.mint.to_account_info(),
-            mint_authority: ctx.accounts.program_config.to_account_info(),
-            update_authority: ctx.accounts.program_config.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
+            update_authority: ctx.accounts.mint_authority.to_account_info(),
             payer: ctx.accounts.payer.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
             rent: ctx.accounts.rent.to_account_info(),
@@ -154,8 +182,8 @@ pub mod ticket_nft {
         let master_edition_accounts = CreateMasterEditionV3 {
             edition: ctx.accounts.master_edition.to_account_info(),
             mint: ctx.accounts.mint.to_account_info(),
-            update_authority: ctx.accounts.program_config.to_account_info(),
-            mint_authority: ctx.accounts.program_config.to_account_info(),
+            update_authority: ctx.accounts.mint_authority.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
             payer: ctx.accounts.payer.to_account_info(),
             metadata: ctx.accounts.metadata.to_account_info(),
             token_program: ctx.accounts.token_program.to_account_info(),
@@ -215,6 +243,9 @@ pub mod ticket_nft {
                 <= ctx.accounts.program_config.max_supply,
             TicketError::MaxSupplyReached
         );
+        for ticket in tickets.iter() {
+            validate_metadata_uri(&ticket.metadata_uri)?;
+        }
 
         let batch_data = &mut ctx.accounts.batch_data;
         batch_data.minter = ctx.accounts.minter.key();
@@ -248,6 +279,8 @@ pub mod ticket_nft {
         };
 
         require!(can_enable, TicketError::TransferNotAllowed);
+        // Tickets minted before URI validation must still carry usable metadata to trade
+        validate_metadata_uri(&ticket_data.metadata_uri)?;
 
         ticket_data.is_transferable = true;
 
@@ -259,13 +292,20 @@ pub mod ticket_nft {
         Ok(())
     }
 
-    /// Verify content access for ticket holder
-    pub fn verify_access(ctx: Context<VerifyAccess>, content_id: String) -> Result<bool> {
+    /// Verify content access for ticket holder. Content needing a higher
+    /// level than the holder's tier maps to is denied regardless of timing.
+    /// The required level comes from the content's record, not the caller.
+    pub fn verify_access(
+        ctx: Context<VerifyAccess>,
+        content_id: String,
+    ) -> Result<bool> {
         let ticket_data = &ctx.accounts.ticket_data;
+        let required_level = ctx.accounts.content_record.required_level;
         require!(!ticket_data.is_used, TicketError::TicketAlreadyUsed);
         
         let clock = Clock::get()?;
-        let has_access = match ticket_data.tier {
+        let tier_level = ctx.accounts.program_config.tier_access_levels.for_tier(ticket_data.tier);
+        let in_window = match ticket_data.tier {
             TicketTier::VIP => true, // VIP has access to all content
             TicketTier::Premium => {
                 // Premium has access 24 hours before event
@@ -276,6 +316,7 @@ pub mod ticket_nft {
                 clock.unix_timestamp >= ticket_data.event_timestamp - 3600
             },
         };
+        let has_access = in_window && tier_level.satisfies(&required_level);
 
         emit!(AccessVerified {
             mint: ticket_data.mint,
@@ -283,6 +324,7 @@ pub mod ticket_nft {
             content_id,
             has_access,
             tier: ticket_data.tier,
+            required_level,
         });
 
         Ok(has_access)
@@ -349,6 +391,179 @@ pub mod ticket_nft {
 
         Ok(())
     }
+
+    /// Update the highest content access level each tier reaches (admin only)
+    pub fn update_tier_access_levels(
+        ctx: Context<UpdateTierAccessLevels>,
+        tier_access_levels: TierAccessLevels,
+    ) -> Result<()> {
+        ctx.accounts.program_config.tier_access_levels = tier_access_levels;
+
+        emit!(TierAccessLevelsUpdated {
+            admin: ctx.accounts.admin.key(),
+            tier_access_levels,
+        });
+
+        Ok(())
+    }
+
+    /// Set the access level a content id requires (admin only)
+    pub fn set_content_access_level(
+        ctx: Context<SetContentAccessLevel>,
+        content_id: String,
+        required_level: AccessLevel,
+    ) -> Result<()> {
+        require!(content_id.len() <= 64, TicketError::ContentIdTooLong);
+
+        let content_record = &mut ctx.accounts.content_record;
+        content_record.content_id = content_id.clone();
+        content_record.required_level = required_level;
+        content_record.bump = *ctx.bumps.get("content_record").unwrap();
+
+        emit!(ContentAccessLevelSet {
+            admin: ctx.accounts.admin.key(),
+            content_id,
+            required_level,
+        });
+
+        Ok(())
+    }
+
+    /// Grow a program config created before tier access levels to the
+    /// current layout, starting it on the default tier mapping (admin only)
+    pub fn migrate_program_config(ctx: Context<MigrateProgramConfig>) -> Result<()> {
+        let config_info = ctx.accounts.program_config.to_account_info();
+        require_keys_eq!(*config_info.owner, crate::ID, TicketError::InvalidProgramConfig);
+        require!(
+            config_info.data_len() == ProgramConfig::LEGACY_LEN,
+            TicketError::ProgramConfigAlreadyMigrated
+        );
+        {
+            let data = config_info.try_borrow_data()?;
+            require!(
+                data[..8] == ProgramConfig::DISCRIMINATOR[..],
+                TicketError::InvalidProgramConfig
+            );
+            let admin = Pubkey::try_from(&data[8..40]).unwrap();
+            require_keys_eq!(admin, ctx.accounts.admin.key(), TicketError::InvalidProgramConfig);
+        }
+
+        let new_len = 8 + ProgramConfig::INIT_SPACE;
+        let rent_shortfall = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(config_info.lamports());
+        if rent_shortfall > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: config_info.clone(),
+                    },
+                ),
+                rent_shortfall,
+            )?;
+        }
+        config_info.realloc(new_len, false)?;
+
+        let tier_access_levels = TierAccessLevels::default();
+        tier_access_levels.serialize(&mut &mut config_info.try_borrow_mut_data()?[ProgramConfig::LEGACY_LEN..])?;
+
+        emit!(TierAccessLevelsUpdated {
+            admin: ctx.accounts.admin.key(),
+            tier_access_levels,
+        });
+
+        Ok(())
+    }
+
+    /// Give an event its own mint authority (admin only)
+    pub fn create_event_authority(ctx: Context<CreateEventAuthority>, event_id: String) -> Result<()> {
+        require!(event_id.len() <= 50, TicketError::EventIdTooLong);
+
+        let (mint_authority, authority_bump) =
+            EventAuthority::derive_mint_authority(ctx.program_id, &event_id, 0);
+
+        let event_authority = &mut ctx.accounts.event_authority;
+        event_authority.event_id = event_id.clone();
+        event_authority.generation = 0;
+        event_authority.authority_bump = authority_bump;
+        event_authority.is_paused = false;
+        event_authority.bump = *ctx.bumps.get("event_authority").unwrap();
+
+        emit!(EventAuthorityCreated {
+            admin: ctx.accounts.admin.key(),
+            event_id,
+            mint_authority,
+        });
+
+        Ok(())
+    }
+
+    /// Stop minting for one event (admin only)
+    pub fn pause_event_authority(ctx: Context<ManageEventAuthority>, event_id: String) -> Result<()> {
+        ctx.accounts.event_authority.is_paused = true;
+
+        emit!(EventMintingPaused {
+            admin: ctx.accounts.admin.key(),
+            event_id,
+        });
+
+        Ok(())
+    }
+
+    /// Resume minting for one event (admin only)
+    pub fn unpause_event_authority(ctx: Context<ManageEventAuthority>, event_id: String) -> Result<()> {
+        ctx.accounts.event_authority.is_paused = false;
+
+        emit!(EventMintingUnpaused {
+            admin: ctx.accounts.admin.key(),
+            event_id,
+        });
+
+        Ok(())
+    }
+
+    /// Move an event's minting to a fresh authority; the old one can no
+    /// longer mint (admin only)
+    pub fn rotate_event_authority(ctx: Context<ManageEventAuthority>, event_id: String) -> Result<()> {
+        let event_authority = &mut ctx.accounts.event_authority;
+        let (old_authority, _) =
+            EventAuthority::derive_mint_authority(ctx.program_id, &event_id, event_authority.generation);
+
+        let generation = event_authority
+            .generation
+            .checked_add(1)
+            .ok_or(TicketError::InvalidMintAuthority)?;
+        let (new_authority, authority_bump) =
+            EventAuthority::derive_mint_authority(ctx.program_id, &event_id, generation);
+        event_authority.generation = generation;
+        event_authority.authority_bump = authority_bump;
+
+        emit!(EventAuthorityRotated {
+            admin: ctx.accounts.admin.key(),
+            event_id,
+            old_authority,
+            new_authority,
+            generation,
+        });
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Reject metadata URIs that are empty, too long, or use an unsupported scheme
+pub fn validate_metadata_uri(uri: &str) -> Result<()> {
+    require!(
+        !uri.is_empty() && uri.len() <= MAX_METADATA_URI_LEN,
+        TicketError::InvalidMetadata
+    );
+    require!(metadata_uri::has_allowed_scheme(uri), TicketError::InvalidMetadata);
+    Ok(())
 }
 
 // ============================================================================
@@ -427,10 +642,22 @@ pub struct MintTicket<'info> {
         init,
         payer = payer,
         mint::decimals = 0,
-        mint::authority = program_config,
+        mint::authority = mint_authority,
     )]
     pub mint: Account<'info, Mint>,
     
+    /// The event's authority record, if it has its own mint authority
+    #[account(
+        seeds = [b"event_authority", event_id.as_bytes()],
+        bump
+    )]
+    /// CHECK: PDA derived from the event ID, may be uninitialized
+    pub event_authority: UncheckedAccount<'info>,
+    
+    /// The event's current mint authority, or the program config for events without one
+    /// CHECK: Validated against the event authority in the handler
+    pub mint_authority: UncheckedAccount<'info>,
+    
     #[account(
         init,
         payer = payer,
@@ -462,6 +689,51 @@ pub struct MintTicket<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+#[instruction(event_id: String)]
+pub struct CreateEventAuthority<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = admin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + EventAuthority::INIT_SPACE,
+        seeds = [b"event_authority", event_id.as_bytes()],
+        bump
+    )]
+    pub event_authority: Account<'info, EventAuthority>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String)]
+pub struct ManageEventAuthority<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = admin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"event_authority", event_id.as_bytes()],
+        bump = event_authority.bump
+    )]
+    pub event_authority: Account<'info, EventAuthority>,
+    
+    pub admin: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct BatchMintTickets<'info> {
     #[account(
@@ -514,7 +786,14 @@ pub struct EnableTransfer<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(content_id: String)]
 pub struct VerifyAccess<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
     #[account(
         seeds = [b"ticket_data", ticket_data.mint.as_ref()],
         bump = ticket_data.bump,
@@ -522,6 +801,12 @@ pub struct VerifyAccess<'info> {
     )]
     pub ticket_data: Account<'info, TicketData>,
     
+    #[account(
+        seeds = [b"content", ContentRecord::seed(&content_id).as_ref()],
+        bump = content_record.bump
+    )]
+    pub content_record: Account<'info, ContentRecord>,
+    
     pub owner: Signer<'info>,
 }
 
@@ -577,6 +862,61 @@ pub struct UpdateRoyalty<'info> {
     pub admin: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateTierAccessLevels<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = admin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(content_id: String)]
+pub struct SetContentAccessLevel<'info> {
+    #[account(
+        seeds = [b"program_config"],
+        bump = program_config.bump,
+        has_one = admin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + ContentRecord::INIT_SPACE,
+        seeds = [b"content", ContentRecord::seed(&content_id).as_ref()],
+        bump
+    )]
+    pub content_record: Account<'info, ContentRecord>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProgramConfig<'info> {
+    /// The config in its legacy layout, which `Account` can't deserialize
+    /// CHECK: Owner, discriminator and admin are checked in the handler
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump
+    )]
+    pub program_config: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 // ============================================================================
 // Data Structs
 // ============================================================================
@@ -590,6 +930,31 @@ pub struct ProgramConfig {
     pub total_minted: u64,
     pub is_paused: bool,
     pub bump: u8,
+    /// Appended after `bump`; configs created before it must be migrated
+    pub tier_access_levels: TierAccessLevels,
+}
+
+impl ProgramConfig {
+    /// Account size before `tier_access_levels` was added
+    pub const LEGACY_LEN: usize = 8 + 32 + 2 + 8 + 8 + 1 + 1;
+}
+
+/// Content access level required for a content id, set by the admin.
+/// Keyed by a hash of the content id so ids longer than a seed still fit.
+#[account]
+#[derive(InitSpace)]
+pub struct ContentRecord {
+    #[max_len(64)]
+    pub content_id: String,
+    pub required_level: AccessLevel,
+    pub bump: u8,
+}
+
+impl ContentRecord {
+    /// PDA seed for a content id
+    pub fn seed(content_id: &str) -> [u8; 32] {
+        solana_program::hash::hash(content_id.as_bytes()).to_bytes()
+    }
 }
 
 #[account]
@@ -601,6 +966,37 @@ pub struct MinterConfig {
     pub bump: u8,
 }
 
+/// An event's own mint authority. The signing PDA is derived from the event
+/// ID and generation, so rotating bumps the generation to a new address.
+#[account]
+#[derive(InitSpace)]
+pub struct EventAuthority {
+    #[max_len(50)]
+    pub event_id: String,
+    pub generation: u32,
+    pub authority_bump: u8,
+    pub is_paused: bool,
+    pub bump: u8,
+}
+
+impl EventAuthority {
+    /// Mint authority PDA for an event at the given generation
+    pub fn derive_mint_authority(program_id: &Pubkey, event_id: &str, generation: u32) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"event_mint_authority", event_id.as_bytes(), &generation.to_le_bytes()],
+            program_id,
+        )
+    }
+
+    /// Loads an event's authority record, or `None` if the event mints under the program config
+    pub fn try_load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Account::<EventAuthority>::try_from(info)?.into_inner()))
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct TicketData {
@@ -636,6 +1032,63 @@ pub enum TicketTier {
     VIP,
 }
 
+/// Content access levels, matching the tickettoken program's hierarchy
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AccessLevel {
+    Basic,
+    Premium,
+    VIP,
+    Exclusive,
+    Backstage,
+}
+
+impl AccessLevel {
+    /// Position in the access hierarchy, lowest first
+    pub fn rank(&self) -> u8 {
+        match self {
+            AccessLevel::Basic => 0,
+            AccessLevel::Premium => 1,
+            AccessLevel::VIP => 2,
+            AccessLevel::Exclusive => 3,
+            AccessLevel::Backstage => 4,
+        }
+    }
+
+    /// Whether a holder at this level meets a `required` level
+    pub fn satisfies(&self, required: &AccessLevel) -> bool {
+        self.rank() >= required.rank()
+    }
+}
+
+/// Highest content access level reached by each ticket tier
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct TierAccessLevels {
+    pub standard: AccessLevel,
+    pub premium: AccessLevel,
+    pub vip: AccessLevel,
+}
+
+impl Default for TierAccessLevels {
+    /// VIP keeps its all-content access; other tiers reach their own level
+    fn default() -> Self {
+        Self {
+            standard: AccessLevel::Basic,
+            premium: AccessLevel::Premium,
+            vip: AccessLevel::Backstage,
+        }
+    }
+}
+
+impl TierAccessLevels {
+    pub fn for_tier(&self, tier: TicketTier) -> AccessLevel {
+        match tier {
+            TicketTier::Standard => self.standard,
+            TicketTier::Premium => self.premium,
+            TicketTier::VIP => self.vip,
+        }
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct TicketMintData {
     #[max_len(50)]
@@ -707,6 +1160,7 @@ pub struct AccessVerified {
     pub content_id: String,
     pub has_access: bool,
     pub tier: TicketTier,
+    pub required_level: AccessLevel,
 }
 
 #[event]
@@ -734,6 +1188,47 @@ pub struct RoyaltyUpdated {
     pub new_percentage: u16,
 }
 
+#[event]
+pub struct TierAccessLevelsUpdated {
+    pub admin: Pubkey,
+    pub tier_access_levels: TierAccessLevels,
+}
+
+#[event]
+pub struct ContentAccessLevelSet {
+    pub admin: Pubkey,
+    pub content_id: String,
+    pub required_level: AccessLevel,
+}
+
+#[event]
+pub struct EventAuthorityCreated {
+    pub admin: Pubkey,
+    pub event_id: String,
+    pub mint_authority: Pubkey,
+}
+
+#[event]
+pub struct EventMintingPaused {
+    pub admin: Pubkey,
+    pub event_id: String,
+}
+
+#[event]
+pub struct EventMintingUnpaused {
+    pub admin: Pubkey,
+    pub event_id: String,
+}
+
+#[event]
+pub struct EventAuthorityRotated {
+    pub admin: Pubkey,
+    pub event_id: String,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub generation: u32,
+}
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -778,4 +1273,22 @@ pub enum TicketError {
     
     #[msg("Batch size too large (max 10)")]
     BatchSizeTooLarge,
+    
+    #[msg("Invalid metadata URI (must be https://, ipfs:// or ar://, max 200 characters)")]
+    InvalidMetadata,
+    
+    #[msg("Minting is paused for this event")]
+    EventMintingPaused,
+    
+    #[msg("Mint authority is not the event's current mint authority")]
+    InvalidMintAuthority,
+    
+    #[msg("Content ID too long (max 64 characters)")]
+    ContentIdTooLong,
+    
+    #[msg("Account is not this program's config")]
+    InvalidProgramConfig,
+    
+    #[msg("Program config is already on the current layout")]
+    ProgramConfigAlreadyMigrated,
 }
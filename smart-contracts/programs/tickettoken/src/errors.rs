@@ -307,4 +307,46 @@ pub enum TicketTokenError {
 
     #[msg("Feature sunset")]
     FeatureSunset,
+
+    #[msg("Original owner account required for paid transfer")]
+    OriginalOwnerAccountRequired,
+
+    #[msg("Duplicate content id in batch")]
+    DuplicateContentId,
+
+    #[msg("Ticket is not a season pass")]
+    NotSeasonPass,
+
+    #[msg("Too many linked events")]
+    TooManyLinkedEvents,
+
+    #[msg("Duplicate linked event")]
+    DuplicateLinkedEvent,
+
+    #[msg("Event is not linked to this season pass")]
+    EventNotLinked,
+
+    #[msg("Season pass already used for this event")]
+    SeasonPassEntryUsed,
+
+    #[msg("Program authority account required for paid mint")]
+    ProgramAuthorityAccountRequired,
+
+    #[msg("Re-entry cooldown cannot be negative")]
+    InvalidReentryCooldown,
+
+    #[msg("Re-entry is not supported for linked season passes")]
+    ReentryNotSupported,
+
+    #[msg("Ticket was scanned too recently for re-entry")]
+    ReentryCooldownActive,
+
+    #[msg("Ticket has not been used for entry")]
+    TicketNotUsed,
+
+    #[msg("Attendance NFT already issued for this ticket")]
+    PoapAlreadyIssued,
+
+    #[msg("Sale amount does not match the recorded sale price")]
+    SaleAmountMismatch,
 }
@@ -38,6 +38,7 @@ pub fn handler(ctx: Context<CancelListing>) -> Result<()> {
     let program_state = &ctx.accounts.program_state;
     
     require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
+    marketplace_listing.require_current_version()?;
     
     // Check if auction has bids (cannot cancel auction with bids)
     if matches!(marketplace_listing.listing_type, ListingType::Auction) {
@@ -52,7 +52,7 @@ pub fn handler(
     let program_state = &ctx.accounts.program_state;
     
     require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
-    require!(!ticket_data.is_used, TicketTokenError::TicketAlreadyUsed);
+    require!(!ticket_data.has_attended(), TicketTokenError::TicketAlreadyUsed);
     require!(!ticket_data.is_listed, TicketTokenError::TicketCurrentlyListed);
     require!(price > 0, TicketTokenError::InvalidListingPrice);
     
@@ -79,6 +79,7 @@ pub fn handler(
     marketplace_listing.highest_bid = None;
     marketplace_listing.highest_bidder = None;
     marketplace_listing.bump = *ctx.bumps.get("marketplace_listing").unwrap();
+    marketplace_listing.schema_version = MarketplaceListing::CURRENT_SCHEMA_VERSION;
     
     // Mark ticket as listed
     ticket_data.is_listed = true;
@@ -36,6 +36,11 @@ pub fn handler(
     
     require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
     require!(sale_amount > 0, TicketTokenError::InvalidRefundAmount);
+    // Royalties are owed on the recorded sale price, not a caller-chosen amount
+    require!(
+        sale_amount == ticket_data.last_sale_amount,
+        TicketTokenError::SaleAmountMismatch
+    );
     
     // Find the recipient in the royalty recipients list
     let royalty_recipient = ticket_data.royalty_recipients
@@ -39,9 +39,29 @@ pub fn handler(
     let program_state = &ctx.accounts.program_state;
     
     require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
-    require!(!ticket_data.is_used, TicketTokenError::TicketAlreadyUsed);
+    require!(!ticket_data.has_attended(), TicketTokenError::TicketAlreadyUsed);
     require!(content_id.len() <= 64, TicketTokenError::InvalidEventId);
     
+    upsert_content_access(ticket_data, &content_id, &access_level);
+    
+    emit!(ContentAccessGranted {
+        mint: ticket_data.mint,
+        owner: ticket_data.owner,
+        content_id,
+        access_level,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Content access granted successfully");
+    Ok(())
+}
+
+/// Grants or refreshes access to a content id on a ticket
+pub(crate) fn upsert_content_access(
+    ticket_data: &mut TicketData,
+    content_id: &str,
+    access_level: &AccessLevel,
+) {
     // Check if content access already exists
     let existing_access = ticket_data.content_access
         .iter_mut()
@@ -55,7 +75,7 @@ pub fn handler(
     } else {
         // Add new content access
         let new_access = ContentAccess {
-            content_id: content_id.clone(),
+            content_id: content_id.to_string(),
             access_level: access_level.clone(),
             expiry_timestamp: None,
             is_active: true,
@@ -63,15 +83,4 @@ pub fn handler(
         
         ticket_data.content_access.push(new_access);
     }
-    
-    emit!(ContentAccessGranted {
-        mint: ticket_data.mint,
-        owner: ticket_data.owner,
-        content_id,
-        access_level,
-        timestamp: Clock::get()?.unix_timestamp,
-    });
-    
-    msg!("Content access granted successfully");
-    Ok(())
 }
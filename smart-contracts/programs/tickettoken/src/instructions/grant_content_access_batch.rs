@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::*;
+use crate::errors::*;
+use super::grant_content_access::upsert_content_access;
+
+#[derive(Accounts)]
+pub struct GrantContentAccessBatch<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = program_state.bump,
+        constraint = program_state.authority == authority.key() @ TicketTokenError::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"ticket_data", ticket_data.mint.as_ref()],
+        bump = ticket_data.bump,
+    )]
+    pub ticket_data: Account<'info, TicketData>,
+
+    #[account(
+        constraint = owner_token_account.mint == ticket_data.mint @ TicketTokenError::TicketMintMismatch,
+        constraint = owner_token_account.owner == ticket_data.owner @ TicketTokenError::TokenAccountMismatch,
+        constraint = owner_token_account.amount == 1 @ TicketTokenError::InvalidTokenAmount,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<GrantContentAccessBatch>,
+    grants: Vec<ContentAccessGrant>,
+) -> Result<()> {
+    let ticket_data = &mut ctx.accounts.ticket_data;
+    let program_state = &ctx.accounts.program_state;
+    
+    require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
+    require!(!ticket_data.has_attended(), TicketTokenError::TicketAlreadyUsed);
+    require!(
+        grants.len() <= ContentAccessGrant::MAX_BATCH_SIZE,
+        TicketTokenError::BatchOperationLimitExceeded
+    );
+    
+    // Validate the whole batch before writing anything
+    for (i, grant) in grants.iter().enumerate() {
+        require!(grant.content_id.len() <= 64, TicketTokenError::InvalidEventId);
+        require!(
+            !grants[..i].iter().any(|other| other.content_id == grant.content_id),
+            TicketTokenError::DuplicateContentId
+        );
+    }
+    
+    let timestamp = Clock::get()?.unix_timestamp;
+    
+    for grant in grants {
+        upsert_content_access(ticket_data, &grant.content_id, &grant.access_level);
+        
+        emit!(ContentAccessGranted {
+            mint: ticket_data.mint,
+            owner: ticket_data.owner,
+            content_id: grant.content_id,
+            access_level: grant.access_level,
+            timestamp,
+        });
+    }
+    
+    msg!("Batch content access granted successfully");
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, FreezeAccount, Mint, MintTo, Token, TokenAccount};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct IssuePoap<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"ticket_data", ticket_data.mint.as_ref()],
+        bump = ticket_data.bump,
+        constraint = ticket_data.owner == attendee.key() @ TicketTokenError::NotTicketOwner,
+    )]
+    pub ticket_data: Account<'info, TicketData>,
+
+    /// Attendance NFT mint; the program freezes holder accounts so it can't move
+    #[account(
+        init,
+        payer = attendee,
+        mint::decimals = 0,
+        mint::authority = program_state,
+        mint::freeze_authority = program_state,
+    )]
+    pub poap_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = attendee,
+        space = 8 + PoapRecord::LEN,
+        seeds = [b"poap", poap_mint.key().as_ref()],
+        bump,
+    )]
+    pub poap_record: Account<'info, PoapRecord>,
+
+    #[account(
+        init,
+        payer = attendee,
+        associated_token::mint = poap_mint,
+        associated_token::authority = attendee,
+    )]
+    pub attendee_token_account: Account<'info, TokenAccount>,
+
+    /// Holder of the used ticket
+    #[account(mut)]
+    pub attendee: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler(
+    ctx: Context<IssuePoap>,
+) -> Result<()> {
+    let ticket_data = &mut ctx.accounts.ticket_data;
+    let program_state = &ctx.accounts.program_state;
+    
+    require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
+    // Re-entry tickets and season passes are never spent, but a scan still counts
+    require!(ticket_data.has_attended(), TicketTokenError::TicketNotUsed);
+    require!(ticket_data.poap_mint.is_none(), TicketTokenError::PoapAlreadyIssued);
+    
+    let current_time = Clock::get()?.unix_timestamp;
+    let poap_mint = ctx.accounts.poap_mint.key();
+    
+    let poap_record = &mut ctx.accounts.poap_record;
+    poap_record.poap_mint = poap_mint;
+    poap_record.ticket_mint = ticket_data.mint;
+    poap_record.event_id = ticket_data.event_id.clone();
+    poap_record.attendee = ctx.accounts.attendee.key();
+    poap_record.issued_at = current_time;
+    poap_record.bump = *ctx.bumps.get("poap_record").unwrap();
+    
+    ticket_data.poap_mint = Some(poap_mint);
+    
+    let authority_seeds = &[
+        b"program_state",
+        &[program_state.bump],
+    ];
+    let signer = &[&authority_seeds[..]];
+    
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.poap_mint.to_account_info(),
+                to: ctx.accounts.attendee_token_account.to_account_info(),
+                authority: ctx.accounts.program_state.to_account_info(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+    
+    // Freezing the attendee's account makes the collectible non-transferable
+    token::freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        FreezeAccount {
+            account: ctx.accounts.attendee_token_account.to_account_info(),
+            mint: ctx.accounts.poap_mint.to_account_info(),
+            authority: ctx.accounts.program_state.to_account_info(),
+        },
+        signer,
+    ))?;
+    
+    emit!(PoapIssued {
+        ticket_mint: ticket_data.mint,
+        poap_mint,
+        attendee: ctx.accounts.attendee.key(),
+        event_id: ticket_data.event_id.clone(),
+        timestamp: current_time,
+    });
+    
+    msg!("Attendance NFT issued for event: {}", ticket_data.event_id);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct LinkSeasonPassEvents<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = program_state.bump,
+        constraint = program_state.authority == authority.key() @ TicketTokenError::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"ticket_data", ticket_data.mint.as_ref()],
+        bump = ticket_data.bump,
+    )]
+    pub ticket_data: Account<'info, TicketData>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<LinkSeasonPassEvents>,
+    linked_events: Vec<Pubkey>,
+) -> Result<()> {
+    let ticket_data = &mut ctx.accounts.ticket_data;
+    let program_state = &ctx.accounts.program_state;
+    
+    require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
+    require!(
+        ticket_data.ticket_type == TicketType::SeasonPass,
+        TicketTokenError::NotSeasonPass
+    );
+    require!(
+        linked_events.len() <= TicketData::MAX_LINKED_EVENTS,
+        TicketTokenError::TooManyLinkedEvents
+    );
+    
+    for (i, event) in linked_events.iter().enumerate() {
+        require!(
+            !linked_events[..i].contains(event),
+            TicketTokenError::DuplicateLinkedEvent
+        );
+    }
+    
+    // Entries already used for events that stay linked are kept
+    ticket_data.used_events.retain(|event| linked_events.contains(event));
+    ticket_data.linked_events = linked_events;
+    
+    emit!(SeasonPassEventsLinked {
+        mint: ticket_data.mint,
+        linked_events: ticket_data.linked_events.clone(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!(
+        "Season pass {} linked to {} events",
+        ticket_data.mint,
+        ticket_data.linked_events.len()
+    );
+    Ok(())
+}
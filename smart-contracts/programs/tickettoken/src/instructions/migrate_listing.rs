@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct MigrateListing<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace_listing", marketplace_listing.ticket_mint.as_ref()],
+        bump = marketplace_listing.bump,
+        constraint = marketplace_listing.version() <= MarketplaceListing::CURRENT_SCHEMA_VERSION @ TicketTokenError::VersionMismatch,
+        realloc = 8 + MarketplaceListing::LEN,
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub marketplace_listing: Account<'info, MarketplaceListing>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateListing>) -> Result<()> {
+    let marketplace_listing = &mut ctx.accounts.marketplace_listing;
+    let from_version = marketplace_listing.version();
+
+    // Already on the current layout, nothing to do
+    if from_version == MarketplaceListing::CURRENT_SCHEMA_VERSION {
+        msg!("Listing for ticket {} is already at schema version {}", marketplace_listing.ticket_mint, from_version);
+        return Ok(());
+    }
+
+    // v1 -> v2: the only layout change is the stored version byte, which
+    // occupies previously zeroed padding, so stamping it completes the upgrade
+    marketplace_listing.schema_version = MarketplaceListing::CURRENT_SCHEMA_VERSION;
+
+    emit!(ListingMigrated {
+        mint: marketplace_listing.ticket_mint,
+        from_version,
+        to_version: MarketplaceListing::CURRENT_SCHEMA_VERSION,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Listing migrated from schema version {} to {}", from_version, MarketplaceListing::CURRENT_SCHEMA_VERSION);
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::*;
@@ -45,6 +46,13 @@ pub struct MintTicket<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// CHECK: Program authority receiving the ticket price (required for paid mints)
+    #[account(
+        mut,
+        address = program_state.authority @ TicketTokenError::Unauthorized,
+    )]
+    pub program_authority: Option<UncheckedAccount<'info>>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -59,6 +67,7 @@ pub fn handler(
     transfer_restrictions: TransferRestrictions,
     content_access: Vec<ContentAccess>,
     royalty_recipients: Vec<RoyaltyRecipient>,
+    price: u64,
 ) -> Result<()> {
     let program_state = &mut ctx.accounts.program_state;
     let ticket_data = &mut ctx.accounts.ticket_data;
@@ -66,6 +75,7 @@ pub fn handler(
     require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
     require!(event_id.len() <= 64, TicketTokenError::InvalidEventId);
     require!(metadata.name.len() <= 32, TicketTokenError::InvalidMetadata);
+    require!(metadata.has_valid_uris(), TicketTokenError::InvalidMetadata);
     require!(royalty_recipients.len() <= 5, TicketTokenError::TooManyRoyaltyRecipients);
     
     // Validate royalty percentages sum to 100%
@@ -87,6 +97,33 @@ pub fn handler(
     ticket_data.mint_timestamp = Clock::get()?.unix_timestamp;
     ticket_data.usage_timestamp = None;
     ticket_data.transfer_count = 0;
+    ticket_data.linked_events = Vec::new();
+    ticket_data.used_events = Vec::new();
+    ticket_data.paid_by = ctx.accounts.payer.key();
+    ticket_data.paid_amount = price;
+    ticket_data.reentry_allowed = false;
+    ticket_data.reentry_cooldown = 0;
+    ticket_data.last_scan_at = None;
+    ticket_data.poap_mint = None;
+    ticket_data.last_sale_amount = 0;
+    
+    // Collect the ticket price; it is refunded to the payer, not the holder
+    if price > 0 {
+        let program_authority = ctx.accounts.program_authority
+            .as_ref()
+            .ok_or(TicketTokenError::ProgramAuthorityAccountRequired)?;
+        
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: program_authority.to_account_info(),
+                },
+            ),
+            price,
+        )?;
+    }
     ticket_data.bump = *ctx.bumps.get("ticket_data").unwrap();
     
     // Mint the token
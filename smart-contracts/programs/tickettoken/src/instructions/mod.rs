@@ -2,15 +2,23 @@ pub mod initialize;
 pub mod mint_ticket;
 pub mod transfer_ticket;
 pub mod verify_ownership;
+pub mod set_content_access_level;
 pub mod grant_content_access;
+pub mod grant_content_access_batch;
 pub mod create_listing;
 pub mod purchase_ticket;
 pub mod cancel_listing;
+pub mod migrate_listing;
 pub mod distribute_royalty;
 pub mod use_ticket;
 pub mod update_metadata;
 pub mod set_program_pause;
 pub mod update_fees;
+pub mod link_season_pass_events;
+pub mod verify_season_pass;
+pub mod refund_ticket;
+pub mod set_reentry_policy;
+pub mod issue_poap;
 
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
@@ -22,12 +30,20 @@ pub use initialize::*;
 pub use mint_ticket::*;
 pub use transfer_ticket::*;
 pub use verify_ownership::*;
+pub use set_content_access_level::*;
 pub use grant_content_access::*;
+pub use grant_content_access_batch::*;
 pub use create_listing::*;
 pub use purchase_ticket::*;
 pub use cancel_listing::*;
+pub use migrate_listing::*;
 pub use distribute_royalty::*;
 pub use use_ticket::*;
 pub use update_metadata::*;
 pub use set_program_pause::*;
 pub use update_fees::*;
+pub use link_season_pass_events::*;
+pub use verify_season_pass::*;
+pub use refund_ticket::*;
+pub use set_reentry_policy::*;
+pub use issue_poap::*;
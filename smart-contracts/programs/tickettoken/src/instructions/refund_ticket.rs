@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct RefundTicket<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = program_state.bump,
+        constraint = program_state.authority == authority.key() @ TicketTokenError::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"ticket_data", ticket_data.mint.as_ref()],
+        bump = ticket_data.bump,
+        constraint = ticket_data.owner == owner.key() @ TicketTokenError::NotTicketOwner,
+    )]
+    pub ticket_data: Account<'info, TicketData>,
+
+    #[account(
+        mut,
+        address = ticket_data.mint @ TicketTokenError::TicketMintMismatch,
+    )]
+    pub ticket_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == ticket_data.mint @ TicketTokenError::TicketMintMismatch,
+        constraint = owner_token_account.owner == owner.key() @ TicketTokenError::TokenAccountMismatch,
+        constraint = owner_token_account.amount == 1 @ TicketTokenError::InvalidTokenAmount,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    /// Current holder, who gives up the ticket
+    pub owner: Signer<'info>,
+
+    /// CHECK: Wallet that paid at mint, may differ from the current owner
+    #[account(
+        mut,
+        address = ticket_data.paid_by @ TicketTokenError::InvalidAccountOwner,
+    )]
+    pub paid_by: UncheckedAccount<'info>,
+
+    /// Program authority funding the refund
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<RefundTicket>,
+) -> Result<()> {
+    let ticket_data = &mut ctx.accounts.ticket_data;
+    let program_state = &ctx.accounts.program_state;
+    
+    require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
+    require!(!ticket_data.has_attended(), TicketTokenError::TicketAlreadyUsed);
+    require!(!ticket_data.is_listed, TicketTokenError::TicketCurrentlyListed);
+    require!(ticket_data.paid_amount > 0, TicketTokenError::RefundNotAllowed);
+    
+    // Burn the holder's ticket so it can't be used after the refund
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.ticket_mint.to_account_info(),
+        from: ctx.accounts.owner_token_account.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    token::burn(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), 1)?;
+    
+    // Return the mint price to the original payer, whoever holds the ticket now
+    let amount = ticket_data.paid_amount;
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.paid_by.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    
+    ticket_data.paid_amount = 0;
+    
+    emit!(TicketRefunded {
+        mint: ticket_data.mint,
+        owner: ticket_data.owner,
+        paid_by: ticket_data.paid_by,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Refunded {} lamports to {} for ticket {}", amount, ticket_data.paid_by, ticket_data.mint);
+    Ok(())
+}
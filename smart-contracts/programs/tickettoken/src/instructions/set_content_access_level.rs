@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+#[instruction(content_id: String)]
+pub struct SetContentAccessLevel<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = program_state.bump,
+        constraint = program_state.authority == authority.key() @ TicketTokenError::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ContentRecord::LEN,
+        seeds = [b"content", ContentRecord::seed(&content_id).as_ref()],
+        bump,
+    )]
+    pub content_record: Account<'info, ContentRecord>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetContentAccessLevel>,
+    content_id: String,
+    required_level: AccessLevel,
+) -> Result<()> {
+    let content_record = &mut ctx.accounts.content_record;
+    let program_state = &ctx.accounts.program_state;
+    
+    require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
+    require!(content_id.len() <= 64, TicketTokenError::InvalidEventId);
+    
+    content_record.content_id = content_id.clone();
+    content_record.required_level = required_level.clone();
+    content_record.bump = *ctx.bumps.get("content_record").unwrap();
+    
+    emit!(ContentAccessLevelSet {
+        content_id: content_id.clone(),
+        required_level,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Content {} access level set", content_id);
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetReentryPolicy<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = program_state.bump,
+        constraint = program_state.authority == authority.key() @ TicketTokenError::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"ticket_data", ticket_data.mint.as_ref()],
+        bump = ticket_data.bump,
+    )]
+    pub ticket_data: Account<'info, TicketData>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<SetReentryPolicy>,
+    reentry_allowed: bool,
+    reentry_cooldown: i64,
+) -> Result<()> {
+    let ticket_data = &mut ctx.accounts.ticket_data;
+    let program_state = &ctx.accounts.program_state;
+    
+    require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
+    require!(reentry_cooldown >= 0, TicketTokenError::InvalidReentryCooldown);
+    // Season passes already admit one entry per linked event
+    require!(
+        !ticket_data.is_linked_season_pass(),
+        TicketTokenError::ReentryNotSupported
+    );
+    
+    ticket_data.reentry_allowed = reentry_allowed;
+    ticket_data.reentry_cooldown = reentry_cooldown;
+    
+    emit!(ReentryPolicyUpdated {
+        mint: ticket_data.mint,
+        reentry_allowed,
+        reentry_cooldown,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!(
+        "Re-entry for ticket {} set to {} with a {}s cooldown",
+        ticket_data.mint,
+        reentry_allowed,
+        reentry_cooldown
+    );
+    Ok(())
+}
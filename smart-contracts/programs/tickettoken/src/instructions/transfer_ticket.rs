@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::state::*;
 use crate::errors::*;
@@ -33,23 +34,34 @@ pub struct TransferTicket<'info> {
     )]
     pub new_owner_token_account: Account<'info, TokenAccount>,
 
+    #[account(mut)]
     pub current_owner: Signer<'info>,
 
-    /// CHECK: New owner of the ticket
+    /// CHECK: New owner of the ticket (must sign for paid transfers)
+    #[account(mut)]
     pub new_owner: UncheckedAccount<'info>,
 
+    /// CHECK: Original owner receiving the transfer fee (required for paid transfers with original owner royalty)
+    #[account(
+        mut,
+        address = ticket_data.original_owner @ TicketTokenError::InvalidAccountOwner,
+    )]
+    pub original_owner: Option<UncheckedAccount<'info>>,
+
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(
     ctx: Context<TransferTicket>,
     transfer_type: TransferType,
+    payment_amount: u64,
 ) -> Result<()> {
     let ticket_data = &mut ctx.accounts.ticket_data;
     let program_state = &ctx.accounts.program_state;
     
     require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
-    require!(!ticket_data.is_used, TicketTokenError::TicketAlreadyUsed);
+    require!(!ticket_data.has_attended(), TicketTokenError::TicketAlreadyUsed);
     require!(!ticket_data.is_listed, TicketTokenError::TicketCurrentlyListed);
     
     // Check transfer restrictions
@@ -85,6 +97,64 @@ pub fn handler(
         );
     }
     
+    // Settle payment for paid transfers, routing the transfer fee to the original owner first
+    if payment_amount > 0 {
+        require!(ctx.accounts.new_owner.is_signer, TicketTokenError::MissingRequiredSignature);
+        
+        let restrictions = &ticket_data.transfer_restrictions;
+        let original_owner_fee = if restrictions.original_owner_royalty {
+            (payment_amount as u128)
+                .checked_mul(restrictions.transfer_fee_bps as u128)
+                .and_then(|amount| amount.checked_div(10000))
+                .and_then(|amount| u64::try_from(amount).ok())
+                .ok_or(TicketTokenError::ArithmeticOverflow)?
+        } else {
+            0
+        };
+        
+        if original_owner_fee > 0 {
+            let original_owner = ctx.accounts.original_owner
+                .as_ref()
+                .ok_or(TicketTokenError::OriginalOwnerAccountRequired)?;
+            
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.new_owner.to_account_info(),
+                        to: original_owner.to_account_info(),
+                    },
+                ),
+                original_owner_fee,
+            )?;
+            
+            emit!(RoyaltyDistributed {
+                mint: ticket_data.mint,
+                sale_amount: payment_amount,
+                recipient: original_owner.key(),
+                royalty_amount: original_owner_fee,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+        
+        let seller_proceeds = payment_amount
+            .checked_sub(original_owner_fee)
+            .ok_or(TicketTokenError::ArithmeticOverflow)?;
+        
+        if seller_proceeds > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.new_owner.to_account_info(),
+                        to: ctx.accounts.current_owner.to_account_info(),
+                    },
+                ),
+                seller_proceeds,
+            )?;
+        }
+    }
+    
     // Perform the transfer
     let cpi_accounts = Transfer {
         from: ctx.accounts.current_owner_token_account.to_account_info(),
@@ -102,6 +172,8 @@ pub fn handler(
     ticket_data.transfer_count = ticket_data.transfer_count
         .checked_add(1)
         .ok_or(TicketTokenError::ArithmeticOverflow)?;
+    // Royalty distribution is checked against this recorded sale price
+    ticket_data.last_sale_amount = payment_amount;
     
     emit!(TicketTransferred {
         mint: ticket_data.mint,
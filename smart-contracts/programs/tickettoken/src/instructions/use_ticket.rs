@@ -35,6 +35,7 @@ pub struct UseTicket<'info> {
 pub fn handler(
     ctx: Context<UseTicket>,
     verification_code: String,
+    event: Option<Pubkey>,
 ) -> Result<()> {
     let ticket_data = &mut ctx.accounts.ticket_data;
     let program_state = &ctx.accounts.program_state;
@@ -46,6 +47,42 @@ pub fn handler(
     
     let current_time = Clock::get()?.unix_timestamp;
     
+    // Simple verification code check (in production, this would be more sophisticated)
+    let expected_code = format!("{}_{}", ticket_data.event_id, ticket_data.mint.to_string()[..8].to_string());
+    
+    // A linked season pass admits one entry per linked event; the pass's own
+    // event date doesn't apply, the verifier vouches for the linked event
+    if ticket_data.is_linked_season_pass() {
+        let event = event.ok_or(TicketTokenError::EventNotLinked)?;
+        ticket_data.require_season_pass_entry(&event)?;
+        require!(
+            verification_code == expected_code,
+            TicketTokenError::InvalidVerificationCode
+        );
+        
+        ticket_data.used_events.push(event);
+        ticket_data.usage_timestamp = Some(current_time);
+        ticket_data.last_scan_at = Some(current_time);
+        let entries_remaining = ticket_data.entries_remaining();
+        ticket_data.is_used = entries_remaining == 0;
+        
+        emit!(SeasonPassEntryUsed {
+            mint: ticket_data.mint,
+            owner: ticket_data.owner,
+            event,
+            entries_remaining,
+            timestamp: current_time,
+        });
+        
+        msg!(
+            "Season pass used for event {} ({} entries remaining)",
+            event,
+            entries_remaining
+        );
+        
+        return Ok(());
+    }
+    
     // Check if event has started (basic validation)
     require!(
         current_time >= ticket_data.metadata.event_datetime - 3600, // Allow 1 hour early
@@ -58,16 +95,23 @@ pub fn handler(
         TicketTokenError::EventEnded
     );
     
-    // Simple verification code check (in production, this would be more sophisticated)
-    let expected_code = format!("{}_{}", ticket_data.event_id, ticket_data.mint.to_string()[..8].to_string());
     require!(
         verification_code == expected_code,
         TicketTokenError::InvalidVerificationCode
     );
     
-    // Mark ticket as used
-    ticket_data.is_used = true;
-    ticket_data.usage_timestamp = Some(current_time);
+    // Re-entry tickets stay usable, but scans must be spaced by the cooldown;
+    // single-use tickets are spent on their first scan
+    if ticket_data.reentry_allowed {
+        ticket_data.require_reentry_cooldown_elapsed(current_time)?;
+        if ticket_data.usage_timestamp.is_none() {
+            ticket_data.usage_timestamp = Some(current_time);
+        }
+    } else {
+        ticket_data.is_used = true;
+        ticket_data.usage_timestamp = Some(current_time);
+    }
+    ticket_data.last_scan_at = Some(current_time);
     
     emit!(TicketUsed {
         mint: ticket_data.mint,
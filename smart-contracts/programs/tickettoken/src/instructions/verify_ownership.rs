@@ -12,6 +12,13 @@ pub struct VerifyOwnership<'info> {
     )]
     pub program_state: Account<'info, ProgramState>,
 
+    /// The level the content requires, as set by the program authority
+    #[account(
+        seeds = [b"content", ContentRecord::seed(&content_id).as_ref()],
+        bump = content_record.bump,
+    )]
+    pub content_record: Account<'info, ContentRecord>,
+
     #[account(
         seeds = [b"ticket_data", ticket_data.mint.as_ref()],
         bump = ticket_data.bump,
@@ -47,6 +54,12 @@ pub fn handler(
     
     require!(content_access.is_active, TicketTokenError::ContentAccessNotFound);
     
+    // Higher levels include everything below them
+    require!(
+        content_access.access_level.satisfies(&ctx.accounts.content_record.required_level),
+        TicketTokenError::InsufficientAccessLevel
+    );
+    
     // Check if access has expired
     if let Some(expiry) = content_access.expiry_timestamp {
         let current_time = Clock::get()?.unix_timestamp;
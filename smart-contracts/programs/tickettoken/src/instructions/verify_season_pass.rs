@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct VerifySeasonPass<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        seeds = [b"ticket_data", ticket_data.mint.as_ref()],
+        bump = ticket_data.bump,
+        constraint = ticket_data.owner == owner.key() @ TicketTokenError::NotTicketOwner,
+    )]
+    pub ticket_data: Account<'info, TicketData>,
+
+    #[account(
+        constraint = owner_token_account.mint == ticket_data.mint @ TicketTokenError::TicketMintMismatch,
+        constraint = owner_token_account.owner == owner.key() @ TicketTokenError::TokenAccountMismatch,
+        constraint = owner_token_account.amount == 1 @ TicketTokenError::InvalidTokenAmount,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<VerifySeasonPass>,
+    event: Pubkey,
+) -> Result<()> {
+    let ticket_data = &ctx.accounts.ticket_data;
+    let program_state = &ctx.accounts.program_state;
+    
+    require!(!program_state.is_paused, TicketTokenError::ProgramPaused);
+    require!(!ticket_data.is_used, TicketTokenError::TicketAlreadyUsed);
+    require!(ticket_data.is_linked_season_pass(), TicketTokenError::NotSeasonPass);
+    ticket_data.require_season_pass_entry(&event)?;
+    
+    msg!(
+        "Season pass {} verified for event {} ({} entries remaining)",
+        ticket_data.mint,
+        event,
+        ticket_data.entries_remaining()
+    );
+    
+    Ok(())
+}
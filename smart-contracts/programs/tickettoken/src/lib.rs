@@ -35,6 +35,7 @@ pub mod tickettoken {
         transfer_restrictions: TransferRestrictions,
         content_access: Vec<ContentAccess>,
         royalty_recipients: Vec<RoyaltyRecipient>,
+        price: u64,
     ) -> Result<()> {
         instructions::mint_ticket::handler(
             ctx,
@@ -44,18 +45,21 @@ pub mod tickettoken {
             transfer_restrictions,
             content_access,
             royalty_recipients,
+            price,
         )
     }
 
-    /// Transfer ticket with restrictions
+    /// Transfer ticket with restrictions, optionally settling a payment from the new owner
     pub fn transfer_ticket(
         ctx: Context<TransferTicket>,
         transfer_type: TransferType,
+        payment_amount: u64,
     ) -> Result<()> {
-        instructions::transfer_ticket::handler(ctx, transfer_type)
+        instructions::transfer_ticket::handler(ctx, transfer_type, payment_amount)
     }
 
-    /// Verify ticket ownership and grant content access
+    /// Verify ticket ownership and grant content access if the holder's level
+    /// meets the level the content requires
     pub fn verify_ownership(
         ctx: Context<VerifyOwnership>,
         content_id: String,
@@ -63,6 +67,15 @@ pub mod tickettoken {
         instructions::verify_ownership::handler(ctx, content_id)
     }
 
+    /// Set the access level a piece of content requires (admin only)
+    pub fn set_content_access_level(
+        ctx: Context<SetContentAccessLevel>,
+        content_id: String,
+        required_level: AccessLevel,
+    ) -> Result<()> {
+        instructions::set_content_access_level::handler(ctx, content_id, required_level)
+    }
+
     /// Grant content access to ticket holder
     pub fn grant_content_access(
         ctx: Context<GrantContentAccess>,
@@ -72,6 +85,14 @@ pub mod tickettoken {
         instructions::grant_content_access::handler(ctx, content_id, access_level)
     }
 
+    /// Grant access to multiple content ids in one transaction
+    pub fn grant_content_access_batch(
+        ctx: Context<GrantContentAccessBatch>,
+        grants: Vec<ContentAccessGrant>,
+    ) -> Result<()> {
+        instructions::grant_content_access_batch::handler(ctx, grants)
+    }
+
     /// Create marketplace listing
     pub fn create_listing(
         ctx: Context<CreateListing>,
@@ -96,6 +117,13 @@ pub mod tickettoken {
         instructions::cancel_listing::handler(ctx)
     }
 
+    /// Upgrade a marketplace listing to the current account layout
+    pub fn migrate_listing(
+        ctx: Context<MigrateListing>,
+    ) -> Result<()> {
+        instructions::migrate_listing::handler(ctx)
+    }
+
     /// Distribute royalties to artists/creators
     pub fn distribute_royalty(
         ctx: Context<DistributeRoyalty>,
@@ -104,12 +132,52 @@ pub mod tickettoken {
         instructions::distribute_royalty::handler(ctx, sale_amount)
     }
 
-    /// Use ticket for event entry
+    /// Use ticket for event entry; season passes name the linked event being entered
     pub fn use_ticket(
         ctx: Context<UseTicket>,
         verification_code: String,
+        event: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::use_ticket::handler(ctx, verification_code, event)
+    }
+
+    /// Mint a non-transferable attendance NFT for a used ticket
+    pub fn issue_poap(
+        ctx: Context<IssuePoap>,
+    ) -> Result<()> {
+        instructions::issue_poap::handler(ctx)
+    }
+
+    /// Set the events a season pass admits entry to (admin only)
+    pub fn link_season_pass_events(
+        ctx: Context<LinkSeasonPassEvents>,
+        linked_events: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::link_season_pass_events::handler(ctx, linked_events)
+    }
+
+    /// Allow a ticket to be scanned repeatedly, spaced by a cooldown (admin only)
+    pub fn set_reentry_policy(
+        ctx: Context<SetReentryPolicy>,
+        reentry_allowed: bool,
+        reentry_cooldown: i64,
+    ) -> Result<()> {
+        instructions::set_reentry_policy::handler(ctx, reentry_allowed, reentry_cooldown)
+    }
+
+    /// Verify a season pass still admits entry to a linked event
+    pub fn verify_season_pass(
+        ctx: Context<VerifySeasonPass>,
+        event: Pubkey,
+    ) -> Result<()> {
+        instructions::verify_season_pass::handler(ctx, event)
+    }
+
+    /// Refund a ticket's mint price to whoever paid for it, burning the ticket
+    pub fn refund_ticket(
+        ctx: Context<RefundTicket>,
     ) -> Result<()> {
-        instructions::use_ticket::handler(ctx, verification_code)
+        instructions::refund_ticket::handler(ctx)
     }
 
     /// Update ticket metadata (admin only)
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+#[path = "../../shared/metadata_uri.rs"]
+mod metadata_uri;
+
 /// Program state account
 #[account]
 pub struct ProgramState {
@@ -54,10 +57,97 @@ pub struct TicketData {
     pub transfer_count: u32,
     /// Bump seed for PDA
     pub bump: u8,
+    /// Events a season pass admits entry to
+    pub linked_events: Vec<Pubkey>,
+    /// Linked events the season pass has already been used for
+    pub used_events: Vec<Pubkey>,
+    /// Wallet that paid for the ticket at mint, refunds go back here
+    pub paid_by: Pubkey,
+    /// Amount paid at mint in lamports (0 once refunded)
+    pub paid_amount: u64,
+    /// Whether the ticket may be scanned again after the first entry
+    pub reentry_allowed: bool,
+    /// Minimum seconds between scans when re-entry is allowed
+    pub reentry_cooldown: i64,
+    /// Timestamp of the most recent entry scan
+    pub last_scan_at: Option<i64>,
+    /// Attendance NFT issued for this ticket after use, if any
+    pub poap_mint: Option<Pubkey>,
+    /// Price of the most recent paid transfer in lamports (0 if the last transfer was free)
+    pub last_sale_amount: u64,
 }
 
 impl TicketData {
-    pub const LEN: usize = 32 + 32 + 32 + 64 + 1 + 256 + 32 + 512 + 256 + 1 + 1 + 8 + 9 + 4 + 1 + 8; // ~1300 bytes + discriminator
+    pub const LEN: usize = 32 + 32 + 32 + 64 + 1 + 256 + 32 + 512 + 256 + 1 + 1 + 8 + 9 + 4 + 1 + 8
+        + (4 + 32 * Self::MAX_LINKED_EVENTS) * 2 + 32 + 8 + 1 + 8 + 9 + 33 + 8; // ~1933 bytes + discriminator
+
+    /// Maximum number of events a season pass can be linked to
+    pub const MAX_LINKED_EVENTS: usize = 8;
+
+    /// Whether this ticket is a season pass linked to a set of events
+    pub fn is_linked_season_pass(&self) -> bool {
+        self.ticket_type == TicketType::SeasonPass && !self.linked_events.is_empty()
+    }
+
+    /// Check that the season pass can still admit entry to `event`
+    pub fn require_season_pass_entry(&self, event: &Pubkey) -> Result<()> {
+        require!(
+            self.linked_events.contains(event),
+            crate::errors::TicketTokenError::EventNotLinked
+        );
+        require!(
+            !self.used_events.contains(event),
+            crate::errors::TicketTokenError::SeasonPassEntryUsed
+        );
+        Ok(())
+    }
+
+    /// Whether the ticket has been scanned for entry at least once. Re-entry
+    /// tickets and partly used season passes stay unspent after a scan, so
+    /// `is_used` alone doesn't say whether the holder attended
+    pub fn has_attended(&self) -> bool {
+        self.usage_timestamp.is_some()
+    }
+
+    /// Check that a re-entry ticket's cooldown since its last scan has elapsed
+    pub fn require_reentry_cooldown_elapsed(&self, now: i64) -> Result<()> {
+        if let Some(last_scan_at) = self.last_scan_at {
+            require!(
+                now >= last_scan_at.saturating_add(self.reentry_cooldown),
+                crate::errors::TicketTokenError::ReentryCooldownActive
+            );
+        }
+        Ok(())
+    }
+
+    /// Number of linked events the season pass can still be used for
+    pub fn entries_remaining(&self) -> u32 {
+        self.linked_events
+            .iter()
+            .filter(|event| !self.used_events.contains(event))
+            .count() as u32
+    }
+}
+
+/// Proof-of-attendance record linking an attendance NFT to its event
+#[account]
+pub struct PoapRecord {
+    /// Mint of the attendance NFT
+    pub poap_mint: Pubkey,
+    /// Used ticket the attendance NFT was issued for
+    pub ticket_mint: Pubkey,
+    /// Event attended
+    pub event_id: String,
+    /// Wallet the attendance NFT was issued to
+    pub attendee: Pubkey,
+    /// Timestamp of issuance
+    pub issued_at: i64,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl PoapRecord {
+    pub const LEN: usize = 32 + 32 + (4 + 64) + 32 + 8 + 1;
 }
 
 /// Marketplace listing data
@@ -85,10 +175,35 @@ pub struct MarketplaceListing {
     pub highest_bidder: Option<Pubkey>,
     /// Bump seed for PDA
     pub bump: u8,
+    /// Layout version of this account (0 for listings created before versioning)
+    pub schema_version: u8,
 }
 
 impl MarketplaceListing {
-    pub const LEN: usize = 32 + 32 + 8 + 1 + 33 + 8 + 9 + 1 + 9 + 33 + 1 + 8; // ~175 bytes + discriminator
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 33 + 8 + 9 + 1 + 9 + 33 + 1 + 1 + 8; // ~176 bytes + discriminator
+
+    /// Original listing layout, without a stored version
+    pub const SCHEMA_V1: u8 = 1;
+    /// Layout written by the current program
+    pub const CURRENT_SCHEMA_VERSION: u8 = 2;
+
+    /// Layout version of the account, treating unversioned listings as v1
+    pub fn version(&self) -> u8 {
+        if self.schema_version == 0 {
+            Self::SCHEMA_V1
+        } else {
+            self.schema_version
+        }
+    }
+
+    /// Ensure the listing uses the current layout before it is operated on
+    pub fn require_current_version(&self) -> Result<()> {
+        match self.version() {
+            v if v == Self::CURRENT_SCHEMA_VERSION => Ok(()),
+            v if v < Self::CURRENT_SCHEMA_VERSION => err!(crate::errors::TicketTokenError::MigrationRequired),
+            _ => err!(crate::errors::TicketTokenError::VersionMismatch),
+        }
+    }
 }
 
 /// Content access verification
@@ -108,6 +223,41 @@ impl ContentAccess {
     pub const LEN: usize = 64 + 1 + 9 + 1; // 75 bytes
 }
 
+/// Access level a piece of content requires, set by the program authority.
+/// Keyed by a hash of the content id so ids longer than a seed still fit.
+#[account]
+pub struct ContentRecord {
+    /// Content identifier
+    pub content_id: String,
+    /// Lowest access level that may view the content
+    pub required_level: AccessLevel,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ContentRecord {
+    pub const LEN: usize = (4 + 64) + 1 + 1; // 70 bytes + discriminator
+
+    /// PDA seed for a content id
+    pub fn seed(content_id: &str) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(content_id.as_bytes()).to_bytes()
+    }
+}
+
+/// Single entry in a batch content access grant
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ContentAccessGrant {
+    /// Content identifier
+    pub content_id: String,
+    /// Access level to grant
+    pub access_level: AccessLevel,
+}
+
+impl ContentAccessGrant {
+    /// Maximum number of grants in a single batch
+    pub const MAX_BATCH_SIZE: usize = 5;
+}
+
 /// Royalty recipient information
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub struct RoyaltyRecipient {
@@ -146,6 +296,21 @@ pub struct TicketMetadata {
 
 impl TicketMetadata {
     pub const LEN: usize = 32 + 256 + 128 + 129 + 65 + 8 + 64 + 256; // ~938 bytes
+
+    /// Maximum URI length, matching the space reserved for `image_uri`
+    pub const MAX_URI_LEN: usize = 128;
+    /// Whether a URI is non-empty, within the stored max and uses an allowed scheme
+    pub fn is_valid_uri(uri: &str) -> bool {
+        !uri.is_empty()
+            && uri.len() <= Self::MAX_URI_LEN
+            && metadata_uri::has_allowed_scheme(uri)
+    }
+
+    /// Whether the image URI and optional external URI are both valid
+    pub fn has_valid_uris(&self) -> bool {
+        Self::is_valid_uri(&self.image_uri)
+            && self.external_uri.as_deref().is_none_or(Self::is_valid_uri)
+    }
 }
 
 /// Seat information for assigned seating
@@ -233,6 +398,25 @@ pub enum AccessLevel {
     Backstage,
 }
 
+impl AccessLevel {
+    /// Position in the access hierarchy, lowest first. Spelled out rather than
+    /// taken from declaration order so reordering variants can't change access.
+    pub fn rank(&self) -> u8 {
+        match self {
+            AccessLevel::Basic => 0,
+            AccessLevel::Premium => 1,
+            AccessLevel::VIP => 2,
+            AccessLevel::Exclusive => 3,
+            AccessLevel::Backstage => 4,
+        }
+    }
+
+    /// Whether a holder at this level meets a `required` level
+    pub fn satisfies(&self, required: &AccessLevel) -> bool {
+        self.rank() >= required.rank()
+    }
+}
+
 /// Types of transfers allowed
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub enum AllowedTransferType {
@@ -300,6 +484,14 @@ pub struct TicketListed {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct ListingMigrated {
+    pub mint: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TicketSold {
     pub mint: Pubkey,
@@ -317,6 +509,55 @@ pub struct TicketUsed {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct PoapIssued {
+    pub ticket_mint: Pubkey,
+    pub poap_mint: Pubkey,
+    pub attendee: Pubkey,
+    pub event_id: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SeasonPassEntryUsed {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub event: Pubkey,
+    pub entries_remaining: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ContentAccessLevelSet {
+    pub content_id: String,
+    pub required_level: AccessLevel,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReentryPolicyUpdated {
+    pub mint: Pubkey,
+    pub reentry_allowed: bool,
+    pub reentry_cooldown: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SeasonPassEventsLinked {
+    pub mint: Pubkey,
+    pub linked_events: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TicketRefunded {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub paid_by: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct ContentAccessGranted {
     pub mint: Pubkey,
@@ -33,6 +33,7 @@ describe('Governance Contract Tests', () => {
   const VOTER1_BALANCE = 50000; // 50k tokens
   const VOTER2_BALANCE = 30000; // 30k tokens
   const DELEGATE_BALANCE = 20000; // 20k tokens
+  const NO_PARTICIPATION_TIERS = Array(3).fill({ minVotes: 0, multiplierBps: 0 });
   
   before(async () => {
     console.log("Setting up governance test environment...");
@@ -137,6 +138,10 @@ describe('Governance Contract Tests', () => {
         votingDuration: new anchor.BN(7 * 24 * 60 * 60), // 7 days
         executionWindow: new anchor.BN(3 * 24 * 60 * 60), // 3 days
         proposalCooldown: new anchor.BN(24 * 60 * 60), // 1 day
+        maxTitleLen: 100,
+        maxDescriptionLen: 1000,
+        maxExecutionInstructionsLen: 512,
+        participationTiers: NO_PARTICIPATION_TIERS,
       };
       
       await program.methods
@@ -176,7 +181,8 @@ describe('Governance Contract Tests', () => {
           { general: {} }, // ProposalType::General
           "Test Proposal",
           "This is a test proposal for governance",
-          [] // No execution instructions for this test
+          [], // No execution instructions for this test
+          [] // Yes/no proposal, no options
         )
         .accounts({
           proposer: proposer.publicKey,
@@ -203,6 +209,82 @@ describe('Governance Contract Tests', () => {
       const governance = await program.account.governance.fetch(governanceAddress);
       assert.equal(governance.proposalCount.toNumber(), 1);
     });
+    
+    it("Rejects a proposal with an over-length title", async () => {
+      const [proposalAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('proposal'), governanceAddress.toBuffer(), Buffer.from([1, 0, 0, 0, 0, 0, 0, 0])],
+        program.programId
+      );
+      
+      const [proposerVoterWeightAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('voter_weight'), governanceAddress.toBuffer(), proposer.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      try {
+        await program.methods
+          .createProposal(
+            { general: {} },
+            "T".repeat(101),
+            "Title exceeds the configured maximum",
+            [],
+            []
+          )
+          .accounts({
+            proposer: proposer.publicKey,
+            governance: governanceAddress,
+            governanceTokenMint: governanceTokenMint,
+            proposerTokenAccount: proposerTokenAccount,
+            proposerVoterWeight: proposerVoterWeightAddress,
+            proposal: proposalAddress,
+            relatedEvent: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([proposer])
+          .rpc();
+        assert.fail("Proposal with over-length title should have been rejected");
+      } catch (error) {
+        assert.include(error.toString(), "TitleTooLong");
+      }
+    });
+    
+    it("Rejects a proposal with oversized execution instructions", async () => {
+      const [proposalAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('proposal'), governanceAddress.toBuffer(), Buffer.from([1, 0, 0, 0, 0, 0, 0, 0])],
+        program.programId
+      );
+      
+      const [proposerVoterWeightAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('voter_weight'), governanceAddress.toBuffer(), proposer.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      try {
+        await program.methods
+          .createProposal(
+            { configuration: {} },
+            "Oversized Proposal",
+            "Execution instructions exceed the configured maximum",
+            Buffer.alloc(513),
+            []
+          )
+          .accounts({
+            proposer: proposer.publicKey,
+            governance: governanceAddress,
+            governanceTokenMint: governanceTokenMint,
+            proposerTokenAccount: proposerTokenAccount,
+            proposerVoterWeight: proposerVoterWeightAddress,
+            proposal: proposalAddress,
+            relatedEvent: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([proposer])
+          .rpc();
+        assert.fail("Proposal with oversized execution instructions should have been rejected");
+      } catch (error) {
+        assert.include(error.toString(), "ExecutionInstructionsTooLarge");
+      }
+    });
   });
   
   describe("Voting", () => {
@@ -292,6 +374,40 @@ describe('Governance Contract Tests', () => {
       assert.equal(proposal.totalVotes.toNumber(), (VOTER1_BALANCE + VOTER2_BALANCE) * 10**6);
       assert.equal(proposal.voterCount, 2);
     });
+    
+    it("Changes a vote from 'Yes' to 'No' within the voting window", async () => {
+      const [proposalAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('proposal'), governanceAddress.toBuffer(), Buffer.from([0, 0, 0, 0, 0, 0, 0, 0])],
+        program.programId
+      );
+      
+      const [voter1VoteAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('vote'), proposalAddress.toBuffer(), voter1.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .changeVote({ no: {} })
+        .accounts({
+          voter: voter1.publicKey,
+          governance: governanceAddress,
+          proposal: proposalAddress,
+          vote: voter1VoteAddress,
+        })
+        .signers([voter1])
+        .rpc();
+        
+      // Verify the vote record was updated
+      const vote = await program.account.vote.fetch(voter1VoteAddress);
+      assert.deepEqual(vote.voteType, { no: {} });
+      
+      // Verify the weight moved between tallies without changing totals
+      const proposal = await program.account.proposal.fetch(proposalAddress);
+      assert.equal(proposal.yesVotes.toNumber(), 0);
+      assert.equal(proposal.noVotes.toNumber(), (VOTER1_BALANCE + VOTER2_BALANCE) * 10**6);
+      assert.equal(proposal.totalVotes.toNumber(), (VOTER1_BALANCE + VOTER2_BALANCE) * 10**6);
+      assert.equal(proposal.voterCount, 2);
+    });
   });
   
   describe("Vote Delegation", () => {
@@ -411,5 +527,676 @@ describe('Governance Contract Tests', () => {
       const proposal = await program.account.proposal.fetch(proposalAddress);
       assert.deepEqual(proposal.state, { canceled: {} });
     });
+    
+    it("Rejects vote changes once voting has closed", async () => {
+      const [proposalAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('proposal'), governanceAddress.toBuffer(), Buffer.from([0, 0, 0, 0, 0, 0, 0, 0])],
+        program.programId
+      );
+      
+      const [voter2VoteAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('vote'), proposalAddress.toBuffer(), voter2.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      try {
+        await program.methods
+          .changeVote({ yes: {} })
+          .accounts({
+            voter: voter2.publicKey,
+            governance: governanceAddress,
+            proposal: proposalAddress,
+            vote: voter2VoteAddress,
+          })
+          .signers([voter2])
+          .rpc();
+        assert.fail("Vote change should be rejected after voting closes");
+      } catch (error) {
+        assert.include(error.toString(), "VotingEnded");
+      }
+    });
+  });
+
+  describe("Proposal Cooldown", () => {
+    const PROPOSAL_COOLDOWN = 3; // seconds
+    
+    let cooldownMint: PublicKey;
+    let cooldownGovernance: PublicKey;
+    let proposerAccount: PublicKey;
+    let voter1Account: PublicKey;
+    
+    const createCooldownProposal = async (signer: Keypair, tokenAccount: PublicKey, title: string) => {
+      const governance = await program.account.governance.fetch(cooldownGovernance);
+      const [proposalAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('proposal'), cooldownGovernance.toBuffer(), governance.proposalCount.toArrayLike(Buffer, 'le', 8)],
+        program.programId
+      );
+      const [voterWeightAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('voter_weight'), cooldownGovernance.toBuffer(), signer.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createProposal({ general: {} }, title, "Proposal cooldown test", [], [])
+        .accounts({
+          proposer: signer.publicKey,
+          governance: cooldownGovernance,
+          governanceTokenMint: cooldownMint,
+          proposerTokenAccount: tokenAccount,
+          proposerVoterWeight: voterWeightAddress,
+          proposal: proposalAddress,
+          relatedEvent: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+    };
+    
+    before(async () => {
+      cooldownMint = await createMint(
+        provider.connection,
+        governanceAuthority,
+        governanceAuthority.publicKey,
+        null,
+        6
+      );
+      
+      proposerAccount = await createAssociatedTokenAccount(provider.connection, proposer, cooldownMint, proposer.publicKey);
+      voter1Account = await createAssociatedTokenAccount(provider.connection, voter1, cooldownMint, voter1.publicKey);
+      await mintTo(provider.connection, governanceAuthority, cooldownMint, proposerAccount, governanceAuthority.publicKey, PROPOSER_BALANCE * 10**6);
+      await mintTo(provider.connection, governanceAuthority, cooldownMint, voter1Account, governanceAuthority.publicKey, VOTER1_BALANCE * 10**6);
+      
+      [cooldownGovernance] = await PublicKey.findProgramAddress(
+        [Buffer.from('governance'), cooldownMint.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .initializeGovernance({
+          proposalThreshold: new anchor.BN(1000 * 10**6),
+          quorumThresholdBps: 500,
+          approvalThresholdBps: 5000,
+          votingDuration: new anchor.BN(7 * 24 * 60 * 60),
+          executionWindow: new anchor.BN(3 * 24 * 60 * 60),
+          proposalCooldown: new anchor.BN(PROPOSAL_COOLDOWN),
+          maxTitleLen: 100,
+          maxDescriptionLen: 1000,
+          maxExecutionInstructionsLen: 512,
+          participationTiers: NO_PARTICIPATION_TIERS,
+        })
+        .accounts({
+          authority: governanceAuthority.publicKey,
+          governanceTokenMint: cooldownMint,
+          governance: cooldownGovernance,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([governanceAuthority])
+        .rpc();
+    });
+    
+    it("Rejects a second proposal during the cooldown", async () => {
+      await createCooldownProposal(proposer, proposerAccount, "First Proposal");
+      
+      try {
+        await createCooldownProposal(proposer, proposerAccount, "Too Soon");
+        assert.fail("Second proposal inside the cooldown should be rejected");
+      } catch (error) {
+        assert.include(error.toString(), "ProposalCooldownActive");
+      }
+      
+      const governance = await program.account.governance.fetch(cooldownGovernance);
+      assert.equal(governance.proposalCount.toNumber(), 1);
+    });
+    
+    it("Tracks the cooldown independently for each proposer", async () => {
+      // voter1 has never proposed, so the first proposer's cooldown does not apply
+      await createCooldownProposal(voter1, voter1Account, "Independent Proposal");
+      
+      const governance = await program.account.governance.fetch(cooldownGovernance);
+      assert.equal(governance.proposalCount.toNumber(), 2);
+    });
+    
+    it("Allows a second proposal once the cooldown has elapsed", async () => {
+      await new Promise(resolve => setTimeout(resolve, (PROPOSAL_COOLDOWN + 1) * 1000));
+      
+      await createCooldownProposal(proposer, proposerAccount, "Second Proposal");
+      
+      const [voterWeightAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('voter_weight'), cooldownGovernance.toBuffer(), proposer.publicKey.toBuffer()],
+        program.programId
+      );
+      const voterWeight = await program.account.voterWeight.fetch(voterWeightAddress);
+      const governance = await program.account.governance.fetch(cooldownGovernance);
+      assert.equal(governance.proposalCount.toNumber(), 3);
+      assert.isAbove(voterWeight.lastProposalTime.toNumber(), 0);
+    });
+  });
+
+  describe("Multiple-Choice Proposals", () => {
+    const VOTING_DURATION = 4; // seconds
+    
+    let choiceMint: PublicKey;
+    let choiceGovernance: PublicKey;
+    const choiceAccounts = new Map<Keypair, PublicKey>();
+    
+    const THREE_OPTIONS = [
+      { label: "Venue A", executionInstructions: Buffer.from([1]) },
+      { label: "Venue B", executionInstructions: Buffer.from([2]) },
+      { label: "Venue C", executionInstructions: Buffer.from([3]) },
+    ];
+    
+    const createChoiceProposal = async (title: string) => {
+      const governance = await program.account.governance.fetch(choiceGovernance);
+      const [proposalAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('proposal'), choiceGovernance.toBuffer(), governance.proposalCount.toArrayLike(Buffer, 'le', 8)],
+        program.programId
+      );
+      const [voterWeightAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('voter_weight'), choiceGovernance.toBuffer(), proposer.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createProposal({ general: {} }, title, "Pick the venue for the season finale", [], THREE_OPTIONS)
+        .accounts({
+          proposer: proposer.publicKey,
+          governance: choiceGovernance,
+          governanceTokenMint: choiceMint,
+          proposerTokenAccount: choiceAccounts.get(proposer),
+          proposerVoterWeight: voterWeightAddress,
+          proposal: proposalAddress,
+          relatedEvent: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc();
+      
+      return proposalAddress;
+    };
+    
+    const voteFor = async (proposalAddress: PublicKey, voter: Keypair, voteType: any) => {
+      const [voterWeightAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('voter_weight'), choiceGovernance.toBuffer(), voter.publicKey.toBuffer()],
+        program.programId
+      );
+      const [voteAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('vote'), proposalAddress.toBuffer(), voter.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .castVote(voteType, null)
+        .accounts({
+          voter: voter.publicKey,
+          governance: choiceGovernance,
+          governanceTokenMint: choiceMint,
+          proposal: proposalAddress,
+          voterTokenAccount: choiceAccounts.get(voter),
+          voterWeight: voterWeightAddress,
+          vote: voteAddress,
+          delegateVoterWeight: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc();
+    };
+    
+    const execute = (proposalAddress: PublicKey, instructions: Buffer = Buffer.alloc(0)) =>
+      program.methods
+        .executeProposal(instructions)
+        .accounts({
+          executor: governanceAuthority.publicKey,
+          governance: choiceGovernance,
+          governanceTokenMint: choiceMint,
+          proposal: proposalAddress,
+        })
+        .signers([governanceAuthority])
+        .rpc();
+    
+    before(async () => {
+      choiceMint = await createMint(
+        provider.connection,
+        governanceAuthority,
+        governanceAuthority.publicKey,
+        null,
+        6
+      );
+      
+      const balances: [Keypair, number][] = [
+        [proposer, PROPOSER_BALANCE],
+        [voter1, VOTER1_BALANCE],
+        [voter2, VOTER2_BALANCE],
+        [delegate, DELEGATE_BALANCE],
+      ];
+      for (const [holder, balance] of balances) {
+        const tokenAccount = await createAssociatedTokenAccount(provider.connection, holder, choiceMint, holder.publicKey);
+        await mintTo(provider.connection, governanceAuthority, choiceMint, tokenAccount, governanceAuthority.publicKey, balance * 10**6);
+        choiceAccounts.set(holder, tokenAccount);
+      }
+      
+      [choiceGovernance] = await PublicKey.findProgramAddress(
+        [Buffer.from('governance'), choiceMint.toBuffer()],
+        program.programId
+      );
+      
+      // Supply is 110k tokens, so quorum at 50% needs 55k tokens of votes
+      await program.methods
+        .initializeGovernance({
+          proposalThreshold: new anchor.BN(1000 * 10**6),
+          quorumThresholdBps: 5000,
+          approvalThresholdBps: 5000,
+          votingDuration: new anchor.BN(VOTING_DURATION),
+          executionWindow: new anchor.BN(3 * 24 * 60 * 60),
+          proposalCooldown: new anchor.BN(0),
+          maxTitleLen: 100,
+          maxDescriptionLen: 1000,
+          maxExecutionInstructionsLen: 512,
+          participationTiers: NO_PARTICIPATION_TIERS,
+        })
+        .accounts({
+          authority: governanceAuthority.publicKey,
+          governanceTokenMint: choiceMint,
+          governance: choiceGovernance,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([governanceAuthority])
+        .rpc();
+    });
+    
+    it("Executes the highest-weighted option of a three-option proposal", async () => {
+      const proposalAddress = await createChoiceProposal("Finale Venue");
+      
+      await voteFor(proposalAddress, voter1, { choice: [1] }); // 50k
+      await voteFor(proposalAddress, voter2, { choice: [0] }); // 30k
+      await voteFor(proposalAddress, delegate, { choice: [2] }); // 20k
+      
+      let proposal = await program.account.proposal.fetch(proposalAddress);
+      assert.equal(proposal.options.length, 3);
+      assert.equal(proposal.optionVotes[0].toNumber(), VOTER2_BALANCE * 10**6);
+      assert.equal(proposal.optionVotes[1].toNumber(), VOTER1_BALANCE * 10**6);
+      assert.equal(proposal.optionVotes[2].toNumber(), DELEGATE_BALANCE * 10**6);
+      
+      await new Promise(resolve => setTimeout(resolve, (VOTING_DURATION + 2) * 1000));
+      await execute(proposalAddress, THREE_OPTIONS[1].executionInstructions);
+      
+      proposal = await program.account.proposal.fetch(proposalAddress);
+      assert.deepEqual(proposal.state, { executed: {} });
+      assert.equal(proposal.winningOption, 1);
+    });
+    
+    it("Rejects votes that do not match a proposal option", async () => {
+      const proposalAddress = await createChoiceProposal("Invalid Votes");
+      
+      for (const voteType of [{ choice: [3] }, { yes: {} }]) {
+        try {
+          await voteFor(proposalAddress, voter1, voteType);
+          assert.fail("Vote outside the proposal's options should be rejected");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidVoteOption");
+        }
+      }
+    });
+    
+    it("Does not execute a winning option when quorum is not met", async () => {
+      const proposalAddress = await createChoiceProposal("Low Turnout");
+      
+      // 30k of 110k tokens is below the 50% quorum
+      await voteFor(proposalAddress, voter2, { choice: [2] });
+      
+      await new Promise(resolve => setTimeout(resolve, (VOTING_DURATION + 2) * 1000));
+      
+      try {
+        await execute(proposalAddress);
+        assert.fail("Proposal without quorum should not execute");
+      } catch (error) {
+        assert.include(error.toString(), "ExecutionThresholdNotMet");
+      }
+      
+      const proposal = await program.account.proposal.fetch(proposalAddress);
+      assert.deepEqual(proposal.state, { active: {} });
+      assert.isNull(proposal.winningOption);
+    });
+  });
+
+  describe("Participation Boost", () => {
+    const VOTING_DURATION = 3; // seconds
+    const BOOSTED_MULTIPLIER_BPS = 15000; // 1.5x after one counted vote
+    
+    let boostMint: PublicKey;
+    let boostGovernance: PublicKey;
+    const boostAccounts = new Map<Keypair, PublicKey>();
+    
+    const pdas = (proposalAddress: PublicKey, voter: Keypair) => {
+      const [voterWeight] = PublicKey.findProgramAddressSync(
+        [Buffer.from('voter_weight'), boostGovernance.toBuffer(), voter.publicKey.toBuffer()],
+        program.programId
+      );
+      const [vote] = PublicKey.findProgramAddressSync(
+        [Buffer.from('vote'), proposalAddress.toBuffer(), voter.publicKey.toBuffer()],
+        program.programId
+      );
+      return { voterWeight, vote };
+    };
+    
+    const createBoostProposal = async (title: string) => {
+      const governance = await program.account.governance.fetch(boostGovernance);
+      const [proposalAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('proposal'), boostGovernance.toBuffer(), governance.proposalCount.toArrayLike(Buffer, 'le', 8)],
+        program.programId
+      );
+      
+      await program.methods
+        .createProposal({ general: {} }, title, "Participation boost test", [], [])
+        .accounts({
+          proposer: proposer.publicKey,
+          governance: boostGovernance,
+          governanceTokenMint: boostMint,
+          proposerTokenAccount: boostAccounts.get(proposer),
+          proposerVoterWeight: pdas(proposalAddress, proposer).voterWeight,
+          proposal: proposalAddress,
+          relatedEvent: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc();
+      
+      return proposalAddress;
+    };
+    
+    const voteYes = (proposalAddress: PublicKey, voter: Keypair) =>
+      program.methods
+        .castVote({ yes: {} }, null)
+        .accounts({
+          voter: voter.publicKey,
+          governance: boostGovernance,
+          governanceTokenMint: boostMint,
+          proposal: proposalAddress,
+          voterTokenAccount: boostAccounts.get(voter),
+          ...pdas(proposalAddress, voter),
+          delegateVoterWeight: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc();
+    
+    const recordParticipation = (proposalAddress: PublicKey, voter: Keypair) =>
+      program.methods
+        .recordParticipation()
+        .accounts({
+          governance: boostGovernance,
+          governanceTokenMint: boostMint,
+          proposal: proposalAddress,
+          ...pdas(proposalAddress, voter),
+        })
+        .rpc();
+    
+    let quorumProposal: PublicKey;
+    let failedProposal: PublicKey;
+    
+    before(async () => {
+      boostMint = await createMint(
+        provider.connection,
+        governanceAuthority,
+        governanceAuthority.publicKey,
+        null,
+        6
+      );
+      
+      const balances: [Keypair, number][] = [
+        [proposer, PROPOSER_BALANCE],
+        [voter1, VOTER1_BALANCE],
+        [voter2, VOTER2_BALANCE],
+      ];
+      for (const [holder, balance] of balances) {
+        const tokenAccount = await createAssociatedTokenAccount(provider.connection, holder, boostMint, holder.publicKey);
+        await mintTo(provider.connection, governanceAuthority, boostMint, tokenAccount, governanceAuthority.publicKey, balance * 10**6);
+        boostAccounts.set(holder, tokenAccount);
+      }
+      
+      [boostGovernance] = await PublicKey.findProgramAddress(
+        [Buffer.from('governance'), boostMint.toBuffer()],
+        program.programId
+      );
+      
+      // Supply is 90k tokens, so quorum at 50% needs 45k tokens of votes
+      await program.methods
+        .initializeGovernance({
+          proposalThreshold: new anchor.BN(1000 * 10**6),
+          quorumThresholdBps: 5000,
+          approvalThresholdBps: 5000,
+          votingDuration: new anchor.BN(VOTING_DURATION),
+          executionWindow: new anchor.BN(3 * 24 * 60 * 60),
+          proposalCooldown: new anchor.BN(0),
+          maxTitleLen: 100,
+          maxDescriptionLen: 1000,
+          maxExecutionInstructionsLen: 512,
+          participationTiers: [
+            { minVotes: 1, multiplierBps: BOOSTED_MULTIPLIER_BPS },
+            { minVotes: 0, multiplierBps: 0 },
+            { minVotes: 0, multiplierBps: 0 },
+          ],
+        })
+        .accounts({
+          authority: governanceAuthority.publicKey,
+          governanceTokenMint: boostMint,
+          governance: boostGovernance,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([governanceAuthority])
+        .rpc();
+    });
+    
+    it("Votes at the base multiplier for a new voter", async () => {
+      quorumProposal = await createBoostProposal("Reaches Quorum");
+      failedProposal = await createBoostProposal("Misses Quorum");
+      
+      await voteYes(quorumProposal, voter1); // 50k, meets quorum
+      await voteYes(failedProposal, voter2); // 30k, below quorum
+      
+      const vote = await program.account.vote.fetch(pdas(quorumProposal, voter1).vote);
+      assert.equal(vote.votingPowerMultiplierBps, 10000);
+      assert.equal(vote.weight.toNumber(), VOTER1_BALANCE * 10**6);
+    });
+    
+    it("Does not count votes on proposals that missed quorum", async () => {
+      await new Promise(resolve => setTimeout(resolve, (VOTING_DURATION + 2) * 1000));
+      
+      try {
+        await recordParticipation(failedProposal, voter2);
+        assert.fail("Votes on a failed proposal should not count");
+      } catch (error) {
+        assert.include(error.toString(), "ParticipationNotEligible");
+      }
+      
+      const voterWeight = await program.account.voterWeight.fetch(pdas(failedProposal, voter2).voterWeight);
+      assert.equal(voterWeight.participationCount, 0);
+    });
+    
+    it("Counts each vote on a quorum-reaching proposal once", async () => {
+      await recordParticipation(quorumProposal, voter1);
+      
+      try {
+        await recordParticipation(quorumProposal, voter1);
+        assert.fail("Participation should only be recorded once per vote");
+      } catch (error) {
+        assert.include(error.toString(), "ParticipationAlreadyRecorded");
+      }
+      
+      const voterWeight = await program.account.voterWeight.fetch(pdas(quorumProposal, voter1).voterWeight);
+      assert.equal(voterWeight.participationCount, 1);
+    });
+    
+    it("Boosts voting power for a long-term voter", async () => {
+      const proposalAddress = await createBoostProposal("Boosted Vote");
+      
+      await voteYes(proposalAddress, voter1);
+      await voteYes(proposalAddress, voter2);
+      
+      const boosted = await program.account.vote.fetch(pdas(proposalAddress, voter1).vote);
+      assert.equal(boosted.votingPowerMultiplierBps, BOOSTED_MULTIPLIER_BPS);
+      assert.equal(boosted.weight.toNumber(), VOTER1_BALANCE * 10**6 * 1.5);
+      
+      // voter2's only vote was on a failed proposal, so it stays at the base multiplier
+      const base = await program.account.vote.fetch(pdas(proposalAddress, voter2).vote);
+      assert.equal(base.votingPowerMultiplierBps, 10000);
+      assert.equal(base.weight.toNumber(), VOTER2_BALANCE * 10**6);
+    });
+  });
+
+  describe("Proposal Execution Replay Guard", () => {
+    const VOTING_DURATION = 3; // seconds
+    const EXECUTION_INSTRUCTIONS = Buffer.from([7, 7, 7]);
+    
+    let guardMint: PublicKey;
+    let guardGovernance: PublicKey;
+    const guardAccounts = new Map<Keypair, PublicKey>();
+    
+    const voterWeightFor = (voter: Keypair) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('voter_weight'), guardGovernance.toBuffer(), voter.publicKey.toBuffer()],
+        program.programId
+      )[0];
+    
+    const createPassingProposal = async (title: string) => {
+      const governance = await program.account.governance.fetch(guardGovernance);
+      const [proposalAddress] = PublicKey.findProgramAddressSync(
+        [Buffer.from('proposal'), guardGovernance.toBuffer(), governance.proposalCount.toArrayLike(Buffer, 'le', 8)],
+        program.programId
+      );
+      
+      await program.methods
+        .createProposal({ configuration: {} }, title, "Replay guard test", EXECUTION_INSTRUCTIONS, [])
+        .accounts({
+          proposer: proposer.publicKey,
+          governance: guardGovernance,
+          governanceTokenMint: guardMint,
+          proposerTokenAccount: guardAccounts.get(proposer),
+          proposerVoterWeight: voterWeightFor(proposer),
+          proposal: proposalAddress,
+          relatedEvent: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc();
+      
+      const [voteAddress] = PublicKey.findProgramAddressSync(
+        [Buffer.from('vote'), proposalAddress.toBuffer(), voter1.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .castVote({ yes: {} }, null)
+        .accounts({
+          voter: voter1.publicKey,
+          governance: guardGovernance,
+          governanceTokenMint: guardMint,
+          proposal: proposalAddress,
+          voterTokenAccount: guardAccounts.get(voter1),
+          voterWeight: voterWeightFor(voter1),
+          vote: voteAddress,
+          delegateVoterWeight: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+      
+      return proposalAddress;
+    };
+    
+    const execute = (proposalAddress: PublicKey, instructions: Buffer) =>
+      program.methods
+        .executeProposal(instructions)
+        .accounts({
+          executor: governanceAuthority.publicKey,
+          governance: guardGovernance,
+          governanceTokenMint: guardMint,
+          proposal: proposalAddress,
+        })
+        .signers([governanceAuthority])
+        .rpc();
+    
+    let executedProposal: PublicKey;
+    let tamperedProposal: PublicKey;
+    
+    before(async () => {
+      guardMint = await createMint(
+        provider.connection,
+        governanceAuthority,
+        governanceAuthority.publicKey,
+        null,
+        6
+      );
+      
+      const balances: [Keypair, number][] = [
+        [proposer, PROPOSER_BALANCE],
+        [voter1, VOTER1_BALANCE],
+      ];
+      for (const [holder, balance] of balances) {
+        const tokenAccount = await createAssociatedTokenAccount(provider.connection, holder, guardMint, holder.publicKey);
+        await mintTo(provider.connection, governanceAuthority, guardMint, tokenAccount, governanceAuthority.publicKey, balance * 10**6);
+        guardAccounts.set(holder, tokenAccount);
+      }
+      
+      [guardGovernance] = PublicKey.findProgramAddressSync(
+        [Buffer.from('governance'), guardMint.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .initializeGovernance({
+          proposalThreshold: new anchor.BN(1000 * 10**6),
+          quorumThresholdBps: 5000,
+          approvalThresholdBps: 5000,
+          votingDuration: new anchor.BN(VOTING_DURATION),
+          executionWindow: new anchor.BN(3 * 24 * 60 * 60),
+          proposalCooldown: new anchor.BN(0),
+          maxTitleLen: 100,
+          maxDescriptionLen: 1000,
+          maxExecutionInstructionsLen: 512,
+          participationTiers: NO_PARTICIPATION_TIERS,
+        })
+        .accounts({
+          authority: governanceAuthority.publicKey,
+          governanceTokenMint: guardMint,
+          governance: guardGovernance,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([governanceAuthority])
+        .rpc();
+      
+      // voter1 holds 50k of 60k tokens, so both proposals pass
+      executedProposal = await createPassingProposal("Executes Once");
+      tamperedProposal = await createPassingProposal("Tampered Instructions");
+      
+      await new Promise(resolve => setTimeout(resolve, (VOTING_DURATION + 2) * 1000));
+    });
+    
+    it("Executes a passed proposal with its committed instructions", async () => {
+      await execute(executedProposal, EXECUTION_INSTRUCTIONS);
+      
+      const proposal = await program.account.proposal.fetch(executedProposal);
+      assert.deepEqual(proposal.state, { executed: {} });
+      assert.isTrue(proposal.executed);
+    });
+    
+    it("Rejects executing the same proposal a second time", async () => {
+      try {
+        await execute(executedProposal, EXECUTION_INSTRUCTIONS);
+        assert.fail("Proposal should only execute once");
+      } catch (error) {
+        assert.include(error.toString(), "ProposalAlreadyExecuted");
+      }
+    });
+    
+    it("Rejects execution with tampered instructions", async () => {
+      try {
+        await execute(tamperedProposal, Buffer.from([6, 6, 6]));
+        assert.fail("Tampered instructions should be rejected");
+      } catch (error) {
+        assert.include(error.toString(), "ExecutionInstructionsMismatch");
+      }
+      
+      const proposal = await program.account.proposal.fetch(tamperedProposal);
+      assert.isFalse(proposal.executed);
+    });
   });
 });
@@ -14,13 +14,21 @@ import {
   TOKEN_PROGRAM_ID, 
   ASSOCIATED_TOKEN_PROGRAM_ID,
   createMint,
+  createAccount,
   createAssociatedTokenAccount,
   mintTo,
   getAccount,
+  getMint,
   getAssociatedTokenAddress,
+  getOrCreateAssociatedTokenAccount,
   createTransferInstruction
 } from "@solana/spl-token";
+import {
+  PROGRAM_ID as METADATA_PROGRAM_ID,
+  createCreateMetadataAccountV3Instruction,
+} from "@metaplex-foundation/mpl-token-metadata";
 import { assert } from "chai";
+import { createHash } from "crypto";
 import BN from "bn.js";
 
 describe("marketplace", () => {
@@ -60,6 +68,24 @@ describe("marketplace", () => {
   const BID_AMOUNT = new BN(8 * LAMPORTS_PER_SOL);
   const ROYALTY_BPS = 500; // 5%
 
+  // Receipt index and current receipt segment for a royalty recipient
+  const royaltyReceiptAccounts = async (recipient: PublicKey) => {
+    const [royaltyReceiptIndex] = PublicKey.findProgramAddressSync(
+      [Buffer.from("royalty_receipt_index"), recipient.toBuffer()],
+      marketplace.programId
+    );
+    const index = await marketplace.account.royaltyReceiptIndex.fetchNullable(royaltyReceiptIndex);
+    const [royaltyReceiptSegment] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("royalty_receipts"),
+        recipient.toBuffer(),
+        new BN(index ? index.currentSegment : 0).toArrayLike(Buffer, "le", 4),
+      ],
+      marketplace.programId
+    );
+    return { royaltyReceiptIndex, royaltyReceiptSegment };
+  };
+
   before(async () => {
     // Initialize test wallets
     platform = Keypair.generate();
@@ -871,6 +897,7 @@ describe("marketplace", () => {
           marketplaceConfig,
           feeCollector: feeCollector.publicKey,
           creator: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           nftProgram: nftProgram.programId,
@@ -930,6 +957,7 @@ describe("marketplace", () => {
             marketplaceConfig,
             feeCollector: feeCollector.publicKey,
             creator: creator.publicKey,
+            fallbackRoyaltyRecipient: platform.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             nftProgram: nftProgram.programId,
@@ -1055,6 +1083,7 @@ describe("marketplace", () => {
             marketplaceConfig,
             feeCollector: feeCollector.publicKey,
             creator: creator.publicKey,
+            fallbackRoyaltyRecipient: platform.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             nftProgram: nftProgram.programId,
@@ -1943,6 +1972,7 @@ describe("marketplace", () => {
           marketplaceConfig,
           feeCollector: feeCollector.publicKey,
           creator: creator1.publicKey, // First creator for primary royalty
+          fallbackRoyaltyRecipient: platform.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           nftProgram: nftProgram.programId,
@@ -2731,4 +2761,6999 @@ describe("marketplace", () => {
       assert.isBelow(totalTime / batchSize, 2, "Each listing should complete within 2 seconds");
     });
   });
+
+  describe("Dispute Auto-Resolution", () => {
+    const ESCROW_AMOUNT = 1_000_000;
+    const ESCROW_FEE_BPS = 250;
+    const ARBITRATION_WINDOW = 2; // seconds
+
+    let paymentMint: PublicKey;
+    let buyerPaymentToken: PublicKey;
+    let sellerPaymentToken: PublicKey;
+    let marketplaceState: PublicKey;
+    let feeVault: PublicKey;
+    let vaultTokenAccount: PublicKey;
+
+    const openEscrowDispute = async (transactionId: string, defaultResolution: any, category: any = { itemNotReceived: {} }) => {
+      const [escrowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), Buffer.from(transactionId)],
+        marketplace.programId
+      );
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, paymentMint, escrowPda, true)
+      ).address;
+
+      await marketplace.methods
+        .createEscrow({
+          transactionId,
+          amount: new BN(ESCROW_AMOUNT),
+          expiryTime: new BN(Math.floor(Date.now() / 1000) + 3600),
+          terms: "Ticket delivery before event",
+          disputePeriod: new BN(3600),
+          platformFeeRate: ESCROW_FEE_BPS,
+          arbitrationWindow: new BN(ARBITRATION_WINDOW),
+          inspectionPeriod: new BN(0),
+          minTimeBeforeDispute: new BN(0),
+          defaultResolution,
+          releaseCondition: null,
+          confirmers: [],
+        })
+        .accounts({
+          creator: buyer.publicKey,
+          escrow: escrowPda,
+          seller: seller.publicKey,
+          arbitrator: platform.publicKey,
+          creatorTokenAccount: buyerPaymentToken,
+          escrowTokenAccount: escrowToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const [disputePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("dispute"), escrowPda.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .initiateDispute({
+          category,
+          description: "Ticket never delivered",
+          evidenceLinks: [],
+          requestedResolution: { refundBuyer: {} },
+        })
+        .accounts({
+          disputer: buyer.publicKey,
+          dispute: disputePda,
+          escrow: escrowPda,
+          marketplace: marketplaceState,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      return { escrow: escrowPda, dispute: disputePda, escrowToken };
+    };
+
+    const autoResolve = (caller: Keypair, accounts: { escrow: PublicKey; dispute: PublicKey; escrowToken: PublicKey }) =>
+      marketplace.methods
+        .autoResolveExpiredDispute()
+        .accounts({
+          caller: caller.publicKey,
+          dispute: accounts.dispute,
+          escrow: accounts.escrow,
+          escrowTokenAccount: accounts.escrowToken,
+          buyerTokenAccount: buyerPaymentToken,
+          sellerTokenAccount: sellerPaymentToken,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([caller])
+        .rpc();
+
+    before(async () => {
+      [marketplaceState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("marketplace")],
+        marketplace.programId
+      );
+      [feeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_vault"), marketplaceState.toBuffer()],
+        marketplace.programId
+      );
+
+      paymentMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      buyerPaymentToken = await createAssociatedTokenAccount(provider.connection, buyer, paymentMint, buyer.publicKey);
+      sellerPaymentToken = await createAssociatedTokenAccount(provider.connection, seller, paymentMint, seller.publicKey);
+      vaultTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, paymentMint, feeVault, true)
+      ).address;
+      await mintTo(provider.connection, platform, paymentMint, buyerPaymentToken, platform, ESCROW_AMOUNT * 10);
+    });
+
+    it("rejects auto-resolution before the arbitrator deadline", async () => {
+      const accounts = await openEscrowDispute("auto-resolve-early", { favorBuyer: {} });
+
+      try {
+        await autoResolve(buyer, accounts);
+        assert.fail("Should not auto-resolve before the deadline");
+      } catch (error) {
+        assert.include(error.toString(), "DisputeDeadlineNotReached");
+      }
+
+      const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+      assert.deepEqual(dispute.state, { open: {} });
+    });
+
+    it("auto-resolves in favor of the buyer after the deadline", async () => {
+      const accounts = await openEscrowDispute("auto-resolve-buyer", { favorBuyer: {} });
+      const buyerBefore = await getAccount(provider.connection, buyerPaymentToken);
+
+      // Let the arbitration window lapse
+      await new Promise(resolve => setTimeout(resolve, (ARBITRATION_WINDOW + 2) * 1000));
+
+      await autoResolve(buyer, accounts);
+
+      const buyerAfter = await getAccount(provider.connection, buyerPaymentToken);
+      assert.equal(Number(buyerAfter.amount - buyerBefore.amount), ESCROW_AMOUNT);
+
+      const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+      assert.deepEqual(dispute.state, { resolved: {} });
+      assert.deepEqual(dispute.decision, { favorBuyer: {} });
+
+      const escrowAccount = await marketplace.account.escrow.fetch(accounts.escrow);
+      assert.deepEqual(escrowAccount.state, { completed: {} });
+      assert.equal(escrowAccount.amount.toNumber(), 0);
+    });
+
+    it("splits escrowed funds and charges the fee on the seller share", async () => {
+      const accounts = await openEscrowDispute("auto-resolve-split", { split: {} });
+      const buyerBefore = await getAccount(provider.connection, buyerPaymentToken);
+      const sellerBefore = await getAccount(provider.connection, sellerPaymentToken);
+      const vaultBefore = await getAccount(provider.connection, vaultTokenAccount);
+      const feeVaultBefore = await marketplace.account.feeVault.fetch(feeVault);
+
+      await new Promise(resolve => setTimeout(resolve, (ARBITRATION_WINDOW + 2) * 1000));
+
+      // Either party may trigger the fallback
+      await autoResolve(seller, accounts);
+
+      const buyerShare = Math.floor(ESCROW_AMOUNT / 2);
+      const sellerGross = ESCROW_AMOUNT - buyerShare;
+      const platformFee = Math.floor(sellerGross * ESCROW_FEE_BPS / 10000);
+
+      const buyerAfter = await getAccount(provider.connection, buyerPaymentToken);
+      const sellerAfter = await getAccount(provider.connection, sellerPaymentToken);
+      const vaultAfter = await getAccount(provider.connection, vaultTokenAccount);
+      const feeVaultAfter = await marketplace.account.feeVault.fetch(feeVault);
+      const escrowTokenAfter = await getAccount(provider.connection, accounts.escrowToken);
+
+      assert.equal(Number(buyerAfter.amount - buyerBefore.amount), buyerShare);
+      assert.equal(Number(sellerAfter.amount - sellerBefore.amount), sellerGross - platformFee);
+      assert.equal(Number(escrowTokenAfter.amount), 0);
+
+      // The fee is held in the vault and counted as withdrawable
+      assert.equal(Number(vaultAfter.amount - vaultBefore.amount), platformFee);
+      assert.equal(
+        feeVaultAfter.accumulatedFees.sub(feeVaultBefore.accumulatedFees).toNumber(),
+        platformFee
+      );
+    });
+
+    it("rejects a platform fee account outside the fee vault", async () => {
+      const accounts = await openEscrowDispute("auto-resolve-fee-account", { favorSeller: {} });
+      const sellerOwnedAccount = await createAccount(provider.connection, seller, paymentMint, seller.publicKey, Keypair.generate());
+
+      await new Promise(resolve => setTimeout(resolve, (ARBITRATION_WINDOW + 2) * 1000));
+
+      try {
+        await marketplace.methods
+          .autoResolveExpiredDispute()
+          .accounts({
+            caller: seller.publicKey,
+            dispute: accounts.dispute,
+            escrow: accounts.escrow,
+            escrowTokenAccount: accounts.escrowToken,
+            buyerTokenAccount: buyerPaymentToken,
+            sellerTokenAccount: sellerPaymentToken,
+            marketplace: marketplaceState,
+            feeVault,
+            vaultTokenAccount: sellerOwnedAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([seller])
+          .rpc();
+        assert.fail("Should not pay the platform fee to the seller");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidVaultTokenAccount");
+      }
+
+      const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+      assert.deepEqual(dispute.state, { open: {} });
+    });
+
+    it("blocks unpausing while a critical dispute is open", async () => {
+      const unpause = () =>
+        marketplace.methods
+          .unpauseMarketplace({ gradualRollout: false, featuresToEnable: [] })
+          .accounts({ admin: platform.publicKey, marketplace: marketplaceState, feeVault, vaultTokenAccount })
+          .signers([platform])
+          .rpc();
+
+      await marketplace.methods
+        .updateHealthThresholds({
+          requireFeeVaultSolvency: true,
+          feeMint: paymentMint,
+          minPauseDuration: new BN(0),
+          blockOnCriticalDisputes: true,
+        })
+        .accounts({ admin: platform.publicKey, marketplace: marketplaceState })
+        .signers([platform])
+        .rpc();
+
+      const before = await marketplace.account.marketplace.fetch(marketplaceState);
+      const accounts = await openEscrowDispute("critical-dispute-unpause", { favorBuyer: {} }, { fraud: {} });
+      const during = await marketplace.account.marketplace.fetch(marketplaceState);
+      assert.equal(during.openCriticalDisputes, before.openCriticalDisputes + 1);
+
+      await marketplace.methods
+        .pauseMarketplace({ pauseLevel: { full: {} }, reason: "Fraud report", duration: null, affectedFeatures: [] })
+        .accounts({ admin: platform.publicKey, marketplace: marketplaceState })
+        .signers([platform])
+        .rpc();
+
+      try {
+        await unpause();
+        assert.fail("Unpause should wait for the critical dispute");
+      } catch (err) {
+        assert.include(err.toString(), "SystemHealthCheckFailed");
+        // Reason code 3: critical disputes pending
+        assert.isTrue(err.logs.some((log: string) => log.includes("reason code 3")));
+      }
+
+      await new Promise(resolve => setTimeout(resolve, (ARBITRATION_WINDOW + 2) * 1000));
+      await autoResolve(buyer, accounts);
+
+      const after = await marketplace.account.marketplace.fetch(marketplaceState);
+      assert.equal(after.openCriticalDisputes, before.openCriticalDisputes);
+      await unpause();
+      assert.isFalse((await marketplace.account.marketplace.fetch(marketplaceState)).isPaused);
+    });
+  });
+
+  describe("Royalty Rounding Modes", () => {
+    // 2.5% fee -> 25000.25, 5% royalty -> 50000.5: neither divides evenly
+    const UNEVEN_PRICE = new BN(1_000_010);
+
+    const expectedSplit = (mode: "floor" | "ceil" | "nearest") => {
+      const round = (numerator: number) => {
+        if (mode === "floor") return Math.floor(numerator / 10000);
+        if (mode === "ceil") return Math.ceil(numerator / 10000);
+        return Math.floor((numerator + 5000) / 10000);
+      };
+      const price = UNEVEN_PRICE.toNumber();
+      const platformFee = round(price * PLATFORM_FEE_BPS);
+      const royalty = round(price * ROYALTY_BPS);
+      return { platformFee, royalty, seller: price - platformFee - royalty };
+    };
+
+    const sellUnderMode = async (mode: "floor" | "ceil" | "nearest") => {
+      await marketplace.methods
+        .updateRoundingMode({ [mode]: {} })
+        .accounts({
+          marketplaceConfig,
+          admin: platform.publicKey,
+        })
+        .signers([platform])
+        .rpc();
+
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const mintSellerToken = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      const mintBuyerToken = await createAssociatedTokenAccount(provider.connection, buyer, mint, buyer.publicKey);
+      await mintTo(provider.connection, seller, mint, mintSellerToken, seller, 1);
+
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), mint.toBuffer()],
+        nftProgram.programId
+      );
+      await nftProgram.methods
+        .createMetadata("Rounding NFT", "RNFT", "https://rounding.uri", ROYALTY_BPS, [creator.publicKey])
+        .accounts({
+          metadata,
+          mint,
+          mintAuthority: seller.publicKey,
+          payer: seller.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      const [mintListing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const [mintEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), mintListing.toBuffer()],
+        marketplace.programId
+      );
+      const mintEscrowToken = await getAssociatedTokenAddress(mint, mintEscrow, true);
+
+      await marketplace.methods
+        .createListing(UNEVEN_PRICE, null, null)
+        .accounts({
+          seller: seller.publicKey,
+          nftMint: mint,
+          nftMetadata: metadata,
+          listing: mintListing,
+          escrow: mintEscrow,
+          sellerTokenAccount: mintSellerToken,
+          escrowTokenAccount: mintEscrowToken,
+          marketplaceConfig,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          nftProgram: nftProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      const sellerBefore = await provider.connection.getBalance(seller.publicKey);
+      const feeCollectorBefore = await provider.connection.getBalance(feeCollector.publicKey);
+      const creatorBefore = await provider.connection.getBalance(creator.publicKey);
+
+      await marketplace.methods
+        .buyNow()
+        .accounts({
+          buyer: buyer.publicKey,
+          seller: seller.publicKey,
+          listing: mintListing,
+          escrow: mintEscrow,
+          nftMint: mint,
+          nftMetadata: metadata,
+          sellerTokenAccount: mintSellerToken,
+          buyerTokenAccount: mintBuyerToken,
+          escrowTokenAccount: mintEscrowToken,
+          marketplaceConfig,
+          feeCollector: feeCollector.publicKey,
+          creator: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          nftProgram: nftProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      return {
+        platformFee: (await provider.connection.getBalance(feeCollector.publicKey)) - feeCollectorBefore,
+        royalty: (await provider.connection.getBalance(creator.publicKey)) - creatorBefore,
+        seller: (await provider.connection.getBalance(seller.publicKey)) - sellerBefore,
+      };
+    };
+
+    after(async () => {
+      // Restore the default so later suites see truncating math
+      await marketplace.methods
+        .updateRoundingMode({ floor: {} })
+        .accounts({ marketplaceConfig, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+    });
+
+    it("rounds fee and royalty down in floor mode", async () => {
+      const split = await sellUnderMode("floor");
+      assert.deepEqual(split, expectedSplit("floor"));
+      assert.equal(split.platformFee + split.royalty + split.seller, UNEVEN_PRICE.toNumber());
+    });
+
+    it("rounds fee and royalty up in ceil mode", async () => {
+      const split = await sellUnderMode("ceil");
+      assert.deepEqual(split, expectedSplit("ceil"));
+      assert.equal(split.platformFee + split.royalty + split.seller, UNEVEN_PRICE.toNumber());
+    });
+
+    it("rounds half up in nearest mode", async () => {
+      const split = await sellUnderMode("nearest");
+      assert.deepEqual(split, expectedSplit("nearest"));
+      assert.equal(split.platformFee + split.royalty + split.seller, UNEVEN_PRICE.toNumber());
+
+      // The three modes disagree on this price, so the distinction is observable
+      assert.notDeepEqual(expectedSplit("floor"), expectedSplit("ceil"));
+      assert.notDeepEqual(expectedSplit("nearest"), expectedSplit("floor"));
+      assert.notDeepEqual(expectedSplit("nearest"), expectedSplit("ceil"));
+    });
+
+    it("rejects rounding mode changes from non-admin", async () => {
+      try {
+        await marketplace.methods
+          .updateRoundingMode({ ceil: {} })
+          .accounts({ marketplaceConfig, admin: seller.publicKey })
+          .signers([seller])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "ConstraintHasOne");
+      }
+    });
+  });
+
+  describe("Unpause Health Checks", () => {
+    let marketplaceState: PublicKey;
+    let feeVault: PublicKey;
+    let feeMint: PublicKey;
+    let vaultTokenAccount: PublicKey;
+
+    const pause = (duration: number | null) =>
+      marketplace.methods
+        .pauseMarketplace({
+          pauseLevel: { full: {} },
+          reason: "Scheduled maintenance",
+          duration: duration === null ? null : new BN(duration),
+          affectedFeatures: [],
+        })
+        .accounts({ admin: platform.publicKey, marketplace: marketplaceState })
+        .signers([platform])
+        .rpc();
+
+    const setThresholds = (minPauseDuration: number) =>
+      marketplace.methods
+        .updateHealthThresholds({
+          requireFeeVaultSolvency: true,
+          feeMint,
+          minPauseDuration: new BN(minPauseDuration),
+          blockOnCriticalDisputes: true,
+        })
+        .accounts({ admin: platform.publicKey, marketplace: marketplaceState })
+        .signers([platform])
+        .rpc();
+
+    const unpause = (vault = vaultTokenAccount) =>
+      marketplace.methods
+        .unpauseMarketplace({ gradualRollout: false, featuresToEnable: [] })
+        .accounts({
+          admin: platform.publicKey,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount: vault,
+        })
+        .signers([platform])
+        .rpc();
+
+    const tryAutoUnpause = (caller: Keypair) =>
+      marketplace.methods
+        .tryAutoUnpause()
+        .accounts({
+          caller: caller.publicKey,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+        })
+        .signers([caller])
+        .rpc();
+
+    before(async () => {
+      [marketplaceState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("marketplace")],
+        marketplace.programId
+      );
+      [feeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_vault"), marketplaceState.toBuffer()],
+        marketplace.programId
+      );
+
+      feeMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      vaultTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, feeMint, feeVault, true)
+      ).address;
+    });
+
+    it("blocks unpause while a health check fails", async () => {
+      await setThresholds(3600); // Must stay paused for an hour
+      await pause(null);
+
+      try {
+        await unpause();
+        assert.fail("Unpause should be blocked by the health check");
+      } catch (err) {
+        assert.include(err.toString(), "SystemHealthCheckFailed");
+        // Reason code 2: minimum pause duration not met
+        assert.isTrue(err.logs.some((log: string) => log.includes("reason code 2")));
+      }
+
+      const state = await marketplace.account.marketplace.fetch(marketplaceState);
+      assert.isTrue(state.isPaused);
+    });
+
+    it("rejects a vault account for a mint other than the fee mint", async () => {
+      await setThresholds(0);
+      const otherMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      const otherVault = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, otherMint, feeVault, true)
+      ).address;
+
+      try {
+        await unpause(otherVault);
+        assert.fail("Unpause should only read the fee mint's vault account");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidVaultTokenAccount");
+      }
+    });
+
+    it("unpauses once all health checks pass", async () => {
+      await setThresholds(0);
+      await unpause();
+
+      const state = await marketplace.account.marketplace.fetch(marketplaceState);
+      assert.isFalse(state.isPaused);
+      assert.isNotNull(state.unpausedAt);
+    });
+
+    it("lets anyone unpause after the scheduled time", async () => {
+      await pause(2);
+
+      // Too early
+      try {
+        await tryAutoUnpause(buyer);
+        assert.fail("Auto-unpause should wait for the scheduled time");
+      } catch (err) {
+        assert.include(err.toString(), "AutoUnpauseNotReady");
+      }
+
+      await new Promise(resolve => setTimeout(resolve, 4000));
+      await tryAutoUnpause(buyer);
+
+      const state = await marketplace.account.marketplace.fetch(marketplaceState);
+      assert.isFalse(state.isPaused);
+      assert.isNull(state.autoUnpauseAt);
+    });
+
+    it("rejects auto-unpause when no schedule was set", async () => {
+      await pause(null);
+
+      try {
+        await tryAutoUnpause(buyer);
+        assert.fail("Auto-unpause should require a schedule");
+      } catch (err) {
+        assert.include(err.toString(), "AutoUnpauseNotScheduled");
+      }
+
+      await unpause();
+    });
+  });
+
+  describe("Fallback Royalty Recipient", () => {
+    const SALE_PRICE = new BN(LAMPORTS_PER_SOL);
+    const fallbackRecipient = Keypair.generate();
+
+    const setFallback = (recipient: PublicKey) =>
+      marketplace.methods
+        .updateFallbackRoyaltyRecipient(recipient)
+        .accounts({ marketplaceConfig, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+
+    // Lists a fresh NFT whose royalty goes to `royaltyRecipient`, then buys it
+    const sellWithRoyaltyRecipient = async (royaltyRecipient: PublicKey) => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const mintSellerToken = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      const mintBuyerToken = await createAssociatedTokenAccount(provider.connection, buyer, mint, buyer.publicKey);
+      await mintTo(provider.connection, seller, mint, mintSellerToken, seller, 1);
+
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), mint.toBuffer()],
+        nftProgram.programId
+      );
+      await nftProgram.methods
+        .createMetadata("Fallback NFT", "FNFT", "https://fallback.uri", ROYALTY_BPS, [royaltyRecipient])
+        .accounts({
+          metadata,
+          mint,
+          mintAuthority: seller.publicKey,
+          payer: seller.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      const [mintListing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const [mintEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), mintListing.toBuffer()],
+        marketplace.programId
+      );
+      const mintEscrowToken = await getAssociatedTokenAddress(mint, mintEscrow, true);
+
+      await marketplace.methods
+        .createListing(SALE_PRICE, null, null)
+        .accounts({
+          seller: seller.publicKey,
+          nftMint: mint,
+          nftMetadata: metadata,
+          listing: mintListing,
+          escrow: mintEscrow,
+          sellerTokenAccount: mintSellerToken,
+          escrowTokenAccount: mintEscrowToken,
+          marketplaceConfig,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          nftProgram: nftProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      const payments: { recipient: string; amount: number; usedFallback: boolean }[] = [];
+      const listener = marketplace.addEventListener("RoyaltyPaid", (event) => {
+        payments.push({
+          recipient: event.recipient.toBase58(),
+          amount: event.amount.toNumber(),
+          usedFallback: event.usedFallback,
+        });
+      });
+
+      await marketplace.methods
+        .buyNow()
+        .accounts({
+          buyer: buyer.publicKey,
+          seller: seller.publicKey,
+          listing: mintListing,
+          escrow: mintEscrow,
+          nftMint: mint,
+          nftMetadata: metadata,
+          sellerTokenAccount: mintSellerToken,
+          buyerTokenAccount: mintBuyerToken,
+          escrowTokenAccount: mintEscrowToken,
+          marketplaceConfig,
+          feeCollector: feeCollector.publicKey,
+          creator: royaltyRecipient,
+          fallbackRoyaltyRecipient: fallbackRecipient.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          nftProgram: nftProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      const buyerToken = await getAccount(provider.connection, mintBuyerToken);
+      return { payments, buyerTokenAmount: Number(buyerToken.amount) };
+    };
+
+    const expectedRoyalty = (SALE_PRICE.toNumber() * ROYALTY_BPS) / 10000;
+
+    before(async () => {
+      await setFallback(fallbackRecipient.publicKey);
+    });
+
+    after(async () => {
+      // Restore the default (admin) fallback for later suites
+      await setFallback(platform.publicKey);
+    });
+
+    it("pays the configured royalty recipient when its account is open", async () => {
+      const creatorBefore = await provider.connection.getBalance(creator.publicKey);
+
+      const { payments } = await sellWithRoyaltyRecipient(creator.publicKey);
+
+      const creatorAfter = await provider.connection.getBalance(creator.publicKey);
+      assert.equal(creatorAfter - creatorBefore, expectedRoyalty);
+      assert.deepEqual(payments, [
+        { recipient: creator.publicKey.toBase58(), amount: expectedRoyalty, usedFallback: false },
+      ]);
+    });
+
+    it("reroutes the royalty to the fallback when the recipient is closed", async () => {
+      // Fund a creator wallet, then drain it so the account no longer exists
+      const closedCreator = Keypair.generate();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(closedCreator.publicKey, LAMPORTS_PER_SOL)
+      );
+      const balance = await provider.connection.getBalance(closedCreator.publicKey);
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: closedCreator.publicKey,
+            toPubkey: platform.publicKey,
+            lamports: balance - 5000, // Leave exactly the transaction fee
+          })
+        ),
+        [closedCreator]
+      );
+      assert.isNull(await provider.connection.getAccountInfo(closedCreator.publicKey));
+
+      const fallbackBefore = await provider.connection.getBalance(fallbackRecipient.publicKey);
+
+      const { payments, buyerTokenAmount } = await sellWithRoyaltyRecipient(closedCreator.publicKey);
+
+      const fallbackAfter = await provider.connection.getBalance(fallbackRecipient.publicKey);
+      assert.equal(fallbackAfter - fallbackBefore, expectedRoyalty);
+      assert.equal(await provider.connection.getBalance(closedCreator.publicKey), 0);
+      assert.deepEqual(payments, [
+        { recipient: fallbackRecipient.publicKey.toBase58(), amount: expectedRoyalty, usedFallback: true },
+      ]);
+
+      // The sale still completed
+      assert.equal(buyerTokenAmount, 1);
+    });
+
+    it("rejects fallback recipient changes from non-admin", async () => {
+      try {
+        await marketplace.methods
+          .updateFallbackRoyaltyRecipient(buyer.publicKey)
+          .accounts({ marketplaceConfig, admin: seller.publicKey })
+          .signers([seller])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "ConstraintHasOne");
+      }
+    });
+  });
+
+  describe("Listing Offers", () => {
+    const LIST_PRICE = new BN(2 * LAMPORTS_PER_SOL);
+    const OFFER_AMOUNT = new BN(LAMPORTS_PER_SOL);
+
+    const listTicket = async (allowOffers: boolean) => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerTokenAccount, seller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+
+      await marketplace.methods
+        .createListing(LIST_PRICE, { fixedPrice: {} }, null, { creator: creator.publicKey, percentageBps: ROYALTY_BPS, royaltyPayoutMint: null, minRoyalty: new BN(0) })
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      if (allowOffers) {
+        await marketplace.methods
+          .setAllowOffers(true)
+          .accounts({ seller: seller.publicKey, listing })
+          .signers([seller])
+          .rpc();
+      }
+
+      return { mint, listing, escrowTokenAccount };
+    };
+
+    const offerPda = (listing: PublicKey, offerer: Keypair) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("offer"), listing.toBuffer(), offerer.publicKey.toBuffer()],
+        marketplace.programId
+      )[0];
+
+    const makeOffer = (listing: PublicKey, offerer: Keypair, amount = OFFER_AMOUNT) =>
+      marketplace.methods
+        .makeOffer(amount)
+        .accounts({
+          buyer: offerer.publicKey,
+          marketplaceConfig,
+          listing,
+          offer: offerPda(listing, offerer),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([offerer])
+        .rpc();
+
+    const rejectOffer = (listing: PublicKey, offerer: Keypair, authority: Keypair) =>
+      marketplace.methods
+        .rejectOffer()
+        .accounts({
+          authority: authority.publicKey,
+          listing,
+          offer: offerPda(listing, offerer),
+          buyer: offerer.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    it("escrows an offer on an offer-enabled listing", async () => {
+      const { listing } = await listTicket(true);
+      const buyerBefore = await provider.connection.getBalance(buyer.publicKey);
+
+      await makeOffer(listing, buyer);
+
+      const offer = await marketplace.account.marketplaceOffer.fetch(offerPda(listing, buyer));
+      assert.equal(offer.listing.toBase58(), listing.toBase58());
+      assert.equal(offer.buyer.toBase58(), buyer.publicKey.toBase58());
+      assert.equal(offer.amount.toNumber(), OFFER_AMOUNT.toNumber());
+
+      // The offer account holds the offered lamports on top of its rent
+      const offerInfo = await provider.connection.getAccountInfo(offerPda(listing, buyer));
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(offerInfo.data.length);
+      assert.equal(offerInfo.lamports, rent + OFFER_AMOUNT.toNumber());
+
+      const buyerAfter = await provider.connection.getBalance(buyer.publicKey);
+      assert.isAtLeast(buyerBefore - buyerAfter, OFFER_AMOUNT.toNumber());
+    });
+
+    it("rejects offers on a listing with offers disabled", async () => {
+      const { listing } = await listTicket(false);
+
+      try {
+        await makeOffer(listing, buyer);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "OffersNotAllowed");
+      }
+
+      assert.isNull(await provider.connection.getAccountInfo(offerPda(listing, buyer)));
+    });
+
+    it("rejects a zero-amount offer", async () => {
+      const { listing } = await listTicket(true);
+
+      try {
+        await makeOffer(listing, buyer, new BN(0));
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidOfferAmount");
+      }
+    });
+
+    it("sells the ticket to the buyer when the seller accepts", async () => {
+      const { mint, listing, escrowTokenAccount } = await listTicket(true);
+      await makeOffer(listing, buyer);
+
+      const sellerBefore = await provider.connection.getBalance(seller.publicKey);
+      const creatorBefore = await provider.connection.getBalance(creator.publicKey);
+      const buyerTokenAccount = await getAssociatedTokenAddress(mint, buyer.publicKey);
+
+      await marketplace.methods
+        .acceptOffer()
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerStats: PublicKey.findProgramAddressSync(
+            [Buffer.from("seller_stats"), seller.publicKey.toBuffer()],
+            marketplace.programId
+          )[0],
+          offer: offerPda(listing, buyer),
+          buyer: buyer.publicKey,
+          escrowTokenAccount,
+          buyerTokenAccount,
+          mint,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      const buyerToken = await getAccount(provider.connection, buyerTokenAccount);
+      assert.equal(Number(buyerToken.amount), 1);
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.isFalse(listingAccount.isActive);
+      assert.isNull(await provider.connection.getAccountInfo(offerPda(listing, buyer)));
+
+      const amount = OFFER_AMOUNT.toNumber();
+      const royalty = Math.floor((amount * ROYALTY_BPS) / 10000);
+      const creatorAfter = await provider.connection.getBalance(creator.publicKey);
+      assert.equal(creatorAfter - creatorBefore, royalty);
+
+      // Seller also paid rent for the buyer's token account, so only bound the proceeds
+      const sellerAfter = await provider.connection.getBalance(seller.publicKey);
+      assert.isAbove(sellerAfter, sellerBefore);
+      assert.isAtMost(sellerAfter - sellerBefore, amount - royalty);
+    });
+
+    it("refunds the buyer when the seller rejects", async () => {
+      const { listing } = await listTicket(true);
+      await makeOffer(listing, bidder1);
+
+      const offerInfo = await provider.connection.getAccountInfo(offerPda(listing, bidder1));
+      const bidderBefore = await provider.connection.getBalance(bidder1.publicKey);
+
+      await rejectOffer(listing, bidder1, seller);
+
+      // Escrowed amount and rent both return to the buyer
+      const bidderAfter = await provider.connection.getBalance(bidder1.publicKey);
+      assert.equal(bidderAfter - bidderBefore, offerInfo.lamports);
+      assert.isNull(await provider.connection.getAccountInfo(offerPda(listing, bidder1)));
+
+      // The listing is unaffected
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.isTrue(listingAccount.isActive);
+    });
+
+    it("does not let a third party reject an offer", async () => {
+      const { listing } = await listTicket(true);
+      await makeOffer(listing, bidder1);
+
+      try {
+        await rejectOffer(listing, bidder1, bidder2);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidOwner");
+      }
+    });
+  });
+
+  describe("Escrow Fee Collection", () => {
+    const ESCROW_AMOUNT = 1_000_000;
+    const ESCROW_FEE_BPS = 250;
+    const EXPECTED_FEE = (ESCROW_AMOUNT * ESCROW_FEE_BPS) / 10000;
+
+    let marketplaceState: PublicKey;
+    let feeVault: PublicKey;
+    let paymentMint: PublicKey;
+    let vaultTokenAccount: PublicKey;
+    let treasuryTokenAccount: PublicKey;
+    let buyerPaymentToken: PublicKey;
+    let sellerPaymentToken: PublicKey;
+
+    before(async () => {
+      [marketplaceState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("marketplace")],
+        marketplace.programId
+      );
+      [feeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_vault"), marketplaceState.toBuffer()],
+        marketplace.programId
+      );
+
+      paymentMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      buyerPaymentToken = await createAssociatedTokenAccount(provider.connection, buyer, paymentMint, buyer.publicKey);
+      sellerPaymentToken = await createAssociatedTokenAccount(provider.connection, seller, paymentMint, seller.publicKey);
+      vaultTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, paymentMint, feeVault, true)
+      ).address;
+
+      const state = await marketplace.account.marketplace.fetch(marketplaceState);
+      treasuryTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, paymentMint, state.treasury, true)
+      ).address;
+
+      await mintTo(provider.connection, platform, paymentMint, buyerPaymentToken, platform, ESCROW_AMOUNT * 10);
+    });
+
+    it("routes the escrow platform fee into the fee vault", async () => {
+      const transactionId = "escrow-fee-vault";
+      const [escrowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), Buffer.from(transactionId)],
+        marketplace.programId
+      );
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, paymentMint, escrowPda, true)
+      ).address;
+
+      await marketplace.methods
+        .createEscrow({
+          transactionId,
+          amount: new BN(ESCROW_AMOUNT),
+          expiryTime: new BN(Math.floor(Date.now() / 1000) + 3600),
+          terms: "Ticket delivery before event",
+          disputePeriod: new BN(3600),
+          platformFeeRate: ESCROW_FEE_BPS,
+          arbitrationWindow: null,
+          inspectionPeriod: new BN(0),
+          minTimeBeforeDispute: new BN(0),
+          defaultResolution: { favorBuyer: {} },
+          releaseCondition: null,
+          confirmers: [],
+        })
+        .accounts({
+          creator: buyer.publicKey,
+          escrow: escrowPda,
+          seller: seller.publicKey,
+          arbitrator: platform.publicKey,
+          creatorTokenAccount: buyerPaymentToken,
+          escrowTokenAccount: escrowToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const vaultBefore = await marketplace.account.feeVault.fetch(feeVault);
+      const vaultBalanceBefore = (await getAccount(provider.connection, vaultTokenAccount)).amount;
+
+      await marketplace.methods
+        .releaseEscrow({ releaseType: { full: {} }, partialAmount: null })
+        .accounts({
+          authority: buyer.publicKey,
+          escrow: escrowPda,
+          escrowTokenAccount: escrowToken,
+          sellerTokenAccount: sellerPaymentToken,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const vaultAfter = await marketplace.account.feeVault.fetch(feeVault);
+      const vaultBalanceAfter = (await getAccount(provider.connection, vaultTokenAccount)).amount;
+      assert.equal(Number(vaultBalanceAfter - vaultBalanceBefore), EXPECTED_FEE);
+      assert.equal(
+        vaultAfter.accumulatedFees.sub(vaultBefore.accumulatedFees).toNumber(),
+        EXPECTED_FEE
+      );
+
+      const sellerAccount = await getAccount(provider.connection, sellerPaymentToken);
+      assert.equal(Number(sellerAccount.amount), ESCROW_AMOUNT - EXPECTED_FEE);
+    });
+
+    it("lets the admin withdraw escrow fees from the vault", async () => {
+      const vaultBefore = await marketplace.account.feeVault.fetch(feeVault);
+      const treasuryBefore = (await getAccount(provider.connection, treasuryTokenAccount)).amount;
+
+      await marketplace.methods
+        .withdrawFees(new BN(EXPECTED_FEE))
+        .accounts({
+          admin: platform.publicKey,
+          marketplace: marketplaceState,
+          marketplaceConfig,
+          feeVault,
+          vaultTokenAccount,
+          feeMint: paymentMint,
+          treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([platform])
+        .rpc();
+
+      const vaultAfter = await marketplace.account.feeVault.fetch(feeVault);
+      const treasuryAfter = (await getAccount(provider.connection, treasuryTokenAccount)).amount;
+      assert.equal(Number(treasuryAfter - treasuryBefore), EXPECTED_FEE);
+      assert.equal(
+        vaultBefore.accumulatedFees.sub(vaultAfter.accumulatedFees).toNumber(),
+        EXPECTED_FEE
+      );
+    });
+  });
+
+  describe("Unsettled Auction Refunds", () => {
+    const BID_AMOUNT_LAMPORTS = new BN(LAMPORTS_PER_SOL);
+    const AUCTION_DURATION = 3; // seconds
+    const GRACE_PERIOD = 3; // seconds
+
+    const setGracePeriod = (seconds: number) =>
+      marketplace.methods
+        .updateSettlementGracePeriod(new BN(seconds))
+        .accounts({ marketplaceConfig, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+
+    // Lists a fresh NFT as a short auction and places a single winning bid
+    const auctionWithWinningBid = async () => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerTokenAccount, seller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          BID_AMOUNT_LAMPORTS,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      const [winningBid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder1.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), winningBid.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .placeBid(BID_AMOUNT_LAMPORTS)
+        .accounts({
+          bidder: bidder1.publicKey,
+          marketplaceConfig,
+          listing,
+          bid: winningBid,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder1])
+        .rpc();
+
+      return { mint, listing, escrowTokenAccount, sellerTokenAccount, winningBid, bidEscrow };
+    };
+
+    const forceRefund = (auction: Awaited<ReturnType<typeof auctionWithWinningBid>>, caller: Keypair) =>
+      marketplace.methods
+        .forceRefundWinner()
+        .accounts({
+          caller: caller.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          sellerTokenAccount: auction.sellerTokenAccount,
+          winningBid: auction.winningBid,
+          bidEscrow: auction.bidEscrow,
+          mint: auction.mint,
+          winner: bidder1.publicKey,
+          seller: seller.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([caller])
+        .rpc();
+
+    before(async () => {
+      await setGracePeriod(GRACE_PERIOD);
+    });
+
+    after(async () => {
+      await setGracePeriod(7 * 24 * 60 * 60);
+    });
+
+    it("rejects voiding before the grace window has elapsed", async () => {
+      const auction = await auctionWithWinningBid();
+
+      // Auction has ended, but the grace window is still running
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      try {
+        await forceRefund(auction, buyer);
+        assert.fail("Void should wait for the grace window");
+      } catch (err) {
+        assert.include(err.toString(), "SettlementGracePeriodActive");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.isTrue(listingAccount.isActive);
+    });
+
+    it("voids the sale after the grace window, refunding the winner and returning the NFT", async () => {
+      const auction = await auctionWithWinningBid();
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + GRACE_PERIOD + 1) * 1000));
+
+      const winnerBefore = await provider.connection.getBalance(bidder1.publicKey);
+      const escrowBefore = await provider.connection.getBalance(auction.bidEscrow);
+
+      const listener = marketplace.addEventListener("AuctionVoided", (event) => {
+        assert.equal(event.listing.toBase58(), auction.listing.toBase58());
+        assert.equal(event.winner.toBase58(), bidder1.publicKey.toBase58());
+        assert.equal(event.refundedAmount.toString(), BID_AMOUNT_LAMPORTS.toString());
+      });
+
+      // Anyone may void a stuck auction once the grace window is over
+      await forceRefund(auction, buyer);
+      await marketplace.removeEventListener(listener);
+
+      // Winning bid refunded out of the bid escrow
+      const winnerAfter = await provider.connection.getBalance(bidder1.publicKey);
+      const escrowAfter = await provider.connection.getBalance(auction.bidEscrow);
+      assert.equal(winnerAfter - winnerBefore, BID_AMOUNT_LAMPORTS.toNumber());
+      assert.equal(escrowBefore - escrowAfter, BID_AMOUNT_LAMPORTS.toNumber());
+
+      // NFT returned to the seller
+      const sellerToken = await getAccount(provider.connection, auction.sellerTokenAccount);
+      assert.equal(sellerToken.amount.toString(), "1");
+      const escrowToken = await getAccount(provider.connection, auction.escrowTokenAccount);
+      assert.equal(escrowToken.amount.toString(), "0");
+
+      // Sale voided
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.isFalse(listingAccount.isActive);
+      const bidAccount = await marketplace.account.bid.fetch(auction.winningBid);
+      assert.isFalse(bidAccount.isActive);
+    });
+
+    it("refuses to void the sale through a bid that was outbid", async () => {
+      const auction = await auctionWithWinningBid();
+
+      const [leadingBid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.listing.toBuffer(), bidder2.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [leadingBidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), leadingBid.toBuffer()],
+        marketplace.programId
+      );
+      await marketplace.methods
+        .placeBid(BID_AMOUNT_LAMPORTS.muln(2))
+        .accounts({
+          bidder: bidder2.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          bid: leadingBid,
+          bidEscrow: leadingBidEscrow,
+          mint: auction.mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder2])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + GRACE_PERIOD + 1) * 1000));
+
+      // bidder1's bid is still active but no longer the leader
+      try {
+        await forceRefund(auction, buyer);
+        assert.fail("Should not void through an outbid bid");
+      } catch (err) {
+        assert.include(err.toString(), "NotHighestBidder");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.isTrue(listingAccount.isActive);
+    });
+  });
+
+  describe("Seller Fee Exemptions", () => {
+    const SALE_PRICE = new BN(LAMPORTS_PER_SOL);
+    const exemptSeller = Keypair.generate();
+
+    const feeExemptionFor = (sellerKey: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_exemption"), sellerKey.toBuffer()],
+        marketplace.programId
+      )[0];
+
+    // Lists a fresh NFT from `listingSeller` and buys it, returning the fee and proceeds paid
+    const sellFrom = async (listingSeller: Keypair) => {
+      const mint = await createMint(provider.connection, listingSeller, listingSeller.publicKey, null, 0);
+      const mintSellerToken = await createAssociatedTokenAccount(provider.connection, listingSeller, mint, listingSeller.publicKey);
+      const mintBuyerToken = await createAssociatedTokenAccount(provider.connection, buyer, mint, buyer.publicKey);
+      await mintTo(provider.connection, listingSeller, mint, mintSellerToken, listingSeller, 1);
+
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), mint.toBuffer()],
+        nftProgram.programId
+      );
+      await nftProgram.methods
+        .createMetadata("Exempt NFT", "XNFT", "https://exempt.uri", ROYALTY_BPS, [creator.publicKey])
+        .accounts({
+          metadata,
+          mint,
+          mintAuthority: listingSeller.publicKey,
+          payer: listingSeller.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([listingSeller])
+        .rpc();
+
+      const [mintListing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const [mintEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), mintListing.toBuffer()],
+        marketplace.programId
+      );
+      const mintEscrowToken = await getAssociatedTokenAddress(mint, mintEscrow, true);
+
+      await marketplace.methods
+        .createListing(SALE_PRICE, null, null)
+        .accounts({
+          seller: listingSeller.publicKey,
+          nftMint: mint,
+          nftMetadata: metadata,
+          listing: mintListing,
+          escrow: mintEscrow,
+          sellerTokenAccount: mintSellerToken,
+          escrowTokenAccount: mintEscrowToken,
+          marketplaceConfig,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          nftProgram: nftProgram.programId,
+        })
+        .signers([listingSeller])
+        .rpc();
+
+      const feeCollectorBefore = await provider.connection.getBalance(feeCollector.publicKey);
+      const creatorBefore = await provider.connection.getBalance(creator.publicKey);
+      const sellerBefore = await provider.connection.getBalance(listingSeller.publicKey);
+
+      await marketplace.methods
+        .buyNow()
+        .accounts({
+          buyer: buyer.publicKey,
+          seller: listingSeller.publicKey,
+          feeExemption: feeExemptionFor(listingSeller.publicKey),
+          listing: mintListing,
+          escrow: mintEscrow,
+          nftMint: mint,
+          nftMetadata: metadata,
+          sellerTokenAccount: mintSellerToken,
+          buyerTokenAccount: mintBuyerToken,
+          escrowTokenAccount: mintEscrowToken,
+          marketplaceConfig,
+          feeCollector: feeCollector.publicKey,
+          creator: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          nftProgram: nftProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      return {
+        platformFee: (await provider.connection.getBalance(feeCollector.publicKey)) - feeCollectorBefore,
+        royalty: (await provider.connection.getBalance(creator.publicKey)) - creatorBefore,
+        sellerProceeds: (await provider.connection.getBalance(listingSeller.publicKey)) - sellerBefore,
+      };
+    };
+
+    const expectedRoyalty = (SALE_PRICE.toNumber() * ROYALTY_BPS) / 10000;
+    const expectedPlatformFee = (SALE_PRICE.toNumber() * PLATFORM_FEE_BPS) / 10000;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(exemptSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      await marketplace.methods
+        .addFeeExemption(exemptSeller.publicKey)
+        .accounts({
+          marketplaceConfig,
+          feeExemption: feeExemptionFor(exemptSeller.publicKey),
+          admin: platform.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([platform])
+        .rpc();
+    });
+
+    it("skips the platform fee for an exempt seller but still pays royalties", async () => {
+      const exemption = await marketplace.account.feeExemption.fetch(feeExemptionFor(exemptSeller.publicKey));
+      assert.equal(exemption.seller.toBase58(), exemptSeller.publicKey.toBase58());
+
+      const { platformFee, royalty, sellerProceeds } = await sellFrom(exemptSeller);
+
+      assert.equal(platformFee, 0);
+      assert.equal(royalty, expectedRoyalty);
+      assert.equal(sellerProceeds, SALE_PRICE.toNumber() - expectedRoyalty);
+    });
+
+    it("charges the platform fee for a non-exempt seller", async () => {
+      const { platformFee, royalty, sellerProceeds } = await sellFrom(seller);
+
+      assert.equal(platformFee, expectedPlatformFee);
+      assert.equal(royalty, expectedRoyalty);
+      assert.equal(sellerProceeds, SALE_PRICE.toNumber() - expectedPlatformFee - expectedRoyalty);
+    });
+
+    it("rejects fee exemption changes from non-admin", async () => {
+      try {
+        await marketplace.methods
+          .addFeeExemption(seller.publicKey)
+          .accounts({
+            marketplaceConfig,
+            feeExemption: feeExemptionFor(seller.publicKey),
+            admin: seller.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([seller])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "ConstraintHasOne");
+      }
+    });
+
+    it("charges the platform fee again once the exemption is removed", async () => {
+      await marketplace.methods
+        .removeFeeExemption()
+        .accounts({
+          marketplaceConfig,
+          feeExemption: feeExemptionFor(exemptSeller.publicKey),
+          admin: platform.publicKey,
+        })
+        .signers([platform])
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(feeExemptionFor(exemptSeller.publicKey)));
+
+      const { platformFee, sellerProceeds } = await sellFrom(exemptSeller);
+
+      assert.equal(platformFee, expectedPlatformFee);
+      assert.equal(sellerProceeds, SALE_PRICE.toNumber() - expectedPlatformFee - expectedRoyalty);
+    });
+  });
+
+  describe("Settlement Rent Reimbursement", () => {
+    const BID_AMOUNT_LAMPORTS = new BN(LAMPORTS_PER_SOL);
+    const AUCTION_DURATION = 3; // seconds
+    const settler = Keypair.generate();
+
+    // Lists a fresh NFT as a short auction, places a winning bid and waits for it to end
+    const endedAuction = async () => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerTokenAccount, seller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          BID_AMOUNT_LAMPORTS,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      const [winningBid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder1.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), winningBid.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .placeBid(BID_AMOUNT_LAMPORTS)
+        .accounts({
+          bidder: bidder1.publicKey,
+          marketplaceConfig,
+          listing,
+          bid: winningBid,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder1])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      const winnerTokenAccount = await getAssociatedTokenAddress(mint, bidder1.publicKey);
+      return { mint, listing, escrowTokenAccount, winnerTokenAccount, winningBid, bidEscrow };
+    };
+
+    // Settles the auction as the third-party settler, returning any reimbursement events
+    const settle = async (auction: Awaited<ReturnType<typeof endedAuction>>) => {
+      const reimbursements: number[] = [];
+      const listener = marketplace.addEventListener("SettlementRentReimbursed", (event) => {
+        assert.equal(event.caller.toBase58(), settler.publicKey.toBase58());
+        reimbursements.push(event.amount.toNumber());
+      });
+
+      await marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: settler.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          winnerTokenAccount: auction.winnerTokenAccount,
+          sellerTokenAccount: await getAssociatedTokenAddress(auction.mint, seller.publicKey),
+          winningBid: auction.winningBid,
+          bidEscrow: auction.bidEscrow,
+          mint: auction.mint,
+          winner: bidder1.publicKey,
+          seller: seller.publicKey,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([settler])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+      return reimbursements;
+    };
+
+    const expectedPlatformFee = (BID_AMOUNT_LAMPORTS.toNumber() * PLATFORM_FEE_BPS) / 10000;
+    const TX_FEE = 5000;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(settler.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+    });
+
+    it("reimburses the settler's rent when it creates the winner's token account", async () => {
+      const auction = await endedAuction();
+      assert.isNull(await provider.connection.getAccountInfo(auction.winnerTokenAccount));
+
+      const tokenAccountRent = await provider.connection.getMinimumBalanceForRentExemption(165);
+      const settlerBefore = await provider.connection.getBalance(settler.publicKey);
+      const sellerBefore = await provider.connection.getBalance(seller.publicKey);
+
+      const reimbursements = await settle(auction);
+
+      assert.deepEqual(reimbursements, [tokenAccountRent]);
+
+      // The settler is only out the transaction fee
+      const settlerAfter = await provider.connection.getBalance(settler.publicKey);
+      assert.equal(settlerBefore - settlerAfter, TX_FEE);
+
+      // The rent came out of the seller's proceeds
+      const sellerAfter = await provider.connection.getBalance(seller.publicKey);
+      assert.equal(
+        sellerAfter - sellerBefore,
+        BID_AMOUNT_LAMPORTS.toNumber() - expectedPlatformFee - tokenAccountRent
+      );
+
+      const winnerToken = await getAccount(provider.connection, auction.winnerTokenAccount);
+      assert.equal(winnerToken.amount.toString(), "1");
+    });
+
+    it("does not reimburse when the winner's token account already exists", async () => {
+      const auction = await endedAuction();
+      await createAssociatedTokenAccount(provider.connection, bidder1, auction.mint, bidder1.publicKey);
+
+      const settlerBefore = await provider.connection.getBalance(settler.publicKey);
+      const sellerBefore = await provider.connection.getBalance(seller.publicKey);
+
+      const reimbursements = await settle(auction);
+
+      assert.deepEqual(reimbursements, []);
+
+      const settlerAfter = await provider.connection.getBalance(settler.publicKey);
+      assert.equal(settlerBefore - settlerAfter, TX_FEE);
+
+      const sellerAfter = await provider.connection.getBalance(seller.publicKey);
+      assert.equal(sellerAfter - sellerBefore, BID_AMOUNT_LAMPORTS.toNumber() - expectedPlatformFee);
+
+      const winnerToken = await getAccount(provider.connection, auction.winnerTokenAccount);
+      assert.equal(winnerToken.amount.toString(), "1");
+    });
+
+    it("rejects a winner token account that is not the winner's ATA", async () => {
+      const auction = await endedAuction();
+
+      try {
+        await settle({
+          ...auction,
+          winnerTokenAccount: await getAssociatedTokenAddress(auction.mint, settler.publicKey),
+        });
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidWinnerTokenAccount");
+      }
+    });
+  });
+
+  describe("Royalty Creator Verification", () => {
+    const ROYALTY_PRICE = new BN(LAMPORTS_PER_SOL);
+
+    // Mints a fresh NFT to the seller with Metaplex metadata listing `creators`.
+    // The creator keypair is the mint and update authority, so it can sign as verified.
+    const mintWithCreators = async (creators: { address: PublicKey; verified: boolean; share: number }[] | null) => {
+      const mint = await createMint(provider.connection, creator, creator.publicKey, null, 0);
+      const mintSellerToken = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, creator, mint, mintSellerToken, creator, 1);
+
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        METADATA_PROGRAM_ID
+      );
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          createCreateMetadataAccountV3Instruction(
+            {
+              metadata,
+              mint,
+              mintAuthority: creator.publicKey,
+              payer: creator.publicKey,
+              updateAuthority: creator.publicKey,
+            },
+            {
+              createMetadataAccountArgsV3: {
+                data: {
+                  name: "Royalty NFT",
+                  symbol: "RNFT",
+                  uri: "https://royalty.uri",
+                  sellerFeeBasisPoints: ROYALTY_BPS,
+                  creators,
+                  collection: null,
+                  uses: null,
+                },
+                isMutable: true,
+                collectionDetails: null,
+              },
+            }
+          )
+        ),
+        [creator]
+      );
+
+      return { mint, metadata, sellerTokenAccount: mintSellerToken };
+    };
+
+    const listWithRoyaltyCreator = async (
+      nft: Awaited<ReturnType<typeof mintWithCreators>>,
+      royaltyCreator: PublicKey
+    ) => {
+      const [mintListing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), nft.mint.toBuffer()],
+        marketplace.programId
+      );
+      const mintEscrowToken = await getAssociatedTokenAddress(nft.mint, mintListing, true);
+
+      await marketplace.methods
+        .createListing(ROYALTY_PRICE, { fixedPrice: {} }, null, {
+          creator: royaltyCreator,
+          percentageBps: ROYALTY_BPS,
+          royaltyPayoutMint: null,
+          minRoyalty: new BN(0),
+        })
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing: mintListing,
+          sellerTokenAccount: nft.sellerTokenAccount,
+          escrowTokenAccount: mintEscrowToken,
+          mint: nft.mint,
+          nftMetadata: nft.metadata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      return mintListing;
+    };
+
+    it("accepts a royalty creator that is a verified creator of the NFT", async () => {
+      const nft = await mintWithCreators([{ address: creator.publicKey, verified: true, share: 100 }]);
+
+      const mintListing = await listWithRoyaltyCreator(nft, creator.publicKey);
+
+      const listingAccount = await marketplace.account.listing.fetch(mintListing);
+      assert.equal(listingAccount.royaltyConfig.creator.toBase58(), creator.publicKey.toBase58());
+      assert.equal(listingAccount.royaltyConfig.percentageBps, ROYALTY_BPS);
+    });
+
+    it("rejects a seller declaring themselves as the royalty creator", async () => {
+      const nft = await mintWithCreators([{ address: creator.publicKey, verified: true, share: 100 }]);
+
+      try {
+        await listWithRoyaltyCreator(nft, seller.publicKey);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "UnverifiedRoyaltyCreator");
+      }
+    });
+
+    it("rejects a royalty creator when the NFT has no on-chain creators", async () => {
+      const nft = await mintWithCreators(null);
+
+      try {
+        await listWithRoyaltyCreator(nft, creator.publicKey);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "UnverifiedRoyaltyCreator");
+      }
+    });
+  });
+
+  describe("Auction Bidder Diversity", () => {
+    const AUCTION_DURATION = 4; // seconds
+    const START_PRICE = new BN(LAMPORTS_PER_SOL / 10);
+    let mint: PublicKey;
+    let auctionListing: PublicKey;
+    let escrowTokenAccount: PublicKey;
+
+    const bidAccountsFor = (bidder: PublicKey) => {
+      const [bid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auctionListing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bid.toBuffer()],
+        marketplace.programId
+      );
+      return { bid, bidEscrow };
+    };
+
+    const placeBid = (bidder: Keypair, amount: BN) =>
+      marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing: auctionListing,
+          ...bidAccountsFor(bidder.publicKey),
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+
+    const uniqueBidders = async () =>
+      (await marketplace.account.listing.fetch(auctionListing)).uniqueBidders;
+
+    before(async () => {
+      mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerTokenAccount, seller, 1);
+
+      [auctionListing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      escrowTokenAccount = await getAssociatedTokenAddress(mint, auctionListing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          START_PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing: auctionListing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+    });
+
+    it("counts a bidder only on their first bid", async () => {
+      assert.equal(await uniqueBidders(), 0);
+
+      await placeBid(bidder1, START_PRICE);
+      assert.equal(await uniqueBidders(), 1);
+
+      // Raising an existing bid escrows the difference but adds no new bidder
+      const { bid, bidEscrow } = bidAccountsFor(bidder1.publicKey);
+      const escrowBefore = await provider.connection.getBalance(bidEscrow);
+      await placeBid(bidder1, START_PRICE.muln(2));
+      assert.equal(await uniqueBidders(), 1);
+      assert.equal((await provider.connection.getBalance(bidEscrow)) - escrowBefore, START_PRICE.toNumber());
+      assert.equal((await marketplace.account.bid.fetch(bid)).amount.toString(), START_PRICE.muln(2).toString());
+
+      await placeBid(bidder2, START_PRICE.muln(3));
+      assert.equal(await uniqueBidders(), 2);
+    });
+
+    it("rejects a raise that does not exceed the bidder's current bid", async () => {
+      try {
+        await placeBid(bidder1, START_PRICE.muln(2));
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "BidTooLow");
+      }
+      assert.equal(await uniqueBidders(), 2);
+    });
+
+    it("reports the unique bidder count when the auction ends", async () => {
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      let reported: number | null = null;
+      const listener = marketplace.addEventListener("AuctionEnded", (event) => {
+        if (event.listing.equals(auctionListing)) {
+          reported = event.uniqueBidders;
+        }
+      });
+
+      const { bid, bidEscrow } = bidAccountsFor(bidder2.publicKey);
+      await marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: buyer.publicKey,
+          marketplaceConfig,
+          listing: auctionListing,
+          escrowTokenAccount,
+          winnerTokenAccount: await getAssociatedTokenAddress(mint, bidder2.publicKey),
+          sellerTokenAccount: await getAssociatedTokenAddress(mint, seller.publicKey),
+          winningBid: bid,
+          bidEscrow,
+          mint,
+          winner: bidder2.publicKey,
+          seller: seller.publicKey,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      assert.equal(reported, 2);
+    });
+  });
+
+  describe("Batch Listing Cancellation", () => {
+    const LIST_PRICE = new BN(LAMPORTS_PER_SOL);
+
+    const listFixedPrice = async (owner: Keypair) => {
+      const mint = await createMint(provider.connection, owner, owner.publicKey, null, 0);
+      const ownerTokenAccount = await createAssociatedTokenAccount(provider.connection, owner, mint, owner.publicKey);
+      await mintTo(provider.connection, owner, mint, ownerTokenAccount, owner, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+
+      await marketplace.methods
+        .createListing(LIST_PRICE, { fixedPrice: {} }, null, null)
+        .accounts({
+          seller: owner.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount: ownerTokenAccount,
+          escrowTokenAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+      return { mint, listing, escrowTokenAccount, ownerTokenAccount };
+    };
+
+    type BatchEntry = Awaited<ReturnType<typeof listFixedPrice>>;
+
+    // Each listing is passed as listing, mint, escrow and the signer's token account
+    const batchAccountsFor = async (entries: BatchEntry[], signer: PublicKey) =>
+      (
+        await Promise.all(
+          entries.map(async ({ listing, mint, escrowTokenAccount }) => [
+            { pubkey: listing, isWritable: true, isSigner: false },
+            { pubkey: mint, isWritable: false, isSigner: false },
+            { pubkey: escrowTokenAccount, isWritable: true, isSigner: false },
+            { pubkey: await getAssociatedTokenAddress(mint, signer), isWritable: true, isSigner: false },
+          ])
+        )
+      ).flat();
+
+    const batchCancel = async (entries: BatchEntry[], signer: Keypair) =>
+      marketplace.methods
+        .batchCancelListings()
+        .accounts({
+          seller: signer.publicKey,
+          marketplaceConfig,
+          sellerStats: PublicKey.findProgramAddressSync(
+            [Buffer.from("seller_stats"), signer.publicKey.toBuffer()],
+            marketplace.programId
+          )[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(await batchAccountsFor(entries, signer.publicKey))
+        .signers([signer])
+        .rpc();
+
+    it("cancels three listings at once and returns the NFTs and escrow rent", async () => {
+      const entries = [
+        await listFixedPrice(seller),
+        await listFixedPrice(seller),
+        await listFixedPrice(seller),
+      ];
+
+      let escrowRent = 0;
+      for (const { escrowTokenAccount } of entries) {
+        escrowRent += await provider.connection.getBalance(escrowTokenAccount);
+      }
+      const sellerBefore = await provider.connection.getBalance(seller.publicKey);
+
+      const cancelledListings: string[] = [];
+      let summary: { cancelled: number; skipped: number } | null = null;
+      const cancelListener = marketplace.addEventListener("ListingCancelled", (event) => {
+        cancelledListings.push(event.listing.toString());
+      });
+      const summaryListener = marketplace.addEventListener("ListingsBatchCancelled", (event) => {
+        if (event.seller.equals(seller.publicKey)) {
+          summary = { cancelled: event.cancelled, skipped: event.skipped };
+        }
+      });
+
+      await batchCancel(entries, seller);
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(cancelListener);
+      await marketplace.removeEventListener(summaryListener);
+
+      for (const { listing, escrowTokenAccount, ownerTokenAccount } of entries) {
+        const listingAccount = await marketplace.account.listing.fetch(listing);
+        assert.isFalse(listingAccount.isActive);
+
+        const returned = await getAccount(provider.connection, ownerTokenAccount);
+        assert.equal(returned.amount.toString(), "1");
+
+        // The emptied escrow is closed
+        assert.isNull(await provider.connection.getAccountInfo(escrowTokenAccount));
+        assert.include(cancelledListings, listing.toString());
+      }
+
+      // The seller paid the transaction fee but got every escrow's rent back
+      const sellerAfter = await provider.connection.getBalance(seller.publicKey);
+      assert.isAbove(sellerAfter - sellerBefore, escrowRent - 10000);
+      assert.deepEqual(summary, { cancelled: 3, skipped: 0 });
+    });
+
+    it("skips listings owned by another seller", async () => {
+      const own = await listFixedPrice(seller);
+      const foreign = await listFixedPrice(buyer);
+
+      let summary: { cancelled: number; skipped: number } | null = null;
+      const listener = marketplace.addEventListener("ListingsBatchCancelled", (event) => {
+        if (event.seller.equals(seller.publicKey)) {
+          summary = { cancelled: event.cancelled, skipped: event.skipped };
+        }
+      });
+
+      await batchCancel([own, foreign], seller);
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      assert.isFalse((await marketplace.account.listing.fetch(own.listing)).isActive);
+
+      // The other seller's listing and escrowed NFT are untouched
+      const foreignListing = await marketplace.account.listing.fetch(foreign.listing);
+      assert.isTrue(foreignListing.isActive);
+      const foreignEscrow = await getAccount(provider.connection, foreign.escrowTokenAccount);
+      assert.equal(foreignEscrow.amount.toString(), "1");
+
+      assert.deepEqual(summary, { cancelled: 1, skipped: 1 });
+    });
+
+    it("rejects a batch with an incomplete account group", async () => {
+      const entry = await listFixedPrice(seller);
+      const accounts = (await batchAccountsFor([entry], seller.publicKey)).slice(0, 3);
+
+      try {
+        await marketplace.methods
+          .batchCancelListings()
+          .accounts({
+            seller: seller.publicKey,
+            marketplaceConfig,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .remainingAccounts(accounts)
+          .signers([seller])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidBatchAccounts");
+      }
+    });
+  });
+
+  describe("Seller Auction Cap", () => {
+    const MAX_ACTIVE_AUCTIONS = 2;
+    const BID_AMOUNT = new BN(LAMPORTS_PER_SOL / 10);
+    const AUCTION_DURATION = 3; // seconds
+    const capSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), capSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    const setMaxActiveAuctions = (max: number) =>
+      marketplace.methods
+        .updateMaxActiveAuctions(max)
+        .accounts({ marketplaceConfig, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+
+    const activeAuctions = async () =>
+      (await marketplace.account.sellerStats.fetch(sellerStats)).activeAuctions;
+
+    const createAuction = async () => {
+      const mint = await createMint(provider.connection, capSeller, capSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, capSeller, mint, capSeller.publicKey);
+      await mintTo(provider.connection, capSeller, mint, sellerTokenAccount, capSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          BID_AMOUNT,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: capSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([capSeller])
+        .rpc();
+
+      return { mint, listing, escrowTokenAccount };
+    };
+
+    const bidAndSettle = async (auction: Awaited<ReturnType<typeof createAuction>>) => {
+      const [winningBid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.listing.toBuffer(), bidder1.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), winningBid.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .placeBid(BID_AMOUNT)
+        .accounts({
+          bidder: bidder1.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          bid: winningBid,
+          bidEscrow,
+          mint: auction.mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder1])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      await marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: bidder1.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          winnerTokenAccount: await getAssociatedTokenAddress(auction.mint, bidder1.publicKey),
+          sellerTokenAccount: await getAssociatedTokenAddress(auction.mint, capSeller.publicKey),
+          winningBid,
+          bidEscrow,
+          mint: auction.mint,
+          winner: bidder1.publicKey,
+          seller: capSeller.publicKey,
+          sellerStats,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder1])
+        .rpc();
+    };
+
+    let firstAuction: Awaited<ReturnType<typeof createAuction>>;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(capSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+      await setMaxActiveAuctions(MAX_ACTIVE_AUCTIONS);
+    });
+
+    after(async () => {
+      await setMaxActiveAuctions(0);
+    });
+
+    it("allows a seller to run auctions up to the cap", async () => {
+      const config = await marketplace.account.marketplaceConfig.fetch(marketplaceConfig);
+      assert.equal(config.maxActiveAuctionsPerSeller, MAX_ACTIVE_AUCTIONS);
+
+      firstAuction = await createAuction();
+      assert.equal(await activeAuctions(), 1);
+      await createAuction();
+      assert.equal(await activeAuctions(), 2);
+
+      const stats = await marketplace.account.sellerStats.fetch(sellerStats);
+      assert.equal(stats.seller.toBase58(), capSeller.publicKey.toBase58());
+    });
+
+    it("rejects an auction over the cap", async () => {
+      try {
+        await createAuction();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "TooManyActiveAuctions");
+      }
+      assert.equal(await activeAuctions(), MAX_ACTIVE_AUCTIONS);
+    });
+
+    it("frees a slot once an auction settles", async () => {
+      await bidAndSettle(firstAuction);
+      assert.equal(await activeAuctions(), MAX_ACTIVE_AUCTIONS - 1);
+
+      await createAuction();
+      assert.equal(await activeAuctions(), MAX_ACTIVE_AUCTIONS);
+    });
+  });
+
+  describe("Reserve-Aware Anti-Sniping", () => {
+    const PRICE = new BN(LAMPORTS_PER_SOL / 10);
+    const RESERVE = new BN(LAMPORTS_PER_SOL / 2);
+    const EXTENSION_PERIOD = 60; // seconds
+    const snipeSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), snipeSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    let mint: PublicKey;
+    let listing: PublicKey;
+
+    const endTime = async () =>
+      (await marketplace.account.listing.fetch(listing)).auctionConfig.endTime.toNumber();
+
+    const bid = async (bidder: Keypair, amount: BN) => {
+      const [bidPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bidPda.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing,
+          bid: bidPda,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+    };
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(snipeSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      mint = await createMint(provider.connection, snipeSeller, snipeSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, snipeSeller, mint, snipeSeller.publicKey);
+      await mintTo(provider.connection, snipeSeller, mint, sellerTokenAccount, snipeSeller, 1);
+
+      [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      // The whole auction sits inside the extension window, and only one extension is allowed
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + 600),
+            minBidIncrement: new BN(1),
+            reservePrice: RESERVE,
+            openingBid: null,
+            extensionWindow: new BN(3600),
+            extensionPeriod: new BN(EXTENSION_PERIOD),
+            maxExtensions: 1,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: snipeSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([snipeSeller])
+        .rpc();
+    });
+
+    it("does not extend the auction for a bid below the reserve", async () => {
+      const before = await endTime();
+
+      await bid(bidder1, PRICE);
+
+      assert.equal(await endTime(), before);
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.extensionsApplied, 0);
+    });
+
+    it("extends the auction for a bid that meets the reserve", async () => {
+      const before = await endTime();
+
+      await bid(bidder2, RESERVE);
+
+      assert.equal(await endTime(), before + EXTENSION_PERIOD);
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.extensionsApplied, 1);
+    });
+
+    it("stops extending once the extension cap is reached", async () => {
+      const before = await endTime();
+
+      await bid(bidder1, RESERVE.add(PRICE));
+
+      assert.equal(await endTime(), before);
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.extensionsApplied, 1);
+    });
+  });
+
+  describe("Opening Bids", () => {
+    const PRICE = new BN(LAMPORTS_PER_SOL / 10);
+    const OPENING_BID = new BN(LAMPORTS_PER_SOL / 2);
+    const MIN_INCREMENT = new BN(LAMPORTS_PER_SOL / 20);
+    const openingSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), openingSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    let mint: PublicKey;
+    let listing: PublicKey;
+
+    const bid = async (bidder: Keypair, amount: BN) => {
+      const [bidPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bidPda.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing,
+          bid: bidPda,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+    };
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(openingSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      mint = await createMint(provider.connection, openingSeller, openingSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, openingSeller, mint, openingSeller.publicKey);
+      await mintTo(provider.connection, openingSeller, mint, sellerTokenAccount, openingSeller, 1);
+
+      [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + 600),
+            minBidIncrement: MIN_INCREMENT,
+            reservePrice: null,
+            openingBid: OPENING_BID,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: openingSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([openingSeller])
+        .rpc();
+    });
+
+    it("rejects a first bid below the opening bid", async () => {
+      try {
+        await bid(bidder1, PRICE);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "BidTooLow");
+      }
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.highestBid.toNumber(), 0);
+    });
+
+    it("accepts a first bid at the opening bid", async () => {
+      await bid(bidder1, OPENING_BID);
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.highestBid.toString(), OPENING_BID.toString());
+    });
+
+    it("requires later bids to beat the highest bid by the minimum increment", async () => {
+      try {
+        await bid(bidder2, OPENING_BID.add(MIN_INCREMENT).subn(1));
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "BidTooLow");
+      }
+
+      await bid(bidder2, OPENING_BID.add(MIN_INCREMENT));
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.highestBid.toString(), OPENING_BID.add(MIN_INCREMENT).toString());
+    });
+  });
+
+  describe("Dispute Category Policies", () => {
+    const ESCROW_AMOUNT = 1_000_000;
+    const ARBITRATION_WINDOW = 2; // seconds
+
+    let paymentMint: PublicKey;
+    let buyerPaymentToken: PublicKey;
+    let sellerPaymentToken: PublicKey;
+    let arbitratorPaymentToken: PublicKey;
+    let marketplaceState: PublicKey;
+    let feeVault: PublicKey;
+    let vaultTokenAccount: PublicKey;
+
+    const openEscrowDispute = async (transactionId: string, category: any, defaultResolution: any) => {
+      const [escrowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), Buffer.from(transactionId)],
+        marketplace.programId
+      );
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, paymentMint, escrowPda, true)
+      ).address;
+
+      await marketplace.methods
+        .createEscrow({
+          transactionId,
+          amount: new BN(ESCROW_AMOUNT),
+          expiryTime: new BN(Math.floor(Date.now() / 1000) + 3600),
+          terms: "Ticket delivery before event",
+          disputePeriod: new BN(3600),
+          platformFeeRate: 0,
+          arbitrationWindow: new BN(ARBITRATION_WINDOW),
+          inspectionPeriod: new BN(0),
+          minTimeBeforeDispute: new BN(0),
+          defaultResolution,
+          releaseCondition: null,
+          confirmers: [],
+        })
+        .accounts({
+          creator: buyer.publicKey,
+          escrow: escrowPda,
+          seller: seller.publicKey,
+          arbitrator: platform.publicKey,
+          creatorTokenAccount: buyerPaymentToken,
+          escrowTokenAccount: escrowToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const [disputePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("dispute"), escrowPda.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .initiateDispute({
+          category,
+          description: "Dispute category policy test",
+          evidenceLinks: [],
+          requestedResolution: { refundBuyer: {} },
+        })
+        .accounts({
+          disputer: buyer.publicKey,
+          dispute: disputePda,
+          escrow: escrowPda,
+          marketplace: marketplaceState,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      return { escrow: escrowPda, dispute: disputePda, escrowToken };
+    };
+
+    const resolve = (
+      accounts: { escrow: PublicKey; dispute: PublicKey; escrowToken: PublicKey },
+      decision: any,
+      buyerAmount: number,
+      sellerAmount: number,
+      platformFee = 0
+    ) =>
+      marketplace.methods
+        .resolveDispute({
+          decision,
+          reasoning: "Arbitrator ruling",
+          buyerAmount: new BN(buyerAmount),
+          sellerAmount: new BN(sellerAmount),
+          platformFee: new BN(platformFee),
+        })
+        .accounts({
+          arbitrator: platform.publicKey,
+          marketplaceConfig,
+          dispute: accounts.dispute,
+          escrow: accounts.escrow,
+          escrowTokenAccount: accounts.escrowToken,
+          buyerTokenAccount: buyerPaymentToken,
+          sellerTokenAccount: sellerPaymentToken,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+          arbitratorTokenAccount: arbitratorPaymentToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([platform])
+        .rpc();
+
+    before(async () => {
+      [marketplaceState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("marketplace")],
+        marketplace.programId
+      );
+      [feeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_vault"), marketplaceState.toBuffer()],
+        marketplace.programId
+      );
+
+      paymentMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      buyerPaymentToken = await createAssociatedTokenAccount(provider.connection, buyer, paymentMint, buyer.publicKey);
+      sellerPaymentToken = await createAssociatedTokenAccount(provider.connection, seller, paymentMint, seller.publicKey);
+      vaultTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, paymentMint, feeVault, true)
+      ).address;
+      // The arbitrator is also the platform here, so its fees land in a separate account
+      arbitratorPaymentToken = await createAccount(provider.connection, platform, paymentMint, platform.publicKey, Keypair.generate());
+      await mintTo(provider.connection, platform, paymentMint, buyerPaymentToken, platform, ESCROW_AMOUNT * 10);
+    });
+
+    it("resolves a dispute with a decision its category allows", async () => {
+      const accounts = await openEscrowDispute("policy-allowed", { itemNotReceived: {} }, { favorBuyer: {} });
+      const buyerBefore = await getAccount(provider.connection, buyerPaymentToken);
+
+      await resolve(accounts, { split: {} }, ESCROW_AMOUNT / 2, ESCROW_AMOUNT / 2);
+
+      const buyerAfter = await getAccount(provider.connection, buyerPaymentToken);
+      assert.equal(Number(buyerAfter.amount - buyerBefore.amount), ESCROW_AMOUNT / 2);
+
+      const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+      assert.deepEqual(dispute.state, { resolved: {} });
+      assert.deepEqual(dispute.decision, { split: {} });
+    });
+
+    it("rejects a decision outside the category's policy", async () => {
+      const accounts = await openEscrowDispute("policy-rejected", { eventCancelled: {} }, { favorBuyer: {} });
+
+      try {
+        await resolve(accounts, { favorSeller: {} }, 0, ESCROW_AMOUNT);
+        assert.fail("Should not pay the seller for a cancelled event");
+      } catch (error) {
+        assert.include(error.toString(), "ResolutionNotAllowedForCategory");
+      }
+
+      const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+      assert.deepEqual(dispute.state, { open: {} });
+    });
+
+    it("rejects a payout that contradicts the decision", async () => {
+      const accounts = await openEscrowDispute("policy-payout", { itemNotReceived: {} }, { favorBuyer: {} });
+
+      // Labelled for the buyer, but paying the seller everything
+      try {
+        await resolve(accounts, { favorBuyer: {} }, 0, ESCROW_AMOUNT);
+        assert.fail("Should reject a buyer ruling that pays the seller");
+      } catch (error) {
+        assert.include(error.toString(), "ResolutionAmountsMismatchDecision");
+      }
+
+      // A split must pay both sides
+      try {
+        await resolve(accounts, { split: {} }, ESCROW_AMOUNT, 0);
+        assert.fail("Should reject a one-sided split");
+      } catch (error) {
+        assert.include(error.toString(), "ResolutionAmountsMismatchDecision");
+      }
+
+      const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+      assert.deepEqual(dispute.state, { open: {} });
+    });
+
+    it("applies the category default when auto-resolving a disallowed escrow default", async () => {
+      // The escrow would split, but cancelled events always refund the buyer
+      const accounts = await openEscrowDispute("policy-auto", { eventCancelled: {} }, { split: {} });
+      const buyerBefore = await getAccount(provider.connection, buyerPaymentToken);
+
+      await new Promise(resolve => setTimeout(resolve, (ARBITRATION_WINDOW + 2) * 1000));
+
+      await marketplace.methods
+        .autoResolveExpiredDispute()
+        .accounts({
+          caller: buyer.publicKey,
+          dispute: accounts.dispute,
+          escrow: accounts.escrow,
+          escrowTokenAccount: accounts.escrowToken,
+          buyerTokenAccount: buyerPaymentToken,
+          sellerTokenAccount: sellerPaymentToken,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const buyerAfter = await getAccount(provider.connection, buyerPaymentToken);
+      assert.equal(Number(buyerAfter.amount - buyerBefore.amount), ESCROW_AMOUNT);
+
+      const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+      assert.deepEqual(dispute.decision, { favorBuyer: {} });
+    });
+
+    it("pays an explicit platform fee when the split sums to the escrow", async () => {
+      const accounts = await openEscrowDispute("fee-explicit", { itemNotReceived: {} }, { favorBuyer: {} });
+      const buyerBefore = await getAccount(provider.connection, buyerPaymentToken);
+      const sellerBefore = await getAccount(provider.connection, sellerPaymentToken);
+      const vaultBefore = await getAccount(provider.connection, vaultTokenAccount);
+      const feeVaultBefore = await marketplace.account.feeVault.fetch(feeVault);
+
+      await resolve(accounts, { split: {} }, 600_000, 350_000, 50_000);
+
+      const buyerAfter = await getAccount(provider.connection, buyerPaymentToken);
+      const sellerAfter = await getAccount(provider.connection, sellerPaymentToken);
+      const vaultAfter = await getAccount(provider.connection, vaultTokenAccount);
+      const feeVaultAfter = await marketplace.account.feeVault.fetch(feeVault);
+      assert.equal(Number(buyerAfter.amount - buyerBefore.amount), 600_000);
+      assert.equal(Number(sellerAfter.amount - sellerBefore.amount), 350_000);
+      assert.equal(Number(vaultAfter.amount - vaultBefore.amount), 50_000);
+      assert.equal(feeVaultAfter.accumulatedFees.sub(feeVaultBefore.accumulatedFees).toNumber(), 50_000);
+
+      const escrowBalance = await getAccount(provider.connection, accounts.escrowToken);
+      assert.equal(Number(escrowBalance.amount), 0);
+    });
+
+    it("rejects a split that does not sum to the escrow amount", async () => {
+      const accounts = await openEscrowDispute("fee-mismatch", { itemNotReceived: {} }, { favorBuyer: {} });
+
+      // Leftover is no longer swept to the platform implicitly
+      try {
+        await resolve(accounts, { split: {} }, 600_000, 350_000);
+        assert.fail("Should reject an under-allocated split");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidResolutionAmounts");
+      }
+
+      try {
+        await resolve(accounts, { split: {} }, 600_000, 350_000, 100_000);
+        assert.fail("Should reject an over-allocated split");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidResolutionAmounts");
+      }
+
+      const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+      assert.deepEqual(dispute.state, { open: {} });
+    });
+
+    it("resolves with no platform fee", async () => {
+      const accounts = await openEscrowDispute("fee-zero", { itemNotReceived: {} }, { favorBuyer: {} });
+      const vaultBefore = await getAccount(provider.connection, vaultTokenAccount);
+      const buyerBefore = await getAccount(provider.connection, buyerPaymentToken);
+
+      await resolve(accounts, { favorBuyer: {} }, ESCROW_AMOUNT, 0, 0);
+
+      const buyerAfter = await getAccount(provider.connection, buyerPaymentToken);
+      const vaultAfter = await getAccount(provider.connection, vaultTokenAccount);
+      assert.equal(Number(buyerAfter.amount - buyerBefore.amount), ESCROW_AMOUNT);
+      assert.equal(Number(vaultAfter.amount - vaultBefore.amount), 0);
+    });
+
+    describe("with an arbitrator fee", () => {
+      const ARBITRATOR_FEE_BPS = 200; // 2%
+      const ARBITRATOR_FEE = (ESCROW_AMOUNT * ARBITRATOR_FEE_BPS) / 10_000;
+
+      const setArbitratorFee = (bps: number) =>
+        marketplace.methods
+          .updateArbitratorFee(bps)
+          .accounts({ marketplaceConfig, admin: platform.publicKey })
+          .signers([platform])
+          .rpc();
+
+      before(async () => {
+        await setArbitratorFee(ARBITRATOR_FEE_BPS);
+      });
+
+      after(async () => {
+        await setArbitratorFee(0);
+      });
+
+      it("pays the arbitrator fee out of the escrow", async () => {
+        const accounts = await openEscrowDispute("arbitrator-fee", { itemNotReceived: {} }, { favorBuyer: {} });
+        const buyerBefore = await getAccount(provider.connection, buyerPaymentToken);
+        const sellerBefore = await getAccount(provider.connection, sellerPaymentToken);
+        const vaultBefore = await getAccount(provider.connection, vaultTokenAccount);
+        const arbitratorBefore = await getAccount(provider.connection, arbitratorPaymentToken);
+
+        await resolve(accounts, { split: {} }, 600_000, 350_000 - ARBITRATOR_FEE, 50_000);
+
+        const buyerAfter = await getAccount(provider.connection, buyerPaymentToken);
+        const sellerAfter = await getAccount(provider.connection, sellerPaymentToken);
+        const vaultAfter = await getAccount(provider.connection, vaultTokenAccount);
+        const arbitratorAfter = await getAccount(provider.connection, arbitratorPaymentToken);
+        assert.equal(Number(buyerAfter.amount - buyerBefore.amount), 600_000);
+        assert.equal(Number(sellerAfter.amount - sellerBefore.amount), 350_000 - ARBITRATOR_FEE);
+        assert.equal(Number(vaultAfter.amount - vaultBefore.amount), 50_000);
+        assert.equal(Number(arbitratorAfter.amount - arbitratorBefore.amount), ARBITRATOR_FEE);
+
+        const escrowBalance = await getAccount(provider.connection, accounts.escrowToken);
+        assert.equal(Number(escrowBalance.amount), 0);
+      });
+
+      it("rejects a split that leaves no room for the arbitrator fee", async () => {
+        const accounts = await openEscrowDispute("arbitrator-fee-mismatch", { itemNotReceived: {} }, { favorBuyer: {} });
+
+        try {
+          await resolve(accounts, { split: {} }, 600_000, 350_000, 50_000);
+          assert.fail("Should reject a split that ignores the arbitrator fee");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidResolutionAmounts");
+        }
+
+        const dispute = await marketplace.account.dispute.fetch(accounts.dispute);
+        assert.deepEqual(dispute.state, { open: {} });
+      });
+
+      it("pays nothing to the arbitrator once the fee is zero", async () => {
+        await setArbitratorFee(0);
+
+        const accounts = await openEscrowDispute("arbitrator-fee-zero", { itemNotReceived: {} }, { favorBuyer: {} });
+        const arbitratorBefore = await getAccount(provider.connection, arbitratorPaymentToken);
+
+        await resolve(accounts, { favorBuyer: {} }, ESCROW_AMOUNT, 0, 0);
+
+        const arbitratorAfter = await getAccount(provider.connection, arbitratorPaymentToken);
+        assert.equal(Number(arbitratorAfter.amount - arbitratorBefore.amount), 0);
+      });
+    });
+  });
+
+  describe("Proxy Bidding", () => {
+    const PRICE = new BN(LAMPORTS_PER_SOL);
+    const MIN_INCREMENT = new BN(LAMPORTS_PER_SOL / 10);
+    const proxySeller = Keypair.generate();
+    const manualBidder = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), proxySeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    let mint: PublicKey;
+    let listing: PublicKey;
+
+    const bidAccounts = (bidder: PublicKey) => {
+      const [bidPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bidPda.toBuffer()],
+        marketplace.programId
+      );
+      return { bidPda, bidEscrow };
+    };
+
+    const leaderBidAccounts = async () => {
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      if (listingAccount.highestBidder.equals(PublicKey.default)) {
+        return [];
+      }
+      return [{ pubkey: bidAccounts(listingAccount.highestBidder).bidPda, isWritable: true, isSigner: false }];
+    };
+
+    const bid = async (bidder: Keypair, amount: BN, maxBid?: BN) => {
+      const { bidPda, bidEscrow } = bidAccounts(bidder.publicKey);
+      const method = maxBid
+        ? marketplace.methods.placeProxyBid(amount, maxBid)
+        : marketplace.methods.placeBid(amount);
+
+      await method
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing,
+          bid: bidPda,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(await leaderBidAccounts())
+        .signers([bidder])
+        .rpc();
+    };
+
+    const sol = (amount: number) => new BN(amount * LAMPORTS_PER_SOL);
+
+    before(async () => {
+      for (const wallet of [proxySeller, manualBidder]) {
+        await provider.connection.confirmTransaction(
+          await provider.connection.requestAirdrop(wallet.publicKey, 10 * LAMPORTS_PER_SOL)
+        );
+      }
+
+      mint = await createMint(provider.connection, proxySeller, proxySeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, proxySeller, mint, proxySeller.publicKey);
+      await mintTo(provider.connection, proxySeller, mint, sellerTokenAccount, proxySeller, 1);
+
+      [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + 600),
+            minBidIncrement: MIN_INCREMENT,
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: proxySeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([proxySeller])
+        .rpc();
+    });
+
+    it("lets the higher proxy maximum win at just above the lower maximum", async () => {
+      await bid(bidder1, PRICE, sol(3));
+
+      let listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.highestBid.toString(), PRICE.toString());
+      assert.isTrue(listingAccount.highestBidder.equals(bidder1.publicKey));
+
+      const { bidEscrow } = bidAccounts(bidder2.publicKey);
+      await bid(bidder2, PRICE.add(MIN_INCREMENT), sol(5));
+
+      listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.isTrue(listingAccount.highestBidder.equals(bidder2.publicKey));
+      assert.equal(listingAccount.highestBid.toString(), sol(3).add(MIN_INCREMENT).toString());
+      assert.equal(listingAccount.highestMaxBid.toString(), sol(5).toString());
+
+      const losingBid = await marketplace.account.bid.fetch(bidAccounts(bidder1.publicKey).bidPda);
+      assert.equal(losingBid.amount.toString(), sol(3).toString());
+
+      const winningBid = await marketplace.account.bid.fetch(bidAccounts(bidder2.publicKey).bidPda);
+      assert.equal(winningBid.amount.toString(), sol(3).add(MIN_INCREMENT).toString());
+      assert.equal(winningBid.maxBid.toString(), sol(5).toString());
+
+      // The full maximum is held in escrow
+      const escrowBalance = await provider.connection.getBalance(bidEscrow);
+      assert.isAtLeast(escrowBalance, sol(5).toNumber());
+    });
+
+    it("auto-increments the leading proxy when a manual bid challenges it", async () => {
+      const manualAmount = sol(3).add(MIN_INCREMENT.muln(2));
+      await bid(manualBidder, manualAmount);
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.isTrue(listingAccount.highestBidder.equals(bidder2.publicKey));
+      assert.equal(listingAccount.highestBid.toString(), manualAmount.add(MIN_INCREMENT).toString());
+
+      const proxyBid = await marketplace.account.bid.fetch(bidAccounts(bidder2.publicKey).bidPda);
+      assert.equal(proxyBid.amount.toString(), manualAmount.add(MIN_INCREMENT).toString());
+
+      const manualBid = await marketplace.account.bid.fetch(bidAccounts(manualBidder.publicKey).bidPda);
+      assert.equal(manualBid.amount.toString(), manualAmount.toString());
+      assert.equal(manualBid.maxBid.toString(), manualAmount.toString());
+    });
+
+    it("requires the leader's bid account to resolve a challenge to their proxy", async () => {
+      const { bidPda, bidEscrow } = bidAccounts(manualBidder.publicKey);
+      try {
+        await marketplace.methods
+          .placeBid(sol(4))
+          .accounts({
+            bidder: manualBidder.publicKey,
+            marketplaceConfig,
+            listing,
+            bid: bidPda,
+            bidEscrow,
+            mint,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([manualBidder])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "MissingLeadingBid");
+      }
+    });
+  });
+
+  describe("Auction Auto-Relist", () => {
+    const PRICE = new BN(LAMPORTS_PER_SOL);
+    const RELIST_PRICE = new BN(LAMPORTS_PER_SOL / 2);
+    const AUCTION_DURATION = 3; // seconds
+    const relistSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), relistSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    // Lists a fresh NFT as a short auction that relists once at half price
+    const relistableAuction = async (reservePrice: BN | null = null) => {
+      const mint = await createMint(provider.connection, relistSeller, relistSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, relistSeller, mint, relistSeller.publicKey);
+      await mintTo(provider.connection, relistSeller, mint, sellerTokenAccount, relistSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: relistSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([relistSeller])
+        .rpc();
+
+      await marketplace.methods
+        .setRelistConfig({
+          price: RELIST_PRICE,
+          duration: new BN(AUCTION_DURATION),
+          maxRelists: 1,
+        })
+        .accounts({ seller: relistSeller.publicKey, listing })
+        .signers([relistSeller])
+        .rpc();
+
+      return { mint, listing };
+    };
+
+    const bidAccounts = (listing: PublicKey, bidder: PublicKey) => {
+      const [bid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bid.toBuffer()],
+        marketplace.programId
+      );
+      return { bid, bidEscrow };
+    };
+
+    // Anyone can trigger the relist; bidder2 stands in for a keeper. Every
+    // bidder's bid, escrow and wallet are passed so their bids are refunded
+    const relist = (listing: PublicKey, bidders: PublicKey[] = []) =>
+      marketplace.methods
+        .relistAuction()
+        .accounts({
+          caller: bidder2.publicKey,
+          listing,
+          sellerVerification: PublicKey.findProgramAddressSync(
+            [Buffer.from("seller_verification"), relistSeller.publicKey.toBuffer()],
+            marketplace.programId
+          )[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          bidders.flatMap((bidder) => {
+            const { bid, bidEscrow } = bidAccounts(listing, bidder);
+            return [
+              { pubkey: bid, isSigner: false, isWritable: true },
+              { pubkey: bidEscrow, isSigner: false, isWritable: true },
+              { pubkey: bidder, isSigner: false, isWritable: true },
+            ];
+          })
+        )
+        .signers([bidder2])
+        .rpc();
+
+    const placeBid = (mint: PublicKey, listing: PublicKey, amount: BN) => {
+      const { bid, bidEscrow } = bidAccounts(listing, bidder1.publicKey);
+      return marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder1.publicKey,
+          marketplaceConfig,
+          listing,
+          bid,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder1])
+        .rpc();
+    };
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(relistSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+    });
+
+    it("relists an auction that ended without bids at the lower price", async () => {
+      const { listing } = await relistableAuction();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+      await relist(listing);
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      const now = Math.floor(Date.now() / 1000);
+      assert.isTrue(listingAccount.isActive);
+      assert.equal(listingAccount.price.toString(), RELIST_PRICE.toString());
+      assert.equal(listingAccount.relistsApplied, 1);
+      assert.equal(listingAccount.highestBid.toNumber(), 0);
+      assert.isAbove(listingAccount.auctionConfig.endTime.toNumber(), now);
+    });
+
+    it("stops relisting once the relist cap is reached", async () => {
+      const { listing } = await relistableAuction();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+      await relist(listing);
+
+      // The relisted auction also ends unsold, but the single relist is used up
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+      try {
+        await relist(listing);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "RelistLimitReached");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.relistsApplied, 1);
+    });
+
+    it("refunds bids under the reserve and lets the bidder bid again after a relist", async () => {
+      const { mint, listing } = await relistableAuction(PRICE.muln(2));
+      await placeBid(mint, listing, PRICE);
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      // Every bidder must be passed
+      try {
+        await relist(listing);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidBatchAccounts");
+      }
+
+      const bidderBefore = await provider.connection.getBalance(bidder1.publicKey);
+      await relist(listing, [bidder1.publicKey]);
+      const bidderAfter = await provider.connection.getBalance(bidder1.publicKey);
+      assert.isAtLeast(bidderAfter - bidderBefore, PRICE.toNumber());
+
+      const { bid } = bidAccounts(listing, bidder1.publicKey);
+      assert.isNull(await provider.connection.getAccountInfo(bid));
+      let listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.uniqueBidders, 0);
+      assert.equal(listingAccount.highestBid.toNumber(), 0);
+
+      // The old bid no longer blocks a fresh bid at the relist price
+      await placeBid(mint, listing, RELIST_PRICE);
+      listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.highestBid.toString(), RELIST_PRICE.toString());
+      assert.equal(listingAccount.uniqueBidders, 1);
+    });
+
+    it("refuses to relist an auction that sold", async () => {
+      const { mint, listing } = await relistableAuction();
+      await placeBid(mint, listing, PRICE);
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+      try {
+        await relist(listing, [bidder1.publicKey]);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "AuctionAlreadySold");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.price.toString(), PRICE.toString());
+      assert.equal(listingAccount.relistsApplied, 0);
+    });
+  });
+
+  describe("Fee Burn Split", () => {
+    const ESCROW_AMOUNT = 1_000_000;
+    const ESCROW_FEE_BPS = 250;
+    const COLLECTED_FEE = (ESCROW_AMOUNT * ESCROW_FEE_BPS) / 10000;
+    const BURN_SHARE_BPS = 2000; // 20%
+
+    let marketplaceState: PublicKey;
+    let feeVault: PublicKey;
+    let projectMint: PublicKey;
+    let vaultTokenAccount: PublicKey;
+    let treasuryTokenAccount: PublicKey;
+
+    const setFeeBurn = (burnShareBps: number, projectTokenMint: PublicKey) =>
+      marketplace.methods
+        .updateFeeBurn(burnShareBps, projectTokenMint)
+        .accounts({ marketplaceConfig, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+
+    const withdraw = (amount: number, feeMint: PublicKey, vault: PublicKey, treasury: PublicKey) =>
+      marketplace.methods
+        .withdrawFees(new BN(amount))
+        .accounts({
+          admin: platform.publicKey,
+          marketplace: marketplaceState,
+          marketplaceConfig,
+          feeVault,
+          vaultTokenAccount: vault,
+          feeMint,
+          treasuryTokenAccount: treasury,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([platform])
+        .rpc();
+
+    before(async () => {
+      [marketplaceState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("marketplace")],
+        marketplace.programId
+      );
+      [feeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_vault"), marketplaceState.toBuffer()],
+        marketplace.programId
+      );
+
+      projectMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      const buyerProjectToken = await createAssociatedTokenAccount(provider.connection, buyer, projectMint, buyer.publicKey);
+      const sellerProjectToken = await createAssociatedTokenAccount(provider.connection, seller, projectMint, seller.publicKey);
+      vaultTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, projectMint, feeVault, true)
+      ).address;
+
+      const state = await marketplace.account.marketplace.fetch(marketplaceState);
+      treasuryTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, projectMint, state.treasury, true)
+      ).address;
+
+      await mintTo(provider.connection, platform, projectMint, buyerProjectToken, platform, ESCROW_AMOUNT);
+
+      // Collect a platform fee in the project token through an escrow release
+      const transactionId = "fee-burn-split";
+      const [escrowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), Buffer.from(transactionId)],
+        marketplace.programId
+      );
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, projectMint, escrowPda, true)
+      ).address;
+
+      await marketplace.methods
+        .createEscrow({
+          transactionId,
+          amount: new BN(ESCROW_AMOUNT),
+          expiryTime: new BN(Math.floor(Date.now() / 1000) + 3600),
+          terms: "Ticket delivery before event",
+          disputePeriod: new BN(3600),
+          platformFeeRate: ESCROW_FEE_BPS,
+          arbitrationWindow: null,
+          inspectionPeriod: new BN(0),
+          minTimeBeforeDispute: new BN(0),
+          defaultResolution: { favorBuyer: {} },
+          releaseCondition: null,
+          confirmers: [],
+        })
+        .accounts({
+          creator: buyer.publicKey,
+          escrow: escrowPda,
+          seller: seller.publicKey,
+          arbitrator: platform.publicKey,
+          creatorTokenAccount: buyerProjectToken,
+          escrowTokenAccount: escrowToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+
+      await marketplace.methods
+        .releaseEscrow({ releaseType: { full: {} }, partialAmount: null })
+        .accounts({
+          authority: buyer.publicKey,
+          escrow: escrowPda,
+          escrowTokenAccount: escrowToken,
+          sellerTokenAccount: sellerProjectToken,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+      await setFeeBurn(BURN_SHARE_BPS, projectMint);
+    });
+
+    after(async () => {
+      await setFeeBurn(0, PublicKey.default);
+    });
+
+    it("rejects a burn split on a fee token other than the project token", async () => {
+      const otherMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      const otherVault = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, otherMint, feeVault, true)
+      ).address;
+      const state = await marketplace.account.marketplace.fetch(marketplaceState);
+      const otherTreasury = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, otherMint, state.treasury, true)
+      ).address;
+      await mintTo(provider.connection, platform, otherMint, otherVault, platform, COLLECTED_FEE);
+
+      try {
+        await withdraw(1, otherMint, otherVault, otherTreasury);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "FeeTokenNotBurnable");
+      }
+
+      const otherVaultAccount = await getAccount(provider.connection, otherVault);
+      assert.equal(Number(otherVaultAccount.amount), COLLECTED_FEE);
+    });
+
+    it("burns the configured share of withdrawn fees and sends the rest to treasury", async () => {
+      const expectedBurn = (COLLECTED_FEE * BURN_SHARE_BPS) / 10000;
+      const supplyBefore = (await getMint(provider.connection, projectMint)).supply;
+      const treasuryBefore = (await getAccount(provider.connection, treasuryTokenAccount)).amount;
+      const vaultBefore = await marketplace.account.feeVault.fetch(feeVault);
+
+      await withdraw(COLLECTED_FEE, projectMint, vaultTokenAccount, treasuryTokenAccount);
+
+      const supplyAfter = (await getMint(provider.connection, projectMint)).supply;
+      const treasuryAfter = (await getAccount(provider.connection, treasuryTokenAccount)).amount;
+      const vaultAfter = await marketplace.account.feeVault.fetch(feeVault);
+      assert.equal(Number(supplyBefore - supplyAfter), expectedBurn);
+      assert.equal(Number(treasuryAfter - treasuryBefore), COLLECTED_FEE - expectedBurn);
+      assert.equal(
+        vaultBefore.accumulatedFees.sub(vaultAfter.accumulatedFees).toNumber(),
+        COLLECTED_FEE
+      );
+    });
+  });
+
+  describe("Token Royalty Payouts", () => {
+    const SALE_PRICE = new BN(LAMPORTS_PER_SOL);
+    const USDC_PER_SOL = 150_000_000; // 150 USDC at 6 decimals
+    const AUCTION_DURATION = 3; // seconds
+    const royaltySeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), royaltySeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+    const [liquidityAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("royalty_liquidity")],
+      marketplace.programId
+    );
+
+    const priceFeedFor = (payoutMint: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("royalty_price_feed"), payoutMint.toBuffer()],
+        marketplace.programId
+      )[0];
+
+    // Creates a payout token with a posted rate, `liquidity` units held by the marketplace
+    const payoutToken = async (liquidity: number) => {
+      const payoutMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      const liquidityAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, payoutMint, liquidityAuthority, true)
+      ).address;
+      if (liquidity > 0) {
+        await mintTo(provider.connection, platform, payoutMint, liquidityAccount, platform, liquidity);
+      }
+      const creatorAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, payoutMint, creator.publicKey)
+      ).address;
+
+      await marketplace.methods
+        .updateRoyaltyPriceFeed(new BN(USDC_PER_SOL))
+        .accounts({
+          marketplaceConfig,
+          priceFeed: priceFeedFor(payoutMint),
+          payoutMint,
+          admin: platform.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([platform])
+        .rpc();
+
+      return { payoutMint, liquidityAccount, creatorAccount };
+    };
+
+    // Auctions an NFT whose verified creator takes a royalty in `payoutMint`,
+    // with a single bid at the sale price, and waits for the auction to end
+    const endedRoyaltyAuction = async (payoutMint: PublicKey) => {
+      const mint = await createMint(provider.connection, creator, creator.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, royaltySeller, mint, royaltySeller.publicKey);
+      await mintTo(provider.connection, creator, mint, sellerTokenAccount, creator, 1);
+
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        METADATA_PROGRAM_ID
+      );
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          createCreateMetadataAccountV3Instruction(
+            {
+              metadata,
+              mint,
+              mintAuthority: creator.publicKey,
+              payer: creator.publicKey,
+              updateAuthority: creator.publicKey,
+            },
+            {
+              createMetadataAccountArgsV3: {
+                data: {
+                  name: "Royalty NFT",
+                  symbol: "RNFT",
+                  uri: "https://royalty.uri",
+                  sellerFeeBasisPoints: ROYALTY_BPS,
+                  creators: [{ address: creator.publicKey, verified: true, share: 100 }],
+                  collection: null,
+                  uses: null,
+                },
+                isMutable: true,
+                collectionDetails: null,
+              },
+            }
+          )
+        ),
+        [creator]
+      );
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          SALE_PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          { creator: creator.publicKey, percentageBps: ROYALTY_BPS, royaltyPayoutMint: payoutMint, minRoyalty: new BN(0) }
+        )
+        .accounts({
+          seller: royaltySeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          nftMetadata: metadata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([royaltySeller])
+        .rpc();
+
+      const [winningBid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder1.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), winningBid.toBuffer()],
+        marketplace.programId
+      );
+      await marketplace.methods
+        .placeBid(SALE_PRICE)
+        .accounts({
+          bidder: bidder1.publicKey,
+          marketplaceConfig,
+          listing,
+          bid: winningBid,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder1])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      const winnerTokenAccount = await getAssociatedTokenAddress(mint, bidder1.publicKey);
+      return { mint, listing, escrowTokenAccount, winnerTokenAccount, winningBid, bidEscrow };
+    };
+
+    const settle = (
+      auction: Awaited<ReturnType<typeof endedRoyaltyAuction>>,
+      payout: Awaited<ReturnType<typeof payoutToken>>,
+      royaltyRecipient = creator.publicKey
+    ) =>
+      marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: bidder1.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          winnerTokenAccount: auction.winnerTokenAccount,
+          sellerTokenAccount: await getAssociatedTokenAddress(auction.mint, royaltySeller.publicKey),
+          winningBid: auction.winningBid,
+          bidEscrow: auction.bidEscrow,
+          mint: auction.mint,
+          winner: bidder1.publicKey,
+          seller: royaltySeller.publicKey,
+          sellerStats,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(royaltyRecipient)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: priceFeedFor(payout.payoutMint), isWritable: false, isSigner: false },
+          { pubkey: liquidityAuthority, isWritable: true, isSigner: false },
+          { pubkey: payout.liquidityAccount, isWritable: true, isSigner: false },
+          { pubkey: payout.creatorAccount, isWritable: true, isSigner: false },
+        ])
+        .signers([bidder1])
+        .rpc();
+
+    const royaltyLamports = (SALE_PRICE.toNumber() * ROYALTY_BPS) / 10000;
+    const expectedPayout = (royaltyLamports * USDC_PER_SOL) / LAMPORTS_PER_SOL;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(royaltySeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+    });
+
+    it("pays the royalty of a SOL sale in the payout token at the admin-posted rate", async () => {
+      const payout = await payoutToken(expectedPayout * 10);
+      const auction = await endedRoyaltyAuction(payout.payoutMint);
+
+      const creatorLamportsBefore = await provider.connection.getBalance(creator.publicKey);
+      const liquidityLamportsBefore = await provider.connection.getBalance(liquidityAuthority);
+
+      await settle(auction, payout);
+
+      const creatorTokens = await getAccount(provider.connection, payout.creatorAccount);
+      assert.equal(Number(creatorTokens.amount), expectedPayout);
+      const liquidityTokens = await getAccount(provider.connection, payout.liquidityAccount);
+      assert.equal(Number(liquidityTokens.amount), expectedPayout * 9);
+
+      // The SOL royalty funds the liquidity pool instead of the creator
+      assert.equal(await provider.connection.getBalance(creator.publicKey), creatorLamportsBefore);
+      assert.equal(
+        (await provider.connection.getBalance(liquidityAuthority)) - liquidityLamportsBefore,
+        royaltyLamports
+      );
+    });
+
+    it("rejects settlement when the liquidity can't cover the royalty", async () => {
+      const payout = await payoutToken(expectedPayout - 1);
+      const auction = await endedRoyaltyAuction(payout.payoutMint);
+
+      try {
+        await settle(auction, payout);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientRoyaltyLiquidity");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.isTrue(listingAccount.isActive);
+      const creatorTokens = await getAccount(provider.connection, payout.creatorAccount);
+      assert.equal(Number(creatorTokens.amount), 0);
+    });
+
+    it("rejects a royalty recipient other than the listing's creator", async () => {
+      const payout = await payoutToken(expectedPayout * 10);
+      const auction = await endedRoyaltyAuction(payout.payoutMint);
+
+      // The settler tries to route the royalty, and its receipts, to their own wallet
+      try {
+        await settle(auction, payout, bidder1.publicKey);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidRoyaltyRecipient");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.isTrue(listingAccount.isActive);
+    })
+    it("lets only the admin withdraw the SOL the liquidity pool collected", async () => {
+      const payout = await payoutToken(expectedPayout * 10);
+      await settle(await endedRoyaltyAuction(payout.payoutMint), payout);
+      const collected = await provider.connection.getBalance(liquidityAuthority);
+
+      const withdraw = (admin: Keypair) =>
+        marketplace.methods
+          .withdrawRoyaltyLiquidity(new BN(collected))
+          .accounts({
+            marketplaceConfig,
+            liquidityAuthority,
+            recipient: feeCollector.publicKey,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+
+      try {
+        await withdraw(bidder1);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "ConstraintHasOne");
+      }
+
+      const recipientBefore = await provider.connection.getBalance(feeCollector.publicKey);
+      await withdraw(platform);
+      assert.equal(await provider.connection.getBalance(liquidityAuthority), 0);
+      assert.equal(
+        (await provider.connection.getBalance(feeCollector.publicKey)) - recipientBefore,
+        collected
+      );
+    });
+  });
+
+  describe("Escrow Confirmation Thresholds", () => {
+    const ESCROW_AMOUNT = 1_000_000;
+    const ESCROW_FEE_BPS = 250;
+
+    let marketplaceState: PublicKey;
+    let feeVault: PublicKey;
+    let paymentMint: PublicKey;
+    let vaultTokenAccount: PublicKey;
+    let buyerPaymentToken: PublicKey;
+    let sellerPaymentToken: PublicKey;
+    let inspector: Keypair;
+
+    const createMultiConfirmationEscrow = async (transactionId: string) => {
+      const [escrowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), Buffer.from(transactionId)],
+        marketplace.programId
+      );
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, paymentMint, escrowPda, true)
+      ).address;
+
+      await marketplace.methods
+        .createEscrow({
+          transactionId,
+          amount: new BN(ESCROW_AMOUNT),
+          expiryTime: new BN(Math.floor(Date.now() / 1000) + 3600),
+          terms: "Ticket inspected before release",
+          disputePeriod: new BN(3600),
+          platformFeeRate: ESCROW_FEE_BPS,
+          arbitrationWindow: null,
+          inspectionPeriod: new BN(0),
+          minTimeBeforeDispute: new BN(0),
+          defaultResolution: { favorBuyer: {} },
+          releaseCondition: { multiConfirmation: { required: 2 } },
+          confirmers: [buyer.publicKey, inspector.publicKey],
+        })
+        .accounts({
+          creator: buyer.publicKey,
+          escrow: escrowPda,
+          seller: seller.publicKey,
+          arbitrator: platform.publicKey,
+          creatorTokenAccount: buyerPaymentToken,
+          escrowTokenAccount: escrowToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+
+      return { escrow: escrowPda, escrowToken };
+    };
+
+    const confirmRelease = (confirmer: Keypair, escrowPda: PublicKey) =>
+      marketplace.methods
+        .confirmEscrowRelease()
+        .accounts({
+          confirmer: confirmer.publicKey,
+          escrow: escrowPda,
+        })
+        .signers([confirmer])
+        .rpc();
+
+    const releaseFull = (escrowPda: PublicKey, escrowToken: PublicKey) =>
+      marketplace.methods
+        .releaseEscrow({ releaseType: { full: {} }, partialAmount: null })
+        .accounts({
+          authority: buyer.publicKey,
+          escrow: escrowPda,
+          escrowTokenAccount: escrowToken,
+          sellerTokenAccount: sellerPaymentToken,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+    before(async () => {
+      inspector = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(inspector.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+
+      [marketplaceState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("marketplace")],
+        marketplace.programId
+      );
+      [feeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_vault"), marketplaceState.toBuffer()],
+        marketplace.programId
+      );
+
+      paymentMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      buyerPaymentToken = await createAssociatedTokenAccount(provider.connection, buyer, paymentMint, buyer.publicKey);
+      sellerPaymentToken = await createAssociatedTokenAccount(provider.connection, seller, paymentMint, seller.publicKey);
+      vaultTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, paymentMint, feeVault, true)
+      ).address;
+
+      await mintTo(provider.connection, platform, paymentMint, buyerPaymentToken, platform, ESCROW_AMOUNT * 10);
+    });
+
+    it("releases once both buyer and inspector have confirmed", async () => {
+      const { escrow: escrowPda, escrowToken } = await createMultiConfirmationEscrow("multi-confirm-release");
+
+      await confirmRelease(buyer, escrowPda);
+      await confirmRelease(inspector, escrowPda);
+
+      const confirmed = await marketplace.account.escrow.fetch(escrowPda);
+      assert.equal(confirmed.confirmations.length, 2);
+
+      const sellerBefore = (await getAccount(provider.connection, sellerPaymentToken)).amount;
+      await releaseFull(escrowPda, escrowToken);
+
+      const released = await marketplace.account.escrow.fetch(escrowPda);
+      assert.deepEqual(released.state, { completed: {} });
+
+      const expectedFee = (ESCROW_AMOUNT * ESCROW_FEE_BPS) / 10000;
+      const sellerAfter = (await getAccount(provider.connection, sellerPaymentToken)).amount;
+      assert.equal(Number(sellerAfter - sellerBefore), ESCROW_AMOUNT - expectedFee);
+    });
+
+    it("rejects release with only one confirmation", async () => {
+      const { escrow: escrowPda, escrowToken } = await createMultiConfirmationEscrow("multi-confirm-short");
+
+      await confirmRelease(inspector, escrowPda);
+
+      try {
+        await releaseFull(escrowPda, escrowToken);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientConfirmations");
+      }
+
+      const escrowAccount = await marketplace.account.escrow.fetch(escrowPda);
+      assert.deepEqual(escrowAccount.state, { active: {} });
+      assert.equal(escrowAccount.amount.toNumber(), ESCROW_AMOUNT);
+    });
+
+    it("does not double-count a repeated confirmation", async () => {
+      const { escrow: escrowPda, escrowToken } = await createMultiConfirmationEscrow("multi-confirm-duplicate");
+
+      await confirmRelease(buyer, escrowPda);
+
+      try {
+        await confirmRelease(buyer, escrowPda);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "EscrowAlreadyConfirmed");
+      }
+
+      const escrowAccount = await marketplace.account.escrow.fetch(escrowPda);
+      assert.equal(escrowAccount.confirmations.length, 1);
+
+      try {
+        await releaseFull(escrowPda, escrowToken);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientConfirmations");
+      }
+    });
+  });
+
+  describe("Private Auction Whitelists", () => {
+    const PRICE = new BN(LAMPORTS_PER_SOL);
+    const privateSeller = Keypair.generate();
+    const invitedBidder = Keypair.generate();
+    const otherInvitee = Keypair.generate();
+    const uninvitedBidder = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), privateSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    // Mirrors AuctionConfig::admits_bidder: sha256 leaves, sorted-pair nodes
+    const sha256 = (...parts: Buffer[]) => createHash("sha256").update(Buffer.concat(parts)).digest();
+    const hashPair = (a: Buffer, b: Buffer) => (Buffer.compare(a, b) <= 0 ? sha256(a, b) : sha256(b, a));
+    const leafFor = (wallet: PublicKey) => sha256(wallet.toBuffer());
+
+    const invitedLeaf = leafFor(invitedBidder.publicKey);
+    const otherLeaf = leafFor(otherInvitee.publicKey);
+    const whitelistRoot = hashPair(invitedLeaf, otherLeaf);
+
+    const createAuction = async (bidderWhitelist: number[] | null) => {
+      const mint = await createMint(provider.connection, privateSeller, privateSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, privateSeller, mint, privateSeller.publicKey);
+      await mintTo(provider.connection, privateSeller, mint, sellerTokenAccount, privateSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + 600),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: privateSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([privateSeller])
+        .rpc();
+
+      return { mint, listing };
+    };
+
+    const bidAccounts = (auction: { mint: PublicKey; listing: PublicKey }, bidder: Keypair) => {
+      const [bidPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.listing.toBuffer(), bidder.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bidPda.toBuffer()],
+        marketplace.programId
+      );
+      return {
+        bidder: bidder.publicKey,
+        marketplaceConfig,
+        listing: auction.listing,
+        bid: bidPda,
+        bidEscrow,
+        mint: auction.mint,
+        systemProgram: SystemProgram.programId,
+      };
+    };
+
+    before(async () => {
+      for (const wallet of [privateSeller, invitedBidder, otherInvitee, uninvitedBidder]) {
+        await provider.connection.confirmTransaction(
+          await provider.connection.requestAirdrop(wallet.publicKey, 10 * LAMPORTS_PER_SOL)
+        );
+      }
+    });
+
+    it("accepts a whitelisted bidder with a valid proof", async () => {
+      const auction = await createAuction(Array.from(whitelistRoot));
+
+      await marketplace.methods
+        .placeWhitelistedBid(PRICE, null, [Array.from(otherLeaf)])
+        .accounts(bidAccounts(auction, invitedBidder))
+        .signers([invitedBidder])
+        .rpc();
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.equal(listingAccount.highestBid.toString(), PRICE.toString());
+      assert.isTrue(listingAccount.highestBidder.equals(invitedBidder.publicKey));
+    });
+
+    it("rejects a bidder without a valid proof", async () => {
+      const auction = await createAuction(Array.from(whitelistRoot));
+
+      // Borrowing an invitee's proof doesn't prove a different wallet
+      try {
+        await marketplace.methods
+          .placeWhitelistedBid(PRICE, null, [Array.from(otherLeaf)])
+          .accounts(bidAccounts(auction, uninvitedBidder))
+          .signers([uninvitedBidder])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "BidderNotWhitelisted");
+      }
+
+      // Plain bids carry no proof, so they can't reach a private auction either
+      try {
+        await marketplace.methods
+          .placeBid(PRICE)
+          .accounts(bidAccounts(auction, uninvitedBidder))
+          .signers([uninvitedBidder])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "BidderNotWhitelisted");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.equal(listingAccount.highestBid.toNumber(), 0);
+    });
+
+    it("lets anyone bid on a public auction", async () => {
+      const auction = await createAuction(null);
+
+      await marketplace.methods
+        .placeBid(PRICE)
+        .accounts(bidAccounts(auction, uninvitedBidder))
+        .signers([uninvitedBidder])
+        .rpc();
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.isTrue(listingAccount.highestBidder.equals(uninvitedBidder.publicKey));
+    });
+  });
+
+  describe("Seller Fee Tiers", () => {
+    const SALE_PRICE = new BN(LAMPORTS_PER_SOL);
+    const TIER_FEE_BPS = 100;
+    const AUCTION_DURATION = 3; // seconds
+    const tierSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), tierSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    const setFeeTiers = (feeTiers: { minVolume: BN; feeBps: number }[]) =>
+      marketplace.methods
+        .updateFeeTiers(feeTiers)
+        .accounts({
+          marketplaceConfig,
+          admin: platform.publicKey,
+        })
+        .signers([platform])
+        .rpc();
+
+    // Auctions a fresh NFT for the sale price, settles it, and returns the
+    // platform fee charged along with any tier changes announced
+    const sellAtAuction = async () => {
+      const mint = await createMint(provider.connection, tierSeller, tierSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, tierSeller, mint, tierSeller.publicKey);
+      await mintTo(provider.connection, tierSeller, mint, sellerTokenAccount, tierSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          SALE_PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: tierSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([tierSeller])
+        .rpc();
+
+      const [winningBid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder1.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), winningBid.toBuffer()],
+        marketplace.programId
+      );
+      await marketplace.methods
+        .placeBid(SALE_PRICE)
+        .accounts({
+          bidder: bidder1.publicKey,
+          marketplaceConfig,
+          listing,
+          bid: winningBid,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder1])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      const tierChanges: { oldFeeBps: number; newFeeBps: number }[] = [];
+      const listener = marketplace.addEventListener("SellerFeeTierChanged", (event) => {
+        assert.equal(event.seller.toBase58(), tierSeller.publicKey.toBase58());
+        tierChanges.push({ oldFeeBps: event.oldFeeBps, newFeeBps: event.newFeeBps });
+      });
+      const feeRecipientBefore = await provider.connection.getBalance(platform.publicKey);
+
+      await marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: bidder1.publicKey,
+          marketplaceConfig,
+          listing,
+          escrowTokenAccount,
+          winnerTokenAccount: await getAssociatedTokenAddress(mint, bidder1.publicKey),
+          sellerTokenAccount: await getAssociatedTokenAddress(mint, tierSeller.publicKey),
+          winningBid,
+          bidEscrow,
+          mint,
+          winner: bidder1.publicKey,
+          seller: tierSeller.publicKey,
+          sellerStats,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder1])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      const platformFee = (await provider.connection.getBalance(platform.publicKey)) - feeRecipientBefore;
+      return { platformFee, tierChanges };
+    };
+
+    const feeAt = (bps: number) => (SALE_PRICE.toNumber() * bps) / 10000;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(tierSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      // Sellers drop to the lower tier once they've sold two sales' worth
+      await setFeeTiers([{ minVolume: SALE_PRICE.muln(2), feeBps: TIER_FEE_BPS }]);
+    });
+
+    after(async () => {
+      await setFeeTiers([]);
+    });
+
+    it("does not announce a tier change for a sale within the base tier", async () => {
+      const { platformFee, tierChanges } = await sellAtAuction();
+
+      assert.deepEqual(tierChanges, []);
+      assert.equal(platformFee, feeAt(PLATFORM_FEE_BPS));
+
+      const stats = await marketplace.account.sellerStats.fetch(sellerStats);
+      assert.equal(stats.totalVolume.toString(), SALE_PRICE.toString());
+    });
+
+    it("announces the tier change on the sale that crosses the threshold", async () => {
+      const { platformFee, tierChanges } = await sellAtAuction();
+
+      assert.deepEqual(tierChanges, [{ oldFeeBps: PLATFORM_FEE_BPS, newFeeBps: TIER_FEE_BPS }]);
+      // The crossing sale itself is still charged at the old tier
+      assert.equal(platformFee, feeAt(PLATFORM_FEE_BPS));
+    });
+
+    it("charges the lower fee without re-announcing sales inside the new tier", async () => {
+      const { platformFee, tierChanges } = await sellAtAuction();
+
+      assert.deepEqual(tierChanges, []);
+      assert.equal(platformFee, feeAt(TIER_FEE_BPS));
+
+      const stats = await marketplace.account.sellerStats.fetch(sellerStats);
+      assert.equal(stats.totalVolume.toString(), SALE_PRICE.muln(3).toString());
+    });
+
+    it("charges the tier fee on an accepted offer and counts it toward volume", async () => {
+      const mint = await createMint(provider.connection, tierSeller, tierSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, tierSeller, mint, tierSeller.publicKey);
+      await mintTo(provider.connection, tierSeller, mint, sellerTokenAccount, tierSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const [offer] = PublicKey.findProgramAddressSync(
+        [Buffer.from("offer"), listing.toBuffer(), buyer.publicKey.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .createListing(SALE_PRICE, { fixedPrice: {} }, null, null)
+        .accounts({
+          seller: tierSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([tierSeller])
+        .rpc();
+      await marketplace.methods
+        .setAllowOffers(true)
+        .accounts({ seller: tierSeller.publicKey, listing })
+        .signers([tierSeller])
+        .rpc();
+      await marketplace.methods
+        .makeOffer(SALE_PRICE)
+        .accounts({
+          buyer: buyer.publicKey,
+          marketplaceConfig,
+          listing,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const feeRecipientBefore = await provider.connection.getBalance(platform.publicKey);
+
+      await marketplace.methods
+        .acceptOffer()
+        .accounts({
+          seller: tierSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerStats,
+          offer,
+          buyer: buyer.publicKey,
+          escrowTokenAccount,
+          buyerTokenAccount: await getAssociatedTokenAddress(mint, buyer.publicKey),
+          mint,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([tierSeller])
+        .rpc();
+
+      const platformFee = (await provider.connection.getBalance(platform.publicKey)) - feeRecipientBefore;
+      assert.equal(platformFee, feeAt(TIER_FEE_BPS));
+
+      const stats = await marketplace.account.sellerStats.fetch(sellerStats);
+      assert.equal(stats.totalVolume.toString(), SALE_PRICE.muln(4).toString());
+    });
+  });
+
+  describe("Escrow Inspection Period", () => {
+    const ESCROW_AMOUNT = 1_000_000;
+    const ESCROW_FEE_BPS = 250;
+    const INSPECTION_PERIOD = 3; // seconds
+
+    let marketplaceState: PublicKey;
+    let feeVault: PublicKey;
+    let paymentMint: PublicKey;
+    let vaultTokenAccount: PublicKey;
+    let buyerPaymentToken: PublicKey;
+    let sellerPaymentToken: PublicKey;
+
+    // Opens an escrow with an inspection period and has the seller mark it delivered
+    const deliveredEscrow = async (transactionId: string) => {
+      const [escrowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), Buffer.from(transactionId)],
+        marketplace.programId
+      );
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, paymentMint, escrowPda, true)
+      ).address;
+
+      await marketplace.methods
+        .createEscrow({
+          transactionId,
+          amount: new BN(ESCROW_AMOUNT),
+          expiryTime: new BN(Math.floor(Date.now() / 1000) + 3600),
+          terms: "Buyer may inspect the tickets after delivery",
+          disputePeriod: new BN(3600),
+          platformFeeRate: ESCROW_FEE_BPS,
+          arbitrationWindow: null,
+          inspectionPeriod: new BN(INSPECTION_PERIOD),
+          minTimeBeforeDispute: new BN(0),
+          defaultResolution: { favorBuyer: {} },
+          releaseCondition: null,
+          confirmers: [],
+        })
+        .accounts({
+          creator: buyer.publicKey,
+          escrow: escrowPda,
+          seller: seller.publicKey,
+          arbitrator: platform.publicKey,
+          creatorTokenAccount: buyerPaymentToken,
+          escrowTokenAccount: escrowToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+
+      await marketplace.methods
+        .markEscrowDelivered()
+        .accounts({
+          seller: seller.publicKey,
+          escrow: escrowPda,
+        })
+        .signers([seller])
+        .rpc();
+
+      return { escrow: escrowPda, escrowToken };
+    };
+
+    const releaseFull = (escrowPda: PublicKey, escrowToken: PublicKey) =>
+      marketplace.methods
+        .releaseEscrow({ releaseType: { full: {} }, partialAmount: null })
+        .accounts({
+          authority: buyer.publicKey,
+          escrow: escrowPda,
+          escrowTokenAccount: escrowToken,
+          sellerTokenAccount: sellerPaymentToken,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+    before(async () => {
+      [marketplaceState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("marketplace")],
+        marketplace.programId
+      );
+      [feeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_vault"), marketplaceState.toBuffer()],
+        marketplace.programId
+      );
+
+      paymentMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      buyerPaymentToken = await createAssociatedTokenAccount(provider.connection, buyer, paymentMint, buyer.publicKey);
+      sellerPaymentToken = await createAssociatedTokenAccount(provider.connection, seller, paymentMint, seller.publicKey);
+      vaultTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, paymentMint, feeVault, true)
+      ).address;
+
+      await mintTo(provider.connection, platform, paymentMint, buyerPaymentToken, platform, ESCROW_AMOUNT * 10);
+    });
+
+    it("releases funds once the inspection period has elapsed", async () => {
+      const { escrow: escrowPda, escrowToken } = await deliveredEscrow("inspection-elapsed");
+
+      const delivered = await marketplace.account.escrow.fetch(escrowPda);
+      assert.isNotNull(delivered.deliveredAt);
+
+      await new Promise(resolve => setTimeout(resolve, (INSPECTION_PERIOD + 1) * 1000));
+
+      const sellerBefore = (await getAccount(provider.connection, sellerPaymentToken)).amount;
+      await releaseFull(escrowPda, escrowToken);
+
+      const expectedFee = (ESCROW_AMOUNT * ESCROW_FEE_BPS) / 10000;
+      const sellerAfter = (await getAccount(provider.connection, sellerPaymentToken)).amount;
+      assert.equal(Number(sellerAfter - sellerBefore), ESCROW_AMOUNT - expectedFee);
+
+      const released = await marketplace.account.escrow.fetch(escrowPda);
+      assert.deepEqual(released.state, { completed: {} });
+    });
+
+    it("rejects release during the inspection period", async () => {
+      const { escrow: escrowPda, escrowToken } = await deliveredEscrow("inspection-active");
+
+      try {
+        await releaseFull(escrowPda, escrowToken);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InspectionPeriodActive");
+      }
+
+      const escrowAccount = await marketplace.account.escrow.fetch(escrowPda);
+      assert.equal(escrowAccount.amount.toNumber(), ESCROW_AMOUNT);
+    });
+
+    it("keeps funds locked when the buyer disputes during inspection", async () => {
+      const { escrow: escrowPda, escrowToken } = await deliveredEscrow("inspection-disputed");
+      const [disputePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("dispute"), escrowPda.toBuffer()],
+        marketplace.programId
+      );
+
+      await marketplace.methods
+        .initiateDispute({
+          category: { itemNotAsDescribed: {} },
+          description: "Seats don't match the listing",
+          evidenceLinks: [],
+          requestedResolution: { refundBuyer: {} },
+        })
+        .accounts({
+          disputer: buyer.publicKey,
+          dispute: disputePda,
+          escrow: escrowPda,
+          marketplace: PublicKey.findProgramAddressSync([Buffer.from("marketplace")], marketplace.programId)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      // Even after the window closes, a disputed escrow goes to arbitration
+      await new Promise(resolve => setTimeout(resolve, (INSPECTION_PERIOD + 1) * 1000));
+
+      try {
+        await releaseFull(escrowPda, escrowToken);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "EscrowNotActive");
+      }
+
+      const escrowAccount = await marketplace.account.escrow.fetch(escrowPda);
+      assert.deepEqual(escrowAccount.state, { disputed: {} });
+      assert.equal(escrowAccount.amount.toNumber(), ESCROW_AMOUNT);
+    });
+  });
+
+  describe("Store Credit", () => {
+    const BID_PRICE = new BN(LAMPORTS_PER_SOL);
+    const SALE_PRICE = new BN(2 * LAMPORTS_PER_SOL);
+    const creditSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), creditSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+    const [storeCredit] = PublicKey.findProgramAddressSync(
+      [Buffer.from("store_credit"), bidder1.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    let mint: PublicKey;
+    let listing: PublicKey;
+
+    const bidAccounts = (bidder: PublicKey) => {
+      const [bidPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), listing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bidPda.toBuffer()],
+        marketplace.programId
+      );
+      return { bidPda, bidEscrow };
+    };
+
+    const placeBid = (bidder: Keypair, amount: BN) => {
+      const { bidPda, bidEscrow } = bidAccounts(bidder.publicKey);
+      return marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing,
+          bid: bidPda,
+          bidEscrow,
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+    };
+
+    const convertBid = (bidder: Keypair) => {
+      const { bidPda, bidEscrow } = bidAccounts(bidder.publicKey);
+      const [bidderCredit] = PublicKey.findProgramAddressSync(
+        [Buffer.from("store_credit"), bidder.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      return marketplace.methods
+        .convertBidToCredit()
+        .accounts({
+          bidder: bidder.publicKey,
+          listing,
+          bid: bidPda,
+          bidEscrow,
+          storeCredit: bidderCredit,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+    };
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(creditSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      mint = await createMint(provider.connection, creditSeller, creditSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, creditSeller, mint, creditSeller.publicKey);
+      await mintTo(provider.connection, creditSeller, mint, sellerTokenAccount, creditSeller, 1);
+
+      [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          BID_PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + 600),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: creditSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creditSeller])
+        .rpc();
+
+      // bidder1 is outbid by bidder2
+      await placeBid(bidder1, BID_PRICE);
+      await placeBid(bidder2, BID_PRICE.addn(1));
+    });
+
+    it("converts a losing bid into store credit", async () => {
+      const { bidPda, bidEscrow } = bidAccounts(bidder1.publicKey);
+      const escrowBefore = await provider.connection.getBalance(bidEscrow);
+
+      await convertBid(bidder1);
+
+      const credit = await marketplace.account.storeCredit.fetch(storeCredit);
+      assert.isTrue(credit.owner.equals(bidder1.publicKey));
+      assert.equal(credit.balance.toString(), BID_PRICE.toString());
+
+      const escrowAfter = await provider.connection.getBalance(bidEscrow);
+      assert.equal(escrowBefore - escrowAfter, BID_PRICE.toNumber());
+
+      const bid = await marketplace.account.bid.fetch(bidPda);
+      assert.isFalse(bid.isActive);
+    });
+
+    it("never credits more than the bid", async () => {
+      // A converted bid can't be converted again
+      try {
+        await convertBid(bidder1);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "BidNotActive");
+      }
+
+      // The leading bid isn't refundable, so it can't become credit
+      try {
+        await convertBid(bidder2);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "LeadingBidNotConvertible");
+      }
+
+      const credit = await marketplace.account.storeCredit.fetch(storeCredit);
+      assert.equal(credit.balance.toString(), BID_PRICE.toString());
+    });
+
+    it("spends store credit toward a purchase", async () => {
+      const saleMint = await createMint(provider.connection, creditSeller, creditSeller.publicKey, null, 0);
+      const saleSellerToken = await createAssociatedTokenAccount(provider.connection, creditSeller, saleMint, creditSeller.publicKey);
+      const saleBuyerToken = await createAssociatedTokenAccount(provider.connection, bidder1, saleMint, bidder1.publicKey);
+      await mintTo(provider.connection, creditSeller, saleMint, saleSellerToken, creditSeller, 1);
+
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), saleMint.toBuffer()],
+        nftProgram.programId
+      );
+      await nftProgram.methods
+        .createMetadata("Credit NFT", "CNFT", "https://credit.uri", ROYALTY_BPS, [creator.publicKey])
+        .accounts({
+          metadata,
+          mint: saleMint,
+          mintAuthority: creditSeller.publicKey,
+          payer: creditSeller.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creditSeller])
+        .rpc();
+
+      const [saleListing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), saleMint.toBuffer()],
+        marketplace.programId
+      );
+      const [saleEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), saleListing.toBuffer()],
+        marketplace.programId
+      );
+      const saleEscrowToken = await getAssociatedTokenAddress(saleMint, saleEscrow, true);
+
+      await marketplace.methods
+        .createListing(SALE_PRICE, null, null)
+        .accounts({
+          seller: creditSeller.publicKey,
+          nftMint: saleMint,
+          nftMetadata: metadata,
+          listing: saleListing,
+          escrow: saleEscrow,
+          sellerTokenAccount: saleSellerToken,
+          escrowTokenAccount: saleEscrowToken,
+          marketplaceConfig,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          nftProgram: nftProgram.programId,
+        })
+        .signers([creditSeller])
+        .rpc();
+
+      const buyerBefore = await provider.connection.getBalance(bidder1.publicKey);
+
+      await marketplace.methods
+        .buyNow()
+        .accounts({
+          buyer: bidder1.publicKey,
+          seller: creditSeller.publicKey,
+          feeExemption: PublicKey.findProgramAddressSync(
+            [Buffer.from("fee_exemption"), creditSeller.publicKey.toBuffer()],
+            marketplace.programId
+          )[0],
+          listing: saleListing,
+          escrow: saleEscrow,
+          nftMint: saleMint,
+          nftMetadata: metadata,
+          sellerTokenAccount: saleSellerToken,
+          buyerTokenAccount: saleBuyerToken,
+          escrowTokenAccount: saleEscrowToken,
+          marketplaceConfig,
+          feeCollector: feeCollector.publicKey,
+          creator: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          nftProgram: nftProgram.programId,
+        })
+        .remainingAccounts([{ pubkey: storeCredit, isWritable: true, isSigner: false }])
+        .signers([bidder1])
+        .rpc();
+
+      // The credit covered part of the price; the wallet paid only the rest
+      const buyerAfter = await provider.connection.getBalance(bidder1.publicKey);
+      assert.equal(buyerBefore - buyerAfter, SALE_PRICE.sub(BID_PRICE).toNumber());
+
+      const credit = await marketplace.account.storeCredit.fetch(storeCredit);
+      assert.equal(credit.balance.toNumber(), 0);
+
+      const buyerToken = await getAccount(provider.connection, saleBuyerToken);
+      assert.equal(buyerToken.amount.toString(), "1");
+    });
+  });
+
+  describe("Minimum Listing Duration", () => {
+    const LIST_PRICE = new BN(LAMPORTS_PER_SOL);
+    const MIN_LISTING_DURATION = 3; // seconds
+    const durationSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), durationSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    const setMinListingDuration = (seconds: number) =>
+      marketplace.methods
+        .updateMinListingDuration(new BN(seconds))
+        .accounts({
+          marketplaceConfig,
+          admin: platform.publicKey,
+        })
+        .signers([platform])
+        .rpc();
+
+    // Lists a fresh ticket from durationSeller, as an auction if a config is given
+    const listTicket = async (auctionConfig: any = null) => {
+      const mint = await createMint(provider.connection, durationSeller, durationSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, durationSeller, mint, durationSeller.publicKey);
+      await mintTo(provider.connection, durationSeller, mint, sellerTokenAccount, durationSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+
+      await marketplace.methods
+        .createListing(LIST_PRICE, auctionConfig ? { auction: {} } : { fixedPrice: {} }, auctionConfig, null)
+        .accounts({
+          seller: durationSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([durationSeller])
+        .rpc();
+
+      return { mint, listing, escrowTokenAccount, sellerTokenAccount };
+    };
+
+    const listFixedPrice = () => listTicket();
+
+    type ListedTicket = Awaited<ReturnType<typeof listFixedPrice>>;
+
+    const adminCancel = ({ mint, listing, escrowTokenAccount, sellerTokenAccount }: ListedTicket) =>
+      marketplace.methods
+        .adminCancelListing()
+        .accounts({
+          admin: platform.publicKey,
+          marketplaceConfig,
+          listing,
+          escrowTokenAccount,
+          sellerTokenAccount,
+          seller: durationSeller.publicKey,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([platform])
+        .rpc();
+
+    const cancel = ({ mint, listing, escrowTokenAccount, sellerTokenAccount }: ListedTicket) =>
+      marketplace.methods
+        .cancelListing()
+        .accounts({
+          seller: durationSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          escrowTokenAccount,
+          sellerTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([durationSeller])
+        .rpc();
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(durationSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+      await setMinListingDuration(MIN_LISTING_DURATION);
+    });
+
+    after(async () => {
+      await setMinListingDuration(0);
+    });
+
+    it("lets the seller cancel once the minimum duration has passed", async () => {
+      const ticket = await listFixedPrice();
+      await new Promise(resolve => setTimeout(resolve, (MIN_LISTING_DURATION + 1) * 1000));
+
+      await cancel(ticket);
+
+      assert.isFalse((await marketplace.account.listing.fetch(ticket.listing)).isActive);
+      const returned = await getAccount(provider.connection, ticket.sellerTokenAccount);
+      assert.equal(returned.amount.toString(), "1");
+    });
+
+    it("rejects a cancellation before the minimum duration", async () => {
+      const ticket = await listFixedPrice();
+
+      try {
+        await cancel(ticket);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "ListingTooNew");
+      }
+
+      assert.isTrue((await marketplace.account.listing.fetch(ticket.listing)).isActive);
+    });
+
+    it("lets the admin cancel a listing early", async () => {
+      const ticket = await listFixedPrice();
+
+      await adminCancel(ticket);
+
+      assert.isFalse((await marketplace.account.listing.fetch(ticket.listing)).isActive);
+      const returned = await getAccount(provider.connection, ticket.sellerTokenAccount);
+      assert.equal(returned.amount.toString(), "1");
+    });
+
+    it("rejects an admin cancel once the auction has started", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const ticket = await listTicket({
+        startTime: new BN(now + 1),
+        endTime: new BN(now + 600),
+        minBidIncrement: new BN(1),
+        reservePrice: null,
+        openingBid: null,
+        extensionWindow: new BN(0),
+        extensionPeriod: new BN(0),
+        maxExtensions: 0,
+        bidderWhitelist: null,
+        reserveHidden: false,
+        minDistinctBidders: 0,
+        incrementTiers: [],
+      });
+      await new Promise(resolve => setTimeout(resolve, 3000));
+
+      try {
+        await adminCancel(ticket);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "AuctionAlreadyStarted");
+      }
+
+      assert.isTrue((await marketplace.account.listing.fetch(ticket.listing)).isActive);
+    });
+  });
+
+  describe("Bidder Balance Checks", () => {
+    const BID_AMOUNT = new BN(LAMPORTS_PER_SOL);
+    const auctionSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), auctionSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    const createAuction = async () => {
+      const mint = await createMint(provider.connection, auctionSeller, auctionSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, auctionSeller, mint, auctionSeller.publicKey);
+      await mintTo(provider.connection, auctionSeller, mint, sellerTokenAccount, auctionSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          BID_AMOUNT,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + 600),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: auctionSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([auctionSeller])
+        .rpc();
+
+      return { mint, listing };
+    };
+
+    const placeBid = (auction: { mint: PublicKey; listing: PublicKey }, bidder: Keypair) => {
+      const [bidPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.listing.toBuffer(), bidder.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bidPda.toBuffer()],
+        marketplace.programId
+      );
+      return marketplace.methods
+        .placeBid(BID_AMOUNT)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          bid: bidPda,
+          bidEscrow,
+          mint: auction.mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+    };
+
+    // A bidder funded with exactly `lamports`; the provider wallet pays fees
+    const fundedBidder = async (lamports: number) => {
+      const bidder = Keypair.generate();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(bidder.publicKey, lamports)
+      );
+      return bidder;
+    };
+
+    // Rent the bidder pays to open its bid and bid escrow accounts
+    const bidAccountsRent = async () =>
+      (await provider.connection.getMinimumBalanceForRentExemption(marketplace.account.bid.size)) +
+      (await provider.connection.getMinimumBalanceForRentExemption(0));
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(auctionSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+    });
+
+    it("accepts a bid the bidder can cover", async () => {
+      const auction = await createAuction();
+      const bidder = await fundedBidder(2 * LAMPORTS_PER_SOL);
+
+      await placeBid(auction, bidder);
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.equal(listingAccount.highestBid.toString(), BID_AMOUNT.toString());
+      assert.isTrue(listingAccount.highestBidder.equals(bidder.publicKey));
+    });
+
+    it("rejects a bid larger than the bidder's balance", async () => {
+      const auction = await createAuction();
+      const bidder = await fundedBidder(LAMPORTS_PER_SOL / 2);
+
+      try {
+        await placeBid(auction, bidder);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientFunds");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.equal(listingAccount.highestBid.toString(), "0");
+    });
+
+    it("counts the bidder's rent reserve toward the required balance", async () => {
+      const auction = await createAuction();
+      const rentReserve = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+      // Enough for the bid itself, but the escrow would leave the bidder
+      // below its own rent-exempt minimum
+      const bidder = await fundedBidder(
+        BID_AMOUNT.toNumber() + (await bidAccountsRent()) + Math.floor(rentReserve / 2)
+      );
+
+      try {
+        await placeBid(auction, bidder);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientFunds");
+      }
+    });
+  });
+
+  describe("Emergency Auction Cancellation", () => {
+    const OPENING_BID = new BN(LAMPORTS_PER_SOL);
+    const RAISED_BID = new BN(2 * LAMPORTS_PER_SOL);
+    const auctionSeller = Keypair.generate();
+    const REASON = "NFT reported stolen";
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), auctionSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    type Auction = { mint: PublicKey; listing: PublicKey; escrowTokenAccount: PublicKey; sellerTokenAccount: PublicKey };
+
+    const createAuction = async (): Promise<Auction> => {
+      const mint = await createMint(provider.connection, auctionSeller, auctionSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, auctionSeller, mint, auctionSeller.publicKey);
+      await mintTo(provider.connection, auctionSeller, mint, sellerTokenAccount, auctionSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          OPENING_BID,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + 600),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: auctionSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([auctionSeller])
+        .rpc();
+
+      return { mint, listing, escrowTokenAccount, sellerTokenAccount };
+    };
+
+    const bidPdas = (auction: Auction, bidder: PublicKey) => {
+      const [bid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.listing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bid.toBuffer()],
+        marketplace.programId
+      );
+      return { bid, bidEscrow };
+    };
+
+    const placeBid = (auction: Auction, bidder: Keypair, amount: BN) =>
+      marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          ...bidPdas(auction, bidder.publicKey),
+          mint: auction.mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+
+    // Each bidder is passed as their bid, bid escrow and wallet
+    const forceCancel = (auction: Auction, signer: Keypair, bidders: PublicKey[]) =>
+      marketplace.methods
+        .forceCancelAuction(REASON)
+        .accounts({
+          admin: signer.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          sellerTokenAccount: auction.sellerTokenAccount,
+          seller: auctionSeller.publicKey,
+          sellerStats,
+          mint: auction.mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(bidders.flatMap((bidder) => {
+          const { bid, bidEscrow } = bidPdas(auction, bidder);
+          return [
+            { pubkey: bid, isWritable: true, isSigner: false },
+            { pubkey: bidEscrow, isWritable: true, isSigner: false },
+            { pubkey: bidder, isWritable: true, isSigner: false },
+          ];
+        }))
+        .signers([signer])
+        .rpc();
+
+    let auction: Auction;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(auctionSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      auction = await createAuction();
+      await placeBid(auction, bidder1, OPENING_BID);
+      await placeBid(auction, bidder2, RAISED_BID);
+    });
+
+    it("rejects a force-cancel from someone other than the admin", async () => {
+      try {
+        await forceCancel(auction, auctionSeller, [bidder1.publicKey, bidder2.publicKey]);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "ConstraintHasOne");
+      }
+
+      assert.isTrue((await marketplace.account.listing.fetch(auction.listing)).isActive);
+    });
+
+    it("refunds every bidder when the admin force-cancels", async () => {
+      const bidder1Before = await provider.connection.getBalance(bidder1.publicKey);
+      const bidder2Before = await provider.connection.getBalance(bidder2.publicKey);
+
+      let cancelled: { biddersRefunded: number; refundedAmount: string; reason: string } | null = null;
+      const listener = marketplace.addEventListener("AuctionForceCancelled", (event) => {
+        if (event.listing.equals(auction.listing)) {
+          cancelled = {
+            biddersRefunded: event.biddersRefunded,
+            refundedAmount: event.refundedAmount.toString(),
+            reason: event.reason,
+          };
+        }
+      });
+
+      await forceCancel(auction, platform, [bidder1.publicKey, bidder2.publicKey]);
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      const bidder1After = await provider.connection.getBalance(bidder1.publicKey);
+      const bidder2After = await provider.connection.getBalance(bidder2.publicKey);
+      assert.equal(bidder1After - bidder1Before, OPENING_BID.toNumber());
+      assert.equal(bidder2After - bidder2Before, RAISED_BID.toNumber());
+
+      for (const bidder of [bidder1, bidder2]) {
+        const bid = await marketplace.account.bid.fetch(bidPdas(auction, bidder.publicKey).bid);
+        assert.isFalse(bid.isActive);
+      }
+      assert.deepEqual(cancelled, {
+        biddersRefunded: 2,
+        refundedAmount: OPENING_BID.add(RAISED_BID).toString(),
+        reason: REASON,
+      });
+    });
+
+    it("returns the NFT to the seller", async () => {
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.isFalse(listingAccount.isActive);
+
+      const returned = await getAccount(provider.connection, auction.sellerTokenAccount);
+      assert.equal(returned.amount.toString(), "1");
+      const escrow = await getAccount(provider.connection, auction.escrowTokenAccount);
+      assert.equal(escrow.amount.toString(), "0");
+    });
+  });
+
+  describe("Escrow Token Account Validation", () => {
+    const ESCROW_AMOUNT = 1_000_000;
+
+    let marketplaceState: PublicKey;
+    let feeVault: PublicKey;
+    let paymentMint: PublicKey;
+    let vaultTokenAccount: PublicKey;
+    let buyerPaymentToken: PublicKey;
+    let sellerPaymentToken: PublicKey;
+
+    const escrowPdaFor = (transactionId: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), Buffer.from(transactionId)],
+        marketplace.programId
+      )[0];
+
+    // A token account owned by the escrow PDA that isn't its associated token account
+    const nonCanonicalAccount = (escrowPda: PublicKey) =>
+      createAccount(provider.connection, buyer, paymentMint, escrowPda, Keypair.generate());
+
+    const createEscrow = (transactionId: string, escrowTokenAccount: PublicKey) =>
+      marketplace.methods
+        .createEscrow({
+          transactionId,
+          amount: new BN(ESCROW_AMOUNT),
+          expiryTime: new BN(Math.floor(Date.now() / 1000) + 3600),
+          terms: "Tickets delivered on payment",
+          disputePeriod: new BN(3600),
+          platformFeeRate: 250,
+          arbitrationWindow: null,
+          inspectionPeriod: new BN(0),
+          minTimeBeforeDispute: new BN(0),
+          defaultResolution: { favorBuyer: {} },
+          releaseCondition: null,
+          confirmers: [],
+        })
+        .accounts({
+          creator: buyer.publicKey,
+          escrow: escrowPdaFor(transactionId),
+          seller: seller.publicKey,
+          arbitrator: platform.publicKey,
+          creatorTokenAccount: buyerPaymentToken,
+          escrowTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+
+    const releaseFull = (escrowPda: PublicKey, escrowTokenAccount: PublicKey) =>
+      marketplace.methods
+        .releaseEscrow({ releaseType: { full: {} }, partialAmount: null })
+        .accounts({
+          authority: buyer.publicKey,
+          escrow: escrowPda,
+          escrowTokenAccount,
+          sellerTokenAccount: sellerPaymentToken,
+          marketplace: marketplaceState,
+          feeVault,
+          vaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+    before(async () => {
+      [marketplaceState] = PublicKey.findProgramAddressSync(
+        [Buffer.from("marketplace")],
+        marketplace.programId
+      );
+      [feeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_vault"), marketplaceState.toBuffer()],
+        marketplace.programId
+      );
+
+      paymentMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      buyerPaymentToken = await createAssociatedTokenAccount(provider.connection, buyer, paymentMint, buyer.publicKey);
+      sellerPaymentToken = await createAssociatedTokenAccount(provider.connection, seller, paymentMint, seller.publicKey);
+      vaultTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, platform, paymentMint, feeVault, true)
+      ).address;
+
+      await mintTo(provider.connection, platform, paymentMint, buyerPaymentToken, platform, ESCROW_AMOUNT * 10);
+    });
+
+    it("accepts the escrow PDA's associated token account", async () => {
+      const escrowPda = escrowPdaFor("canonical-ata");
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, paymentMint, escrowPda, true)
+      ).address;
+
+      await createEscrow("canonical-ata", escrowToken);
+
+      const funded = await getAccount(provider.connection, escrowToken);
+      assert.equal(Number(funded.amount), ESCROW_AMOUNT);
+
+      await releaseFull(escrowPda, escrowToken);
+      const released = await marketplace.account.escrow.fetch(escrowPda);
+      assert.deepEqual(released.state, { completed: {} });
+    });
+
+    it("rejects funding a non-canonical account owned by the escrow", async () => {
+      const escrowPda = escrowPdaFor("non-canonical-create");
+      const substitute = await nonCanonicalAccount(escrowPda);
+
+      try {
+        await createEscrow("non-canonical-create", substitute);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidEscrowTokenAccount");
+      }
+    });
+
+    it("rejects releasing from a non-canonical account owned by the escrow", async () => {
+      const escrowPda = escrowPdaFor("non-canonical-release");
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, paymentMint, escrowPda, true)
+      ).address;
+      await createEscrow("non-canonical-release", escrowToken);
+
+      const substitute = await nonCanonicalAccount(escrowPda);
+      try {
+        await releaseFull(escrowPda, substitute);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidEscrowTokenAccount");
+      }
+
+      const escrowAccount = await marketplace.account.escrow.fetch(escrowPda);
+      assert.deepEqual(escrowAccount.state, { active: {} });
+    });
+  });
+
+  describe("Royalty Receipts", () => {
+    const LIST_PRICE = new BN(2 * LAMPORTS_PER_SOL);
+    const SEGMENT_CAPACITY = 1;
+    const receiptCreator = Keypair.generate();
+
+    const [receiptIndex] = PublicKey.findProgramAddressSync(
+      [Buffer.from("royalty_receipt_index"), receiptCreator.publicKey.toBuffer()],
+      marketplace.programId
+    );
+    const segmentPda = (segment: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("royalty_receipts"),
+          receiptCreator.publicKey.toBuffer(),
+          new BN(segment).toArrayLike(Buffer, "le", 4),
+        ],
+        marketplace.programId
+      )[0];
+
+    // Lists a ticket paying royalties to the receipt creator and sells it through an accepted offer
+    const sellThroughOffer = async () => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerTokenAccount, seller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+
+      await marketplace.methods
+        .createListing(LIST_PRICE, { fixedPrice: {} }, null, { creator: receiptCreator.publicKey, percentageBps: ROYALTY_BPS, royaltyPayoutMint: null, minRoyalty: new BN(0) })
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      await marketplace.methods
+        .setAllowOffers(true)
+        .accounts({ seller: seller.publicKey, listing })
+        .signers([seller])
+        .rpc();
+
+      const [offer] = PublicKey.findProgramAddressSync(
+        [Buffer.from("offer"), listing.toBuffer(), buyer.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      await marketplace.methods
+        .makeOffer(LIST_PRICE)
+        .accounts({
+          buyer: buyer.publicKey,
+          marketplaceConfig,
+          listing,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      await marketplace.methods
+        .acceptOffer()
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerStats: PublicKey.findProgramAddressSync(
+            [Buffer.from("seller_stats"), seller.publicKey.toBuffer()],
+            marketplace.programId
+          )[0],
+          offer,
+          buyer: buyer.publicKey,
+          escrowTokenAccount,
+          buyerTokenAccount: await getAssociatedTokenAddress(mint, buyer.publicKey),
+          mint,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: receiptCreator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(receiptCreator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      return listing;
+    };
+
+    const expectedRoyalty = (LIST_PRICE.toNumber() * ROYALTY_BPS) / 10000;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(receiptCreator.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+    });
+
+    it("rejects a segment capacity outside the allowed range", async () => {
+      try {
+        await marketplace.methods
+          .enableRoyaltyReceipts(0)
+          .accounts({
+            royaltyReceiptIndex: receiptIndex,
+            royaltyReceiptSegment: segmentPda(0),
+            creator: receiptCreator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([receiptCreator])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidReceiptSegmentCapacity");
+      }
+    });
+
+    it("appends a receipt when a royalty is paid", async () => {
+      await marketplace.methods
+        .enableRoyaltyReceipts(SEGMENT_CAPACITY)
+        .accounts({
+          royaltyReceiptIndex: receiptIndex,
+          royaltyReceiptSegment: segmentPda(0),
+          creator: receiptCreator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([receiptCreator])
+        .rpc();
+
+      const recorded: { segment: number; position: number; receiptNumber: number }[] = [];
+      const listener = marketplace.addEventListener("RoyaltyReceiptRecorded", (event) => {
+        recorded.push({
+          segment: event.segment,
+          position: event.position,
+          receiptNumber: event.receiptNumber.toNumber(),
+        });
+      });
+
+      const listing = await sellThroughOffer();
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      const segment = await marketplace.account.royaltyReceiptSegment.fetch(segmentPda(0));
+      assert.equal(segment.receipts.length, 1);
+      assert.equal(segment.receipts[0].sale.toBase58(), listing.toBase58());
+      assert.equal(segment.receipts[0].amount.toNumber(), expectedRoyalty);
+      assert.equal(segment.receipts[0].payer.toBase58(), buyer.publicKey.toBase58());
+      assert.deepEqual(recorded, [{ segment: 0, position: 0, receiptNumber: 0 }]);
+    });
+
+    it("rotates to a new segment once the current one fills", async () => {
+      // The single-receipt first segment is already full
+      let index = await marketplace.account.royaltyReceiptIndex.fetch(receiptIndex);
+      assert.equal(index.currentSegment, 1);
+
+      const listing = await sellThroughOffer();
+
+      const firstSegment = await marketplace.account.royaltyReceiptSegment.fetch(segmentPda(0));
+      assert.equal(firstSegment.receipts.length, 1);
+
+      const secondSegment = await marketplace.account.royaltyReceiptSegment.fetch(segmentPda(1));
+      assert.equal(secondSegment.segment, 1);
+      assert.equal(secondSegment.receipts.length, 1);
+      assert.equal(secondSegment.receipts[0].sale.toBase58(), listing.toBase58());
+
+      index = await marketplace.account.royaltyReceiptIndex.fetch(receiptIndex);
+      assert.equal(index.totalReceipts.toNumber(), 2);
+      assert.equal(index.currentSegment, 2);
+    });
+  });
+
+  describe("Minimum Royalty", () => {
+    const LIST_PRICE = new BN(2 * LAMPORTS_PER_SOL);
+    const MIN_ROYALTY = new BN(500);
+
+    // Lists a ticket with a royalty floor and sells it through an accepted offer, returning the royalty paid
+    const sellWithMinRoyalty = async (offerAmount: BN, minRoyalty: BN) => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerTokenAccount, seller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+
+      await marketplace.methods
+        .createListing(LIST_PRICE, { fixedPrice: {} }, null, { creator: creator.publicKey, percentageBps: ROYALTY_BPS, royaltyPayoutMint: null, minRoyalty })
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.royaltyConfig.minRoyalty.toString(), minRoyalty.toString());
+
+      await marketplace.methods
+        .setAllowOffers(true)
+        .accounts({ seller: seller.publicKey, listing })
+        .signers([seller])
+        .rpc();
+
+      const [offer] = PublicKey.findProgramAddressSync(
+        [Buffer.from("offer"), listing.toBuffer(), buyer.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      await marketplace.methods
+        .makeOffer(offerAmount)
+        .accounts({
+          buyer: buyer.publicKey,
+          marketplaceConfig,
+          listing,
+          offer,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const creatorBefore = await provider.connection.getBalance(creator.publicKey);
+
+      await marketplace.methods
+        .acceptOffer()
+        .accounts({
+          seller: seller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerStats: PublicKey.findProgramAddressSync(
+            [Buffer.from("seller_stats"), seller.publicKey.toBuffer()],
+            marketplace.programId
+          )[0],
+          offer,
+          buyer: buyer.publicKey,
+          escrowTokenAccount,
+          buyerTokenAccount: await getAssociatedTokenAddress(mint, buyer.publicKey),
+          mint,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      return (await provider.connection.getBalance(creator.publicKey)) - creatorBefore;
+    };
+
+    const platformFeeOn = async (amount: BN) => {
+      const config = await marketplace.account.marketplaceConfig.fetch(marketplaceConfig);
+      return Math.floor((amount.toNumber() * config.platformFeeBps) / 10000);
+    };
+
+    it("raises a low-price royalty to the floor", async () => {
+      const offerAmount = new BN(2000);
+      // 5% of the price is well under the floor
+      assert.isBelow((offerAmount.toNumber() * ROYALTY_BPS) / 10000, MIN_ROYALTY.toNumber());
+
+      const royalty = await sellWithMinRoyalty(offerAmount, MIN_ROYALTY);
+      assert.equal(royalty, MIN_ROYALTY.toNumber());
+    });
+
+    it("pays the percentage royalty when it exceeds the floor", async () => {
+      const offerAmount = new BN(LAMPORTS_PER_SOL);
+
+      const royalty = await sellWithMinRoyalty(offerAmount, MIN_ROYALTY);
+      assert.equal(royalty, (offerAmount.toNumber() * ROYALTY_BPS) / 10000);
+    });
+
+    it("caps the floor at what is left of the sale price", async () => {
+      const offerAmount = new BN(1000);
+
+      const royalty = await sellWithMinRoyalty(offerAmount, new BN(10000));
+      assert.equal(royalty, offerAmount.toNumber() - (await platformFeeOn(offerAmount)));
+    });
+  });
+
+  describe("Hidden Reserve", () => {
+    const AUCTION_DURATION = 5; // seconds
+    const OPENING_BID = new BN(LAMPORTS_PER_SOL / 10);
+    const RESERVE = new BN(LAMPORTS_PER_SOL / 2);
+    const reserveSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), reserveSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    type Auction = { mint: PublicKey; listing: PublicKey; escrowTokenAccount: PublicKey; announcedReserve: BN | null };
+
+    // Creates an auction with a reserve, capturing the reserve ListingCreated announced
+    const createAuction = async (reserveHidden: boolean): Promise<Auction> => {
+      const mint = await createMint(provider.connection, reserveSeller, reserveSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, reserveSeller, mint, reserveSeller.publicKey);
+      await mintTo(provider.connection, reserveSeller, mint, sellerTokenAccount, reserveSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      let announcedReserve: BN | null | undefined;
+      const listener = marketplace.addEventListener("ListingCreated", (event) => {
+        if (event.listing.equals(listing)) {
+          announcedReserve = event.reservePrice;
+        }
+      });
+
+      await marketplace.methods
+        .createListing(
+          OPENING_BID,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: RESERVE,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: reserveSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([reserveSeller])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+      assert.notStrictEqual(announcedReserve, undefined, "ListingCreated was not emitted");
+
+      return { mint, listing, escrowTokenAccount, announcedReserve };
+    };
+
+    const bidPdas = (auction: Auction, bidder: PublicKey) => {
+      const [bid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.listing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bid.toBuffer()],
+        marketplace.programId
+      );
+      return { bid, bidEscrow };
+    };
+
+    const placeBid = (auction: Auction, bidder: Keypair, amount: BN) =>
+      marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          ...bidPdas(auction, bidder.publicKey),
+          mint: auction.mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+
+    const endAuction = async (auction: Auction, winner: Keypair) => {
+      const { bid, bidEscrow } = bidPdas(auction, winner.publicKey);
+      await marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: winner.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          winnerTokenAccount: await getAssociatedTokenAddress(auction.mint, winner.publicKey),
+          sellerTokenAccount: await getAssociatedTokenAddress(auction.mint, reserveSeller.publicKey),
+          winningBid: bid,
+          bidEscrow,
+          mint: auction.mint,
+          winner: winner.publicKey,
+          seller: reserveSeller.publicKey,
+          sellerStats,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([winner])
+        .rpc();
+    };
+
+    let hiddenBelowReserve: Auction;
+    let hiddenMeetingReserve: Auction;
+    let publicReserve: Auction;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(reserveSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      hiddenBelowReserve = await createAuction(true);
+      hiddenMeetingReserve = await createAuction(true);
+      publicReserve = await createAuction(false);
+
+      await placeBid(hiddenBelowReserve, bidder1, OPENING_BID);
+      await placeBid(hiddenMeetingReserve, bidder1, RESERVE);
+    });
+
+    it("omits a hidden reserve from ListingCreated", async () => {
+      assert.isNull(hiddenBelowReserve.announcedReserve);
+      assert.isNull(hiddenMeetingReserve.announcedReserve);
+
+      // The reserve is still stored on the listing for enforcement
+      const listing = await marketplace.account.listing.fetch(hiddenBelowReserve.listing);
+      assert.equal(listing.auctionConfig.reservePrice.toString(), RESERVE.toString());
+      assert.isTrue(listing.auctionConfig.reserveHidden);
+    });
+
+    it("includes a public reserve in ListingCreated", async () => {
+      assert.equal(publicReserve.announcedReserve.toString(), RESERVE.toString());
+    });
+
+    it("still enforces a hidden reserve at settlement", async () => {
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      try {
+        await endAuction(hiddenBelowReserve, bidder1);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "ReservePriceNotMet");
+      }
+    });
+
+    it("reveals a hidden reserve when the auction ends", async () => {
+      let revealed: BN | null = null;
+      const listener = marketplace.addEventListener("AuctionEnded", (event) => {
+        if (event.listing.equals(hiddenMeetingReserve.listing)) {
+          revealed = event.reservePrice;
+        }
+      });
+
+      await endAuction(hiddenMeetingReserve, bidder1);
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      assert.equal(revealed.toString(), RESERVE.toString());
+    });
+  });
+
+  describe("Winner Claim Window", () => {
+    const AUCTION_DURATION = 3; // seconds
+    const CLAIM_WINDOW = 3; // seconds
+    const PRICE = new BN(LAMPORTS_PER_SOL / 10);
+    const claimSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), claimSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    type Auction = { mint: PublicKey; listing: PublicKey; escrowTokenAccount: PublicKey };
+
+    const setClaimWindow = (seconds: number) =>
+      marketplace.methods
+        .updateWinnerClaimWindow(new BN(seconds))
+        .accounts({ marketplaceConfig, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+
+    const createAuction = async (): Promise<Auction> => {
+      const mint = await createMint(provider.connection, claimSeller, claimSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, claimSeller, mint, claimSeller.publicKey);
+      await mintTo(provider.connection, claimSeller, mint, sellerTokenAccount, claimSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: claimSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([claimSeller])
+        .rpc();
+
+      return { mint, listing, escrowTokenAccount };
+    };
+
+    const bidPdas = (auction: Auction, bidder: PublicKey) => {
+      const [bid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.listing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bid.toBuffer()],
+        marketplace.programId
+      );
+      return { bid, bidEscrow };
+    };
+
+    // Lists a fresh auction, wins it with `winner` and settles it once it ends
+    const settledAuction = async (winner: Keypair): Promise<Auction> => {
+      const auction = await createAuction();
+      const { bid, bidEscrow } = bidPdas(auction, winner.publicKey);
+
+      await marketplace.methods
+        .placeBid(PRICE)
+        .accounts({
+          bidder: winner.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          bid,
+          bidEscrow,
+          mint: auction.mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([winner])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+      await marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: platform.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          winnerTokenAccount: await getAssociatedTokenAddress(auction.mint, winner.publicKey),
+          sellerTokenAccount: await getAssociatedTokenAddress(auction.mint, claimSeller.publicKey),
+          winningBid: bid,
+          bidEscrow,
+          mint: auction.mint,
+          winner: winner.publicKey,
+          seller: claimSeller.publicKey,
+          sellerStats,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([platform])
+        .rpc();
+
+      return auction;
+    };
+
+    const claimNft = async (auction: Auction, winner: Keypair) =>
+      marketplace.methods
+        .claimNft()
+        .accounts({
+          winner: winner.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          winnerTokenAccount: await getAssociatedTokenAddress(auction.mint, winner.publicKey),
+          winningBid: bidPdas(auction, winner.publicKey).bid,
+          bidEscrow: bidPdas(auction, winner.publicKey).bidEscrow,
+          mint: auction.mint,
+          seller: claimSeller.publicKey,
+          sellerStats,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([winner])
+        .rpc();
+
+    const revertUnclaimed = async (auction: Auction, winner: PublicKey) =>
+      marketplace.methods
+        .revertUnclaimedAuction()
+        .accounts({
+          caller: platform.publicKey,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          sellerTokenAccount: await getAssociatedTokenAddress(auction.mint, claimSeller.publicKey),
+          winningBid: bidPdas(auction, winner).bid,
+          bidEscrow: bidPdas(auction, winner).bidEscrow,
+          mint: auction.mint,
+          winner,
+          seller: claimSeller.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([platform])
+        .rpc();
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(claimSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+      await setClaimWindow(CLAIM_WINDOW);
+    });
+
+    after(async () => {
+      await setClaimWindow(0);
+    });
+
+    it("rejects a negative claim window", async () => {
+      try {
+        await setClaimWindow(-1);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidClaimWindow");
+      }
+    });
+
+    it("locks the sale price at settlement and pays it out on a timely claim", async () => {
+      const sellerBefore = await provider.connection.getBalance(claimSeller.publicKey);
+      const auction = await settledAuction(bidder1);
+      const volumeBefore = (await marketplace.account.sellerStats.fetch(sellerStats)).totalVolume;
+
+      // Nothing is delivered or paid at settlement: the NFT and the whole price stay in escrow
+      const listingAccount = await marketplace.account.listing.fetch(auction.listing);
+      assert.isNotNull(listingAccount.claimDeadline);
+      assert.isTrue(listingAccount.lockedProceeds.gtn(0));
+      assert.isTrue(listingAccount.lockedPlatformFee.gtn(0));
+      assert.equal(
+        listingAccount.lockedProceeds.add(listingAccount.lockedPlatformFee).add(listingAccount.lockedRoyalty).toString(),
+        PRICE.toString()
+      );
+      assert.equal(await provider.connection.getBalance(claimSeller.publicKey), sellerBefore);
+      const escrowToken = await getAccount(provider.connection, auction.escrowTokenAccount);
+      assert.equal(Number(escrowToken.amount), 1);
+
+      const platformBefore = await provider.connection.getBalance(platform.publicKey);
+      await claimNft(auction, bidder1);
+
+      assert.equal(
+        await provider.connection.getBalance(platform.publicKey),
+        platformBefore + listingAccount.lockedPlatformFee.toNumber()
+      );
+      const stats = await marketplace.account.sellerStats.fetch(sellerStats);
+      assert.equal(stats.totalVolume.toString(), volumeBefore.add(PRICE).toString());
+
+      const winnerToken = await getAccount(
+        provider.connection,
+        await getAssociatedTokenAddress(auction.mint, bidder1.publicKey)
+      );
+      assert.equal(Number(winnerToken.amount), 1);
+      assert.equal(
+        await provider.connection.getBalance(claimSeller.publicKey),
+        sellerBefore + listingAccount.lockedProceeds.toNumber()
+      );
+
+      const claimed = await marketplace.account.listing.fetch(auction.listing);
+      assert.isNull(claimed.claimDeadline);
+      assert.equal(claimed.lockedProceeds.toNumber(), 0);
+      assert.equal(claimed.lockedPlatformFee.toNumber(), 0);
+    });
+
+    it("reverts an unclaimed sale once the window has passed", async () => {
+      const auction = await settledAuction(bidder2);
+      const { lockedProceeds, lockedPlatformFee, lockedRoyalty } = await marketplace.account.listing.fetch(auction.listing);
+      const volumeBefore = (await marketplace.account.sellerStats.fetch(sellerStats)).totalVolume;
+
+      try {
+        await revertUnclaimed(auction, bidder2.publicKey);
+        assert.fail("Should not revert while the winner can still claim");
+      } catch (err) {
+        assert.include(err.toString(), "ClaimWindowActive");
+      }
+
+      await new Promise(resolve => setTimeout(resolve, (CLAIM_WINDOW + 1) * 1000));
+
+      try {
+        await claimNft(auction, bidder2);
+        assert.fail("Should not claim after the window");
+      } catch (err) {
+        assert.include(err.toString(), "ClaimWindowExpired");
+      }
+
+      const winnerBefore = await provider.connection.getBalance(bidder2.publicKey);
+      const sellerBefore = await provider.connection.getBalance(claimSeller.publicKey);
+      await revertUnclaimed(auction, bidder2.publicKey);
+
+      // The NFT goes back to the seller and the whole price, fees included, back to the winner
+      const sellerToken = await getAccount(
+        provider.connection,
+        await getAssociatedTokenAddress(auction.mint, claimSeller.publicKey)
+      );
+      assert.equal(Number(sellerToken.amount), 1);
+      assert.equal(
+        await provider.connection.getBalance(bidder2.publicKey),
+        winnerBefore + lockedProceeds.add(lockedPlatformFee).add(lockedRoyalty).toNumber()
+      );
+      assert.equal(await provider.connection.getBalance(claimSeller.publicKey), sellerBefore);
+
+      // A sale that never completed does not count toward the seller's volume
+      const stats = await marketplace.account.sellerStats.fetch(sellerStats);
+      assert.equal(stats.totalVolume.toString(), volumeBefore.toString());
+
+      const reverted = await marketplace.account.listing.fetch(auction.listing);
+      assert.isNull(reverted.claimDeadline);
+    });
+  });
+
+  describe("Per-Mint Auction Cap", () => {
+    const MAX_AUCTIONS_PER_MINT = 2;
+    const PRICE = new BN(LAMPORTS_PER_SOL);
+    const RELIST_PRICE = new BN(LAMPORTS_PER_SOL / 2);
+    const AUCTION_DURATION = 3; // seconds
+    const capSeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), capSeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    let mint: PublicKey;
+    let listing: PublicKey;
+    let mintStats: PublicKey;
+
+    const setMaxAuctionsPerMint = (max: number) =>
+      marketplace.methods
+        .updateMaxAuctionsPerMint(max)
+        .accounts({ marketplaceConfig, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+
+    const auctionCount = async () =>
+      (await marketplace.account.mintStats.fetch(mintStats)).auctionCount;
+
+    const waitForAuctionEnd = () =>
+      new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+
+    // Each relist of an unsold auction runs the NFT through another auction
+    const relist = () =>
+      marketplace.methods
+        .relistAuction()
+        .accounts({
+          caller: bidder2.publicKey,
+          listing,
+          marketplaceConfig,
+          mintStats,
+          sellerVerification: PublicKey.findProgramAddressSync(
+            [Buffer.from("seller_verification"), capSeller.publicKey.toBuffer()],
+            marketplace.programId
+          )[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder2])
+        .rpc();
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(capSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+      await setMaxAuctionsPerMint(MAX_AUCTIONS_PER_MINT);
+
+      mint = await createMint(provider.connection, capSeller, capSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, capSeller, mint, capSeller.publicKey);
+      await mintTo(provider.connection, capSeller, mint, sellerTokenAccount, capSeller, 1);
+
+      [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      [mintStats] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_stats"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: capSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mintStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([capSeller])
+        .rpc();
+
+      await marketplace.methods
+        .setRelistConfig({
+          price: RELIST_PRICE,
+          duration: new BN(AUCTION_DURATION),
+          maxRelists: 3,
+        })
+        .accounts({ seller: capSeller.publicKey, listing })
+        .signers([capSeller])
+        .rpc();
+    });
+
+    after(async () => {
+      await setMaxAuctionsPerMint(0);
+    });
+
+    it("allows an NFT to be auctioned up to the cap", async () => {
+      const config = await marketplace.account.marketplaceConfig.fetch(marketplaceConfig);
+      assert.equal(config.maxAuctionsPerMint, MAX_AUCTIONS_PER_MINT);
+
+      const stats = await marketplace.account.mintStats.fetch(mintStats);
+      assert.equal(stats.mint.toBase58(), mint.toBase58());
+      assert.equal(stats.auctionCount, 1);
+
+      await waitForAuctionEnd();
+      await relist();
+      assert.equal(await auctionCount(), MAX_AUCTIONS_PER_MINT);
+    });
+
+    it("rejects an auction over the cap", async () => {
+      await waitForAuctionEnd();
+      try {
+        await relist();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "MintAuctionLimitReached");
+      }
+
+      assert.equal(await auctionCount(), MAX_AUCTIONS_PER_MINT);
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.relistsApplied, 1);
+    });
+
+    it("only lets the admin reset the count", async () => {
+      try {
+        await marketplace.methods
+          .resetMintAuctionCount()
+          .accounts({ marketplaceConfig, mintStats, admin: capSeller.publicKey })
+          .signers([capSeller])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "ConstraintHasOne");
+      }
+      assert.equal(await auctionCount(), MAX_AUCTIONS_PER_MINT);
+    });
+
+    it("allows more auctions after an admin reset", async () => {
+      await marketplace.methods
+        .resetMintAuctionCount()
+        .accounts({ marketplaceConfig, mintStats, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+      assert.equal(await auctionCount(), 0);
+
+      await relist();
+      assert.equal(await auctionCount(), 1);
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.equal(listingAccount.relistsApplied, 2);
+    });
+  });
+
+  describe("Verified Sellers For High-Value Listings", () => {
+    const THRESHOLD = new BN(5 * LAMPORTS_PER_SOL);
+    const verifiedSeller = Keypair.generate();
+    const unverifiedSeller = Keypair.generate();
+
+    const sellerVerificationFor = (sellerKey: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("seller_verification"), sellerKey.toBuffer()],
+        marketplace.programId
+      )[0];
+
+    const setThreshold = (threshold: BN) =>
+      marketplace.methods
+        .updateVerifiedSellerThreshold(threshold)
+        .accounts({ marketplaceConfig, admin: platform.publicKey })
+        .signers([platform])
+        .rpc();
+
+    // Lists a fresh NFT from `listingSeller` at `price`, as an auction if a config is given
+    const listFrom = async (listingSeller: Keypair, price: BN, auctionConfig: any = null) => {
+      const mint = await createMint(provider.connection, listingSeller, listingSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, listingSeller, mint, listingSeller.publicKey);
+      await mintTo(provider.connection, listingSeller, mint, sellerTokenAccount, listingSeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+
+      await marketplace.methods
+        .createListing(price, auctionConfig ? { auction: {} } : { fixedPrice: {} }, auctionConfig, null)
+        .accounts({
+          seller: listingSeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerVerification: sellerVerificationFor(listingSeller.publicKey),
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([listingSeller])
+        .rpc();
+
+      return listing;
+    };
+
+    before(async () => {
+      for (const account of [verifiedSeller, unverifiedSeller]) {
+        await provider.connection.confirmTransaction(
+          await provider.connection.requestAirdrop(account.publicKey, 10 * LAMPORTS_PER_SOL)
+        );
+      }
+
+      await marketplace.methods
+        .addSellerVerification(verifiedSeller.publicKey)
+        .accounts({
+          marketplaceConfig,
+          sellerVerification: sellerVerificationFor(verifiedSeller.publicKey),
+          admin: platform.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([platform])
+        .rpc();
+
+      await setThreshold(THRESHOLD);
+    });
+
+    after(async () => {
+      await setThreshold(new BN(0));
+    });
+
+    it("lets a verified seller list a high-value item", async () => {
+      const verification = await marketplace.account.sellerVerification.fetch(
+        sellerVerificationFor(verifiedSeller.publicKey)
+      );
+      assert.equal(verification.seller.toBase58(), verifiedSeller.publicKey.toBase58());
+
+      const listing = await listFrom(verifiedSeller, THRESHOLD);
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.isTrue(listingAccount.isActive);
+      assert.equal(listingAccount.price.toString(), THRESHOLD.toString());
+    });
+
+    it("rejects a high-value listing from an unverified seller", async () => {
+      try {
+        await listFrom(unverifiedSeller, THRESHOLD);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "SellerVerificationRequired");
+      }
+    });
+
+    it("lets an unverified seller list below the threshold", async () => {
+      const listing = await listFrom(unverifiedSeller, THRESHOLD.subn(1));
+
+      const listingAccount = await marketplace.account.listing.fetch(listing);
+      assert.isTrue(listingAccount.isActive);
+    });
+
+    it("values an auction at its reserve, not just its starting price", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      try {
+        await listFrom(unverifiedSeller, new BN(LAMPORTS_PER_SOL), {
+          startTime: new BN(now + 60),
+          endTime: new BN(now + 3600),
+          minBidIncrement: new BN(1),
+          reservePrice: THRESHOLD,
+          openingBid: null,
+          extensionWindow: new BN(0),
+          extensionPeriod: new BN(0),
+          maxExtensions: 0,
+          bidderWhitelist: null,
+          reserveHidden: false,
+          minDistinctBidders: 0,
+          incrementTiers: [],
+        });
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "SellerVerificationRequired");
+      }
+    });
+  });
+
+  describe("Minimum Distinct Bidders", () => {
+    const AUCTION_DURATION = 3; // seconds
+    const PRICE = new BN(LAMPORTS_PER_SOL / 10);
+    const diversitySeller = Keypair.generate();
+
+    const [sellerStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_stats"), diversitySeller.publicKey.toBuffer()],
+      marketplace.programId
+    );
+
+    type Auction = { mint: PublicKey; listing: PublicKey; escrowTokenAccount: PublicKey; sellerTokenAccount: PublicKey };
+
+    const createAuction = async (minDistinctBidders: number): Promise<Auction> => {
+      const mint = await createMint(provider.connection, diversitySeller, diversitySeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, diversitySeller, mint, diversitySeller.publicKey);
+      await mintTo(provider.connection, diversitySeller, mint, sellerTokenAccount, diversitySeller, 1);
+
+      const [listing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const escrowTokenAccount = await getAssociatedTokenAddress(mint, listing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: diversitySeller.publicKey,
+          marketplaceConfig,
+          listing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([diversitySeller])
+        .rpc();
+
+      return { mint, listing, escrowTokenAccount, sellerTokenAccount };
+    };
+
+    const bidPdas = (auction: Auction, bidder: PublicKey) => {
+      const [bid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auction.listing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bid.toBuffer()],
+        marketplace.programId
+      );
+      return { bid, bidEscrow };
+    };
+
+    const placeBid = (auction: Auction, bidder: Keypair, amount: BN) =>
+      marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          ...bidPdas(auction, bidder.publicKey),
+          mint: auction.mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+
+    const endAuction = async (auction: Auction, winner: Keypair) => {
+      const { bid, bidEscrow } = bidPdas(auction, winner.publicKey);
+      await marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: buyer.publicKey,
+          marketplaceConfig,
+          listing: auction.listing,
+          escrowTokenAccount: auction.escrowTokenAccount,
+          winnerTokenAccount: await getAssociatedTokenAddress(auction.mint, winner.publicKey),
+          sellerTokenAccount: auction.sellerTokenAccount,
+          winningBid: bid,
+          bidEscrow,
+          mint: auction.mint,
+          winner: winner.publicKey,
+          seller: diversitySeller.publicKey,
+          sellerStats,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+    };
+
+    let contested: Auction;
+    let singleBidder: Auction;
+    let unrestricted: Auction;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(diversitySeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      contested = await createAuction(2);
+      singleBidder = await createAuction(2);
+      unrestricted = await createAuction(0);
+
+      await placeBid(contested, bidder1, PRICE);
+      await placeBid(contested, bidder2, PRICE.muln(2));
+      await placeBid(singleBidder, bidder1, PRICE);
+      await placeBid(unrestricted, bidder1, PRICE);
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+    });
+
+    it("refuses to settle to a bidder who was outbid", async () => {
+      try {
+        await endAuction(contested, bidder1);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "NotHighestBidder");
+      }
+
+      const listingAccount = await marketplace.account.listing.fetch(contested.listing);
+      assert.isTrue(listingAccount.isActive);
+    });
+
+    it("settles normally with enough distinct bidders", async () => {
+      await endAuction(contested, bidder2);
+
+      const winnerToken = await getAccount(
+        provider.connection,
+        await getAssociatedTokenAddress(contested.mint, bidder2.publicKey)
+      );
+      assert.equal(Number(winnerToken.amount), 1);
+      const listingAccount = await marketplace.account.listing.fetch(contested.listing);
+      assert.isFalse(listingAccount.isActive);
+    });
+
+    it("treats too few distinct bidders as a no-sale and refunds the winner", async () => {
+      const { bid, bidEscrow } = bidPdas(singleBidder, bidder1.publicKey);
+      const escrowed = await provider.connection.getBalance(bidEscrow);
+      const bidderBefore = await provider.connection.getBalance(bidder1.publicKey);
+
+      let noSale: { uniqueBidders: number; minDistinctBidders: number; refundedAmount: BN } | null = null;
+      const listener = marketplace.addEventListener("AuctionNoSale", (event) => {
+        if (event.listing.equals(singleBidder.listing)) {
+          noSale = event;
+        }
+      });
+
+      await endAuction(singleBidder, bidder1);
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      assert.equal(noSale.uniqueBidders, 1);
+      assert.equal(noSale.minDistinctBidders, 2);
+      assert.equal(noSale.refundedAmount.toString(), PRICE.toString());
+
+      // The NFT goes back to the seller and the bid escrow returns to the winner
+      const sellerToken = await getAccount(provider.connection, singleBidder.sellerTokenAccount);
+      assert.equal(Number(sellerToken.amount), 1);
+      assert.equal(
+        await provider.connection.getBalance(bidder1.publicKey),
+        bidderBefore + PRICE.toNumber()
+      );
+      assert.equal(await provider.connection.getBalance(bidEscrow), escrowed - PRICE.toNumber());
+
+      const listingAccount = await marketplace.account.listing.fetch(singleBidder.listing);
+      assert.isFalse(listingAccount.isActive);
+      assert.isFalse((await marketplace.account.bid.fetch(bid)).isActive);
+    });
+
+    it("settles a single-bidder auction when no minimum is set", async () => {
+      await endAuction(unrestricted, bidder1);
+
+      const winnerToken = await getAccount(
+        provider.connection,
+        await getAssociatedTokenAddress(unrestricted.mint, bidder1.publicKey)
+      );
+      assert.equal(Number(winnerToken.amount), 1);
+    });
+  });
+
+  describe("Bid Increment Tiers", () => {
+    const PRICE = new BN(LAMPORTS_PER_SOL / 10);
+    const FLAT_INCREMENT = PRICE.divn(10);
+    const TIER_PRICE = PRICE.muln(2);
+    const TIER_INCREMENT = PRICE.divn(2);
+    const tieredSeller = Keypair.generate();
+
+    let mint: PublicKey;
+    let auctionListing: PublicKey;
+
+    const bidPdas = (bidder: PublicKey) => {
+      const [bid] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid"), auctionListing.toBuffer(), bidder.toBuffer()],
+        marketplace.programId
+      );
+      const [bidEscrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), bid.toBuffer()],
+        marketplace.programId
+      );
+      return { bid, bidEscrow };
+    };
+
+    const placeBid = (bidder: Keypair, amount: BN) =>
+      marketplace.methods
+        .placeBid(amount)
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplaceConfig,
+          listing: auctionListing,
+          ...bidPdas(bidder.publicKey),
+          mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+
+    const expectBidTooLow = async (bidder: Keypair, amount: BN) => {
+      try {
+        await placeBid(bidder, amount);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "BidTooLow");
+      }
+    };
+
+    const highestBid = async () =>
+      (await marketplace.account.listing.fetch(auctionListing)).highestBid.toString();
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(tieredSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      mint = await createMint(provider.connection, tieredSeller, tieredSeller.publicKey, null, 0);
+      const sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, tieredSeller, mint, tieredSeller.publicKey);
+      await mintTo(provider.connection, tieredSeller, mint, sellerTokenAccount, tieredSeller, 1);
+
+      [auctionListing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      const [sellerStats] = PublicKey.findProgramAddressSync(
+        [Buffer.from("seller_stats"), tieredSeller.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      const now = Math.floor(Date.now() / 1000);
+
+      // Bids under TIER_PRICE need the flat increment, bids from it a larger one
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + 600),
+            minBidIncrement: FLAT_INCREMENT,
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [{ minPrice: TIER_PRICE, minIncrement: TIER_INCREMENT }],
+          },
+          null
+        )
+        .accounts({
+          seller: tieredSeller.publicKey,
+          marketplaceConfig,
+          listing: auctionListing,
+          sellerTokenAccount,
+          escrowTokenAccount: await getAssociatedTokenAddress(mint, auctionListing, true),
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([tieredSeller])
+        .rpc();
+    });
+
+    it("applies the flat increment in the low band", async () => {
+      await placeBid(bidder1, PRICE);
+
+      await expectBidTooLow(bidder2, PRICE.add(FLAT_INCREMENT).subn(1));
+      await placeBid(bidder2, PRICE.add(FLAT_INCREMENT));
+      assert.equal(await highestBid(), PRICE.add(FLAT_INCREMENT).toString());
+    });
+
+    it("switches to the tier increment once the standing bid reaches the tier", async () => {
+      // Topping a bid just under the tier still only needs the flat increment
+      await placeBid(bidder1, TIER_PRICE);
+      assert.equal(await highestBid(), TIER_PRICE.toString());
+
+      // From the tier's price the flat increment is no longer enough
+      await expectBidTooLow(bidder2, TIER_PRICE.add(FLAT_INCREMENT));
+      await placeBid(bidder2, TIER_PRICE.add(TIER_INCREMENT));
+      assert.equal(await highestBid(), TIER_PRICE.add(TIER_INCREMENT).toString());
+    });
+
+    it("applies the tier increment in the high band", async () => {
+      const standing = TIER_PRICE.add(TIER_INCREMENT);
+
+      await expectBidTooLow(bidder1, standing.add(TIER_INCREMENT).subn(1));
+      await placeBid(bidder1, standing.add(TIER_INCREMENT));
+      assert.equal(await highestBid(), standing.add(TIER_INCREMENT).toString());
+    });
+  });
+
+  describe("No-Bid Auction Settlement", () => {
+    const AUCTION_DURATION = 3; // seconds
+    const PRICE = new BN(LAMPORTS_PER_SOL / 10);
+    const unbidSeller = Keypair.generate();
+
+    let mint: PublicKey;
+    let auctionListing: PublicKey;
+    let sellerTokenAccount: PublicKey;
+    let escrowTokenAccount: PublicKey;
+    let sellerStats: PublicKey;
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(unbidSeller.publicKey, 10 * LAMPORTS_PER_SOL)
+      );
+
+      mint = await createMint(provider.connection, unbidSeller, unbidSeller.publicKey, null, 0);
+      sellerTokenAccount = await createAssociatedTokenAccount(provider.connection, unbidSeller, mint, unbidSeller.publicKey);
+      await mintTo(provider.connection, unbidSeller, mint, sellerTokenAccount, unbidSeller, 1);
+
+      [auctionListing] = PublicKey.findProgramAddressSync(
+        [Buffer.from("listing"), mint.toBuffer()],
+        marketplace.programId
+      );
+      [sellerStats] = PublicKey.findProgramAddressSync(
+        [Buffer.from("seller_stats"), unbidSeller.publicKey.toBuffer()],
+        marketplace.programId
+      );
+      escrowTokenAccount = await getAssociatedTokenAddress(mint, auctionListing, true);
+      const now = Math.floor(Date.now() / 1000);
+
+      await marketplace.methods
+        .createListing(
+          PRICE,
+          { auction: {} },
+          {
+            startTime: new BN(now - 1),
+            endTime: new BN(now + AUCTION_DURATION),
+            minBidIncrement: new BN(1),
+            reservePrice: null,
+            openingBid: null,
+            extensionWindow: new BN(0),
+            extensionPeriod: new BN(0),
+            maxExtensions: 0,
+            bidderWhitelist: null,
+            reserveHidden: false,
+            minDistinctBidders: 0,
+            incrementTiers: [],
+          },
+          null
+        )
+        .accounts({
+          seller: unbidSeller.publicKey,
+          marketplaceConfig,
+          listing: auctionListing,
+          sellerTokenAccount,
+          escrowTokenAccount,
+          sellerStats,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([unbidSeller])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, (AUCTION_DURATION + 1) * 1000));
+    });
+
+    it("returns the NFT and the escrow rent to the seller", async () => {
+      const escrowRent = await provider.connection.getBalance(escrowTokenAccount);
+      const sellerBefore = await provider.connection.getBalance(unbidSeller.publicKey);
+      const activeBefore = (await marketplace.account.sellerStats.fetch(sellerStats)).activeAuctions;
+
+      let expired: { returnedRent: BN } | null = null;
+      const listener = marketplace.addEventListener("AuctionExpired", (event) => {
+        if (event.listing.equals(auctionListing)) {
+          expired = event;
+        }
+      });
+
+      // With no bids there is no winner, so the seller stands in for one
+      await marketplace.methods
+        .endAuction()
+        .accounts({
+          caller: buyer.publicKey,
+          marketplaceConfig,
+          listing: auctionListing,
+          escrowTokenAccount,
+          winnerTokenAccount: sellerTokenAccount,
+          sellerTokenAccount,
+          winningBid: null,
+          bidEscrow: null,
+          mint,
+          winner: unbidSeller.publicKey,
+          seller: unbidSeller.publicKey,
+          sellerStats,
+          feeRecipient: platform.publicKey,
+          royaltyRecipient: creator.publicKey,
+          fallbackRoyaltyRecipient: platform.publicKey,
+          ...(await royaltyReceiptAccounts(creator.publicKey)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await marketplace.removeEventListener(listener);
+
+      const sellerToken = await getAccount(provider.connection, sellerTokenAccount);
+      assert.equal(Number(sellerToken.amount), 1);
+
+      // The escrow is closed and its rent lands with the seller
+      assert.isNull(await provider.connection.getAccountInfo(escrowTokenAccount));
+      assert.equal(
+        await provider.connection.getBalance(unbidSeller.publicKey),
+        sellerBefore + escrowRent
+      );
+      assert.equal(expired.returnedRent.toNumber(), escrowRent);
+
+      const listingAccount = await marketplace.account.listing.fetch(auctionListing);
+      assert.isFalse(listingAccount.isActive);
+      assert.equal(
+        (await marketplace.account.sellerStats.fetch(sellerStats)).activeAuctions,
+        activeBefore - 1
+      );
+    });
+  });
+
+  describe("Escrow Dispute Delay", () => {
+    const ESCROW_AMOUNT = 1_000_000;
+    const DISPUTE_DELAY = 2; // seconds
+
+    let paymentMint: PublicKey;
+    let buyerPaymentToken: PublicKey;
+
+    const escrowPdas = (transactionId: string) => {
+      const [escrow] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), Buffer.from(transactionId)],
+        marketplace.programId
+      );
+      const [dispute] = PublicKey.findProgramAddressSync(
+        [Buffer.from("dispute"), escrow.toBuffer()],
+        marketplace.programId
+      );
+      return { escrow, dispute };
+    };
+
+    const createEscrow = async (transactionId: string, minTimeBeforeDispute: number, expiresIn = 3600) => {
+      const { escrow } = escrowPdas(transactionId);
+      const escrowToken = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer, paymentMint, escrow, true)
+      ).address;
+
+      await marketplace.methods
+        .createEscrow({
+          transactionId,
+          amount: new BN(ESCROW_AMOUNT),
+          expiryTime: new BN(Math.floor(Date.now() / 1000) + expiresIn),
+          terms: "Seller delivers before disputes open",
+          disputePeriod: new BN(3600),
+          platformFeeRate: 0,
+          arbitrationWindow: null,
+          inspectionPeriod: new BN(0),
+          minTimeBeforeDispute: new BN(minTimeBeforeDispute),
+          defaultResolution: { favorBuyer: {} },
+          releaseCondition: null,
+          confirmers: [],
+        })
+        .accounts({
+          creator: buyer.publicKey,
+          escrow,
+          seller: seller.publicKey,
+          arbitrator: platform.publicKey,
+          creatorTokenAccount: buyerPaymentToken,
+          escrowTokenAccount: escrowToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+    };
+
+    const initiateDispute = (transactionId: string) =>
+      marketplace.methods
+        .initiateDispute({
+          category: { itemNotReceived: {} },
+          description: "Tickets not delivered",
+          evidenceLinks: [],
+          requestedResolution: { refundBuyer: {} },
+        })
+        .accounts({
+          disputer: buyer.publicKey,
+          ...escrowPdas(transactionId),
+          marketplace: PublicKey.findProgramAddressSync([Buffer.from("marketplace")], marketplace.programId)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+    before(async () => {
+      paymentMint = await createMint(provider.connection, platform, platform.publicKey, null, 6);
+      buyerPaymentToken = await createAssociatedTokenAccount(provider.connection, buyer, paymentMint, buyer.publicKey);
+      await mintTo(provider.connection, platform, paymentMint, buyerPaymentToken, platform, ESCROW_AMOUNT * 10);
+    });
+
+    it("accepts a dispute once the minimum time has passed", async () => {
+      await createEscrow("dispute-delay-elapsed", DISPUTE_DELAY);
+      await new Promise(resolve => setTimeout(resolve, (DISPUTE_DELAY + 1) * 1000));
+
+      await initiateDispute("dispute-delay-elapsed");
+
+      const { escrow } = escrowPdas("dispute-delay-elapsed");
+      const escrowAccount = await marketplace.account.escrow.fetch(escrow);
+      assert.deepEqual(escrowAccount.state, { disputed: {} });
+    });
+
+    it("rejects a dispute raised too soon after the escrow was created", async () => {
+      await createEscrow("dispute-delay-early", DISPUTE_DELAY);
+
+      try {
+        await initiateDispute("dispute-delay-early");
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "DisputeTooEarly");
+      }
+
+      const { escrow } = escrowPdas("dispute-delay-early");
+      const escrowAccount = await marketplace.account.escrow.fetch(escrow);
+      assert.deepEqual(escrowAccount.state, { active: {} });
+    });
+
+    it("rejects a delay that would end after the escrow expires", async () => {
+      try {
+        await createEscrow("dispute-delay-past-expiry", 60, 60);
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidDisputeDelay");
+      }
+    });
+
+    it("still closes disputes at escrow expiry", async () => {
+      await createEscrow("dispute-delay-expired", 1, DISPUTE_DELAY + 1);
+      await new Promise(resolve => setTimeout(resolve, (DISPUTE_DELAY + 2) * 1000));
+
+      try {
+        await initiateDispute("dispute-delay-expired");
+        assert.fail("Should have thrown error");
+      } catch (err) {
+        assert.include(err.toString(), "EscrowExpired");
+      }
+    });
+  });
 });
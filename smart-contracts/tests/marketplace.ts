@@ -1,7 +1,8 @@
 import * as anchor from '@project-serum/anchor';
 import { Program } from '@project-serum/anchor';
-import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from '@solana/web3.js';
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL, Transaction, sendAndConfirmTransaction } from '@solana/web3.js';
 import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { PROGRAM_ID as METADATA_PROGRAM_ID, createCreateMetadataAccountV3Instruction } from '@metaplex-foundation/mpl-token-metadata';
 import { assert } from 'chai';
 import { Marketplace } from '../target/types/marketplace';
 
@@ -138,7 +139,7 @@ describe('marketplace', () => {
   
   it('Buys a ticket from a fixed price listing', async () => {
     await program.methods
-      .buyTicket()
+      .buyTicket(1)
       .accounts({
         buyer: buyer.publicKey,
         listing: listingAddress,
@@ -165,6 +166,745 @@ describe('marketplace', () => {
     assert.equal(buyerTokenBalance.value.amount, '1');
   });
   
+  describe('Buyer token account creation', () => {
+    // Lists a fresh ticket at PRICE, returning its mint and listing accounts
+    const listFreshTicket = async () => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerAccount, seller.publicKey, 1);
+      
+      const [listing] = await PublicKey.findProgramAddress(
+        [Buffer.from('listing'), mint.toBuffer()],
+        program.programId
+      );
+      const escrow = await anchor.utils.token.associatedAddress({ mint, owner: listing });
+      
+      await program.methods
+        .createListing(new anchor.BN(PRICE), { fixedPrice: {} }, null)
+        .accounts({
+          seller: seller.publicKey,
+          ticketMint: mint,
+          sellerTicketAccount: sellerAccount,
+          escrowTokenAccount: escrow,
+          listing,
+          event: eventOrganizer.publicKey,
+          royaltyRecipient: eventOrganizer.publicKey,
+          marketplaceAuthority: marketplaceAuthority.publicKey,
+          auction: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([seller])
+        .rpc();
+      
+      return { mint, listing, escrow };
+    };
+    
+    const buy = async (purchaser: Keypair, mint: PublicKey, listing: PublicKey, escrow: PublicKey) => {
+      const [marketplaceConfig] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_config')],
+        program.programId
+      );
+      const config = await program.account.marketplaceConfig.fetch(marketplaceConfig);
+      const [feeExemption] = await PublicKey.findProgramAddress(
+        [Buffer.from('fee_exemption'), seller.publicKey.toBuffer()],
+        program.programId
+      );
+      const [sellerStats] = await PublicKey.findProgramAddress(
+        [Buffer.from('seller_stats'), seller.publicKey.toBuffer()],
+        program.programId
+      );
+      const [royaltyReceiptIndex] = await PublicKey.findProgramAddress(
+        [Buffer.from('royalty_receipt_index'), eventOrganizer.publicKey.toBuffer()],
+        program.programId
+      );
+      const [royaltyReceiptSegment] = await PublicKey.findProgramAddress(
+        [Buffer.from('royalty_receipts'), eventOrganizer.publicKey.toBuffer(), Buffer.alloc(4)],
+        program.programId
+      );
+      
+      await program.methods
+        .buyTicket(1)
+        .accounts({
+          buyer: purchaser.publicKey,
+          marketplaceConfig,
+          listing,
+          seller: seller.publicKey,
+          feeExemption,
+          sellerStats,
+          marketplaceAuthority: marketplaceAuthority.publicKey,
+          royaltyRecipient: eventOrganizer.publicKey,
+          fallbackRoyaltyRecipient: config.fallbackRoyaltyRecipient,
+          royaltyReceiptIndex,
+          royaltyReceiptSegment,
+          ticketMint: mint,
+          escrowTokenAccount: escrow,
+          buyerTokenAccount: await anchor.utils.token.associatedAddress({ mint, owner: purchaser.publicKey }),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([purchaser])
+        .rpc();
+    };
+    
+    it('Creates the buyer token account during the purchase', async () => {
+      const { mint, listing, escrow } = await listFreshTicket();
+      const buyerAta = await anchor.utils.token.associatedAddress({ mint, owner: buyer.publicKey });
+      assert.isNull(await provider.connection.getAccountInfo(buyerAta));
+      
+      await buy(buyer, mint, listing, escrow);
+      
+      const buyerTokenBalance = await provider.connection.getTokenAccountBalance(buyerAta);
+      assert.equal(buyerTokenBalance.value.amount, '1');
+      const listingAccount = await program.account.listing.fetch(listing);
+      assert.deepEqual(listingAccount.state, { sold: {} });
+    });
+    
+    it('Moves no funds when the buyer token account cannot be created', async () => {
+      const { mint, listing, escrow } = await listFreshTicket();
+      
+      // Enough for the price, but nothing left for the token account's rent
+      const brokeBuyer = Keypair.generate();
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: provider.wallet.publicKey,
+            toPubkey: brokeBuyer.publicKey,
+            lamports: PRICE,
+          })
+        )
+      );
+      
+      const buyerBefore = await provider.connection.getBalance(brokeBuyer.publicKey);
+      const sellerBefore = await provider.connection.getBalance(seller.publicKey);
+      const authorityBefore = await provider.connection.getBalance(marketplaceAuthority.publicKey);
+      
+      try {
+        await buy(brokeBuyer, mint, listing, escrow);
+        assert.fail('Purchase should fail when the token account cannot be created');
+      } catch (error) {
+        assert.notInclude(error.toString(), 'Purchase should fail');
+      }
+      
+      assert.equal(await provider.connection.getBalance(brokeBuyer.publicKey), buyerBefore);
+      assert.equal(await provider.connection.getBalance(seller.publicKey), sellerBefore);
+      assert.equal(await provider.connection.getBalance(marketplaceAuthority.publicKey), authorityBefore);
+      
+      const listingAccount = await program.account.listing.fetch(listing);
+      assert.deepEqual(listingAccount.state, { active: {} });
+      const escrowBalance = await provider.connection.getTokenAccountBalance(escrow);
+      assert.equal(escrowBalance.value.amount, '1');
+    });
+    
+    describe('Self-purchase prevention', () => {
+      it('Lets another wallet buy the listing', async () => {
+        const { mint, listing, escrow } = await listFreshTicket();
+        await buy(buyer, mint, listing, escrow);
+        
+        const listingAccount = await program.account.listing.fetch(listing);
+        assert.deepEqual(listingAccount.state, { sold: {} });
+      });
+      
+      it('Rejects the seller buying their own listing', async () => {
+        const { mint, listing, escrow } = await listFreshTicket();
+        try {
+          await buy(seller, mint, listing, escrow);
+          assert.fail('Seller should not be able to buy their own listing');
+        } catch (error) {
+          assert.include(error.toString(), 'CannotBuyOwnListing');
+        }
+      });
+      
+      it('Leaves the listing and fee totals untouched by a blocked self-purchase', async () => {
+        const { mint, listing, escrow } = await listFreshTicket();
+        const listingBefore = await program.account.listing.fetch(listing);
+        const authorityBefore = await provider.connection.getBalance(marketplaceAuthority.publicKey);
+        const organizerBefore = await provider.connection.getBalance(eventOrganizer.publicKey);
+        
+        try {
+          await buy(seller, mint, listing, escrow);
+          assert.fail('Seller should not be able to buy their own listing');
+        } catch (error) {
+          assert.include(error.toString(), 'CannotBuyOwnListing');
+        }
+        
+        // No fee or royalty was collected and the unit is still for sale
+        assert.equal(await provider.connection.getBalance(marketplaceAuthority.publicKey), authorityBefore);
+        assert.equal(await provider.connection.getBalance(eventOrganizer.publicKey), organizerBefore);
+        const listingAfter = await program.account.listing.fetch(listing);
+        assert.deepEqual(listingAfter.state, { active: {} });
+        assert.equal(listingAfter.quantity, listingBefore.quantity);
+        const escrowBalance = await provider.connection.getTokenAccountBalance(escrow);
+        assert.equal(escrowBalance.value.amount, '1');
+      });
+    });
+  });
+  
+  describe('Royalty paths', () => {
+    const coCreator1 = Keypair.generate();
+    const coCreator2 = Keypair.generate();
+    
+    const enhancedRoyalty = () => ({
+      recipients: [
+        { recipient: coCreator1.publicKey, basisPoints: 300 },
+        { recipient: coCreator2.publicKey, basisPoints: 200 },
+      ],
+      tieredRates: false,
+      tierThresholds: null,
+      tierBasisPointsAdjustments: null,
+    });
+    
+    const receiptIndexFor = async (creator: PublicKey) => (await PublicKey.findProgramAddress(
+      [Buffer.from('royalty_receipt_index'), creator.toBuffer()],
+      program.programId
+    ))[0];
+    const receiptSegmentFor = async (creator: PublicKey) => (await PublicKey.findProgramAddress(
+      [Buffer.from('royalty_receipts'), creator.toBuffer(), Buffer.alloc(4)],
+      program.programId
+    ))[0];
+    
+    // Lists a fresh ticket at PRICE, on the enhanced royalty path if a config is given
+    const listFreshTicket = async (royaltyConfig: any = null) => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerAccount, seller.publicKey, 1);
+      
+      const [listing] = await PublicKey.findProgramAddress(
+        [Buffer.from('listing'), mint.toBuffer()],
+        program.programId
+      );
+      const escrow = await anchor.utils.token.associatedAddress({ mint, owner: listing });
+      
+      await program.methods
+        .createListing(new anchor.BN(PRICE), { fixedPrice: {} }, null, royaltyConfig)
+        .accounts({
+          seller: seller.publicKey,
+          ticketMint: mint,
+          sellerTicketAccount: sellerAccount,
+          escrowTokenAccount: escrow,
+          listing,
+          event: eventOrganizer.publicKey,
+          royaltyRecipient: eventOrganizer.publicKey,
+          marketplaceAuthority: marketplaceAuthority.publicKey,
+          auction: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([seller])
+        .rpc();
+      
+      return { mint, listing, escrow };
+    };
+    
+    const buy = async (mint: PublicKey, listing: PublicKey, escrow: PublicKey, recipients: PublicKey[]) => {
+      const [marketplaceConfig] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_config')],
+        program.programId
+      );
+      const config = await program.account.marketplaceConfig.fetch(marketplaceConfig);
+      const [feeExemption] = await PublicKey.findProgramAddress(
+        [Buffer.from('fee_exemption'), seller.publicKey.toBuffer()],
+        program.programId
+      );
+      const [sellerStats] = await PublicKey.findProgramAddress(
+        [Buffer.from('seller_stats'), seller.publicKey.toBuffer()],
+        program.programId
+      );
+      const [royaltyReceiptIndex] = await PublicKey.findProgramAddress(
+        [Buffer.from('royalty_receipt_index'), eventOrganizer.publicKey.toBuffer()],
+        program.programId
+      );
+      const [royaltyReceiptSegment] = await PublicKey.findProgramAddress(
+        [Buffer.from('royalty_receipts'), eventOrganizer.publicKey.toBuffer(), Buffer.alloc(4)],
+        program.programId
+      );
+      
+      return program.methods
+        .buyTicket(1)
+        .accounts({
+          buyer: buyer.publicKey,
+          marketplaceConfig,
+          listing,
+          seller: seller.publicKey,
+          feeExemption,
+          sellerStats,
+          marketplaceAuthority: marketplaceAuthority.publicKey,
+          royaltyRecipient: eventOrganizer.publicKey,
+          fallbackRoyaltyRecipient: config.fallbackRoyaltyRecipient,
+          royaltyReceiptIndex,
+          royaltyReceiptSegment,
+          ticketMint: mint,
+          escrowTokenAccount: escrow,
+          buyerTokenAccount: await anchor.utils.token.associatedAddress({ mint, owner: buyer.publicKey }),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .remainingAccounts([
+          ...recipients.map(pubkey => ({ pubkey, isWritable: true, isSigner: false })),
+          ...(await Promise.all(recipients.map(async (recipient) => [
+            { pubkey: await receiptIndexFor(recipient), isWritable: true, isSigner: false },
+            { pubkey: await receiptSegmentFor(recipient), isWritable: true, isSigner: false },
+          ]))).flat(),
+        ])
+        .signers([buyer])
+        .rpc();
+    };
+    
+    before(async () => {
+      // Recipients must exist to receive their share without tripping rent
+      for (const recipient of [coCreator1, coCreator2]) {
+        await provider.connection.confirmTransaction(
+          await provider.connection.requestAirdrop(recipient.publicKey, LAMPORTS_PER_SOL)
+        );
+      }
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(buyer.publicKey, LAMPORTS_PER_SOL * 5)
+      );
+    });
+    
+    it('Pays the single recipient on the legacy path', async () => {
+      const { mint, listing, escrow } = await listFreshTicket();
+      const listingAccount = await program.account.listing.fetch(listing);
+      assert.deepEqual(listingAccount.royaltyPath, { legacy: {} });
+      
+      const organizerBefore = await provider.connection.getBalance(eventOrganizer.publicKey);
+      await buy(mint, listing, escrow, []);
+      
+      const organizerAfter = await provider.connection.getBalance(eventOrganizer.publicKey);
+      assert.equal(organizerAfter - organizerBefore, PRICE * ROYALTY_BPS / 10000);
+    });
+    
+    it('Pays only the configured recipients on the enhanced path', async () => {
+      const { mint, listing, escrow } = await listFreshTicket(enhancedRoyalty());
+      const listingAccount = await program.account.listing.fetch(listing);
+      assert.deepEqual(listingAccount.royaltyPath, { enhanced: {} });
+      
+      const organizerBefore = await provider.connection.getBalance(eventOrganizer.publicKey);
+      const first = await provider.connection.getBalance(coCreator1.publicKey);
+      const second = await provider.connection.getBalance(coCreator2.publicKey);
+      
+      await buy(mint, listing, escrow, [coCreator1.publicKey, coCreator2.publicKey]);
+      
+      assert.equal(await provider.connection.getBalance(coCreator1.publicKey) - first, PRICE * 300 / 10000);
+      assert.equal(await provider.connection.getBalance(coCreator2.publicKey) - second, PRICE * 200 / 10000);
+      // The legacy recipient is a placeholder here and must not be paid too
+      assert.equal(await provider.connection.getBalance(eventOrganizer.publicKey), organizerBefore);
+    });
+    
+    it('Records a royalty receipt for each paid recipient on the enhanced path', async () => {
+      await program.methods
+        .enableRoyaltyReceipts(4)
+        .accounts({
+          royaltyReceiptIndex: await receiptIndexFor(coCreator1.publicKey),
+          royaltyReceiptSegment: await receiptSegmentFor(coCreator1.publicKey),
+          creator: coCreator1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([coCreator1])
+        .rpc();
+      
+      const { mint, listing, escrow } = await listFreshTicket(enhancedRoyalty());
+      await buy(mint, listing, escrow, [coCreator1.publicKey, coCreator2.publicKey]);
+      
+      const segment = await program.account.royaltyReceiptSegment.fetch(await receiptSegmentFor(coCreator1.publicKey));
+      assert.equal(segment.receipts.length, 1);
+      assert.equal(segment.receipts[0].sale.toString(), listing.toString());
+      assert.equal(segment.receipts[0].amount.toNumber(), PRICE * 300 / 10000);
+      assert.equal(segment.receipts[0].payer.toString(), buyer.publicKey.toString());
+      
+      // The other recipient never enabled receipts, so nothing is kept for them
+      assert.isNull(await provider.connection.getAccountInfo(await receiptIndexFor(coCreator2.publicKey)));
+    });
+    
+    it('Rejects recipient accounts that do not match the royalty path', async () => {
+      const enhanced = await listFreshTicket(enhancedRoyalty());
+      try {
+        await buy(enhanced.mint, enhanced.listing, enhanced.escrow, []);
+        assert.fail('Enhanced listing should require its recipients');
+      } catch (error) {
+        assert.include(error.toString(), 'RoyaltyPathMismatch');
+      }
+      
+      const legacy = await listFreshTicket();
+      try {
+        await buy(legacy.mint, legacy.listing, legacy.escrow, [coCreator1.publicKey, coCreator2.publicKey]);
+        assert.fail('Legacy listing should not take enhanced recipients');
+      } catch (error) {
+        assert.include(error.toString(), 'RoyaltyPathMismatch');
+      }
+      
+      const listingAccount = await program.account.listing.fetch(legacy.listing);
+      assert.deepEqual(listingAccount.state, { active: {} });
+    });
+    
+    describe('Royalty recipient cap', () => {
+      const MAX_ROYALTY_RECIPIENTS = 5;
+      const SHARE_BPS = 100;
+      const coCreators = Array.from({ length: MAX_ROYALTY_RECIPIENTS + 1 }, () => Keypair.generate());
+      
+      const splitAcross = (recipients: Keypair[]) => ({
+        recipients: recipients.map(recipient => ({ recipient: recipient.publicKey, basisPoints: SHARE_BPS })),
+        tieredRates: false,
+        tierThresholds: null,
+        tierBasisPointsAdjustments: null,
+      });
+      
+      before(async () => {
+        for (const recipient of coCreators) {
+          await provider.connection.confirmTransaction(
+            await provider.connection.requestAirdrop(recipient.publicKey, LAMPORTS_PER_SOL)
+          );
+        }
+      });
+      
+      it('Pays a split at the maximum number of recipients', async () => {
+        const recipients = coCreators.slice(0, MAX_ROYALTY_RECIPIENTS);
+        const { mint, listing, escrow } = await listFreshTicket(splitAcross(recipients));
+        const before = await Promise.all(recipients.map(r => provider.connection.getBalance(r.publicKey)));
+        
+        await buy(mint, listing, escrow, recipients.map(r => r.publicKey));
+        
+        for (let i = 0; i < recipients.length; i++) {
+          const after = await provider.connection.getBalance(recipients[i].publicKey);
+          assert.equal(after - before[i], PRICE * SHARE_BPS / 10000);
+        }
+      });
+      
+      it('Rejects a split with one recipient over the maximum', async () => {
+        const recipients = coCreators;
+        try {
+          const { mint, listing, escrow } = await listFreshTicket(splitAcross(recipients));
+          await buy(mint, listing, escrow, recipients.map(r => r.publicKey));
+          assert.fail('Oversized royalty split should be rejected');
+        } catch (error) {
+          assert.include(error.toString(), 'TooManyRoyaltyRecipients');
+        }
+      });
+      
+      it('Distributes across the maximum recipients within the default compute budget', async () => {
+        const recipients = coCreators.slice(0, MAX_ROYALTY_RECIPIENTS);
+        const { mint, listing, escrow } = await listFreshTicket(splitAcross(recipients));
+        
+        const signature = await buy(mint, listing, escrow, recipients.map(r => r.publicKey));
+        await provider.connection.confirmTransaction(signature, 'confirmed');
+        const tx = await provider.connection.getTransaction(signature, { commitment: 'confirmed' });
+        
+        assert.isNotNull(tx.meta.computeUnitsConsumed);
+        assert.isAtMost(tx.meta.computeUnitsConsumed, 200_000);
+      });
+    });
+  });
+  
+  describe('Multi-unit listings', () => {
+    // Mints a fresh GA ticket to the seller, returning its mint, metadata and token account
+    const mintTicket = async (uri = 'https://tickets.example/ga.json') => {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const sellerAccount = await createAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey);
+      await mintTo(provider.connection, seller, mint, sellerAccount, seller.publicKey, 1);
+      
+      const [metadata] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        METADATA_PROGRAM_ID
+      );
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          createCreateMetadataAccountV3Instruction(
+            { metadata, mint, mintAuthority: seller.publicKey, payer: seller.publicKey, updateAuthority: seller.publicKey },
+            {
+              createMetadataAccountArgsV3: {
+                data: { name: 'GA Ticket', symbol: 'GA', uri, sellerFeeBasisPoints: ROYALTY_BPS, creators: null, collection: null, uses: null },
+                isMutable: true,
+                collectionDetails: null,
+              },
+            }
+          )
+        ),
+        [seller]
+      );
+      return { mint, metadata, sellerAccount };
+    };
+    
+    // Moves the given tickets into the listing as extra units
+    const addUnits = async (listing: PublicKey, listedMetadata: PublicKey, tickets: Awaited<ReturnType<typeof mintTicket>>[], allowPartialFill: boolean) => {
+      const unitAccounts = [];
+      for (const { mint, metadata, sellerAccount } of tickets) {
+        unitAccounts.push(
+          { pubkey: mint, isWritable: false, isSigner: false },
+          { pubkey: metadata, isWritable: false, isSigner: false },
+          { pubkey: sellerAccount, isWritable: true, isSigner: false },
+          { pubkey: await anchor.utils.token.associatedAddress({ mint, owner: listing }), isWritable: true, isSigner: false },
+        );
+      }
+      
+      await program.methods
+        .addListingUnits(allowPartialFill)
+        .accounts({
+          seller: seller.publicKey,
+          listing,
+          ticketMetadata: listedMetadata,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(unitAccounts)
+        .signers([seller])
+        .rpc();
+    };
+    
+    // Returns the given units from the listing's escrow to the seller
+    const withdrawUnits = async (listing: PublicKey, units: { mint: PublicKey, escrow: PublicKey }[]) => {
+      const unitAccounts = [];
+      for (const { mint, escrow } of units) {
+        unitAccounts.push(
+          { pubkey: mint, isWritable: false, isSigner: false },
+          { pubkey: escrow, isWritable: true, isSigner: false },
+          { pubkey: await anchor.utils.token.associatedAddress({ mint, owner: seller.publicKey }), isWritable: true, isSigner: false },
+        );
+      }
+      
+      await program.methods
+        .withdrawListingUnits()
+        .accounts({
+          seller: seller.publicKey,
+          listing,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(unitAccounts)
+        .signers([seller])
+        .rpc();
+    };
+    
+    // Lists `quantity` identical tickets at PRICE each, returning the listing and every unit's mint and escrow
+    const listUnits = async (quantity: number, allowPartialFill: boolean) => {
+      const first = await mintTicket();
+      const [listing] = await PublicKey.findProgramAddress(
+        [Buffer.from('listing'), first.mint.toBuffer()],
+        program.programId
+      );
+      const firstEscrow = await anchor.utils.token.associatedAddress({ mint: first.mint, owner: listing });
+      
+      await program.methods
+        .createListing(new anchor.BN(PRICE), { fixedPrice: {} }, null)
+        .accounts({
+          seller: seller.publicKey,
+          ticketMint: first.mint,
+          sellerTicketAccount: first.sellerAccount,
+          escrowTokenAccount: firstEscrow,
+          listing,
+          event: eventOrganizer.publicKey,
+          royaltyRecipient: eventOrganizer.publicKey,
+          marketplaceAuthority: marketplaceAuthority.publicKey,
+          auction: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([seller])
+        .rpc();
+      
+      const units = [{ mint: first.mint, escrow: firstEscrow }];
+      const extras = [];
+      for (let i = 1; i < quantity; i++) {
+        const ticket = await mintTicket();
+        extras.push(ticket);
+        units.push({ mint: ticket.mint, escrow: await anchor.utils.token.associatedAddress({ mint: ticket.mint, owner: listing }) });
+      }
+      if (extras.length > 0) {
+        await addUnits(listing, first.metadata, extras, allowPartialFill);
+      }
+      
+      return { listing, units, listedMetadata: first.metadata };
+    };
+    
+    // Buys the given units; the first goes in the named accounts, the rest as remaining accounts
+    const buyUnits = async (listing: PublicKey, units: { mint: PublicKey, escrow: PublicKey }[], quantity: number) => {
+      const [marketplaceConfig] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_config')],
+        program.programId
+      );
+      const config = await program.account.marketplaceConfig.fetch(marketplaceConfig);
+      const [feeExemption] = await PublicKey.findProgramAddress(
+        [Buffer.from('fee_exemption'), seller.publicKey.toBuffer()],
+        program.programId
+      );
+      const [sellerStats] = await PublicKey.findProgramAddress(
+        [Buffer.from('seller_stats'), seller.publicKey.toBuffer()],
+        program.programId
+      );
+      const [royaltyReceiptIndex] = await PublicKey.findProgramAddress(
+        [Buffer.from('royalty_receipt_index'), eventOrganizer.publicKey.toBuffer()],
+        program.programId
+      );
+      const [royaltyReceiptSegment] = await PublicKey.findProgramAddress(
+        [Buffer.from('royalty_receipts'), eventOrganizer.publicKey.toBuffer(), Buffer.alloc(4)],
+        program.programId
+      );
+      
+      const [first, ...rest] = units;
+      const unitAccounts = [];
+      for (const { mint, escrow } of rest) {
+        unitAccounts.push(
+          { pubkey: mint, isWritable: false, isSigner: false },
+          { pubkey: escrow, isWritable: true, isSigner: false },
+          { pubkey: await anchor.utils.token.associatedAddress({ mint, owner: buyer.publicKey }), isWritable: true, isSigner: false },
+        );
+      }
+      
+      await program.methods
+        .buyTicket(quantity)
+        .accounts({
+          buyer: buyer.publicKey,
+          marketplaceConfig,
+          listing,
+          seller: seller.publicKey,
+          feeExemption,
+          sellerStats,
+          marketplaceAuthority: marketplaceAuthority.publicKey,
+          royaltyRecipient: eventOrganizer.publicKey,
+          fallbackRoyaltyRecipient: config.fallbackRoyaltyRecipient,
+          royaltyReceiptIndex,
+          royaltyReceiptSegment,
+          ticketMint: first.mint,
+          escrowTokenAccount: first.escrow,
+          buyerTokenAccount: await anchor.utils.token.associatedAddress({ mint: first.mint, owner: buyer.publicKey }),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .remainingAccounts(unitAccounts)
+        .signers([buyer])
+        .rpc();
+    };
+    
+    const buyerBalanceOf = async (mint: PublicKey) => {
+      const account = await anchor.utils.token.associatedAddress({ mint, owner: buyer.publicKey });
+      return (await provider.connection.getTokenAccountBalance(account)).value.amount;
+    };
+    
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(buyer.publicKey, LAMPORTS_PER_SOL * 5)
+      );
+    });
+    
+    it('Sells part of a listing and keeps the rest on sale', async () => {
+      const { listing, units } = await listUnits(3, true);
+      const listingBefore = await program.account.listing.fetch(listing);
+      assert.equal(listingBefore.quantity, 3);
+      
+      const sellerBefore = await provider.connection.getBalance(seller.publicKey);
+      await buyUnits(listing, units.slice(0, 2), 2);
+      
+      assert.equal(await buyerBalanceOf(units[0].mint), '1');
+      assert.equal(await buyerBalanceOf(units[1].mint), '1');
+      const escrowBalance = await provider.connection.getTokenAccountBalance(units[2].escrow);
+      assert.equal(escrowBalance.value.amount, '1');
+      
+      // The seller is paid for both units, less fees and royalties
+      const sellerAfter = await provider.connection.getBalance(seller.publicKey);
+      const expectedProceeds = 2 * PRICE * (10000 - MARKETPLACE_FEE_BPS - ROYALTY_BPS) / 10000;
+      assert.equal(sellerAfter - sellerBefore, expectedProceeds);
+      
+      const listingAfter = await program.account.listing.fetch(listing);
+      assert.equal(listingAfter.quantity, 1);
+      assert.deepEqual(listingAfter.state, { active: {} });
+    });
+    
+    it('Closes the listing once every unit has sold', async () => {
+      const { listing, units } = await listUnits(3, true);
+      await buyUnits(listing, units.slice(0, 1), 1);
+      await buyUnits(listing, units.slice(1), 2);
+      
+      for (const { mint } of units) {
+        assert.equal(await buyerBalanceOf(mint), '1');
+      }
+      
+      const listingAccount = await program.account.listing.fetch(listing);
+      assert.equal(listingAccount.quantity, 0);
+      assert.deepEqual(listingAccount.state, { sold: {} });
+    });
+    
+    it('Rejects buying more units than the listing has left', async () => {
+      const { listing, units } = await listUnits(2, true);
+      
+      try {
+        await buyUnits(listing, units, 3);
+        assert.fail('Buying more units than listed should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'InsufficientListingQuantity');
+      }
+      
+      const listingAccount = await program.account.listing.fetch(listing);
+      assert.equal(listingAccount.quantity, 2);
+      assert.deepEqual(listingAccount.state, { active: {} });
+    });
+    
+    it('Rejects a partial buy when the seller requires the whole lot', async () => {
+      const { listing, units } = await listUnits(2, false);
+      
+      try {
+        await buyUnits(listing, units.slice(0, 1), 1);
+        assert.fail('Partial buy should fail when partial fills are disabled');
+      } catch (error) {
+        assert.include(error.toString(), 'PartialFillNotAllowed');
+      }
+    });
+    
+    it('Rejects adding a unit that is not the listed ticket', async () => {
+      const { listing, listedMetadata } = await listUnits(1, true);
+      const otherTicket = await mintTicket('https://tickets.example/vip.json');
+      
+      try {
+        await addUnits(listing, listedMetadata, [otherTicket], true);
+        assert.fail('A different ticket should not be added as a unit');
+      } catch (error) {
+        assert.include(error.toString(), 'ListingUnitMismatch');
+      }
+    });
+    
+    it('Returns unsold units to the seller and cancels once none are left', async () => {
+      const { listing, units } = await listUnits(3, true);
+      await buyUnits(listing, units.slice(0, 1), 1);
+      
+      await withdrawUnits(listing, units.slice(1, 2));
+      const sellerAccount = await anchor.utils.token.associatedAddress({ mint: units[1].mint, owner: seller.publicKey });
+      assert.equal((await provider.connection.getTokenAccountBalance(sellerAccount)).value.amount, '1');
+      let listingAccount = await program.account.listing.fetch(listing);
+      assert.equal(listingAccount.quantity, 1);
+      assert.deepEqual(listingAccount.state, { active: {} });
+      
+      await withdrawUnits(listing, units.slice(2));
+      listingAccount = await program.account.listing.fetch(listing);
+      assert.equal(listingAccount.quantity, 0);
+      assert.deepEqual(listingAccount.state, { canceled: {} });
+    });
+    
+    it('Rejects withdrawing a unit that has already sold', async () => {
+      const { listing, units } = await listUnits(2, true);
+      await buyUnits(listing, units.slice(0, 1), 1);
+      
+      try {
+        await withdrawUnits(listing, units.slice(0, 1));
+        assert.fail('A sold unit should not be withdrawn');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidListingUnitAccounts');
+      }
+    });
+  });
+  
   it('Creates an auction listing', async () => {
     // Create a new NFT for this test
     const auctionTicketMint = await createMint(
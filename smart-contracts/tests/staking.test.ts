@@ -1,7 +1,13 @@
 import * as anchor from '@project-serum/anchor';
 import { Program } from '@project-serum/anchor';
-import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from '@solana/web3.js';
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo, getAccount } from '@solana/spl-token';
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL, Transaction, sendAndConfirmTransaction } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo, getAccount, getAssociatedTokenAddress, transfer } from '@solana/spl-token';
+import {
+  PROGRAM_ID as METADATA_PROGRAM_ID,
+  createCreateMetadataAccountV3Instruction,
+  createCreateMasterEditionV3Instruction,
+  createVerifyCollectionInstruction,
+} from '@metaplex-foundation/mpl-token-metadata';
 import { assert } from 'chai';
 import { TicketStaking } from '../target/types/ticket_staking';
 
@@ -37,6 +43,19 @@ describe('Staking Contract Tests', () => {
   const REWARD_POOL_BALANCE = 1000000 * 10**6; // 1M reward tokens
   const STAKE_AMOUNT_1 = 10000 * 10**6; // 10k tokens
   const STAKE_AMOUNT_2 = 5000 * 10**6; // 5k tokens
+  const MIN_STAKING_DURATION = 2; // seconds
+  
+  // Receipt NFT accounts for a user's position in a pool
+  const receiptAccountsFor = async (userStake: PublicKey, user: PublicKey) => {
+    const [receiptMint] = await PublicKey.findProgramAddress(
+      [Buffer.from('stake_receipt'), userStake.toBuffer()],
+      program.programId
+    );
+    return {
+      receiptMint,
+      receiptTokenAccount: await getAssociatedTokenAddress(receiptMint, user),
+    };
+  };
   
   before(async () => {
     console.log("Setting up staking test environment...");
@@ -110,7 +129,7 @@ describe('Staking Contract Tests', () => {
     it("Initializes staking program", async () => {
       const defaultConfig = {
         defaultCooldownPeriod: new anchor.BN(7 * 24 * 60 * 60), // 7 days
-        rewardClaimCooldown: new anchor.BN(24 * 60 * 60), // 1 day
+        rewardClaimCooldown: new anchor.BN(0), // No claim cooldown so claims can be exercised
         earlyUnstakeFeeBps: 500, // 5%
         maxStakePools: 10,
         paused: false,
@@ -158,10 +177,14 @@ describe('Staking Contract Tests', () => {
         minStakeAmount: new anchor.BN(100 * 10**6), // 100 tokens
         maxStakeAmount: new anchor.BN(1000000 * 10**6), // 1M tokens
         cooldownPeriod: null, // Use default
-        minStakingDuration: new anchor.BN(24 * 60 * 60), // 1 day
+        minStakingDuration: new anchor.BN(MIN_STAKING_DURATION),
         acceptingStakes: true,
         poolCapacity: new anchor.BN(0), // Unlimited
         tierBonusEnabled: true,
+        maxTotalRewards: new anchor.BN(0), // Unlimited
+        soulboundReceipts: false,
+        ticketBoost: null,
+        minCompoundInterval: new anchor.BN(0),
       };
       
       await program.methods
@@ -216,8 +239,11 @@ describe('Staking Contract Tests', () => {
           userTokenAccount: staker1TokenAccount,
           stakeVault: stakeVault,
           userStake: userStakeAddress,
+          ...(await receiptAccountsFor(userStakeAddress, staker1.publicKey)),
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         })
         .signers([staker1])
         .rpc();
@@ -256,8 +282,11 @@ describe('Staking Contract Tests', () => {
           userTokenAccount: staker2TokenAccount,
           stakeVault: stakeVault,
           userStake: userStakeAddress,
+          ...(await receiptAccountsFor(userStakeAddress, staker2.publicKey)),
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         })
         .signers([staker2])
         .rpc();
@@ -322,6 +351,9 @@ describe('Staking Contract Tests', () => {
       
       const unstakeAmount = STAKE_AMOUNT_1 / 2; // Unstake half
       
+      // Wait out the pool's minimum staking duration
+      await new Promise(resolve => setTimeout(resolve, (MIN_STAKING_DURATION + 1) * 1000));
+      
       await program.methods
         .unstakeTokens(new anchor.BN(unstakeAmount))
         .accounts({
@@ -344,4 +376,1293 @@ describe('Staking Contract Tests', () => {
       assert.equal(stakePool.totalStaked.toNumber(), STAKE_AMOUNT_1 - unstakeAmount + STAKE_AMOUNT_2);
     });
   });
+
+  describe("Secondary Reward Token", () => {
+    const POOL_STAKE_AMOUNT = 20000 * 10**6; // 20k tokens
+    const POOL_REWARDS = 50000 * 10**6; // 50k secondary reward tokens
+    
+    let secondaryRewardMint: PublicKey;
+    let secondaryPool: PublicKey;
+    let secondaryStakeVault: Keypair;
+    let secondaryRewardVault: Keypair;
+    let staker1SecondaryRewardAccount: PublicKey;
+    let userStakeAddress: PublicKey;
+    
+    const secondaryPoolConfig = {
+      rewardRateBps: 1200, // 12% APY
+      minStakeAmount: new anchor.BN(100 * 10**6),
+      maxStakeAmount: new anchor.BN(1000000 * 10**6),
+      cooldownPeriod: null,
+      minStakingDuration: new anchor.BN(0),
+      acceptingStakes: true,
+      poolCapacity: new anchor.BN(0),
+      tierBonusEnabled: false,
+      maxTotalRewards: new anchor.BN(0),
+      soulboundReceipts: false,
+      ticketBoost: null,
+      minCompoundInterval: new anchor.BN(0),
+    };
+    
+    const claim = (rewardVault: PublicKey) =>
+      program.methods
+        .claimRewards()
+        .accounts({
+          user: staker1.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: secondaryPool,
+          userStake: userStakeAddress,
+          userRewardAccount: staker1SecondaryRewardAccount,
+          rewardVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([staker1])
+        .rpc();
+    
+    before(async () => {
+      secondaryRewardMint = await createMint(
+        provider.connection,
+        stakingAuthority,
+        stakingAuthority.publicKey,
+        null,
+        6
+      );
+      staker1SecondaryRewardAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        staker1,
+        secondaryRewardMint,
+        staker1.publicKey
+      );
+      
+      const stakingProgram = await program.account.stakingProgram.fetch(stakingProgramAddress);
+      const poolIndex = Buffer.alloc(4);
+      poolIndex.writeUInt32LE(stakingProgram.activePools);
+      [secondaryPool] = await PublicKey.findProgramAddress(
+        [Buffer.from('stake_pool'), stakingProgramAddress.toBuffer(), poolIndex],
+        program.programId
+      );
+      [userStakeAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('user_stake'), secondaryPool.toBuffer(), staker1.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      secondaryStakeVault = Keypair.generate();
+      secondaryRewardVault = Keypair.generate();
+    });
+    
+    it("Creates a pool rewarding in a different token", async () => {
+      await program.methods
+        .createStakePool(secondaryPoolConfig, { general: {} })
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakeTokenMint: stakingTokenMint,
+          rewardTokenMint: secondaryRewardMint,
+          stakeVault: secondaryStakeVault.publicKey,
+          rewardVault: secondaryRewardVault.publicKey,
+          stakePool: secondaryPool,
+          associatedEvent: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([stakingAuthority, secondaryStakeVault, secondaryRewardVault])
+        .rpc();
+      
+      const stakePool = await program.account.stakePool.fetch(secondaryPool);
+      assert.equal(stakePool.stakeTokenMint.toString(), stakingTokenMint.toString());
+      assert.equal(stakePool.rewardTokenMint.toString(), secondaryRewardMint.toString());
+      
+      const rewardVaultAccount = await getAccount(provider.connection, secondaryRewardVault.publicKey);
+      assert.equal(rewardVaultAccount.mint.toString(), secondaryRewardMint.toString());
+    });
+    
+    it("Stakes and distributes rewards in the secondary token", async () => {
+      await program.methods
+        .stakeTokens(new anchor.BN(POOL_STAKE_AMOUNT))
+        .accounts({
+          user: staker1.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: secondaryPool,
+          userTokenAccount: staker1TokenAccount,
+          stakeVault: secondaryStakeVault.publicKey,
+          userStake: userStakeAddress,
+          ...(await receiptAccountsFor(userStakeAddress, staker1.publicKey)),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([staker1])
+        .rpc();
+      
+      const authoritySecondaryAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        stakingAuthority,
+        secondaryRewardMint,
+        stakingAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        stakingAuthority,
+        secondaryRewardMint,
+        authoritySecondaryAccount,
+        stakingAuthority.publicKey,
+        POOL_REWARDS
+      );
+      
+      await program.methods
+        .distributeRewards(new anchor.BN(POOL_REWARDS))
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: secondaryPool,
+          authorityRewardAccount: authoritySecondaryAccount,
+          rewardVault: secondaryRewardVault.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stakingAuthority])
+        .rpc();
+      
+      const stakePool = await program.account.stakePool.fetch(secondaryPool);
+      assert.equal(stakePool.availableRewards.toNumber(), POOL_REWARDS);
+      
+      const rewardVaultAccount = await getAccount(provider.connection, secondaryRewardVault.publicKey);
+      assert.equal(Number(rewardVaultAccount.amount), POOL_REWARDS);
+    });
+    
+    it("Rejects a claim from a reward vault of the wrong mint", async () => {
+      try {
+        // The stake vault holds the staked token, not the reward token
+        await claim(secondaryStakeVault.publicKey);
+        assert.fail("Claim from a mismatched reward vault should fail");
+      } catch (error) {
+        assert.include(error.toString(), "RewardVaultMintMismatch");
+      }
+    });
+    
+    it("Pays and accounts rewards in the secondary token", async () => {
+      // Let rewards accrue
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      
+      const stakeBalanceBefore = await getAccount(provider.connection, staker1TokenAccount);
+      const rewardBalanceBefore = await getAccount(provider.connection, staker1SecondaryRewardAccount);
+      
+      await claim(secondaryRewardVault.publicKey);
+      
+      const stakeBalanceAfter = await getAccount(provider.connection, staker1TokenAccount);
+      const rewardBalanceAfter = await getAccount(provider.connection, staker1SecondaryRewardAccount);
+      const claimed = Number(rewardBalanceAfter.amount) - Number(rewardBalanceBefore.amount);
+      
+      assert.isAbove(claimed, 0);
+      // Rewards never touch the staked token balance
+      assert.equal(stakeBalanceAfter.amount.toString(), stakeBalanceBefore.amount.toString());
+      
+      const userStake = await program.account.userStake.fetch(userStakeAddress);
+      assert.equal(userStake.totalRewardsClaimed.toNumber(), claimed);
+      
+      const stakePool = await program.account.stakePool.fetch(secondaryPool);
+      assert.equal(stakePool.totalRewardsDistributed.toNumber(), claimed);
+      assert.equal(stakePool.availableRewards.toNumber(), POOL_REWARDS - claimed);
+      
+      const rewardVaultAccount = await getAccount(provider.connection, secondaryRewardVault.publicKey);
+      assert.equal(Number(rewardVaultAccount.amount), POOL_REWARDS - claimed);
+    });
+  });
+
+  describe("Reward Emission Cap", () => {
+    const REWARD_CAP = 1000;
+    const CAPPED_STAKE_AMOUNT = 40000 * 10**6; // 40k tokens
+    
+    let cappedPool: PublicKey;
+    let cappedStakeVault: Keypair;
+    let cappedRewardVault: Keypair;
+    let userStakeAddress: PublicKey;
+    let authorityRewardAccount: PublicKey;
+    
+    const distribute = (amount: number) =>
+      program.methods
+        .distributeRewards(new anchor.BN(amount))
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: cappedPool,
+          authorityRewardAccount,
+          rewardVault: cappedRewardVault.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stakingAuthority])
+        .rpc();
+    
+    const claim = () =>
+      program.methods
+        .claimRewards()
+        .accounts({
+          user: staker2.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: cappedPool,
+          userStake: userStakeAddress,
+          userRewardAccount: staker2RewardAccount,
+          rewardVault: cappedRewardVault.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([staker2])
+        .rpc();
+    
+    before(async () => {
+      const stakingProgram = await program.account.stakingProgram.fetch(stakingProgramAddress);
+      const poolIndex = Buffer.alloc(4);
+      poolIndex.writeUInt32LE(stakingProgram.activePools);
+      [cappedPool] = await PublicKey.findProgramAddress(
+        [Buffer.from('stake_pool'), stakingProgramAddress.toBuffer(), poolIndex],
+        program.programId
+      );
+      [userStakeAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('user_stake'), cappedPool.toBuffer(), staker2.publicKey.toBuffer()],
+        program.programId
+      );
+      authorityRewardAccount = await anchor.utils.token.associatedAddress({
+        mint: rewardTokenMint,
+        owner: stakingAuthority.publicKey
+      });
+      
+      cappedStakeVault = Keypair.generate();
+      cappedRewardVault = Keypair.generate();
+      
+      await program.methods
+        .createStakePool({
+          rewardRateBps: 10000, // 100% APY so rewards quickly exceed the cap
+          minStakeAmount: new anchor.BN(100 * 10**6),
+          maxStakeAmount: new anchor.BN(1000000 * 10**6),
+          cooldownPeriod: null,
+          minStakingDuration: new anchor.BN(0),
+          acceptingStakes: true,
+          poolCapacity: new anchor.BN(0),
+          tierBonusEnabled: false,
+          maxTotalRewards: new anchor.BN(REWARD_CAP),
+          soulboundReceipts: false,
+          ticketBoost: null,
+          minCompoundInterval: new anchor.BN(0),
+        }, { general: {} })
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakeTokenMint: stakingTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          stakeVault: cappedStakeVault.publicKey,
+          rewardVault: cappedRewardVault.publicKey,
+          stakePool: cappedPool,
+          associatedEvent: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([stakingAuthority, cappedStakeVault, cappedRewardVault])
+        .rpc();
+      
+      await program.methods
+        .stakeTokens(new anchor.BN(CAPPED_STAKE_AMOUNT))
+        .accounts({
+          user: staker2.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: cappedPool,
+          userTokenAccount: staker2TokenAccount,
+          stakeVault: cappedStakeVault.publicKey,
+          userStake: userStakeAddress,
+          ...(await receiptAccountsFor(userStakeAddress, staker2.publicKey)),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([staker2])
+        .rpc();
+    });
+    
+    it("Distributes rewards under the cap", async () => {
+      await distribute(600);
+      
+      const stakePool = await program.account.stakePool.fetch(cappedPool);
+      assert.equal(stakePool.config.maxTotalRewards.toNumber(), REWARD_CAP);
+      assert.equal(stakePool.availableRewards.toNumber(), 600);
+    });
+    
+    it("Clamps a distribution that would exceed the cap", async () => {
+      const vaultBefore = await getAccount(provider.connection, cappedRewardVault.publicKey);
+      
+      await distribute(1000);
+      
+      // Only the 400 remaining under the cap is funded
+      const stakePool = await program.account.stakePool.fetch(cappedPool);
+      assert.equal(stakePool.availableRewards.toNumber(), REWARD_CAP);
+      const vaultAfter = await getAccount(provider.connection, cappedRewardVault.publicKey);
+      assert.equal(Number(vaultAfter.amount) - Number(vaultBefore.amount), 400);
+    });
+    
+    it("Rejects further distribution once the cap is reached", async () => {
+      try {
+        await distribute(1);
+        assert.fail("Distribution past the cap should fail");
+      } catch (error) {
+        assert.include(error.toString(), "RewardCapReached");
+      }
+    });
+    
+    it("Pays claims up to the cap and rejects claims after it is reached", async () => {
+      // Let accrued rewards outgrow the cap
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      
+      const balanceBefore = await getAccount(provider.connection, staker2RewardAccount);
+      await claim();
+      const balanceAfter = await getAccount(provider.connection, staker2RewardAccount);
+      assert.equal(Number(balanceAfter.amount) - Number(balanceBefore.amount), REWARD_CAP);
+      
+      const stakePool = await program.account.stakePool.fetch(cappedPool);
+      assert.equal(stakePool.totalRewardsDistributed.toNumber(), REWARD_CAP);
+      assert.equal(stakePool.availableRewards.toNumber(), 0);
+      
+      // Rewards beyond the cap stay pending but can never be paid out
+      const userStake = await program.account.userStake.fetch(userStakeAddress);
+      assert.isAbove(userStake.pendingRewards.toNumber(), 0);
+      
+      try {
+        await claim();
+        assert.fail("Claims after the cap is reached should fail");
+      } catch (error) {
+        assert.include(error.toString(), "RewardCapReached");
+      }
+    });
+  });
+
+  describe("Minimum Staking Duration", () => {
+    const LOCKED_STAKE_AMOUNT = 1000 * 10**6; // 1k tokens
+    const LOCK_DURATION = 3; // seconds
+    
+    let lockedPool: PublicKey;
+    let lockedStakeVault: Keypair;
+    let userStakeAddress: PublicKey;
+    
+    const stake = async () => {
+      const signature = await program.methods
+        .stakeTokens(new anchor.BN(LOCKED_STAKE_AMOUNT))
+        .accounts({
+          user: staker1.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: lockedPool,
+          userTokenAccount: staker1TokenAccount,
+          stakeVault: lockedStakeVault.publicKey,
+          userStake: userStakeAddress,
+          ...(await receiptAccountsFor(userStakeAddress, staker1.publicKey)),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([staker1])
+        .rpc({ commitment: 'confirmed' });
+      
+      const tx = await provider.connection.getTransaction(signature, { commitment: 'confirmed' });
+      return tx.blockTime;
+    };
+    
+    const unstake = (amount: number) =>
+      program.methods
+        .unstakeTokens(new anchor.BN(amount))
+        .accounts({
+          user: staker1.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: lockedPool,
+          userStake: userStakeAddress,
+        })
+        .signers([staker1])
+        .rpc();
+    
+    before(async () => {
+      const stakingProgram = await program.account.stakingProgram.fetch(stakingProgramAddress);
+      const poolIndex = Buffer.alloc(4);
+      poolIndex.writeUInt32LE(stakingProgram.activePools);
+      [lockedPool] = await PublicKey.findProgramAddress(
+        [Buffer.from('stake_pool'), stakingProgramAddress.toBuffer(), poolIndex],
+        program.programId
+      );
+      [userStakeAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('user_stake'), lockedPool.toBuffer(), staker1.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      lockedStakeVault = Keypair.generate();
+      const lockedRewardVault = Keypair.generate();
+      
+      await program.methods
+        .createStakePool({
+          rewardRateBps: 1200,
+          minStakeAmount: new anchor.BN(100 * 10**6),
+          maxStakeAmount: new anchor.BN(1000000 * 10**6),
+          cooldownPeriod: null,
+          minStakingDuration: new anchor.BN(LOCK_DURATION),
+          acceptingStakes: true,
+          poolCapacity: new anchor.BN(0),
+          tierBonusEnabled: false,
+          maxTotalRewards: new anchor.BN(0),
+          soulboundReceipts: false,
+          ticketBoost: null,
+          minCompoundInterval: new anchor.BN(0),
+        }, { general: {} })
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakeTokenMint: stakingTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          stakeVault: lockedStakeVault.publicKey,
+          rewardVault: lockedRewardVault.publicKey,
+          stakePool: lockedPool,
+          associatedEvent: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([stakingAuthority, lockedStakeVault, lockedRewardVault])
+        .rpc();
+    });
+    
+    it("Records the last stake time on every stake", async () => {
+      const firstStakeTime = await stake();
+      let userStake = await program.account.userStake.fetch(userStakeAddress);
+      assert.equal(userStake.lastStakeTime.toNumber(), firstStakeTime);
+      assert.equal(userStake.initialStakeTime.toNumber(), firstStakeTime);
+      
+      await new Promise(resolve => setTimeout(resolve, 2000));
+      
+      // A top-up restarts the minimum staking period
+      const secondStakeTime = await stake();
+      userStake = await program.account.userStake.fetch(userStakeAddress);
+      assert.isAbove(secondStakeTime, firstStakeTime);
+      assert.equal(userStake.lastStakeTime.toNumber(), secondStakeTime);
+      assert.equal(userStake.initialStakeTime.toNumber(), firstStakeTime);
+      assert.equal(userStake.stakedAmount.toNumber(), LOCKED_STAKE_AMOUNT * 2);
+    });
+    
+    it("Rejects unstaking before the minimum duration", async () => {
+      try {
+        await unstake(LOCKED_STAKE_AMOUNT);
+        assert.fail("Unstaking before the minimum duration should fail");
+      } catch (error) {
+        assert.include(error.toString(), "MinimumStakingPeriodNotMet");
+      }
+      
+      const userStake = await program.account.userStake.fetch(userStakeAddress);
+      assert.isNull(userStake.unstakeRequest);
+      assert.equal(userStake.stakedAmount.toNumber(), LOCKED_STAKE_AMOUNT * 2);
+    });
+    
+    it("Allows unstaking once the minimum duration has passed", async () => {
+      await new Promise(resolve => setTimeout(resolve, (LOCK_DURATION + 1) * 1000));
+      
+      await unstake(LOCKED_STAKE_AMOUNT);
+      
+      const userStake = await program.account.userStake.fetch(userStakeAddress);
+      assert.equal(userStake.unstakeRequest.amount.toNumber(), LOCKED_STAKE_AMOUNT);
+      assert.equal(userStake.stakedAmount.toNumber(), LOCKED_STAKE_AMOUNT);
+    });
+  });
+  
+  describe("Stake Receipts", () => {
+    const RECEIPT_STAKE_AMOUNT = 1000 * 10**6; // 1k tokens
+    
+    const createPool = async (soulboundReceipts: boolean) => {
+      const stakingProgram = await program.account.stakingProgram.fetch(stakingProgramAddress);
+      const poolIndex = Buffer.alloc(4);
+      poolIndex.writeUInt32LE(stakingProgram.activePools);
+      const [pool] = await PublicKey.findProgramAddress(
+        [Buffer.from('stake_pool'), stakingProgramAddress.toBuffer(), poolIndex],
+        program.programId
+      );
+      const [userStake] = await PublicKey.findProgramAddress(
+        [Buffer.from('user_stake'), pool.toBuffer(), staker2.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      const poolStakeVault = Keypair.generate();
+      const poolRewardVault = Keypair.generate();
+      
+      await program.methods
+        .createStakePool({
+          rewardRateBps: 1200,
+          minStakeAmount: new anchor.BN(100 * 10**6),
+          maxStakeAmount: new anchor.BN(1000000 * 10**6),
+          cooldownPeriod: new anchor.BN(0), // Withdrawable as soon as unstaked
+          minStakingDuration: new anchor.BN(0),
+          acceptingStakes: true,
+          poolCapacity: new anchor.BN(0),
+          tierBonusEnabled: false,
+          maxTotalRewards: new anchor.BN(0),
+          soulboundReceipts,
+          ticketBoost: null,
+          minCompoundInterval: new anchor.BN(0),
+        }, { general: {} })
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakeTokenMint: stakingTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          stakeVault: poolStakeVault.publicKey,
+          rewardVault: poolRewardVault.publicKey,
+          stakePool: pool,
+          associatedEvent: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([stakingAuthority, poolStakeVault, poolRewardVault])
+        .rpc();
+      
+      return {
+        pool,
+        stakeVault: poolStakeVault.publicKey,
+        userStake,
+        ...(await receiptAccountsFor(userStake, staker2.publicKey)),
+      };
+    };
+    
+    type ReceiptPool = Awaited<ReturnType<typeof createPool>>;
+    
+    const stake = (position: ReceiptPool) =>
+      program.methods
+        .stakeTokens(new anchor.BN(RECEIPT_STAKE_AMOUNT))
+        .accounts({
+          user: staker2.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: position.pool,
+          userTokenAccount: staker2TokenAccount,
+          stakeVault: position.stakeVault,
+          userStake: position.userStake,
+          receiptMint: position.receiptMint,
+          receiptTokenAccount: position.receiptTokenAccount,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([staker2])
+        .rpc();
+    
+    const unstake = (position: ReceiptPool) =>
+      program.methods
+        .unstakeTokens(new anchor.BN(RECEIPT_STAKE_AMOUNT))
+        .accounts({
+          user: staker2.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: position.pool,
+          userStake: position.userStake,
+        })
+        .signers([staker2])
+        .rpc();
+    
+    const withdraw = async (position: ReceiptPool, withReceipt = true, holder = staker2) =>
+      program.methods
+        .withdrawUnstaked()
+        .accounts({
+          user: holder.publicKey,
+          stakePool: position.pool,
+          userStake: position.userStake,
+          userTokenAccount: await getAssociatedTokenAddress(stakingTokenMint, holder.publicKey),
+          stakeVault: position.stakeVault,
+          receiptMint: withReceipt ? position.receiptMint : null,
+          receiptTokenAccount: withReceipt
+            ? await getAssociatedTokenAddress(position.receiptMint, holder.publicKey)
+            : null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([holder])
+        .rpc();
+    
+    describe("Transferable receipts", () => {
+      let position: ReceiptPool;
+      let staker1ReceiptAccount: PublicKey;
+      
+      before(async () => {
+        position = await createPool(false);
+      });
+      
+      it("Mints a receipt NFT referencing the stake", async () => {
+        await stake(position);
+        
+        const userStake = await program.account.userStake.fetch(position.userStake);
+        assert.equal(userStake.receiptMint.toString(), position.receiptMint.toString());
+        
+        const receipt = await getAccount(provider.connection, position.receiptTokenAccount);
+        assert.equal(Number(receipt.amount), 1);
+        assert.equal(receipt.owner.toString(), staker2.publicKey.toString());
+        assert.isFalse(receipt.isFrozen);
+        
+        const supply = await provider.connection.getTokenSupply(position.receiptMint);
+        assert.equal(supply.value.amount, '1');
+        assert.equal(supply.value.decimals, 0);
+      });
+      
+      it("Rejects a withdrawal without the receipt", async () => {
+        await unstake(position);
+        
+        try {
+          await withdraw(position, false);
+          assert.fail("Withdrawal without the receipt accounts should fail");
+        } catch (error) {
+          assert.include(error.toString(), "StakeReceiptRequired");
+        }
+        
+        // Transferring the receipt away hands off the right to withdraw
+        staker1ReceiptAccount = await createAssociatedTokenAccount(
+          provider.connection,
+          staker1,
+          position.receiptMint,
+          staker1.publicKey
+        );
+        await transfer(provider.connection, staker2, position.receiptTokenAccount, staker1ReceiptAccount, staker2, 1);
+        
+        try {
+          await withdraw(position);
+          assert.fail("Withdrawal after transferring the receipt away should fail");
+        } catch (error) {
+          assert.include(error.toString(), "StakeReceiptRequired");
+        }
+        
+        const userStake = await program.account.userStake.fetch(position.userStake);
+        assert.equal(userStake.unstakeRequest.amount.toNumber(), RECEIPT_STAKE_AMOUNT);
+      });
+      
+      it("Lets the receipt holder withdraw and burns the receipt", async () => {
+        const balanceBefore = Number((await getAccount(provider.connection, staker1TokenAccount)).amount);
+        
+        await withdraw(position, true, staker1);
+        
+        const balanceAfter = Number((await getAccount(provider.connection, staker1TokenAccount)).amount);
+        assert.equal(balanceAfter - balanceBefore, RECEIPT_STAKE_AMOUNT);
+        
+        const receipt = await getAccount(provider.connection, staker1ReceiptAccount);
+        assert.equal(Number(receipt.amount), 0);
+        const supply = await provider.connection.getTokenSupply(position.receiptMint);
+        assert.equal(supply.value.amount, '0');
+        
+        const userStake = await program.account.userStake.fetch(position.userStake);
+        assert.isNull(userStake.receiptMint);
+        assert.isNull(userStake.unstakeRequest);
+      });
+    });
+    
+    describe("Soulbound receipts", () => {
+      let position: ReceiptPool;
+      
+      before(async () => {
+        position = await createPool(true);
+      });
+      
+      it("Freezes the receipt in the staker's wallet", async () => {
+        await stake(position);
+        
+        const receipt = await getAccount(provider.connection, position.receiptTokenAccount);
+        assert.equal(Number(receipt.amount), 1);
+        assert.isTrue(receipt.isFrozen);
+        
+        const otherAccount = await createAssociatedTokenAccount(
+          provider.connection,
+          staker1,
+          position.receiptMint,
+          staker1.publicKey
+        );
+        try {
+          await transfer(provider.connection, staker2, position.receiptTokenAccount, otherAccount, staker2, 1);
+          assert.fail("Soulbound receipt should not be transferable");
+        } catch (error) {
+          assert.include(error.toString(), "0x11"); // TokenError::AccountFrozen
+        }
+      });
+      
+      it("Thaws and burns the receipt when the position is withdrawn", async () => {
+        await unstake(position);
+        await withdraw(position);
+        
+        const supply = await provider.connection.getTokenSupply(position.receiptMint);
+        assert.equal(supply.value.amount, '0');
+        
+        const userStake = await program.account.userStake.fetch(position.userStake);
+        assert.isNull(userStake.receiptMint);
+      });
+    });
+  });
+
+  describe("Batch Reward Distribution", () => {
+    const POOL_COUNT = 2;
+    const BATCH_AMOUNT = 500 * 10**6; // 500 reward tokens per pool
+    
+    type BatchPool = { stakePool: PublicKey; rewardVault: PublicKey };
+    const pools: BatchPool[] = [];
+    let authorityRewardAccount: PublicKey;
+    
+    const createPool = async (): Promise<BatchPool> => {
+      const stakingProgram = await program.account.stakingProgram.fetch(stakingProgramAddress);
+      const poolIndex = Buffer.alloc(4);
+      poolIndex.writeUInt32LE(stakingProgram.activePools);
+      const [stakePool] = await PublicKey.findProgramAddress(
+        [Buffer.from('stake_pool'), stakingProgramAddress.toBuffer(), poolIndex],
+        program.programId
+      );
+      const poolStakeVault = Keypair.generate();
+      const poolRewardVault = Keypair.generate();
+      
+      await program.methods
+        .createStakePool({
+          rewardRateBps: 1000,
+          minStakeAmount: new anchor.BN(100 * 10**6),
+          maxStakeAmount: new anchor.BN(1000000 * 10**6),
+          cooldownPeriod: null,
+          minStakingDuration: new anchor.BN(0),
+          acceptingStakes: true,
+          poolCapacity: new anchor.BN(0),
+          tierBonusEnabled: false,
+          maxTotalRewards: new anchor.BN(0),
+          soulboundReceipts: false,
+          ticketBoost: null,
+          minCompoundInterval: new anchor.BN(0),
+        }, { general: {} })
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakeTokenMint: stakingTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          stakeVault: poolStakeVault.publicKey,
+          rewardVault: poolRewardVault.publicKey,
+          stakePool,
+          associatedEvent: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([stakingAuthority, poolStakeVault, poolRewardVault])
+        .rpc();
+      
+      return { stakePool, rewardVault: poolRewardVault.publicKey };
+    };
+    
+    // Each pool is passed as its stake pool, the reward source and its reward vault
+    const batchDistribute = (amounts: number[]) =>
+      program.methods
+        .batchDistributeRewards(amounts.map(amount => new anchor.BN(amount)))
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(pools.flatMap(({ stakePool, rewardVault }) => [
+          { pubkey: stakePool, isWritable: true, isSigner: false },
+          { pubkey: authorityRewardAccount, isWritable: true, isSigner: false },
+          { pubkey: rewardVault, isWritable: true, isSigner: false },
+        ]))
+        .signers([stakingAuthority])
+        .rpc();
+    
+    before(async () => {
+      authorityRewardAccount = await anchor.utils.token.associatedAddress({
+        mint: rewardTokenMint,
+        owner: stakingAuthority.publicKey
+      });
+      for (let i = 0; i < POOL_COUNT; i++) {
+        pools.push(await createPool());
+      }
+    });
+    
+    it("Funds every pool in one transaction", async () => {
+      const distributed: { poolId: number; amount: number }[] = [];
+      const listener = program.addEventListener("RewardsDistributed", (event) => {
+        distributed.push({ poolId: event.poolId, amount: event.amount.toNumber() });
+      });
+      
+      await batchDistribute([BATCH_AMOUNT, BATCH_AMOUNT * 2]);
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+      
+      const amounts = [BATCH_AMOUNT, BATCH_AMOUNT * 2];
+      for (const [i, { stakePool, rewardVault }] of pools.entries()) {
+        const pool = await program.account.stakePool.fetch(stakePool);
+        assert.equal(pool.availableRewards.toNumber(), amounts[i]);
+        const vault = await getAccount(provider.connection, rewardVault);
+        assert.equal(Number(vault.amount), amounts[i]);
+        assert.deepInclude(distributed, { poolId: pool.poolId, amount: amounts[i] });
+      }
+    });
+    
+    it("Reverts every pool when one of them is underfunded", async () => {
+      const source = await getAccount(provider.connection, authorityRewardAccount);
+      const poolsBefore = await Promise.all(pools.map(({ stakePool }) => program.account.stakePool.fetch(stakePool)));
+      
+      try {
+        // The second pool asks for more than the source has left
+        await batchDistribute([BATCH_AMOUNT, Number(source.amount)]);
+        assert.fail("Underfunded batch should fail");
+      } catch (error) {
+        assert.include(error.toString(), "InsufficientRewards");
+      }
+      
+      // The first pool's transfer was rolled back with the rest
+      for (const [i, { stakePool }] of pools.entries()) {
+        const pool = await program.account.stakePool.fetch(stakePool);
+        assert.equal(pool.availableRewards.toNumber(), poolsBefore[i].availableRewards.toNumber());
+      }
+      const sourceAfter = await getAccount(provider.connection, authorityRewardAccount);
+      assert.equal(sourceAfter.amount.toString(), source.amount.toString());
+    });
+  });
+
+  describe("Ticket Holder Boost", () => {
+    const BOOST_STAKE_AMOUNT = 20000 * 10**6; // 20k tokens
+    const BOOST_REWARDS = 100000 * 10**6; // 100k reward tokens
+    const BONUS_BPS = 5000; // +50%
+    
+    let boostPool: PublicKey;
+    let boostStakeVault: Keypair;
+    let boostRewardVault: Keypair;
+    let userStakeAddress: PublicKey;
+    let collectionMint: PublicKey;
+    let ticketMint: PublicKey;
+    let staker1TicketAccount: PublicKey;
+    
+    const metadataFor = (mint: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('metadata'), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        METADATA_PROGRAM_ID
+      )[0];
+    
+    const editionFor = (mint: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('metadata'), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from('edition')],
+        METADATA_PROGRAM_ID
+      )[0];
+    
+    // Mints an NFT to `owner` with Metaplex metadata naming `collection`, if any
+    const mintNft = async (owner: PublicKey, collection: PublicKey | null) => {
+      const mint = await createMint(provider.connection, stakingAuthority, stakingAuthority.publicKey, null, 0);
+      const tokenAccount = await createAssociatedTokenAccount(provider.connection, stakingAuthority, mint, owner);
+      await mintTo(provider.connection, stakingAuthority, mint, tokenAccount, stakingAuthority.publicKey, 1);
+      
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          createCreateMetadataAccountV3Instruction(
+            {
+              metadata: metadataFor(mint),
+              mint,
+              mintAuthority: stakingAuthority.publicKey,
+              payer: stakingAuthority.publicKey,
+              updateAuthority: stakingAuthority.publicKey,
+            },
+            {
+              createMetadataAccountArgsV3: {
+                data: {
+                  name: 'Event Ticket',
+                  symbol: 'TIX',
+                  uri: 'https://ticket.uri',
+                  sellerFeeBasisPoints: 0,
+                  creators: null,
+                  collection: collection ? { key: collection, verified: false } : null,
+                  uses: null,
+                },
+                isMutable: true,
+                collectionDetails: null,
+              },
+            }
+          )
+        ),
+        [stakingAuthority]
+      );
+      
+      return { mint, tokenAccount };
+    };
+    
+    // Mints a ticket in the qualifying collection to `owner`, verified by the collection authority
+    const mintCollectionTicket = async (owner: PublicKey) => {
+      const ticket = await mintNft(owner, collectionMint);
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          createVerifyCollectionInstruction({
+            metadata: metadataFor(ticket.mint),
+            collectionAuthority: stakingAuthority.publicKey,
+            payer: stakingAuthority.publicKey,
+            collectionMint,
+            collection: metadataFor(collectionMint),
+            collectionMasterEditionAccount: editionFor(collectionMint),
+          })
+        ),
+        [stakingAuthority]
+      );
+      return ticket;
+    };
+    
+    // Claims for staker1, optionally presenting a ticket token account and its
+    // metadata; returns the bonus granted
+    const claim = async (ticketAccount: PublicKey | null, ticketMetadata: PublicKey = metadataFor(ticketMint)) => {
+      let bonus: number | null = null;
+      const listener = program.addEventListener("TicketBoostApplied", (event) => {
+        if (event.user.equals(staker1.publicKey)) {
+          bonus = event.bonus.toNumber();
+        }
+      });
+      
+      await program.methods
+        .claimRewards()
+        .accounts({
+          user: staker1.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: boostPool,
+          userStake: userStakeAddress,
+          userRewardAccount: staker1RewardAccount,
+          rewardVault: boostRewardVault.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          ticketAccount
+            ? [
+                { pubkey: ticketAccount, isWritable: false, isSigner: false },
+                { pubkey: ticketMetadata, isWritable: false, isSigner: false },
+              ]
+            : []
+        )
+        .signers([staker1])
+        .rpc();
+      
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+      return bonus;
+    };
+    
+    before(async () => {
+      // The event's ticket collection, and a ticket in it held by staker1
+      collectionMint = (await mintNft(stakingAuthority.publicKey, null)).mint;
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          createCreateMasterEditionV3Instruction(
+            {
+              edition: editionFor(collectionMint),
+              mint: collectionMint,
+              updateAuthority: stakingAuthority.publicKey,
+              mintAuthority: stakingAuthority.publicKey,
+              payer: stakingAuthority.publicKey,
+              metadata: metadataFor(collectionMint),
+            },
+            { createMasterEditionArgs: { maxSupply: 0 } }
+          )
+        ),
+        [stakingAuthority]
+      );
+      ({ mint: ticketMint, tokenAccount: staker1TicketAccount } = await mintCollectionTicket(staker1.publicKey));
+      
+      const stakingProgram = await program.account.stakingProgram.fetch(stakingProgramAddress);
+      const poolIndex = Buffer.alloc(4);
+      poolIndex.writeUInt32LE(stakingProgram.activePools);
+      [boostPool] = await PublicKey.findProgramAddress(
+        [Buffer.from('stake_pool'), stakingProgramAddress.toBuffer(), poolIndex],
+        program.programId
+      );
+      [userStakeAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('user_stake'), boostPool.toBuffer(), staker1.publicKey.toBuffer()],
+        program.programId
+      );
+      boostStakeVault = Keypair.generate();
+      boostRewardVault = Keypair.generate();
+      
+      await program.methods
+        .createStakePool({
+          rewardRateBps: 10000,
+          minStakeAmount: new anchor.BN(100 * 10**6),
+          maxStakeAmount: new anchor.BN(1000000 * 10**6),
+          cooldownPeriod: null,
+          minStakingDuration: new anchor.BN(0),
+          acceptingStakes: true,
+          poolCapacity: new anchor.BN(0),
+          tierBonusEnabled: false,
+          maxTotalRewards: new anchor.BN(0),
+          soulboundReceipts: false,
+          ticketBoost: { qualifyingCollection: collectionMint, bonusBps: BONUS_BPS },
+          minCompoundInterval: new anchor.BN(0),
+        }, { eventSpecific: {} })
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakeTokenMint: stakingTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          stakeVault: boostStakeVault.publicKey,
+          rewardVault: boostRewardVault.publicKey,
+          stakePool: boostPool,
+          associatedEvent: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([stakingAuthority, boostStakeVault, boostRewardVault])
+        .rpc();
+      
+      await mintTo(provider.connection, stakingAuthority, stakingTokenMint, staker1TokenAccount, stakingAuthority.publicKey, BOOST_STAKE_AMOUNT);
+      await program.methods
+        .stakeTokens(new anchor.BN(BOOST_STAKE_AMOUNT))
+        .accounts({
+          user: staker1.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: boostPool,
+          userTokenAccount: staker1TokenAccount,
+          stakeVault: boostStakeVault.publicKey,
+          userStake: userStakeAddress,
+          ...(await receiptAccountsFor(userStakeAddress, staker1.publicKey)),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([staker1])
+        .rpc();
+      
+      const authorityRewardAccount = await getAssociatedTokenAddress(rewardTokenMint, stakingAuthority.publicKey);
+      await mintTo(provider.connection, stakingAuthority, rewardTokenMint, authorityRewardAccount, stakingAuthority.publicKey, BOOST_REWARDS);
+      await program.methods
+        .distributeRewards(new anchor.BN(BOOST_REWARDS))
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: boostPool,
+          authorityRewardAccount,
+          rewardVault: boostRewardVault.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stakingAuthority])
+        .rpc();
+    });
+    
+    it("Boosts a claim while the staker holds the qualifying ticket", async () => {
+      // Let rewards accrue
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      
+      const balanceBefore = await getAccount(provider.connection, staker1RewardAccount);
+      const bonus = await claim(staker1TicketAccount);
+      const balanceAfter = await getAccount(provider.connection, staker1RewardAccount);
+      const claimed = Number(balanceAfter.amount) - Number(balanceBefore.amount);
+      
+      assert.isNotNull(bonus);
+      assert.isAbove(bonus, 0);
+      // The bonus is half of the base rewards it was paid on
+      assert.approximately(bonus, (claimed - bonus) * BONUS_BPS / 10000, 1);
+    });
+    
+    it("Pays no boost when no ticket is presented", async () => {
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      
+      assert.isNull(await claim(null));
+    });
+    
+    it("Boosts any ticket in the collection, but not one outside it or unverified", async () => {
+      const otherTicket = await mintCollectionTicket(staker1.publicKey);
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      assert.isNotNull(await claim(otherTicket.tokenAccount, metadataFor(otherTicket.mint)));
+      
+      // A ticket that only claims the collection without its authority's verification
+      const unverifiedTicket = await mintNft(staker1.publicKey, collectionMint);
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      assert.isNull(await claim(unverifiedTicket.tokenAccount, metadataFor(unverifiedTicket.mint)));
+      
+      // Nor does presenting a qualifying ticket's metadata for an unrelated NFT
+      const strayNft = await mintNft(staker1.publicKey, null);
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      assert.isNull(await claim(strayNft.tokenAccount, metadataFor(ticketMint)));
+    });
+    
+    it("Removes the boost once the ticket is transferred away", async () => {
+      const staker2TicketAccount = await createAssociatedTokenAccount(provider.connection, staker2, ticketMint, staker2.publicKey);
+      await transfer(provider.connection, staker1, staker1TicketAccount, staker2TicketAccount, staker1, 1);
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      
+      // staker1's emptied account no longer qualifies
+      assert.isNull(await claim(staker1TicketAccount));
+      
+      // Nor does presenting someone else's ticket
+      await new Promise(resolve => setTimeout(resolve, 3000));
+      assert.isNull(await claim(staker2TicketAccount));
+    });
+    
+    it("Rejects a boost above 100%", async () => {
+      const pool = await program.account.stakePool.fetch(boostPool);
+      try {
+        await program.methods
+          .updateStakePool({ ...pool.config, ticketBoost: { qualifyingCollection: collectionMint, bonusBps: 10001 } })
+          .accounts({
+            authority: stakingAuthority.publicKey,
+            stakingProgram: stakingProgramAddress,
+            stakePool: boostPool,
+          })
+          .signers([stakingAuthority])
+          .rpc();
+        assert.fail("Boost above 100% should be rejected");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidStakePoolConfig");
+      }
+    });
+  });
+  
+  describe("Reward Compounding", () => {
+    const COMPOUND_STAKE_AMOUNT = 20000 * 10**6; // 20k tokens
+    const COMPOUND_REWARDS = 100000 * 10**6; // 100k reward tokens
+    const MIN_COMPOUND_INTERVAL = 3; // seconds
+    const TIER_MULTIPLIERS = { bronze: 100, silver: 110, gold: 125, platinum: 150, diamond: 200 };
+    
+    let compoundPool: PublicKey;
+    let compoundStakeVault: Keypair;
+    let compoundRewardVault: Keypair;
+    let userStakeAddress: PublicKey;
+    
+    const compound = () =>
+      program.methods
+        .compoundRewards()
+        .accounts({
+          user: staker2.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: compoundPool,
+          userStake: userStakeAddress,
+          stakeVault: compoundStakeVault.publicKey,
+          rewardVault: compoundRewardVault.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([staker2])
+        .rpc();
+    
+    before(async () => {
+      const stakingProgram = await program.account.stakingProgram.fetch(stakingProgramAddress);
+      const poolIndex = Buffer.alloc(4);
+      poolIndex.writeUInt32LE(stakingProgram.activePools);
+      [compoundPool] = await PublicKey.findProgramAddress(
+        [Buffer.from('stake_pool'), stakingProgramAddress.toBuffer(), poolIndex],
+        program.programId
+      );
+      [userStakeAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('user_stake'), compoundPool.toBuffer(), staker2.publicKey.toBuffer()],
+        program.programId
+      );
+      compoundStakeVault = Keypair.generate();
+      compoundRewardVault = Keypair.generate();
+      
+      // Rewards are paid in the staked token, so they can be compounded
+      await program.methods
+        .createStakePool({
+          rewardRateBps: 10000,
+          minStakeAmount: new anchor.BN(100 * 10**6),
+          maxStakeAmount: new anchor.BN(1000000 * 10**6),
+          cooldownPeriod: null,
+          minStakingDuration: new anchor.BN(0),
+          acceptingStakes: true,
+          poolCapacity: new anchor.BN(0),
+          tierBonusEnabled: false,
+          maxTotalRewards: new anchor.BN(0),
+          soulboundReceipts: false,
+          ticketBoost: null,
+          minCompoundInterval: new anchor.BN(MIN_COMPOUND_INTERVAL),
+        }, { general: {} })
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakeTokenMint: stakingTokenMint,
+          rewardTokenMint: stakingTokenMint,
+          stakeVault: compoundStakeVault.publicKey,
+          rewardVault: compoundRewardVault.publicKey,
+          stakePool: compoundPool,
+          associatedEvent: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([stakingAuthority, compoundStakeVault, compoundRewardVault])
+        .rpc();
+      
+      await mintTo(provider.connection, stakingAuthority, stakingTokenMint, staker2TokenAccount, stakingAuthority.publicKey, COMPOUND_STAKE_AMOUNT);
+      await program.methods
+        .stakeTokens(new anchor.BN(COMPOUND_STAKE_AMOUNT))
+        .accounts({
+          user: staker2.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: compoundPool,
+          userTokenAccount: staker2TokenAccount,
+          stakeVault: compoundStakeVault.publicKey,
+          userStake: userStakeAddress,
+          ...(await receiptAccountsFor(userStakeAddress, staker2.publicKey)),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([staker2])
+        .rpc();
+      
+      const authorityRewardAccount = await getAssociatedTokenAddress(stakingTokenMint, stakingAuthority.publicKey);
+      await mintTo(provider.connection, stakingAuthority, stakingTokenMint, authorityRewardAccount, stakingAuthority.publicKey, COMPOUND_REWARDS);
+      await program.methods
+        .distributeRewards(new anchor.BN(COMPOUND_REWARDS))
+        .accounts({
+          authority: stakingAuthority.publicKey,
+          stakingProgram: stakingProgramAddress,
+          stakePool: compoundPool,
+          authorityRewardAccount,
+          rewardVault: compoundRewardVault.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stakingAuthority])
+        .rpc();
+    });
+    
+    it("Compounds pending rewards into the stake", async () => {
+      // Let rewards accrue
+      await new Promise(resolve => setTimeout(resolve, (MIN_COMPOUND_INTERVAL + 1) * 1000));
+      
+      const vaultBefore = await getAccount(provider.connection, compoundStakeVault.publicKey);
+      await compound();
+      const vaultAfter = await getAccount(provider.connection, compoundStakeVault.publicKey);
+      const compounded = Number(vaultAfter.amount) - Number(vaultBefore.amount);
+      
+      assert.isAbove(compounded, 0);
+      const userStake = await program.account.userStake.fetch(userStakeAddress);
+      assert.equal(userStake.stakedAmount.toNumber(), COMPOUND_STAKE_AMOUNT + compounded);
+      assert.equal(userStake.pendingRewards.toNumber(), 0);
+      assert.isAbove(userStake.lastCompoundAt.toNumber(), 0);
+      
+      const pool = await program.account.stakePool.fetch(compoundPool);
+      assert.equal(pool.totalStaked.toNumber(), COMPOUND_STAKE_AMOUNT + compounded);
+    });
+    
+    it("Rejects a compound before the interval elapses", async () => {
+      const stakeBefore = await program.account.userStake.fetch(userStakeAddress);
+      
+      try {
+        await compound();
+        assert.fail("Compound within the interval should be rejected");
+      } catch (error) {
+        assert.include(error.toString(), "CompoundTooSoon");
+      }
+      
+      const stakeAfter = await program.account.userStake.fetch(userStakeAddress);
+      assert.equal(stakeAfter.stakedAmount.toString(), stakeBefore.stakedAmount.toString());
+    });
+    
+    it("Accrues rewards on the compounded stake between compounds", async () => {
+      await new Promise(resolve => setTimeout(resolve, (MIN_COMPOUND_INTERVAL + 1) * 1000));
+      
+      const stakeBefore = await program.account.userStake.fetch(userStakeAddress);
+      await compound();
+      const stakeAfter = await program.account.userStake.fetch(userStakeAddress);
+      
+      // Rewards since the last compound are earned on the grown stake, with the tier bonus
+      const rewardPerTokenDelta = stakeAfter.rewardPerTokenPaid.sub(stakeBefore.rewardPerTokenPaid);
+      const earned = stakeBefore.stakedAmount.mul(rewardPerTokenDelta).div(new anchor.BN(10).pow(new anchor.BN(12)));
+      const multiplier = TIER_MULTIPLIERS[Object.keys(stakeBefore.stakingTier)[0]];
+      const expected = earned.muln(multiplier).divn(100);
+      
+      assert.equal(stakeAfter.stakedAmount.sub(stakeBefore.stakedAmount).toString(), expected.toString());
+      assert.isAtLeast(
+        stakeAfter.lastCompoundAt.toNumber(),
+        stakeBefore.lastCompoundAt.toNumber() + MIN_COMPOUND_INTERVAL
+      );
+    });
+  });
 });
@@ -421,10 +421,11 @@ describe('ticket-minter-error-cases', () => {
       
       // Mint ticket
       await program.methods
-        .mintTicket(metadataUri, null)
+        .mintTicket(metadataUri, null, null)
         .accounts({
           event: eventPda,
           ticketType: ticketTypePda,
+          kycRegistry: null,
           mint: mintKeypair.publicKey,
           ticketMintAuthority: mintAuthority,
           tokenAccount,
@@ -506,10 +507,11 @@ describe('ticket-minter-error-cases', () => {
       try {
         // Mint ticket for past event
         await program.methods
-          .mintTicket(metadataUri, null)
+          .mintTicket(metadataUri, null, null)
           .accounts({
             event: pastEventPda,
             ticketType: pastTicketTypePda,
+            kycRegistry: null,
             mint: pastEventMintKeypair.publicKey,
             ticketMintAuthority: mintAuthority,
             tokenAccount,
@@ -579,10 +581,11 @@ describe('ticket-minter-error-cases', () => {
         
         // Mint ticket
         await program.methods
-          .mintTicket('https://tickettoken.app/metadata/ticket-' + i + '.json', null)
+          .mintTicket('https://tickettoken.app/metadata/ticket-' + i + '.json', null, null)
           .accounts({
             event: eventPda,
             ticketType: ticketTypePda,
+            kycRegistry: null,
             mint: mintKeypair.publicKey,
             ticketMintAuthority: mintAuthority,
             tokenAccount,
@@ -646,10 +649,11 @@ describe('ticket-minter-error-cases', () => {
       try {
         // Try to mint one more ticket
         await program.methods
-          .mintTicket('https://tickettoken.app/metadata/sold-out-ticket.json', null)
+          .mintTicket('https://tickettoken.app/metadata/sold-out-ticket.json', null, null)
           .accounts({
             event: eventPda,
             ticketType: ticketTypePda,
+            kycRegistry: null,
             mint: soldOutMintKeypair.publicKey,
             ticketMintAuthority: mintAuthority,
             tokenAccount,
@@ -722,6 +726,7 @@ describe('ticket-minter-error-cases', () => {
           .accounts({
             event: eventPda,
             ticket: ticketPda,
+            ticketType: ticketTypePda,
             organizer: unauthorizedUser.publicKey, // Not the organizer
           })
           .signers([unauthorizedUser])
@@ -739,6 +744,7 @@ describe('ticket-minter-error-cases', () => {
         .accounts({
           event: eventPda,
           ticket: ticketPda,
+          ticketType: ticketTypePda,
           organizer: eventOrganizer.publicKey,
         })
         .signers([eventOrganizer])
@@ -781,11 +787,14 @@ describe('ticket-minter-error-cases', () => {
         .transferTicket()
         .accounts({
           ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
           mint: mintKeypair.publicKey,
           fromTokenAccount,
           toTokenAccount,
           from: buyer.publicKey,
           to: unauthorizedUser.publicKey,
+          event: eventPda,
+          kycRegistry: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           paymentAmount: 0,
         })
@@ -815,6 +824,7 @@ describe('ticket-minter-error-cases', () => {
         .accounts({
           event: eventPda,
           ticket: ticketPda,
+          ticketType: ticketTypePda,
           organizer: eventOrganizer.publicKey,
         })
         .signers([eventOrganizer])
@@ -838,6 +848,8 @@ describe('ticket-minter-error-cases', () => {
         )
         .accounts({
           ticket: ticketPda,
+          event: (await program.account.ticket.fetch(ticketPda)).event,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
           listing: listingPda,
           owner: buyer.publicKey,
           systemProgram: SystemProgram.programId,
@@ -275,10 +275,11 @@ describe('ticket-minter-performance', () => {
           const startTime = performance.now();
           
           await program.methods
-            .mintTicket(metadataUri, null)
+            .mintTicket(metadataUri, null, null)
             .accounts({
               event: event.eventPda,
               ticketType: ticketType.typePda,
+              kycRegistry: null,
               mint: mintKeypair.publicKey,
               ticketMintAuthority: mintAuthority,
               tokenAccount,
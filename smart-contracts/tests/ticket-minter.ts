@@ -8,15 +8,22 @@ import {
   SYSVAR_RENT_PUBKEY,
   Transaction,
   sendAndConfirmTransaction,
+  ComputeBudgetProgram,
 } from '@solana/web3.js';
 import {
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddress,
   createAssociatedTokenAccountInstruction,
+  createAssociatedTokenAccount,
+  createMint,
+  mintTo,
   getAccount,
+  getOrCreateAssociatedTokenAccount,
+  transfer,
 } from '@solana/spl-token';
 import { assert } from 'chai';
+import { createHash } from 'crypto';
 
 describe('ticket-minter', () => {
   // Configure the client to use the local cluster
@@ -61,6 +68,7 @@ describe('ticket-minter', () => {
   let masterEditionAccount: PublicKey;
   let mintKeypair: Keypair;
   let secondMintKeypair: Keypair;
+  let acceptedPaymentMintsPda: PublicKey;
   
   // Token metadata program ID
   const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
@@ -84,6 +92,11 @@ describe('ticket-minter', () => {
     
     // Wait for confirmations
     await new Promise((resolve) => setTimeout(resolve, 2000));
+    
+    [acceptedPaymentMintsPda] = await PublicKey.findProgramAddress(
+      [Buffer.from('accepted_payment_mints')],
+      program.programId
+    );
   });
   
   describe('Event Management', () => {
@@ -279,10 +292,11 @@ describe('ticket-minter', () => {
       
       // Mint ticket
       await program.methods
-        .mintTicket(metadataUri, null)
+        .mintTicket(metadataUri, null, null)
         .accounts({
           event: eventPda,
           ticketType: ticketTypePda,
+          kycRegistry: null,
           mint: mintKeypair.publicKey,
           ticketMintAuthority,
           tokenAccount,
@@ -372,10 +386,11 @@ describe('ticket-minter', () => {
       
       // Mint ticket
       await program.methods
-        .mintTicket(metadataUri, null)
+        .mintTicket(metadataUri, null, null)
         .accounts({
           event: eventPda,
           ticketType: ticketTypePda,
+          kycRegistry: null,
           mint: secondMintKeypair.publicKey,
           ticketMintAuthority: mintAuthority,
           tokenAccount,
@@ -483,6 +498,8 @@ describe('ticket-minter', () => {
         )
         .accounts({
           ticket: secondTicketPda,
+          event: (await program.account.ticket.fetch(secondTicketPda)).event,
+          ticketType: (await program.account.ticket.fetch(secondTicketPda)).ticketType,
           listing: transferListingPda,
           owner: buyer.publicKey,
           systemProgram: SystemProgram.programId,
@@ -560,11 +577,14 @@ describe('ticket-minter', () => {
         .transferTicket()
         .accounts({
           ticket: secondTicketPda,
+          ticketType: (await program.account.ticket.fetch(secondTicketPda)).ticketType,
           mint: secondMintKeypair.publicKey,
           fromTokenAccount,
           toTokenAccount,
           from: buyer.publicKey,
           to: secondBuyer.publicKey,
+          event: eventPda,
+          kycRegistry: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           paymentAmount: 0,
         })
@@ -652,6 +672,8 @@ describe('ticket-minter', () => {
         )
         .accounts({
           ticket: secondTicketPda,
+          event: (await program.account.ticket.fetch(secondTicketPda)).event,
+          ticketType: (await program.account.ticket.fetch(secondTicketPda)).ticketType,
           listing: listingPda,
           owner: secondBuyer.publicKey,
           systemProgram: SystemProgram.programId,
@@ -683,6 +705,7 @@ describe('ticket-minter', () => {
         .accounts({
           event: eventPda,
           ticket: secondTicketPda,
+          ticketType: ticketTypePda,
           organizer: eventOrganizer.publicKey,
         })
         .signers([eventOrganizer])
@@ -733,11 +756,14 @@ describe('ticket-minter', () => {
         .transferTicket()
         .accounts({
           ticket: secondTicketPda,
+          ticketType: (await program.account.ticket.fetch(secondTicketPda)).ticketType,
           mint: secondMintKeypair.publicKey,
           fromTokenAccount,
           toTokenAccount,
           from: secondBuyer.publicKey,
           to: buyer.publicKey,
+          event: eventPda,
+          kycRegistry: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           paymentAmount: 0,
         })
@@ -773,6 +799,7 @@ describe('ticket-minter', () => {
         .accounts({
           event: eventPda,
           ticket: secondTicketPda,
+          ticketType: ticketTypePda,
           organizer: eventOrganizer.publicKey,
         })
         .signers([eventOrganizer])
@@ -848,4 +875,4674 @@ describe('ticket-minter', () => {
       assert.isNotEmpty(challengeAccount.challengeData);
     });
   });
+
+  describe('Marketplace Relist Cooldown', () => {
+    const relistCooldown = 3; // seconds
+    
+    const listingPdaFor = async (ticket: PublicKey, listingId: string) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticket.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      return listingPda;
+    };
+    
+    const lastListingAttemptPdaFor = async (mint: PublicKey) => {
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mint.toBuffer()],
+        program.programId
+      );
+      return attemptPda;
+    };
+    
+    const createAuction = async (seller: PublicKey, listingId: string, signers: Keypair[] = []) => {
+      const listingPda = await listingPdaFor(ticketPda, listingId);
+      const [auctionHistoryPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('auction_history'), listingPda.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createAuction(listingId, new anchor.BN(1000000000), new anchor.BN(100000000), new anchor.BN(3600))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: auctionHistoryPda,
+          lastListingAttempt: await lastListingAttemptPdaFor(mintKeypair.publicKey),
+          event: eventPda,
+          owner: seller,
+          systemProgram: SystemProgram.programId,
+          paymentMint: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers(signers)
+        .rpc();
+      
+      return listingPda;
+    };
+    
+    const createListing = async (seller: PublicKey, listingId: string, signers: Keypair[] = []) => {
+      const listingPda = await listingPdaFor(ticketPda, listingId);
+      
+      await program.methods
+        .createListing(listingId, new anchor.BN(1500000000))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: await lastListingAttemptPdaFor(mintKeypair.publicKey),
+          event: eventPda,
+          owner: seller,
+          systemProgram: SystemProgram.programId,
+          paymentMint: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers(signers)
+        .rpc();
+      
+      return listingPda;
+    };
+    
+    const cancelListing = async (listingPda: PublicKey, seller: PublicKey, signers: Keypair[] = []) => {
+      await program.methods
+        .cancelListing()
+        .accounts({
+          ticket: ticketPda,
+          listing: listingPda,
+          lastListingAttempt: await lastListingAttemptPdaFor(mintKeypair.publicKey),
+          owner: seller,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(signers)
+        .rpc();
+    };
+    
+    before(async () => {
+      await program.methods
+        .setRelistCooldown(new anchor.BN(relistCooldown))
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.equal(eventAccount.relistCooldown.toNumber(), relistCooldown);
+    });
+    
+    it('Rejects a relist during the cooldown after canceling an auction', async () => {
+      const auctionPda = await createAuction(buyer.publicKey, 'cooldown-auction-1');
+      await cancelListing(auctionPda, buyer.publicKey);
+      
+      const attempt = await program.account.lastListingAttempt.fetch(
+        await lastListingAttemptPdaFor(mintKeypair.publicKey)
+      );
+      assert.equal(attempt.seller.toString(), buyer.publicKey.toString());
+      assert.isNotNull(attempt.auctionCanceledAt);
+      
+      try {
+        await createListing(buyer.publicKey, 'cooldown-relist-1');
+        assert.fail('Relist during cooldown should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'RelistCooldownActive');
+      }
+    });
+    
+    it('Allows a relist once the cooldown has elapsed', async () => {
+      // Wait out the cooldown started by the previous test
+      await new Promise((resolve) => setTimeout(resolve, (relistCooldown + 1) * 1000));
+      
+      const listingPda = await createListing(buyer.publicKey, 'cooldown-relist-2');
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.deepEqual(listingAccount.status, { active: {} });
+      
+      // Canceling a fixed-price listing does not start a cooldown
+      await cancelListing(listingPda, buyer.publicKey);
+    });
+    
+    it('Does not apply the cooldown to a different seller', async () => {
+      const auctionPda = await createAuction(buyer.publicKey, 'cooldown-auction-2');
+      await cancelListing(auctionPda, buyer.publicKey);
+      
+      // Hand the ticket to another seller while the cooldown is still running
+      const fromTokenAccount = await getAssociatedTokenAddress(mintKeypair.publicKey, buyer.publicKey);
+      const toTokenAccount = await getAssociatedTokenAddress(mintKeypair.publicKey, secondBuyer.publicKey);
+      
+      const transaction = new Transaction();
+      if (!(await provider.connection.getAccountInfo(toTokenAccount))) {
+        transaction.add(
+          createAssociatedTokenAccountInstruction(
+            buyer.publicKey,
+            toTokenAccount,
+            secondBuyer.publicKey,
+            mintKeypair.publicKey
+          )
+        );
+      }
+      transaction.add(
+        await program.methods
+          .transferTicket()
+          .accounts({
+            ticket: ticketPda,
+            ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+            mint: mintKeypair.publicKey,
+            fromTokenAccount,
+            toTokenAccount,
+            from: buyer.publicKey,
+            to: secondBuyer.publicKey,
+            event: eventPda,
+            kycRegistry: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .instruction()
+      );
+      await sendAndConfirmTransaction(provider.connection, transaction, [buyer.payer]);
+      
+      const listingPda = await createListing(secondBuyer.publicKey, 'cooldown-relist-3', [secondBuyer]);
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.owner.toString(), secondBuyer.publicKey.toString());
+    });
+  });
+
+  describe('Marketplace Price Floor', () => {
+    const minListingPrice = new anchor.BN(1000000000); // 1 SOL
+    
+    // The ticket ended the relist cooldown tests owned by secondBuyer
+    const listTicket = async (listingId: string, price: anchor.BN) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createListing(listingId, price)
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          owner: secondBuyer.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([secondBuyer])
+        .rpc();
+      
+      return listingPda;
+    };
+    
+    const setFloor = async (price: anchor.BN) => {
+      await program.methods
+        .setMinListingPrice(price)
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    it('Accepts any price when no floor is configured', async () => {
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.equal(eventAccount.minListingPrice.toString(), '0');
+      
+      const listingPda = await listTicket('floor-none', new anchor.BN(1));
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.price.toString(), '1');
+    });
+    
+    it('Accepts a listing above the floor', async () => {
+      await setFloor(minListingPrice);
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.equal(eventAccount.minListingPrice.toString(), minListingPrice.toString());
+      
+      const listingPda = await listTicket('floor-above', minListingPrice.muln(2));
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.price.toString(), minListingPrice.muln(2).toString());
+    });
+    
+    it('Rejects a listing below the floor', async () => {
+      try {
+        await listTicket('floor-below', minListingPrice.divn(2));
+        assert.fail('Listing below the floor should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'PriceBelowMinimum');
+      }
+      
+      // Clear the floor for any later suites
+      await setFloor(new anchor.BN(0));
+    });
+  });
+
+  describe('Listing Price Updates', () => {
+    const listPrice = new anchor.BN(2000000000); // 2 SOL
+    
+    // The ticket is still owned by secondBuyer after the price floor tests
+    const listTicket = async (listingId: string) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createListing(listingId, listPrice)
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          owner: secondBuyer.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([secondBuyer])
+        .rpc();
+      
+      return listingPda;
+    };
+    
+    const updatePrice = async (listingPda: PublicKey, price: anchor.BN) => {
+      await program.methods
+        .updateListingPrice(price)
+        .accounts({
+          listing: listingPda,
+          event: eventPda,
+          owner: secondBuyer.publicKey,
+        })
+        .signers([secondBuyer])
+        .rpc();
+    };
+    
+    const setPolicy = async (maxListingPrice: anchor.BN, allowIncrease: boolean) => {
+      await program.methods
+        .setListingPricePolicy(maxListingPrice, allowIncrease)
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    it('Lets the seller lower the price', async () => {
+      const listingPda = await listTicket('reprice-lower');
+      const lowerPrice = listPrice.divn(2);
+      
+      await updatePrice(listingPda, lowerPrice);
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.price.toString(), lowerPrice.toString());
+    });
+    
+    it('Rejects a price increase by default', async () => {
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.isFalse(eventAccount.allowListingPriceIncrease);
+      
+      const listingPda = await listTicket('reprice-raise-blocked');
+      
+      try {
+        await updatePrice(listingPda, listPrice.muln(2));
+        assert.fail('Raising the price should fail when increases are disallowed');
+      } catch (error) {
+        assert.include(error.toString(), 'PriceIncreaseNotAllowed');
+      }
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.price.toString(), listPrice.toString());
+    });
+    
+    it('Lets the seller raise the price when the event allows it', async () => {
+      await setPolicy(new anchor.BN(0), true);
+      
+      const listingPda = await listTicket('reprice-raise');
+      const higherPrice = listPrice.muln(2);
+      
+      await updatePrice(listingPda, higherPrice);
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.price.toString(), higherPrice.toString());
+    });
+    
+    it('Rejects a price above the resale cap', async () => {
+      const maxListingPrice = listPrice.muln(3);
+      await setPolicy(maxListingPrice, true);
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.equal(eventAccount.maxListingPrice.toString(), maxListingPrice.toString());
+      
+      const listingPda = await listTicket('reprice-over-cap');
+      
+      try {
+        await updatePrice(listingPda, maxListingPrice.addn(1));
+        assert.fail('Raising the price above the cap should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'PriceAboveMaximum');
+      }
+    });
+    
+    it('Rejects an auction starting above the resale cap', async () => {
+      const maxListingPrice = listPrice.muln(3);
+      const listingId = 'auction-over-cap';
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      const [auctionHistoryPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('auction_history'), listingPda.toBuffer()],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      try {
+        await program.methods
+          .createAuction(listingId, maxListingPrice.addn(1), new anchor.BN(100), new anchor.BN(3600))
+          .accounts({
+            ticket: ticketPda,
+            ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+            mint: mintKeypair.publicKey,
+            listing: listingPda,
+            auctionHistory: auctionHistoryPda,
+            lastListingAttempt: attemptPda,
+            event: eventPda,
+            owner: secondBuyer.publicKey,
+            systemProgram: SystemProgram.programId,
+            paymentMint: null,
+            acceptedPaymentMints: acceptedPaymentMintsPda,
+          })
+          .signers([secondBuyer])
+          .rpc();
+        assert.fail('Starting an auction above the cap should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'PriceAboveMaximum');
+      }
+      
+      // Reset the policy for any later suites
+      await setPolicy(new anchor.BN(0), false);
+    });
+  });
+
+  describe('Dutch Auction Ending Floor', () => {
+    const startPrice = new anchor.BN(2000000000); // 2 SOL
+    
+    // The ticket is still owned by secondBuyer after the price floor tests
+    const createDutchAuction = async (listingId: string, endPrice: anchor.BN) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createDutchAuction(listingId, startPrice, endPrice, new anchor.BN(3600))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          owner: secondBuyer.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([secondBuyer])
+        .rpc();
+      
+      return listingPda;
+    };
+    
+    const setEndingFloor = async (floor: any) => {
+      await program.methods
+        .setMinEndingPrice(floor)
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    it('Accepts an ending price above the configured floor', async () => {
+      // Floor at half the start price
+      await setEndingFloor({ startPriceBps: { 0: 5000 } });
+      
+      const endPrice = startPrice.muln(3).divn(4);
+      const listingPda = await createDutchAuction('dutch-valid', endPrice);
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.endingPrice.toString(), endPrice.toString());
+    });
+    
+    it('Rejects a zero ending price', async () => {
+      await setEndingFloor({ absolute: { 0: new anchor.BN(0) } });
+      
+      try {
+        await createDutchAuction('dutch-zero', new anchor.BN(0));
+        assert.fail('Dutch auction with a zero ending price should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'ZeroEndingPrice');
+      }
+    });
+    
+    it('Rejects an ending price below the configured floor', async () => {
+      await setEndingFloor({ absolute: { 0: startPrice.divn(2) } });
+      
+      try {
+        await createDutchAuction('dutch-below', startPrice.divn(4));
+        assert.fail('Dutch auction below the ending floor should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'EndingPriceBelowFloor');
+      }
+      
+      // Clear the floor for any later suites
+      await setEndingFloor({ absolute: { 0: new anchor.BN(0) } });
+    });
+  });
+
+  describe('KYC-Restricted Transfers', () => {
+    let kycRegistryPda: PublicKey;
+    
+    // Moves the first ticket between wallets, creating the recipient's token account if needed
+    const transferFirstTicket = async (from: Keypair, to: PublicKey, kycRegistry: PublicKey | null) => {
+      const fromTokenAccount = await getAssociatedTokenAddress(mintKeypair.publicKey, from.publicKey);
+      const toTokenAccount = await getAssociatedTokenAddress(mintKeypair.publicKey, to);
+      
+      const transaction = new Transaction();
+      if (!(await provider.connection.getAccountInfo(toTokenAccount))) {
+        transaction.add(
+          createAssociatedTokenAccountInstruction(
+            buyer.publicKey,
+            toTokenAccount,
+            to,
+            mintKeypair.publicKey
+          )
+        );
+      }
+      transaction.add(
+        await program.methods
+          .transferTicket()
+          .accounts({
+            ticket: ticketPda,
+            ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+            mint: mintKeypair.publicKey,
+            fromTokenAccount,
+            toTokenAccount,
+            from: from.publicKey,
+            to,
+            event: eventPda,
+            kycRegistry,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .instruction()
+      );
+      
+      const signers = from.publicKey.equals(buyer.publicKey) ? [buyer.payer] : [buyer.payer, from];
+      await sendAndConfirmTransaction(provider.connection, transaction, signers);
+    };
+    
+    const setKycRequired = async (kycRequired: boolean) => {
+      await program.methods
+        .setKycRequired(kycRequired)
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    before(async () => {
+      [kycRegistryPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('kyc_registry'), eventPda.toBuffer()],
+        program.programId
+      );
+      
+      await setKycRequired(true);
+      
+      await program.methods
+        .addKycWallet(buyer.publicKey)
+        .accounts({
+          event: eventPda,
+          kycRegistry: kycRegistryPda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      const registry = await program.account.kycRegistry.fetch(kycRegistryPda);
+      assert.equal(registry.event.toString(), eventPda.toString());
+      assert.equal(registry.approvedWallets.length, 1);
+    });
+    
+    it('Transfers to a KYC-approved wallet', async () => {
+      // The first ticket is held by secondBuyer after the marketplace tests
+      await transferFirstTicket(secondBuyer, buyer.publicKey, kycRegistryPda);
+      
+      const ticketAccount = await program.account.ticket.fetch(ticketPda);
+      assert.equal(ticketAccount.owner.toString(), buyer.publicKey.toString());
+    });
+    
+    it('Rejects a transfer to a wallet not in the registry', async () => {
+      try {
+        await transferFirstTicket(buyer.payer, validator.publicKey, kycRegistryPda);
+        assert.fail('Transfer to an unapproved wallet should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'RecipientNotKycApproved');
+      }
+      
+      // Leaving out the registry does not bypass the check
+      try {
+        await transferFirstTicket(buyer.payer, validator.publicKey, null);
+        assert.fail('Transfer without the registry should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'RecipientNotKycApproved');
+      }
+    });
+    
+    it('Rejects a primary sale to a wallet not in the registry', async () => {
+      const kycMint = Keypair.generate();
+      const [kycMintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), kycMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [kycTicketPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), kycMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [kycMetadata] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), kycMint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [kycMasterEdition] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), kycMint.publicKey.toBuffer(), Buffer.from('edition')],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      try {
+        await program.methods
+          .mintTicket('https://tickettoken.app/metadata/kyc-ticket.json', null, null)
+          .accounts({
+            event: eventPda,
+            ticketType: ticketTypePda,
+            kycRegistry: kycRegistryPda,
+            mint: kycMint.publicKey,
+            ticketMintAuthority: kycMintAuthority,
+            tokenAccount: await getAssociatedTokenAddress(kycMint.publicKey, secondBuyer.publicKey),
+            metadataAccount: kycMetadata,
+            masterEdition: kycMasterEdition,
+            ticket: kycTicketPda,
+            buyer: secondBuyer.publicKey,
+            organizer: eventOrganizer.publicKey,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .signers([secondBuyer, kycMint])
+          .rpc();
+        assert.fail('Minting to an unapproved wallet should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'RecipientNotKycApproved');
+      }
+    });
+    
+    it('Allows any transfer once the flag is disabled', async () => {
+      await setKycRequired(false);
+      
+      await transferFirstTicket(buyer.payer, validator.publicKey, null);
+      
+      const ticketAccount = await program.account.ticket.fetch(ticketPda);
+      assert.equal(ticketAccount.owner.toString(), validator.publicKey.toString());
+    });
+    
+    it('Removes a wallet from the registry', async () => {
+      await program.methods
+        .removeKycWallet(buyer.publicKey)
+        .accounts({
+          event: eventPda,
+          kycRegistry: kycRegistryPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      const registry = await program.account.kycRegistry.fetch(kycRegistryPda);
+      assert.equal(registry.approvedWallets.length, 0);
+    });
+  });
+
+  describe('Event Listing Index', () => {
+    // The first ticket is held by validator after the KYC tests
+    const seller = validator;
+    const purchaser = secondBuyer;
+    let indexPda: PublicKey;
+    let paymentMint: PublicKey;
+    
+    const listingPdaFor = async (listingId: string) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      return listingPda;
+    };
+    
+    const createIndexedListing = async (listingId: string) => {
+      const listingPda = await listingPdaFor(listingId);
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createListing(listingId, new anchor.BN(1000000))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          eventListingIndex: indexPda,
+          owner: seller.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([seller])
+        .rpc();
+      
+      return listingPda;
+    };
+    
+    const cancelIndexedListing = async (listingPda: PublicKey, eventListingIndex: PublicKey | null) => {
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .cancelListing()
+        .accounts({
+          ticket: ticketPda,
+          listing: listingPda,
+          lastListingAttempt: attemptPda,
+          eventListingIndex,
+          owner: seller.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+    };
+    
+    const indexedListings = async () => {
+      const index = await program.account.eventListingIndex.fetch(indexPda);
+      return index.listings.map((listing) => listing.toString()).sort();
+    };
+    
+    before(async () => {
+      [indexPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('event_listing_index'), eventPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, 'le', 4)],
+        program.programId
+      );
+      
+      await program.methods
+        .createEventListingIndex(0)
+        .accounts({
+          event: eventPda,
+          eventListingIndex: indexPda,
+          payer: buyer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      
+      // Payment token for the purchase, funded in the purchaser's wallet
+      paymentMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      const purchaserPayment = await createAssociatedTokenAccount(
+        provider.connection, buyer.payer, paymentMint, purchaser.publicKey
+      );
+      await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, seller.publicKey);
+      await mintTo(provider.connection, buyer.payer, paymentMint, purchaserPayment, buyer.payer, 10000000);
+    });
+    
+    it('Records new listings in the event index', async () => {
+      const indexBefore = await program.account.eventListingIndex.fetch(indexPda);
+      assert.equal(indexBefore.event.toString(), eventPda.toString());
+      assert.equal(indexBefore.segment, 0);
+      assert.equal(indexBefore.listings.length, 0);
+      
+      const listingA = await createIndexedListing('index-a');
+      const listingB = await createIndexedListing('index-b');
+      const listingC = await createIndexedListing('index-c');
+      
+      assert.deepEqual(
+        await indexedListings(),
+        [listingA, listingB, listingC].map((listing) => listing.toString()).sort()
+      );
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingA);
+      assert.equal(listingAccount.indexSegment, 0);
+      
+      // The view logs every active listing in the segment
+      const { raw } = await program.methods
+        .viewEventListings()
+        .accounts({ event: eventPda, eventListingIndex: indexPda })
+        .simulate();
+      const logs = raw.join('\n');
+      assert.include(logs, 'has 3 active listings');
+      for (const listing of [listingA, listingB, listingC]) {
+        assert.include(logs, `Active listing: ${listing.toString()}`);
+      }
+    });
+    
+    it('Prunes a canceled listing from the index', async () => {
+      const listingA = await listingPdaFor('index-a');
+      await cancelIndexedListing(listingA, indexPda);
+      
+      assert.deepEqual(
+        await indexedListings(),
+        [await listingPdaFor('index-b'), await listingPdaFor('index-c')].map((listing) => listing.toString()).sort()
+      );
+    });
+    
+    it('Requires the index segment when canceling an indexed listing', async () => {
+      try {
+        await cancelIndexedListing(await listingPdaFor('index-c'), null);
+        assert.fail('Cancel without the index segment should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'ListingIndexRequired');
+      }
+      
+      assert.equal((await indexedListings()).length, 2);
+    });
+    
+    it('Prunes a purchased listing from the index', async () => {
+      const listingB = await listingPdaFor('index-b');
+      
+      await program.methods
+        .purchaseListing(null)
+        .accounts({
+          ticket: ticketPda,
+          listing: listingB,
+          event: eventPda,
+          kycRegistry: null,
+          mint: mintKeypair.publicKey,
+          fromTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, seller.publicKey),
+          toTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, purchaser.publicKey),
+          seller: seller.publicKey,
+          buyer: purchaser.publicKey,
+          paymentFromAccount: await getAssociatedTokenAddress(paymentMint, purchaser.publicKey),
+          paymentToAccount: await getAssociatedTokenAddress(paymentMint, seller.publicKey),
+          royaltyAccount: null,
+          transferRecord: null,
+          eventListingIndex: indexPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([seller, purchaser])
+        .rpc();
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingB);
+      assert.deepEqual(listingAccount.status, { sold: {} });
+      
+      assert.deepEqual(await indexedListings(), [(await listingPdaFor('index-c')).toString()]);
+      
+      // The remaining listing is still prunable once its seller cancels it
+      await cancelIndexedListing(await listingPdaFor('index-c'), indexPda);
+      assert.deepEqual(await indexedListings(), []);
+    });
+  });
+
+  describe('Auction Bid Bonds', () => {
+    const bondBasisPoints = 2000; // 20%
+    const auctionDuration = 4; // seconds
+    const paymentWindow = 4; // seconds
+    const startPrice = new anchor.BN(1000);
+    let paymentMint: PublicKey;
+    
+    const listingPdaFor = async (listingId: string) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      return listingPda;
+    };
+    
+    const escrowFor = async (listingPda: PublicKey) => {
+      const [escrowAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('escrow'), listingPda.toBuffer()],
+        program.programId
+      );
+      const escrowAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection, buyer.payer, paymentMint, escrowAuthority, true
+      );
+      return { escrowAuthority, escrowAccount: escrowAccount.address };
+    };
+    
+    const paymentAccountOf = (owner: PublicKey) => getAssociatedTokenAddress(paymentMint, owner);
+    
+    const paymentBalance = async (owner: PublicKey) =>
+      Number((await getAccount(provider.connection, await paymentAccountOf(owner))).amount);
+    
+    // Lists the first ticket as a short auction in bond mode
+    const createBondAuction = async (seller: Keypair, listingId: string) => {
+      const listingPda = await listingPdaFor(listingId);
+      const [auctionHistoryPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('auction_history'), listingPda.toBuffer()],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createAuction(listingId, startPrice, new anchor.BN(100), new anchor.BN(auctionDuration))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: auctionHistoryPda,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          eventListingIndex: null,
+          owner: seller.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([seller])
+        .rpc();
+      
+      await program.methods
+        .setAuctionBond(bondBasisPoints, new anchor.BN(paymentWindow))
+        .accounts({ listing: listingPda, owner: seller.publicKey })
+        .signers([seller])
+        .rpc();
+      
+      return { listingPda, auctionHistoryPda, ...(await escrowFor(listingPda)) };
+    };
+    
+    type BondAuction = Awaited<ReturnType<typeof createBondAuction>>;
+    
+    const placeBid = async (auction: BondAuction, bidder: Keypair, amount: number, refundTo: PublicKey | null) => {
+      await program.methods
+        .placeBid(new anchor.BN(amount))
+        .accounts({
+          ticket: ticketPda,
+          listing: auction.listingPda,
+          auctionHistory: auction.auctionHistoryPda,
+          bidder: bidder.publicKey,
+          paymentFromAccount: await paymentAccountOf(bidder.publicKey),
+          escrowAccount: auction.escrowAccount,
+          escrowAuthority: auction.escrowAuthority,
+          refundAccount: refundTo ? await paymentAccountOf(refundTo) : null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([bidder])
+        .rpc();
+    };
+    
+    const finalizeWinningPayment = async (auction: BondAuction, winner: Keypair) => {
+      await program.methods
+        .finalizeWinningPayment()
+        .accounts({
+          listing: auction.listingPda,
+          winner: winner.publicKey,
+          paymentFromAccount: await paymentAccountOf(winner.publicKey),
+          escrowAccount: auction.escrowAccount,
+          escrowAuthority: auction.escrowAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([winner])
+        .rpc();
+    };
+    
+    const settleAuction = async (auction: BondAuction, seller: Keypair, winner: PublicKey) => {
+      await program.methods
+        .settleAuction()
+        .accounts({
+          ticket: ticketPda,
+          listing: auction.listingPda,
+          event: eventPda,
+          kycRegistry: null,
+          mint: mintKeypair.publicKey,
+          fromTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, seller.publicKey),
+          toTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, winner),
+          seller: seller.publicKey,
+          escrowAccount: auction.escrowAccount,
+          escrowAuthority: auction.escrowAuthority,
+          paymentToAccount: await paymentAccountOf(seller.publicKey),
+          royaltyAccount: null,
+          transferRecord: null,
+          eventListingIndex: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+    };
+    
+    const escrowBalance = async (auction: BondAuction) =>
+      Number((await getAccount(provider.connection, auction.escrowAccount)).amount);
+    
+    const bondFor = (amount: number) => Math.floor((amount * bondBasisPoints) / 10000);
+    
+    before(async () => {
+      paymentMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      for (const owner of [buyer.publicKey, validator.publicKey, secondBuyer.publicKey]) {
+        const account = await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, owner);
+        await mintTo(provider.connection, buyer.payer, paymentMint, account, buyer.payer, 100000);
+      }
+    });
+    
+    describe('Winner tops up', () => {
+      // The first ticket is held by secondBuyer after the listing index tests
+      let auction: BondAuction;
+      
+      before(async () => {
+        auction = await createBondAuction(secondBuyer, 'bond-auction-1');
+      });
+      
+      it('Escrows only the bond and refunds it when the bidder is outbid', async () => {
+        const listingAccount = await program.account.marketplaceListing.fetch(auction.listingPda);
+        assert.equal(listingAccount.bondBasisPoints, bondBasisPoints);
+        assert.equal(listingAccount.paymentWindow.toNumber(), paymentWindow);
+        
+        const loserBefore = await paymentBalance(buyer.publicKey);
+        
+        await placeBid(auction, buyer.payer, 1000, null);
+        assert.equal(await escrowBalance(auction), bondFor(1000));
+        assert.equal(loserBefore - (await paymentBalance(buyer.publicKey)), bondFor(1000));
+        
+        // Outbidding returns the first bidder's bond
+        await placeBid(auction, validator, 1100, buyer.publicKey);
+        assert.equal(await escrowBalance(auction), bondFor(1100));
+        assert.equal(await paymentBalance(buyer.publicKey), loserBefore);
+      });
+      
+      it('Rejects bond changes once bidding has started', async () => {
+        try {
+          await program.methods
+            .setAuctionBond(0, new anchor.BN(0))
+            .accounts({ listing: auction.listingPda, owner: secondBuyer.publicKey })
+            .signers([secondBuyer])
+            .rpc();
+          assert.fail('Bond change after bids should fail');
+        } catch (error) {
+          assert.include(error.toString(), 'BondLockedAfterBids');
+        }
+      });
+      
+      it('Settles after the winner pays the remainder', async () => {
+        await new Promise((resolve) => setTimeout(resolve, (auctionDuration + 1) * 1000));
+        
+        // Settlement waits for the winner while the payment window is open
+        try {
+          await settleAuction(auction, secondBuyer, validator.publicKey);
+          assert.fail('Settlement before payment should fail');
+        } catch (error) {
+          assert.include(error.toString(), 'PaymentWindowActive');
+        }
+        
+        const winnerBefore = await paymentBalance(validator.publicKey);
+        await finalizeWinningPayment(auction, validator);
+        assert.equal(winnerBefore - (await paymentBalance(validator.publicKey)), 1100 - bondFor(1100));
+        assert.equal(await escrowBalance(auction), 1100);
+        
+        const listingAccount = await program.account.marketplaceListing.fetch(auction.listingPda);
+        assert.isTrue(listingAccount.paymentFinalized);
+        
+        const sellerBefore = await paymentBalance(secondBuyer.publicKey);
+        await settleAuction(auction, secondBuyer, validator.publicKey);
+        
+        const royalty = Math.floor((1100 * listingAccount.royaltyBasisPoints) / 10000);
+        assert.equal((await paymentBalance(secondBuyer.publicKey)) - sellerBefore, 1100 - royalty);
+        
+        const settled = await program.account.marketplaceListing.fetch(auction.listingPda);
+        assert.deepEqual(settled.status, { auctionEnded: {} });
+        const ticketAccount = await program.account.ticket.fetch(ticketPda);
+        assert.equal(ticketAccount.owner.toString(), validator.publicKey.toString());
+      });
+    });
+    
+    describe('Winner defaults', () => {
+      // The first ticket is now held by validator
+      let auction: BondAuction;
+      
+      before(async () => {
+        auction = await createBondAuction(validator, 'bond-auction-2');
+      });
+      
+      it('Forfeits the bond to the seller when the winner misses the payment window', async () => {
+        await placeBid(auction, buyer.payer, 1000, null);
+        assert.equal(await escrowBalance(auction), bondFor(1000));
+        
+        await new Promise((resolve) => setTimeout(resolve, (auctionDuration + paymentWindow + 1) * 1000));
+        
+        try {
+          await finalizeWinningPayment(auction, buyer.payer);
+          assert.fail('Payment after the window should fail');
+        } catch (error) {
+          assert.include(error.toString(), 'PaymentWindowClosed');
+        }
+        
+        const sellerBefore = await paymentBalance(validator.publicKey);
+        await settleAuction(auction, validator, buyer.publicKey);
+        
+        assert.equal((await paymentBalance(validator.publicKey)) - sellerBefore, bondFor(1000));
+        assert.equal(await escrowBalance(auction), 0);
+        
+        // The seller keeps the ticket
+        const listingAccount = await program.account.marketplaceListing.fetch(auction.listingPda);
+        assert.deepEqual(listingAccount.status, { auctionExpired: {} });
+        const ticketAccount = await program.account.ticket.fetch(ticketPda);
+        assert.equal(ticketAccount.owner.toString(), validator.publicKey.toString());
+      });
+    });
+  });
+  
+  describe('Transfer Freeze Window', () => {
+    let secondTicketPda: PublicKey;
+    
+    // Moves the second ticket between wallets, creating the recipient's token account if needed
+    const transferSecondTicket = async (from: Keypair, to: Keypair) => {
+      const fromTokenAccount = await getAssociatedTokenAddress(secondMintKeypair.publicKey, from.publicKey);
+      const toTokenAccount = await getAssociatedTokenAddress(secondMintKeypair.publicKey, to.publicKey);
+      
+      const transaction = new Transaction();
+      if (!(await provider.connection.getAccountInfo(toTokenAccount))) {
+        transaction.add(
+          createAssociatedTokenAccountInstruction(
+            buyer.publicKey,
+            toTokenAccount,
+            to.publicKey,
+            secondMintKeypair.publicKey
+          )
+        );
+      }
+      transaction.add(
+        await program.methods
+          .transferTicket()
+          .accounts({
+            ticket: secondTicketPda,
+            ticketType: (await program.account.ticket.fetch(secondTicketPda)).ticketType,
+            mint: secondMintKeypair.publicKey,
+            fromTokenAccount,
+            toTokenAccount,
+            from: from.publicKey,
+            to: to.publicKey,
+            event: eventPda,
+            kycRegistry: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .instruction()
+      );
+      
+      const signers = from.publicKey.equals(buyer.publicKey) ? [buyer.payer] : [buyer.payer, from];
+      await sendAndConfirmTransaction(provider.connection, transaction, signers);
+    };
+    
+    const setTransferFreeze = async (freezeBefore: number, freezeAfter: number) => {
+      await program.methods
+        .setTransferFreeze(new anchor.BN(freezeBefore), new anchor.BN(freezeAfter))
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    before(async () => {
+      [secondTicketPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), secondMintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+    
+    after(async () => {
+      await setTransferFreeze(0, 0);
+    });
+    
+    it('Allows transfers before the freeze window opens', async () => {
+      // The event starts in a day, so a one hour window is still ahead of us
+      await setTransferFreeze(3600, 3600);
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.equal(eventAccount.freezeBefore.toNumber(), 3600);
+      assert.equal(eventAccount.freezeAfter.toNumber(), 3600);
+      
+      await transferSecondTicket(secondBuyer, buyer.payer);
+      
+      const ticketAccount = await program.account.ticket.fetch(secondTicketPda);
+      assert.equal(ticketAccount.owner.toString(), buyer.publicKey.toString());
+    });
+    
+    it('Rejects transfers inside the freeze window', async () => {
+      // Two days before the start date reaches back past the current time
+      await setTransferFreeze(2 * 86400, 3600);
+      
+      try {
+        await transferSecondTicket(buyer.payer, secondBuyer);
+        assert.fail('Transfer during the freeze should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'TransfersFrozen');
+      }
+      
+      const ticketAccount = await program.account.ticket.fetch(secondTicketPda);
+      assert.equal(ticketAccount.owner.toString(), buyer.publicKey.toString());
+    });
+    
+    it('Still admits the holder during the freeze window', async () => {
+      await program.methods
+        .verifyAndMarkUsed()
+        .accounts({
+          event: eventPda,
+          ticket: secondTicketPda,
+          ticketOwner: buyer.publicKey,
+          validator: validator.publicKey,
+        })
+        .signers([validator])
+        .rpc();
+      
+      const ticketAccount = await program.account.ticket.fetch(secondTicketPda);
+      assert.deepEqual(ticketAccount.status, { used: {} });
+    });
+    
+    it('Rejects a negative freeze offset', async () => {
+      try {
+        await setTransferFreeze(-1, 0);
+        assert.fail('Negative offsets should be rejected');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidTransferFreeze');
+      }
+    });
+  });
+  
+  describe('Quick Flip Royalties', () => {
+    // The first ticket is held by validator after the bid bond tests
+    const initialBasisPoints = 3000;
+    const salePrice = 1000000;
+    let paymentMint: PublicKey;
+    
+    const listingPdaFor = async (listingId: string) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      return listingPda;
+    };
+    
+    const paymentAccountOf = (owner: PublicKey) => getAssociatedTokenAddress(paymentMint, owner);
+    
+    const setFlipRoyalty = async (initial: number, decayWindow: number) => {
+      await program.methods
+        .setFlipRoyalty({ initialBasisPoints: initial, decayWindow: new anchor.BN(decayWindow) })
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    // Lists the first ticket and sells it, returning the royalty paid and how long the seller held it
+    const resell = async (seller: Keypair, purchaser: Keypair, listingId: string) => {
+      const listingPda = await listingPdaFor(listingId);
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createListing(listingId, new anchor.BN(salePrice))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          eventListingIndex: null,
+          owner: seller.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([seller])
+        .rpc();
+      
+      const toTokenAccount = await getAssociatedTokenAddress(mintKeypair.publicKey, purchaser.publicKey);
+      if (!(await provider.connection.getAccountInfo(toTokenAccount))) {
+        await createAssociatedTokenAccount(provider.connection, buyer.payer, mintKeypair.publicKey, purchaser.publicKey);
+      }
+      
+      const acquiredAt = (await program.account.ticket.fetch(ticketPda)).lastTransferAt.toNumber();
+      const royaltyAccount = await paymentAccountOf(eventOrganizer.publicKey);
+      const royaltyBefore = Number((await getAccount(provider.connection, royaltyAccount)).amount);
+      
+      await program.methods
+        .purchaseListing(null)
+        .accounts({
+          ticket: ticketPda,
+          listing: listingPda,
+          event: eventPda,
+          kycRegistry: null,
+          mint: mintKeypair.publicKey,
+          fromTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, seller.publicKey),
+          toTokenAccount,
+          seller: seller.publicKey,
+          buyer: purchaser.publicKey,
+          paymentFromAccount: await paymentAccountOf(purchaser.publicKey),
+          paymentToAccount: await paymentAccountOf(seller.publicKey),
+          royaltyAccount,
+          transferRecord: null,
+          eventListingIndex: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([seller, purchaser])
+        .rpc();
+      
+      const ticketAccount = await program.account.ticket.fetch(ticketPda);
+      assert.equal(ticketAccount.owner.toString(), purchaser.publicKey.toString());
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      return {
+        royalty: Number((await getAccount(provider.connection, royaltyAccount)).amount) - royaltyBefore,
+        heldFor: ticketAccount.lastTransferAt.toNumber() - acquiredAt,
+        baseBasisPoints: listingAccount.royaltyBasisPoints,
+      };
+    };
+    
+    const royaltyFor = (basisPoints: number) => Math.floor((salePrice * basisPoints) / 10000);
+    
+    // Mirrors the on-chain linear decay from the initial rate to the base rate
+    const decayedBasisPoints = (base: number, decayWindow: number, heldFor: number) => {
+      if (heldFor >= decayWindow) {
+        return base;
+      }
+      return base + Math.floor(((initialBasisPoints - base) * (decayWindow - heldFor)) / decayWindow);
+    };
+    
+    before(async () => {
+      paymentMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      for (const owner of [validator.publicKey, secondBuyer.publicKey, eventOrganizer.publicKey]) {
+        const account = await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, owner);
+        await mintTo(provider.connection, buyer.payer, paymentMint, account, buyer.payer, 10 * salePrice);
+      }
+    });
+    
+    after(async () => {
+      await setFlipRoyalty(0, 0);
+    });
+    
+    it('Charges the escalated royalty on a flip within an hour of purchase', async () => {
+      const decayWindow = 10 * 86400;
+      await setFlipRoyalty(initialBasisPoints, decayWindow);
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.equal(eventAccount.flipRoyalty.initialBasisPoints, initialBasisPoints);
+      assert.equal(eventAccount.flipRoyalty.decayWindow.toNumber(), decayWindow);
+      
+      const { royalty, heldFor, baseBasisPoints } = await resell(validator, secondBuyer, 'flip-quick');
+      
+      assert.isBelow(heldFor, 3600);
+      assert.equal(royalty, royaltyFor(decayedBasisPoints(baseBasisPoints, decayWindow, heldFor)));
+      // Within an hour of a ten day window the rate has barely moved off the initial one
+      assert.isAtLeast(royalty, royaltyFor(initialBasisPoints - 10));
+    });
+    
+    it('Charges the base royalty once the decay window has passed', async () => {
+      const decayWindow = 2;
+      await setFlipRoyalty(initialBasisPoints, decayWindow);
+      await new Promise((resolve) => setTimeout(resolve, (decayWindow + 1) * 1000));
+      
+      const { royalty, heldFor, baseBasisPoints } = await resell(secondBuyer, validator, 'flip-settled');
+      
+      assert.isAtLeast(heldFor, decayWindow);
+      assert.equal(royalty, royaltyFor(baseBasisPoints));
+    });
+    
+    it('Charges a decayed royalty halfway through the window', async () => {
+      const decayWindow = 20;
+      await setFlipRoyalty(initialBasisPoints, decayWindow);
+      await new Promise((resolve) => setTimeout(resolve, (decayWindow / 2) * 1000));
+      
+      const { royalty, heldFor, baseBasisPoints } = await resell(validator, secondBuyer, 'flip-midpoint');
+      
+      assert.isBelow(heldFor, decayWindow);
+      assert.equal(royalty, royaltyFor(decayedBasisPoints(baseBasisPoints, decayWindow, heldFor)));
+      // Allow a few seconds of drift around the midpoint of the decay
+      const midpoint = royaltyFor((initialBasisPoints + baseBasisPoints) / 2);
+      const perSecond = (salePrice * (initialBasisPoints - baseBasisPoints)) / 10000 / decayWindow;
+      assert.closeTo(royalty, midpoint, 5 * perSecond);
+    });
+    
+    it('Rejects a flip royalty above 100%', async () => {
+      try {
+        await setFlipRoyalty(10001, 3600);
+        assert.fail('Royalty above 100% should be rejected');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidFlipRoyalty');
+      }
+    });
+  });
+  
+  describe('Accepted Payment Mints', () => {
+    // The first ticket is held by secondBuyer after the quick flip tests.
+    // Once the registry exists every later listing must name an accepted mint.
+    const salePrice = 1000000;
+    let acceptedMint: PublicKey;
+    let unlistedMint: PublicKey;
+    
+    const listingPdaFor = async (listingId: string) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      return listingPda;
+    };
+    
+    const createListing = async (listingId: string, paymentMint: PublicKey) => {
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createListing(listingId, new anchor.BN(salePrice))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: await listingPdaFor(listingId),
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          eventListingIndex: null,
+          owner: secondBuyer.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([secondBuyer])
+        .rpc();
+    };
+    
+    const purchaseListing = async (listingId: string, paymentMint: PublicKey) => {
+      const toTokenAccount = await getAssociatedTokenAddress(mintKeypair.publicKey, validator.publicKey);
+      if (!(await provider.connection.getAccountInfo(toTokenAccount))) {
+        await createAssociatedTokenAccount(provider.connection, buyer.payer, mintKeypair.publicKey, validator.publicKey);
+      }
+      
+      await program.methods
+        .purchaseListing(null)
+        .accounts({
+          ticket: ticketPda,
+          listing: await listingPdaFor(listingId),
+          event: eventPda,
+          kycRegistry: null,
+          mint: mintKeypair.publicKey,
+          fromTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, secondBuyer.publicKey),
+          toTokenAccount,
+          seller: secondBuyer.publicKey,
+          buyer: validator.publicKey,
+          paymentFromAccount: await getAssociatedTokenAddress(paymentMint, validator.publicKey),
+          paymentToAccount: await getAssociatedTokenAddress(paymentMint, secondBuyer.publicKey),
+          royaltyAccount: await getAssociatedTokenAddress(paymentMint, eventOrganizer.publicKey),
+          transferRecord: null,
+          eventListingIndex: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([secondBuyer, validator])
+        .rpc();
+    };
+    
+    // The program's data account, which records its upgrade authority
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    );
+    
+    const updateRegistry = async (action: 'add' | 'remove', mint: PublicKey) => {
+      const method = action === 'add'
+        ? program.methods.addAcceptedPaymentMint(mint)
+        : program.methods.removeAcceptedPaymentMint(mint);
+      await method
+        .accounts({
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          admin: buyer.publicKey,
+        })
+        .rpc();
+    };
+    
+    before(async () => {
+      acceptedMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      unlistedMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      for (const paymentMint of [acceptedMint, unlistedMint]) {
+        for (const owner of [validator.publicKey, secondBuyer.publicKey, eventOrganizer.publicKey]) {
+          const account = await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, owner);
+          await mintTo(provider.connection, buyer.payer, paymentMint, account, buyer.payer, 10 * salePrice);
+        }
+      }
+      
+    });
+    
+    it('Only lets the program upgrade authority create the registry', async () => {
+      const createRegistry = (admin: PublicKey) =>
+        program.methods
+          .createAcceptedPaymentMints()
+          .accounts({
+            acceptedPaymentMints: acceptedPaymentMintsPda,
+            programData,
+            admin,
+            systemProgram: SystemProgram.programId,
+          });
+      
+      try {
+        await createRegistry(validator.publicKey).signers([validator]).rpc();
+        assert.fail('Should have thrown an error');
+      } catch (error) {
+        assert.include(error.toString(), 'Unauthorized');
+      }
+      
+      await createRegistry(buyer.publicKey).rpc();
+      const registry = await program.account.acceptedPaymentMints.fetch(acceptedPaymentMintsPda);
+      assert.equal(registry.admin.toString(), buyer.publicKey.toString());
+    });
+    
+    it('Lets the admin add and remove accepted mints', async () => {
+      await updateRegistry('add', acceptedMint);
+      await updateRegistry('add', unlistedMint);
+      
+      let registry = await program.account.acceptedPaymentMints.fetch(acceptedPaymentMintsPda);
+      assert.equal(registry.admin.toString(), buyer.publicKey.toString());
+      assert.deepEqual(registry.mints.map((mint) => mint.toString()), [acceptedMint.toString(), unlistedMint.toString()]);
+      
+      await updateRegistry('remove', unlistedMint);
+      
+      registry = await program.account.acceptedPaymentMints.fetch(acceptedPaymentMintsPda);
+      assert.deepEqual(registry.mints.map((mint) => mint.toString()), [acceptedMint.toString()]);
+    });
+    
+    it('Rejects registry updates from anyone but the admin', async () => {
+      try {
+        await program.methods
+          .addAcceptedPaymentMint(unlistedMint)
+          .accounts({
+            acceptedPaymentMints: acceptedPaymentMintsPda,
+            admin: secondBuyer.publicKey,
+          })
+          .signers([secondBuyer])
+          .rpc();
+        assert.fail('Non-admin should not be able to update the registry');
+      } catch (error) {
+        assert.include(error.toString(), 'Unauthorized');
+      }
+    });
+    
+    it('Rejects a listing that asks for an unlisted mint', async () => {
+      try {
+        await createListing('unlisted-mint', unlistedMint);
+        assert.fail('Listing in an unlisted mint should be rejected');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidPaymentToken');
+      }
+    });
+    
+    it('Rejects a purchase paid in an unlisted mint', async () => {
+      await createListing('accepted-mint', acceptedMint);
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(await listingPdaFor('accepted-mint'));
+      assert.equal(listingAccount.paymentMint.toString(), acceptedMint.toString());
+      
+      try {
+        await purchaseListing('accepted-mint', unlistedMint);
+        assert.fail('Purchase in an unlisted mint should be rejected');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidPaymentToken');
+      }
+    });
+    
+    it('Completes a purchase paid in an accepted mint', async () => {
+      const sellerPayment = await getAssociatedTokenAddress(acceptedMint, secondBuyer.publicKey);
+      const sellerBefore = Number((await getAccount(provider.connection, sellerPayment)).amount);
+      
+      await purchaseListing('accepted-mint', acceptedMint);
+      
+      const ticketAccount = await program.account.ticket.fetch(ticketPda);
+      assert.equal(ticketAccount.owner.toString(), validator.publicKey.toString());
+      
+      const sellerAfter = Number((await getAccount(provider.connection, sellerPayment)).amount);
+      assert.isAbove(sellerAfter, sellerBefore);
+    });
+  });
+  
+  describe('Auction Tie Breaking', () => {
+    // The first ticket is held by validator after the accepted payment mint tests
+    const listingId = 'tie-auction';
+    const auctionDuration = 4; // seconds
+    const bidAmount = 1000;
+    let paymentMint: PublicKey;
+    let listingPda: PublicKey;
+    let auctionHistoryPda: PublicKey;
+    let escrowAuthority: PublicKey;
+    let escrowAccount: PublicKey;
+    
+    const paymentAccountOf = (owner: PublicKey) => getAssociatedTokenAddress(paymentMint, owner);
+    
+    const placeBid = async (bidder: Keypair, amount: number, refundTo: PublicKey | null) => {
+      await program.methods
+        .placeBid(new anchor.BN(amount))
+        .accounts({
+          ticket: ticketPda,
+          listing: listingPda,
+          auctionHistory: auctionHistoryPda,
+          bidder: bidder.publicKey,
+          paymentFromAccount: await paymentAccountOf(bidder.publicKey),
+          escrowAccount,
+          escrowAuthority,
+          refundAccount: refundTo ? await paymentAccountOf(refundTo) : null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([bidder])
+        .rpc();
+    };
+    
+    const settleAuction = async (winner: PublicKey) => {
+      await program.methods
+        .settleAuction()
+        .accounts({
+          ticket: ticketPda,
+          listing: listingPda,
+          event: eventPda,
+          kycRegistry: null,
+          mint: mintKeypair.publicKey,
+          fromTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, validator.publicKey),
+          toTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, winner),
+          seller: validator.publicKey,
+          escrowAccount,
+          escrowAuthority,
+          paymentToAccount: await paymentAccountOf(validator.publicKey),
+          royaltyAccount: null,
+          transferRecord: null,
+          eventListingIndex: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([validator])
+        .rpc();
+    };
+    
+    before(async () => {
+      paymentMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      for (const owner of [buyer.publicKey, validator.publicKey, secondBuyer.publicKey]) {
+        const account = await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, owner);
+        await mintTo(provider.connection, buyer.payer, paymentMint, account, buyer.payer, 100000);
+      }
+      for (const owner of [buyer.publicKey, secondBuyer.publicKey]) {
+        const ticketAccount = await getAssociatedTokenAddress(mintKeypair.publicKey, owner);
+        if (!(await provider.connection.getAccountInfo(ticketAccount))) {
+          await createAssociatedTokenAccount(provider.connection, buyer.payer, mintKeypair.publicKey, owner);
+        }
+      }
+      await program.methods
+        .addAcceptedPaymentMint(paymentMint)
+        .accounts({
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          admin: buyer.publicKey,
+        })
+        .rpc();
+      
+      [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      [auctionHistoryPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('auction_history'), listingPda.toBuffer()],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      [escrowAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('escrow'), listingPda.toBuffer()],
+        program.programId
+      );
+      escrowAccount = (await getOrCreateAssociatedTokenAccount(
+        provider.connection, buyer.payer, paymentMint, escrowAuthority, true
+      )).address;
+      
+      // A zero increment is what makes equal bids possible
+      await program.methods
+        .createAuction(listingId, new anchor.BN(bidAmount), new anchor.BN(0), new anchor.BN(auctionDuration))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: auctionHistoryPda,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          eventListingIndex: null,
+          owner: validator.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([validator])
+        .rpc();
+    });
+    
+    it('Rejects a bid that only ties the highest bid', async () => {
+      await placeBid(buyer.payer, bidAmount, null);
+      
+      try {
+        await placeBid(secondBuyer, bidAmount, buyer.publicKey);
+        assert.fail('Tying bid should be rejected');
+      } catch (error) {
+        assert.include(error.toString(), 'BidTiesHighest');
+      }
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.highestBid.toNumber(), bidAmount);
+      assert.equal(listingAccount.highestBidder.toString(), buyer.publicKey.toString());
+      
+      const history = await program.account.auctionHistory.fetch(auctionHistoryPda);
+      assert.equal(history.bids.length, 1);
+      assert.equal(history.bids[0].bidder.toString(), buyer.publicKey.toString());
+    });
+    
+    it('Settles to the earliest bidder at the winning amount', async () => {
+      await new Promise((resolve) => setTimeout(resolve, (auctionDuration + 1) * 1000));
+      
+      // The rejected tying bidder cannot take delivery
+      try {
+        await settleAuction(secondBuyer.publicKey);
+        assert.fail('Settlement to a non-winner should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'NotAuctionWinner');
+      }
+      
+      await settleAuction(buyer.publicKey);
+      
+      const ticketAccount = await program.account.ticket.fetch(ticketPda);
+      assert.equal(ticketAccount.owner.toString(), buyer.publicKey.toString());
+      
+      const winnerTicket = await getAccount(
+        provider.connection,
+        await getAssociatedTokenAddress(mintKeypair.publicKey, buyer.publicKey)
+      );
+      assert.equal(Number(winnerTicket.amount), 1);
+    });
+  });
+  
+  describe('Closing Terminal Listings', () => {
+    // The first ticket is held by buyer after the tie breaking tests; the
+    // 'accepted-mint' listing was sold by secondBuyer in the payment mint tests
+    let paymentMint: PublicKey;
+    
+    const listingPdaFor = async (ticket: PublicKey, listingId: string) => {
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticket.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      return listingPda;
+    };
+    
+    const escrowAuthorityFor = async (listingPda: PublicKey) => {
+      const [escrowAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('escrow'), listingPda.toBuffer()],
+        program.programId
+      );
+      return escrowAuthority;
+    };
+    
+    const createListing = async (ticket: PublicKey, mint: PublicKey, listingId: string) => {
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mint.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createListing(listingId, new anchor.BN(1000000))
+        .accounts({
+          ticket,
+          ticketType: (await program.account.ticket.fetch(ticket)).ticketType,
+          mint,
+          listing: await listingPdaFor(ticket, listingId),
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          eventListingIndex: null,
+          owner: buyer.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .rpc();
+    };
+    
+    const cancelListing = async (ticket: PublicKey, mint: PublicKey, listingId: string) => {
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mint.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .cancelListing()
+        .accounts({
+          ticket,
+          listing: await listingPdaFor(ticket, listingId),
+          lastListingAttempt: attemptPda,
+          eventListingIndex: null,
+          owner: buyer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    };
+    
+    // Listings with a payment mint show its canonical escrow account unless `withPaymentEscrow` is false
+    const closeListing = async (listingPda: PublicKey, mint: PublicKey, withPaymentEscrow = true) => {
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      const escrowAuthority = await escrowAuthorityFor(listingPda);
+      const paymentEscrowAccount = withPaymentEscrow && listingAccount.paymentMint
+        ? await getAssociatedTokenAddress(listingAccount.paymentMint, escrowAuthority, true)
+        : null;
+      
+      // Called by a third party to show cleanup is permissionless
+      await program.methods
+        .closeTerminalListing()
+        .accounts({
+          listing: listingPda,
+          owner: listingAccount.owner,
+          escrowAuthority,
+          nftEscrowAccount: await getAssociatedTokenAddress(mint, escrowAuthority, true),
+          paymentEscrowAccount,
+          closer: validator.publicKey,
+        })
+        .signers([validator])
+        .rpc();
+    };
+    
+    before(async () => {
+      paymentMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      await program.methods
+        .addAcceptedPaymentMint(paymentMint)
+        .accounts({
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          admin: buyer.publicKey,
+        })
+        .rpc();
+    });
+    
+    it('Closes a sold listing and returns its rent to the seller', async () => {
+      const listingPda = await listingPdaFor(ticketPda, 'accepted-mint');
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.deepEqual(listingAccount.status, { sold: {} });
+      
+      const rent = await provider.connection.getBalance(listingPda);
+      const sellerBefore = await provider.connection.getBalance(secondBuyer.publicKey);
+      
+      await closeListing(listingPda, mintKeypair.publicKey);
+      
+      assert.isNull(await provider.connection.getAccountInfo(listingPda));
+      assert.equal(await provider.connection.getBalance(secondBuyer.publicKey), sellerBefore + rent);
+    });
+    
+    it('Refuses to close an active listing until it is canceled', async () => {
+      await createListing(ticketPda, mintKeypair.publicKey, 'close-active');
+      const listingPda = await listingPdaFor(ticketPda, 'close-active');
+      
+      try {
+        await closeListing(listingPda, mintKeypair.publicKey);
+        assert.fail('Active listing should not be closable');
+      } catch (error) {
+        assert.include(error.toString(), 'ListingNotTerminal');
+      }
+      
+      await cancelListing(ticketPda, mintKeypair.publicKey, 'close-active');
+      await closeListing(listingPda, mintKeypair.publicKey);
+      assert.isNull(await provider.connection.getAccountInfo(listingPda));
+    });
+    
+    it('Requires the payment escrow account for a listing with a payment mint', async () => {
+      await createListing(ticketPda, mintKeypair.publicKey, 'close-no-escrow');
+      await cancelListing(ticketPda, mintKeypair.publicKey, 'close-no-escrow');
+      const listingPda = await listingPdaFor(ticketPda, 'close-no-escrow');
+      
+      try {
+        await closeListing(listingPda, mintKeypair.publicKey, false);
+        assert.fail('Listing should not close without its payment escrow');
+      } catch (error) {
+        assert.include(error.toString(), 'PaymentEscrowRequired');
+      }
+      
+      await closeListing(listingPda, mintKeypair.publicKey);
+      assert.isNull(await provider.connection.getAccountInfo(listingPda));
+    });
+    
+    it('Refuses to close a listing whose escrow still holds the NFT', async () => {
+      // Mint a throwaway ticket, since an NFT sent to the escrow can't be recovered
+      const throwawayMint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), throwawayMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [throwawayTicket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), throwawayMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), throwawayMint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          throwawayMint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const ownerTokenAccount = await getAssociatedTokenAddress(throwawayMint.publicKey, buyer.publicKey);
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-close.json', null, null)
+        .accounts({
+          event: eventPda,
+          ticketType: ticketTypePda,
+          kycRegistry: null,
+          mint: throwawayMint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: ownerTokenAccount,
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket: throwawayTicket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([throwawayMint])
+        .rpc();
+      
+      await createListing(throwawayTicket, throwawayMint.publicKey, 'close-escrowed');
+      await cancelListing(throwawayTicket, throwawayMint.publicKey, 'close-escrowed');
+      
+      const listingPda = await listingPdaFor(throwawayTicket, 'close-escrowed');
+      const escrowNft = await getOrCreateAssociatedTokenAccount(
+        provider.connection, buyer.payer, throwawayMint.publicKey, await escrowAuthorityFor(listingPda), true
+      );
+      await transfer(provider.connection, buyer.payer, ownerTokenAccount, escrowNft.address, buyer.payer, 1);
+      
+      try {
+        await closeListing(listingPda, throwawayMint.publicKey);
+        assert.fail('Listing with an escrowed NFT should not be closable');
+      } catch (error) {
+        assert.include(error.toString(), 'ListingEscrowNotEmpty');
+      }
+      
+      assert.isNotNull(await provider.connection.getAccountInfo(listingPda));
+    });
+  });
+  
+  describe('Event Postponement', () => {
+    const POSTPONE_BY = 7 * 86400; // one week
+    const postponedMint = Keypair.generate();
+    let postponedTicket: PublicKey;
+    let originalStartDate: number;
+    let originalEndDate: number;
+    
+    const postponeEvent = async (newStartDate: number, newEndDate: number, openRefunds: boolean) => {
+      await program.methods
+        .postponeEvent(new anchor.BN(newStartDate), new anchor.BN(newEndDate), openRefunds)
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    before(async () => {
+      const eventAccount = await program.account.event.fetch(eventPda);
+      originalStartDate = eventAccount.startDate.toNumber();
+      originalEndDate = eventAccount.endDate.toNumber();
+      
+      // Mint a fresh ticket so its validity can be checked across the postponement
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), postponedMint.publicKey.toBuffer()],
+        program.programId
+      );
+      [postponedTicket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), postponedMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), postponedMint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          postponedMint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-postponed.json', null, null)
+        .accounts({
+          event: eventPda,
+          ticketType: ticketTypePda,
+          kycRegistry: null,
+          mint: postponedMint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(postponedMint.publicKey, buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket: postponedTicket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([postponedMint])
+        .rpc();
+    });
+    
+    it('Postpones an event and records its original dates', async () => {
+      await postponeEvent(originalStartDate + POSTPONE_BY, originalEndDate + POSTPONE_BY, false);
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.deepEqual(eventAccount.status, { postponed: {} });
+      assert.equal(eventAccount.startDate.toNumber(), originalStartDate + POSTPONE_BY);
+      assert.equal(eventAccount.endDate.toNumber(), originalEndDate + POSTPONE_BY);
+      assert.equal(eventAccount.originalStartDate.toNumber(), originalStartDate);
+      assert.equal(eventAccount.originalEndDate.toNumber(), originalEndDate);
+      assert.isFalse(eventAccount.refundsOpen);
+      assert.isTrue(eventAccount.active);
+    });
+    
+    it('Keeps tickets valid and transferable after a postponement', async () => {
+      let ticketAccount = await program.account.ticket.fetch(postponedTicket);
+      assert.deepEqual(ticketAccount.status, { valid: {} });
+      
+      const fromTokenAccount = await getAssociatedTokenAddress(postponedMint.publicKey, buyer.publicKey);
+      const toTokenAccount = await getAssociatedTokenAddress(postponedMint.publicKey, secondBuyer.publicKey);
+      const transaction = new Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          buyer.publicKey,
+          toTokenAccount,
+          secondBuyer.publicKey,
+          postponedMint.publicKey
+        ),
+        await program.methods
+          .transferTicket()
+          .accounts({
+            ticket: postponedTicket,
+            ticketType: (await program.account.ticket.fetch(postponedTicket)).ticketType,
+            mint: postponedMint.publicKey,
+            fromTokenAccount,
+            toTokenAccount,
+            from: buyer.publicKey,
+            to: secondBuyer.publicKey,
+            event: eventPda,
+            kycRegistry: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .instruction()
+      );
+      await sendAndConfirmTransaction(provider.connection, transaction, [buyer.payer]);
+      
+      ticketAccount = await program.account.ticket.fetch(postponedTicket);
+      assert.deepEqual(ticketAccount.status, { valid: {} });
+      assert.equal(ticketAccount.owner.toString(), secondBuyer.publicKey.toString());
+    });
+    
+    const refundTicket = async () => {
+      await program.methods
+        .refundTicket()
+        .accounts({
+          event: eventPda,
+          ticket: postponedTicket,
+          ticketType: ticketTypePda,
+          holder: secondBuyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([secondBuyer, eventOrganizer])
+        .rpc();
+    };
+    
+    it('Rejects a refund while refunds are closed', async () => {
+      try {
+        await refundTicket();
+        assert.fail('Refund before refunds are opened should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'RefundsNotOpen');
+      }
+    });
+    
+    it('Opens refunds on a further postponement without losing the original dates', async () => {
+      await postponeEvent(originalStartDate + 2 * POSTPONE_BY, originalEndDate + 2 * POSTPONE_BY, true);
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.deepEqual(eventAccount.status, { postponed: {} });
+      assert.equal(eventAccount.startDate.toNumber(), originalStartDate + 2 * POSTPONE_BY);
+      assert.equal(eventAccount.originalStartDate.toNumber(), originalStartDate);
+      assert.equal(eventAccount.originalEndDate.toNumber(), originalEndDate);
+      assert.isTrue(eventAccount.refundsOpen);
+    });
+    
+    it('Refunds the face value once refunds are open', async () => {
+      const holderBefore = await provider.connection.getBalance(secondBuyer.publicKey);
+      await refundTicket();
+      
+      const holderAfter = await provider.connection.getBalance(secondBuyer.publicKey);
+      assert.equal(holderAfter - holderBefore, ticketPrice.toNumber());
+      const ticketAccount = await program.account.ticket.fetch(postponedTicket);
+      assert.deepEqual(ticketAccount.status, { revoked: {} });
+      
+      // A refunded ticket can't be refunded again
+      try {
+        await refundTicket();
+        assert.fail('Second refund should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidTicket');
+      }
+    });
+    
+    it('Rejects a postponement to an earlier date', async () => {
+      try {
+        await postponeEvent(originalStartDate, originalEndDate, false);
+        assert.fail('Postponing to an earlier date should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidEventDates');
+      }
+    });
+  });
+  
+  describe('Multi-Unit Purchases', () => {
+    const batchTypeId = 'ga-batch';
+    const BATCH_QUANTITY = 5;
+    const batchPrice = new anchor.BN(1000000);
+    let batchTypePda: PublicKey;
+    
+    const walletMintRecordFor = async (wallet: PublicKey) => {
+      const [recordPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('wallet_mint_record'), batchTypePda.toBuffer(), wallet.toBuffer()],
+        program.programId
+      );
+      return recordPda;
+    };
+    
+    // Per-ticket remaining accounts for serial numbers after `sold`
+    const batchAccountsFor = async (owner: PublicKey, sold: number, quantity: number) => {
+      const accounts = [];
+      for (let serial = sold + 1; serial <= sold + quantity; serial++) {
+        const serialBytes = Buffer.alloc(4);
+        serialBytes.writeUInt32LE(serial);
+        const [mint] = await PublicKey.findProgramAddress(
+          [Buffer.from('ticket_mint'), batchTypePda.toBuffer(), serialBytes],
+          program.programId
+        );
+        const [mintAuthority] = await PublicKey.findProgramAddress(
+          [Buffer.from('ticket_authority'), mint.toBuffer()],
+          program.programId
+        );
+        const [metadataAddress] = await PublicKey.findProgramAddress(
+          [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+          TOKEN_METADATA_PROGRAM_ID
+        );
+        const [masterEditionAddress] = await PublicKey.findProgramAddress(
+          [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from('edition')],
+          TOKEN_METADATA_PROGRAM_ID
+        );
+        const [ticket] = await PublicKey.findProgramAddress(
+          [Buffer.from('ticket'), mint.toBuffer()],
+          program.programId
+        );
+        accounts.push(
+          { pubkey: mint, isWritable: true, isSigner: false },
+          { pubkey: mintAuthority, isWritable: false, isSigner: false },
+          { pubkey: await getAssociatedTokenAddress(mint, owner), isWritable: true, isSigner: false },
+          { pubkey: metadataAddress, isWritable: true, isSigner: false },
+          { pubkey: masterEditionAddress, isWritable: true, isSigner: false },
+          { pubkey: ticket, isWritable: true, isSigner: false },
+        );
+      }
+      return accounts;
+    };
+    
+    const mintTickets = async (owner: Keypair, quantity: number) => {
+      const ticketType = await program.account.ticketType.fetch(batchTypePda);
+      const remainingAccounts = await batchAccountsFor(owner.publicKey, ticketType.sold, quantity);
+      
+      await program.methods
+        .mintTickets(quantity, 'https://tickettoken.app/metadata/ga-batch.json', null)
+        .accounts({
+          event: eventPda,
+          ticketType: batchTypePda,
+          kycRegistry: null,
+          walletMintRecord: await walletMintRecordFor(owner.publicKey),
+          buyer: owner.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .remainingAccounts(remainingAccounts)
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+        .signers(owner.publicKey.equals(buyer.publicKey) ? [] : [owner])
+        .rpc();
+      
+      return remainingAccounts;
+    };
+    
+    before(async () => {
+      [batchTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), eventPda.toBuffer(), Buffer.from(batchTypeId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createTicketType(batchTypeId, 'General Admission', 'Standing room', batchPrice, BATCH_QUANTITY, [])
+        .accounts({
+          event: eventPda,
+          ticketType: batchTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    });
+    
+    it('Buys three tickets in one instruction, each its own NFT', async () => {
+      const organizerBefore = await provider.connection.getBalance(eventOrganizer.publicKey);
+      
+      const remainingAccounts = await mintTickets(buyer.payer, 3);
+      
+      const ticketType = await program.account.ticketType.fetch(batchTypePda);
+      assert.equal(ticketType.sold, 3);
+      const record = await program.account.walletMintRecord.fetch(await walletMintRecordFor(buyer.publicKey));
+      assert.equal(record.minted, 3);
+      assert.equal(
+        await provider.connection.getBalance(eventOrganizer.publicKey),
+        organizerBefore + 3 * batchPrice.toNumber()
+      );
+      
+      for (let i = 0; i < 3; i++) {
+        const mint = remainingAccounts[i * 6].pubkey;
+        const ticket = await program.account.ticket.fetch(remainingAccounts[i * 6 + 5].pubkey);
+        assert.equal(ticket.mint.toString(), mint.toString());
+        assert.equal(ticket.owner.toString(), buyer.publicKey.toString());
+        assert.equal(ticket.serialNumber, i + 1);
+        assert.deepEqual(ticket.status, { valid: {} });
+        
+        const tokenAccount = await getAccount(provider.connection, remainingAccounts[i * 6 + 2].pubkey);
+        assert.equal(Number(tokenAccount.amount), 1);
+      }
+    });
+    
+    it('Rejects a quantity over the remaining supply', async () => {
+      // Two of five tickets remain
+      try {
+        await mintTickets(buyer.payer, 3);
+        assert.fail('Buying past the remaining supply should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'TicketTypeSoldOut');
+      }
+      
+      const ticketType = await program.account.ticketType.fetch(batchTypePda);
+      assert.equal(ticketType.sold, 3);
+    });
+    
+    it('Enforces the per-wallet cap across purchases', async () => {
+      await program.methods
+        .setMaxPerWallet(3)
+        .accounts({
+          event: eventPda,
+          ticketType: batchTypePda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      // buyer already holds three, so even one more is over the cap
+      try {
+        await mintTickets(buyer.payer, 1);
+        assert.fail('Buying past the wallet cap should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'WalletMintLimitExceeded');
+      }
+      
+      // Another wallet still has its own allowance
+      await mintTickets(secondBuyer, 2);
+      const record = await program.account.walletMintRecord.fetch(await walletMintRecordFor(secondBuyer.publicKey));
+      assert.equal(record.minted, 2);
+      
+      const ticketType = await program.account.ticketType.fetch(batchTypePda);
+      assert.equal(ticketType.sold, BATCH_QUANTITY);
+    });
+  });
+  
+  describe('Event Details Anchoring', () => {
+    const anchoredEventId = 'anchored-event-001';
+    const anchoredName = 'Anchored Festival';
+    const anchoredVenue = 'Harbor Stage';
+    const anchoredStart = now + 30 * 86400;
+    const anchoredEnd = anchoredStart + 86400;
+    let anchoredEventPda: PublicKey;
+    
+    // Mirrors Event::compute_details_hash: length-prefixed strings, LE dates
+    const detailsHash = (name: string, venue: string, start: number, end: number): number[] => {
+      const lengthPrefixed = (value: string) => {
+        const bytes = Buffer.from(value);
+        const length = Buffer.alloc(4);
+        length.writeUInt32LE(bytes.length);
+        return Buffer.concat([length, bytes]);
+      };
+      const timestamp = (value: number) => new anchor.BN(value).toTwos(64).toArrayLike(Buffer, 'le', 8);
+      
+      return Array.from(
+        createHash('sha256')
+          .update(Buffer.concat([lengthPrefixed(name), lengthPrefixed(venue), timestamp(start), timestamp(end)]))
+          .digest()
+      );
+    };
+    
+    const verifyDetails = async (hash: number[]) => {
+      await program.methods
+        .verifyEventDetails(hash)
+        .accounts({
+          event: anchoredEventPda,
+        })
+        .rpc();
+    };
+    
+    before(async () => {
+      [anchoredEventPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('event'), eventOrganizer.publicKey.toBuffer(), Buffer.from(anchoredEventId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createEvent(
+          anchoredEventId,
+          anchoredName,
+          eventSymbol,
+          eventDescription,
+          anchoredVenue,
+          new anchor.BN(anchoredStart),
+          new anchor.BN(anchoredEnd),
+          ticketQuantity,
+          500
+        )
+        .accounts({
+          event: anchoredEventPda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    });
+    
+    it('Anchors the details hash at creation and verifies a matching hash', async () => {
+      const expectedHash = detailsHash(anchoredName, anchoredVenue, anchoredStart, anchoredEnd);
+      
+      const eventAccount = await program.account.event.fetch(anchoredEventPda);
+      assert.deepEqual(Array.from(eventAccount.detailsHash), expectedHash);
+      
+      await verifyDetails(expectedHash);
+    });
+    
+    it('Rejects a hash of tampered details', async () => {
+      const tamperedHash = detailsHash(anchoredName, 'Back Alley Stage', anchoredStart, anchoredEnd);
+      
+      try {
+        await verifyDetails(tamperedHash);
+        assert.fail('Verification should fail for tampered details');
+      } catch (error) {
+        assert.include(error.toString(), 'EventDetailsMismatch');
+      }
+    });
+    
+    it('Updates the anchored hash on legitimate edits and logs both hashes', async () => {
+      const previousHash = detailsHash(anchoredName, anchoredVenue, anchoredStart, anchoredEnd);
+      const updatedName = 'Anchored Festival (Extended)';
+      const updatedEnd = anchoredEnd + 3600;
+      const updatedHash = detailsHash(updatedName, anchoredVenue, anchoredStart, updatedEnd);
+      
+      let loggedUpdate: any = null;
+      const listener = program.addEventListener('EventUpdated', (event) => {
+        loggedUpdate = event;
+      });
+      
+      await program.methods
+        .updateEvent(updatedName, null, null, null, new anchor.BN(updatedEnd))
+        .accounts({
+          event: anchoredEventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+      
+      const eventAccount = await program.account.event.fetch(anchoredEventPda);
+      assert.deepEqual(Array.from(eventAccount.detailsHash), updatedHash);
+      
+      assert.isNotNull(loggedUpdate);
+      assert.deepEqual(Array.from(loggedUpdate.previousDetailsHash), previousHash);
+      assert.deepEqual(Array.from(loggedUpdate.detailsHash), updatedHash);
+      
+      await verifyDetails(updatedHash);
+      
+      // The pre-edit hash no longer matches
+      try {
+        await verifyDetails(previousHash);
+        assert.fail('Verification should fail for the superseded hash');
+      } catch (error) {
+        assert.include(error.toString(), 'EventDetailsMismatch');
+      }
+    });
+  });
+  
+  describe('Batch Status Limits', () => {
+    let batchTicketPdas: PublicKey[];
+    
+    // Ticket accounts minted by the Multi-Unit Purchases suite, by serial number
+    const batchTicketPdaFor = async (serial: number) => {
+      const [batchTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), eventPda.toBuffer(), Buffer.from('ga-batch')],
+        program.programId
+      );
+      const serialBytes = Buffer.alloc(4);
+      serialBytes.writeUInt32LE(serial);
+      const [mint] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_mint'), batchTypePda.toBuffer(), serialBytes],
+        program.programId
+      );
+      const [ticket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), mint.toBuffer()],
+        program.programId
+      );
+      return ticket;
+    };
+    
+    const setMaxBatchSize = async (maxBatchSize: number) => {
+      await program.methods
+        .setMaxBatchSize(maxBatchSize)
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    const batchUpdate = (tickets: PublicKey[], newStatus: any) =>
+      program.methods
+        .batchUpdateTicketStatus(newStatus)
+        .accounts({
+          event: eventPda,
+          validator: eventOrganizer.publicKey,
+        })
+        .remainingAccounts(tickets.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+        .signers([eventOrganizer]);
+    
+    before(async () => {
+      batchTicketPdas = [];
+      for (let serial = 1; serial <= 5; serial++) {
+        batchTicketPdas.push(await batchTicketPdaFor(serial));
+      }
+      
+      await setMaxBatchSize(2);
+    });
+    
+    it('Updates a batch at the limit and returns the count processed', async () => {
+      const batch = batchTicketPdas.slice(0, 2);
+      
+      const processed = await batchUpdate(batch, { used: {} }).view();
+      assert.equal(processed, 2);
+      
+      const checkedInBefore = (await program.account.event.fetch(eventPda)).checkedInCount;
+      await batchUpdate(batch, { used: {} }).rpc();
+      
+      for (const ticketPda of batch) {
+        const ticket = await program.account.ticket.fetch(ticketPda);
+        assert.deepEqual(ticket.status, { used: {} });
+        assert.isNotNull(ticket.usedAt);
+      }
+      assert.equal((await program.account.event.fetch(eventPda)).checkedInCount, checkedInBefore + 2);
+      
+      // Marking the same tickets used again doesn't count them twice
+      await batchUpdate(batch, { used: {} }).rpc();
+      assert.equal((await program.account.event.fetch(eventPda)).checkedInCount, checkedInBefore + 2);
+    });
+    
+    it('Rejects a batch over the limit', async () => {
+      try {
+        await batchUpdate(batchTicketPdas.slice(2, 5), { used: {} }).rpc();
+        assert.fail('Batch over the limit should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'BatchOperationLimitExceeded');
+      }
+      
+      const ticket = await program.account.ticket.fetch(batchTicketPdas[2]);
+      assert.deepEqual(ticket.status, { valid: {} });
+    });
+    
+    it('Skips ineligible accounts within a valid batch', async () => {
+      await setMaxBatchSize(3);
+      
+      // The event account isn't a ticket and is passed over
+      const batch = [batchTicketPdas[0], eventPda, batchTicketPdas[2]];
+      
+      const processed = await batchUpdate(batch, { expired: {} }).view();
+      assert.equal(processed, 2);
+      
+      // A used ticket can't go back to valid
+      const skipped = await batchUpdate([batchTicketPdas[1], eventPda], { valid: {} }).view();
+      assert.equal(skipped, 0);
+      
+      await batchUpdate(batch, { expired: {} }).rpc();
+      
+      const expired = await program.account.ticket.fetch(batchTicketPdas[2]);
+      assert.deepEqual(expired.status, { expired: {} });
+      const untouched = await program.account.ticket.fetch(batchTicketPdas[3]);
+      assert.deepEqual(untouched.status, { valid: {} });
+    });
+  });
+  
+  describe('Primary Sale Splits', () => {
+    const venue = Keypair.generate();
+    const artist = Keypair.generate();
+    const promoter = Keypair.generate();
+    const SPLIT = [
+      { payee: venue, shareBps: 5000 },
+      { payee: artist, shareBps: 3000 },
+      { payee: promoter, shareBps: 2000 },
+    ];
+    
+    const setPrimaryPayees = async (payees: { recipient: PublicKey; shareBps: number }[]) => {
+      await program.methods
+        .setPrimaryPayees(payees)
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    // Mints a fresh ticket of the VIP type, passing the payee accounts in order
+    const mintWithPayees = async (payees: PublicKey[]) => {
+      const mint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [ticket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-split.json', null, null)
+        .accounts({
+          event: eventPda,
+          ticketType: ticketTypePda,
+          kycRegistry: null,
+          mint: mint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(mint.publicKey, buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .remainingAccounts(payees.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+        .signers([mint])
+        .rpc();
+    };
+    
+    const balanceOf = (pubkey: PublicKey) => provider.connection.getBalance(pubkey);
+    
+    it('Splits a primary sale three ways', async () => {
+      await setPrimaryPayees(SPLIT.map(({ payee, shareBps }) => ({ recipient: payee.publicKey, shareBps })));
+      
+      const organizerBefore = await balanceOf(eventOrganizer.publicKey);
+      await mintWithPayees(SPLIT.map(({ payee }) => payee.publicKey));
+      
+      for (const { payee, shareBps } of SPLIT) {
+        const expected = (ticketPrice.toNumber() * shareBps) / 10000;
+        assert.equal(await balanceOf(payee.publicKey), expected);
+      }
+      // The organizer isn't paid directly once a split is configured
+      assert.equal(await balanceOf(eventOrganizer.publicKey), organizerBefore);
+    });
+    
+    it('Rejects payee shares that do not add up to 100%', async () => {
+      try {
+        await setPrimaryPayees([
+          { recipient: venue.publicKey, shareBps: 5000 },
+          { recipient: artist.publicKey, shareBps: 3000 },
+        ]);
+        assert.fail('Shares summing to 80% should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidPrimaryPayees');
+      }
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      assert.equal(eventAccount.primaryPayees.length, SPLIT.length);
+    });
+    
+    it('Pays the organizer alone when no split is configured', async () => {
+      await setPrimaryPayees([]);
+      
+      const organizerBefore = await balanceOf(eventOrganizer.publicKey);
+      const venueBefore = await balanceOf(venue.publicKey);
+      await mintWithPayees([]);
+      
+      assert.equal(await balanceOf(eventOrganizer.publicKey) - organizerBefore, ticketPrice.toNumber());
+      assert.equal(await balanceOf(venue.publicKey), venueBefore);
+    });
+  });
+  
+  describe('Ticket Type Presale Codes', () => {
+    const presaleTypeId = 'vip-presale';
+    const presalePrice = new anchor.BN(1000000);
+    const PRESALE_CODE = 'VIP-EARLY-ACCESS';
+    const PRESALE_WINDOW = 4; // seconds
+    let presaleTypePda: PublicKey;
+    
+    const hashPresaleCode = (code: string) => Array.from(createHash('sha256').update(code).digest());
+    
+    // Mints a fresh ticket of the presale type, optionally presenting a code
+    const mintPresaleTicket = async (presaleCode: string | null) => {
+      const mint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [ticket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-presale.json', null, presaleCode)
+        .accounts({
+          event: eventPda,
+          ticketType: presaleTypePda,
+          kycRegistry: null,
+          mint: mint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(mint.publicKey, buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint])
+        .rpc();
+      
+      return ticket;
+    };
+    
+    before(async () => {
+      [presaleTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), eventPda.toBuffer(), Buffer.from(presaleTypeId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createTicketType(presaleTypeId, 'VIP Presale', 'Early access for VIP members', presalePrice, 10, [])
+        .accounts({
+          event: eventPda,
+          ticketType: presaleTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .setPresaleCode(hashPresaleCode(PRESALE_CODE), new anchor.BN(Math.floor(Date.now() / 1000) + PRESALE_WINDOW))
+        .accounts({
+          event: eventPda,
+          ticketType: presaleTypePda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    });
+    
+    it('Mints during the presale with the correct code', async () => {
+      const ticket = await mintPresaleTicket(PRESALE_CODE);
+      
+      const ticketAccount = await program.account.ticket.fetch(ticket);
+      assert.equal(ticketAccount.ticketType.toString(), presaleTypePda.toString());
+      const ticketType = await program.account.ticketType.fetch(presaleTypePda);
+      assert.equal(ticketType.sold, 1);
+    });
+    
+    it('Rejects a wrong or missing code during the presale', async () => {
+      try {
+        await mintPresaleTicket('NOT-THE-CODE');
+        assert.fail('Minting with a wrong code should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidPresaleCode');
+      }
+      
+      try {
+        await mintPresaleTicket(null);
+        assert.fail('Minting without a code should fail during the presale');
+      } catch (error) {
+        assert.include(error.toString(), 'PresaleAccessRequired');
+      }
+      
+      const ticketType = await program.account.ticketType.fetch(presaleTypePda);
+      assert.equal(ticketType.sold, 1);
+    });
+    
+    it('Opens minting to everyone after the presale', async () => {
+      await new Promise((resolve) => setTimeout(resolve, (PRESALE_WINDOW + 1) * 1000));
+      
+      await mintPresaleTicket(null);
+      
+      const ticketType = await program.account.ticketType.fetch(presaleTypePda);
+      assert.equal(ticketType.sold, 2);
+    });
+  });
+  
+  describe('Dutch Auction Slippage Protection', () => {
+    // The first ticket is held by buyer after the closing listing tests
+    const listingId = 'dutch-slippage';
+    const startPrice = new anchor.BN(2000000);
+    const endPrice = new anchor.BN(1000000);
+    let paymentMint: PublicKey;
+    let listingPda: PublicKey;
+    
+    const purchaseDutchAuction = async (maxPrice: anchor.BN) => {
+      await program.methods
+        .purchaseListing(maxPrice)
+        .accounts({
+          ticket: ticketPda,
+          listing: listingPda,
+          event: eventPda,
+          kycRegistry: null,
+          mint: mintKeypair.publicKey,
+          fromTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, buyer.publicKey),
+          toTokenAccount: await getAssociatedTokenAddress(mintKeypair.publicKey, validator.publicKey),
+          seller: buyer.publicKey,
+          buyer: validator.publicKey,
+          paymentFromAccount: await getAssociatedTokenAddress(paymentMint, validator.publicKey),
+          paymentToAccount: await getAssociatedTokenAddress(paymentMint, buyer.publicKey),
+          royaltyAccount: null,
+          transferRecord: null,
+          eventListingIndex: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([validator])
+        .rpc();
+    };
+    
+    before(async () => {
+      paymentMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      await program.methods
+        .addAcceptedPaymentMint(paymentMint)
+        .accounts({
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          admin: buyer.publicKey,
+        })
+        .rpc();
+      
+      const purchaserPayment = await createAssociatedTokenAccount(
+        provider.connection, buyer.payer, paymentMint, validator.publicKey
+      );
+      await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, buyer.publicKey);
+      await mintTo(provider.connection, buyer.payer, paymentMint, purchaserPayment, buyer.payer, startPrice.toNumber());
+      
+      [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createDutchAuction(listingId, startPrice, endPrice, new anchor.BN(3600))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          owner: buyer.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .rpc();
+    });
+    
+    it('Rejects a purchase when the current price exceeds the buyer maximum', async () => {
+      // An hour-long decay has barely moved off the starting price
+      try {
+        await purchaseDutchAuction(endPrice);
+        assert.fail('Purchase above the maximum price should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'SlippageToleranceExceeded');
+      }
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.deepEqual(listingAccount.status, { auctionActive: {} });
+    });
+    
+    it('Buys the auction at its current price within the buyer maximum', async () => {
+      const sellerPayment = await getAssociatedTokenAddress(paymentMint, buyer.publicKey);
+      
+      await purchaseDutchAuction(startPrice);
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.deepEqual(listingAccount.status, { sold: {} });
+      
+      const ticketAccount = await program.account.ticket.fetch(ticketPda);
+      assert.equal(ticketAccount.owner.toString(), validator.publicKey.toString());
+      
+      // The decayed price lies between the ending price and the maximum
+      const paid = Number((await getAccount(provider.connection, sellerPayment)).amount);
+      assert.isAtMost(paid, startPrice.toNumber());
+      assert.isAbove(paid, endPrice.toNumber());
+    });
+  });
+  
+  describe('Auction Currency Lock', () => {
+    // The first ticket is held by validator after the slippage tests
+    const listingId = 'currency-lock';
+    const bidAmount = 1000;
+    let auctionMint: PublicKey;
+    let otherMint: PublicKey;
+    let listingPda: PublicKey;
+    let auctionHistoryPda: PublicKey;
+    let escrowAuthority: PublicKey;
+    
+    const placeBid = async (bidder: Keypair, amount: number, paymentMint: PublicKey, refundTo: PublicKey | null) => {
+      await program.methods
+        .placeBid(new anchor.BN(amount))
+        .accounts({
+          ticket: ticketPda,
+          listing: listingPda,
+          auctionHistory: auctionHistoryPda,
+          bidder: bidder.publicKey,
+          paymentFromAccount: await getAssociatedTokenAddress(paymentMint, bidder.publicKey),
+          escrowAccount: await getAssociatedTokenAddress(paymentMint, escrowAuthority, true),
+          escrowAuthority,
+          refundAccount: refundTo ? await getAssociatedTokenAddress(paymentMint, refundTo) : null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([bidder])
+        .rpc();
+    };
+    
+    before(async () => {
+      [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticketPda.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      [auctionHistoryPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('auction_history'), listingPda.toBuffer()],
+        program.programId
+      );
+      [escrowAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('escrow'), listingPda.toBuffer()],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      // Both mints are accepted by the marketplace, but the auction is listed in one
+      auctionMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      otherMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      for (const paymentMint of [auctionMint, otherMint]) {
+        await program.methods
+          .addAcceptedPaymentMint(paymentMint)
+          .accounts({
+            acceptedPaymentMints: acceptedPaymentMintsPda,
+            admin: buyer.publicKey,
+          })
+          .rpc();
+        for (const owner of [buyer.publicKey, secondBuyer.publicKey]) {
+          const account = await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, owner);
+          await mintTo(provider.connection, buyer.payer, paymentMint, account, buyer.payer, 100000);
+        }
+        await getOrCreateAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, escrowAuthority, true);
+      }
+      
+      await program.methods
+        .createAuction(listingId, new anchor.BN(bidAmount), new anchor.BN(100), new anchor.BN(3600))
+        .accounts({
+          ticket: ticketPda,
+          ticketType: (await program.account.ticket.fetch(ticketPda)).ticketType,
+          mint: mintKeypair.publicKey,
+          listing: listingPda,
+          auctionHistory: auctionHistoryPda,
+          lastListingAttempt: attemptPda,
+          event: eventPda,
+          eventListingIndex: null,
+          owner: validator.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint: auctionMint,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([validator])
+        .rpc();
+    });
+    
+    it('Accepts bids in the auction currency', async () => {
+      await placeBid(buyer.payer, bidAmount, auctionMint, null);
+      await placeBid(secondBuyer, bidAmount + 100, auctionMint, buyer.publicKey);
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.paymentMint.toString(), auctionMint.toString());
+      assert.equal(listingAccount.highestBid.toNumber(), bidAmount + 100);
+      assert.equal(listingAccount.highestBidder.toString(), secondBuyer.publicKey.toString());
+      
+      const escrow = await getAccount(
+        provider.connection,
+        await getAssociatedTokenAddress(auctionMint, escrowAuthority, true)
+      );
+      assert.equal(Number(escrow.amount), bidAmount + 100);
+    });
+    
+    it('Rejects a bid in a different currency', async () => {
+      try {
+        await placeBid(buyer.payer, bidAmount + 500, otherMint, secondBuyer.publicKey);
+        assert.fail('Bid in another mint should be rejected');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidPaymentToken');
+      }
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.equal(listingAccount.highestBidder.toString(), secondBuyer.publicKey.toString());
+    });
+  });
+  
+  describe('Ticket Metadata Schema Versions', () => {
+    const schemaTypeId = 'schema-versions';
+    let schemaTypePda: PublicKey;
+    let legacyMint: Keypair;
+    let legacyTicket: PublicKey;
+    
+    const setSchemaVersion = async (version: number) => {
+      await program.methods
+        .setTicketSchemaVersion(version)
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    const migrate = async (ticket: PublicKey) => {
+      await program.methods
+        .migrateTicketMetadata()
+        .accounts({ event: eventPda, ticket })
+        .rpc();
+    };
+    
+    // Mints a fresh ticket of the schema test type to buyer
+    const mintSchemaTicket = async () => {
+      const mint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [ticket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-schema.json', null, null)
+        .accounts({
+          event: eventPda,
+          ticketType: schemaTypePda,
+          kycRegistry: null,
+          mint: mint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(mint.publicKey, buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint])
+        .rpc();
+      
+      return { mint, ticket };
+    };
+    
+    const transferToSecondBuyer = async (mint: Keypair, ticket: PublicKey) => {
+      const fromTokenAccount = await getAssociatedTokenAddress(mint.publicKey, buyer.publicKey);
+      const toTokenAccount = await getAssociatedTokenAddress(mint.publicKey, secondBuyer.publicKey);
+      const transaction = new Transaction();
+      if (!(await provider.connection.getAccountInfo(toTokenAccount))) {
+        transaction.add(
+          createAssociatedTokenAccountInstruction(
+            buyer.publicKey,
+            toTokenAccount,
+            secondBuyer.publicKey,
+            mint.publicKey
+          )
+        );
+      }
+      transaction.add(
+        await program.methods
+          .transferTicket()
+          .accounts({
+            ticket,
+            ticketType: (await program.account.ticket.fetch(ticket)).ticketType,
+            mint: mint.publicKey,
+            fromTokenAccount,
+            toTokenAccount,
+            from: buyer.publicKey,
+            to: secondBuyer.publicKey,
+            event: eventPda,
+            kycRegistry: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .instruction()
+      );
+      await sendAndConfirmTransaction(provider.connection, transaction, [buyer.payer]);
+    };
+    
+    before(async () => {
+      [schemaTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), eventPda.toBuffer(), Buffer.from(schemaTypeId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createTicketType(schemaTypeId, 'Schema Test', 'Tickets for schema migration tests', new anchor.BN(1000000), 10, [])
+        .accounts({
+          event: eventPda,
+          ticketType: schemaTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      // Issue a ticket on the legacy schema
+      await setSchemaVersion(1);
+      ({ mint: legacyMint, ticket: legacyTicket } = await mintSchemaTicket());
+    });
+    
+    it('Rejects schema versions the program does not support', async () => {
+      for (const version of [0, 3]) {
+        try {
+          await setSchemaVersion(version);
+          assert.fail('Unsupported schema version should be rejected');
+        } catch (error) {
+          assert.include(error.toString(), 'InvalidSchemaVersion');
+        }
+      }
+    });
+    
+    it('Blocks transfers of tickets on an outdated schema', async () => {
+      const ticketAccount = await program.account.ticket.fetch(legacyTicket);
+      assert.equal(ticketAccount.metadataSchemaVersion, 1);
+      assert.deepEqual(ticketAccount.eventDetailsHash, new Array(32).fill(0));
+      
+      await setSchemaVersion(2);
+      
+      try {
+        await transferToSecondBuyer(legacyMint, legacyTicket);
+        assert.fail('Transfer of an outdated ticket should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'TicketSchemaOutdated');
+      }
+    });
+    
+    it('Migrates a v1 ticket to the current schema', async () => {
+      await migrate(legacyTicket);
+      
+      const eventAccount = await program.account.event.fetch(eventPda);
+      const ticketAccount = await program.account.ticket.fetch(legacyTicket);
+      assert.equal(ticketAccount.metadataSchemaVersion, 2);
+      assert.deepEqual(ticketAccount.eventDetailsHash, eventAccount.detailsHash);
+      
+      // The migrated ticket clears the schema gate
+      await transferToSecondBuyer(legacyMint, legacyTicket);
+      const transferred = await program.account.ticket.fetch(legacyTicket);
+      assert.equal(transferred.owner.toString(), secondBuyer.publicKey.toString());
+    });
+    
+    it('Leaves tickets already on the current schema unchanged', async () => {
+      const { ticket } = await mintSchemaTicket();
+      const unchanged = await program.account.ticket.fetch(ticket);
+      assert.equal(unchanged.metadataSchemaVersion, 2);
+      
+      await migrate(ticket);
+      await migrate(legacyTicket);
+      
+      const after = await program.account.ticket.fetch(ticket);
+      assert.equal(after.metadataSchemaVersion, 2);
+      assert.deepEqual(after.eventDetailsHash, unchanged.eventDetailsHash);
+      
+      const migrated = await program.account.ticket.fetch(legacyTicket);
+      assert.equal(migrated.metadataSchemaVersion, 2);
+    });
+  });
+  
+  describe('Batch Ticket Verification', () => {
+    // A separate event already under way, so tickets can be scanned for entry
+    const gateEventId = 'gate-event-001';
+    const gateTypeId = 'gate-ga';
+    let gateEventPda: PublicKey;
+    let gateTypePda: PublicKey;
+    let gateTickets: PublicKey[];
+    
+    const mintGateTicket = async () => {
+      const mint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [ticket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-gate.json', null, null)
+        .accounts({
+          event: gateEventPda,
+          ticketType: gateTypePda,
+          kycRegistry: null,
+          mint: mint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(mint.publicKey, buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint])
+        .rpc();
+      
+      return ticket;
+    };
+    
+    const batchVerify = (tickets: PublicKey[], isWritable = true) =>
+      program.methods
+        .batchVerifyTickets()
+        .accounts({
+          event: gateEventPda,
+          validator: eventOrganizer.publicKey,
+        })
+        .remainingAccounts(tickets.map((pubkey) => ({ pubkey, isWritable, isSigner: false })))
+        .signers([eventOrganizer]);
+    
+    before(async () => {
+      [gateEventPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('event'), eventOrganizer.publicKey.toBuffer(), Buffer.from(gateEventId)],
+        program.programId
+      );
+      [gateTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), gateEventPda.toBuffer(), Buffer.from(gateTypeId)],
+        program.programId
+      );
+      
+      const startedAt = Math.floor(Date.now() / 1000) - 3600;
+      await program.methods
+        .createEvent(
+          gateEventId,
+          'Gate Throughput Test',
+          eventSymbol,
+          eventDescription,
+          'Main Gate',
+          new anchor.BN(startedAt),
+          new anchor.BN(startedAt + 2 * 86400),
+          ticketQuantity,
+          500
+        )
+        .accounts({
+          event: gateEventPda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .createTicketType(gateTypeId, 'General Admission', 'Gate scanning tickets', new anchor.BN(1000000), 10, [])
+        .accounts({
+          event: gateEventPda,
+          ticketType: gateTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      gateTickets = [];
+      for (let i = 0; i < 3; i++) {
+        gateTickets.push(await mintGateTicket());
+      }
+    });
+    
+    it('Reports per-ticket results for a mixed batch', async () => {
+      // Use one ticket up front so the batch holds a used ticket
+      await batchVerify([gateTickets[0]]).rpc();
+      
+      // The main event's ticket is for a different event
+      const batch = [gateTickets[1], gateTickets[0], ticketPda, gateTickets[2]];
+      
+      const results = await batchVerify(batch).view();
+      assert.deepEqual(results, [{ valid: {} }, { used: {} }, { invalid: {} }, { valid: {} }]);
+      
+      // A valid ticket passed read-only can't be marked used
+      await batchVerify([gateTickets[1]], false).rpc();
+      const readOnly = await program.account.ticket.fetch(gateTickets[1]);
+      assert.deepEqual(readOnly.status, { valid: {} });
+      assert.deepEqual(await batchVerify([gateTickets[1]], false).view(), [{ invalid: {} }]);
+      
+      await batchVerify(batch).rpc();
+      
+      for (const ticketPda of gateTickets) {
+        const ticket = await program.account.ticket.fetch(ticketPda);
+        assert.deepEqual(ticket.status, { used: {} });
+        assert.isNotNull(ticket.usedAt);
+      }
+      
+      // Scanning the same tickets again reports them all as used
+      const rescanned = await batchVerify(gateTickets).view();
+      assert.deepEqual(rescanned, [{ used: {} }, { used: {} }, { used: {} }]);
+    });
+    
+    it('Rejects a batch over the max batch size', async () => {
+      await program.methods
+        .setMaxBatchSize(2)
+        .accounts({
+          event: gateEventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      try {
+        await batchVerify(gateTickets).rpc();
+        assert.fail('Batch over the limit should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'BatchOperationLimitExceeded');
+      }
+    });
+  });
+  
+  describe('Expired Challenge Cleanup', () => {
+    const challengeTypeId = 'challenge-cleanup';
+    let challengeTypePda: PublicKey;
+    let challengeMint: Keypair;
+    let challengeTicket: PublicKey;
+    
+    const setChallengeTtl = async (ttl: number) => {
+      await program.methods
+        .setChallengeTtl(new anchor.BN(ttl))
+        .accounts({
+          event: eventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    // Generates a challenge for the cleanup ticket, paid for by the organizer
+    const generateChallenge = async () => {
+      const nonce = new anchor.BN(Date.now());
+      const [challengePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('verification'), challengeMint.publicKey.toBuffer(), nonce.toArrayLike(Buffer, 'le', 8)],
+        program.programId
+      );
+      
+      await program.methods
+        .generateVerificationChallenge(nonce)
+        .accounts({
+          event: eventPda,
+          ticket: challengeTicket,
+          ticketOwner: buyer.publicKey,
+          validator: eventOrganizer.publicKey,
+          verificationAccount: challengePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      return challengePda;
+    };
+    
+    const closeChallenge = (challenge: PublicKey) =>
+      program.methods
+        .closeExpiredChallenge()
+        .accounts({
+          verificationAccount: challenge,
+          payer: eventOrganizer.publicKey,
+        })
+        .rpc();
+    
+    before(async () => {
+      [challengeTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), eventPda.toBuffer(), Buffer.from(challengeTypeId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createTicketType(challengeTypeId, 'Challenge Test', 'Tickets for challenge cleanup tests', new anchor.BN(1000000), 10, [])
+        .accounts({
+          event: eventPda,
+          ticketType: challengeTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      challengeMint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), challengeMint.publicKey.toBuffer()],
+        program.programId
+      );
+      [challengeTicket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), challengeMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), challengeMint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          challengeMint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-challenge.json', null, null)
+        .accounts({
+          event: eventPda,
+          ticketType: challengeTypePda,
+          kycRegistry: null,
+          mint: challengeMint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(challengeMint.publicKey, buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket: challengeTicket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([challengeMint])
+        .rpc();
+    });
+    
+    it('Refuses to close a challenge that has not expired', async () => {
+      const challengePda = await generateChallenge();
+      
+      try {
+        await closeChallenge(challengePda);
+        assert.fail('Closing an active challenge should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'ChallengeNotExpired');
+      }
+      
+      const challengeAccount = await program.account.verificationChallenge.fetch(challengePda);
+      assert.equal(challengeAccount.payer.toString(), eventOrganizer.publicKey.toString());
+    });
+    
+    it('Closes an expired challenge and returns rent to the validator', async () => {
+      await setChallengeTtl(1);
+      const challengePda = await generateChallenge();
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      
+      const challengeRent = await provider.connection.getBalance(challengePda);
+      const payerBefore = await provider.connection.getBalance(eventOrganizer.publicKey);
+      
+      // Closed by the provider wallet; the rent still goes to the organizer
+      await closeChallenge(challengePda);
+      
+      const payerAfter = await provider.connection.getBalance(eventOrganizer.publicKey);
+      assert.equal(payerAfter - payerBefore, challengeRent);
+      assert.isNull(await provider.connection.getAccountInfo(challengePda));
+    });
+    
+    it('Rejects closing a challenge that was already closed', async () => {
+      const challengePda = await generateChallenge();
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await closeChallenge(challengePda);
+      
+      try {
+        await closeChallenge(challengePda);
+        assert.fail('Closing a consumed challenge should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'AccountNotInitialized');
+      }
+      
+      await setChallengeTtl(300);
+    });
+    
+    it('Rejects a zero challenge TTL', async () => {
+      try {
+        await setChallengeTtl(0);
+        assert.fail('Zero TTL should be rejected');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidChallengeTtl');
+      }
+    });
+  });
+  
+  describe('Resale Cap', () => {
+    // A fresh event so earlier pricing and freeze settings don't apply
+    const resaleEventId = 'resale-cap-001';
+    const resaleTypeId = 'resale-ga';
+    const maxResales = 2;
+    const salePrice = 1000000;
+    let resaleEventPda: PublicKey;
+    let resaleTypePda: PublicKey;
+    let resaleMint: Keypair;
+    let resaleTicket: PublicKey;
+    let paymentMint: PublicKey;
+    let listingCount = 0;
+    
+    const ticketAccountOf = (owner: PublicKey) => getAssociatedTokenAddress(resaleMint.publicKey, owner);
+    
+    const gift = async (from: Keypair, to: PublicKey) => {
+      await program.methods
+        .transferTicket()
+        .accounts({
+          ticket: resaleTicket,
+          ticketType: (await program.account.ticket.fetch(resaleTicket)).ticketType,
+          mint: resaleMint.publicKey,
+          fromTokenAccount: await ticketAccountOf(from.publicKey),
+          toTokenAccount: await ticketAccountOf(to),
+          from: from.publicKey,
+          to,
+          event: resaleEventPda,
+          kycRegistry: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([from])
+        .rpc();
+    };
+    
+    // Lists the ticket and has `purchaser` buy it through the marketplace
+    const sell = async (seller: Keypair, purchaser: Keypair) => {
+      const listingId = `resale-${listingCount++}`;
+      const [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), resaleTicket.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), resaleMint.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createListing(listingId, new anchor.BN(salePrice))
+        .accounts({
+          ticket: resaleTicket,
+          ticketType: (await program.account.ticket.fetch(resaleTicket)).ticketType,
+          mint: resaleMint.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: resaleEventPda,
+          eventListingIndex: null,
+          owner: seller.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([seller])
+        .rpc();
+      
+      await program.methods
+        .purchaseListing(null)
+        .accounts({
+          ticket: resaleTicket,
+          listing: listingPda,
+          event: resaleEventPda,
+          kycRegistry: null,
+          mint: resaleMint.publicKey,
+          fromTokenAccount: await ticketAccountOf(seller.publicKey),
+          toTokenAccount: await ticketAccountOf(purchaser.publicKey),
+          seller: seller.publicKey,
+          buyer: purchaser.publicKey,
+          paymentFromAccount: await getAssociatedTokenAddress(paymentMint, purchaser.publicKey),
+          paymentToAccount: await getAssociatedTokenAddress(paymentMint, seller.publicKey),
+          royaltyAccount: await getAssociatedTokenAddress(paymentMint, eventOrganizer.publicKey),
+          transferRecord: null,
+          eventListingIndex: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .signers([seller, purchaser])
+        .rpc();
+    };
+    
+    const resaleCount = async () => (await program.account.ticket.fetch(resaleTicket)).resaleCount;
+    
+    before(async () => {
+      [resaleEventPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('event'), eventOrganizer.publicKey.toBuffer(), Buffer.from(resaleEventId)],
+        program.programId
+      );
+      [resaleTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), resaleEventPda.toBuffer(), Buffer.from(resaleTypeId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createEvent(
+          resaleEventId,
+          'Resale Cap Test',
+          eventSymbol,
+          eventDescription,
+          eventVenue,
+          new anchor.BN(startDate),
+          new anchor.BN(endDate),
+          ticketQuantity,
+          500
+        )
+        .accounts({
+          event: resaleEventPda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .createTicketType(resaleTypeId, 'General Admission', 'Resale cap tickets', new anchor.BN(1000000), 10, [])
+        .accounts({
+          event: resaleEventPda,
+          ticketType: resaleTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .setMaxResales(maxResales)
+        .accounts({
+          event: resaleEventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      resaleMint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), resaleMint.publicKey.toBuffer()],
+        program.programId
+      );
+      [resaleTicket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), resaleMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), resaleMint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          resaleMint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-resale.json', null, null)
+        .accounts({
+          event: resaleEventPda,
+          ticketType: resaleTypePda,
+          kycRegistry: null,
+          mint: resaleMint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await ticketAccountOf(buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket: resaleTicket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([resaleMint])
+        .rpc();
+      
+      for (const owner of [secondBuyer.publicKey, validator.publicKey]) {
+        await createAssociatedTokenAccount(provider.connection, buyer.payer, resaleMint.publicKey, owner);
+      }
+      
+      // Sales are paid in an accepted mint, with the organizer taking royalties
+      paymentMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      await program.methods
+        .addAcceptedPaymentMint(paymentMint)
+        .accounts({
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          admin: buyer.publicKey,
+        })
+        .rpc();
+      for (const owner of [secondBuyer.publicKey, validator.publicKey, eventOrganizer.publicKey]) {
+        const account = await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, owner);
+        await mintTo(provider.connection, buyer.payer, paymentMint, account, buyer.payer, 10 * salePrice);
+      }
+    });
+    
+    it('Does not count a gift toward the resale cap', async () => {
+      await gift(buyer.payer, secondBuyer.publicKey);
+      
+      const ticketAccount = await program.account.ticket.fetch(resaleTicket);
+      assert.equal(ticketAccount.owner.toString(), secondBuyer.publicKey.toString());
+      assert.equal(ticketAccount.resaleCount, 0);
+    });
+    
+    it('Allows resales up to the cap', async () => {
+      await sell(secondBuyer, validator);
+      assert.equal(await resaleCount(), 1);
+      
+      await sell(validator, secondBuyer);
+      assert.equal(await resaleCount(), maxResales);
+    });
+    
+    it('Rejects a resale over the cap', async () => {
+      try {
+        await sell(secondBuyer, validator);
+        assert.fail('Resale over the cap should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'ResaleLimitReached');
+      }
+      
+      const ticketAccount = await program.account.ticket.fetch(resaleTicket);
+      assert.equal(ticketAccount.owner.toString(), secondBuyer.publicKey.toString());
+      assert.equal(ticketAccount.resaleCount, maxResales);
+    });
+    
+    it('Rejects a holder buying their own listing without counting a resale', async () => {
+      const holder = (await program.account.ticket.fetch(resaleTicket)).owner;
+      const seller = holder.equals(validator.publicKey) ? validator : secondBuyer;
+      const before = await resaleCount();
+      
+      try {
+        await sell(seller, seller);
+        assert.fail('Self-purchase should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'CannotBuyOwnListing');
+      }
+      
+      const ticketAccount = await program.account.ticket.fetch(resaleTicket);
+      assert.equal(ticketAccount.owner.toString(), seller.publicKey.toString());
+      assert.equal(ticketAccount.resaleCount, before);
+    });
+    
+    it('Still allows gifts once the cap is reached', async () => {
+      await gift(secondBuyer, validator.publicKey);
+      
+      const ticketAccount = await program.account.ticket.fetch(resaleTicket);
+      assert.equal(ticketAccount.owner.toString(), validator.publicKey.toString());
+      assert.equal(ticketAccount.resaleCount, maxResales);
+    });
+  });
+  
+  describe('Organizer Buyback', () => {
+    // A fresh event so earlier freeze and resale cap settings don't apply
+    const buybackEventId = 'buyback-001';
+    const buybackTypeId = 'buyback-ga';
+    const listingId = 'buyback-listing';
+    const listPrice = 1500000;
+    const buybackPrice = 1000000;
+    let buybackEventPda: PublicKey;
+    let buybackTypePda: PublicKey;
+    let buybackMint: Keypair;
+    let buybackTicket: PublicKey;
+    let listingPda: PublicKey;
+    let paymentMint: PublicKey;
+    
+    const ticketAccountOf = (owner: PublicKey) => getAssociatedTokenAddress(buybackMint.publicKey, owner);
+    const paymentAccountOf = (owner: PublicKey) => getAssociatedTokenAddress(paymentMint, owner);
+    const balanceOf = async (owner: PublicKey) =>
+      Number((await getAccount(provider.connection, await paymentAccountOf(owner))).amount);
+    
+    const setBuyback = async (window: number) => {
+      await program.methods
+        .setBuybackConfig(new anchor.BN(buybackPrice), new anchor.BN(window))
+        .accounts({
+          event: buybackEventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    };
+    
+    // Buys back the listing with `organizer` signing for the event
+    const buyback = async (organizer: Keypair) => {
+      await program.methods
+        .buybackListing()
+        .accounts({
+          ticket: buybackTicket,
+          listing: listingPda,
+          event: buybackEventPda,
+          mint: buybackMint.publicKey,
+          fromTokenAccount: await ticketAccountOf(buyer.publicKey),
+          toTokenAccount: await ticketAccountOf(organizer.publicKey),
+          seller: buyer.publicKey,
+          organizer: organizer.publicKey,
+          treasuryTokenAccount: await paymentAccountOf(organizer.publicKey),
+          paymentToAccount: await paymentAccountOf(buyer.publicKey),
+          transferRecord: null,
+          eventListingIndex: null,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([organizer])
+        .rpc();
+    };
+    
+    before(async () => {
+      [buybackEventPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('event'), eventOrganizer.publicKey.toBuffer(), Buffer.from(buybackEventId)],
+        program.programId
+      );
+      [buybackTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), buybackEventPda.toBuffer(), Buffer.from(buybackTypeId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createEvent(
+          buybackEventId,
+          'Buyback Test',
+          eventSymbol,
+          eventDescription,
+          eventVenue,
+          new anchor.BN(startDate),
+          new anchor.BN(endDate),
+          ticketQuantity,
+          500
+        )
+        .accounts({
+          event: buybackEventPda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .createTicketType(buybackTypeId, 'General Admission', 'Buyback tickets', new anchor.BN(1000000), 10, [])
+        .accounts({
+          event: buybackEventPda,
+          ticketType: buybackTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      buybackMint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), buybackMint.publicKey.toBuffer()],
+        program.programId
+      );
+      [buybackTicket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), buybackMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), buybackMint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          buybackMint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-buyback.json', null, null)
+        .accounts({
+          event: buybackEventPda,
+          ticketType: buybackTypePda,
+          kycRegistry: null,
+          mint: buybackMint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await ticketAccountOf(buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket: buybackTicket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buybackMint])
+        .rpc();
+      
+      for (const owner of [eventOrganizer.publicKey, secondBuyer.publicKey]) {
+        await createAssociatedTokenAccount(provider.connection, buyer.payer, buybackMint.publicKey, owner);
+      }
+      
+      // The organizer's treasury and the seller both hold the accepted payment mint
+      paymentMint = await createMint(provider.connection, buyer.payer, buyer.publicKey, null, 0);
+      await program.methods
+        .addAcceptedPaymentMint(paymentMint)
+        .accounts({
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+          admin: buyer.publicKey,
+        })
+        .rpc();
+      for (const owner of [buyer.publicKey, eventOrganizer.publicKey, secondBuyer.publicKey]) {
+        const account = await createAssociatedTokenAccount(provider.connection, buyer.payer, paymentMint, owner);
+        await mintTo(provider.connection, buyer.payer, paymentMint, account, buyer.payer, 10 * buybackPrice);
+      }
+      
+      [listingPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), buybackTicket.toBuffer(), Buffer.from(listingId)],
+        program.programId
+      );
+      const [attemptPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('last_listing_attempt'), buybackMint.publicKey.toBuffer()],
+        program.programId
+      );
+      
+      await program.methods
+        .createListing(listingId, new anchor.BN(listPrice))
+        .accounts({
+          ticket: buybackTicket,
+          ticketType: (await program.account.ticket.fetch(buybackTicket)).ticketType,
+          mint: buybackMint.publicKey,
+          listing: listingPda,
+          auctionHistory: null,
+          lastListingAttempt: attemptPda,
+          event: buybackEventPda,
+          eventListingIndex: null,
+          owner: buyer.publicKey,
+          systemProgram: SystemProgram.programId,
+          paymentMint,
+          acceptedPaymentMints: acceptedPaymentMintsPda,
+        })
+        .rpc();
+    });
+    
+    it('Rejects a buyback outside the configured window', async () => {
+      // The event starts a day out, so a one-minute window hasn't opened yet
+      await setBuyback(60);
+      
+      try {
+        await buyback(eventOrganizer);
+        assert.fail('Buyback before the window should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'BuybackNotOpen');
+      }
+    });
+    
+    it('Rejects a buyback by someone other than the organizer', async () => {
+      await setBuyback(startDate - Math.floor(Date.now() / 1000) + 3600);
+      
+      try {
+        await buyback(secondBuyer);
+        assert.fail('Buyback by a non-organizer should fail');
+      } catch (error) {
+        assert.include(error.toString(), 'ConstraintHasOne');
+      }
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.deepEqual(listingAccount.status, { active: {} });
+    });
+    
+    it('Buys back a listed ticket at the buyback price', async () => {
+      const sellerBefore = await balanceOf(buyer.publicKey);
+      const treasuryBefore = await balanceOf(eventOrganizer.publicKey);
+      
+      await buyback(eventOrganizer);
+      
+      const ticketAccount = await program.account.ticket.fetch(buybackTicket);
+      assert.equal(ticketAccount.owner.toString(), eventOrganizer.publicKey.toString());
+      assert.equal(ticketAccount.resaleCount, 0);
+      
+      const organizerTicket = await getAccount(provider.connection, await ticketAccountOf(eventOrganizer.publicKey));
+      assert.equal(Number(organizerTicket.amount), 1);
+      
+      const listingAccount = await program.account.marketplaceListing.fetch(listingPda);
+      assert.deepEqual(listingAccount.status, { sold: {} });
+      
+      // The seller gets the buyback price, not the list price, with no royalty
+      // withheld; the treasury pays exactly that and nothing flows back to it
+      assert.equal((await balanceOf(buyer.publicKey)) - sellerBefore, buybackPrice);
+      assert.equal(treasuryBefore - (await balanceOf(eventOrganizer.publicKey)), buybackPrice);
+    });
+  });
+  
+  describe('Attendance Stats', () => {
+    // An event already under way, so tickets can be checked in
+    const attendanceEventId = 'attendance-event-001';
+    const attendanceTypeId = 'attendance-ga';
+    let attendanceEventPda: PublicKey;
+    let attendanceTypePda: PublicKey;
+    let attendanceTickets: PublicKey[];
+    
+    const mintAttendanceTicket = async () => {
+      const mint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [ticket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/test-ticket-attendance.json', null, null)
+        .accounts({
+          event: attendanceEventPda,
+          ticketType: attendanceTypePda,
+          kycRegistry: null,
+          mint: mint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(mint.publicKey, buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint])
+        .rpc();
+      
+      return ticket;
+    };
+    
+    const checkIn = (ticket: PublicKey) =>
+      program.methods
+        .verifyAndMarkUsed()
+        .accounts({
+          event: attendanceEventPda,
+          ticket,
+          ticketOwner: buyer.publicKey,
+          validator: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    
+    const attendanceStats = () =>
+      program.methods
+        .getAttendanceStats()
+        .accounts({ event: attendanceEventPda })
+        .view();
+    
+    before(async () => {
+      [attendanceEventPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('event'), eventOrganizer.publicKey.toBuffer(), Buffer.from(attendanceEventId)],
+        program.programId
+      );
+      [attendanceTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), attendanceEventPda.toBuffer(), Buffer.from(attendanceTypeId)],
+        program.programId
+      );
+      
+      const startedAt = Math.floor(Date.now() / 1000) - 3600;
+      await program.methods
+        .createEvent(
+          attendanceEventId,
+          'Attendance Test',
+          eventSymbol,
+          eventDescription,
+          'Main Gate',
+          new anchor.BN(startedAt),
+          new anchor.BN(startedAt + 2 * 86400),
+          ticketQuantity,
+          500
+        )
+        .accounts({
+          event: attendanceEventPda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .createTicketType(attendanceTypeId, 'General Admission', 'Attendance tickets', new anchor.BN(1000000), 10, [])
+        .accounts({
+          event: attendanceEventPda,
+          ticketType: attendanceTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      attendanceTickets = [];
+      for (let i = 0; i < 3; i++) {
+        attendanceTickets.push(await mintAttendanceTicket());
+      }
+    });
+    
+    it('Counts each ticket once however it is checked in', async () => {
+      await checkIn(attendanceTickets[0]);
+      let event = await program.account.event.fetch(attendanceEventPda);
+      assert.equal(event.checkedInCount, 1);
+      
+      // A second scan of the same ticket is refused and not counted
+      try {
+        await checkIn(attendanceTickets[0]);
+        assert.fail('Should have thrown an error');
+      } catch (error) {
+        assert.include(error.message, 'Ticket is not valid for entry');
+      }
+      event = await program.account.event.fetch(attendanceEventPda);
+      assert.equal(event.checkedInCount, 1);
+      
+      // Batch scans only count the tickets they newly check in
+      await program.methods
+        .batchVerifyTickets()
+        .accounts({
+          event: attendanceEventPda,
+          validator: eventOrganizer.publicKey,
+        })
+        .remainingAccounts(
+          [attendanceTickets[0], attendanceTickets[1]].map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .signers([eventOrganizer])
+        .rpc();
+      event = await program.account.event.fetch(attendanceEventPda);
+      assert.equal(event.checkedInCount, 2);
+    });
+    
+    it('Rejects checking in a ticket for a different event', async () => {
+      try {
+        await checkIn(ticketPda);
+        assert.fail('Should have thrown an error');
+      } catch (error) {
+        assert.include(error.toString(), 'TicketEventMismatch');
+      }
+      const event = await program.account.event.fetch(attendanceEventPda);
+      assert.equal(event.checkedInCount, 2);
+    });
+    
+    it('Reports issued and checked-in counts', async () => {
+      const stats = await attendanceStats();
+      assert.equal(stats.totalIssued, 3);
+      assert.equal(stats.checkedInCount, 2);
+    });
+  });
+
+  describe('Metadata Completeness', () => {
+    // A fresh event so the requirement doesn't affect earlier tickets
+    const completenessEventId = 'metadata-check-001';
+    const completenessTypeId = 'metadata-ga';
+    let completenessEventPda: PublicKey;
+    let completenessTypePda: PublicKey;
+    
+    const mintWithUri = async (metadataUri: string) => {
+      const mint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [ticket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      
+      await program.methods
+        .mintTicket(metadataUri, null, null)
+        .accounts({
+          event: completenessEventPda,
+          ticketType: completenessTypePda,
+          kycRegistry: null,
+          mint: mint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(mint.publicKey, buyer.publicKey),
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket,
+          buyer: buyer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint])
+        .rpc();
+      
+      return ticket;
+    };
+    
+    const setTransferable = (ticket: PublicKey, transferable: boolean) =>
+      program.methods
+        .setTicketTransferability(transferable)
+        .accounts({
+          event: completenessEventPda,
+          ticket,
+          ticketType: completenessTypePda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    
+    const setRequirement = (required: boolean) =>
+      program.methods
+        .setRequireCompleteMetadata(required)
+        .accounts({
+          event: completenessEventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    
+    before(async () => {
+      [completenessEventPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('event'), eventOrganizer.publicKey.toBuffer(), Buffer.from(completenessEventId)],
+        program.programId
+      );
+      [completenessTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), completenessEventPda.toBuffer(), Buffer.from(completenessTypeId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createEvent(
+          completenessEventId,
+          'Metadata Check',
+          eventSymbol,
+          eventDescription,
+          eventVenue,
+          new anchor.BN(startDate),
+          new anchor.BN(endDate),
+          ticketQuantity,
+          500
+        )
+        .accounts({
+          event: completenessEventPda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .createTicketType(completenessTypeId, 'General Admission', 'Metadata check tickets', new anchor.BN(1000000), 10, [])
+        .accounts({
+          event: completenessEventPda,
+          ticketType: completenessTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await setRequirement(true);
+    });
+    
+    it('Enables transfer on a ticket with complete metadata', async () => {
+      const ticket = await mintWithUri('https://tickettoken.app/metadata/complete.json');
+      await setTransferable(ticket, false);
+      await setTransferable(ticket, true);
+      
+      const ticketAccount = await program.account.ticket.fetch(ticket);
+      assert.isTrue(ticketAccount.transferable);
+    });
+    
+    it('Refuses to enable transfer on a ticket without a metadata URI', async () => {
+      const ticket = await mintWithUri('');
+      await setTransferable(ticket, false);
+      
+      try {
+        await setTransferable(ticket, true);
+        assert.fail('Should have thrown an error');
+      } catch (error) {
+        assert.include(error.toString(), 'IncompleteMetadata');
+      }
+      
+      const ticketAccount = await program.account.ticket.fetch(ticket);
+      assert.isFalse(ticketAccount.transferable);
+    });
+    
+    it('Lets the organizer override by turning the requirement off', async () => {
+      const ticket = await mintWithUri('');
+      await setTransferable(ticket, false);
+      
+      await setRequirement(false);
+      const eventAccount = await program.account.event.fetch(completenessEventPda);
+      assert.isFalse(eventAccount.requireCompleteMetadata);
+      
+      await setTransferable(ticket, true);
+      const ticketAccount = await program.account.ticket.fetch(ticket);
+      assert.isTrue(ticketAccount.transferable);
+    })    
+    it('Blocks transferring an incomplete ticket once the requirement is turned on', async () => {
+      const ticket = await mintWithUri('');
+      const { mint, transferable } = await program.account.ticket.fetch(ticket);
+      assert.isTrue(transferable);
+      
+      await setRequirement(true);
+      const recipientTokenAccount = await getAssociatedTokenAddress(mint, secondBuyer.publicKey);
+      try {
+        await program.methods
+          .transferTicket()
+          .accounts({
+            ticket,
+            mint,
+            fromTokenAccount: await getAssociatedTokenAddress(mint, buyer.publicKey),
+            toTokenAccount: recipientTokenAccount,
+            from: buyer.publicKey,
+            to: secondBuyer.publicKey,
+            event: completenessEventPda,
+            ticketType: completenessTypePda,
+            kycRegistry: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .preInstructions([
+            createAssociatedTokenAccountInstruction(buyer.publicKey, recipientTokenAccount, secondBuyer.publicKey, mint),
+          ])
+          .rpc();
+        assert.fail('Should have thrown an error');
+      } catch (error) {
+        assert.include(error.toString(), 'IncompleteMetadata');
+      }
+      
+      const ticketAccount = await program.account.ticket.fetch(ticket);
+      assert.isTrue(ticketAccount.owner.equals(buyer.publicKey));
+    });
+    
+    it('Mints incomplete tickets as non-transferable while the requirement is on', async () => {
+      const incomplete = await mintWithUri('');
+      const complete = await mintWithUri('https://tickettoken.app/metadata/complete.json');
+      
+      assert.isFalse((await program.account.ticket.fetch(incomplete)).transferable);
+      assert.isTrue((await program.account.ticket.fetch(complete)).transferable);
+    });
+  });
+  
+  describe('Distribution Clawback', () => {
+    // A fresh event so the clawback window doesn't affect earlier tickets
+    const clawbackEventId = 'clawback-001';
+    const clawbackTypeId = 'clawback-ga';
+    const clawbackWindow = 3;
+    let clawbackEventPda: PublicKey;
+    let clawbackTypePda: PublicKey;
+    
+    // Mints a ticket to the organizer and distributes it to the second buyer
+    const distributeTicket = async () => {
+      const mint = Keypair.generate();
+      const [mintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [ticket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEditionAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const organizerTokenAccount = await getAssociatedTokenAddress(mint.publicKey, eventOrganizer.publicKey);
+      const recipientTokenAccount = await getAssociatedTokenAddress(mint.publicKey, secondBuyer.publicKey);
+      
+      await program.methods
+        .mintTicket('https://tickettoken.app/metadata/clawback.json', null, null)
+        .accounts({
+          event: clawbackEventPda,
+          ticketType: clawbackTypePda,
+          kycRegistry: null,
+          mint: mint.publicKey,
+          ticketMintAuthority: mintAuthority,
+          tokenAccount: organizerTokenAccount,
+          metadataAccount: metadataAddress,
+          masterEdition: masterEditionAddress,
+          ticket,
+          buyer: eventOrganizer.publicKey,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint, eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .transferTicket()
+        .accounts({
+          ticket,
+          ticketType: (await program.account.ticket.fetch(ticket)).ticketType,
+          mint: mint.publicKey,
+          fromTokenAccount: organizerTokenAccount,
+          toTokenAccount: recipientTokenAccount,
+          from: eventOrganizer.publicKey,
+          to: secondBuyer.publicKey,
+          event: clawbackEventPda,
+          kycRegistry: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .preInstructions([
+          createAssociatedTokenAccountInstruction(
+            eventOrganizer.publicKey,
+            recipientTokenAccount,
+            secondBuyer.publicKey,
+            mint.publicKey
+          ),
+        ])
+        .signers([eventOrganizer])
+        .rpc();
+      
+      return { ticket, mint: mint.publicKey, mintAuthority, recipientTokenAccount };
+    };
+    
+    // Claws back a distributed ticket, minting its replacement to a fresh mint
+    const clawback = async (distributed: {
+      ticket: PublicKey;
+      mint: PublicKey;
+      mintAuthority: PublicKey;
+      recipientTokenAccount: PublicKey;
+    }) => {
+      const replacementMint = Keypair.generate();
+      const [replacementMintAuthority] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_authority'), replacementMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [replacementTicket] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket'), replacementMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [replacementMetadata] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), replacementMint.publicKey.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [replacementMasterEdition] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from('metadata'),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          replacementMint.publicKey.toBuffer(),
+          Buffer.from('edition'),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const organizerTokenAccount = await getAssociatedTokenAddress(
+        replacementMint.publicKey,
+        eventOrganizer.publicKey
+      );
+      
+      await program.methods
+        .clawbackTicket()
+        .accounts({
+          event: clawbackEventPda,
+          ticket: distributed.ticket,
+          ticketType: clawbackTypePda,
+          mint: distributed.mint,
+          holderTokenAccount: distributed.recipientTokenAccount,
+          replacementMint: replacementMint.publicKey,
+          replacementMintAuthority,
+          organizerTokenAccount,
+          replacementMetadata,
+          replacementMasterEdition,
+          replacementTicket,
+          organizer: eventOrganizer.publicKey,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([replacementMint, eventOrganizer])
+        .rpc();
+      
+      return { replacementTicket, organizerTokenAccount };
+    };
+    
+    before(async () => {
+      [clawbackEventPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('event'), eventOrganizer.publicKey.toBuffer(), Buffer.from(clawbackEventId)],
+        program.programId
+      );
+      [clawbackTypePda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_type'), clawbackEventPda.toBuffer(), Buffer.from(clawbackTypeId)],
+        program.programId
+      );
+      
+      await program.methods
+        .createEvent(
+          clawbackEventId,
+          'Clawback Check',
+          eventSymbol,
+          eventDescription,
+          eventVenue,
+          new anchor.BN(startDate),
+          new anchor.BN(endDate),
+          ticketQuantity,
+          500
+        )
+        .accounts({
+          event: clawbackEventPda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .createTicketType(clawbackTypeId, 'General Admission', 'Clawback check tickets', new anchor.BN(1000000), 10, [])
+        .accounts({
+          event: clawbackEventPda,
+          ticketType: clawbackTypePda,
+          organizer: eventOrganizer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .addValidator(validator.publicKey)
+        .accounts({
+          event: clawbackEventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+      
+      await program.methods
+        .setDistributionClawbackWindow(new anchor.BN(clawbackWindow))
+        .accounts({
+          event: clawbackEventPda,
+          organizer: eventOrganizer.publicKey,
+        })
+        .signers([eventOrganizer])
+        .rpc();
+    });
+    
+    it('Claws back a distributed ticket within the window', async () => {
+      const distributed = await distributeTicket();
+      
+      let ticketAccount = await program.account.ticket.fetch(distributed.ticket);
+      assert.equal(ticketAccount.owner.toString(), secondBuyer.publicKey.toString());
+      assert.isTrue(ticketAccount.distributedAt.toNumber() > 0);
+      
+      const { replacementTicket, organizerTokenAccount } = await clawback(distributed);
+      
+      // The misdirected ticket no longer admits its holder
+      ticketAccount = await program.account.ticket.fetch(distributed.ticket);
+      assert.deepEqual(ticketAccount.status, { revoked: {} });
+      assert.equal(ticketAccount.distributedAt.toNumber(), 0);
+      
+      // The organizer holds a valid replacement with the same serial number
+      const replacementAccount = await program.account.ticket.fetch(replacementTicket);
+      assert.equal(replacementAccount.owner.toString(), eventOrganizer.publicKey.toString());
+      assert.equal(replacementAccount.serialNumber, ticketAccount.serialNumber);
+      assert.deepEqual(replacementAccount.status, { valid: {} });
+      assert.equal(replacementAccount.distributedAt.toNumber(), 0);
+      
+      const tokenAccount = await getAccount(provider.connection, organizerTokenAccount);
+      assert.equal(tokenAccount.amount.toString(), '1');
+    });
+    
+    it('Cannot claw back a ticket after the window', async () => {
+      const distributed = await distributeTicket();
+      await new Promise((resolve) => setTimeout(resolve, (clawbackWindow + 2) * 1000));
+      
+      try {
+        await clawback(distributed);
+        assert.fail('Should have thrown an error');
+      } catch (error) {
+        assert.include(error.toString(), 'ClawbackWindowClosed');
+      }
+      
+      const ticketAccount = await program.account.ticket.fetch(distributed.ticket);
+      assert.equal(ticketAccount.owner.toString(), secondBuyer.publicKey.toString());
+    });
+    
+    it('Cannot claw back a used ticket', async () => {
+      const distributed = await distributeTicket();
+      
+      await program.methods
+        .verifyAndMarkUsed()
+        .accounts({
+          event: clawbackEventPda,
+          ticket: distributed.ticket,
+          ticketOwner: secondBuyer.publicKey,
+          validator: validator.publicKey,
+        })
+        .signers([validator])
+        .rpc();
+      
+      try {
+        await clawback(distributed);
+        assert.fail('Should have thrown an error');
+      } catch (error) {
+        assert.include(error.toString(), 'InvalidTicket');
+      }
+      
+      const ticketAccount = await program.account.ticket.fetch(distributed.ticket);
+      assert.equal(ticketAccount.owner.toString(), secondBuyer.publicKey.toString());
+      assert.deepEqual(ticketAccount.status, { used: {} });
+    });
+  });
 });
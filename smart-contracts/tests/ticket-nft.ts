@@ -21,8 +21,10 @@ import {
   PROGRAM_ID as METADATA_PROGRAM_ID,
   createCreateMetadataAccountV3Instruction,
   createUpdateMetadataAccountV2Instruction,
+  Metadata,
 } from "@metaplex-foundation/mpl-token-metadata";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { TicketNft } from "../target/types/ticket_nft";
 
 describe("TicketNFT", () => {
@@ -1602,4 +1604,409 @@ describe("TicketNFT", () => {
       expect(configAccountInfo!.data.length).to.be.lessThan(256); // 256B limit
     });
   });
+
+  describe("Tier Access Levels", () => {
+    const [programConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("program_config")],
+      program.programId
+    );
+    const minter = Keypair.generate();
+    const [minterConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("minter_config"), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const [accessEventAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("event_authority"), Buffer.from("access-event")],
+      program.programId
+    );
+
+    // Mints a ticket to buyer1 for an event `secondsUntilEvent` from now
+    const mintTieredTicket = async (tier: any, secondsUntilEvent: number) => {
+      const mint = Keypair.generate();
+      const [ticketData] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ticket_data"), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        METADATA_PROGRAM_ID
+      );
+      const [masterEdition] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer(), Buffer.from("edition")],
+        METADATA_PROGRAM_ID
+      );
+
+      await program.methods
+        .mintTicket(
+          "access-event",
+          "A1",
+          tier,
+          new BN(Math.floor(Date.now() / 1000) + secondsUntilEvent),
+          eventData.uri,
+          "Access Ticket",
+          "ACCESS"
+        )
+        .accounts({
+          programConfig,
+          minterConfig,
+          ticketData,
+          mint: mint.publicKey,
+          eventAuthority: accessEventAuthority,
+          mintAuthority: programConfig,
+          tokenAccount: await getAssociatedTokenAddress(mint.publicKey, buyer1.publicKey),
+          metadata,
+          masterEdition,
+          recipient: buyer1.publicKey,
+          minter: minter.publicKey,
+          payer: admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          metadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([minter, admin, mint])
+        .rpc();
+
+      return ticketData;
+    };
+
+    const contentRecord = (contentId: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("content"), createHash("sha256").update(contentId).digest()],
+        program.programId
+      )[0];
+
+    const verifyAccess = (ticketData: PublicKey, contentId: string): Promise<boolean> =>
+      program.methods
+        .verifyAccess(contentId)
+        .accounts({
+          programConfig,
+          ticketData,
+          contentRecord: contentRecord(contentId),
+          owner: buyer1.publicKey,
+        })
+        .signers([buyer1])
+        .view();
+
+    before(async () => {
+      await program.methods
+        .initialize(500, new BN(1000))
+        .accounts({
+          programConfig,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      await program.methods
+        .addMinter(minter.publicKey)
+        .accounts({
+          programConfig,
+          minterConfig,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      // Content levels are set by the admin, not by the holder checking access
+      for (const [contentId, requiredLevel] of [
+        ["livestream", { basic: {} }],
+        ["backstage-cam", { backstage: {} }],
+      ] as [string, any][]) {
+        await program.methods
+          .setContentAccessLevel(contentId, requiredLevel)
+          .accounts({
+            programConfig,
+            contentRecord: contentRecord(contentId),
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+      }
+    });
+
+    it("Should only let the admin set a content level", async () => {
+      try {
+        await program.methods
+          .setContentAccessLevel("backstage-cam", { basic: {} })
+          .accounts({
+            programConfig,
+            contentRecord: contentRecord("backstage-cam"),
+            admin: unauthorizedUser.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([unauthorizedUser])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.toString()).to.include("ConstraintHasOne");
+      }
+
+      const record = await program.account.contentRecord.fetch(contentRecord("backstage-cam"));
+      expect(record.requiredLevel).to.deep.equal({ backstage: {} });
+    });
+
+    it("Should map tiers to default access levels", async () => {
+      const config = await program.account.programConfig.fetch(programConfig);
+      expect(config.tierAccessLevels).to.deep.equal({
+        standard: { basic: {} },
+        premium: { premium: {} },
+        vip: { backstage: {} },
+      });
+    });
+
+    it("Should let a VIP ticket pass a Backstage content check", async () => {
+      const ticketData = await mintTieredTicket({ vip: {} }, 600);
+      expect(await verifyAccess(ticketData, "backstage-cam")).to.be.true;
+    });
+
+    it("Should fail a Standard ticket on Backstage content inside its time window", async () => {
+      const ticketData = await mintTieredTicket({ standard: {} }, 600);
+      expect(await verifyAccess(ticketData, "livestream")).to.be.true;
+      expect(await verifyAccess(ticketData, "backstage-cam")).to.be.false;
+    });
+
+    it("Should still apply time windows when the level is met", async () => {
+      // Standard access opens an hour before the event
+      const ticketData = await mintTieredTicket({ standard: {} }, 2 * 3600);
+      expect(await verifyAccess(ticketData, "livestream")).to.be.false;
+    });
+
+    it("Should not migrate a config already on the current layout", async () => {
+      try {
+        await program.methods
+          .migrateProgramConfig()
+          .accounts({ programConfig, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+          .signers([admin])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.toString()).to.include("ProgramConfigAlreadyMigrated");
+      }
+    });
+
+    it("Should apply an updated mapping (admin only)", async () => {
+      const raised = { standard: { backstage: {} }, premium: { premium: {} }, vip: { backstage: {} } };
+
+      try {
+        await program.methods
+          .updateTierAccessLevels(raised)
+          .accounts({ programConfig, admin: unauthorizedUser.publicKey })
+          .signers([unauthorizedUser])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.toString()).to.include("ConstraintHasOne");
+      }
+
+      await program.methods
+        .updateTierAccessLevels(raised)
+        .accounts({ programConfig, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+      const ticketData = await mintTieredTicket({ standard: {} }, 600);
+      expect(await verifyAccess(ticketData, "backstage-cam")).to.be.true;
+
+      await program.methods
+        .updateTierAccessLevels({ standard: { basic: {} }, premium: { premium: {} }, vip: { backstage: {} } })
+        .accounts({ programConfig, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    });
+  });
+
+  describe("Event Mint Authorities", () => {
+    const [programConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("program_config")],
+      program.programId
+    );
+    const minter = Keypair.generate();
+    const [minterConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("minter_config"), minter.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const eventAuthorityFor = (eventId: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("event_authority"), Buffer.from(eventId)],
+        program.programId
+      )[0];
+
+    const mintAuthorityFor = (eventId: string, generation: number) => {
+      const generationBytes = Buffer.alloc(4);
+      generationBytes.writeUInt32LE(generation);
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("event_mint_authority"), Buffer.from(eventId), generationBytes],
+        program.programId
+      )[0];
+    };
+
+    // Mints a ticket for `eventId` to buyer1 signed by `mintAuthority`, returning its metadata account
+    const mintEventTicket = async (eventId: string, mintAuthority: PublicKey) => {
+      const mint = Keypair.generate();
+      const [ticketData] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ticket_data"), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        METADATA_PROGRAM_ID
+      );
+      const [masterEdition] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer(), Buffer.from("edition")],
+        METADATA_PROGRAM_ID
+      );
+
+      await program.methods
+        .mintTicket(
+          eventId,
+          "A1",
+          { standard: {} },
+          new BN(Math.floor(Date.now() / 1000) + 86400),
+          eventData.uri,
+          "Event Ticket",
+          "EVENT"
+        )
+        .accounts({
+          programConfig,
+          minterConfig,
+          ticketData,
+          mint: mint.publicKey,
+          eventAuthority: eventAuthorityFor(eventId),
+          mintAuthority,
+          tokenAccount: await getAssociatedTokenAddress(mint.publicKey, buyer1.publicKey),
+          metadata,
+          masterEdition,
+          recipient: buyer1.publicKey,
+          minter: minter.publicKey,
+          payer: admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          metadataProgram: METADATA_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([minter, admin, mint])
+        .rpc();
+
+      return metadata;
+    };
+
+    const createEventAuthority = async (eventId: string) => {
+      await program.methods
+        .createEventAuthority(eventId)
+        .accounts({
+          programConfig,
+          eventAuthority: eventAuthorityFor(eventId),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    };
+
+    const manageAccounts = (eventId: string) => ({
+      programConfig,
+      eventAuthority: eventAuthorityFor(eventId),
+      admin: admin.publicKey,
+    });
+
+    before(async () => {
+      await program.methods
+        .addMinter(minter.publicKey)
+        .accounts({
+          programConfig,
+          minterConfig,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      await createEventAuthority("scoped-event-a");
+      await createEventAuthority("scoped-event-b");
+    });
+
+    it("Should mint under the event's own authority", async () => {
+      const mintAuthority = mintAuthorityFor("scoped-event-a", 0);
+      const metadata = await mintEventTicket("scoped-event-a", mintAuthority);
+
+      // The event authority signs as the verified creator
+      const metadataAccount = await Metadata.fromAccountAddress(connection, metadata);
+      expect(metadataAccount.data.creators![0].address.toString()).to.equal(mintAuthority.toString());
+      expect(metadataAccount.updateAuthority.toString()).to.equal(mintAuthority.toString());
+
+      // The program config no longer signs for this event
+      try {
+        await mintEventTicket("scoped-event-a", programConfig);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidMintAuthority");
+      }
+    });
+
+    it("Should pause one event's minting while another still mints", async () => {
+      await program.methods
+        .pauseEventAuthority("scoped-event-a")
+        .accounts(manageAccounts("scoped-event-a"))
+        .signers([admin])
+        .rpc();
+
+      try {
+        await mintEventTicket("scoped-event-a", mintAuthorityFor("scoped-event-a", 0));
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.toString()).to.include("EventMintingPaused");
+      }
+
+      await mintEventTicket("scoped-event-b", mintAuthorityFor("scoped-event-b", 0));
+
+      await program.methods
+        .unpauseEventAuthority("scoped-event-a")
+        .accounts(manageAccounts("scoped-event-a"))
+        .signers([admin])
+        .rpc();
+      await mintEventTicket("scoped-event-a", mintAuthorityFor("scoped-event-a", 0));
+    });
+
+    it("Should rotate an event's authority", async () => {
+      await program.methods
+        .rotateEventAuthority("scoped-event-b")
+        .accounts(manageAccounts("scoped-event-b"))
+        .signers([admin])
+        .rpc();
+
+      const eventAuthority = await program.account.eventAuthority.fetch(eventAuthorityFor("scoped-event-b"));
+      expect(eventAuthority.generation).to.equal(1);
+
+      try {
+        await mintEventTicket("scoped-event-b", mintAuthorityFor("scoped-event-b", 0));
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidMintAuthority");
+      }
+
+      await mintEventTicket("scoped-event-b", mintAuthorityFor("scoped-event-b", 1));
+      // Other events keep their authority
+      await mintEventTicket("scoped-event-a", mintAuthorityFor("scoped-event-a", 0));
+    });
+
+    it("Should fail event authority management by non-admin", async () => {
+      try {
+        await program.methods
+          .pauseEventAuthority("scoped-event-b")
+          .accounts({ ...manageAccounts("scoped-event-b"), admin: unauthorizedUser.publicKey })
+          .signers([unauthorizedUser])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.toString()).to.include("ConstraintHasOne");
+      }
+    });
+  });
 });
@@ -0,0 +1,1134 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY, LAMPORTS_PER_SOL } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress, createAssociatedTokenAccount, getAccount } from '@solana/spl-token';
+import { assert } from 'chai';
+import { createHash } from 'crypto';
+import { Tickettoken } from '../target/types/tickettoken';
+
+describe('TicketToken Program Tests', () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Tickettoken as Program<Tickettoken>;
+
+  // Generate keypairs for testing
+  const programAuthority = Keypair.generate();
+  const originalOwner = Keypair.generate();
+  const buyer = Keypair.generate();
+  const secondBuyer = Keypair.generate();
+
+  // Test variables
+  let programStateAddress: PublicKey;
+
+  // Constants
+  const EVENT_ID = 'tt-event-001';
+  const TRANSFER_FEE_BPS = 500; // 5%
+  const PAYMENT_AMOUNT = LAMPORTS_PER_SOL;
+
+  const defaultMetadata = () => ({
+    name: 'Test Ticket',
+    description: 'TicketToken test ticket',
+    imageUri: 'https://tickettoken.io/ticket.png',
+    externalUri: null,
+    seatInfo: null,
+    eventDatetime: new anchor.BN(Math.floor(Date.now() / 1000) + 86400),
+    venue: 'Test Venue',
+    attributes: [],
+  });
+
+  const defaultRestrictions = (overrides: Partial<any> = {}) => ({
+    transferType: { freeTransfer: {} },
+    maxTransfers: null,
+    allowedRecipients: null,
+    transferFeeBps: TRANSFER_FEE_BPS,
+    originalOwnerRoyalty: true,
+    ...overrides,
+  });
+
+  const mintTicket = async (
+    recipient: Keypair,
+    restrictions = defaultRestrictions(),
+    metadata = defaultMetadata(),
+    ticketType: any = { generalAdmission: {} },
+    price = 0
+  ) => {
+    const ticketMint = Keypair.generate();
+    const [ticketData] = await PublicKey.findProgramAddress(
+      [Buffer.from('ticket_data'), ticketMint.publicKey.toBuffer()],
+      program.programId
+    );
+    const recipientTokenAccount = await getAssociatedTokenAddress(ticketMint.publicKey, recipient.publicKey);
+
+    await program.methods
+      .mintTicket(
+        EVENT_ID,
+        ticketType,
+        metadata,
+        restrictions,
+        [],
+        [{ recipient: programAuthority.publicKey, percentageBps: 10000, role: 'organizer' }],
+        new anchor.BN(price)
+      )
+      .accounts({
+        programState: programStateAddress,
+        ticketMint: ticketMint.publicKey,
+        ticketData,
+        recipientTokenAccount,
+        recipient: recipient.publicKey,
+        payer: recipient.publicKey,
+        programAuthority: price > 0 ? programAuthority.publicKey : null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([recipient, ticketMint])
+      .rpc();
+
+    return { mint: ticketMint.publicKey, ticketData, tokenAccount: recipientTokenAccount };
+  };
+
+  before(async () => {
+    console.log("Setting up tickettoken test environment...");
+
+    // Fund accounts
+    const accounts = [programAuthority, originalOwner, buyer, secondBuyer];
+    for (const account of accounts) {
+      await provider.connection.requestAirdrop(account.publicKey, LAMPORTS_PER_SOL * 10);
+    }
+
+    // Wait for confirmations
+    await new Promise(resolve => setTimeout(resolve, 2000));
+
+    // Derive program state PDA
+    [programStateAddress] = await PublicKey.findProgramAddress(
+      [Buffer.from('program_state')],
+      program.programId
+    );
+
+    await program.methods
+      .initializeProgram(programAuthority.publicKey, 250, 500)
+      .accounts({
+        programState: programStateAddress,
+        authority: programAuthority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([programAuthority])
+      .rpc();
+  });
+
+  describe("Paid Transfers", () => {
+    it("Routes the transfer fee to the original owner", async () => {
+      const ticket = await mintTicket(originalOwner);
+
+      // First hop: original owner gives the ticket to buyer
+      const buyerTokenAccount = await createAssociatedTokenAccount(provider.connection, buyer, ticket.mint, buyer.publicKey);
+      await program.methods
+        .transferTicket({ gift: {} }, new anchor.BN(0))
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          currentOwnerTokenAccount: ticket.tokenAccount,
+          newOwnerTokenAccount: buyerTokenAccount,
+          currentOwner: originalOwner.publicKey,
+          newOwner: buyer.publicKey,
+          originalOwner: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([originalOwner])
+        .rpc();
+
+      // Second hop: buyer sells to secondBuyer, original owner earns the fee
+      const secondBuyerTokenAccount = await createAssociatedTokenAccount(provider.connection, secondBuyer, ticket.mint, secondBuyer.publicKey);
+      const originalOwnerBalanceBefore = await provider.connection.getBalance(originalOwner.publicKey);
+
+      await program.methods
+        .transferTicket({ marketplaceSale: {} }, new anchor.BN(PAYMENT_AMOUNT))
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          currentOwnerTokenAccount: buyerTokenAccount,
+          newOwnerTokenAccount: secondBuyerTokenAccount,
+          currentOwner: buyer.publicKey,
+          newOwner: secondBuyer.publicKey,
+          originalOwner: originalOwner.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer, secondBuyer])
+        .rpc();
+
+      const originalOwnerBalanceAfter = await provider.connection.getBalance(originalOwner.publicKey);
+      assert.equal(
+        originalOwnerBalanceAfter - originalOwnerBalanceBefore,
+        PAYMENT_AMOUNT * TRANSFER_FEE_BPS / 10000
+      );
+
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.owner.toString(), secondBuyer.publicKey.toString());
+    });
+
+    it("Pays the seller in full when original owner royalty is disabled", async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions({ originalOwnerRoyalty: false }));
+      const secondBuyerTokenAccount = await createAssociatedTokenAccount(provider.connection, secondBuyer, ticket.mint, secondBuyer.publicKey);
+      const sellerBalanceBefore = await provider.connection.getBalance(buyer.publicKey);
+
+      await program.methods
+        .transferTicket({ marketplaceSale: {} }, new anchor.BN(PAYMENT_AMOUNT))
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          currentOwnerTokenAccount: ticket.tokenAccount,
+          newOwnerTokenAccount: secondBuyerTokenAccount,
+          currentOwner: buyer.publicKey,
+          newOwner: secondBuyer.publicKey,
+          originalOwner: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer, secondBuyer])
+        .rpc();
+
+      // Seller receives the whole payment; buyer pays the transaction fee
+      const sellerBalanceAfter = await provider.connection.getBalance(buyer.publicKey);
+      assert.isAtLeast(sellerBalanceAfter - sellerBalanceBefore, PAYMENT_AMOUNT - 10000);
+    });
+
+    it("Rejects a paid transfer without the original owner account", async () => {
+      const ticket = await mintTicket(buyer);
+      const secondBuyerTokenAccount = await createAssociatedTokenAccount(provider.connection, secondBuyer, ticket.mint, secondBuyer.publicKey);
+
+      try {
+        await program.methods
+          .transferTicket({ marketplaceSale: {} }, new anchor.BN(PAYMENT_AMOUNT))
+          .accounts({
+            programState: programStateAddress,
+            ticketData: ticket.ticketData,
+            currentOwnerTokenAccount: ticket.tokenAccount,
+            newOwnerTokenAccount: secondBuyerTokenAccount,
+            currentOwner: buyer.publicKey,
+            newOwner: secondBuyer.publicKey,
+            originalOwner: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer, secondBuyer])
+          .rpc();
+        assert.fail("Expected transfer to fail without original owner account");
+      } catch (error) {
+        assert.include(error.toString(), "OriginalOwnerAccountRequired");
+      }
+    });
+  });
+
+  describe("Content Access Batch Grants", () => {
+    const grantBatch = (ticket: { ticketData: PublicKey; tokenAccount: PublicKey }, grants: any[]) =>
+      program.methods
+        .grantContentAccessBatch(grants)
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          ownerTokenAccount: ticket.tokenAccount,
+          authority: programAuthority.publicKey,
+        })
+        .signers([programAuthority])
+        .rpc();
+
+    it("Grants three contents in one transaction", async () => {
+      const ticket = await mintTicket(buyer);
+      const grants = [
+        { contentId: 'vip-lounge', accessLevel: { vip: {} } },
+        { contentId: 'backstage-tour', accessLevel: { backstage: {} } },
+        { contentId: 'after-party-stream', accessLevel: { premium: {} } },
+      ];
+
+      const granted: string[] = [];
+      const listener = program.addEventListener('ContentAccessGranted', (event) => {
+        granted.push(event.contentId);
+      });
+
+      await grantBatch(ticket, grants);
+      await new Promise(resolve => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.contentAccess.length, 3);
+      assert.deepEqual(ticketData.contentAccess.map((access) => access.contentId), grants.map((grant) => grant.contentId));
+      assert.deepEqual(ticketData.contentAccess[1].accessLevel, { backstage: {} });
+      assert.isTrue(ticketData.contentAccess.every((access) => access.isActive));
+
+      // One event per grant
+      assert.sameMembers(granted, grants.map((grant) => grant.contentId));
+    });
+
+    it("Rejects a batch containing a duplicate content id", async () => {
+      const ticket = await mintTicket(buyer);
+
+      try {
+        await grantBatch(ticket, [
+          { contentId: 'vip-lounge', accessLevel: { vip: {} } },
+          { contentId: 'vip-lounge', accessLevel: { basic: {} } },
+        ]);
+        assert.fail("Expected batch with duplicate content id to fail");
+      } catch (error) {
+        assert.include(error.toString(), "DuplicateContentId");
+      }
+
+      // Nothing from the rejected batch was written
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.contentAccess.length, 0);
+    });
+
+    it("Rejects a batch larger than the cap", async () => {
+      const ticket = await mintTicket(buyer);
+      const oversized = Array.from({ length: 6 }, (_, i) => ({
+        contentId: `content-${i}`,
+        accessLevel: { basic: {} },
+      }));
+
+      try {
+        await grantBatch(ticket, oversized);
+        assert.fail("Expected oversized batch to fail");
+      } catch (error) {
+        assert.include(error.toString(), "BatchOperationLimitExceeded");
+      }
+
+      // A batch at the cap is accepted
+      await grantBatch(ticket, oversized.slice(0, 5));
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.contentAccess.length, 5);
+    });
+  });
+
+  describe("Listing Schema Migration", () => {
+    // Loaded from tests/fixtures: a listing written with the original,
+    // unversioned MarketplaceListing layout, plus the ticket it lists.
+    const legacySeller = Keypair.fromSecretKey(
+      Uint8Array.from(require('./fixtures/legacy_listing_seller.json'))
+    );
+    const legacyMint = new PublicKey('HqQpD3QgbYKZ46eRYZ8C6xrsd4Kfqs31YQLzgUF6ofNh');
+    let legacyTicketData: PublicKey;
+    let legacyListing: PublicKey;
+
+    const CURRENT_SCHEMA_VERSION = 2;
+
+    const migrate = (marketplaceListing: PublicKey, payer: Keypair) =>
+      program.methods
+        .migrateListing()
+        .accounts({
+          marketplaceListing,
+          payer: payer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc();
+
+    const cancelLegacy = () =>
+      program.methods
+        .cancelListing()
+        .accounts({
+          programState: programStateAddress,
+          ticketData: legacyTicketData,
+          marketplaceListing: legacyListing,
+          seller: legacySeller.publicKey,
+        })
+        .signers([legacySeller])
+        .rpc();
+
+    before(async () => {
+      await provider.connection.requestAirdrop(legacySeller.publicKey, LAMPORTS_PER_SOL * 2);
+      await new Promise(resolve => setTimeout(resolve, 1000));
+
+      [legacyTicketData] = await PublicKey.findProgramAddress(
+        [Buffer.from('ticket_data'), legacyMint.toBuffer()],
+        program.programId
+      );
+      [legacyListing] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), legacyMint.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("rejects operations on an unmigrated listing", async () => {
+      const listing = await program.account.marketplaceListing.fetch(legacyListing);
+      assert.equal(listing.schemaVersion, 0);
+
+      try {
+        await cancelLegacy();
+        assert.fail("Cancelling an unmigrated listing should fail");
+      } catch (error) {
+        assert.include(error.toString(), "MigrationRequired");
+      }
+    });
+
+    it("migrates a v1 listing to v2", async () => {
+      const before = await provider.connection.getAccountInfo(legacyListing);
+
+      await migrate(legacyListing, legacySeller);
+
+      const listing = await program.account.marketplaceListing.fetch(legacyListing);
+      assert.equal(listing.schemaVersion, CURRENT_SCHEMA_VERSION);
+      assert.ok(listing.ticketMint.equals(legacyMint));
+      assert.ok(listing.seller.equals(legacySeller.publicKey));
+      assert.equal(listing.price.toNumber(), LAMPORTS_PER_SOL);
+      assert.isTrue(listing.isActive);
+
+      const after = await provider.connection.getAccountInfo(legacyListing);
+      assert.equal(after.data.length, before.data.length + 1);
+    });
+
+    it("treats migrating an up-to-date listing as a no-op", async () => {
+      // Re-running on the listing that was just migrated
+      const before = await provider.connection.getAccountInfo(legacyListing);
+      await migrate(legacyListing, buyer);
+      const after = await provider.connection.getAccountInfo(legacyListing);
+      assert.ok(after.data.equals(before.data));
+
+      // Listings created by the current program start at the current version
+      const ticket = await mintTicket(secondBuyer);
+      const [marketplaceListing] = await PublicKey.findProgramAddress(
+        [Buffer.from('marketplace_listing'), ticket.mint.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .createListing(new anchor.BN(LAMPORTS_PER_SOL), { fixedPrice: {} }, null)
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          marketplaceListing,
+          sellerTokenAccount: ticket.tokenAccount,
+          seller: secondBuyer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([secondBuyer])
+        .rpc();
+
+      await migrate(marketplaceListing, secondBuyer);
+
+      const listing = await program.account.marketplaceListing.fetch(marketplaceListing);
+      assert.equal(listing.schemaVersion, CURRENT_SCHEMA_VERSION);
+    });
+
+    it("allows operations once the listing is migrated", async () => {
+      await cancelLegacy();
+
+      const listingInfo = await provider.connection.getAccountInfo(legacyListing);
+      assert.isNull(listingInfo);
+
+      const ticketData = await program.account.ticketData.fetch(legacyTicketData);
+      assert.isFalse(ticketData.isListed);
+    });
+  });
+
+  describe('Metadata URI Validation', () => {
+    const withImageUri = (imageUri: string) => ({ ...defaultMetadata(), imageUri });
+
+    it('accepts https, ipfs and ar metadata URIs', async () => {
+      for (const uri of [
+        'https://tickettoken.io/ticket.png',
+        'ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi',
+        'ar://Y2FzdGxlLXRpY2tldC1tZXRhZGF0YQ',
+      ]) {
+        const ticket = await mintTicket(buyer, defaultRestrictions(), withImageUri(uri));
+        const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+        assert.equal(ticketData.metadata.imageUri, uri);
+      }
+    });
+
+    it('rejects an http metadata URI', async () => {
+      try {
+        await mintTicket(buyer, defaultRestrictions(), withImageUri('http://tickettoken.io/ticket.png'));
+        assert.fail('Expected mint with http URI to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'InvalidMetadata');
+      }
+    });
+
+    it('rejects a metadata URI longer than the stored max', async () => {
+      try {
+        await mintTicket(buyer, defaultRestrictions(), withImageUri('https://' + 'a'.repeat(128)));
+        assert.fail('Expected mint with over-length URI to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'InvalidMetadata');
+      }
+    });
+
+    it('rejects an invalid external URI', async () => {
+      try {
+        await mintTicket(buyer, defaultRestrictions(), {
+          ...defaultMetadata(),
+          externalUri: 'ftp://tickettoken.io/ticket.json',
+        });
+        assert.fail('Expected mint with invalid external URI to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'InvalidMetadata');
+      }
+    });
+  });
+
+  describe('Season Pass Linked Events', () => {
+    const linkedEventA = Keypair.generate().publicKey;
+    const linkedEventB = Keypair.generate().publicKey;
+    const unlinkedEvent = Keypair.generate().publicKey;
+    const verifier = Keypair.generate();
+    let pass: Awaited<ReturnType<typeof mintTicket>>;
+
+    const verificationCode = (mint: PublicKey) => `${EVENT_ID}_${mint.toString().slice(0, 8)}`;
+
+    const useForEvent = (event: PublicKey | null) =>
+      program.methods
+        .useTicket(verificationCode(pass.mint), event)
+        .accounts({
+          programState: programStateAddress,
+          ticketData: pass.ticketData,
+          ownerTokenAccount: pass.tokenAccount,
+          owner: buyer.publicKey,
+          verifier: verifier.publicKey,
+        })
+        .signers([buyer, verifier])
+        .rpc();
+
+    const verifyForEvent = (event: PublicKey) =>
+      program.methods
+        .verifySeasonPass(event)
+        .accounts({
+          programState: programStateAddress,
+          ticketData: pass.ticketData,
+          ownerTokenAccount: pass.tokenAccount,
+          owner: buyer.publicKey,
+        })
+        .signers([buyer])
+        .rpc();
+
+    before(async () => {
+      pass = await mintTicket(buyer, defaultRestrictions(), defaultMetadata(), { seasonPass: {} });
+
+      await program.methods
+        .linkSeasonPassEvents([linkedEventA, linkedEventB])
+        .accounts({
+          programState: programStateAddress,
+          ticketData: pass.ticketData,
+          authority: programAuthority.publicKey,
+        })
+        .signers([programAuthority])
+        .rpc();
+    });
+
+    it('admits entry to both linked events', async () => {
+      await verifyForEvent(linkedEventA);
+      await useForEvent(linkedEventA);
+
+      let ticketData = await program.account.ticketData.fetch(pass.ticketData);
+      assert.deepEqual(ticketData.usedEvents.map(String), [linkedEventA.toString()]);
+      assert.isFalse(ticketData.isUsed);
+
+      await verifyForEvent(linkedEventB);
+      await useForEvent(linkedEventB);
+
+      // Every linked event has now been entered, so the pass is spent
+      ticketData = await program.account.ticketData.fetch(pass.ticketData);
+      assert.deepEqual(ticketData.usedEvents.map(String), [linkedEventA, linkedEventB].map(String));
+      assert.isTrue(ticketData.isUsed);
+    });
+
+    it('rejects entry to an event outside the linked set', async () => {
+      const other = await mintTicket(buyer, defaultRestrictions(), defaultMetadata(), { seasonPass: {} });
+      await program.methods
+        .linkSeasonPassEvents([linkedEventA])
+        .accounts({
+          programState: programStateAddress,
+          ticketData: other.ticketData,
+          authority: programAuthority.publicKey,
+        })
+        .signers([programAuthority])
+        .rpc();
+      pass = other;
+
+      for (const attempt of [() => verifyForEvent(unlinkedEvent), () => useForEvent(unlinkedEvent), () => useForEvent(null)]) {
+        try {
+          await attempt();
+          assert.fail('Expected entry to an unlinked event to fail');
+        } catch (e) {
+          assert.include(e.toString(), 'EventNotLinked');
+        }
+      }
+    });
+
+    it('tracks entries per event', async () => {
+      const passData = await mintTicket(buyer, defaultRestrictions(), defaultMetadata(), { seasonPass: {} });
+      await program.methods
+        .linkSeasonPassEvents([linkedEventA, linkedEventB])
+        .accounts({
+          programState: programStateAddress,
+          ticketData: passData.ticketData,
+          authority: programAuthority.publicKey,
+        })
+        .signers([programAuthority])
+        .rpc();
+      pass = passData;
+
+      await useForEvent(linkedEventA);
+
+      // A second entry to the same event is refused, the other event still admits
+      for (const attempt of [() => verifyForEvent(linkedEventA), () => useForEvent(linkedEventA)]) {
+        try {
+          await attempt();
+          assert.fail('Expected a repeat entry to fail');
+        } catch (e) {
+          assert.include(e.toString(), 'SeasonPassEntryUsed');
+        }
+      }
+      await verifyForEvent(linkedEventB);
+
+      const ticketData = await program.account.ticketData.fetch(pass.ticketData);
+      assert.deepEqual(ticketData.usedEvents.map(String), [linkedEventA.toString()]);
+      assert.isFalse(ticketData.isUsed);
+    });
+
+    it('only links events on season passes', async () => {
+      const ticket = await mintTicket(buyer);
+      try {
+        await program.methods
+          .linkSeasonPassEvents([linkedEventA])
+          .accounts({
+            programState: programStateAddress,
+            ticketData: ticket.ticketData,
+            authority: programAuthority.publicKey,
+          })
+          .signers([programAuthority])
+          .rpc();
+        assert.fail('Expected linking a non-season ticket to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'NotSeasonPass');
+      }
+    });
+  });
+
+  describe('Refunds To Original Payer', () => {
+    const TICKET_PRICE = LAMPORTS_PER_SOL / 2;
+
+    const refund = (ticket: { mint: PublicKey; ticketData: PublicKey }, owner: Keypair, ownerTokenAccount: PublicKey, paidBy: PublicKey) =>
+      program.methods
+        .refundTicket()
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          ticketMint: ticket.mint,
+          ownerTokenAccount,
+          owner: owner.publicKey,
+          paidBy,
+          authority: programAuthority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner, programAuthority])
+        .rpc();
+
+    it('records the payer and price at mint', async () => {
+      const authorityBefore = await provider.connection.getBalance(programAuthority.publicKey);
+      const ticket = await mintTicket(buyer, defaultRestrictions(), defaultMetadata(), { generalAdmission: {} }, TICKET_PRICE);
+
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.paidBy.toString(), buyer.publicKey.toString());
+      assert.equal(ticketData.paidAmount.toNumber(), TICKET_PRICE);
+      assert.equal((await provider.connection.getBalance(programAuthority.publicKey)) - authorityBefore, TICKET_PRICE);
+    });
+
+    it('refunds the original payer after the ticket changed hands', async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions(), defaultMetadata(), { generalAdmission: {} }, TICKET_PRICE);
+
+      const secondBuyerTokenAccount = await createAssociatedTokenAccount(provider.connection, secondBuyer, ticket.mint, secondBuyer.publicKey);
+      await program.methods
+        .transferTicket({ gift: {} }, new anchor.BN(0))
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          currentOwnerTokenAccount: ticket.tokenAccount,
+          newOwnerTokenAccount: secondBuyerTokenAccount,
+          currentOwner: buyer.publicKey,
+          newOwner: secondBuyer.publicKey,
+          originalOwner: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      // Refunding to the current holder instead of the payer is refused
+      try {
+        await refund(ticket, secondBuyer, secondBuyerTokenAccount, secondBuyer.publicKey);
+        assert.fail('Expected refund to the current owner to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'InvalidAccountOwner');
+      }
+
+      const payerBefore = await provider.connection.getBalance(buyer.publicKey);
+      const holderBefore = await provider.connection.getBalance(secondBuyer.publicKey);
+      await refund(ticket, secondBuyer, secondBuyerTokenAccount, buyer.publicKey);
+
+      assert.equal((await provider.connection.getBalance(buyer.publicKey)) - payerBefore, TICKET_PRICE);
+      assert.isAtMost(await provider.connection.getBalance(secondBuyer.publicKey), holderBefore);
+
+      const holderTokens = await provider.connection.getTokenAccountBalance(secondBuyerTokenAccount);
+      assert.equal(holderTokens.value.amount, '0');
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.paidAmount.toNumber(), 0);
+    });
+
+    it('refunds the owner when they paid for the ticket themselves', async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions(), defaultMetadata(), { generalAdmission: {} }, TICKET_PRICE);
+
+      const payerBefore = await provider.connection.getBalance(buyer.publicKey);
+      await refund(ticket, buyer, ticket.tokenAccount, buyer.publicKey);
+
+      // The owner also signs, but fees are paid by the provider wallet
+      assert.equal((await provider.connection.getBalance(buyer.publicKey)) - payerBefore, TICKET_PRICE);
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.paidAmount.toNumber(), 0);
+    });
+
+    it('does not refund a free ticket', async () => {
+      const ticket = await mintTicket(buyer);
+      try {
+        await refund(ticket, buyer, ticket.tokenAccount, buyer.publicKey);
+        assert.fail('Expected refund of a free ticket to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'RefundNotAllowed');
+      }
+    });
+  });
+
+  describe('Re-entry Scans', () => {
+    const REENTRY_COOLDOWN = 2; // seconds
+    const verifier = Keypair.generate();
+
+    const verificationCode = (mint: PublicKey) => `${EVENT_ID}_${mint.toString().slice(0, 8)}`;
+
+    // Event already under way, so scans fall inside the entry window
+    const liveEventMetadata = () => ({
+      ...defaultMetadata(),
+      eventDatetime: new anchor.BN(Math.floor(Date.now() / 1000)),
+    });
+
+    const scan = (ticket: Awaited<ReturnType<typeof mintTicket>>) =>
+      program.methods
+        .useTicket(verificationCode(ticket.mint), null)
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          ownerTokenAccount: ticket.tokenAccount,
+          owner: buyer.publicKey,
+          verifier: verifier.publicKey,
+        })
+        .signers([buyer, verifier])
+        .rpc();
+
+    const mintReentryTicket = async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions(), liveEventMetadata());
+      await program.methods
+        .setReentryPolicy(true, new anchor.BN(REENTRY_COOLDOWN))
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          authority: programAuthority.publicKey,
+        })
+        .signers([programAuthority])
+        .rpc();
+      return ticket;
+    };
+
+    it('admits a re-entry once the cooldown has passed', async () => {
+      const ticket = await mintReentryTicket();
+
+      await scan(ticket);
+      let ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      const firstScanAt = ticketData.lastScanAt.toNumber();
+      assert.isFalse(ticketData.isUsed);
+
+      await new Promise(resolve => setTimeout(resolve, (REENTRY_COOLDOWN + 1) * 1000));
+      await scan(ticket);
+
+      ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.isFalse(ticketData.isUsed);
+      assert.isAtLeast(ticketData.lastScanAt.toNumber(), firstScanAt + REENTRY_COOLDOWN);
+      // The first entry time is kept
+      assert.equal(ticketData.usageTimestamp.toNumber(), firstScanAt);
+    });
+
+    it('refuses a re-entry scan within the cooldown', async () => {
+      const ticket = await mintReentryTicket();
+
+      await scan(ticket);
+      try {
+        await scan(ticket);
+        assert.fail('Expected a scan within the cooldown to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'ReentryCooldownActive');
+      }
+    });
+
+    it('refuses refunds and transfers once a re-entry ticket has been scanned', async () => {
+      const ticket = await mintReentryTicket();
+      await scan(ticket);
+
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.isFalse(ticketData.isUsed);
+
+      try {
+        await program.methods
+          .refundTicket()
+          .accounts({
+            programState: programStateAddress,
+            ticketData: ticket.ticketData,
+            ticketMint: ticket.mint,
+            ownerTokenAccount: ticket.tokenAccount,
+            owner: buyer.publicKey,
+            paidBy: buyer.publicKey,
+            authority: programAuthority.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer, programAuthority])
+          .rpc();
+        assert.fail('Expected a refund after entry to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'TicketAlreadyUsed');
+      }
+
+      const secondBuyerTokenAccount = await createAssociatedTokenAccount(provider.connection, secondBuyer, ticket.mint, secondBuyer.publicKey);
+      try {
+        await program.methods
+          .transferTicket({ gift: {} }, new anchor.BN(0))
+          .accounts({
+            programState: programStateAddress,
+            ticketData: ticket.ticketData,
+            currentOwnerTokenAccount: ticket.tokenAccount,
+            newOwnerTokenAccount: secondBuyerTokenAccount,
+            currentOwner: buyer.publicKey,
+            newOwner: secondBuyer.publicKey,
+            originalOwner: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc();
+        assert.fail('Expected a transfer after entry to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'TicketAlreadyUsed');
+      }
+    });
+
+    it('still locks single-use tickets after one scan', async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions(), liveEventMetadata());
+
+      await scan(ticket);
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.isTrue(ticketData.isUsed);
+      assert.isNotNull(ticketData.lastScanAt);
+
+      await new Promise(resolve => setTimeout(resolve, (REENTRY_COOLDOWN + 1) * 1000));
+      try {
+        await scan(ticket);
+        assert.fail('Expected a second scan of a single-use ticket to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'TicketAlreadyUsed');
+      }
+    });
+  });
+
+  describe('Access Level Hierarchy', () => {
+    const grantAccess = (ticket: Awaited<ReturnType<typeof mintTicket>>, contentId: string, accessLevel: any) =>
+      program.methods
+        .grantContentAccess(contentId, accessLevel)
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          ownerTokenAccount: ticket.tokenAccount,
+          authority: programAuthority.publicKey,
+        })
+        .signers([programAuthority])
+        .rpc();
+
+    const contentRecord = (contentId: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('content'), createHash('sha256').update(contentId).digest()],
+        program.programId
+      )[0];
+
+    // The content's level is set by the program authority, not the holder
+    const requireLevel = (contentId: string, requiredLevel: any) =>
+      program.methods
+        .setContentAccessLevel(contentId, requiredLevel)
+        .accounts({
+          programState: programStateAddress,
+          contentRecord: contentRecord(contentId),
+          authority: programAuthority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([programAuthority])
+        .rpc();
+
+    const verifyAccess = (ticket: Awaited<ReturnType<typeof mintTicket>>, contentId: string) =>
+      program.methods
+        .verifyOwnership(contentId)
+        .accounts({
+          programState: programStateAddress,
+          contentRecord: contentRecord(contentId),
+          ticketData: ticket.ticketData,
+          ownerTokenAccount: ticket.tokenAccount,
+          owner: buyer.publicKey,
+        })
+        .signers([buyer])
+        .rpc();
+
+    it('Lets a VIP holder access Basic content', async () => {
+      const ticket = await mintTicket(buyer);
+      await grantAccess(ticket, 'livestream', { vip: {} });
+      await requireLevel('livestream', { basic: {} });
+
+      await verifyAccess(ticket, 'livestream');
+    });
+
+    it('Denies a Basic holder access to VIP content', async () => {
+      const ticket = await mintTicket(buyer);
+      await grantAccess(ticket, 'livestream', { basic: {} });
+      await requireLevel('livestream', { vip: {} });
+
+      try {
+        await verifyAccess(ticket, 'livestream');
+        assert.fail('Basic access should not satisfy a VIP requirement');
+      } catch (error) {
+        assert.include(error.toString(), 'InsufficientAccessLevel');
+      }
+    });
+
+    it('Grants access when the levels match exactly', async () => {
+      const ticket = await mintTicket(buyer);
+      await grantAccess(ticket, 'basic-stream', { basic: {} });
+      await grantAccess(ticket, 'backstage-cam', { backstage: {} });
+
+      await requireLevel('basic-stream', { basic: {} });
+      await requireLevel('backstage-cam', { backstage: {} });
+
+      await verifyAccess(ticket, 'basic-stream');
+      await verifyAccess(ticket, 'backstage-cam');
+
+      // Exclusive sits below Backstage, so it can't reach Backstage content
+      await grantAccess(ticket, 'backstage-cam', { exclusive: {} });
+      try {
+        await verifyAccess(ticket, 'backstage-cam');
+        assert.fail('Exclusive access should not satisfy a Backstage requirement');
+      } catch (error) {
+        assert.include(error.toString(), 'InsufficientAccessLevel');
+      }
+    });
+
+    it('Only lets the program authority set a content level', async () => {
+      try {
+        await program.methods
+          .setContentAccessLevel('vip-lounge', { basic: {} })
+          .accounts({
+            programState: programStateAddress,
+            contentRecord: contentRecord('vip-lounge'),
+            authority: buyer.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc();
+        assert.fail('A holder should not set the level content requires');
+      } catch (error) {
+        assert.include(error.toString(), 'Unauthorized');
+      }
+    });
+  });
+
+  describe('Proof Of Attendance', () => {
+    const verifier = Keypair.generate();
+
+    const verificationCode = (mint: PublicKey) => `${EVENT_ID}_${mint.toString().slice(0, 8)}`;
+
+    // Event already under way, so scans fall inside the entry window
+    const liveEventMetadata = () => ({
+      ...defaultMetadata(),
+      eventDatetime: new anchor.BN(Math.floor(Date.now() / 1000)),
+    });
+
+    const scan = (ticket: Awaited<ReturnType<typeof mintTicket>>) =>
+      program.methods
+        .useTicket(verificationCode(ticket.mint), null)
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          ownerTokenAccount: ticket.tokenAccount,
+          owner: buyer.publicKey,
+          verifier: verifier.publicKey,
+        })
+        .signers([buyer, verifier])
+        .rpc();
+
+    const issuePoap = async (ticket: Awaited<ReturnType<typeof mintTicket>>) => {
+      const poapMint = Keypair.generate();
+      const [poapRecord] = await PublicKey.findProgramAddress(
+        [Buffer.from('poap'), poapMint.publicKey.toBuffer()],
+        program.programId
+      );
+      const attendeeTokenAccount = await getAssociatedTokenAddress(poapMint.publicKey, buyer.publicKey);
+
+      await program.methods
+        .issuePoap()
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          poapMint: poapMint.publicKey,
+          poapRecord,
+          attendeeTokenAccount,
+          attendee: buyer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer, poapMint])
+        .rpc();
+
+      return { mint: poapMint.publicKey, poapRecord, attendeeTokenAccount };
+    };
+
+    it('issues a frozen attendance NFT for a used ticket', async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions(), liveEventMetadata());
+      await scan(ticket);
+
+      const poap = await issuePoap(ticket);
+
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.poapMint.toString(), poap.mint.toString());
+
+      const record = await program.account.poapRecord.fetch(poap.poapRecord);
+      assert.equal(record.ticketMint.toString(), ticket.mint.toString());
+      assert.equal(record.eventId, EVENT_ID);
+      assert.equal(record.attendee.toString(), buyer.publicKey.toString());
+
+      const tokenAccount = await getAccount(provider.connection, poap.attendeeTokenAccount);
+      assert.equal(Number(tokenAccount.amount), 1);
+      assert.isTrue(tokenAccount.isFrozen);
+    });
+
+    it('issues an attendance NFT for a scanned re-entry ticket', async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions(), liveEventMetadata());
+      await program.methods
+        .setReentryPolicy(true, new anchor.BN(60))
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          authority: programAuthority.publicKey,
+        })
+        .signers([programAuthority])
+        .rpc();
+      await scan(ticket);
+
+      // The ticket is still good for re-entry, but the holder has attended
+      let ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.isFalse(ticketData.isUsed);
+
+      const poap = await issuePoap(ticket);
+
+      ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.poapMint.toString(), poap.mint.toString());
+      const tokenAccount = await getAccount(provider.connection, poap.attendeeTokenAccount);
+      assert.equal(Number(tokenAccount.amount), 1);
+    });
+
+    it('refuses to issue before the ticket is used', async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions(), liveEventMetadata());
+
+      try {
+        await issuePoap(ticket);
+        assert.fail('Expected issuing for an unused ticket to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'TicketNotUsed');
+      }
+    });
+
+    it('refuses to issue a second attendance NFT for the same ticket', async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions(), liveEventMetadata());
+      await scan(ticket);
+      const poap = await issuePoap(ticket);
+
+      try {
+        await issuePoap(ticket);
+        assert.fail('Expected a second issue to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'PoapAlreadyIssued');
+      }
+
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.poapMint.toString(), poap.mint.toString());
+    });
+  });
+
+  describe('Royalty Distribution', () => {
+    const sellTicket = async () => {
+      const ticket = await mintTicket(buyer, defaultRestrictions({ originalOwnerRoyalty: false }));
+      const secondBuyerTokenAccount = await createAssociatedTokenAccount(provider.connection, secondBuyer, ticket.mint, secondBuyer.publicKey);
+
+      await program.methods
+        .transferTicket({ marketplaceSale: {} }, new anchor.BN(PAYMENT_AMOUNT))
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          currentOwnerTokenAccount: ticket.tokenAccount,
+          newOwnerTokenAccount: secondBuyerTokenAccount,
+          currentOwner: buyer.publicKey,
+          newOwner: secondBuyer.publicKey,
+          originalOwner: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer, secondBuyer])
+        .rpc();
+
+      return ticket;
+    };
+
+    const distributeRoyalty = (ticket: { ticketData: PublicKey }, saleAmount: number) =>
+      program.methods
+        .distributeRoyalty(new anchor.BN(saleAmount))
+        .accounts({
+          programState: programStateAddress,
+          ticketData: ticket.ticketData,
+          payer: secondBuyer.publicKey,
+          recipient: programAuthority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([secondBuyer])
+        .rpc();
+
+    it('distributes royalties on the recorded sale price', async () => {
+      const ticket = await sellTicket();
+
+      const ticketData = await program.account.ticketData.fetch(ticket.ticketData);
+      assert.equal(ticketData.lastSaleAmount.toNumber(), PAYMENT_AMOUNT);
+
+      const recipientBalanceBefore = await provider.connection.getBalance(programAuthority.publicKey);
+      await distributeRoyalty(ticket, PAYMENT_AMOUNT);
+      const recipientBalanceAfter = await provider.connection.getBalance(programAuthority.publicKey);
+
+      // The only recipient is owed the full 100% share
+      assert.equal(recipientBalanceAfter - recipientBalanceBefore, PAYMENT_AMOUNT);
+    });
+
+    it('rejects a sale amount that differs from the recorded sale', async () => {
+      const ticket = await sellTicket();
+
+      try {
+        await distributeRoyalty(ticket, PAYMENT_AMOUNT / 10);
+        assert.fail('Expected a spoofed sale amount to fail');
+      } catch (e) {
+        assert.include(e.toString(), 'SaleAmountMismatch');
+      }
+    });
+  });
+});